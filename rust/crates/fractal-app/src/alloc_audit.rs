@@ -0,0 +1,115 @@
+#[cfg(any(feature = "alloc-audit", test))]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// ---------------------------------------------------------------------------
+// AllocStats — per-frame heap allocations on the render thread
+// ---------------------------------------------------------------------------
+//
+// Built with `--features alloc-audit`, main.rs installs `CountingAlloc` as
+// the global allocator and the diagnostics panel shows how many allocations
+// the last frame made, so allocations creeping into the steady-state frame
+// path show up.  Counters are thread-local like the GPU stats: the audio,
+// MIDI and stream threads allocate on their own schedule and would drown the
+// frame's numbers.
+
+/// Heap allocations counted since the last [`take_frame`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// `alloc` calls, reallocations included.
+    pub allocations: u32,
+    pub bytes: u64,
+}
+
+thread_local! {
+    // `const` init: no lazy initialisation, so no allocation from inside
+    // the allocator.
+    static STATS: Cell<AllocStats> = const {
+        Cell::new(AllocStats {
+            allocations: 0,
+            bytes: 0,
+        })
+    };
+}
+
+#[cfg(any(feature = "alloc-audit", test))]
+fn count(size: usize) {
+    // `try_with`: the thread-local may already be gone while a thread's
+    // destructors free memory.
+    let _ = STATS.try_with(|s| {
+        let mut stats = s.get();
+        stats.allocations += 1;
+        stats.bytes += size as u64;
+        s.set(stats);
+    });
+}
+
+/// Return the counts accumulated since the previous call and reset them;
+/// `None` when the counting allocator isn't built in.  Call once per frame.
+pub fn take_frame() -> Option<AllocStats> {
+    cfg!(any(feature = "alloc-audit", test)).then(|| STATS.with(|s| s.take()))
+}
+
+/// The system allocator, counting every allocation on the calling thread.
+#[cfg(any(feature = "alloc-audit", test))]
+pub struct CountingAlloc;
+
+#[cfg(any(feature = "alloc-audit", test))]
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_accumulate_until_taken() {
+        STATS.with(|s| s.take());
+        count(16);
+        count(48);
+        let stats = STATS.with(|s| s.take());
+        assert_eq!(
+            stats,
+            AllocStats {
+                allocations: 2,
+                bytes: 64,
+            }
+        );
+        assert_eq!(STATS.with(|s| s.get()), AllocStats::default());
+    }
+
+    #[test]
+    fn counting_alloc_round_trips() {
+        let layout = Layout::from_size_align(32, 8).unwrap();
+        unsafe {
+            let ptr = CountingAlloc.alloc_zeroed(layout);
+            assert!(!ptr.is_null());
+            assert_eq!(*ptr, 0);
+            let ptr = CountingAlloc.realloc(ptr, layout, 64);
+            assert!(!ptr.is_null());
+            CountingAlloc.dealloc(ptr, Layout::from_size_align(64, 8).unwrap());
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use fractal_core::{
+    animation::{npy_header, AnimationSpec},
+    patch::Patch,
+};
+use fractal_gpu::{context::GpuContext, thumbnail::ThumbnailRenderer};
+
+use crate::zoom_video::{encode, startup_patch};
+
+// ---------------------------------------------------------------------------
+// Animation — offline render of the startup patch on a fixed clock
+// ---------------------------------------------------------------------------
+//
+// `--animation SPEC.json` skips the window and renders the startup patch
+// frame by frame at `start + i / fps`, sweeping the spec's params as it
+// goes (see `fractal_core::animation`).  A `.npy` output is one stacked
+// array for NumPy; anything else is piped to ffmpeg like a zoom video.
+
+/// Render the animation described by `spec_path`, starting from the startup
+/// argument's patch.
+pub fn run(spec_path: &Path, startup: Option<&str>) -> Result<(), String> {
+    let spec =
+        AnimationSpec::load(spec_path).map_err(|e| format!("{}: {e}", spec_path.display()))?;
+    let patch = startup_patch(startup)?;
+    render(&spec, patch).map_err(|e| format!("{}: {e}", spec.output.display()))
+}
+
+fn render(spec: &AnimationSpec, mut patch: Patch) -> io::Result<()> {
+    let ctx = pollster::block_on(GpuContext::new_headless());
+    let [w, h] = spec.size;
+    let mut renderer = ThumbnailRenderer::with_size(&ctx.device, w, h);
+    let frames = spec.frame_count();
+    log::info!("animation: {frames} frames → {}", spec.output.display());
+
+    let mut write_frames = |out: &mut dyn Write| {
+        for (i, time) in (0..frames).zip(spec.frame_times()) {
+            spec.apply_sweeps(i, &mut patch.params);
+            out.write_all(&renderer.render(&ctx.device, &ctx.queue, &mut patch, time))?;
+        }
+        Ok(())
+    };
+    if !spec.writes_array() {
+        return encode(spec.size, spec.fps, &spec.output, write_frames);
+    }
+    let mut out = BufWriter::new(File::create(&spec.output)?);
+    out.write_all(&npy_header(&[frames as usize, h as usize, w as usize, 4]))?;
+    write_frames(&mut out)?;
+    out.flush()
+}
@@ -1,56 +1,53 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use fractal_core::{patch::Patch, presets::Preset, EffectKind};
+use fractal_core::{
+    bundle::PatchBundle, overrides::OverrideStore, patch::Patch, presets::Preset,
+    projection::Projection, snapshot::ParamsPublisher, tiling::TileLayout, viewcode::ViewCodeError,
+    warp::OutputCorrection, EffectKind,
+};
 use fractal_gpu::{
-    context::Uniforms,
+    backend::{DeckPasses, WgpuFrame},
+    context::optional_features,
     effect_pipeline::{EffectPass, PingPong},
     generator_pipeline::GeneratorPass,
-    renderer::FULLSCREEN_WGSL,
+    overlay_pipeline::OverlayPass,
+    preview::{preview_size, PreviewCapture},
+    probe::{ProbePass, ProbeResult},
+    renderer::{OutputPass, Viewport},
+    shader::ShaderError,
+    stats::{self, GpuStats},
+    thumbnail::{ThumbnailRenderer, THUMBNAIL_SIZE, THUMBNAIL_TIME},
 };
 use winit::event::WindowEvent;
-use winit::window::Window;
-
-use crate::input::{apply_zoom, clamp_iterations, InputAction, InputState, Key};
-
-// ---------------------------------------------------------------------------
-// FPS counter — tracks frame rate, exposes last known value for the HUD
-// ---------------------------------------------------------------------------
-
-struct FpsCounter {
-    frames: u32,
-    last_report: Instant,
-    last_fps: f32,
-}
-
-impl FpsCounter {
-    fn new() -> Self {
-        Self {
-            frames: 0,
-            last_report: Instant::now(),
-            last_fps: 0.0,
-        }
-    }
-
-    /// Tick one frame.  Updates the stored FPS once per second and returns
-    /// the new value so the caller can log it.
-    fn tick(&mut self) -> Option<f32> {
-        self.frames += 1;
-        let elapsed = self.last_report.elapsed().as_secs_f32();
-        if elapsed >= 1.0 {
-            self.last_fps = self.frames as f32 / elapsed;
-            self.frames = 0;
-            self.last_report = Instant::now();
-            Some(self.last_fps)
-        } else {
-            None
-        }
-    }
-
-    fn fps(&self) -> f32 {
-        self.last_fps
-    }
-}
+use winit::window::{Window, WindowId};
+
+use crate::alloc_audit::{self, AllocStats};
+use crate::autosave::{Autosave, Snapshot};
+use crate::control_window::ControlWindow;
+use crate::cues::{Cue, CueList, CuePatch, CuePlayer};
+use crate::cursor::{draw_crosshair, IdleTimer};
+use crate::fps::FpsCounter;
+use crate::hud::{Controls, HudData, HudResponse};
+use crate::i18n::{trf, Language, Msg};
+use crate::input::{clamp_ceiling, preset_hotkey_label, InputAction, InputState, Key};
+use crate::midi::{is_learnable, ControllerMap, MidiControl, MidiInput, FLASH_KEY};
+use crate::overlay::OverlayContent;
+use crate::pan::PanState;
+use crate::session::{Recorder, Replay, Session};
+use crate::settings::{CrosshairStyle, Settings, MAX_UI_SCALE, MIN_UI_SCALE};
+use crate::shader_editor::{EditorRequest, ShaderEditor, ShaderTarget};
+use crate::state::AppState;
+use crate::stream::{encode_jpeg, StreamServer, STREAM_FPS, STREAM_WIDTH};
+use crate::thumbnails::ThumbnailTextures;
+use crate::tile_window::TileWindow;
+use crate::toast::{ToastLevel, Toasts};
+use crate::video::{load_image, Frame, VideoInput};
+use crate::warmup::WarmUp;
+use crate::watchdog::{self, Watchdog};
+use crate::{config, thumbnails};
 
 // ---------------------------------------------------------------------------
 // Short display name for an EffectKind (used in the HUD)
@@ -63,10 +60,39 @@ fn effect_name(kind: &EffectKind) -> &'static str {
         EffectKind::Echo { .. } => "Echo",
         EffectKind::HueShift { .. } => "Hue Shift",
         EffectKind::BrightnessContrast { .. } => "Brightness/Contrast",
+        EffectKind::Posterize { .. } => "Posterize",
+        EffectKind::Invert { .. } => "Invert",
         EffectKind::MotionBlur { .. } => "Motion Blur",
+        EffectKind::AudioOverlay { .. } => "Audio Overlay",
     }
 }
 
+// ---------------------------------------------------------------------------
+// Adapter and device — requested at startup and again by a GPU rebuild
+// ---------------------------------------------------------------------------
+
+fn request_adapter(instance: &wgpu::Instance, surface: &wgpu::Surface) -> Option<wgpu::Adapter> {
+    pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        compatible_surface: Some(surface),
+        force_fallback_adapter: false,
+    }))
+}
+
+fn request_device(
+    adapter: &wgpu::Adapter,
+) -> Result<(wgpu::Device, wgpu::Queue), wgpu::RequestDeviceError> {
+    pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some("fractal-app device"),
+            required_features: optional_features(adapter),
+            required_limits: wgpu::Limits::default(),
+            memory_hints: Default::default(),
+        },
+        None,
+    ))
+}
+
 // ---------------------------------------------------------------------------
 // App — Phase 11: egui HUD overlay
 // ---------------------------------------------------------------------------
@@ -74,39 +100,139 @@ fn effect_name(kind: &EffectKind) -> &'static str {
 pub struct App {
     // Kept for egui-winit (take/handle input, scale factor)
     window: Arc<Window>,
+    /// Last title set on `window`, so it is only updated on change.
+    window_title: String,
+
+    // Kept to create surfaces for extra windows
+    instance: wgpu::Instance,
+    adapter: wgpu::Adapter,
 
     surface: wgpu::Surface<'static>,
     device: wgpu::Device,
     queue: wgpu::Queue,
     surface_config: wgpu::SurfaceConfiguration,
+    /// Region of the window the fractal fills (all of it unless
+    /// `Settings::frame_aspect` or a tile layout letterboxes it).
+    frame: Viewport,
+    /// Canvas split across the tile windows; while loaded the patch renders
+    /// at the canvas size and the main window shows a scaled overview.
+    tiles: Option<TileLayout>,
+    tile_windows: Vec<TileWindow>,
 
     // GPU passes (size-dependent resources rebuilt on resize)
     gen_pass: GeneratorPass,
     effect_pass: EffectPass,
     pp: PingPong,
-
-    // Fullscreen quad render pipeline
-    render_pipeline: wgpu::RenderPipeline,
-    render_bgl: wgpu::BindGroupLayout,
-    render_sampler: wgpu::Sampler,
-
-    // Patch and preset tracking
-    patch: Patch,
-    current_preset_idx: usize,
+    /// Deck B's generator output, ping-pong pair and crossfade pass; only
+    /// allocated while a patch is cued on deck B.
+    deck_b: Option<DeckPasses>,
+    /// Text / logo overlay composited after the effect chain.
+    overlay_pass: OverlayPass,
+    /// What `overlay_pass` holds, and whether it rendered; re-rendered when
+    /// the overlay settings change.
+    overlay_content: Option<OverlayContent>,
+    overlay_ready: bool,
+    /// Presets still to be drawn once through the passes at startup, so
+    /// their shaders are warm before the user switches to them.
+    warm_up: WarmUp,
+
+    // Fullscreen quad render pass (projection mask included)
+    output: OutputPass,
+    /// Warp loaded into `output`, re-applied after a GPU rebuild.
+    output_correction: Option<OutputCorrection>,
+
+    // Patch, preset tracking and per-preset user overrides
+    state: AppState,
+    /// Where `state.overrides` is persisted.
+    overrides_path: Option<PathBuf>,
+    /// Where `state.midi.map` is persisted.
+    controller_map_path: Option<PathBuf>,
+    /// The MIDI input port, if one could be opened.
+    midi_input: Option<MidiInput>,
+
+    // Preset thumbnails: offscreen renderer + one egui texture per preset
+    thumb_renderer: ThumbnailRenderer,
+    thumbnails: Vec<Option<egui::ColorImage>>,
+    /// Bumped whenever `thumbnails` changes so each egui context re-uploads.
+    thumbnails_generation: u64,
+    /// Thumbnail textures in the main window's egui context.
+    thumb_textures: ThumbnailTextures,
+
+    // WGSL playground
+    shader_editor: ShaderEditor,
+
+    // On-screen notifications
+    toasts: Toasts,
+
+    // App settings (HUD theme, UI scale) and where they are persisted
+    settings: Settings,
+    settings_path: Option<PathBuf>,
+    /// Settings edited but not yet applied/saved (held while a slider drags).
+    settings_dirty: bool,
+    /// Iteration ceiling from `--max-iterations`; wins over the setting.
+    max_iterations_override: Option<u32>,
+
+    // Crash resume: periodic snapshots of the session, and the one a
+    // crashed session left behind with its age in minutes.  Autosaving
+    // waits until that offer is answered so it can't be overwritten.
+    autosave: Option<Autosave>,
+    resume_offer: Option<(Snapshot, u64)>,
 
     // Input
     input: InputState,
     /// Last known cursor position in physical pixels.
     cursor_pos: (f64, f64),
+    /// Time since the pointer last moved; drives cursor auto-hide.
+    pointer_idle: IdleTimer,
+    /// Middle-button drag panning with release momentum.
+    pan: PanState,
+
+    // Input sessions: every handled action is appended to `recorder`;
+    // `replay` feeds a recorded session back through `handle_action`.
+    recorder: Option<Recorder>,
+    replay: Option<Replay>,
+    replay_started: Instant,
+    /// The loaded show, stepped with Go or by its hold timers.
+    cues: CuePlayer,
+
+    // External footage for the Video and Image generators
+    video: Option<VideoInput>,
+    /// Last frame uploaded to the source texture, replayed into passes
+    /// rebuilt after it arrived.
+    source_frame: Option<Frame>,
 
     // Frame timing
     last_frame: Instant,
     fps: FpsCounter,
+    /// GPU call counts from the previous frame, shown in the diagnostics panel.
+    gpu_stats: GpuStats,
+    /// Render-thread heap allocations of the previous frame, when audited.
+    alloc_stats: Option<AllocStats>,
+    /// Rebuilds the GPU state after a hung frame or a lost device.
+    watchdog: Option<Watchdog>,
+
+    // Remote preview: MJPEG server and the downscaled capture feeding it
+    stream: Option<StreamServer>,
+    preview: Option<PreviewCapture>,
+    /// Seconds since the last preview capture.
+    stream_clock: f32,
+    /// Each frame's params for the stream server's `/params.json`.
+    params_snapshot: ParamsPublisher,
+
+    // Iteration data under the cursor, probed on the GPU each frame
+    probe: ProbePass,
+    cursor_probe: Option<ProbeResult>,
 
     // egui
     egui_ctx: egui::Context,
     egui_state: egui_winit::State,
     egui_renderer: egui_wgpu::Renderer,
+
+    /// Detached window holding the egui panels, if open.
+    control: Option<ControlWindow>,
+    /// Open (`true`) / close request for the control window, handled by
+    /// main.rs, which owns the event loop needed to create windows.
+    control_request: Option<bool>,
 }
 
 impl App {
@@ -127,26 +253,16 @@ impl App {
             .expect("failed to create wgpu surface");
 
         // ---- Adapter --------------------------------------------------------
-        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            compatible_surface: Some(&surface),
-            force_fallback_adapter: false,
-        }))
-        .expect("no suitable GPU adapter found");
+        let adapter = request_adapter(&instance, &surface).expect("no suitable GPU adapter found");
 
         log::info!("GPU adapter: {}", adapter.get_info().name);
+        log::info!(
+            "f64 generator kernels: {}",
+            optional_features(&adapter).contains(wgpu::Features::SHADER_F64)
+        );
 
         // ---- Device & Queue -------------------------------------------------
-        let (device, queue) = pollster::block_on(adapter.request_device(
-            &wgpu::DeviceDescriptor {
-                label: Some("fractal-app device"),
-                required_features: wgpu::Features::empty(),
-                required_limits: wgpu::Limits::default(),
-                memory_hints: Default::default(),
-            },
-            None,
-        ))
-        .expect("failed to create GPU device");
+        let (device, queue) = request_device(&adapter).expect("failed to create GPU device");
 
         // ---- Surface configuration ------------------------------------------
         let surface_caps = surface.get_capabilities(&adapter);
@@ -177,14 +293,8 @@ impl App {
             format
         );
 
-        // ---- GPU passes -----------------------------------------------------
-        let gen_pass = GeneratorPass::new(&device, width, height);
-        let effect_pass = EffectPass::new(&device);
-        let pp = PingPong::new(&device, width, height);
-
         // ---- Fullscreen quad render pipeline --------------------------------
-        let (render_bgl, render_sampler, render_pipeline) =
-            Self::build_render_pipeline(&device, format);
+        let output = OutputPass::new(&device, format);
 
         // ---- egui -----------------------------------------------------------
         let egui_ctx = egui::Context::default();
@@ -198,131 +308,402 @@ impl App {
         );
         let egui_renderer = egui_wgpu::Renderer::new(&device, format, None, 1, false);
 
-        // ---- Patch (start with ClassicMandelbrot) ---------------------------
-        let patch = Preset::ClassicMandelbrot.build();
+        // ---- Settings -------------------------------------------------------
+        let mut toasts = Toasts::new();
+        let settings_path = config::settings_path();
+        let env_lang = Language::from_env().unwrap_or_default();
+        let mut settings = match &settings_path {
+            Some(path) => Settings::load(path).unwrap_or_else(|e| {
+                log::warn!("ignoring settings file {}: {e}", path.display());
+                toasts.warn(trf(env_lang, Msg::ToastSettingsUnreadable, &[&e]));
+                Settings::default()
+            }),
+            None => Settings::default(),
+        };
+        // First run: follow the system locale until the user picks a language.
+        if !settings_path.as_deref().is_some_and(Path::exists) {
+            settings.language = env_lang;
+        }
+        let lang = settings.language;
+        settings.apply(&egui_ctx);
+
+        // ---- GPU passes (sized to the framed region) ------------------------
+        let frame = Viewport::fit(width, height, settings.frame_aspect.ratio());
+        let gen_pass = GeneratorPass::new(&device, frame.width, frame.height);
+        let effect_pass = EffectPass::new(&device);
+        let pp = PingPong::new(&device, frame.width, frame.height);
+        let overlay_pass = OverlayPass::new(&device, frame.width, frame.height);
+        let probe = ProbePass::new(&device);
+
+        // ---- User overrides -------------------------------------------------
+        let overrides_path = config::overrides_path();
+        let overrides = match &overrides_path {
+            Some(path) => OverrideStore::load(path).unwrap_or_else(|e| {
+                log::warn!("ignoring overrides file {}: {e}", path.display());
+                toasts.warn(trf(lang, Msg::ToastTweaksUnreadable, &[&e]));
+                OverrideStore::default()
+            }),
+            None => OverrideStore::default(),
+        };
 
-        Self {
+        // ---- MIDI controller map and input ---------------------------------
+        let controller_map_path = config::controller_map_path();
+        let controller_map = match &controller_map_path {
+            Some(path) => ControllerMap::load(path).unwrap_or_else(|e| {
+                log::warn!("ignoring controller map {}: {e}", path.display());
+                toasts.warn(trf(lang, Msg::ToastControllerMapUnreadable, &[&e]));
+                ControllerMap::default()
+            }),
+            None => ControllerMap::default(),
+        };
+        // Most setups have no MIDI hardware, so a failure is only logged.
+        let midi_input = MidiInput::open()
+            .inspect(|m| log::info!("MIDI input: {}", m.port_name()))
+            .inspect_err(|e| log::info!("no MIDI input: {e}"))
+            .ok();
+
+        // ---- Crash resume ---------------------------------------------------
+        let autosave = config::autosave_path().map(Autosave::new);
+        let resume_offer = autosave.as_ref().and_then(|a| {
+            a.pending()
+                .unwrap_or_else(|e| {
+                    log::warn!("ignoring autosave: {e}");
+                    toasts.warn(trf(lang, Msg::ToastAutosaveUnreadable, &[&e]));
+                    None
+                })
+                .map(|(snapshot, age)| (snapshot, age.as_secs() / 60))
+        });
+
+        // ---- Patch (start with ClassicMandelbrot) ---------------------------
+        let mut state = AppState::new(overrides, frame.width, frame.height);
+        state.quantize = settings.quantize;
+        state.lfo_retrigger = settings.lfo_retrigger;
+        state.flash_frames = settings.flash_frames;
+        state.flash_every = settings.flash_every;
+        state.set_auto_gain(settings.audio_auto_gain);
+        state.auto_iterations = settings.auto_iterations_cap();
+        state.smooth_iterations = settings.smooth_iterations;
+        state.samples = settings.antialias.samples();
+        state.effect_lod = settings.effect_lod;
+        state.set_threaded_modulation(settings.threaded_modulation);
+        state.max_iterations = settings.max_iterations;
+        state.midi = MidiControl::new(controller_map);
+
+        let thumb_renderer = ThumbnailRenderer::new(&device, THUMBNAIL_SIZE);
+        let warm_up = WarmUp::new(state.preset());
+
+        let mut app = Self {
             window,
+            window_title: String::new(),
+            instance,
+            adapter,
             surface,
             device,
             queue,
             surface_config,
+            frame,
+            tiles: None,
+            tile_windows: Vec::new(),
             gen_pass,
             effect_pass,
             pp,
-            render_pipeline,
-            render_bgl,
-            render_sampler,
-            patch,
-            current_preset_idx: 0,
+            deck_b: None,
+            overlay_pass,
+            overlay_content: None,
+            overlay_ready: false,
+            warm_up,
+            output,
+            output_correction: None,
+            state,
+            overrides_path,
+            controller_map_path,
+            midi_input,
+            thumb_renderer,
+            thumbnails: vec![None; Preset::ALL.len()],
+            thumbnails_generation: 0,
+            thumb_textures: ThumbnailTextures::default(),
+            shader_editor: ShaderEditor::new(),
+            toasts,
+            settings,
+            settings_path,
+            settings_dirty: false,
+            max_iterations_override: None,
+            autosave,
+            resume_offer,
             input: InputState::new(),
             cursor_pos: (0.0, 0.0),
+            pointer_idle: IdleTimer::new(),
+            pan: PanState::new(),
+            recorder: None,
+            replay: None,
+            replay_started: Instant::now(),
+            cues: CuePlayer::default(),
+            video: None,
+            source_frame: None,
             last_frame: Instant::now(),
             fps: FpsCounter::new(),
+            gpu_stats: GpuStats::default(),
+            alloc_stats: None,
+            watchdog: None,
+            stream: None,
+            preview: None,
+            stream_clock: 0.0,
+            params_snapshot: ParamsPublisher::new(),
+            probe,
+            cursor_probe: None,
             egui_ctx,
             egui_state,
             egui_renderer,
-        }
+            control: None,
+            control_request: None,
+        };
+        app.refresh_thumbnails(false);
+        app.sync_window_title();
+        app.sync_stream();
+        app.sync_watchdog();
+        app
     }
 
     // -------------------------------------------------------------------------
-    // Build the fullscreen-quad render pipeline (resolution-agnostic).
+    // Resize
     // -------------------------------------------------------------------------
 
-    fn build_render_pipeline(
-        device: &wgpu::Device,
-        surface_format: wgpu::TextureFormat,
-    ) -> (wgpu::BindGroupLayout, wgpu::Sampler, wgpu::RenderPipeline) {
-        let render_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("render_bgl"),
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        multisampled: false,
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
-                },
-            ],
-        });
+    pub fn resize(&mut self, new_width: u32, new_height: u32) {
+        if new_width == 0 || new_height == 0 {
+            return;
+        }
+        self.surface_config.width = new_width;
+        self.surface_config.height = new_height;
+        self.surface.configure(&self.device, &self.surface_config);
+        self.frame = Viewport::fit(new_width, new_height, self.frame_aspect());
+        let (width, height) = self.render_size();
+        self.state.resize(width, height);
+
+        let mut gen_pass = GeneratorPass::new(&self.device, width, height);
+        self.restore_generator_edits(&mut gen_pass);
+        self.upload_source_frame(&mut gen_pass);
+        self.gen_pass = gen_pass;
+        self.pp = PingPong::new(&self.device, width, height);
+        self.overlay_pass.resize(&self.device, width, height);
+        // Rebuilt at the new size by the next frame.
+        self.deck_b = None;
+        if self.preview.is_some() {
+            let (w, h) = preview_size(width, height, STREAM_WIDTH);
+            self.preview = Some(PreviewCapture::new(&self.device, w, h));
+        }
 
-        let render_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("render_sampler"),
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Linear,
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            ..Default::default()
-        });
+        log::debug!(
+            "Surface resized to {}×{}, frame {}×{}",
+            new_width,
+            new_height,
+            width,
+            height
+        );
+    }
 
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("render_pl"),
-            bind_group_layouts: &[&render_bgl],
-            push_constant_ranges: &[],
-        });
+    /// Aspect the window's frame is fit to: the canvas's while tiled,
+    /// otherwise the frame-aspect setting.
+    fn frame_aspect(&self) -> Option<f32> {
+        match &self.tiles {
+            Some(layout) => Some(layout.aspect()),
+            None => self.settings.frame_aspect.ratio(),
+        }
+    }
 
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("fullscreen"),
-            source: wgpu::ShaderSource::Wgsl(FULLSCREEN_WGSL.into()),
-        });
+    /// Size the patch renders at: the canvas while tiled, otherwise the
+    /// window's frame.
+    fn render_size(&self) -> (u32, u32) {
+        match &self.tiles {
+            Some(TileLayout {
+                canvas: [width, height],
+                ..
+            }) => (*width, *height),
+            None => (self.frame.width, self.frame.height),
+        }
+    }
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("render_pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
-                    blend: None,
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+    /// Rendered pixels per window pixel inside the frame (1 unless tiled).
+    fn frame_scale(&self) -> f32 {
+        self.render_size().1 as f32 / self.frame.height.max(1) as f32
+    }
+
+    /// `pos` (window pixels) in rendered pixels from the frame's corner.
+    fn to_render(&self, pos: (f32, f32)) -> (f32, f32) {
+        let (x, y) = self.frame.local(pos);
+        let scale = self.frame_scale();
+        (x * scale, y * scale)
+    }
+
+    /// A rebuilt pass starts from the built-in shaders; recompile the
+    /// shader editor's generator edits into it.
+    fn restore_generator_edits(&mut self, gen_pass: &mut GeneratorPass) {
+        for (kind, src) in self.shader_editor.generator_edits() {
+            if let Err(e) = gen_pass.set_source(&self.device, kind, src) {
+                log::warn!("failed to restore edited {kind:?} shader: {e}");
+                self.toasts
+                    .error(self.tr(Msg::ToastShaderNotRestored, &[&format!("{kind:?}"), &e]));
+            }
+        }
+    }
+
+    fn upload_source_frame(&self, gen_pass: &mut GeneratorPass) {
+        if let Some(Frame {
+            width,
+            height,
+            rgba,
+        }) = &self.source_frame
+        {
+            gen_pass.set_frame(&self.device, &self.queue, *width, *height, rgba);
+        }
+    }
+
+    /// Show `source` on the Video / Image generators of both decks.
+    fn set_source_frame(&mut self, source: Frame) {
+        let Frame {
+            width,
+            height,
+            rgba,
+        } = &source;
+        self.gen_pass
+            .set_frame(&self.device, &self.queue, *width, *height, rgba);
+        if let Some(passes) = &mut self.deck_b {
+            passes
+                .gen_pass
+                .set_frame(&self.device, &self.queue, *width, *height, rgba);
+        }
+        self.source_frame = Some(source);
+    }
 
-        (render_bgl, render_sampler, render_pipeline)
+    /// Re-render the overlay image when its text or logo changed, and hand
+    /// the layout to the state while there is something to show.
+    fn sync_overlay(&mut self) {
+        let overlay = &self.settings.overlay;
+        let content = overlay.content();
+        if content != self.overlay_content {
+            self.overlay_ready = false;
+            if let Some(content) = &content {
+                match content.render(self.device.limits().max_texture_dimension_2d) {
+                    Ok(Frame {
+                        width,
+                        height,
+                        rgba,
+                    }) => {
+                        self.overlay_pass.set_image(
+                            &self.device,
+                            &self.queue,
+                            width,
+                            height,
+                            &rgba,
+                        );
+                        self.overlay_ready = true;
+                    }
+                    Err(e) => {
+                        log::warn!("failed to render overlay {content:?}: {e}");
+                        self.toasts.error(self.tr(Msg::ToastOverlayFailed, &[&e]));
+                    }
+                }
+            }
+            self.overlay_content = content;
+        }
+        self.state.overlay = self.overlay_ready.then_some(overlay.layout);
+    }
+
+    /// Allocate deck B's passes when a patch is cued there and free them
+    /// when it is closed.
+    fn sync_deck_passes(&mut self) {
+        match (&self.state.deck_b, &self.deck_b) {
+            (Some(_), None) => {
+                let (width, height) = self.render_size();
+                let mut passes = DeckPasses::new(&self.device, width, height);
+                self.restore_generator_edits(&mut passes.gen_pass);
+                self.upload_source_frame(&mut passes.gen_pass);
+                self.deck_b = Some(passes);
+            }
+            (None, Some(_)) => self.deck_b = None,
+            _ => {}
+        }
+    }
+
+    /// Compile each deck's custom formula into its generator pass.  A pass
+    /// only recompiles when the formula changed, so a broken one is
+    /// reported once and the last good one keeps drawing.
+    fn sync_formulas(&mut self) {
+        let mut failed = Vec::new();
+        if let Some(formula) = self.state.patch.generator.formula() {
+            if let Err(e) = self.gen_pass.set_formula(&self.device, formula) {
+                failed.push((formula.source().to_string(), e));
+            }
+        }
+        if let (Some((_, patch)), Some(passes)) = (&self.state.deck_b, &mut self.deck_b) {
+            if let Some(formula) = patch.generator.formula() {
+                if let Err(e) = passes.gen_pass.set_formula(&self.device, formula) {
+                    failed.push((formula.source().to_string(), e));
+                }
+            }
+        }
+        for (src, e) in failed {
+            log::warn!("formula {src:?} failed to compile: {e}");
+            self.toasts
+                .error(self.tr(Msg::ToastFormulaFailed, &[&src, &e]));
+        }
     }
 
     // -------------------------------------------------------------------------
-    // Resize
+    // Control window
     // -------------------------------------------------------------------------
 
-    pub fn resize(&mut self, new_width: u32, new_height: u32) {
-        if new_width == 0 || new_height == 0 {
-            return;
+    /// Retitle the main window after the running patch, when that changed.
+    fn sync_window_title(&mut self) {
+        let title = self.state.window_title(self.settings.language);
+        if title != self.window_title {
+            self.window.set_title(&title);
+            self.window_title = title;
         }
-        self.surface_config.width = new_width;
-        self.surface_config.height = new_height;
-        self.surface.configure(&self.device, &self.surface_config);
+    }
+
+    /// Localised title for the control window.
+    pub fn control_window_title(&self) -> String {
+        self.tr(Msg::ControlWindowTitle, &[])
+    }
+
+    /// A pending open (`true`) / close request for the control window.
+    pub fn take_control_window_request(&mut self) -> Option<bool> {
+        self.control_request.take()
+    }
+
+    pub fn control_window_id(&self) -> Option<WindowId> {
+        self.control.as_ref().map(ControlWindow::id)
+    }
+
+    /// Move the panels into `window`.
+    pub fn open_control_window(&mut self, window: Arc<Window>) {
+        self.control = Some(ControlWindow::new(
+            window,
+            &self.instance,
+            &self.adapter,
+            &self.device,
+            &self.settings,
+        ));
+        log::info!("Control window opened");
+    }
 
-        self.gen_pass = GeneratorPass::new(&self.device, new_width, new_height);
-        self.pp = PingPong::new(&self.device, new_width, new_height);
+    /// Close the control window; the panels return to the main window.
+    pub fn close_control_window(&mut self) {
+        if self.control.take().is_some() {
+            log::info!("Control window closed");
+        }
+    }
 
-        log::debug!("Surface resized to {}×{}", new_width, new_height);
+    /// Feed an event for the control window to its egui context; `true` if
+    /// egui consumed it.
+    pub fn control_window_event(&mut self, event: &WindowEvent) -> bool {
+        let Some(control) = &mut self.control else {
+            return false;
+        };
+        if let WindowEvent::Resized(size) = event {
+            control.resize(&self.device, size.width, size.height);
+        }
+        control.on_window_event(event)
     }
 
     // -------------------------------------------------------------------------
@@ -345,170 +726,1376 @@ impl App {
         self.input.on_key(key)
     }
 
+    pub fn on_modifiers_changed(&mut self, shift: bool) {
+        self.input.set_shift(shift);
+    }
+
     pub fn on_cursor_moved(&mut self, x: f64, y: f64) {
         self.cursor_pos = (x, y);
-        let w = self.surface_config.width as f64;
-        let h = self.surface_config.height as f64;
-        self.patch.params.mouse_x = (x / w) as f32;
-        self.patch.params.mouse_y = (y / h) as f32;
+        self.pointer_idle.reset();
+        let local = self.to_render((x as f32, y as f32));
+        self.input.update_drag(local);
+        self.pan.on_move((x as f32, y as f32));
+        self.state.set_pointer(local.0 as f64, local.1 as f64);
     }
 
-    pub fn on_mouse_left_click(&self) -> InputAction {
-        let w = self.surface_config.width as f64;
-        let h = self.surface_config.height as f64;
-        let norm_x = (self.cursor_pos.0 / w) as f32;
-        let norm_y = (self.cursor_pos.1 / h) as f32;
-        self.input.on_mouse_click(norm_x, norm_y)
+    /// Left (`zoom_out == false`) or right button pressed over the fractal.
+    /// Presses on the letterbox bars are ignored.
+    pub fn on_mouse_pressed(&mut self, zoom_out: bool) {
+        let pos = (self.cursor_pos.0 as f32, self.cursor_pos.1 as f32);
+        if self.frame.contains(pos) {
+            self.input.begin_drag(self.to_render(pos), zoom_out);
+        }
+    }
+
+    /// Middle button pressed over the fractal: start panning.
+    pub fn on_pan_pressed(&mut self) {
+        self.pan
+            .grab((self.cursor_pos.0 as f32, self.cursor_pos.1 as f32));
+    }
+
+    /// Middle button released: the view glides on with the drag's momentum.
+    pub fn on_pan_released(&mut self) {
+        self.pan.release();
+    }
+
+    /// Button released: a click zooms 2×, a drag zooms to the box.
+    pub fn on_mouse_released(&mut self, zoom_out: bool) -> Option<InputAction> {
+        let (width, height) = self.render_size();
+        let size = (width as f32, height as f32);
+        self.input
+            .end_drag(zoom_out, size, self.settings.lock_box_aspect)
     }
 
     /// Returns `true` if the app should exit.
     pub fn handle_action(&mut self, action: InputAction) -> bool {
+        let record_error = match &mut self.recorder {
+            Some(rec) if action != InputAction::ToggleRecording => rec.record(&action).err(),
+            _ => None,
+        };
+        if let Some(e) = record_error {
+            log::warn!("input recording stopped: {e}");
+            self.toasts.error(self.tr(Msg::ToastRecordingFailed, &[&e]));
+            self.recorder = None;
+        }
+
         match action {
-            InputAction::LoadPreset(preset) => {
-                log::info!("Loading preset: {}", preset.name());
-                if let Some(idx) = Preset::ALL.iter().position(|&p| p == preset) {
-                    self.current_preset_idx = idx;
+            InputAction::Reset => {
+                let preset = self.state.preset();
+                log::info!("Reset to preset defaults: {}", preset.name());
+                self.toasts
+                    .info(self.tr(Msg::ToastPresetReset, &[&preset.name()]));
+                if self.state.reset() {
+                    self.save_overrides();
                 }
-                self.patch = preset.build();
             }
 
-            InputAction::CycleNextPreset => {
-                self.current_preset_idx = (self.current_preset_idx + 1) % Preset::ALL.len();
-                let preset = Preset::ALL[self.current_preset_idx];
-                log::info!("Cycling to preset: {}", preset.name());
-                self.patch = preset.build();
+            InputAction::SaveOverrides => {
+                let preset = self.state.preset();
+                if self
+                    .state
+                    .overrides
+                    .capture(preset, &self.state.patch.params)
+                {
+                    log::info!("Saved overrides for preset: {}", preset.name());
+                    self.toasts
+                        .info(self.tr(Msg::ToastPresetSaved, &[&preset.name()]));
+                } else {
+                    log::info!("No changes from defaults for preset: {}", preset.name());
+                    self.toasts
+                        .info(self.tr(Msg::ToastNothingToSave, &[&preset.name()]));
+                }
+                self.save_overrides();
+                self.refresh_thumbnail(self.state.current_preset_idx, true);
             }
 
-            InputAction::IterationsUp => {
-                self.patch.params.max_iter =
-                    clamp_iterations(self.patch.params.max_iter.saturating_add(10));
-                log::debug!("max_iter → {}", self.patch.params.max_iter);
+            InputAction::ExportBundle => self.export_bundle(),
+
+            InputAction::ToggleFullscreen => {
+                let fullscreen = match self.window.fullscreen() {
+                    Some(_) => None,
+                    None => Some(winit::window::Fullscreen::Borderless(None)),
+                };
+                log::info!("Fullscreen: {}", fullscreen.is_some());
+                self.window.set_fullscreen(fullscreen);
             }
 
-            InputAction::IterationsDown => {
-                self.patch.params.max_iter =
-                    clamp_iterations(self.patch.params.max_iter.saturating_sub(10));
-                log::debug!("max_iter → {}", self.patch.params.max_iter);
+            InputAction::ToggleControlWindow => {
+                self.control_request = Some(self.control.is_none());
             }
 
-            InputAction::Reset => {
-                let preset = Preset::ALL[self.current_preset_idx];
-                log::info!("Reset to preset defaults: {}", preset.name());
-                self.patch = preset.build();
-            }
-
-            InputAction::MouseZoom { norm_x, norm_y } => {
-                let w = self.surface_config.width as f32;
-                let h = self.surface_config.height as f32;
-                let aspect = w / h;
-                let (cx, cy, zoom) = apply_zoom(
-                    self.patch.params.center_x,
-                    self.patch.params.center_y,
-                    self.patch.params.zoom,
-                    norm_x,
-                    norm_y,
-                    aspect,
-                );
-                self.patch.params.center_x = cx;
-                self.patch.params.center_y = cy;
-                self.patch.params.zoom = zoom;
-                log::debug!("Zoom → {:.4}  center ({:.6}, {:.6})", zoom, cx, cy);
+            InputAction::ToggleRecording => {
+                if self.recorder.is_some() {
+                    self.stop_recording();
+                } else if let Some(dir) = config::sessions_dir() {
+                    let secs = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs());
+                    self.start_recording(dir.join(config::session_file_name(secs)));
+                } else {
+                    log::warn!("no config directory — input not recorded");
+                    self.toasts.warn(self.tr(Msg::ToastNoConfigSession, &[]));
+                }
+            }
+
+            InputAction::ToggleShaderEditor => {
+                self.shader_editor.open = !self.shader_editor.open;
+            }
+
+            InputAction::RegenerateThumbnails => {
+                log::info!("Regenerating preset thumbnails");
+                self.refresh_thumbnails(true);
+                self.toasts
+                    .info(self.tr(Msg::ToastThumbnailsRegenerated, &[]));
+            }
+
+            InputAction::CueGo => match self.cues.go() {
+                Some(cue) => self.run_cue(&cue),
+                None => self.toasts.info(self.tr(Msg::ToastCuesEnded, &[])),
+            },
+
+            InputAction::CueToggleRun => {
+                if let Some(cue) = self.cues.toggle_running() {
+                    self.run_cue(&cue);
+                }
+                log::info!("Cue timers running: {}", self.cues.is_running());
+            }
+
+            InputAction::ResetSimulation => {
+                self.gen_pass.reset_state();
+                if let Some(passes) = &self.deck_b {
+                    passes.gen_pass.reset_state();
+                }
             }
 
             InputAction::Quit => return true,
+
+            // Preset switching, iterations and zoom live in `AppState`.
+            action => {
+                self.state.handle(&action);
+            }
         }
         false
     }
 
+    // -------------------------------------------------------------------------
+    // Cue lists
+    // -------------------------------------------------------------------------
+
+    /// Replace the cue list with the one in `path`; nothing fires until Go.
+    pub fn load_cues(&mut self, path: &Path) {
+        match CueList::load(path) {
+            Ok(list) => {
+                log::info!("Cue list {}: {} cues", path.display(), list.cues.len());
+                self.toasts
+                    .info(self.tr(Msg::ToastCuesLoaded, &[&list.cues.len()]));
+                self.cues = CuePlayer::new(list);
+            }
+            Err(e) => {
+                log::warn!("failed to load cue list {}: {e}", path.display());
+                self.toasts
+                    .error(self.tr(Msg::ToastCuesFailed, &[&path.display(), &e]));
+            }
+        }
+    }
+
+    /// Feed the Video generator from `source`, a video file or `/dev/video*`
+    /// device, and switch to the Live Video preset.
+    pub fn open_video(&mut self, source: &Path) {
+        match VideoInput::open(source) {
+            Ok(video) => {
+                self.toasts
+                    .info(self.tr(Msg::ToastVideoOpened, &[&video.source().display()]));
+                self.video = Some(video);
+                self.state.load_preset(Preset::LiveVideo);
+            }
+            Err(e) => {
+                log::warn!("failed to open video input {}: {e}", source.display());
+                self.toasts
+                    .error(self.tr(Msg::ToastVideoFailed, &[&source.display(), &e]));
+            }
+        }
+    }
+
+    /// Show the PNG at `path` through the Image generator and switch to the
+    /// Still Image preset.  Stops any video input, which shares its texture.
+    pub fn open_image(&mut self, path: &Path) {
+        match load_image(path) {
+            Ok(image) => {
+                log::info!("Image {}: {}×{}", path.display(), image.width, image.height);
+                let width = image.width;
+                let image = image.fit(self.device.limits().max_texture_dimension_2d);
+                if image.width == width {
+                    self.toasts
+                        .info(self.tr(Msg::ToastImageOpened, &[&path.display()]));
+                } else {
+                    let size = format!("{}×{}", image.width, image.height);
+                    self.toasts
+                        .info(self.tr(Msg::ToastImageDownscaled, &[&path.display(), &size]));
+                }
+                self.video = None;
+                self.set_source_frame(image);
+                self.state.load_preset(Preset::StillImage);
+            }
+            Err(e) => {
+                log::warn!("failed to open image {}: {e}", path.display());
+                self.toasts
+                    .error(self.tr(Msg::ToastImageFailed, &[&path.display(), &e]));
+            }
+        }
+    }
+
+    /// Split the output across tile windows as described by a JSON file.
+    /// The windows themselves are opened by main.rs, which owns the event
+    /// loop, from [`tile_layout`](Self::tile_layout).
+    pub fn load_tiles(&mut self, path: &Path) {
+        match TileLayout::load(path) {
+            Ok(layout) => {
+                let [w, h] = layout.canvas;
+                log::info!("Tile layout {}: {w}×{h}", path.display());
+                self.toasts.info(self.tr(
+                    Msg::ToastTilesLoaded,
+                    &[&layout.tiles.len(), &format!("{w}×{h}")],
+                ));
+                self.tile_windows.clear();
+                self.tiles = Some(layout);
+                let (w, h) = (self.surface_config.width, self.surface_config.height);
+                self.resize(w, h);
+            }
+            Err(e) => {
+                log::warn!("failed to load tile layout {}: {e}", path.display());
+                self.toasts
+                    .error(self.tr(Msg::ToastTilesFailed, &[&path.display(), &e]));
+            }
+        }
+    }
+
+    pub fn tile_layout(&self) -> Option<&TileLayout> {
+        self.tiles.as_ref()
+    }
+
+    /// Title for the window showing tile `index`.
+    pub fn tile_window_title(&self, index: usize) -> String {
+        let name = self
+            .tiles
+            .as_ref()
+            .and_then(|l| l.tiles.get(index))
+            .map(|t| t.name.clone())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| (index + 1).to_string());
+        self.tr(Msg::TileWindowTitle, &[&name])
+    }
+
+    /// Show tile `index` of the loaded layout in `window`.
+    pub fn open_tile_window(&mut self, index: usize, window: Arc<Window>) {
+        let Some(layout) = &self.tiles else {
+            return;
+        };
+        let Some(tile) = layout.tiles.get(index) else {
+            return;
+        };
+        self.tile_windows.push(TileWindow::new(
+            window,
+            index,
+            &self.instance,
+            &self.adapter,
+            &self.device,
+            &self.queue,
+            tile,
+            layout.canvas,
+        ));
+        log::info!("Tile window {} opened", index + 1);
+    }
+
+    pub fn is_tile_window(&self, id: WindowId) -> bool {
+        self.tile_windows.iter().any(|w| w.id() == id)
+    }
+
+    pub fn resize_tile_window(&mut self, id: WindowId, width: u32, height: u32) {
+        if let Some(window) = self.tile_windows.iter_mut().find(|w| w.id() == id) {
+            window.resize(&self.device, width, height);
+        }
+    }
+
+    /// Close one tile window; the others, and the canvas size, stay.
+    pub fn close_tile_window(&mut self, id: WindowId) {
+        self.tile_windows.retain(|w| w.id() != id);
+    }
+
+    /// Warp and edge-blend the window output as described by a JSON file.
+    pub fn load_output_correction(&mut self, path: &Path) {
+        match OutputCorrection::load(path) {
+            Ok(correction) => {
+                self.output
+                    .set_correction(&self.device, &self.queue, &correction);
+                self.output_correction = Some(correction);
+                self.toasts
+                    .info(self.tr(Msg::ToastWarpLoaded, &[&path.display()]));
+            }
+            Err(e) => {
+                log::warn!("failed to load output warp {}: {e}", path.display());
+                self.toasts
+                    .error(self.tr(Msg::ToastWarpFailed, &[&path.display(), &e]));
+            }
+        }
+    }
+
+    /// Switch to `cue`'s patch using its transition.
+    fn run_cue(&mut self, cue: &Cue) {
+        let number = self.cues.current().map_or(0, |i| i + 1);
+        let loaded = match &cue.patch {
+            CuePatch::Preset(preset) => Ok((*preset, self.state.overrides.build(*preset))),
+            CuePatch::Bundle(path) => {
+                PatchBundle::load(path).and_then(|b| Ok((b.base_preset()?, b.build()?)))
+            }
+        };
+        match loaded {
+            Ok((preset, patch)) => {
+                log::info!("Cue {number}: {}", cue.label());
+                self.toasts
+                    .info(self.tr(Msg::ToastCue, &[&number, &cue.label()]));
+                self.state
+                    .queue_patch(preset, patch, cue.transition.quantize());
+            }
+            Err(e) => {
+                log::warn!("cue {number} ({}) failed: {e}", cue.label());
+                self.toasts
+                    .error(self.tr(Msg::ToastCueFailed, &[&number, &e]));
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Input sessions
+    // -------------------------------------------------------------------------
+
+    /// Append every following action to a session file at `path`.
+    pub fn start_recording(&mut self, path: PathBuf) {
+        let view = self.state.view_code();
+        match Recorder::create(path, view) {
+            Ok(recorder) => {
+                log::info!("Recording input to {}", recorder.path().display());
+                self.toasts
+                    .info(self.tr(Msg::ToastRecordingStarted, &[&recorder.path().display()]));
+                self.recorder = Some(recorder);
+            }
+            Err(e) => {
+                log::warn!("failed to start input recording: {e}");
+                self.toasts.error(self.tr(Msg::ToastRecordingFailed, &[&e]));
+            }
+        }
+    }
+
+    fn stop_recording(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            log::info!("Input recording saved: {}", recorder.path().display());
+            self.toasts
+                .info(self.tr(Msg::ToastRecordingSaved, &[&recorder.path().display()]));
+        }
+    }
+
+    /// Jump to a recorded session's starting view and replay its actions on
+    /// their original schedule.
+    pub fn start_replay(&mut self, path: &Path, looping: bool) {
+        let session = match Session::load(path) {
+            Ok(session) => session,
+            Err(e) => {
+                log::warn!("failed to load session {}: {e}", path.display());
+                self.toasts
+                    .error(self.tr(Msg::ToastReplayFailed, &[&path.display(), &e]));
+                return;
+            }
+        };
+        if let Err(e) = self.apply_view_code(&session.header.view) {
+            log::warn!("session start view ignored: {e}");
+        }
+        log::info!(
+            "Replaying {} ({} actions{})",
+            path.display(),
+            session.events.len(),
+            if looping { ", looping" } else { "" }
+        );
+        self.toasts
+            .info(self.tr(Msg::ToastReplayStarted, &[&path.display()]));
+        self.replay = Some(Replay::new(session.events, looping));
+        self.replay_started = Instant::now();
+    }
+
+    /// Run the replay actions that are due.  Returns `true` if one of them
+    /// quit the app.
+    pub fn tick_replay(&mut self) -> bool {
+        let Some(replay) = &mut self.replay else {
+            return false;
+        };
+        let due = replay.due(self.replay_started.elapsed().as_secs_f64());
+        let finished = replay.is_finished();
+        for action in due {
+            if self.handle_action(action) {
+                return true;
+            }
+        }
+        if finished {
+            log::info!("Replay finished");
+            self.toasts.info(self.tr(Msg::ToastReplayFinished, &[]));
+            self.replay = None;
+        }
+        false
+    }
+
+    /// `msg` in the current UI language, with placeholders filled.
+    fn tr(&self, msg: Msg, args: &[&dyn std::fmt::Display]) -> String {
+        trf(self.settings.language, msg, args)
+    }
+
+    /// Write the override store to disk; failures are logged, not fatal.
+    fn save_overrides(&mut self) {
+        let Some(path) = &self.overrides_path else {
+            log::warn!("no config directory — overrides not persisted");
+            self.toasts.warn(self.tr(Msg::ToastNoConfigTweaks, &[]));
+            return;
+        };
+        if let Err(e) = self.state.overrides.save(path) {
+            log::warn!("failed to write overrides to {}: {e}", path.display());
+            self.toasts
+                .error(self.tr(Msg::ToastSaveTweaksFailed, &[&e]));
+        }
+    }
+
+    /// Write the MIDI controller map; failures are logged, not fatal.
+    fn save_controller_map(&mut self) {
+        let Some(path) = &self.controller_map_path else {
+            return;
+        };
+        if let Err(e) = self.state.midi.map.save(path) {
+            log::warn!("failed to write controller map to {}: {e}", path.display());
+            self.toasts
+                .error(self.tr(Msg::ToastSaveControllerMapFailed, &[&e]));
+        }
+    }
+
+    /// Apply MIDI messages received since the last frame.
+    fn poll_midi(&mut self) {
+        let Some(input) = &self.midi_input else {
+            return;
+        };
+        let messages: Vec<Vec<u8>> = input.drain().collect();
+        for bytes in messages {
+            if let Some(binding) = self.state.midi_message(&bytes) {
+                self.toasts
+                    .info(self.tr(Msg::ToastMidiBound, &[&binding.label(), &binding.key]));
+                self.save_controller_map();
+            }
+        }
+    }
+
+    /// The flash trigger, then the current patch's learnable params sorted,
+    /// each with the label of the controller bound to it.
+    fn midi_params(&self) -> Vec<(String, Option<String>)> {
+        let mut keys: Vec<&str> = self
+            .state
+            .patch
+            .params
+            .fields
+            .keys()
+            .map(String::as_str)
+            .filter(|k| is_learnable(k))
+            .collect();
+        keys.sort();
+        std::iter::once(FLASH_KEY)
+            .chain(keys)
+            .map(|k| {
+                (
+                    k.to_string(),
+                    self.state.midi.map.binding_for(k).map(|b| b.label()),
+                )
+            })
+            .collect()
+    }
+
+    /// Write the settings file; failures are logged, not fatal.
+    fn save_settings(&mut self) {
+        let Some(path) = &self.settings_path else {
+            return;
+        };
+        if let Err(e) = self.settings.save(path) {
+            log::warn!("failed to write settings to {}: {e}", path.display());
+            self.toasts
+                .error(self.tr(Msg::ToastSaveSettingsFailed, &[&e]));
+        }
+    }
+
+    /// Restore the crashed session on offer, if any, without asking.
+    pub fn resume_session(&mut self) {
+        self.answer_resume(true);
+    }
+
+    /// Restore (or drop) the crashed session on offer: its patch, deep
+    /// view and settings.
+    fn answer_resume(&mut self, restore: bool) {
+        let Some((snapshot, _)) = self.resume_offer.take() else {
+            return;
+        };
+        if !restore {
+            return;
+        }
+        match snapshot.build() {
+            Ok((preset, patch)) => {
+                log::info!("Restored last session ({})", preset.name());
+                self.toasts
+                    .info(self.tr(Msg::ToastSessionRestored, &[&preset.name()]));
+                self.state.set_patch(preset, patch);
+                self.settings = snapshot.settings;
+                self.settings_dirty = true;
+            }
+            Err(e) => {
+                log::warn!("failed to restore last session: {e}");
+                self.toasts
+                    .error(self.tr(Msg::ToastSessionNotRestored, &[&e]));
+            }
+        }
+    }
+
+    /// Snapshot the session every [`AUTOSAVE_INTERVAL`](crate::autosave::AUTOSAVE_INTERVAL).
+    fn tick_autosave(&mut self, dt: f32) {
+        if self.resume_offer.is_some() {
+            return;
+        }
+        let Some(autosave) = &mut self.autosave else {
+            return;
+        };
+        if !autosave.tick(dt) {
+            return;
+        }
+        let snapshot = Snapshot::capture(self.state.preset(), &self.state.patch, &self.settings);
+        if let Err(e) = autosave.save(snapshot) {
+            log::warn!("autosave failed: {e}");
+            let text = self.tr(Msg::ToastAutosaveFailed, &[&e]);
+            self.toasts.push(ToastLevel::Error, text, Some("autosave"));
+        }
+    }
+
+    /// The session is ending cleanly: remove the autosave so the next
+    /// start doesn't offer it.
+    pub fn shutdown(&mut self) {
+        if let Some(autosave) = &mut self.autosave {
+            if let Err(e) = autosave.clear() {
+                log::warn!("failed to remove autosave: {e}");
+            }
+        }
+    }
+
+    /// Use `ceiling` as the iteration ceiling for this run without touching
+    /// the saved setting.
+    pub fn override_max_iterations(&mut self, ceiling: u32) {
+        let ceiling = clamp_ceiling(ceiling);
+        self.max_iterations_override = Some(ceiling);
+        self.state.max_iterations = ceiling;
+    }
+
+    /// Write the current look to `<config>/patches/<preset>.fractalpatch`.
+    fn export_bundle(&mut self) {
+        let Some(dir) = config::bundles_dir() else {
+            log::warn!("no config directory — bundle not exported");
+            self.toasts.warn(self.tr(Msg::ToastNoConfigBundle, &[]));
+            return;
+        };
+        let preset = self.state.preset();
+        let path = dir.join(config::bundle_file_name(preset.name()));
+        let mut bundle = PatchBundle::capture_patch(preset, &self.state.patch);
+        self.capture_bundle_shaders(&mut bundle);
+        let author = self.settings.author.trim();
+        if !author.is_empty() && self.state.patch.meta.author().is_none() {
+            bundle.meta = Some(self.state.patch.meta.clone().with_author(author));
+        }
+        match bundle.save(&path) {
+            Ok(()) => {
+                log::info!("Exported bundle: {}", path.display());
+                self.toasts
+                    .info(self.tr(Msg::ToastPatchExported, &[&path.display()]));
+            }
+            Err(e) => {
+                log::warn!("failed to export bundle to {}: {e}", path.display());
+                self.toasts
+                    .error(self.tr(Msg::ToastPatchExportFailed, &[&e]));
+                return;
+            }
+        }
+
+        // Ship a preview alongside the bundle.
+        if let Ok(mut patch) = bundle.build() {
+            let rgba = self.render_thumbnail(&mut patch);
+            let png = dir.join(config::thumbnail_file_name(preset.name()));
+            if let Err(e) = thumbnails::save_png(&png, THUMBNAIL_SIZE, &rgba) {
+                log::warn!("failed to write bundle thumbnail {}: {e}", png.display());
+            }
+        }
+    }
+
+    /// Put the shader editor's edits to the current patch's generator and
+    /// effects, and any gradient the colour map runs, into `bundle`.
+    fn capture_bundle_shaders(&self, bundle: &mut PatchBundle) {
+        let patch = &self.state.patch;
+        let editor = &self.shader_editor;
+        let generator = ShaderTarget::Generator(patch.generator.kind());
+        bundle.wgsl = editor.edit_for(&generator).map(str::to_string);
+        bundle.gradient = editor.gradient().map(<[_]>::to_vec);
+        let effects: BTreeMap<String, String> = patch
+            .effects
+            .iter()
+            .map(|e| e.kind(&patch.params))
+            .filter(|kind| {
+                !(bundle.gradient.is_some() && matches!(kind, EffectKind::ColorMap { .. }))
+            })
+            .filter_map(|kind| {
+                let src = editor.edit_for(&ShaderTarget::Effect(kind.clone()))?;
+                Some((kind.id().to_string(), src.to_string()))
+            })
+            .collect();
+        bundle.effect_wgsl = (!effects.is_empty()).then_some(effects);
+    }
+
+    // -------------------------------------------------------------------------
+    // Thumbnails
+    // -------------------------------------------------------------------------
+
+    fn render_thumbnail(&mut self, patch: &mut Patch) -> Vec<u8> {
+        self.thumb_renderer
+            .render(&self.device, &self.queue, patch, THUMBNAIL_TIME)
+    }
+
+    /// Load (or, if missing / `force`, render and cache) every preset's
+    /// thumbnail.
+    fn refresh_thumbnails(&mut self, force: bool) {
+        for idx in 0..Preset::ALL.len() {
+            self.refresh_thumbnail(idx, force);
+        }
+    }
+
+    /// Load the cached thumbnail for `Preset::ALL[idx]`, rendering it (with
+    /// the user's overrides applied) when missing or when `force` is set.
+    fn refresh_thumbnail(&mut self, idx: usize, force: bool) {
+        let preset = Preset::ALL[idx];
+        let path =
+            config::thumbnails_dir().map(|d| d.join(config::thumbnail_file_name(preset.name())));
+
+        let cached = match &path {
+            Some(p) if !force => thumbnails::load_png(p, THUMBNAIL_SIZE),
+            _ => None,
+        };
+        let rgba = cached.unwrap_or_else(|| {
+            let mut patch = self.state.overrides.build(preset);
+            let rgba = self.render_thumbnail(&mut patch);
+            if let Some(p) = &path {
+                if let Err(e) = thumbnails::save_png(p, THUMBNAIL_SIZE, &rgba) {
+                    log::warn!("failed to cache thumbnail {}: {e}", p.display());
+                }
+            }
+            rgba
+        });
+
+        let size = [THUMBNAIL_SIZE as usize; 2];
+        let image = egui::ColorImage::from_rgba_unmultiplied(size, &rgba);
+        self.thumbnails[idx] = Some(image);
+        self.thumbnails_generation += 1;
+    }
+
+    // -------------------------------------------------------------------------
+    // Shader warm-up
+    // -------------------------------------------------------------------------
+
+    /// Draw the next queued warm-up preset into `encoder`, ahead of the real
+    /// frame, which overwrites every texture it touched.  Nothing is
+    /// presented to `target`.
+    fn warm_up_next(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        projection: Projection,
+    ) {
+        let Some(preset) = self.warm_up.next() else {
+            return;
+        };
+        let patch = self.state.overrides.build(preset);
+        // Half resolution dispatches the blurs together with the
+        // downsample / upsample pair, and makes their textures.
+        self.effect_pass.half_res = true;
+        let mut frame = WgpuFrame::new(
+            &self.device,
+            &self.queue,
+            encoder,
+            &self.gen_pass,
+            &mut self.effect_pass,
+            &mut self.pp,
+            &self.output,
+            target,
+        );
+        self.state.encode_warm_up(&mut frame, &patch, projection);
+        let (done, total) = self.warm_up.progress();
+        let text = self.tr(Msg::ToastWarmingUp, &[&done, &total]);
+        self.toasts.push(ToastLevel::Info, text, Some("warm_up"));
+    }
+
+    /// Start, stop or rebind the preview stream to match the settings.
+    fn sync_stream(&mut self) {
+        let wanted = self
+            .settings
+            .stream_enabled
+            .then_some((self.settings.stream_port, self.settings.stream_lan));
+        if self.stream.as_ref().map(|s| (s.port(), s.lan())) == wanted {
+            return;
+        }
+        // Dropping the old server releases its port before rebinding.
+        self.stream = None;
+        self.preview = None;
+        let Some((port, lan)) = wanted else {
+            return;
+        };
+        match StreamServer::start(port, lan, self.params_snapshot.reader()) {
+            Ok(server) => {
+                let (width, height) = self.render_size();
+                let (w, h) = preview_size(width, height, STREAM_WIDTH);
+                self.preview = Some(PreviewCapture::new(&self.device, w, h));
+                self.stream = Some(server);
+            }
+            Err(e) => {
+                log::warn!("failed to start preview stream on port {port}: {e}");
+                self.toasts.error(self.tr(Msg::ToastStreamFailed, &[&e]));
+            }
+        }
+    }
+
+    /// Hand a finished preview capture to the stream server.
+    fn publish_preview(&mut self) {
+        let (Some(server), Some(preview)) = (&self.stream, &mut self.preview) else {
+            return;
+        };
+        let Some(rgba) = preview.poll(&self.device) else {
+            return;
+        };
+        let (w, h) = preview.size();
+        match encode_jpeg(&rgba, w, h) {
+            Ok(jpeg) => server.publish(jpeg),
+            Err(e) => log::warn!("preview frame not encoded: {e}"),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Watchdog and GPU recovery
+    // -------------------------------------------------------------------------
+
+    /// Start, restart or stop the watchdog to match the settings.
+    fn sync_watchdog(&mut self) {
+        let wanted = self.settings.watchdog_timeout();
+        if self.watchdog.as_ref().map(Watchdog::timeout) == wanted {
+            return;
+        }
+        self.watchdog = None;
+        let Some(timeout) = wanted else {
+            return;
+        };
+        match Watchdog::start(timeout) {
+            Ok(watchdog) => {
+                self.watchdog = Some(watchdog);
+                self.arm_watchdog();
+            }
+            Err(e) => log::warn!("failed to start the watchdog: {e}"),
+        }
+    }
+
+    /// Point the watchdog at the current device: describe it for the hang
+    /// report and trip on its loss.
+    fn arm_watchdog(&self) {
+        let Some(watchdog) = &self.watchdog else {
+            return;
+        };
+        watchdog.set_diagnostics(watchdog::describe_adapter(&self.adapter.get_info()));
+        let tripwire = watchdog.tripwire();
+        self.device
+            .set_device_lost_callback(move |reason, message| match reason {
+                // Our own drop of the device during a rebuild, or a newer
+                // callback replacing this one.
+                wgpu::DeviceLostReason::Dropped
+                | wgpu::DeviceLostReason::Destroyed
+                | wgpu::DeviceLostReason::ReplacedCallback => {}
+                _ => {
+                    log::error!("GPU device lost ({reason:?}): {message}");
+                    tripwire.trip();
+                }
+            });
+    }
+
+    /// Replace the device and everything created on it after a hung frame
+    /// or a lost device.  The patch, settings and windows carry over; if no
+    /// device can be had the old one is kept and the next trip tries again.
+    fn rebuild_gpu(&mut self) {
+        let (width, height) = self.render_size();
+        log::warn!(
+            "rebuilding GPU state: {} at {width}×{height}, last frame {:?}",
+            self.state.preset().name(),
+            self.gpu_stats
+        );
+        let Some(adapter) = request_adapter(&self.instance, &self.surface) else {
+            log::error!("GPU rebuild failed: no suitable adapter");
+            self.toasts
+                .error(self.tr(Msg::ToastGpuRebuildFailed, &[&"no suitable adapter"]));
+            return;
+        };
+        let (device, queue) = match request_device(&adapter) {
+            Ok(pair) => pair,
+            Err(e) => {
+                log::error!("GPU rebuild failed: {e}");
+                self.toasts
+                    .error(self.tr(Msg::ToastGpuRebuildFailed, &[&e]));
+                return;
+            }
+        };
+        log::info!("GPU adapter: {}", adapter.get_info().name);
+        self.adapter = adapter;
+        self.device = device;
+        self.queue = queue;
+        self.surface.configure(&self.device, &self.surface_config);
+
+        // Output and HUD.  egui only uploads a texture once, so its context
+        // starts over too, and with it every texture it holds.
+        self.output = OutputPass::new(&self.device, self.surface_config.format);
+        if let Some(correction) = &self.output_correction {
+            self.output
+                .set_correction(&self.device, &self.queue, correction);
+        }
+        self.egui_ctx = egui::Context::default();
+        self.settings.apply(&self.egui_ctx);
+        self.egui_state = egui_winit::State::new(
+            self.egui_ctx.clone(),
+            egui::ViewportId::ROOT,
+            &*self.window,
+            Some(self.window.scale_factor() as f32),
+            None,
+            Some(self.device.limits().max_texture_dimension_2d as usize),
+        );
+        self.egui_renderer =
+            egui_wgpu::Renderer::new(&self.device, self.surface_config.format, None, 1, false);
+        self.thumb_textures = ThumbnailTextures::default();
+        self.thumbnails_generation += 1;
+
+        // Passes, with the shader editor's edits and the footage restored.
+        let mut gen_pass = GeneratorPass::new(&self.device, width, height);
+        self.restore_generator_edits(&mut gen_pass);
+        self.upload_source_frame(&mut gen_pass);
+        self.gen_pass = gen_pass;
+        self.effect_pass = EffectPass::new(&self.device);
+        for (kind, src) in self.shader_editor.effect_edits() {
+            if let Err(e) = self.effect_pass.set_source(&self.device, kind, src) {
+                let name = effect_name(kind);
+                log::warn!("failed to restore edited {name} shader: {e}");
+                self.toasts
+                    .error(self.tr(Msg::ToastShaderNotRestored, &[&name, &e]));
+            }
+        }
+        self.pp = PingPong::new(&self.device, width, height);
+        self.deck_b = None;
+        self.overlay_pass = OverlayPass::new(&self.device, width, height);
+        self.overlay_ready = false;
+        self.probe = ProbePass::new(&self.device);
+        self.cursor_probe = None;
+        self.thumb_renderer = ThumbnailRenderer::new(&self.device, THUMBNAIL_SIZE);
+        self.warm_up = WarmUp::new(self.state.preset());
+        if self.preview.is_some() {
+            let (w, h) = preview_size(width, height, STREAM_WIDTH);
+            self.preview = Some(PreviewCapture::new(&self.device, w, h));
+        }
+
+        // Extra windows get new surfaces on the new device.
+        for tile_window in std::mem::take(&mut self.tile_windows) {
+            let (index, window) = tile_window.into_parts();
+            self.open_tile_window(index, window);
+        }
+        if let Some(control) = self.control.take() {
+            self.open_control_window(control.into_window());
+        }
+
+        self.arm_watchdog();
+        self.toasts.warn(self.tr(Msg::ToastGpuRebuilt, &[]));
+    }
+
+    /// Load a `.fractalpatch` bundle, replacing the current patch.
+    pub fn import_bundle(&mut self, path: &Path) {
+        let result = PatchBundle::load(path).and_then(|b| Ok((b.base_preset()?, b.build()?, b)));
+        match result {
+            Ok((preset, patch, bundle)) => {
+                log::info!("Imported bundle: {} ({})", path.display(), preset.name());
+                self.toasts
+                    .info(self.tr(Msg::ToastPatchImported, &[&path.display()]));
+                let unused = patch.unconsumed_targets();
+                if !unused.is_empty() {
+                    self.toasts
+                        .warn(self.tr(Msg::ToastUnusedTargets, &[&unused.join(", ")]));
+                }
+                let feedback = patch.feedback_keys();
+                if !feedback.is_empty() {
+                    self.toasts
+                        .warn(self.tr(Msg::ToastModulationFeedback, &[&feedback.join(", ")]));
+                }
+                self.state.set_patch(preset, patch);
+                self.load_bundle_shaders(&bundle);
+            }
+            Err(e) => {
+                log::warn!("failed to import bundle {}: {e}", path.display());
+                self.toasts
+                    .error(self.tr(Msg::ToastPatchImportFailed, &[&e]));
+            }
+        }
+    }
+
+    /// Jump to the location encoded in a view code.  Switches preset only if
+    /// the current one uses a different generator.
+    pub fn apply_view_code(&mut self, code: &str) -> Result<(), ViewCodeError> {
+        self.state.apply_view_code(code)?;
+        log::info!("Applied view code: {}", code.trim());
+        self.toasts.info(self.tr(Msg::ToastJumpedToView, &[]));
+        Ok(())
+    }
+
+    /// Compile an imported bundle's custom WGSL and gradient into the
+    /// passes the current patch uses, as if typed into the shader editor.
+    fn load_bundle_shaders(&mut self, bundle: &PatchBundle) {
+        let patch = &self.state.patch;
+        let mut sources = Vec::new();
+        if let Some(src) = &bundle.wgsl {
+            let target = ShaderTarget::Generator(patch.generator.kind());
+            sources.push((target, src.clone(), None));
+        }
+        for kind in patch.effects.iter().map(|e| e.kind(&patch.params)) {
+            let edit = bundle.effect_wgsl.as_ref().and_then(|m| m.get(kind.id()));
+            let gradient = match (&kind, &bundle.gradient) {
+                (EffectKind::ColorMap { .. }, Some(stops)) => Some(stops),
+                _ => None,
+            };
+            if let Some(stops) = gradient {
+                let src = EffectPass::gradient_source(stops);
+                sources.push((ShaderTarget::Effect(kind), src, Some(stops.clone())));
+            } else if let Some(src) = edit {
+                sources.push((ShaderTarget::Effect(kind), src.clone(), None));
+            }
+        }
+        for (target, src, gradient) in sources {
+            match self.compile_shader(&target, &src) {
+                Ok(()) => self.shader_editor.report_loaded(&target, src, gradient),
+                Err(e) => {
+                    log::warn!("bundle shader failed to compile: {e}");
+                    self.toasts
+                        .error(self.tr(Msg::ToastShaderCompileFailed, &[&e]));
+                }
+            }
+        }
+    }
+
+    /// Recompile `target` from `src` on every pass that runs it.  On error
+    /// the previous pipeline stays in place.
+    fn compile_shader(&mut self, target: &ShaderTarget, src: &str) -> Result<(), ShaderError> {
+        match target {
+            ShaderTarget::Generator(kind) => self
+                .gen_pass
+                .set_source(&self.device, *kind, src)
+                .and_then(|()| match &mut self.deck_b {
+                    Some(deck) => deck.gen_pass.set_source(&self.device, *kind, src),
+                    None => Ok(()),
+                }),
+            ShaderTarget::Effect(kind) => self.effect_pass.set_source(&self.device, kind, src),
+        }
+    }
+
+    /// Apply a shader editor request to the GPU passes and report back.
+    fn apply_editor_request(&mut self, request: EditorRequest) {
+        match request {
+            EditorRequest::Compile(target, src) => {
+                let result = self.compile_shader(&target, &src);
+                match &result {
+                    Ok(()) => self.toasts.dismiss("shader"),
+                    Err(e) => self.toasts.push(
+                        ToastLevel::Error,
+                        self.tr(Msg::ToastShaderCompileFailed, &[&e])
+                            .lines()
+                            .next()
+                            .unwrap_or_default(),
+                        Some("shader"),
+                    ),
+                }
+                self.shader_editor.report_compile(&target, src, result);
+            }
+            EditorRequest::Revert(target) => {
+                match self.compile_shader(&target, target.default_source()) {
+                    Ok(()) => {
+                        self.shader_editor.report_revert(&target);
+                        self.toasts.dismiss("shader");
+                        self.toasts.info(self.tr(Msg::ToastShaderReverted, &[]));
+                    }
+                    Err(e) => {
+                        log::error!("built-in shader failed to recompile: {e}");
+                        self.toasts
+                            .error(self.tr(Msg::ToastBuiltinShaderFailed, &[&e]));
+                    }
+                }
+            }
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Render
     // -------------------------------------------------------------------------
 
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        if self.watchdog.as_ref().is_some_and(Watchdog::take_tripped) {
+            self.rebuild_gpu();
+        }
+
         // --- Timing ----------------------------------------------------------
         let now = Instant::now();
         let dt = now.duration_since(self.last_frame).as_secs_f32();
         self.last_frame = now;
-        self.patch.tick(dt);
+        self.poll_midi();
+        if let Some(cue) = self.cues.tick(dt) {
+            self.run_cue(&cue);
+        }
+        self.state.tick(dt);
+        self.tick_autosave(dt);
 
-        if let Some(fps) = self.fps.tick() {
+        let drag = self.pan.tick(dt, self.settings.pan_friction);
+        if drag != (0.0, 0.0) {
+            let scale = self.frame_scale();
+            self.state.pan_pixels((drag.0 * scale, drag.1 * scale));
+        }
+        self.params_snapshot.publish(&self.state.patch.params);
+
+        if let Some(fps) = self.fps.tick(dt) {
             log::debug!(
                 "FPS: {:.1}  preset: {}  zoom: {:.2}  iter: {}",
                 fps,
-                Preset::ALL[self.current_preset_idx].name(),
-                self.patch.params.zoom,
-                self.patch.params.max_iter,
+                self.state.preset().name(),
+                self.state.patch.params.zoom,
+                self.state.patch.params.max_iter,
             );
         }
 
         let width = self.surface_config.width;
         let height = self.surface_config.height;
 
-        // --- Build uniforms --------------------------------------------------
-        let params = &self.patch.params;
-        let uniforms = Uniforms {
-            resolution: [width as f32, height as f32],
-            center: [params.center_x, params.center_y],
-            zoom: params.zoom,
-            time: params.time,
-            max_iter: params.max_iter,
-            _pad: 0,
-            julia_c: [params.get("julia_cx"), params.get("julia_cy")],
-            _pad2: [0.0, 0.0],
-        };
-
-        let gen_kind = self.patch.generator.kind();
-        let effect_kinds: Vec<_> = self.patch.effects.iter().map(|e| e.kind(params)).collect();
+        let gen_kind = self.state.patch.generator.kind();
+        let effect_kinds = self.state.effect_kinds();
 
         // --- egui frame (CPU side — must happen before GPU encoding) ---------
         // Collect HUD values before calling egui to avoid borrowing self inside
         // the closure.
-        let preset_name = Preset::ALL[self.current_preset_idx].name();
-        let zoom = self.patch.params.zoom;
-        let max_iter = self.patch.params.max_iter;
-        let fps_display = self.fps.fps();
+        self.sync_window_title();
+        let lang = self.settings.language;
         let effect_labels: Vec<&'static str> = effect_kinds.iter().map(effect_name).collect();
+        let hud = HudData {
+            lang,
+            preset_name: self.state.preset().name(),
+            meta: self.state.patch.meta.clone(),
+            preset_slot: preset_hotkey_label(self.state.current_preset_idx).unwrap_or_default(),
+            current_idx: self.state.current_preset_idx,
+            zoom: self.state.patch.params.zoom,
+            max_iter: self.state.effective_max_iter(),
+            cursor_probe: self.cursor_probe,
+            auto_iterations: self.state.auto_iterations.is_some(),
+            effect_labels: effect_labels.clone(),
+            fps: self.fps.fps(),
+            fps_low: self.fps.one_percent_low().unwrap_or(0.0),
+            frame_times: self.fps.frame_times().collect(),
+            worst_frame: self.fps.worst_frame_time().unwrap_or(0.0),
+            view_code: self.state.view_code(),
+            gpu_stats: self.gpu_stats,
+            alloc_stats: self.alloc_stats,
+            bpm: self.state.beat.bpm(),
+            pending_preset: self.state.pending_preset().map(Preset::name),
+            recording: self.recorder.is_some(),
+            replaying: self.replay.is_some(),
+            stream: self
+                .stream
+                .as_ref()
+                .map(|s| (s.port(), s.viewers(), s.lan())),
+            lfos: self.state.patch.lfos(),
+            formula: self
+                .state
+                .patch
+                .generator
+                .formula()
+                .map(|f| f.source().to_string()),
+            feedback: self.state.patch.feedback_keys(),
+            repaired: self.state.patch.repaired.iter().cloned().collect(),
+            macros: self
+                .state
+                .patch
+                .macros
+                .iter()
+                .map(|m| (m.name.clone(), m.value(&self.state.patch.params)))
+                .collect(),
+            cues: self
+                .cues
+                .list
+                .cues
+                .iter()
+                .map(|c| (c.label(), c.hold))
+                .collect(),
+            cue_current: self.cues.current(),
+            cues_running: self.cues.is_running(),
+            deck_b: self.state.deck_b.as_ref().map(|(preset, _)| preset.name()),
+            crossfade: self.state.crossfade,
+            midi_port: self.midi_input.as_ref().map(|m| m.port_name().to_string()),
+            midi_params: self.midi_params(),
+            midi_learning: self.state.midi.learning().map(str::to_string),
+            detached: self.control.is_some(),
+            resume: self.resume_offer.as_ref().map(|(snapshot, minutes)| {
+                let name = snapshot.bundle.base_preset().map_or("?", Preset::name);
+                (*minutes, name)
+            }),
+        };
 
+        let mut shader_targets = vec![(
+            trf(lang, Msg::ShaderGenerator, &[&format!("{gen_kind:?}")]),
+            ShaderTarget::Generator(gen_kind),
+        )];
+        for (kind, label) in effect_kinds.iter().zip(&effect_labels) {
+            let target = ShaderTarget::Effect(kind.clone());
+            if !shader_targets.iter().any(|(_, t)| t.same_as(&target)) {
+                shader_targets.push((trf(lang, Msg::ShaderEffect, &[label]), target));
+            }
+        }
+        let mut new_settings = self.settings.clone();
+        self.toasts.tick(dt);
+        self.pointer_idle.tick(dt);
+        let crosshair = self.settings.crosshair;
+        let panning = self.pan.is_grabbed();
+        // Drags are tracked in rendered pixels; the overlay is drawn in
+        // window coordinates.
+        let frame = self.frame;
+        let aspect = frame.width as f32 / frame.height as f32;
+        let scale = self.frame_scale();
+        let to_window =
+            |(x, y): (f32, f32)| (x / scale + frame.x as f32, y / scale + frame.y as f32);
+        let drag_box = self.input.drag().filter(|d| d.is_drag()).map(|d| {
+            (
+                to_window(d.start),
+                to_window(d.end_corner(self.settings.lock_box_aspect, aspect)),
+                d.zoom_out,
+            )
+        });
+        let cursor_hidden = self.window.fullscreen().is_some()
+            && self.pointer_idle.expired(self.settings.cursor_hide_after);
+
+        // With a control window open the panels live there and the main
+        // window shows only the visuals.
+        let mut response = HudResponse::default();
+        if let Some(control) = &mut self.control {
+            response = control.render(
+                &self.device,
+                &self.queue,
+                &self.thumbnails,
+                self.thumbnails_generation,
+                |ctx, thumbs| {
+                    Controls {
+                        hud: &hud,
+                        thumbs,
+                        settings: &mut new_settings,
+                        shader_editor: &mut self.shader_editor,
+                        shader_targets: &shader_targets,
+                        toasts: &self.toasts,
+                    }
+                    .show_full(ctx)
+                },
+            );
+        }
+        let detached = self.control.is_some();
+        let thumb_ids = if detached {
+            Vec::new()
+        } else {
+            self.thumb_textures
+                .sync(&self.egui_ctx, &self.thumbnails, self.thumbnails_generation)
+        };
+        let shader_editor = &mut self.shader_editor;
+        let toasts = &self.toasts;
+
+        // Text fields (e.g. the shader editor) get pastes, not the view code.
+        let editing_text = self.egui_ctx.wants_keyboard_input();
         let raw_input = self.egui_state.take_egui_input(&self.window);
+        // A pasted view code (Ctrl/Cmd+V) jumps to that location next frame.
+        let pasted = raw_input
+            .events
+            .iter()
+            .find_map(|e| match e {
+                egui::Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            })
+            .filter(|_| !editing_text);
         let full_output = self.egui_ctx.run(raw_input, |ctx| {
-            egui::Window::new("Fractal Explorer")
-                .anchor(egui::Align2::LEFT_TOP, [10.0, 10.0])
-                .collapsible(false)
-                .resizable(false)
-                .frame(
-                    egui::Frame::window(&ctx.style())
-                        .fill(egui::Color32::from_rgba_unmultiplied(0, 0, 0, 200)),
-                )
-                .show(ctx, |ui| {
-                    ui.label(format!("Preset:  {preset_name}"));
-                    ui.label(format!("Zoom:    {zoom:.2}×"));
-                    ui.label(format!("Iter:    {max_iter}"));
-                    let fx = if effect_labels.is_empty() {
-                        "none".to_string()
-                    } else {
-                        effect_labels.join(", ")
-                    };
-                    ui.label(format!("Effects: {fx}"));
-                    ui.label(format!("FPS:     {fps_display:.1}"));
-                    ui.separator();
-                    ui.label("1–5  load preset   Space  cycle");
-                    ui.label("+/-  iterations    R  reset");
-                    ui.label("Click  zoom        Q/Esc  quit");
-                });
+            if !detached {
+                response = Controls {
+                    hud: &hud,
+                    thumbs: &thumb_ids,
+                    settings: &mut new_settings,
+                    shader_editor,
+                    shader_targets: &shader_targets,
+                    toasts,
+                }
+                .show_overlay(ctx);
+            }
+
+            if let Some((start, end, zoom_out)) = drag_box {
+                let ppp = ctx.pixels_per_point();
+                let rect = egui::Rect::from_two_pos(
+                    egui::pos2(start.0, start.1) / ppp,
+                    egui::pos2(end.0, end.1) / ppp,
+                );
+                let color = if zoom_out {
+                    egui::Color32::from_rgb(255, 170, 60)
+                } else {
+                    egui::Color32::WHITE
+                };
+                let painter = ctx.layer_painter(egui::LayerId::background());
+                painter.rect_filled(rect, 0.0, color.gamma_multiply(0.12));
+                painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.5, color));
+            }
+
+            // The crosshair replaces the OS cursor over the fractal; both
+            // vanish after the idle timeout in fullscreen.
+            if cursor_hidden {
+                ctx.set_cursor_icon(egui::CursorIcon::None);
+            } else if panning {
+                ctx.set_cursor_icon(egui::CursorIcon::Grabbing);
+            } else if crosshair != CrosshairStyle::None && !ctx.is_pointer_over_area() {
+                if let Some(pos) = ctx.pointer_hover_pos() {
+                    ctx.set_cursor_icon(egui::CursorIcon::None);
+                    draw_crosshair(
+                        &ctx.layer_painter(egui::LayerId::background()),
+                        pos,
+                        crosshair,
+                    );
+                }
+            }
         });
         self.egui_state
             .handle_platform_output(&self.window, full_output.platform_output);
 
+        if new_settings != self.settings {
+            self.settings = new_settings;
+            self.settings_dirty = true;
+        }
+        // egui's own Ctrl +/- zoom also changes the UI scale; keep it in sync.
+        let host_ctx = self
+            .control
+            .as_ref()
+            .map_or(&self.egui_ctx, |c| c.egui_ctx());
+        let egui_zoom = host_ctx.zoom_factor();
+        if !self.settings_dirty && (egui_zoom - self.settings.ui_scale).abs() > 1e-3 {
+            self.settings.ui_scale = egui_zoom.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+            self.settings_dirty = true;
+        }
+        if self.settings_dirty && !response.slider_dragging {
+            self.settings.apply(&self.egui_ctx);
+            if let Some(control) = &self.control {
+                self.settings.apply(control.egui_ctx());
+                control.set_title(&self.control_window_title());
+            }
+            self.state.quantize = self.settings.quantize;
+            self.state.lfo_retrigger = self.settings.lfo_retrigger;
+            self.state.flash_frames = self.settings.flash_frames;
+            self.state.flash_every = self.settings.flash_every;
+            self.state.set_auto_gain(self.settings.audio_auto_gain);
+            self.state.auto_iterations = self.settings.auto_iterations_cap();
+            self.state.smooth_iterations = self.settings.smooth_iterations;
+            self.state.samples = self.settings.antialias.samples();
+            self.state.effect_lod = self.settings.effect_lod;
+            self.state
+                .set_threaded_modulation(self.settings.threaded_modulation);
+            self.state.max_iterations = self
+                .max_iterations_override
+                .unwrap_or(self.settings.max_iterations);
+            let (w, h) = (self.surface_config.width, self.surface_config.height);
+            if Viewport::fit(w, h, self.frame_aspect()) != self.frame {
+                self.resize(w, h);
+            }
+            self.save_settings();
+            self.sync_stream();
+            self.sync_watchdog();
+            self.settings_dirty = false;
+        }
+        if let Some(request) = response.editor_request {
+            self.apply_editor_request(request);
+        }
+        if let Some(restore) = response.resume {
+            self.answer_resume(restore);
+        }
+        if let Some(preset) = response.clicked_preset {
+            self.handle_action(InputAction::LoadPreset(preset));
+        }
+        if response.toggle_detached {
+            self.handle_action(InputAction::ToggleControlWindow);
+        }
+        if response.cue_go {
+            self.handle_action(InputAction::CueGo);
+        }
+        if response.cue_toggle_run {
+            self.handle_action(InputAction::CueToggleRun);
+        }
+        if let Some(key) = response.midi_learn {
+            self.state.midi.toggle_learn(&key);
+        }
+        if let Some((name, value)) = response.macro_value {
+            self.state.set_macro(&name, value);
+        }
+        if let Some((index, lfo)) = response.lfo {
+            self.state.patch.set_lfo(index, lfo);
+        }
+        if let Some(formula) = response.formula {
+            self.state.patch.set_formula(formula);
+        }
+        if let Some(preset) = response.load_deck_b {
+            self.handle_action(InputAction::LoadDeckB(preset));
+        }
+        if response.close_deck_b {
+            self.handle_action(InputAction::CloseDeckB);
+        }
+        if response.take_deck_b {
+            self.handle_action(InputAction::TakeDeckB);
+        }
+        if let Some(amount) = response.crossfade {
+            self.state.set_crossfade(amount);
+        }
+        if let Some(key) = response.midi_unbind {
+            if self.state.midi.unbind(&key) {
+                self.save_controller_map();
+            }
+        }
+        if let Some(code) = pasted {
+            if let Err(e) = self.apply_view_code(&code) {
+                log::warn!("pasted text is not a view code: {e}");
+                self.toasts.warn(self.tr(Msg::ToastNotAViewCode, &[&e]));
+            }
+        }
+
+        let pixels_per_point = full_output.pixels_per_point;
         let primitives = self
             .egui_ctx
-            .tessellate(full_output.shapes, full_output.pixels_per_point);
+            .tessellate(full_output.shapes, pixels_per_point);
         let textures_delta = full_output.textures_delta;
 
-        // --- Acquire surface texture -----------------------------------------
+        // --- Acquire surface textures ----------------------------------------
         let output = self.surface.get_current_texture()?;
+        // From here until the present the frame is in the GPU's hands.
+        let in_flight = self.watchdog.as_ref().map(Watchdog::frame);
         let surface_view = output
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
+        let tile_outputs: Vec<_> = self
+            .tile_windows
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, w)| Some((i, w.acquire(&self.device)?)))
+            .map(|(i, texture)| {
+                let view = texture.texture.create_view(&Default::default());
+                (i, texture, view)
+            })
+            .collect();
 
         let mut encoder = self
             .device
@@ -516,69 +2103,76 @@ impl App {
                 label: Some("frame-encoder"),
             });
 
-        // --- 1. Generator compute pass ---------------------------------------
-        self.gen_pass
-            .dispatch(&self.device, &mut encoder, &self.queue, gen_kind, &uniforms);
-
-        // --- 2. Effect chain -------------------------------------------------
-        self.effect_pass.dispatch_chain(
+        // --- 1–3. Generator, effect chain, fullscreen quad (Clear → fractal) -
+        let projection = self.settings.projection;
+        self.sync_deck_passes();
+        self.sync_formulas();
+        self.sync_overlay();
+        if let Some(source) = self.video.as_ref().and_then(VideoInput::take_frame) {
+            self.set_source_frame(source);
+        }
+        self.warm_up_next(&mut encoder, &surface_view, projection);
+        // Draft frames take the half-resolution blurs whatever the setting.
+        self.effect_pass.half_res = self.settings.half_res_effects || self.state.drafting_effects();
+        let mut frame = WgpuFrame::new(
             &self.device,
-            &mut encoder,
             &self.queue,
-            &effect_kinds,
-            &uniforms,
-            &self.gen_pass.output_view,
+            &mut encoder,
+            &self.gen_pass,
+            &mut self.effect_pass,
             &mut self.pp,
-            width,
-            height,
-        );
-
-        // --- 3. Fullscreen quad render pass (Clear → fractal) ----------------
-        let final_view: &wgpu::TextureView = if effect_kinds.is_empty() {
-            &self.gen_pass.output_view
-        } else {
-            self.pp.read_view()
-        };
+            &self.output,
+            &surface_view,
+        )
+        .with_viewport(self.frame);
+        if let Some(passes) = &mut self.deck_b {
+            frame = frame.with_deck_b(passes);
+        }
+        frame = frame.with_overlay(&mut self.overlay_pass);
+        self.state.encode_frame(&mut frame, projection);
+        for (i, _, view) in &tile_outputs {
+            frame.present_to(self.tile_windows[*i].output(), view, projection);
+        }
 
-        let render_bg = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("render_bg"),
-            layout: &self.render_bgl,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(final_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&self.render_sampler),
-                },
-            ],
-        });
+        // --- 3b. Downscaled copy for the preview stream, while watched -------
+        if let (Some(server), Some(preview)) = (&self.stream, &mut self.preview) {
+            self.stream_clock += dt;
+            if server.viewers() > 0 && self.stream_clock >= 1.0 / STREAM_FPS && !preview.is_busy() {
+                self.stream_clock = 0.0;
+                frame.capture(preview, projection);
+            }
+        }
 
-        {
-            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("fullscreen-pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &surface_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
+        // --- 3c. Probe the orbit under the cursor for the HUD ----------------
+        if !self.probe.is_busy() {
+            let cursor = (self.cursor_pos.0 as f32, self.cursor_pos.1 as f32);
+            let point = self
+                .frame
+                .contains(cursor)
+                .then(|| self.state.plane_point(self.to_render(cursor), projection))
+                .flatten();
+            let (kind, uniforms, extension) = self.state.generator_inputs(projection);
+            let recorded = point.is_some_and(|point| {
+                self.probe.record(
+                    &self.queue,
+                    &mut encoder,
+                    kind,
+                    &uniforms,
+                    &extension,
+                    &[point],
+                )
             });
-            rpass.set_pipeline(&self.render_pipeline);
-            rpass.set_bind_group(0, &render_bg, &[]);
-            rpass.draw(0..6, 0..1);
+            if !recorded {
+                self.cursor_probe = None;
+            }
         }
 
         // --- 4. egui render pass (Load → draw HUD on top) --------------------
+        // Tessellated at egui's pixels-per-point (OS DPI × UI scale), so the
+        // renderer must use the same factor.
         let screen_descriptor = egui_wgpu::ScreenDescriptor {
             size_in_pixels: [width, height],
-            pixels_per_point: self.window.scale_factor() as f32,
+            pixels_per_point,
         };
 
         // Upload any new/changed font/image textures required by egui
@@ -630,6 +2224,17 @@ impl App {
         self.queue
             .submit(user_cmds.into_iter().chain([encoder.finish()]));
         output.present();
+        for (_, texture, _) in tile_outputs {
+            texture.present();
+        }
+        drop(in_flight);
+        self.publish_preview();
+        self.effect_pass.aux.end_frame();
+        if let Some(results) = self.probe.poll(&self.device) {
+            self.cursor_probe = results.first().copied();
+        }
+        self.gpu_stats = stats::take_frame();
+        self.alloc_stats = alloc_audit::take_frame();
         Ok(())
     }
 }
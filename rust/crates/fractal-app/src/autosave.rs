@@ -0,0 +1,258 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use fractal_core::{
+    bundle::{self, PatchBundle},
+    patch::Patch,
+    perturbation::DeepPoint,
+    presets::Preset,
+    versioning::Format,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::settings::{self, Settings};
+
+/// Seconds between autosaves.
+pub const AUTOSAVE_INTERVAL: f32 = 30.0;
+
+/// The snapshot file's own layout.  The bundle and settings inside it carry
+/// their own versions and are upgraded by their own migrations.
+pub const FORMAT: Format = Format::new("autosave", &[]);
+
+// ---------------------------------------------------------------------------
+// Snapshot — everything needed to pick a session back up
+// ---------------------------------------------------------------------------
+
+/// The running look, its view at full precision and the app settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub bundle: PatchBundle,
+    /// Centre of a deep-zoom view as decimal strings; the bundle only has
+    /// it rounded to f32, which loses a view past a few thousand×.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deep_center: Option<[String; 2]>,
+    pub settings: Settings,
+}
+
+impl Snapshot {
+    pub fn capture(preset: Preset, patch: &Patch, settings: &Settings) -> Self {
+        Self {
+            bundle: PatchBundle::capture_patch(preset, patch),
+            deep_center: patch
+                .deep
+                .as_ref()
+                .map(|d| [d.center.re.to_string(), d.center.im.to_string()]),
+            settings: settings.clone(),
+        }
+    }
+
+    /// Serialize with the snapshot's and its settings' versions stamped on.
+    pub fn to_json(&self) -> String {
+        let mut value = serde_json::to_value(self).expect("Snapshot is always serialisable");
+        FORMAT.stamp(&mut value);
+        settings::FORMAT.stamp(&mut value["settings"]);
+        serde_json::to_string_pretty(&value).expect("a JSON value is always serialisable")
+    }
+
+    /// Parse a snapshot, upgrading it, its bundle and its settings from any
+    /// earlier format version.
+    pub fn from_json(src: &str) -> io::Result<Self> {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+        let mut value: Value = serde_json::from_str(src).map_err(|e| invalid(e.to_string()))?;
+        FORMAT.upgrade(&mut value)?;
+        if let Some(bundle) = value.get_mut("bundle") {
+            *bundle = bundle::migrate(bundle.take()).map_err(|e| invalid(e.to_string()))?;
+        }
+        if let Some(settings) = value.get_mut("settings") {
+            settings::FORMAT.upgrade(settings)?;
+        }
+        serde_json::from_value(value).map_err(|e| invalid(e.to_string()))
+    }
+
+    /// Rebuild the patch, deep centre included.
+    pub fn build(&self) -> Result<(Preset, Patch), String> {
+        let preset = self.bundle.base_preset().map_err(|e| e.to_string())?;
+        let mut patch = self.bundle.build().map_err(|e| e.to_string())?;
+        if let Some([re, im]) = &self.deep_center {
+            let center = DeepPoint::parse(re, im).map_err(|e| format!("deep centre: {e}"))?;
+            patch.set_deep_center(center);
+        }
+        Ok((preset, patch))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Autosave — the snapshot file, present only while a session runs
+// ---------------------------------------------------------------------------
+
+/// Periodic snapshots of the session.  The file is removed on a clean exit,
+/// so finding one at startup means the last session crashed or lost power.
+pub struct Autosave {
+    path: PathBuf,
+    /// Seconds since the last write.
+    clock: f32,
+    /// Last snapshot written, so an idle session doesn't rewrite the file.
+    last: Option<Snapshot>,
+}
+
+impl Autosave {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            clock: 0.0,
+            last: None,
+        }
+    }
+
+    /// The snapshot a crashed session left behind and how long ago it was
+    /// written, if there is one.
+    pub fn pending(&self) -> io::Result<Option<(Snapshot, Duration)>> {
+        let src = match std::fs::read_to_string(&self.path) {
+            Ok(src) => src,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut snapshot = Snapshot::from_json(&src)?;
+        snapshot.settings = snapshot.settings.sanitized();
+        let age = std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| SystemTime::now().duration_since(t).ok())
+            .unwrap_or_default();
+        Ok(Some((snapshot, age)))
+    }
+
+    /// Advance the clock; `true` when a snapshot is due.
+    pub fn tick(&mut self, dt: f32) -> bool {
+        self.clock += dt;
+        self.clock >= AUTOSAVE_INTERVAL
+    }
+
+    /// Write `snapshot` unless it matches the last one.  The file is
+    /// replaced by a rename, so a crash mid-write leaves the previous
+    /// snapshot intact.
+    pub fn save(&mut self, snapshot: Snapshot) -> io::Result<()> {
+        self.clock = 0.0;
+        if self.last.as_ref() == Some(&snapshot) {
+            return Ok(());
+        }
+        write_atomic(&self.path, &snapshot.to_json())?;
+        self.last = Some(snapshot);
+        Ok(())
+    }
+
+    /// Remove the file: the session ended cleanly.
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.last = None;
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Write `contents` to a sibling temp file, then rename it over `path`.
+fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("fractal-autosave-{name}-{}", std::process::id()))
+            .join("autosave.json")
+    }
+
+    #[test]
+    fn snapshot_round_trips_a_deep_view() {
+        let mut patch = Preset::ClassicMandelbrot.build();
+        patch.params.zoom = 1e9;
+        let center = DeepPoint::parse("-0.743643887037158704752", "0.131825904205").unwrap();
+        patch.set_deep_center(center);
+        let settings = Settings {
+            ui_scale: 1.5,
+            ..Settings::default()
+        };
+        let snapshot = Snapshot::capture(Preset::ClassicMandelbrot, &patch, &settings);
+        let back = Snapshot::from_json(&snapshot.to_json()).unwrap();
+        assert_eq!(back, snapshot);
+        let (preset, patch) = back.build().unwrap();
+        assert_eq!(preset, Preset::ClassicMandelbrot);
+        assert_eq!(patch.params.zoom, 1e9);
+        assert_eq!(patch.deep.unwrap().center, center);
+        assert_eq!(back.settings.ui_scale, 1.5);
+    }
+
+    #[test]
+    fn older_formats_are_migrated_on_restore() {
+        let patch = Preset::ShipStorm.build();
+        let snapshot = Snapshot::capture(Preset::ShipStorm, &patch, &Settings::default());
+        // Written before the snapshot and its settings were versioned.
+        let mut value = serde_json::to_value(&snapshot).unwrap();
+        // Version 1 named the base preset instead of giving its id.
+        value["bundle"]["version"] = 1.into();
+        value["bundle"]["preset"] = Preset::ShipStorm.name().into();
+        let back = Snapshot::from_json(&value.to_string()).unwrap();
+        assert_eq!(back, snapshot);
+        assert_eq!(back.build().unwrap().0, Preset::ShipStorm);
+
+        value["bundle"]["version"] = (bundle::BUNDLE_VERSION + 1).into();
+        let err = Snapshot::from_json(&value.to_string()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("bundle version"), "{err}");
+
+        value["bundle"]["version"] = bundle::BUNDLE_VERSION.into();
+        value["settings"]["version"] = (settings::FORMAT.version() + 1).into();
+        let err = Snapshot::from_json(&value.to_string()).unwrap_err();
+        assert!(err.to_string().contains("settings version"), "{err}");
+    }
+
+    #[test]
+    fn file_exists_only_between_save_and_clear() {
+        let path = temp_path("lifecycle");
+        let mut autosave = Autosave::new(path.clone());
+        assert!(autosave.pending().unwrap().is_none());
+        assert!(!autosave.tick(AUTOSAVE_INTERVAL - 1.0));
+        assert!(autosave.tick(1.0));
+        let patch = Preset::ShipStorm.build();
+        let snapshot = Snapshot::capture(Preset::ShipStorm, &patch, &Settings::default());
+        autosave.save(snapshot.clone()).unwrap();
+        assert!(!autosave.tick(1.0), "saving restarts the clock");
+        let (pending, _) = autosave.pending().unwrap().unwrap();
+        assert_eq!(pending, snapshot);
+        autosave.clear().unwrap();
+        assert!(!path.exists());
+        autosave.clear().unwrap();
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn unchanged_snapshots_are_not_rewritten() {
+        let path = temp_path("idle");
+        let mut autosave = Autosave::new(path.clone());
+        let snapshot = Snapshot::capture(
+            Preset::NoiseField,
+            &Preset::NoiseField.build(),
+            &Settings::default(),
+        );
+        autosave.save(snapshot.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        autosave.save(snapshot).unwrap();
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
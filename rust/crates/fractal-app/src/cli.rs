@@ -0,0 +1,169 @@
+use std::path::PathBuf;
+
+// ---------------------------------------------------------------------------
+// Command line — startup bundle / view code plus session record & replay
+// ---------------------------------------------------------------------------
+
+pub const USAGE: &str = "usage: fractal-app [BUNDLE | VIEW-CODE] \
+[--record SESSION.jsonl] [--replay SESSION.jsonl [--loop]] [--max-iterations N] \
+[--cues CUES.json] [--video FILE | /dev/videoN] [--image IMAGE.png] [--warp WARP.json] [--tiles TILES.json] \
+[--zoom-video ZOOM.json] [--animation ANIMATION.json] [--validate BUNDLE] [--resume]";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    /// A `.fractalpatch` bundle path or a view code, applied at startup.
+    pub startup: Option<String>,
+    /// Record every input action to this session file.
+    pub record: Option<PathBuf>,
+    /// Replay a recorded session file.
+    pub replay: Option<PathBuf>,
+    /// Restart the replay whenever it ends.
+    pub replay_loop: bool,
+    /// Iteration ceiling for this run, overriding the settings file.
+    pub max_iterations: Option<u32>,
+    /// Cue list to step through with Go.
+    pub cues: Option<PathBuf>,
+    /// Video file or capture device feeding the Live Video preset.
+    pub video: Option<PathBuf>,
+    /// PNG shown by the Still Image preset.
+    pub image: Option<PathBuf>,
+    /// Output warp grid and edge blend for projection mapping.
+    pub warp: Option<PathBuf>,
+    /// Canvas split across several output windows.
+    pub tiles: Option<PathBuf>,
+    /// Render this zoom video offline instead of opening a window.
+    pub zoom_video: Option<PathBuf>,
+    /// Render this fixed-clock animation offline instead of opening a window.
+    pub animation: Option<PathBuf>,
+    /// Check this bundle, print what's wrong with it and exit.
+    pub validate: Option<PathBuf>,
+    /// Restore a crashed session's autosave without asking.
+    pub resume: bool,
+}
+
+impl CliArgs {
+    /// Parse the arguments after the program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut out = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut path = |flag: &str| {
+                args.next()
+                    .map(PathBuf::from)
+                    .ok_or_else(|| format!("{flag} needs a file argument"))
+            };
+            match arg.as_str() {
+                "--record" => out.record = Some(path("--record")?),
+                "--replay" => out.replay = Some(path("--replay")?),
+                "--loop" => out.replay_loop = true,
+                "--resume" => out.resume = true,
+                "--cues" => out.cues = Some(path("--cues")?),
+                "--video" => out.video = Some(path("--video")?),
+                "--image" => out.image = Some(path("--image")?),
+                "--warp" => out.warp = Some(path("--warp")?),
+                "--tiles" => out.tiles = Some(path("--tiles")?),
+                "--zoom-video" => out.zoom_video = Some(path("--zoom-video")?),
+                "--animation" => out.animation = Some(path("--animation")?),
+                "--validate" => out.validate = Some(path("--validate")?),
+                "--max-iterations" => {
+                    let n = args
+                        .next()
+                        .ok_or("--max-iterations needs a number")?
+                        .parse()
+                        .map_err(|e| format!("--max-iterations: {e}"))?;
+                    out.max_iterations = Some(n);
+                }
+                flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+                _ if out.startup.is_some() => return Err(format!("unexpected argument {arg}")),
+                _ => out.startup = Some(arg),
+            }
+        }
+        if out.replay_loop && out.replay.is_none() {
+            return Err("--loop needs --replay".into());
+        }
+        Ok(out)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        CliArgs::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn no_arguments() {
+        assert_eq!(parse(&[]), Ok(CliArgs::default()));
+    }
+
+    #[test]
+    fn startup_argument_and_flags_in_any_order() {
+        let args = parse(&["--replay", "s.jsonl", "look.fractalpatch", "--loop"]).unwrap();
+        assert_eq!(args.startup.as_deref(), Some("look.fractalpatch"));
+        assert_eq!(args.replay, Some(PathBuf::from("s.jsonl")));
+        assert!(args.replay_loop);
+        assert_eq!(
+            parse(&["--record", "out.jsonl"]).unwrap().record,
+            Some(PathBuf::from("out.jsonl"))
+        );
+        assert_eq!(
+            parse(&["--cues", "show.json"]).unwrap().cues,
+            Some(PathBuf::from("show.json"))
+        );
+        assert_eq!(
+            parse(&["--video", "/dev/video0"]).unwrap().video,
+            Some(PathBuf::from("/dev/video0"))
+        );
+        assert_eq!(
+            parse(&["--image", "logo.png"]).unwrap().image,
+            Some(PathBuf::from("logo.png"))
+        );
+        assert_eq!(
+            parse(&["--warp", "left.json"]).unwrap().warp,
+            Some(PathBuf::from("left.json"))
+        );
+        assert_eq!(
+            parse(&["--tiles", "wall.json"]).unwrap().tiles,
+            Some(PathBuf::from("wall.json"))
+        );
+        assert_eq!(
+            parse(&["--zoom-video", "dive.json"]).unwrap().zoom_video,
+            Some(PathBuf::from("dive.json"))
+        );
+        assert_eq!(
+            parse(&["--animation", "sweep.json"]).unwrap().animation,
+            Some(PathBuf::from("sweep.json"))
+        );
+        assert_eq!(
+            parse(&["--validate", "look.fractalpatch"])
+                .unwrap()
+                .validate,
+            Some(PathBuf::from("look.fractalpatch"))
+        );
+        assert!(parse(&["--resume"]).unwrap().resume);
+    }
+
+    #[test]
+    fn max_iterations_flag() {
+        let args = parse(&["--max-iterations", "100000"]).unwrap();
+        assert_eq!(args.max_iterations, Some(100_000));
+        assert!(parse(&["--max-iterations"]).is_err());
+        assert!(parse(&["--max-iterations", "lots"]).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_usage() {
+        assert!(parse(&["--record"]).is_err());
+        assert!(parse(&["--cues"]).is_err());
+        assert!(parse(&["--video"]).is_err());
+        assert!(parse(&["--loop"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+        assert!(parse(&["a", "b"]).is_err());
+    }
+}
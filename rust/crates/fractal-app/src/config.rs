@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use fractal_core::bundle::BUNDLE_EXTENSION;
+
+// ---------------------------------------------------------------------------
+// Config directory — where user data (overrides) is persisted
+// ---------------------------------------------------------------------------
+
+/// Subdirectory name under the platform config root.
+const APP_DIR: &str = "fractal-explorer";
+
+/// File holding per-preset user overrides.
+const OVERRIDES_FILE: &str = "overrides.json";
+
+/// File holding app settings (HUD theme, UI scale, …).
+const SETTINGS_FILE: &str = "settings.json";
+
+/// File holding MIDI-learned controller bindings.
+const CONTROLLER_MAP_FILE: &str = "controller_map.json";
+
+/// Snapshot of the running session, removed on a clean exit.
+const AUTOSAVE_FILE: &str = "autosave.json";
+
+/// Subdirectory holding exported `.fractalpatch` bundles.
+const BUNDLES_DIR: &str = "patches";
+
+/// Subdirectory holding cached preset thumbnails.
+const THUMBNAILS_DIR: &str = "thumbnails";
+
+/// Subdirectory holding recorded input sessions.
+const SESSIONS_DIR: &str = "sessions";
+
+/// Resolve the config directory from the relevant environment values.
+///
+/// `$XDG_CONFIG_HOME/fractal-explorer` wins, falling back to
+/// `$HOME/.config/fractal-explorer`.  Empty values count as unset.
+fn config_dir_from(xdg_config_home: Option<String>, home: Option<String>) -> Option<PathBuf> {
+    let non_empty = |v: Option<String>| v.filter(|s| !s.is_empty());
+    if let Some(xdg) = non_empty(xdg_config_home) {
+        return Some(PathBuf::from(xdg).join(APP_DIR));
+    }
+    non_empty(home).map(|h| PathBuf::from(h).join(".config").join(APP_DIR))
+}
+
+/// The config directory for this process, if one can be determined.
+pub fn config_dir() -> Option<PathBuf> {
+    config_dir_from(
+        std::env::var("XDG_CONFIG_HOME").ok(),
+        std::env::var("HOME").ok(),
+    )
+}
+
+/// Path of the per-preset overrides file.
+pub fn overrides_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join(OVERRIDES_FILE))
+}
+
+/// Path of the app settings file.
+pub fn settings_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join(SETTINGS_FILE))
+}
+
+/// Path of the MIDI controller map.
+pub fn controller_map_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join(CONTROLLER_MAP_FILE))
+}
+
+/// Path of the crash-resume snapshot.
+pub fn autosave_path() -> Option<PathBuf> {
+    config_dir().map(|d| d.join(AUTOSAVE_FILE))
+}
+
+/// Directory exported patch bundles are written to.
+pub fn bundles_dir() -> Option<PathBuf> {
+    config_dir().map(|d| d.join(BUNDLES_DIR))
+}
+
+/// Directory cached preset thumbnails are written to.
+pub fn thumbnails_dir() -> Option<PathBuf> {
+    config_dir().map(|d| d.join(THUMBNAILS_DIR))
+}
+
+/// Directory recorded input sessions are written to.
+pub fn sessions_dir() -> Option<PathBuf> {
+    config_dir().map(|d| d.join(SESSIONS_DIR))
+}
+
+/// Lower-case, dash-separated file stem for a preset name, e.g.
+/// `"Ship Storm"` → `ship-storm`.
+pub fn slug(preset_name: &str) -> String {
+    let dashed: String = preset_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    dashed
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// File name for a bundle exported from `preset_name`.
+pub fn bundle_file_name(preset_name: &str) -> String {
+    format!("{}.{BUNDLE_EXTENSION}", slug(preset_name))
+}
+
+/// File name for the cached thumbnail of `preset_name`.
+pub fn thumbnail_file_name(preset_name: &str) -> String {
+    format!("{}.png", slug(preset_name))
+}
+
+/// File name for an input session recorded at `unix_secs`.
+pub fn session_file_name(unix_secs: u64) -> String {
+    format!("session-{unix_secs}.jsonl")
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xdg_config_home_takes_priority() {
+        let dir = config_dir_from(Some("/xdg".into()), Some("/home/u".into()));
+        assert_eq!(dir, Some(PathBuf::from("/xdg/fractal-explorer")));
+    }
+
+    #[test]
+    fn falls_back_to_home_dot_config() {
+        let dir = config_dir_from(None, Some("/home/u".into()));
+        assert_eq!(dir, Some(PathBuf::from("/home/u/.config/fractal-explorer")));
+    }
+
+    #[test]
+    fn empty_values_are_ignored() {
+        let dir = config_dir_from(Some(String::new()), Some("/home/u".into()));
+        assert_eq!(dir, Some(PathBuf::from("/home/u/.config/fractal-explorer")));
+        assert_eq!(config_dir_from(None, Some(String::new())), None);
+    }
+
+    #[test]
+    fn none_when_nothing_set() {
+        assert_eq!(config_dir_from(None, None), None);
+    }
+
+    #[test]
+    fn bundle_file_name_is_slugged() {
+        assert_eq!(bundle_file_name("Ship Storm"), "ship-storm.fractalpatch");
+        assert_eq!(
+            bundle_file_name("Figure-Eight Julia"),
+            "figure-eight-julia.fractalpatch"
+        );
+    }
+
+    #[test]
+    fn thumbnail_file_name_is_slugged() {
+        assert_eq!(thumbnail_file_name("Noise Bloom"), "noise-bloom.png");
+    }
+
+    #[test]
+    fn session_file_name_is_timestamped() {
+        assert_eq!(session_file_name(1_700_000_000), "session-1700000000.jsonl");
+    }
+}
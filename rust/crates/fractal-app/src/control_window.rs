@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use winit::event::WindowEvent;
+use winit::window::{Window, WindowId};
+
+use crate::settings::Settings;
+use crate::thumbnails::ThumbnailTextures;
+
+// ---------------------------------------------------------------------------
+// ControlWindow — a second OS window hosting only the egui panels
+// ---------------------------------------------------------------------------
+//
+// Lets a performer keep the main window clean while an operator drives the
+// controls on another screen.  The window has its own surface, egui context
+// and renderer; it shares the device and queue with the main window.
+
+pub struct ControlWindow {
+    window: Arc<Window>,
+    surface: wgpu::Surface<'static>,
+    config: wgpu::SurfaceConfiguration,
+    egui_ctx: egui::Context,
+    egui_state: egui_winit::State,
+    egui_renderer: egui_wgpu::Renderer,
+    /// Preset thumbnails uploaded into this window's egui context.
+    thumbs: ThumbnailTextures,
+}
+
+impl ControlWindow {
+    pub fn new(
+        window: Arc<Window>,
+        instance: &wgpu::Instance,
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        settings: &Settings,
+    ) -> Self {
+        let surface = instance
+            .create_surface(Arc::clone(&window))
+            .expect("failed to create control window surface");
+        let caps = surface.get_capabilities(adapter);
+        let format = caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(caps.formats[0]);
+        let size = window.inner_size();
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: caps.alpha_modes[0],
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        surface.configure(device, &config);
+
+        let egui_ctx = egui::Context::default();
+        settings.apply(&egui_ctx);
+        let egui_state = egui_winit::State::new(
+            egui_ctx.clone(),
+            egui::ViewportId::ROOT,
+            &*window,
+            Some(window.scale_factor() as f32),
+            None,
+            Some(device.limits().max_texture_dimension_2d as usize),
+        );
+        let egui_renderer = egui_wgpu::Renderer::new(device, format, None, 1, false);
+
+        Self {
+            window,
+            surface,
+            config,
+            egui_ctx,
+            egui_state,
+            egui_renderer,
+            thumbs: ThumbnailTextures::default(),
+        }
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    /// Release the surface and egui state, keeping the window for a new
+    /// one on a rebuilt device.
+    pub fn into_window(self) -> Arc<Window> {
+        self.window
+    }
+
+    pub fn set_title(&self, title: &str) {
+        self.window.set_title(title);
+    }
+
+    pub fn egui_ctx(&self) -> &egui::Context {
+        &self.egui_ctx
+    }
+
+    /// Feed a window event to egui; `true` if egui consumed it.
+    pub fn on_window_event(&mut self, event: &WindowEvent) -> bool {
+        self.egui_state
+            .on_window_event(&self.window, event)
+            .consumed
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.config.width = width;
+        self.config.height = height;
+        self.surface.configure(device, &self.config);
+    }
+
+    /// Run one egui frame with `ui` (which receives the thumbnail texture
+    /// ids for this context) and present it.
+    pub fn render<R>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        thumbnails: &[Option<egui::ColorImage>],
+        thumbnails_generation: u64,
+        mut ui: impl FnMut(&egui::Context, &[Option<egui::TextureId>]) -> R,
+    ) -> R {
+        let thumb_ids = self
+            .thumbs
+            .sync(&self.egui_ctx, thumbnails, thumbnails_generation);
+        let raw_input = self.egui_state.take_egui_input(&self.window);
+        let mut result = None;
+        let full_output = self.egui_ctx.run(raw_input, |ctx| {
+            result = Some(ui(ctx, &thumb_ids));
+        });
+        let result = result.expect("egui runs the ui closure once");
+        self.egui_state
+            .handle_platform_output(&self.window, full_output.platform_output);
+
+        let pixels_per_point = full_output.pixels_per_point;
+        let primitives = self
+            .egui_ctx
+            .tessellate(full_output.shapes, pixels_per_point);
+        let textures_delta = full_output.textures_delta;
+
+        let output = match self.surface.get_current_texture() {
+            Ok(output) => output,
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                self.surface.configure(device, &self.config);
+                return result;
+            }
+            Err(e) => {
+                log::warn!("control window: {e:?}");
+                return result;
+            }
+        };
+        let view = output
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("control-encoder"),
+        });
+
+        let screen_descriptor = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [self.config.width, self.config.height],
+            pixels_per_point,
+        };
+        for (id, image_delta) in &textures_delta.set {
+            self.egui_renderer
+                .update_texture(device, queue, *id, image_delta);
+        }
+        let user_cmds = self.egui_renderer.update_buffers(
+            device,
+            queue,
+            &mut encoder,
+            &primitives,
+            &screen_descriptor,
+        );
+        {
+            // See App::render for why forget_lifetime() is sound here.
+            let mut pass = encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("control-egui-pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                })
+                .forget_lifetime();
+            self.egui_renderer
+                .render(&mut pass, &primitives, &screen_descriptor);
+        }
+        for id in &textures_delta.free {
+            self.egui_renderer.free_texture(id);
+        }
+        queue.submit(user_cmds.into_iter().chain([encoder.finish()]));
+        output.present();
+        result
+    }
+}
@@ -0,0 +1,265 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use fractal_core::{audio::Quantize, presets::Preset};
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// Cue lists — a pre-programmed show in a JSON file
+// ---------------------------------------------------------------------------
+
+/// What a cue puts on screen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CuePatch {
+    /// A built-in preset, with the user's overrides.
+    Preset(Preset),
+    /// A `.fractalpatch` bundle.  Relative paths are resolved against the
+    /// cue file's directory.
+    Bundle(PathBuf),
+}
+
+/// How a cue replaces the one before it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Transition {
+    /// Switch as soon as the cue fires.
+    #[default]
+    Cut,
+    /// Wait for the next beat of the tapped tempo.
+    NextBeat,
+    /// Wait for the next bar's downbeat.
+    NextBar,
+}
+
+impl Transition {
+    /// The beat-clock boundary the switch waits for.
+    pub fn quantize(self) -> Quantize {
+        match self {
+            Transition::Cut => Quantize::Off,
+            Transition::NextBeat => Quantize::Beat,
+            Transition::NextBar => Quantize::Bar,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cue {
+    pub patch: CuePatch,
+    #[serde(default)]
+    pub transition: Transition,
+    /// Seconds the cue stays up before a running list moves on; `None`
+    /// waits for Go even while running.
+    #[serde(default)]
+    pub hold: Option<f32>,
+}
+
+impl Cue {
+    /// Preset name or bundle file name, e.g. `Ship Storm`.
+    pub fn label(&self) -> String {
+        match &self.patch {
+            CuePatch::Preset(preset) => preset.name().to_string(),
+            CuePatch::Bundle(path) => path.file_stem().map_or_else(
+                || path.display().to_string(),
+                |s| s.to_string_lossy().into(),
+            ),
+        }
+    }
+}
+
+/// An ordered show, e.g.
+/// `{"cues": [{"patch": {"Preset": "ShipStorm"}, "transition": "NextBar", "hold": 30}]}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CueList {
+    pub cues: Vec<Cue>,
+    /// Go after the last cue returns to the first.
+    pub looping: bool,
+}
+
+impl CueList {
+    /// Load from `path`, resolving relative bundle paths against its
+    /// directory.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let src = std::fs::read_to_string(path)?;
+        let mut list: Self = serde_json::from_str(&src)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for cue in &mut list.cues {
+            if let CuePatch::Bundle(bundle) = &mut cue.patch {
+                if bundle.is_relative() {
+                    *bundle = dir.join(&*bundle);
+                }
+            }
+        }
+        Ok(list)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CuePlayer — steps through a list with Go or on hold timers
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct CuePlayer {
+    pub list: CueList,
+    /// Index of the cue last fired; `None` before the first Go.
+    current: Option<usize>,
+    /// Seconds the current cue has been up.
+    held: f32,
+    /// Advance by itself once the current cue's hold time is up.
+    running: bool,
+}
+
+impl CuePlayer {
+    pub fn new(list: CueList) -> Self {
+        Self {
+            list,
+            ..Self::default()
+        }
+    }
+
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Fire the next cue.  Past the end of a non-looping list nothing
+    /// fires and the timer stops.
+    pub fn go(&mut self) -> Option<Cue> {
+        let mut next = self.current.map_or(0, |i| i + 1);
+        if next >= self.list.cues.len() {
+            if !self.list.looping || self.list.cues.is_empty() {
+                self.running = false;
+                return None;
+            }
+            next = 0;
+        }
+        self.current = Some(next);
+        self.held = 0.0;
+        Some(self.list.cues[next].clone())
+    }
+
+    /// Start or stop the timer.  Starting a list that hasn't begun fires
+    /// its first cue.
+    pub fn toggle_running(&mut self) -> Option<Cue> {
+        self.running = !self.running;
+        if self.running && self.current.is_none() {
+            self.go()
+        } else {
+            None
+        }
+    }
+
+    /// Advance the hold timer by `dt` seconds; returns the next cue when a
+    /// running list's current cue has been up for its hold time.
+    pub fn tick(&mut self, dt: f32) -> Option<Cue> {
+        self.held += dt;
+        let hold = self.list.cues.get(self.current?)?.hold?;
+        if self.running && self.held >= hold {
+            self.go()
+        } else {
+            None
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(preset: Preset, hold: Option<f32>) -> Cue {
+        Cue {
+            patch: CuePatch::Preset(preset),
+            transition: Transition::Cut,
+            hold,
+        }
+    }
+
+    fn show(looping: bool) -> CuePlayer {
+        CuePlayer::new(CueList {
+            cues: vec![
+                cue(Preset::ShipStorm, Some(2.0)),
+                cue(Preset::NoiseBloom, None),
+                cue(Preset::EchoChamber, Some(1.0)),
+            ],
+            looping,
+        })
+    }
+
+    fn fired(cue: Option<Cue>) -> Option<String> {
+        cue.map(|c| c.label())
+    }
+
+    #[test]
+    fn go_steps_through_and_stops_at_the_end() {
+        let mut player = show(false);
+        assert_eq!(fired(player.go()).as_deref(), Some("Ship Storm"));
+        assert_eq!(fired(player.go()).as_deref(), Some("Noise Bloom"));
+        assert_eq!(fired(player.go()).as_deref(), Some("Echo Chamber"));
+        assert_eq!(player.go(), None);
+        assert_eq!(player.current(), Some(2));
+    }
+
+    #[test]
+    fn looping_list_wraps() {
+        let mut player = show(true);
+        for _ in 0..3 {
+            player.go();
+        }
+        assert_eq!(fired(player.go()).as_deref(), Some("Ship Storm"));
+    }
+
+    #[test]
+    fn running_list_follows_hold_times() {
+        let mut player = show(false);
+        assert_eq!(
+            fired(player.toggle_running()).as_deref(),
+            Some("Ship Storm")
+        );
+        assert_eq!(player.tick(1.5), None);
+        assert_eq!(fired(player.tick(0.5)).as_deref(), Some("Noise Bloom"));
+        // No hold time: waits for Go however long it has been up.
+        assert_eq!(player.tick(100.0), None);
+        assert_eq!(fired(player.go()).as_deref(), Some("Echo Chamber"));
+        assert_eq!(player.tick(1.0), None, "end of the list");
+        assert!(!player.is_running());
+    }
+
+    #[test]
+    fn stopped_list_ignores_hold_times() {
+        let mut player = show(false);
+        player.go();
+        assert_eq!(player.tick(10.0), None);
+    }
+
+    #[test]
+    fn load_resolves_bundles_next_to_the_file() {
+        let dir = std::env::temp_dir().join(format!("fractal-cues-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("show.json");
+        std::fs::write(
+            &path,
+            r#"{"cues": [
+                {"patch": {"Bundle": "intro.fractalpatch"}, "transition": "NextBar", "hold": 8},
+                {"patch": {"Preset": "ShipStorm"}}
+            ]}"#,
+        )
+        .unwrap();
+        let list = CueList::load(&path).unwrap();
+        assert_eq!(
+            list.cues[0].patch,
+            CuePatch::Bundle(dir.join("intro.fractalpatch"))
+        );
+        assert_eq!(list.cues[0].label(), "intro");
+        assert_eq!(list.cues[1].transition, Transition::Cut);
+        assert_eq!(list.cues[1].hold, None);
+        assert!(!list.looping);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
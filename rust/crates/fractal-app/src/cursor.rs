@@ -0,0 +1,99 @@
+use crate::settings::CrosshairStyle;
+
+/// Seconds of pointer inactivity after which the cursor hides, by default.
+pub const DEFAULT_HIDE_AFTER: f32 = 3.0;
+
+/// Largest idle timeout offered in the settings panel.
+pub const MAX_HIDE_AFTER: f32 = 30.0;
+
+// ---------------------------------------------------------------------------
+// IdleTimer — how long the pointer has been still
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Default)]
+pub struct IdleTimer {
+    idle: f32,
+}
+
+impl IdleTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pointer moved, clicked or scrolled.
+    pub fn reset(&mut self) {
+        self.idle = 0.0;
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.idle += dt;
+    }
+
+    /// Whether the cursor should be hidden after `timeout` seconds.  A
+    /// timeout of zero never hides.
+    pub fn expired(&self, timeout: f32) -> bool {
+        timeout > 0.0 && self.idle >= timeout
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Crosshair drawing
+// ---------------------------------------------------------------------------
+
+/// Paint `style` centred on `pos` (egui points).  `CrosshairStyle::None`
+/// draws nothing — the OS cursor is shown instead.
+pub fn draw_crosshair(painter: &egui::Painter, pos: egui::Pos2, style: CrosshairStyle) {
+    // Dark outline under a light stroke keeps it visible on any palette.
+    let outline = egui::Stroke::new(3.0, egui::Color32::from_black_alpha(160));
+    let stroke = egui::Stroke::new(1.0, egui::Color32::WHITE);
+    let cross = |arm: f32, gap: f32| {
+        for (dx, dy) in [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)] {
+            let d = egui::vec2(dx, dy);
+            let seg = [pos + d * gap, pos + d * arm];
+            painter.line_segment(seg, outline);
+            painter.line_segment(seg, stroke);
+        }
+    };
+    match style {
+        CrosshairStyle::Cross => cross(9.0, 3.0),
+        CrosshairStyle::LargeCross => cross(40.0, 5.0),
+        CrosshairStyle::Dot => {
+            painter.circle_filled(pos, 3.5, egui::Color32::from_black_alpha(160));
+            painter.circle_filled(pos, 2.0, egui::Color32::WHITE);
+        }
+        CrosshairStyle::None => {}
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hides_after_timeout() {
+        let mut t = IdleTimer::new();
+        t.tick(2.9);
+        assert!(!t.expired(3.0));
+        t.tick(0.2);
+        assert!(t.expired(3.0));
+    }
+
+    #[test]
+    fn activity_resets() {
+        let mut t = IdleTimer::new();
+        t.tick(10.0);
+        t.reset();
+        assert!(!t.expired(3.0));
+    }
+
+    #[test]
+    fn zero_timeout_never_hides() {
+        let mut t = IdleTimer::new();
+        t.tick(1000.0);
+        assert!(!t.expired(0.0));
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Number of recent frame times kept for the HUD graph and 1% lows.
+pub const FRAME_HISTORY: usize = 240;
+
+// ---------------------------------------------------------------------------
+// FpsCounter — frame rate plus a ring buffer of recent frame times
+// ---------------------------------------------------------------------------
+
+/// Tracks the once-per-second average frame rate and the last
+/// [`FRAME_HISTORY`] individual frame times, so single-frame stutters
+/// (resize, pipeline rebuilds) stay visible instead of being averaged away.
+pub struct FpsCounter {
+    frames: u32,
+    last_report: Instant,
+    last_fps: f32,
+    /// Frame times in seconds, oldest first.
+    frame_times: VecDeque<f32>,
+}
+
+impl FpsCounter {
+    pub fn new() -> Self {
+        Self {
+            frames: 0,
+            last_report: Instant::now(),
+            last_fps: 0.0,
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY),
+        }
+    }
+
+    /// Tick one frame that took `dt` seconds.  Updates the stored FPS once
+    /// per second and returns the new value so the caller can log it.
+    pub fn tick(&mut self, dt: f32) -> Option<f32> {
+        self.record(dt);
+        self.frames += 1;
+        let elapsed = self.last_report.elapsed().as_secs_f32();
+        if elapsed >= 1.0 {
+            self.last_fps = self.frames as f32 / elapsed;
+            self.frames = 0;
+            self.last_report = Instant::now();
+            Some(self.last_fps)
+        } else {
+            None
+        }
+    }
+
+    /// Push a frame time into the ring buffer, evicting the oldest.
+    fn record(&mut self, dt: f32) {
+        if self.frame_times.len() == FRAME_HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(dt);
+    }
+
+    pub fn fps(&self) -> f32 {
+        self.last_fps
+    }
+
+    /// Recent frame times in seconds, oldest first.
+    pub fn frame_times(&self) -> impl ExactSizeIterator<Item = f32> + '_ {
+        self.frame_times.iter().copied()
+    }
+
+    /// Longest frame time in the history, in seconds.
+    pub fn worst_frame_time(&self) -> Option<f32> {
+        self.frame_times.iter().copied().reduce(f32::max)
+    }
+
+    /// "1% low" frame rate: the FPS implied by the average of the slowest
+    /// 1% of recorded frames (at least one frame).
+    pub fn one_percent_low(&self) -> Option<f32> {
+        if self.frame_times.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f32> = self.frame_times.iter().copied().collect();
+        sorted.sort_by(|a, b| b.total_cmp(a));
+        let n = sorted.len().div_ceil(100);
+        let avg = sorted[..n].iter().sum::<f32>() / n as f32;
+        (avg > 0.0).then(|| 1.0 / avg)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter_with(times: &[f32]) -> FpsCounter {
+        let mut c = FpsCounter::new();
+        for &t in times {
+            c.tick(t);
+        }
+        c
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let c = counter_with(&[0.016; FRAME_HISTORY + 10]);
+        assert_eq!(c.frame_times().len(), FRAME_HISTORY);
+    }
+
+    #[test]
+    fn oldest_frames_are_evicted_first() {
+        let mut times = vec![0.5];
+        times.extend([0.016; FRAME_HISTORY]);
+        let c = counter_with(&times);
+        assert!(c.frame_times().all(|t| t == 0.016));
+    }
+
+    #[test]
+    fn empty_history_has_no_stats() {
+        let c = FpsCounter::new();
+        assert_eq!(c.one_percent_low(), None);
+        assert_eq!(c.worst_frame_time(), None);
+    }
+
+    #[test]
+    fn one_percent_low_reflects_a_single_stutter() {
+        // 199 smooth frames + one 100 ms hitch: slowest 1% = 2 frames.
+        let mut times = vec![0.01; 199];
+        times.push(0.1);
+        let c = counter_with(&times);
+        let low = c.one_percent_low().unwrap();
+        assert!((low - 1.0 / 0.055).abs() < 0.01, "low = {low}");
+        assert_eq!(c.worst_frame_time(), Some(0.1));
+    }
+
+    #[test]
+    fn one_percent_low_of_steady_frames_matches_fps() {
+        let c = counter_with(&[0.02; 50]);
+        assert!((c.one_percent_low().unwrap() - 50.0).abs() < 1e-3);
+    }
+}
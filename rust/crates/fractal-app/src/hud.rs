@@ -0,0 +1,891 @@
+use fractal_core::audio::Quantize;
+use fractal_core::formula::Formula;
+use fractal_core::meta::PatchMeta;
+use fractal_core::modulators::{Lfo, Waveform};
+use fractal_core::presets::Preset;
+use fractal_core::projection::Projection;
+use fractal_gpu::{probe::ProbeResult, stats::GpuStats};
+
+use crate::alloc_audit::AllocStats;
+use crate::cursor::MAX_HIDE_AFTER;
+use crate::i18n::{tr, trf, Language, Msg};
+use crate::input::{preset_hotkey_label, MAX_ITERATIONS, MIN_ITERATIONS};
+use crate::overlay::{MAX_OVERLAY_PULSE, MAX_OVERLAY_SCALE, MIN_OVERLAY_SCALE};
+use crate::pan::{MAX_FRICTION, MIN_FRICTION};
+use crate::settings::{
+    flash_every_label, projection_label, quantize_label, retrigger_label, waveform_label,
+    Antialias, CrosshairStyle, FrameAspect, HudTheme, LfoDepth, LfoRateUnit, Settings,
+    MAX_UI_SCALE, MIN_LFO_HZ, MIN_UI_SCALE,
+};
+use crate::shader_editor::{EditorRequest, ShaderEditor, ShaderTarget};
+use crate::state::MAX_FLASH_FRAMES;
+use crate::stream::MIN_PORT;
+use crate::toast::Toasts;
+use crate::watchdog::MAX_TIMEOUT;
+
+// ---------------------------------------------------------------------------
+// HudData — per-frame values shown in the control panels
+// ---------------------------------------------------------------------------
+
+/// Snapshot of app state taken before the egui frame, so the panels can be
+/// drawn without borrowing the `App`.
+pub struct HudData {
+    pub lang: Language,
+    pub preset_name: &'static str,
+    /// Title, author, description, tempo and tags of the running patch.
+    pub meta: PatchMeta,
+    pub preset_slot: String,
+    pub current_idx: usize,
+    pub zoom: f32,
+    /// Iterations rendered, after any zoom-aware scaling.
+    pub max_iter: u32,
+    /// Iteration data at the point under the cursor, when it is over an
+    /// escape-time fractal.
+    pub cursor_probe: Option<ProbeResult>,
+    pub auto_iterations: bool,
+    pub effect_labels: Vec<&'static str>,
+    pub fps: f32,
+    pub fps_low: f32,
+    pub frame_times: Vec<f32>,
+    pub worst_frame: f32,
+    pub view_code: String,
+    pub gpu_stats: GpuStats,
+    /// Heap allocations of the previous frame, with `--features alloc-audit`.
+    pub alloc_stats: Option<AllocStats>,
+    /// An input session is being recorded / replayed.
+    pub recording: bool,
+    pub replaying: bool,
+    /// Tapped tempo in BPM, once known.
+    pub bpm: Option<f32>,
+    /// Preset switch waiting for its beat / bar.
+    pub pending_preset: Option<&'static str>,
+    /// Port and viewer count of the running preview stream.
+    pub stream: Option<(u16, usize, bool)>,
+    /// Name and value (0–1) of each of the patch's macros.
+    pub macros: Vec<(String, f32)>,
+    /// Each of the patch's LFOs with its index among the modulators.
+    pub lfos: Vec<(usize, Lfo)>,
+    /// The running custom formula, if the generator has one.
+    pub formula: Option<String>,
+    /// Keys the patch's audio routes feed back into.
+    pub feedback: Vec<String>,
+    /// Keys the sanitize pass has had to repair since the patch loaded.
+    pub repaired: Vec<String>,
+    /// Label and hold time of each cue in the loaded cue list.
+    pub cues: Vec<(String, Option<f32>)>,
+    /// Index of the cue last fired.
+    pub cue_current: Option<usize>,
+    pub cues_running: bool,
+    /// Preset cued on deck B.
+    pub deck_b: Option<&'static str>,
+    /// Crossfader position, 0 = deck A, 1 = deck B.
+    pub crossfade: f32,
+    /// Name of the open MIDI input port.
+    pub midi_port: Option<String>,
+    /// Learnable params of the current patch with their controller label.
+    pub midi_params: Vec<(String, Option<String>)>,
+    /// Param waiting for a knob to be moved.
+    pub midi_learning: Option<String>,
+    /// Whether the panels currently live in the detached control window.
+    pub detached: bool,
+    /// Age in minutes and preset of a crashed session's autosave, offered
+    /// for restore until answered.
+    pub resume: Option<(u64, &'static str)>,
+}
+
+/// What the user did in the panels this frame.
+#[derive(Debug, Default)]
+pub struct HudResponse {
+    pub clicked_preset: Option<Preset>,
+    /// A settings slider is mid-drag; defer applying the new settings.
+    pub slider_dragging: bool,
+    /// The attach / detach button was pressed.
+    pub toggle_detached: bool,
+    pub editor_request: Option<EditorRequest>,
+    /// A param was clicked in the MIDI panel: arm (or cancel) learning it.
+    pub midi_learn: Option<String>,
+    /// A param's controller binding should be removed.
+    pub midi_unbind: Option<String>,
+    /// The cue panel's Go / run buttons were pressed.
+    pub cue_go: bool,
+    pub cue_toggle_run: bool,
+    /// A macro slider moved: its name and new value.
+    pub macro_value: Option<(String, f32)>,
+    /// An LFO was edited: its index among the modulators and new settings.
+    pub lfo: Option<(usize, Lfo)>,
+    /// An edited custom formula was applied.
+    pub formula: Option<Formula>,
+    /// A preset was picked for deck B, or deck B was switched off.
+    pub load_deck_b: Option<Preset>,
+    pub close_deck_b: bool,
+    /// The take button was pressed.
+    pub take_deck_b: bool,
+    /// The crossfader moved to this position.
+    pub crossfade: Option<f32>,
+    /// The restore prompt was answered: restore (`true`) or discard.
+    pub resume: Option<bool>,
+}
+
+// ---------------------------------------------------------------------------
+// Controls — every egui panel, hosted by the main or the control window
+// ---------------------------------------------------------------------------
+
+/// Borrowed state the controls read and edit.
+pub struct Controls<'a> {
+    pub hud: &'a HudData,
+    pub thumbs: &'a [Option<egui::TextureId>],
+    pub settings: &'a mut Settings,
+    pub shader_editor: &'a mut ShaderEditor,
+    pub shader_targets: &'a [(String, ShaderTarget)],
+    pub toasts: &'a Toasts,
+}
+
+impl Controls<'_> {
+    /// Draw the panels as a translucent overlay window over the fractal.
+    pub fn show_overlay(self, ctx: &egui::Context) -> HudResponse {
+        let lang = self.hud.lang;
+        let fill = self.settings.theme.hud_fill();
+        let mut response = HudResponse::default();
+        // Stable ids keep window / header state across language switches.
+        egui::Window::new(tr(lang, Msg::HudTitle))
+            .id(egui::Id::new("hud"))
+            .anchor(egui::Align2::LEFT_TOP, [10.0, 10.0])
+            .collapsible(false)
+            .resizable(false)
+            .frame(egui::Frame::window(&ctx.style()).fill(fill))
+            .show(ctx, |ui| {
+                hud_panels(ui, self.hud, self.thumbs, self.settings, &mut response);
+            });
+        resume_prompt(ctx, self.hud, &mut response);
+        response.editor_request = self.shader_editor.show(ctx, lang, self.shader_targets);
+        self.toasts.show(ctx);
+        response
+    }
+
+    /// Fill a whole (control) window with the panels.
+    pub fn show_full(self, ctx: &egui::Context) -> HudResponse {
+        let lang = self.hud.lang;
+        let mut response = HudResponse::default();
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading(tr(lang, Msg::HudTitle));
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                hud_panels(ui, self.hud, self.thumbs, self.settings, &mut response);
+            });
+        });
+        resume_prompt(ctx, self.hud, &mut response);
+        response.editor_request = self.shader_editor.show(ctx, lang, self.shader_targets);
+        self.toasts.show(ctx);
+        response
+    }
+}
+
+/// Offer to restore a crashed session, centred over everything else.
+fn resume_prompt(ctx: &egui::Context, hud: &HudData, response: &mut HudResponse) {
+    let Some((minutes, preset)) = hud.resume else {
+        return;
+    };
+    let lang = hud.lang;
+    egui::Window::new(tr(lang, Msg::ResumeTitle))
+        .id(egui::Id::new("resume"))
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(trf(lang, Msg::ResumeBody, &[&minutes, &preset]));
+            ui.horizontal(|ui| {
+                if ui.button(tr(lang, Msg::ResumeRestore)).clicked() {
+                    response.resume = Some(true);
+                }
+                if ui.button(tr(lang, Msg::ResumeDiscard)).clicked() {
+                    response.resume = Some(false);
+                }
+            });
+        });
+}
+
+/// Text field for the custom formula, checked as it is typed.  The draft
+/// lives in egui's memory keyed by the running formula, so it starts over
+/// whenever that changes.
+fn formula_panel(ui: &mut egui::Ui, hud: &HudData, response: &mut HudResponse) {
+    let Some(current) = &hud.formula else {
+        return;
+    };
+    let lang = hud.lang;
+    let id = egui::Id::new(("formula_draft", current));
+    let mut draft = ui
+        .data_mut(|d| d.get_temp::<String>(id))
+        .unwrap_or_else(|| current.clone());
+    let edit = ui.add(
+        egui::TextEdit::singleline(&mut draft)
+            .code_editor()
+            .desired_width(260.0),
+    );
+    let entered = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+    let parsed = Formula::parse(&draft);
+    let changed = parsed.as_ref().is_ok_and(|f| f.source() != current);
+    ui.horizontal(|ui| {
+        let apply = ui
+            .add_enabled(changed, egui::Button::new(tr(lang, Msg::FormulaApply)))
+            .clicked();
+        if (apply || entered) && changed {
+            response.formula = parsed.as_ref().ok().cloned();
+        }
+        if ui
+            .add_enabled(draft != *current, egui::Button::new(tr(lang, Msg::Revert)))
+            .clicked()
+        {
+            draft = current.clone();
+        }
+    });
+    match &parsed {
+        Ok(_) => {
+            ui.weak(tr(lang, Msg::FormulaHelp));
+        }
+        Err(e) => {
+            ui.colored_label(
+                egui::Color32::from_rgb(230, 60, 60),
+                trf(lang, Msg::FormulaErrorAt, &[&e.column, &e.message]),
+            );
+        }
+    }
+    ui.data_mut(|d| d.insert_temp(id, draft));
+}
+
+/// Author, description, suggested tempo and tags under the preset line.
+fn patch_meta(ui: &mut egui::Ui, lang: Language, meta: &PatchMeta) {
+    if let Some(author) = meta.author() {
+        ui.label(trf(lang, Msg::PatchAuthor, &[&author]));
+    }
+    if let Some(description) = meta.description() {
+        ui.label(egui::RichText::new(description).weak());
+    }
+    if let Some(bpm) = meta.bpm() {
+        ui.label(trf(lang, Msg::PatchTempo, &[&bpm]));
+    }
+    if !meta.tags.is_empty() {
+        ui.label(trf(lang, Msg::PatchTags, &[&meta.tags.join(", ")]));
+    }
+}
+
+/// Status lines, settings, diagnostics, preset browser and key help.
+fn hud_panels(
+    ui: &mut egui::Ui,
+    hud: &HudData,
+    thumbs: &[Option<egui::TextureId>],
+    settings: &mut Settings,
+    response: &mut HudResponse,
+) {
+    let lang = hud.lang;
+    let t = |msg| tr(lang, msg);
+    ui.label(format!(
+        "{}:  [{}] {}",
+        t(Msg::Preset),
+        hud.preset_slot,
+        hud.meta.title_or(hud.preset_name)
+    ));
+    patch_meta(ui, lang, &hud.meta);
+    ui.label(format!("{}:    {:.2}×", t(Msg::Zoom), hud.zoom));
+    if hud.auto_iterations {
+        let auto = trf(lang, Msg::IterationsAuto, &[&hud.max_iter]);
+        ui.label(format!("{}:    {auto}", t(Msg::Iterations)));
+    } else {
+        ui.label(format!("{}:    {}", t(Msg::Iterations), hud.max_iter));
+    }
+    match hud.cursor_probe {
+        Some(probe) if probe.escaped => {
+            let count = format!("{:.1}", probe.smoothed);
+            ui.label(trf(lang, Msg::CursorIterations, &[&count]));
+        }
+        Some(_) => {
+            ui.label(t(Msg::CursorInterior));
+        }
+        None => {}
+    }
+    let fx = if hud.effect_labels.is_empty() {
+        t(Msg::EffectsNone).to_string()
+    } else {
+        hud.effect_labels.join(", ")
+    };
+    ui.label(format!("{}: {fx}", t(Msg::Effects)));
+    if let Some(bpm) = hud.bpm {
+        ui.label(trf(lang, Msg::Tempo, &[&format!("{bpm:.1}")]));
+    }
+    if let Some(name) = hud.pending_preset {
+        ui.label(trf(lang, Msg::QueuedPreset, &[&name]));
+    }
+    if hud.recording {
+        ui.colored_label(egui::Color32::from_rgb(230, 60, 60), t(Msg::RecordingInput));
+    }
+    if hud.replaying {
+        ui.label(t(Msg::ReplayingSession));
+    }
+    if !hud.feedback.is_empty() {
+        ui.colored_label(
+            egui::Color32::from_rgb(230, 160, 40),
+            trf(lang, Msg::ModulationFeedback, &[&hud.feedback.join(", ")]),
+        );
+    }
+    if !hud.repaired.is_empty() {
+        ui.colored_label(
+            egui::Color32::from_rgb(230, 160, 40),
+            trf(lang, Msg::ParamsRepaired, &[&hud.repaired.join(", ")]),
+        );
+    }
+    ui.label(format!(
+        "{}:     {:.1}   {} {:.1}",
+        t(Msg::Fps),
+        hud.fps,
+        t(Msg::OnePercentLow),
+        hud.fps_low
+    ));
+    frame_time_graph(ui, lang, &hud.frame_times, hud.worst_frame);
+    ui.horizontal(|ui| {
+        ui.label(format!("{}:    {}", t(Msg::View), hud.view_code));
+        if ui.small_button(t(Msg::Copy)).clicked() {
+            ui.ctx().copy_text(hud.view_code.clone());
+        }
+    });
+    let detach = if hud.detached {
+        Msg::AttachControls
+    } else {
+        Msg::DetachControls
+    };
+    if ui.button(t(detach)).clicked() {
+        response.toggle_detached = true;
+    }
+    if !hud.macros.is_empty() {
+        ui.separator();
+        ui.label(t(Msg::Macros));
+        // One wide slider per macro, easy to grab mid-set.
+        ui.scope(|ui| {
+            ui.spacing_mut().slider_width = 240.0;
+            for (name, value) in &hud.macros {
+                let mut v = *value;
+                if ui
+                    .add(egui::Slider::new(&mut v, 0.0..=1.0).text(name))
+                    .changed()
+                {
+                    response.macro_value = Some((name.clone(), v));
+                }
+            }
+        });
+    }
+    if !hud.lfos.is_empty() {
+        egui::CollapsingHeader::new(t(Msg::Lfos))
+            .id_salt("lfos")
+            .show(ui, |ui| lfo_panel(ui, hud, settings, response));
+    }
+    if hud.formula.is_some() {
+        egui::CollapsingHeader::new(t(Msg::Formula))
+            .id_salt("formula")
+            .default_open(true)
+            .show(ui, |ui| formula_panel(ui, hud, response));
+    }
+    if !hud.cues.is_empty() {
+        egui::CollapsingHeader::new(t(Msg::Cues))
+            .id_salt("cues")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    response.cue_go |= ui.button(t(Msg::CueGo)).clicked();
+                    let run = if hud.cues_running {
+                        Msg::CueStop
+                    } else {
+                        Msg::CueRun
+                    };
+                    response.cue_toggle_run |= ui.button(t(run)).clicked();
+                });
+                egui::Grid::new("cue_grid").show(ui, |ui| {
+                    for (i, (label, hold)) in hud.cues.iter().enumerate() {
+                        let text = egui::RichText::new(format!("{}  {label}", i + 1));
+                        if hud.cue_current == Some(i) {
+                            ui.label(text.strong());
+                        } else {
+                            ui.label(text);
+                        }
+                        match hold {
+                            Some(secs) => ui.label(trf(lang, Msg::CueHold, &[secs])),
+                            None => ui.label(t(Msg::CueWaitsForGo)),
+                        };
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+    egui::CollapsingHeader::new(t(Msg::Decks))
+        .id_salt("decks")
+        .show(ui, |ui| {
+            ui.label(trf(lang, Msg::DeckA, &[&hud.preset_name]));
+            egui::ComboBox::new("deck_b", t(Msg::DeckB))
+                .selected_text(hud.deck_b.unwrap_or(t(Msg::DeckOff)))
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(hud.deck_b.is_none(), t(Msg::DeckOff))
+                        .clicked()
+                    {
+                        response.close_deck_b = true;
+                    }
+                    for preset in Preset::ALL {
+                        let cued = hud.deck_b == Some(preset.name());
+                        if ui.selectable_label(cued, preset.name()).clicked() {
+                            response.load_deck_b = Some(preset);
+                        }
+                    }
+                });
+            if hud.deck_b.is_some() {
+                ui.scope(|ui| {
+                    ui.spacing_mut().slider_width = 240.0;
+                    let mut v = hud.crossfade;
+                    if ui
+                        .add(egui::Slider::new(&mut v, 0.0..=1.0).text(t(Msg::Crossfade)))
+                        .changed()
+                    {
+                        response.crossfade = Some(v);
+                    }
+                });
+                response.take_deck_b |= ui.button(t(Msg::TakeDeckB)).clicked();
+            }
+        });
+    egui::CollapsingHeader::new(t(Msg::Overlay))
+        .id_salt("overlay")
+        .show(ui, |ui| {
+            let overlay = &mut settings.overlay;
+            ui.checkbox(&mut overlay.enabled, t(Msg::OverlayShow));
+            ui.horizontal(|ui| {
+                ui.label(t(Msg::OverlayText));
+                ui.text_edit_singleline(&mut overlay.text);
+            });
+            ui.horizontal(|ui| {
+                ui.label(t(Msg::OverlayLogo));
+                // A typed path is applied on Enter / focus loss, not on
+                // every keystroke.
+                let id = ui.id().with("overlay_logo");
+                let mut path = ui
+                    .data_mut(|d| d.get_temp::<String>(id))
+                    .unwrap_or_else(|| {
+                        overlay
+                            .logo
+                            .as_ref()
+                            .map_or_else(String::new, |p| p.display().to_string())
+                    });
+                let edit = ui.text_edit_singleline(&mut path);
+                if edit.lost_focus() {
+                    let path = path.trim();
+                    overlay.logo = (!path.is_empty()).then(|| path.into());
+                    ui.data_mut(|d| d.remove::<String>(id));
+                } else if edit.has_focus() {
+                    ui.data_mut(|d| d.insert_temp(id, path));
+                }
+            });
+            let layout = &mut overlay.layout;
+            for (value, range, label) in [
+                (&mut layout.x, 0.0..=1.0, Msg::OverlayX),
+                (&mut layout.y, 0.0..=1.0, Msg::OverlayY),
+                (
+                    &mut layout.scale,
+                    MIN_OVERLAY_SCALE..=MAX_OVERLAY_SCALE,
+                    Msg::OverlayScale,
+                ),
+                (&mut layout.opacity, 0.0..=1.0, Msg::OverlayOpacity),
+                (
+                    &mut layout.pulse,
+                    0.0..=MAX_OVERLAY_PULSE,
+                    Msg::OverlayPulse,
+                ),
+            ] {
+                response.slider_dragging |= ui
+                    .add(egui::Slider::new(value, range).text(t(label)))
+                    .dragged();
+            }
+        });
+    egui::CollapsingHeader::new(t(Msg::Settings))
+        .id_salt("settings")
+        .show(ui, |ui| {
+            egui::ComboBox::new("theme", t(Msg::Theme))
+                .selected_text(t(settings.theme.label()))
+                .show_ui(ui, |ui| {
+                    for theme in HudTheme::ALL {
+                        ui.selectable_value(&mut settings.theme, theme, t(theme.label()));
+                    }
+                });
+            egui::ComboBox::new("language", t(Msg::LanguageLabel))
+                .selected_text(settings.language.native_name())
+                .show_ui(ui, |ui| {
+                    for language in Language::ALL {
+                        ui.selectable_value(
+                            &mut settings.language,
+                            language,
+                            language.native_name(),
+                        );
+                    }
+                });
+            ui.horizontal(|ui| {
+                ui.label(t(Msg::AuthorName));
+                ui.text_edit_singleline(&mut settings.author);
+            });
+            egui::ComboBox::new("crosshair", t(Msg::Crosshair))
+                .selected_text(t(settings.crosshair.label()))
+                .show_ui(ui, |ui| {
+                    for style in CrosshairStyle::ALL {
+                        ui.selectable_value(&mut settings.crosshair, style, t(style.label()));
+                    }
+                });
+            response.slider_dragging |= ui
+                .add(
+                    egui::Slider::new(&mut settings.cursor_hide_after, 0.0..=MAX_HIDE_AFTER)
+                        .text(t(Msg::HideCursorAfter))
+                        .step_by(0.5),
+                )
+                .dragged();
+            response.slider_dragging |= ui
+                .add(
+                    egui::Slider::new(&mut settings.pan_friction, MIN_FRICTION..=MAX_FRICTION)
+                        .text(t(Msg::PanFriction))
+                        .logarithmic(true),
+                )
+                .dragged();
+            egui::ComboBox::new("projection", t(Msg::ProjectionLabel))
+                .selected_text(t(projection_label(settings.projection)))
+                .show_ui(ui, |ui| {
+                    for p in Projection::ALL {
+                        ui.selectable_value(&mut settings.projection, p, t(projection_label(p)));
+                    }
+                });
+            egui::ComboBox::new("frame_aspect", t(Msg::FrameAspectLabel))
+                .selected_text(t(settings.frame_aspect.label()))
+                .show_ui(ui, |ui| {
+                    for a in FrameAspect::ALL {
+                        ui.selectable_value(&mut settings.frame_aspect, a, t(a.label()));
+                    }
+                });
+            egui::ComboBox::new("quantize", t(Msg::QuantizeLabel))
+                .selected_text(t(quantize_label(settings.quantize)))
+                .show_ui(ui, |ui| {
+                    for q in Quantize::ALL {
+                        ui.selectable_value(&mut settings.quantize, q, t(quantize_label(q)));
+                    }
+                });
+            egui::ComboBox::new("lfo_retrigger", t(Msg::RetriggerLabel))
+                .selected_text(t(retrigger_label(settings.lfo_retrigger)))
+                .show_ui(ui, |ui| {
+                    for q in Quantize::ALL {
+                        ui.selectable_value(&mut settings.lfo_retrigger, q, t(retrigger_label(q)));
+                    }
+                });
+            response.slider_dragging |= ui
+                .add(
+                    egui::Slider::new(&mut settings.flash_frames, 1..=MAX_FLASH_FRAMES)
+                        .text(t(Msg::FlashFrames)),
+                )
+                .dragged();
+            egui::ComboBox::new("flash_every", t(Msg::FlashEveryLabel))
+                .selected_text(t(flash_every_label(settings.flash_every)))
+                .show_ui(ui, |ui| {
+                    for q in Quantize::ALL {
+                        ui.selectable_value(&mut settings.flash_every, q, t(flash_every_label(q)));
+                    }
+                });
+            ui.checkbox(&mut settings.audio_auto_gain, t(Msg::AudioAutoGain));
+            ui.horizontal(|ui| {
+                ui.label(t(Msg::MaxIterations));
+                response.slider_dragging |= ui
+                    .add(
+                        egui::DragValue::new(&mut settings.max_iterations)
+                            .range(MIN_ITERATIONS..=MAX_ITERATIONS)
+                            .speed(50.0),
+                    )
+                    .dragged();
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut settings.auto_iterations, t(Msg::AutoIterations));
+                ui.label(t(Msg::AutoIterationsCap));
+                response.slider_dragging |= ui
+                    .add(
+                        egui::DragValue::new(&mut settings.auto_iterations_cap)
+                            .range(MIN_ITERATIONS..=MAX_ITERATIONS),
+                    )
+                    .dragged();
+            });
+            ui.checkbox(&mut settings.smooth_iterations, t(Msg::SmoothIterations));
+            egui::ComboBox::new("antialias", t(Msg::AntialiasLabel))
+                .selected_text(t(settings.antialias.label()))
+                .show_ui(ui, |ui| {
+                    for a in Antialias::ALL {
+                        ui.selectable_value(&mut settings.antialias, a, t(a.label()));
+                    }
+                });
+            ui.checkbox(&mut settings.half_res_effects, t(Msg::HalfResEffects));
+            ui.checkbox(&mut settings.effect_lod, t(Msg::EffectLod));
+            ui.checkbox(
+                &mut settings.threaded_modulation,
+                t(Msg::ThreadedModulation),
+            );
+            response.slider_dragging |= ui
+                .add(
+                    egui::Slider::new(&mut settings.watchdog_timeout, 0.0..=MAX_TIMEOUT)
+                        .text(t(Msg::WatchdogTimeout))
+                        .step_by(1.0),
+                )
+                .dragged();
+            ui.checkbox(&mut settings.lock_box_aspect, t(Msg::LockBoxAspect));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut settings.stream_enabled, t(Msg::StreamPreview));
+                ui.label(t(Msg::StreamPort));
+                // Applied on release so a drag doesn't rebind every port it passes.
+                response.slider_dragging |= ui
+                    .add(egui::DragValue::new(&mut settings.stream_port).range(MIN_PORT..=u16::MAX))
+                    .dragged();
+            });
+            ui.checkbox(&mut settings.stream_lan, t(Msg::StreamLan));
+            if let Some((port, viewers, lan)) = hud.stream {
+                let msg = if lan {
+                    Msg::StreamStatus
+                } else {
+                    Msg::StreamStatusLocal
+                };
+                ui.label(trf(lang, msg, &[&port, &viewers]));
+            }
+            // Applied on release so the slider doesn't rescale under the cursor.
+            response.slider_dragging |= ui
+                .add(
+                    egui::Slider::new(&mut settings.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE)
+                        .text(t(Msg::UiScale))
+                        .step_by(0.05),
+                )
+                .dragged();
+        });
+    egui::CollapsingHeader::new(t(Msg::Midi))
+        .id_salt("midi")
+        .show(ui, |ui| {
+            match &hud.midi_port {
+                Some(port) => ui.label(trf(lang, Msg::MidiInputPort, &[port])),
+                None => ui.label(t(Msg::MidiNoInput)),
+            };
+            ui.label(t(Msg::MidiLearnHint));
+            egui::Grid::new("midi_grid").show(ui, |ui| {
+                for (key, binding) in &hud.midi_params {
+                    let armed = hud.midi_learning.as_deref() == Some(key.as_str());
+                    if ui.selectable_label(armed, key).clicked() {
+                        response.midi_learn = Some(key.clone());
+                    }
+                    if armed {
+                        ui.label(t(Msg::MidiWaiting));
+                    } else {
+                        ui.label(binding.as_deref().unwrap_or("—"));
+                    }
+                    if binding.is_some() && ui.small_button("×").clicked() {
+                        response.midi_unbind = Some(key.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    egui::CollapsingHeader::new(t(Msg::GpuStats))
+        .id_salt("gpu_stats")
+        .show(ui, |ui| {
+            let stats = &hud.gpu_stats;
+            egui::Grid::new("gpu_stats_grid").show(ui, |ui| {
+                ui.label(t(Msg::Dispatches));
+                ui.label(stats.dispatches.to_string());
+                ui.end_row();
+                ui.label(t(Msg::BindGroups));
+                ui.label(stats.bind_groups.to_string());
+                ui.end_row();
+                ui.label(t(Msg::BuffersMade));
+                ui.label(stats.buffers_created.to_string());
+                ui.end_row();
+                ui.label(t(Msg::BufferWrites));
+                ui.label(format!(
+                    "{} ({} B)",
+                    stats.buffer_writes, stats.bytes_written
+                ));
+                ui.end_row();
+                if let Some(allocs) = &hud.alloc_stats {
+                    ui.label(t(Msg::HeapAllocations));
+                    ui.label(format!("{} ({} B)", allocs.allocations, allocs.bytes));
+                    ui.end_row();
+                }
+            });
+        });
+    egui::CollapsingHeader::new(t(Msg::PresetBanks))
+        .id_salt("preset_banks")
+        .show(ui, |ui| {
+            for (i, preset) in Preset::ALL.iter().enumerate() {
+                let Some(label) = preset_hotkey_label(i) else {
+                    continue;
+                };
+                let text = format!("{label:>7}  {}", preset.name());
+                ui.horizontal(|ui| {
+                    if let Some(id) = thumbs.get(i).copied().flatten() {
+                        let thumb = egui::ImageButton::new((id, egui::vec2(32.0, 32.0)));
+                        if ui.add(thumb).clicked() {
+                            response.clicked_preset = Some(*preset);
+                        }
+                    }
+                    if i == hud.current_idx {
+                        ui.strong(text);
+                    } else {
+                        ui.label(text);
+                    }
+                });
+            }
+        });
+    ui.separator();
+    for msg in [
+        Msg::HelpLoadPreset,
+        Msg::HelpCycle,
+        Msg::HelpIterations,
+        Msg::HelpSaveExport,
+        Msg::HelpThumbsEditor,
+        Msg::HelpFullscreen,
+        Msg::HelpCues,
+        Msg::HelpDecks,
+        Msg::HelpControlWindow,
+        Msg::HelpRecord,
+        Msg::HelpTapTempo,
+        Msg::HelpBoxZoom,
+        Msg::HelpPan,
+        Msg::HelpClickQuit,
+    ] {
+        ui.label(t(msg));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// LFO panel — live waveform, rate and swing of each of the patch's LFOs
+// ---------------------------------------------------------------------------
+
+/// One row per LFO.  Edits go out through `response.lfo` rather than into
+/// the patch, which swaps in the edited copy.
+fn lfo_panel(
+    ui: &mut egui::Ui,
+    hud: &HudData,
+    settings: &mut Settings,
+    response: &mut HudResponse,
+) {
+    let t = |msg| tr(hud.lang, msg);
+    ui.horizontal(|ui| {
+        egui::ComboBox::new("lfo_rate_unit", t(Msg::LfoRateUnitLabel))
+            .selected_text(t(settings.lfo_rate_unit.label()))
+            .show_ui(ui, |ui| {
+                for unit in LfoRateUnit::ALL {
+                    ui.selectable_value(&mut settings.lfo_rate_unit, unit, t(unit.label()));
+                }
+            });
+        egui::ComboBox::new("lfo_depth", t(Msg::LfoDepthLabel))
+            .selected_text(t(settings.lfo_depth.label()))
+            .show_ui(ui, |ui| {
+                for depth in LfoDepth::ALL {
+                    ui.selectable_value(&mut settings.lfo_depth, depth, t(depth.label()));
+                }
+            });
+    });
+    let unit = settings.lfo_rate_unit.effective(hud.bpm);
+    egui::Grid::new("lfo_grid").show(ui, |ui| {
+        for (index, lfo) in &hud.lfos {
+            let index = *index;
+            let mut edited = lfo.clone();
+            ui.label(lfo.target);
+            egui::ComboBox::new(("lfo_wave", index), "")
+                .selected_text(t(waveform_label(&lfo.waveform)))
+                .show_ui(ui, |ui| {
+                    for w in Waveform::ALL {
+                        let label = t(waveform_label(&w));
+                        ui.selectable_value(&mut edited.waveform, w, label);
+                    }
+                });
+
+            // A stopped LFO's slider sits at the slow end, not at infinity.
+            let mut rate = unit.in_unit(lfo.frequency.max(MIN_LFO_HZ), hud.bpm);
+            let rate_slider = egui::Slider::new(&mut rate, unit.range(hud.bpm))
+                .logarithmic(true)
+                .text(t(Msg::LfoRate));
+            if ui.add(rate_slider).changed() {
+                edited.frequency = unit.to_hz(rate, hud.bpm);
+            }
+
+            // Drag speed follows the swing so small and large targets are
+            // both easy to nudge.
+            let speed = 0.005 * (lfo.amplitude.abs() + lfo.offset.abs()).max(0.1);
+            let drag = |ui: &mut egui::Ui, value: &mut f32, label| {
+                ui.add(
+                    egui::DragValue::new(value)
+                        .speed(speed)
+                        .prefix(format!("{} ", t(label))),
+                )
+                .changed()
+            };
+            match settings.lfo_depth {
+                LfoDepth::AmplitudeOffset => {
+                    drag(ui, &mut edited.amplitude, Msg::LfoAmplitude);
+                    drag(ui, &mut edited.offset, Msg::LfoOffset);
+                }
+                LfoDepth::MinMax => {
+                    let (mut low, mut high) = lfo.range();
+                    let moved = drag(ui, &mut low, Msg::LfoMin) | drag(ui, &mut high, Msg::LfoMax);
+                    if moved {
+                        edited.set_range(low, high);
+                    }
+                }
+            }
+            if edited != *lfo {
+                response.lfo = Some((index, edited));
+            }
+            ui.end_row();
+        }
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Frame-time graph — scrolling bars, newest on the right
+// ---------------------------------------------------------------------------
+
+/// Draw `frame_times` (seconds, oldest first) as a bar graph with 60 / 30 FPS
+/// reference lines.  The vertical scale grows to fit the `worst` frame so
+/// spikes are never clipped.
+fn frame_time_graph(ui: &mut egui::Ui, lang: Language, frame_times: &[f32], worst: f32) {
+    const TARGET_60: f32 = 1.0 / 60.0;
+    const TARGET_30: f32 = 1.0 / 30.0;
+
+    let size = egui::vec2(ui.available_width().max(180.0), 40.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_black_alpha(120));
+
+    let max_t = worst.max(TARGET_30 * 1.25);
+    let y_for = |t: f32| rect.bottom() - (t / max_t).min(1.0) * rect.height();
+
+    for (target, color) in [
+        (TARGET_60, egui::Color32::from_rgb(60, 160, 60)),
+        (TARGET_30, egui::Color32::from_rgb(180, 140, 40)),
+    ] {
+        let y = y_for(target);
+        painter.hline(rect.x_range(), y, egui::Stroke::new(1.0, color));
+    }
+
+    let bar_w = rect.width() / crate::fps::FRAME_HISTORY as f32;
+    let x0 = rect.right() - frame_times.len() as f32 * bar_w;
+    for (i, &t) in frame_times.iter().enumerate() {
+        let color = if t > TARGET_30 {
+            egui::Color32::from_rgb(220, 70, 60)
+        } else if t > TARGET_60 {
+            egui::Color32::from_rgb(220, 180, 60)
+        } else {
+            egui::Color32::from_gray(200)
+        };
+        let x = x0 + i as f32 * bar_w;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(x, y_for(t)),
+            egui::pos2(x + bar_w.max(1.0), rect.bottom()),
+        );
+        painter.rect_filled(bar, 0.0, color);
+    }
+
+    painter.text(
+        rect.left_top() + egui::vec2(4.0, 2.0),
+        egui::Align2::LEFT_TOP,
+        trf(lang, Msg::WorstFrame, &[&format!("{:.1}", worst * 1000.0)]),
+        egui::FontId::monospace(10.0),
+        egui::Color32::from_gray(220),
+    );
+}
@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// Language — selectable UI language
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// Name of the language in that language (shown in the picker).
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// Match a POSIX locale such as `de_DE.UTF-8` or a tag like `de-AT`.
+    pub fn from_locale(locale: &str) -> Option<Language> {
+        let lang = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match lang.as_str() {
+            "en" => Some(Language::English),
+            "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    /// Language from `LC_ALL` / `LC_MESSAGES` / `LANG`, if supported.
+    pub fn from_env() -> Option<Language> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|v| !v.is_empty())
+            .and_then(|v| Language::from_locale(&v))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// String catalog
+// ---------------------------------------------------------------------------
+//
+// One entry per UI string; every language must provide every key, so a
+// missing translation is a compile error.  `{}` placeholders are filled in
+// order by `trf`.
+
+macro_rules! catalog {
+    ($($key:ident => { en: $en:expr, de: $de:expr $(,)? }),* $(,)?) => {
+        /// Key of a translatable UI string.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Msg {
+            $($key),*
+        }
+
+        impl Msg {
+            #[cfg(test)]
+            pub const ALL: &'static [Msg] = &[$(Msg::$key),*];
+        }
+
+        /// The template for `msg` in `lang`.
+        pub fn tr(lang: Language, msg: Msg) -> &'static str {
+            match lang {
+                Language::English => match msg { $(Msg::$key => $en),* },
+                Language::German => match msg { $(Msg::$key => $de),* },
+            }
+        }
+    };
+}
+
+catalog! {
+    // --- HUD ---------------------------------------------------------------
+    HudTitle => { en: "Fractal Explorer", de: "Fraktal-Explorer" },
+    Preset => { en: "Preset", de: "Preset" },
+    Zoom => { en: "Zoom", de: "Zoom" },
+    Iterations => { en: "Iter", de: "Iter." },
+    IterationsAuto => { en: "{} (auto)", de: "{} (auto)" },
+    CursorIterations => { en: "Cursor: escapes after {} iterations", de: "Cursor: entkommt nach {} Iterationen" },
+    CursorInterior => { en: "Cursor: inside the set", de: "Cursor: innerhalb der Menge" },
+    Effects => { en: "Effects", de: "Effekte" },
+    EffectsNone => { en: "none", de: "keine" },
+    Fps => { en: "FPS", de: "FPS" },
+    OnePercentLow => { en: "1% low", de: "1%-Tief" },
+    WorstFrame => { en: "worst {} ms", de: "max. {} ms" },
+    View => { en: "View", de: "Ansicht" },
+    Copy => { en: "Copy", de: "Kopieren" },
+    DetachControls => { en: "Detach controls", de: "Bedienelemente abtrennen" },
+    AttachControls => { en: "Attach controls", de: "Bedienelemente andocken" },
+    ControlWindowTitle => { en: "Fractal Explorer — Controls", de: "Fraktal-Explorer — Bedienung" },
+    MainWindowTitle => { en: "{} — Fractal Explorer", de: "{} — Fraktal-Explorer" },
+    MainWindowTitleBy => { en: "{} by {} — Fractal Explorer", de: "{} von {} — Fraktal-Explorer" },
+    TileWindowTitle => { en: "Fractal Explorer — Output {}", de: "Fraktal-Explorer — Ausgabe {}" },
+    PresetBanks => { en: "Preset banks", de: "Preset-Bänke" },
+    GpuStats => { en: "GPU stats", de: "GPU-Statistik" },
+    Dispatches => { en: "Dispatches", de: "Dispatches" },
+    BindGroups => { en: "Bind groups", de: "Bind-Groups" },
+    BuffersMade => { en: "Buffers made", de: "Puffer erstellt" },
+    BufferWrites => { en: "Buffer writes", de: "Puffer-Writes" },
+    HeapAllocations => { en: "Heap allocations", de: "Heap-Allokationen" },
+    Formula => { en: "Formula", de: "Formel" },
+    FormulaApply => { en: "Apply", de: "Übernehmen" },
+    FormulaHelp => { en: "z c a b t i pi e · + - * / ^ · sin cos tan sinh cosh tanh exp log sqrt abs conj re im", de: "z c a b t i pi e · + - * / ^ · sin cos tan sinh cosh tanh exp log sqrt abs conj re im" },
+    FormulaErrorAt => { en: "column {}: {}", de: "Spalte {}: {}" },
+    ResumeTitle => { en: "Restore last session?", de: "Letzte Sitzung wiederherstellen?" },
+    ResumeBody => { en: "The last session ended unexpectedly. Its autosave from {} min before that shows {}.", de: "Die letzte Sitzung wurde unerwartet beendet. Ihre automatische Sicherung von {} Min. davor zeigt {}." },
+    ResumeRestore => { en: "Restore", de: "Wiederherstellen" },
+    ResumeDiscard => { en: "Discard", de: "Verwerfen" },
+
+    // --- Settings ----------------------------------------------------------
+    Settings => { en: "Settings", de: "Einstellungen" },
+    Theme => { en: "Theme", de: "Design" },
+    ThemeDark => { en: "Dark", de: "Dunkel" },
+    ThemeLight => { en: "Light", de: "Hell" },
+    ThemeHighContrast => { en: "High contrast", de: "Hoher Kontrast" },
+    UiScale => { en: "UI scale", de: "UI-Skalierung" },
+    LanguageLabel => { en: "Language", de: "Sprache" },
+    Crosshair => { en: "Crosshair", de: "Fadenkreuz" },
+    CrosshairCross => { en: "Cross", de: "Kreuz" },
+    CrosshairLargeCross => { en: "Large cross", de: "Großes Kreuz" },
+    CrosshairDot => { en: "Dot", de: "Punkt" },
+    CrosshairNone => { en: "None (OS cursor)", de: "Keins (System-Cursor)" },
+    LockBoxAspect => { en: "Lock zoom box to window aspect", de: "Zoom-Rahmen an Fensterformat binden" },
+    ProjectionLabel => { en: "Projection", de: "Projektion" },
+    ProjectionFlat => { en: "Flat", de: "Flach" },
+    ProjectionDome => { en: "Dome (180° fisheye)", de: "Kuppel (180°-Fischauge)" },
+    ProjectionExpMap => { en: "Exponential map (log-polar)", de: "Exponentialkarte (log-polar)" },
+    FrameAspectLabel => { en: "Frame aspect", de: "Bildformat" },
+    FrameAspectWindow => { en: "Fill window", de: "Fenster füllen" },
+    FrameAspectWidescreen => { en: "16:9", de: "16:9" },
+    FrameAspectClassic => { en: "4:3", de: "4:3" },
+    FrameAspectSquare => { en: "1:1 (square)", de: "1:1 (quadratisch)" },
+    FrameAspectPortrait => { en: "9:16 (portrait)", de: "9:16 (hochkant)" },
+    QuantizeLabel => { en: "Quantize switches", de: "Wechsel quantisieren" },
+    QuantizeOff => { en: "Immediately", de: "Sofort" },
+    QuantizeBeat => { en: "Next beat", de: "Nächster Schlag" },
+    QuantizeBar => { en: "Next bar", de: "Nächster Takt" },
+    RetriggerLabel => { en: "Retrigger LFOs", de: "LFOs neu starten" },
+    RetriggerOff => { en: "Never (free-running)", de: "Nie (frei laufend)" },
+    RetriggerBeat => { en: "Every beat", de: "Jeden Schlag" },
+    RetriggerBar => { en: "Every bar", de: "Jeden Takt" },
+    FlashFrames => { en: "Flash length (frames)", de: "Blitzdauer (Frames)" },
+    FlashEveryLabel => { en: "Auto flash", de: "Automatischer Blitz" },
+    FlashEveryOff => { en: "Off (X / MIDI only)", de: "Aus (nur X / MIDI)" },
+    FlashEveryBeat => { en: "Every beat", de: "Jeden Schlag" },
+    FlashEveryBar => { en: "Every bar", de: "Jeden Takt" },
+    Macros => { en: "Macros", de: "Makros" },
+    Cues => { en: "Cues", de: "Cues" },
+    CueGo => { en: "Go", de: "Los" },
+    CueRun => { en: "Run timers", de: "Timer starten" },
+    CueStop => { en: "Stop timers", de: "Timer anhalten" },
+    CueHold => { en: "{} s", de: "{} s" },
+    CueWaitsForGo => { en: "waits for Go", de: "wartet auf Los" },
+    Decks => { en: "Decks", de: "Decks" },
+    DeckA => { en: "Deck A: {}", de: "Deck A: {}" },
+    DeckB => { en: "Deck B", de: "Deck B" },
+    DeckOff => { en: "off", de: "aus" },
+    Crossfade => { en: "A ↔ B", de: "A ↔ B" },
+    TakeDeckB => { en: "Take deck B", de: "Deck B übernehmen" },
+    Overlay => { en: "Overlay", de: "Einblendung" },
+    OverlayShow => { en: "Show overlay", de: "Einblendung zeigen" },
+    OverlayText => { en: "Text", de: "Text" },
+    OverlayLogo => { en: "Logo PNG", de: "Logo-PNG" },
+    OverlayX => { en: "Position X", de: "Position X" },
+    OverlayY => { en: "Position Y", de: "Position Y" },
+    OverlayScale => { en: "Size", de: "Größe" },
+    OverlayOpacity => { en: "Opacity", de: "Deckkraft" },
+    OverlayPulse => { en: "Beat pulse", de: "Beat-Puls" },
+    Midi => { en: "MIDI", de: "MIDI" },
+    MidiInputPort => { en: "Input: {}", de: "Eingang: {}" },
+    MidiNoInput => { en: "No MIDI input", de: "Kein MIDI-Eingang" },
+    MidiLearnHint => { en: "Click a parameter, then move a knob to bind it", de: "Parameter anklicken, dann einen Regler bewegen, um ihn zuzuweisen" },
+    MidiWaiting => { en: "move a control…", de: "Regler bewegen…" },
+    MaxIterations => { en: "Iteration ceiling", de: "Iterationsobergrenze" },
+    AutoIterations => { en: "Scale iterations with zoom", de: "Iterationen mit Zoom erhöhen" },
+    AutoIterationsCap => { en: "up to", de: "bis" },
+    AntialiasLabel => { en: "Anti-aliasing", de: "Kantenglättung" },
+    AntialiasOff => { en: "Off", de: "Aus" },
+    AntialiasX2 => { en: "2× supersampling", de: "2-fache Überabtastung" },
+    AntialiasX4 => { en: "4× supersampling", de: "4-fache Überabtastung" },
+    AntialiasX8 => { en: "8× supersampling", de: "8-fache Überabtastung" },
+    SmoothIterations => { en: "Smooth colour bands", de: "Farbstufen glätten" },
+    EffectLod => { en: "Lighter effects while moving", de: "Leichtere Effekte bei Bewegung" },
+    Lfos => { en: "LFOs", de: "LFOs" },
+    LfoRateUnitLabel => { en: "Rate in", de: "Rate in" },
+    LfoRateHertz => { en: "Hz", de: "Hz" },
+    LfoRateSeconds => { en: "seconds per cycle", de: "Sekunden pro Zyklus" },
+    LfoRateBeats => { en: "beats per cycle", de: "Schläge pro Zyklus" },
+    LfoDepthLabel => { en: "Swing as", de: "Ausschlag als" },
+    LfoDepthAmplitudeOffset => { en: "amplitude ± offset", de: "Amplitude ± Offset" },
+    LfoDepthMinMax => { en: "min … max", de: "Min … Max" },
+    LfoRate => { en: "rate", de: "Rate" },
+    LfoAmplitude => { en: "amplitude", de: "Amplitude" },
+    LfoOffset => { en: "offset", de: "Offset" },
+    LfoMin => { en: "min", de: "Min" },
+    LfoMax => { en: "max", de: "Max" },
+    WaveSine => { en: "Sine", de: "Sinus" },
+    WaveTriangle => { en: "Triangle", de: "Dreieck" },
+    WaveSquare => { en: "Square", de: "Rechteck" },
+    WaveSaw => { en: "Saw", de: "Sägezahn" },
+    WaveSmoothRandom => { en: "Smooth random", de: "Weicher Zufall" },
+    WaveExponential => { en: "Exponential", de: "Exponentiell" },
+    WaveTable => { en: "Wavetable (patch)", de: "Wellentabelle (Patch)" },
+    ThreadedModulation => { en: "Modulate on a background thread (240 Hz)", de: "Modulation im Hintergrund-Thread (240 Hz)" },
+    WatchdogTimeout => { en: "Restart rendering after a hang (s, 0 = off)", de: "Rendern nach Hänger neu starten (s, 0 = aus)" },
+    HalfResEffects => { en: "Half-resolution blurs (faster)", de: "Unschärfe in halber Auflösung (schneller)" },
+    AudioAutoGain => { en: "Audio auto-gain", de: "Automatische Audio-Aussteuerung" },
+    Tempo => { en: "Tempo:  {} BPM", de: "Tempo:  {} BPM" },
+    PatchAuthor => { en: "by {}", de: "von {}" },
+    PatchTempo => { en: "Suggested tempo:  {} BPM", de: "Empfohlenes Tempo:  {} BPM" },
+    PatchTags => { en: "Tags: {}", de: "Schlagwörter: {}" },
+    AuthorName => { en: "Your name (signs exported patches)", de: "Ihr Name (signiert exportierte Patches)" },
+    QueuedPreset => { en: "Next: {} (waiting for the beat)", de: "Als Nächstes: {} (wartet auf den Schlag)" },
+    PanFriction => { en: "Pan glide friction", de: "Gleitreibung beim Schwenken" },
+    RecordingInput => { en: "● Recording input (F9 to stop)", de: "● Eingaben werden aufgezeichnet (F9 beendet)" },
+    ModulationFeedback => { en: "⚠ Modulation feedback: {}", de: "⚠ Modulationsrückkopplung: {}" },
+    ParamsRepaired => { en: "⚠ Invalid values reset: {}", de: "⚠ Ungültige Werte zurückgesetzt: {}" },
+    ReplayingSession => { en: "▶ Replaying input session", de: "▶ Eingabesitzung wird abgespielt" },
+    StreamPreview => { en: "Stream preview over HTTP", de: "Vorschau per HTTP streamen" },
+    StreamPort => { en: "Port", de: "Port" },
+    StreamLan => { en: "Allow viewers on the local network", de: "Zuschauer im lokalen Netz zulassen" },
+    StreamStatusLocal => { en: "Serving http://localhost:{}/ — {} viewer(s)", de: "Sendet auf http://localhost:{}/ — {} Zuschauer" },
+    StreamStatus => { en: "Serving http://<this machine>:{}/ — {} viewer(s)", de: "Sendet auf http://<dieser Rechner>:{}/ — {} Zuschauer" },
+    HideCursorAfter => { en: "Hide cursor after (s, fullscreen; 0 = never)", de: "Cursor ausblenden nach (s, Vollbild; 0 = nie)" },
+
+    // --- Key help ----------------------------------------------------------
+    HelpLoadPreset => { en: "0–9  load preset   Shift  bank 2", de: "0–9  Preset laden  Shift  Bank 2" },
+    HelpCycle => { en: "Space  cycle", de: "Leertaste  weiter" },
+    HelpIterations => { en: "+/-  iterations (Shift ×10)    R  reset", de: "+/-  Iterationen (Umschalt ×10)   R  zurücksetzen" },
+    HelpSaveExport => { en: "S  save tweaks     E  export patch", de: "S  Änderungen sichern  E  Patch exportieren" },
+    HelpThumbsEditor => { en: "T  redo thumbnails W  shader editor", de: "T  Vorschaubilder  W  Shader-Editor" },
+    HelpFullscreen => { en: "F  fullscreen       X  flash", de: "F  Vollbild         X  Blitz" },
+    HelpCues => { en: "G  next cue         Shift+G  run cue timers", de: "G  nächster Cue     Umschalt+G  Cue-Timer" },
+    HelpDecks => { en: "D  take deck B", de: "D  Deck B übernehmen" },
+    HelpControlWindow => { en: "C  detach controls", de: "C  Bedienfenster abtrennen" },
+    HelpRecord => { en: "F9  record input", de: "F9  Eingaben aufzeichnen" },
+    HelpTapTempo => { en: "B  tap tempo       L  retrigger LFOs", de: "B  Tempo antippen  L  LFOs neu starten" },
+    HelpBoxZoom => { en: "Drag  box zoom     Right-drag  zoom out", de: "Ziehen  Rahmen-Zoom  Rechts ziehen  herauszoomen" },
+    HelpPan => { en: "Middle-drag  pan (flick to glide)", de: "Mittlere Taste ziehen  schwenken (Schwung)" },
+    HelpClickQuit => { en: "Click  zoom        Q/Esc  quit", de: "Klick  Zoom        Q/Esc  Beenden" },
+
+    // --- Shader editor -----------------------------------------------------
+    ShaderEditor => { en: "Shader editor", de: "Shader-Editor" },
+    ShaderGenerator => { en: "Generator: {}", de: "Generator: {}" },
+    ShaderEffect => { en: "Effect: {}", de: "Effekt: {}" },
+    Revert => { en: "Revert", de: "Zurücksetzen" },
+    Compiled => { en: "compiled", de: "kompiliert" },
+    CompileFailedAt => { en: "line {}: compile failed — last good shader still running", de: "Zeile {}: Kompilieren fehlgeschlagen — letzter funktionierender Shader läuft weiter" },
+    CompileFailed => { en: "compile failed — last good shader still running", de: "Kompilieren fehlgeschlagen — letzter funktionierender Shader läuft weiter" },
+
+    // --- Toasts ------------------------------------------------------------
+    ToastSettingsUnreadable => { en: "Settings could not be read: {}", de: "Einstellungen konnten nicht gelesen werden: {}" },
+    ToastTweaksUnreadable => { en: "Saved tweaks could not be read: {}", de: "Gespeicherte Änderungen konnten nicht gelesen werden: {}" },
+    ToastShaderNotRestored => { en: "Edited {} shader could not be restored: {}", de: "Bearbeiteter {}-Shader konnte nicht wiederhergestellt werden: {}" },
+    ToastPresetReset => { en: "{} reset to factory defaults", de: "{} auf Werkseinstellungen zurückgesetzt" },
+    ToastPresetSaved => { en: "Preset saved: {}", de: "Preset gespeichert: {}" },
+    ToastNothingToSave => { en: "{} has no changes to save", de: "{} hat keine Änderungen zum Speichern" },
+    ToastWarmingUp => { en: "Preparing preset shaders {}/{}", de: "Preset-Shader werden vorbereitet {}/{}" },
+    ToastThumbnailsRegenerated => { en: "Thumbnails regenerated", de: "Vorschaubilder neu erzeugt" },
+    ToastNoConfigTweaks => { en: "No config directory — tweaks are not persisted", de: "Kein Konfigurationsverzeichnis — Änderungen werden nicht gespeichert" },
+    ToastSaveTweaksFailed => { en: "Saving tweaks failed: {}", de: "Speichern der Änderungen fehlgeschlagen: {}" },
+    ToastSaveSettingsFailed => { en: "Saving settings failed: {}", de: "Speichern der Einstellungen fehlgeschlagen: {}" },
+    ToastNoConfigBundle => { en: "No config directory — bundle not exported", de: "Kein Konfigurationsverzeichnis — Patch nicht exportiert" },
+    ToastPatchExported => { en: "Patch exported to {}", de: "Patch exportiert nach {}" },
+    ToastPatchExportFailed => { en: "Patch export failed: {}", de: "Patch-Export fehlgeschlagen: {}" },
+    ToastPatchImported => { en: "Patch imported: {}", de: "Patch importiert: {}" },
+    ToastUnusedTargets => { en: "Nothing reads modulation target(s): {}", de: "Modulationsziel(e) werden nirgends gelesen: {}" },
+    ToastModulationFeedback => { en: "Modulation routes feed back into: {}", de: "Modulationsrouten koppeln auf sich selbst zurück: {}" },
+    ToastPatchImportFailed => { en: "Patch import failed: {}", de: "Patch-Import fehlgeschlagen: {}" },
+    ToastJumpedToView => { en: "Jumped to shared view", de: "Geteilte Ansicht geöffnet" },
+    ToastShaderCompileFailed => { en: "Shader compile failed: {}", de: "Shader-Kompilierung fehlgeschlagen: {}" },
+    ToastShaderReverted => { en: "Shader reverted", de: "Shader zurückgesetzt" },
+    ToastBuiltinShaderFailed => { en: "Built-in shader failed to recompile: {}", de: "Eingebauter Shader ließ sich nicht kompilieren: {}" },
+    ToastStreamFailed => { en: "Could not start the preview stream: {}", de: "Vorschau-Stream konnte nicht gestartet werden: {}" },
+    ToastGpuRebuilt => { en: "The GPU stopped responding; rendering was restarted", de: "Die GPU reagierte nicht mehr; das Rendern wurde neu gestartet" },
+    ToastGpuRebuildFailed => { en: "Could not restart rendering: {}", de: "Rendern konnte nicht neu gestartet werden: {}" },
+    ToastRecordingStarted => { en: "Recording input to {}", de: "Eingaben werden aufgezeichnet nach {}" },
+    ToastRecordingSaved => { en: "Input recording saved: {}", de: "Eingabeaufzeichnung gespeichert: {}" },
+    ToastRecordingFailed => { en: "Input recording failed: {}", de: "Eingabeaufzeichnung fehlgeschlagen: {}" },
+    ToastNoConfigSession => { en: "No config directory — input not recorded", de: "Kein Konfigurationsverzeichnis — Eingaben werden nicht aufgezeichnet" },
+    ToastReplayStarted => { en: "Replaying {}", de: "Spiele {} ab" },
+    ToastReplayFinished => { en: "Replay finished", de: "Wiedergabe beendet" },
+    ToastReplayFailed => { en: "Could not replay {}: {}", de: "{} konnte nicht abgespielt werden: {}" },
+    ToastCuesLoaded => { en: "Cue list loaded: {} cues — G to go", de: "Cue-Liste geladen: {} Cues — G zum Starten" },
+    ToastCuesFailed => { en: "Could not load cue list {}: {}", de: "Cue-Liste {} konnte nicht geladen werden: {}" },
+    ToastVideoOpened => { en: "Video input: {}", de: "Videoeingang: {}" },
+    ToastImageOpened => { en: "Image: {}", de: "Bild: {}" },
+    ToastImageDownscaled => { en: "Image: {} (shrunk to {} to fit the GPU)", de: "Bild: {} (auf {} verkleinert, passend für die GPU)" },
+    ToastImageFailed => { en: "Could not open image {}: {}", de: "Bild {} konnte nicht geöffnet werden: {}" },
+    ToastTilesLoaded => { en: "Tiled output: {} windows on a {} canvas", de: "Gekachelte Ausgabe: {} Fenster, Leinwand {}" },
+    ToastTilesFailed => { en: "Could not load tile layout {}: {}", de: "Kachel-Layout {} konnte nicht geladen werden: {}" },
+    ToastWarpLoaded => { en: "Output warp: {}", de: "Ausgabeentzerrung: {}" },
+    ToastWarpFailed => { en: "Could not load output warp {}: {}", de: "Ausgabeentzerrung {} konnte nicht geladen werden: {}" },
+    ToastOverlayFailed => { en: "Could not load overlay: {}", de: "Einblendung konnte nicht geladen werden: {}" },
+    ToastVideoFailed => { en: "Could not open video {}: {}", de: "Video {} konnte nicht geöffnet werden: {}" },
+    ToastCue => { en: "Cue {}: {}", de: "Cue {}: {}" },
+    ToastCuesEnded => { en: "End of cue list", de: "Ende der Cue-Liste" },
+    ToastCueFailed => { en: "Cue {} could not be loaded: {}", de: "Cue {} konnte nicht geladen werden: {}" },
+    ToastControllerMapUnreadable => { en: "MIDI controller map could not be read: {}", de: "MIDI-Controller-Zuordnung konnte nicht gelesen werden: {}" },
+    ToastSaveControllerMapFailed => { en: "Saving the MIDI controller map failed: {}", de: "Speichern der MIDI-Controller-Zuordnung fehlgeschlagen: {}" },
+    ToastMidiBound => { en: "{} bound to {}", de: "{} zugewiesen an {}" },
+    ToastSessionRestored => { en: "Last session restored: {}", de: "Letzte Sitzung wiederhergestellt: {}" },
+    ToastSessionNotRestored => { en: "Could not restore the last session: {}", de: "Letzte Sitzung konnte nicht wiederhergestellt werden: {}" },
+    ToastAutosaveUnreadable => { en: "Autosave could not be read: {}", de: "Automatische Sicherung konnte nicht gelesen werden: {}" },
+    ToastAutosaveFailed => { en: "Autosave failed: {}", de: "Automatische Sicherung fehlgeschlagen: {}" },
+    ToastFormulaFailed => { en: "Formula {} failed to compile — last good one still running: {}", de: "Formel {} konnte nicht kompiliert werden — letzte funktionierende läuft weiter: {}" },
+    ToastNotAViewCode => { en: "Pasted text is not a view code: {}", de: "Eingefügter Text ist kein Ansichts-Code: {}" },
+}
+
+/// Translate `msg` and fill its `{}` placeholders with `args`, in order.
+/// Surplus placeholders are left as-is; surplus args are ignored.
+pub fn trf(lang: Language, msg: Msg, args: &[&dyn std::fmt::Display]) -> String {
+    let template = tr(lang, msg);
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        match args.next() {
+            Some(a) => out.push_str(&a.to_string()),
+            None => out.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_message_is_translated() {
+        for &lang in &Language::ALL {
+            for &msg in Msg::ALL {
+                assert!(!tr(lang, msg).is_empty(), "{lang:?} {msg:?} is empty");
+            }
+        }
+    }
+
+    #[test]
+    fn placeholder_counts_match_english() {
+        for &msg in Msg::ALL {
+            let en = tr(Language::English, msg).matches("{}").count();
+            for &lang in &Language::ALL {
+                assert_eq!(
+                    tr(lang, msg).matches("{}").count(),
+                    en,
+                    "{lang:?} {msg:?} has a different number of placeholders"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn trf_fills_placeholders_in_order() {
+        let s = trf(
+            Language::English,
+            Msg::ToastShaderNotRestored,
+            &[&"Julia", &"boom"],
+        );
+        assert_eq!(s, "Edited Julia shader could not be restored: boom");
+    }
+
+    #[test]
+    fn trf_tolerates_missing_args() {
+        assert_eq!(
+            trf(Language::English, Msg::ToastPresetSaved, &[]),
+            "Preset saved: {}"
+        );
+    }
+
+    #[test]
+    fn german_differs_from_english() {
+        assert_eq!(tr(Language::German, Msg::Settings), "Einstellungen");
+        assert_ne!(
+            tr(Language::German, Msg::HudTitle),
+            tr(Language::English, Msg::HudTitle)
+        );
+    }
+
+    #[test]
+    fn locale_parsing() {
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), Some(Language::German));
+        assert_eq!(Language::from_locale("de-AT"), Some(Language::German));
+        assert_eq!(Language::from_locale("en_US"), Some(Language::English));
+        assert_eq!(Language::from_locale("fr_FR"), None);
+        assert_eq!(Language::from_locale(""), None);
+    }
+}
@@ -1,4 +1,5 @@
 use fractal_core::presets::Preset;
+use serde::{Deserialize, Serialize};
 
 // ---------------------------------------------------------------------------
 // Key — windowing-library-independent key representation
@@ -10,31 +11,86 @@ use fractal_core::presets::Preset;
 /// in the input pipeline works purely with this enum.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Key {
+    Digit0,
     Digit1,
     Digit2,
     Digit3,
     Digit4,
     Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
     Space,
-    Equal, // = / + (same physical key; Shift state ignored)
-    Minus, // - / _ (same physical key; Shift state ignored)
+    B,
+    C,
+    D,
+    G,
+    L,
+    N,
+    X,
+    Equal, // = / + (same physical key; Shift selects the coarse step)
+    Minus, // - / _ (same physical key; Shift selects the coarse step)
+    E,
+    F,
     R,
+    S,
+    T,
+    W,
     Q,
     Escape,
+    F9,
 }
 
 // ---------------------------------------------------------------------------
 // InputAction — what the app does in response to input
 // ---------------------------------------------------------------------------
 
-/// High-level action produced by a key press or mouse click.
-#[derive(Debug, Clone, PartialEq)]
+/// High-level action produced by a key press or mouse click.  Serialisable
+/// so input sessions can be recorded and replayed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum InputAction {
     LoadPreset(Preset),
     CycleNextPreset,
     IterationsUp,
     IterationsDown,
+    /// Ten times the `IterationsUp` / `IterationsDown` step (Shift held).
+    IterationsUpCoarse,
+    IterationsDownCoarse,
     Reset,
+    /// Persist the current preset's tweaks as user overrides.
+    SaveOverrides,
+    /// Write the current look to a `.fractalpatch` bundle.
+    ExportBundle,
+    /// Re-render every cached preset thumbnail.
+    RegenerateThumbnails,
+    /// Show / hide the WGSL shader editor.
+    ToggleShaderEditor,
+    /// Move the control panels into / out of a separate window.
+    ToggleControlWindow,
+    /// Switch between windowed and borderless fullscreen.
+    ToggleFullscreen,
+    /// Start / stop recording input actions to a session file.
+    ToggleRecording,
+    /// Tap a beat: sets the beat clock's tempo and fires momentary triggers.
+    TapTempo,
+    /// Restart every LFO's cycle now.
+    RetriggerLfos,
+    /// Spike the patch's flash targets for a few frames (strobe hit).
+    Flash,
+    /// Start a stateful generator's simulation (the reaction–diffusion)
+    /// over from its seed pattern.
+    ResetSimulation,
+    /// Fire the next cue of the loaded cue list.
+    CueGo,
+    /// Start / stop the cue list's hold timers.
+    CueToggleRun,
+    /// Start `preset` running on deck B, ready to crossfade in.
+    LoadDeckB(Preset),
+    /// Stop deck B and return the output to deck A.
+    CloseDeckB,
+    /// Make deck B's patch the live one and pull the crossfader back.
+    TakeDeckB,
     Quit,
     /// Zoom in 2× centred on a normalised screen position.
     /// `norm_x` and `norm_y` are in \[0, 1\] (0 = left/top, 1 = right/bottom).
@@ -42,32 +98,160 @@ pub enum InputAction {
         norm_x: f32,
         norm_y: f32,
     },
+    /// Zoom to a dragged rectangle given by two normalised corners.  With
+    /// `zoom_out` the current viewport is shrunk into the rectangle instead.
+    BoxZoom {
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        zoom_out: bool,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Preset banks — digit keys address slots in `Preset::ALL`
+// ---------------------------------------------------------------------------
+
+/// Number of preset slots reachable from the digit row (1–9, then 0).
+pub const SLOTS_PER_BANK: usize = 10;
+
+/// Number of banks: plain digits, then Shift+digit.
+pub const BANKS: usize = 2;
+
+/// Slot index of a digit key in keyboard order — `1` is slot 0 and `0` is
+/// slot 9, matching the physical layout of the digit row.
+pub fn digit_slot(key: Key) -> Option<usize> {
+    match key {
+        Key::Digit1 => Some(0),
+        Key::Digit2 => Some(1),
+        Key::Digit3 => Some(2),
+        Key::Digit4 => Some(3),
+        Key::Digit5 => Some(4),
+        Key::Digit6 => Some(5),
+        Key::Digit7 => Some(6),
+        Key::Digit8 => Some(7),
+        Key::Digit9 => Some(8),
+        Key::Digit0 => Some(9),
+        _ => None,
+    }
+}
+
+/// Preset assigned to `slot` of `bank`, if the registry is long enough.
+pub fn preset_for_slot(bank: usize, slot: usize) -> Option<Preset> {
+    if bank >= BANKS || slot >= SLOTS_PER_BANK {
+        return None;
+    }
+    Preset::ALL.get(bank * SLOTS_PER_BANK + slot).copied()
+}
+
+/// Hotkey label for the preset at `index` in `Preset::ALL`, e.g. `"3"` or
+/// `"Shift+3"`.  `None` if the preset is beyond the last bank.
+pub fn preset_hotkey_label(index: usize) -> Option<String> {
+    let bank = index / SLOTS_PER_BANK;
+    if bank >= BANKS {
+        return None;
+    }
+    let digit = (index % SLOTS_PER_BANK + 1) % 10;
+    Some(if bank == 0 {
+        digit.to_string()
+    } else {
+        format!("Shift+{digit}")
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Box zoom — drag a rectangle to zoom in (left) or out (right)
+// ---------------------------------------------------------------------------
+
+/// Drags shorter than this (physical pixels) count as a plain click.
+pub const DRAG_THRESHOLD: f32 = 4.0;
+
+/// A drag in progress, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxDrag {
+    pub start: (f32, f32),
+    pub end: (f32, f32),
+    pub zoom_out: bool,
+}
+
+impl BoxDrag {
+    /// Whether the pointer has moved far enough to count as a drag.
+    pub fn is_drag(&self) -> bool {
+        let dx = self.end.0 - self.start.0;
+        let dy = self.end.1 - self.start.1;
+        dx.abs().max(dy.abs()) >= DRAG_THRESHOLD
+    }
+
+    /// The far corner, optionally grown so the box matches `aspect`
+    /// (width / height).  The start corner stays anchored.
+    pub fn end_corner(&self, lock_aspect: bool, aspect: f32) -> (f32, f32) {
+        if !lock_aspect {
+            return self.end;
+        }
+        let dx = self.end.0 - self.start.0;
+        let dy = self.end.1 - self.start.1;
+        let (w, h) = if dx.abs() > dy.abs() * aspect {
+            (dx.abs(), dx.abs() / aspect)
+        } else {
+            (dy.abs() * aspect, dy.abs())
+        };
+        (self.start.0 + w.copysign(dx), self.start.1 + h.copysign(dy))
+    }
 }
 
 // ---------------------------------------------------------------------------
 // InputState
 // ---------------------------------------------------------------------------
 
-pub struct InputState;
+#[derive(Default)]
+pub struct InputState {
+    /// Whether Shift is held — selects the second preset bank and the
+    /// coarse iteration step.
+    shift: bool,
+    /// Box-zoom drag in progress, if a mouse button is held.
+    drag: Option<BoxDrag>,
+}
 
 impl InputState {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Update the tracked modifier state (fed from winit `ModifiersChanged`).
+    pub fn set_shift(&mut self, held: bool) {
+        self.shift = held;
     }
 
     /// Translate a `Key` press into an `InputAction`, if the key is mapped.
     pub fn on_key(&self, key: Key) -> Option<InputAction> {
+        if let Some(slot) = digit_slot(key) {
+            let bank = usize::from(self.shift);
+            return preset_for_slot(bank, slot).map(InputAction::LoadPreset);
+        }
         match key {
-            Key::Digit1 => Some(InputAction::LoadPreset(Preset::ClassicMandelbrot)),
-            Key::Digit2 => Some(InputAction::LoadPreset(Preset::PsychedelicJulia)),
-            Key::Digit3 => Some(InputAction::LoadPreset(Preset::TrippyMandelbrot)),
-            Key::Digit4 => Some(InputAction::LoadPreset(Preset::BurningShipTrails)),
-            Key::Digit5 => Some(InputAction::LoadPreset(Preset::NoiseField)),
             Key::Space => Some(InputAction::CycleNextPreset),
+            Key::Equal if self.shift => Some(InputAction::IterationsUpCoarse),
+            Key::Minus if self.shift => Some(InputAction::IterationsDownCoarse),
             Key::Equal => Some(InputAction::IterationsUp),
             Key::Minus => Some(InputAction::IterationsDown),
             Key::R => Some(InputAction::Reset),
+            Key::S => Some(InputAction::SaveOverrides),
+            Key::E => Some(InputAction::ExportBundle),
+            Key::T => Some(InputAction::RegenerateThumbnails),
+            Key::W => Some(InputAction::ToggleShaderEditor),
+            Key::F => Some(InputAction::ToggleFullscreen),
+            Key::C => Some(InputAction::ToggleControlWindow),
+            Key::B => Some(InputAction::TapTempo),
+            Key::D => Some(InputAction::TakeDeckB),
+            Key::G if self.shift => Some(InputAction::CueToggleRun),
+            Key::G => Some(InputAction::CueGo),
+            Key::L => Some(InputAction::RetriggerLfos),
+            Key::X => Some(InputAction::Flash),
+            Key::N => Some(InputAction::ResetSimulation),
+            Key::F9 => Some(InputAction::ToggleRecording),
             Key::Q | Key::Escape => Some(InputAction::Quit),
+            _ => None,
         }
     }
 
@@ -75,6 +259,52 @@ impl InputState {
     pub fn on_mouse_click(&self, norm_x: f32, norm_y: f32) -> InputAction {
         InputAction::MouseZoom { norm_x, norm_y }
     }
+
+    /// A button went down over the fractal at `pos` (physical pixels).
+    pub fn begin_drag(&mut self, pos: (f32, f32), zoom_out: bool) {
+        self.drag = Some(BoxDrag {
+            start: pos,
+            end: pos,
+            zoom_out,
+        });
+    }
+
+    /// The pointer moved; extends the drag if one is in progress.
+    pub fn update_drag(&mut self, pos: (f32, f32)) {
+        if let Some(drag) = &mut self.drag {
+            drag.end = pos;
+        }
+    }
+
+    /// The drag in progress, if any (for drawing the selection).
+    pub fn drag(&self) -> Option<&BoxDrag> {
+        self.drag.as_ref()
+    }
+
+    /// Finish the drag for the button that started it.  A left click that
+    /// never moved is a 2× click-zoom; a short right click does nothing.
+    /// `size` is the window size in physical pixels.
+    pub fn end_drag(
+        &mut self,
+        zoom_out: bool,
+        size: (f32, f32),
+        lock_aspect: bool,
+    ) -> Option<InputAction> {
+        let drag = self.drag.filter(|d| d.zoom_out == zoom_out)?;
+        self.drag = None;
+        let (w, h) = size;
+        if !drag.is_drag() {
+            return (!drag.zoom_out).then(|| self.on_mouse_click(drag.end.0 / w, drag.end.1 / h));
+        }
+        let (ex, ey) = drag.end_corner(lock_aspect, w / h);
+        Some(InputAction::BoxZoom {
+            x0: drag.start.0 / w,
+            y0: drag.start.1 / h,
+            x1: ex / w,
+            y1: ey / h,
+            zoom_out: drag.zoom_out,
+        })
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -105,13 +335,76 @@ pub fn apply_zoom(
     (new_cx, new_cy, zoom * 2.0)
 }
 
+/// Height of the view in the complex plane at zoom 1, matching the
+/// generator shaders' pixel → plane mapping.
+pub const VIEW_SPAN: f32 = 2.0;
+
+/// Fit the view to a normalised rectangle, returning
+/// `(new_center_x, new_center_y, new_zoom)`.
+///
+/// Zooming in makes the box fill the window (the longer side, relative to
+/// the window, decides the scale so nothing in the box is cut off).  Zooming
+/// out is the inverse: the whole current view shrinks into the box.
+pub fn apply_box_zoom(
+    cx: f32,
+    cy: f32,
+    zoom: f32,
+    (x0, y0): (f32, f32),
+    (x1, y1): (f32, f32),
+    aspect: f32, // width / height
+    zoom_out: bool,
+) -> (f32, f32, f32) {
+    let bx = (x0 + x1) * 0.5;
+    let by = (y0 + y1) * 0.5;
+    let fit = (x1 - x0).abs().max((y1 - y0).abs()).max(1e-6);
+    let scale = VIEW_SPAN / zoom;
+    if zoom_out {
+        let new_zoom = zoom * fit;
+        let new_scale = VIEW_SPAN / new_zoom;
+        let new_cx = cx - (bx - 0.5) * new_scale * aspect;
+        let new_cy = cy - (by - 0.5) * new_scale;
+        (new_cx, new_cy, new_zoom)
+    } else {
+        let new_cx = cx + (bx - 0.5) * scale * aspect;
+        let new_cy = cy + (by - 0.5) * scale;
+        (new_cx, new_cy, zoom / fit)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Iteration clamping
 // ---------------------------------------------------------------------------
 
-/// Clamp an iteration count to the valid range \[20, 500\].
-pub fn clamp_iterations(iter: u32) -> u32 {
-    iter.clamp(20, 500)
+/// Fewest iterations a view may use.
+pub const MIN_ITERATIONS: u32 = 20;
+
+/// Default iteration ceiling; settings or `--max-iterations` change it.
+pub const DEFAULT_MAX_ITERATIONS: u32 = 10_000;
+
+/// Highest ceiling that can be configured.
+pub const MAX_ITERATIONS: u32 = 100_000;
+
+/// Iterations the automatic schedule adds per doubling of zoom.
+pub const ITERATIONS_PER_OCTAVE: f32 = 40.0;
+
+/// A configured ceiling brought into \[`MIN_ITERATIONS`, `MAX_ITERATIONS`\].
+pub fn clamp_ceiling(ceiling: u32) -> u32 {
+    ceiling.clamp(MIN_ITERATIONS, MAX_ITERATIONS)
+}
+
+/// Clamp an iteration count to \[`MIN_ITERATIONS`, `ceiling`\].
+pub fn clamp_iterations(iter: u32, ceiling: u32) -> u32 {
+    iter.clamp(MIN_ITERATIONS, clamp_ceiling(ceiling))
+}
+
+/// Zoom-aware iteration count: `base` at zoom 1 and below, plus
+/// `ITERATIONS_PER_OCTAVE` for every doubling of zoom beyond that, never
+/// above `cap` — nor below `base`, so a hand-raised count is kept.  The
+/// caller still clamps the result to its ceiling.
+pub fn auto_iterations(base: u32, zoom: f32, cap: u32) -> u32 {
+    let octaves = zoom.max(1.0).log2();
+    let scheduled = base as f32 + ITERATIONS_PER_OCTAVE * octaves;
+    (scheduled as u32).min(cap).max(base)
 }
 
 // ---------------------------------------------------------------------------
@@ -121,6 +414,7 @@ pub fn clamp_iterations(iter: u32) -> u32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     fn input() -> InputState {
         InputState::new()
@@ -168,6 +462,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn digit_0_loads_tenth_slot() {
+        assert_eq!(
+            input().on_key(Key::Digit0),
+            Some(InputAction::LoadPreset(Preset::ALL[9]))
+        );
+    }
+
+    // --- Shift bank -----------------------------------------------------------
+
+    fn shifted() -> InputState {
+        let mut s = InputState::new();
+        s.set_shift(true);
+        s
+    }
+
+    #[test]
+    fn shift_digit_1_loads_eleventh_preset() {
+        assert_eq!(
+            shifted().on_key(Key::Digit1),
+            Some(InputAction::LoadPreset(Preset::ALL[SLOTS_PER_BANK]))
+        );
+    }
+
+    #[test]
+    fn shift_digit_0_loads_twentieth_slot() {
+        assert_eq!(
+            shifted().on_key(Key::Digit0),
+            Some(InputAction::LoadPreset(Preset::ALL[2 * SLOTS_PER_BANK - 1]))
+        );
+    }
+
+    #[test]
+    fn slots_past_the_last_bank_are_unmapped() {
+        assert_eq!(preset_for_slot(BANKS, 0), None);
+        assert_eq!(preset_for_slot(0, SLOTS_PER_BANK), None);
+    }
+
+    #[test]
+    fn shift_does_not_affect_other_keys() {
+        assert_eq!(shifted().on_key(Key::R), Some(InputAction::Reset));
+    }
+
+    #[test]
+    fn releasing_shift_returns_to_first_bank() {
+        let mut s = shifted();
+        s.set_shift(false);
+        assert_eq!(
+            s.on_key(Key::Digit1),
+            Some(InputAction::LoadPreset(Preset::ClassicMandelbrot))
+        );
+    }
+
+    #[test]
+    fn hotkey_labels_follow_digit_row() {
+        assert_eq!(preset_hotkey_label(0).as_deref(), Some("1"));
+        assert_eq!(preset_hotkey_label(8).as_deref(), Some("9"));
+        assert_eq!(preset_hotkey_label(9).as_deref(), Some("0"));
+        assert_eq!(preset_hotkey_label(10).as_deref(), Some("Shift+1"));
+        assert_eq!(preset_hotkey_label(19).as_deref(), Some("Shift+0"));
+        assert_eq!(preset_hotkey_label(20), None);
+    }
+
+    #[test]
+    fn hotkey_labels_round_trip_through_slots() {
+        for (i, preset) in Preset::ALL.iter().enumerate().take(BANKS * SLOTS_PER_BANK) {
+            let bank = i / SLOTS_PER_BANK;
+            let slot = i % SLOTS_PER_BANK;
+            assert_eq!(preset_for_slot(bank, slot), Some(*preset));
+        }
+    }
+
     // --- Other key mappings ---------------------------------------------------
 
     #[test]
@@ -191,11 +557,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn shift_selects_coarse_iteration_steps() {
+        assert_eq!(
+            shifted().on_key(Key::Equal),
+            Some(InputAction::IterationsUpCoarse)
+        );
+        assert_eq!(
+            shifted().on_key(Key::Minus),
+            Some(InputAction::IterationsDownCoarse)
+        );
+    }
+
     #[test]
     fn r_resets() {
         assert_eq!(input().on_key(Key::R), Some(InputAction::Reset));
     }
 
+    #[test]
+    fn s_saves_overrides() {
+        assert_eq!(input().on_key(Key::S), Some(InputAction::SaveOverrides));
+    }
+
+    #[test]
+    fn e_exports_bundle() {
+        assert_eq!(input().on_key(Key::E), Some(InputAction::ExportBundle));
+    }
+
+    #[test]
+    fn t_regenerates_thumbnails() {
+        assert_eq!(
+            input().on_key(Key::T),
+            Some(InputAction::RegenerateThumbnails)
+        );
+    }
+
+    #[test]
+    fn w_toggles_shader_editor() {
+        assert_eq!(
+            input().on_key(Key::W),
+            Some(InputAction::ToggleShaderEditor)
+        );
+    }
+
+    #[test]
+    fn c_toggles_control_window() {
+        assert_eq!(
+            input().on_key(Key::C),
+            Some(InputAction::ToggleControlWindow)
+        );
+    }
+
+    #[test]
+    fn b_taps_tempo() {
+        assert_eq!(input().on_key(Key::B), Some(InputAction::TapTempo));
+    }
+
+    #[test]
+    fn l_retriggers_lfos() {
+        assert_eq!(input().on_key(Key::L), Some(InputAction::RetriggerLfos));
+        assert_eq!(input().on_key(Key::X), Some(InputAction::Flash));
+    }
+
+    #[test]
+    fn n_resets_the_simulation() {
+        assert_eq!(input().on_key(Key::N), Some(InputAction::ResetSimulation));
+    }
+
+    #[test]
+    fn g_fires_cues_and_shift_g_runs_them() {
+        assert_eq!(input().on_key(Key::G), Some(InputAction::CueGo));
+        assert_eq!(shifted().on_key(Key::G), Some(InputAction::CueToggleRun));
+    }
+
+    #[test]
+    fn d_takes_deck_b() {
+        assert_eq!(input().on_key(Key::D), Some(InputAction::TakeDeckB));
+    }
+
+    #[test]
+    fn f9_toggles_recording() {
+        assert_eq!(input().on_key(Key::F9), Some(InputAction::ToggleRecording));
+    }
+
+    #[test]
+    fn f_toggles_fullscreen() {
+        assert_eq!(input().on_key(Key::F), Some(InputAction::ToggleFullscreen));
+    }
+
     #[test]
     fn q_quits() {
         assert_eq!(input().on_key(Key::Q), Some(InputAction::Quit));
@@ -206,7 +655,7 @@ mod tests {
         assert_eq!(input().on_key(Key::Escape), Some(InputAction::Quit));
     }
 
-    // --- All five digit keys are distinct ------------------------------------
+    // --- All ten digit keys are distinct -------------------------------------
 
     #[test]
     fn all_digit_keys_map_to_different_presets() {
@@ -216,6 +665,11 @@ mod tests {
             Key::Digit3,
             Key::Digit4,
             Key::Digit5,
+            Key::Digit6,
+            Key::Digit7,
+            Key::Digit8,
+            Key::Digit9,
+            Key::Digit0,
         ]
         .iter()
         .map(|&k| input().on_key(k))
@@ -252,6 +706,107 @@ mod tests {
         }
     }
 
+    // --- Box zoom -------------------------------------------------------------
+
+    const WINDOW: (f32, f32) = (800.0, 600.0);
+
+    #[test]
+    fn click_without_drag_is_click_zoom() {
+        let mut s = input();
+        s.begin_drag((200.0, 150.0), false);
+        s.update_drag((201.0, 151.0));
+        assert_eq!(
+            s.end_drag(false, WINDOW, false),
+            Some(InputAction::MouseZoom {
+                norm_x: 201.0 / 800.0,
+                norm_y: 151.0 / 600.0
+            })
+        );
+        assert!(s.drag().is_none());
+    }
+
+    #[test]
+    fn short_right_click_does_nothing() {
+        let mut s = input();
+        s.begin_drag((200.0, 150.0), true);
+        assert_eq!(s.end_drag(true, WINDOW, false), None);
+    }
+
+    #[test]
+    fn drag_produces_box_zoom() {
+        let mut s = input();
+        s.begin_drag((0.0, 0.0), true);
+        s.update_drag((400.0, 300.0));
+        assert_eq!(
+            s.end_drag(true, WINDOW, false),
+            Some(InputAction::BoxZoom {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 0.5,
+                y1: 0.5,
+                zoom_out: true
+            })
+        );
+    }
+
+    #[test]
+    fn release_of_other_button_keeps_drag() {
+        let mut s = input();
+        s.begin_drag((0.0, 0.0), false);
+        assert_eq!(s.end_drag(true, WINDOW, false), None);
+        assert!(s.drag().is_some());
+    }
+
+    #[test]
+    fn aspect_lock_grows_short_side() {
+        let drag = BoxDrag {
+            start: (100.0, 100.0),
+            end: (500.0, 120.0),
+            zoom_out: false,
+        };
+        assert_eq!(drag.end_corner(true, 2.0), (500.0, 300.0));
+        assert_eq!(drag.end_corner(false, 2.0), (500.0, 120.0));
+        // Dragging up-left keeps the direction.
+        let drag = BoxDrag {
+            end: (90.0, -100.0),
+            ..drag
+        };
+        assert_eq!(drag.end_corner(true, 2.0), (-300.0, -100.0));
+    }
+
+    #[test]
+    fn box_zoom_in_fits_box() {
+        // Centred box covering half the window → zoom doubles, no pan.
+        let (cx, cy, zoom) = apply_box_zoom(-0.5, 0.0, 1.0, (0.25, 0.25), (0.75, 0.75), 1.5, false);
+        assert!((cx + 0.5).abs() < 1e-5 && cy.abs() < 1e-5);
+        assert!((zoom - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn box_zoom_out_inverts_zoom_in() {
+        let start = (0.3, -0.2, 5.0);
+        let (a, b) = ((0.1, 0.2), (0.4, 0.5));
+        let (cx, cy, z) = apply_box_zoom(start.0, start.1, start.2, a, b, 1.5, false);
+        let (cx, cy, z) = apply_box_zoom(cx, cy, z, a, b, 1.5, true);
+        assert!((cx - start.0).abs() < 1e-5, "cx={cx}");
+        assert!((cy - start.1).abs() < 1e-5, "cy={cy}");
+        assert!((z - start.2).abs() < 1e-4, "zoom={z}");
+    }
+
+    #[test]
+    fn box_zoom_centres_on_box() {
+        // A box in the right half recentres by a quarter of the view width.
+        let (cx, cy, _) = apply_box_zoom(0.0, 0.0, 2.0, (0.5, 0.25), (1.0, 0.75), 1.5, false);
+        assert!((cx - 0.25 * VIEW_SPAN / 2.0 * 1.5).abs() < 1e-6, "cx={cx}");
+        assert!(cy.abs() < 1e-6);
+    }
+
+    #[test]
+    fn box_zoom_uses_longer_side() {
+        let (_, _, zoom) = apply_box_zoom(0.0, 0.0, 1.0, (0.0, 0.0), (0.5, 0.25), 1.0, false);
+        assert!((zoom - 2.0).abs() < 1e-5);
+    }
+
     // --- Zoom math ------------------------------------------------------------
 
     #[test]
@@ -295,27 +850,79 @@ mod tests {
         assert!((cy2 - cy1 / 2.0).abs() < 1e-5, "cy1={cy1} cy2={cy2}");
     }
 
+    proptest! {
+        #[test]
+        fn click_zoom_always_deepens_and_pans_less(
+            zoom in 1e-3f32..1e6,
+            x in 0.0f32..=1.0,
+            y in 0.0f32..=1.0,
+            aspect in 0.25f32..4.0,
+        ) {
+            let (cx1, cy1, z1) = apply_zoom(0.0, 0.0, zoom, x, y, aspect);
+            let (cx2, cy2, z2) = apply_zoom(0.0, 0.0, z1, x, y, aspect);
+            prop_assert!(zoom < z1 && z1 < z2);
+            prop_assert!(cx2.abs() <= cx1.abs() && cy2.abs() <= cy1.abs());
+        }
+
+        #[test]
+        fn box_zoom_in_deepens_and_out_undoes_it(
+            zoom in 1e-3f32..1e6,
+            (x0, x1) in (0.0f32..=1.0, 0.0f32..=1.0),
+            (y0, y1) in (0.0f32..=1.0, 0.0f32..=1.0),
+            aspect in 0.25f32..4.0,
+        ) {
+            let (cx, cy, z_in) =
+                apply_box_zoom(0.3, -0.2, zoom, (x0, y0), (x1, y1), aspect, false);
+            prop_assert!(z_in >= zoom, "zoom in went {zoom} → {z_in}");
+            let (_, _, z_out) = apply_box_zoom(0.3, -0.2, zoom, (x0, y0), (x1, y1), aspect, true);
+            prop_assert!(z_out <= zoom, "zoom out went {zoom} → {z_out}");
+            let (bx, by, back) = apply_box_zoom(cx, cy, z_in, (x0, y0), (x1, y1), aspect, true);
+            prop_assert!((back - zoom).abs() <= zoom * 1e-4, "{zoom} → {z_in} → {back}");
+            let tolerance = 1e-3 * (1.0 + VIEW_SPAN / zoom);
+            prop_assert!((bx - 0.3).abs() <= tolerance && (by + 0.2).abs() <= tolerance);
+        }
+    }
+
     // --- Iteration clamping ---------------------------------------------------
 
     #[test]
     fn clamp_iterations_enforces_minimum() {
-        assert_eq!(clamp_iterations(0), 20);
-        assert_eq!(clamp_iterations(1), 20);
-        assert_eq!(clamp_iterations(19), 20);
-        assert_eq!(clamp_iterations(20), 20);
+        assert_eq!(clamp_iterations(0, 500), 20);
+        assert_eq!(clamp_iterations(1, 500), 20);
+        assert_eq!(clamp_iterations(19, 500), 20);
+        assert_eq!(clamp_iterations(20, 500), 20);
     }
 
     #[test]
-    fn clamp_iterations_enforces_maximum() {
-        assert_eq!(clamp_iterations(500), 500);
-        assert_eq!(clamp_iterations(501), 500);
-        assert_eq!(clamp_iterations(9999), 500);
+    fn clamp_iterations_enforces_ceiling() {
+        assert_eq!(clamp_iterations(500, 500), 500);
+        assert_eq!(clamp_iterations(501, 500), 500);
+        assert_eq!(clamp_iterations(99_999, 100_000), 99_999);
+        // Ceilings themselves are bounded.
+        assert_eq!(clamp_iterations(u32::MAX, u32::MAX), MAX_ITERATIONS);
+        assert_eq!(clamp_iterations(100, 0), MIN_ITERATIONS);
     }
 
     #[test]
     fn clamp_iterations_passes_through_valid_values() {
-        assert_eq!(clamp_iterations(21), 21);
-        assert_eq!(clamp_iterations(100), 100);
-        assert_eq!(clamp_iterations(499), 499);
+        assert_eq!(clamp_iterations(21, 500), 21);
+        assert_eq!(clamp_iterations(100, 500), 100);
+        assert_eq!(clamp_iterations(499, 500), 499);
+        assert_eq!(clamp_iterations(5000, DEFAULT_MAX_ITERATIONS), 5000);
+    }
+
+    #[test]
+    fn auto_iterations_grow_logarithmically() {
+        assert_eq!(auto_iterations(100, 0.5, 5000), 100);
+        assert_eq!(auto_iterations(100, 1.0, 5000), 100);
+        assert_eq!(auto_iterations(100, 2.0, 5000), 140);
+        assert_eq!(auto_iterations(100, 1024.0, 5000), 500);
+    }
+
+    #[test]
+    fn auto_iterations_respect_cap_and_base() {
+        assert_eq!(auto_iterations(100, 1e6, 300), 300);
+        // The base wins over a lower cap.
+        assert_eq!(auto_iterations(400, 2.0, 300), 400);
     }
 }
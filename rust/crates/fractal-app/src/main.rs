@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::Arc;
 
 use winit::{
@@ -5,36 +6,122 @@ use winit::{
     event::{ElementState, KeyEvent, MouseButton, WindowEvent},
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
     keyboard::{KeyCode, PhysicalKey},
-    window::{Window, WindowId},
+    window::{Fullscreen, Window, WindowId},
 };
 
+mod alloc_audit;
+mod animation;
 mod app;
+mod autosave;
+mod cli;
+mod config;
+mod control_window;
+mod cues;
+mod cursor;
+mod fps;
+mod hud;
+mod i18n;
 mod input;
+mod midi;
+mod modulation;
+mod motion;
+mod overlay;
+mod pan;
+mod session;
+mod settings;
+mod shader_editor;
+mod state;
+mod stream;
+mod thumbnails;
+mod tile_window;
+mod toast;
+mod video;
+mod warmup;
+mod watchdog;
+mod zoom_video;
 
 use app::App;
+use cli::CliArgs;
+use fractal_core::{bundle::BUNDLE_EXTENSION, validate};
 use input::Key;
 
+// Tests always count, so the frame path can assert it allocates nothing.
+#[cfg(any(feature = "alloc-audit", test))]
+#[global_allocator]
+static ALLOC: alloc_audit::CountingAlloc = alloc_audit::CountingAlloc;
+
 // ---------------------------------------------------------------------------
 // Key mapping — winit PhysicalKey → input::Key
 // ---------------------------------------------------------------------------
 
 fn winit_to_key(code: KeyCode) -> Option<Key> {
     match code {
+        KeyCode::Digit0 => Some(Key::Digit0),
         KeyCode::Digit1 => Some(Key::Digit1),
         KeyCode::Digit2 => Some(Key::Digit2),
         KeyCode::Digit3 => Some(Key::Digit3),
         KeyCode::Digit4 => Some(Key::Digit4),
         KeyCode::Digit5 => Some(Key::Digit5),
+        KeyCode::Digit6 => Some(Key::Digit6),
+        KeyCode::Digit7 => Some(Key::Digit7),
+        KeyCode::Digit8 => Some(Key::Digit8),
+        KeyCode::Digit9 => Some(Key::Digit9),
         KeyCode::Space => Some(Key::Space),
+        KeyCode::KeyB => Some(Key::B),
+        KeyCode::KeyG => Some(Key::G),
+        KeyCode::KeyL => Some(Key::L),
+        KeyCode::KeyX => Some(Key::X),
+        KeyCode::KeyN => Some(Key::N),
+        KeyCode::KeyC => Some(Key::C),
+        KeyCode::KeyD => Some(Key::D),
         KeyCode::Equal => Some(Key::Equal),
         KeyCode::Minus => Some(Key::Minus),
+        KeyCode::KeyE => Some(Key::E),
+        KeyCode::KeyF => Some(Key::F),
         KeyCode::KeyR => Some(Key::R),
+        KeyCode::KeyS => Some(Key::S),
+        KeyCode::KeyT => Some(Key::T),
+        KeyCode::KeyW => Some(Key::W),
         KeyCode::KeyQ => Some(Key::Q),
         KeyCode::Escape => Some(Key::Escape),
+        KeyCode::F9 => Some(Key::F9),
         _ => None,
     }
 }
 
+/// Run the action bound to a key press; exits the loop on `Quit`.
+fn press_key(app: &mut App, event_loop: &ActiveEventLoop, code: KeyCode) {
+    if let Some(action) = winit_to_key(code).and_then(|key| app.on_key_pressed(key)) {
+        if app.handle_action(action) {
+            event_loop.exit();
+        }
+    }
+}
+
+/// Open a window for every tile of the app's tile layout, fullscreen on its
+/// monitor when the tile names one.
+fn open_tile_windows(app: &mut App, event_loop: &ActiveEventLoop) {
+    let Some(layout) = app.tile_layout().cloned() else {
+        return;
+    };
+    for (index, tile) in layout.tiles.iter().enumerate() {
+        let mut attrs = Window::default_attributes()
+            .with_title(app.tile_window_title(index))
+            .with_inner_size(winit::dpi::PhysicalSize::new(tile.width, tile.height));
+        if let Some(n) = tile.monitor {
+            let monitor = event_loop.available_monitors().nth(n);
+            if monitor.is_none() {
+                log::warn!("tile {}: no monitor {n}", index + 1);
+            }
+            attrs = attrs.with_fullscreen(Some(Fullscreen::Borderless(monitor)));
+        }
+        match event_loop.create_window(attrs) {
+            Ok(window) => app.open_tile_window(index, Arc::new(window)),
+            Err(e) => log::warn!("failed to create tile window {}: {e}", index + 1),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Handler — winit ApplicationHandler (Phase 10: input wired up)
 // ---------------------------------------------------------------------------
@@ -42,6 +129,8 @@ fn winit_to_key(code: KeyCode) -> Option<Key> {
 struct Handler {
     window: Option<Arc<Window>>,
     app: Option<App>,
+    /// Startup bundle / view code and session record / replay options.
+    cli: CliArgs,
 }
 
 impl ApplicationHandler for Handler {
@@ -60,7 +149,43 @@ impl ApplicationHandler for Handler {
 
         log::info!("Window created (800×600)");
 
-        let gpu_app = App::new(Arc::clone(&window));
+        let mut gpu_app = App::new(Arc::clone(&window));
+        if let Some(n) = self.cli.max_iterations {
+            gpu_app.override_max_iterations(n);
+        }
+        if let Some(arg) = &self.cli.startup {
+            let path = Path::new(arg);
+            if path.extension().is_some_and(|e| e == BUNDLE_EXTENSION) || path.is_file() {
+                gpu_app.import_bundle(path);
+            } else if let Err(e) = gpu_app.apply_view_code(arg) {
+                log::warn!("argument is neither a bundle nor a view code: {e}");
+            }
+        }
+        if self.cli.resume {
+            gpu_app.resume_session();
+        }
+        if let Some(path) = &self.cli.cues {
+            gpu_app.load_cues(path);
+        }
+        if let Some(path) = &self.cli.video {
+            gpu_app.open_video(path);
+        }
+        if let Some(path) = &self.cli.image {
+            gpu_app.open_image(path);
+        }
+        if let Some(path) = &self.cli.warp {
+            gpu_app.load_output_correction(path);
+        }
+        if let Some(path) = &self.cli.tiles {
+            gpu_app.load_tiles(path);
+            open_tile_windows(&mut gpu_app, event_loop);
+        }
+        if let Some(path) = &self.cli.replay {
+            gpu_app.start_replay(path, self.cli.replay_loop);
+        }
+        if let Some(path) = &self.cli.record {
+            gpu_app.start_recording(path.clone());
+        }
         self.window = Some(window);
         self.app = Some(gpu_app);
     }
@@ -68,9 +193,58 @@ impl ApplicationHandler for Handler {
     fn window_event(
         &mut self,
         event_loop: &ActiveEventLoop,
-        _window_id: WindowId,
+        window_id: WindowId,
         event: WindowEvent,
     ) {
+        // The detached control window only hosts egui; it shares the
+        // keyboard shortcuts but never zooms or pans the fractal.
+        if let Some(app) = &mut self.app {
+            if app.control_window_id() == Some(window_id) {
+                let egui_consumed = app.control_window_event(&event);
+                match event {
+                    WindowEvent::CloseRequested => app.close_control_window(),
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                physical_key: PhysicalKey::Code(code),
+                                state: ElementState::Pressed,
+                                ..
+                            },
+                        ..
+                    } if !egui_consumed => press_key(app, event_loop, code),
+                    WindowEvent::ModifiersChanged(modifiers) => {
+                        app.on_modifiers_changed(modifiers.state().shift_key());
+                    }
+                    _ => {}
+                }
+                return;
+            }
+
+            // Tile windows only show the canvas; keys still work there.
+            if app.is_tile_window(window_id) {
+                match event {
+                    WindowEvent::CloseRequested => app.close_tile_window(window_id),
+                    WindowEvent::Resized(size) => {
+                        app.resize_tile_window(window_id, size.width, size.height)
+                    }
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                physical_key: PhysicalKey::Code(code),
+                                state: ElementState::Pressed,
+                                ..
+                            },
+                        ..
+                    } => press_key(app, event_loop, code),
+                    WindowEvent::ModifiersChanged(modifiers) => {
+                        app.on_modifiers_changed(modifiers.state().shift_key());
+                    }
+                    _ => {}
+                }
+                return;
+            }
+        }
+
         // Feed every event to egui first; game input is skipped when egui
         // reports the event was consumed (e.g. a click inside the HUD panel).
         let egui_consumed = if let Some(app) = &mut self.app {
@@ -100,14 +274,17 @@ impl ApplicationHandler for Handler {
                     },
                 ..
             } if !egui_consumed => {
-                if let Some(key) = winit_to_key(code) {
-                    if let Some(app) = &mut self.app {
-                        if let Some(action) = app.on_key_pressed(key) {
-                            if app.handle_action(action) {
-                                event_loop.exit();
-                            }
-                        }
-                    }
+                if let Some(app) = &mut self.app {
+                    press_key(app, event_loop, code);
+                }
+            }
+
+            // ----------------------------------------------------------------
+            // Modifiers — Shift selects the second preset bank
+            // ----------------------------------------------------------------
+            WindowEvent::ModifiersChanged(modifiers) => {
+                if let Some(app) = &mut self.app {
+                    app.on_modifiers_changed(modifiers.state().shift_key());
                 }
             }
 
@@ -121,17 +298,59 @@ impl ApplicationHandler for Handler {
             }
 
             // ----------------------------------------------------------------
-            // Mouse — left click → zoom (skip if egui consumed)
+            // Mouse — left click/drag zooms in, right drag zooms out.  Presses
+            // over egui are skipped; releases always end a drag in progress.
             // ----------------------------------------------------------------
             WindowEvent::MouseInput {
-                button: MouseButton::Left,
-                state: ElementState::Pressed,
+                button: button @ (MouseButton::Left | MouseButton::Right),
+                state,
                 ..
-            } if !egui_consumed => {
+            } => {
+                if let Some(app) = &mut self.app {
+                    let zoom_out = button == MouseButton::Right;
+                    match state {
+                        ElementState::Pressed if !egui_consumed => app.on_mouse_pressed(zoom_out),
+                        ElementState::Pressed => {}
+                        ElementState::Released => {
+                            if let Some(action) = app.on_mouse_released(zoom_out) {
+                                if app.handle_action(action) {
+                                    event_loop.exit();
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // ----------------------------------------------------------------
+            // Mouse — middle drag pans with momentum
+            // ----------------------------------------------------------------
+            WindowEvent::MouseInput {
+                button: MouseButton::Middle,
+                state,
+                ..
+            } => {
                 if let Some(app) = &mut self.app {
-                    let action = app.on_mouse_left_click();
-                    if app.handle_action(action) {
-                        event_loop.exit();
+                    match state {
+                        ElementState::Pressed if !egui_consumed => app.on_pan_pressed(),
+                        ElementState::Pressed => {}
+                        ElementState::Released => app.on_pan_released(),
+                    }
+                }
+            }
+
+            // ----------------------------------------------------------------
+            // Drag-and-drop — import a `.fractalpatch` bundle or show a PNG
+            // ----------------------------------------------------------------
+            WindowEvent::DroppedFile(path) => {
+                if let Some(app) = &mut self.app {
+                    if path
+                        .extension()
+                        .is_some_and(|e| e.eq_ignore_ascii_case("png"))
+                    {
+                        app.open_image(&path);
+                    } else {
+                        app.import_bundle(&path);
                     }
                 }
             }
@@ -171,8 +390,34 @@ impl ApplicationHandler for Handler {
         }
     }
 
-    /// Drive continuous redraws (game-loop style).
-    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+    /// Clean exit: drop the crash-resume autosave.
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        if let Some(app) = &mut self.app {
+            app.shutdown();
+        }
+    }
+
+    /// Run due replay actions, open / close the control window on request,
+    /// then drive continuous redraws (game-loop style).
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if let Some(app) = &mut self.app {
+            if app.tick_replay() {
+                event_loop.exit();
+            }
+            match app.take_control_window_request() {
+                Some(true) => {
+                    let attrs = Window::default_attributes()
+                        .with_title(app.control_window_title())
+                        .with_inner_size(winit::dpi::LogicalSize::new(420u32, 720u32));
+                    match event_loop.create_window(attrs) {
+                        Ok(window) => app.open_control_window(Arc::new(window)),
+                        Err(e) => log::warn!("failed to create control window: {e}"),
+                    }
+                }
+                Some(false) => app.close_control_window(),
+                None => {}
+            }
+        }
         if let Some(window) = &self.window {
             window.request_redraw();
         }
@@ -183,15 +428,64 @@ impl ApplicationHandler for Handler {
 // Entry point
 // ---------------------------------------------------------------------------
 
+/// `--validate`: print the bundle's diagnostics, one per line, and return
+/// the exit status — 1 if it has errors or can't be read.
+fn validate_bundle(path: &Path) -> i32 {
+    let src = match std::fs::read_to_string(path) {
+        Ok(src) => src,
+        Err(e) => {
+            eprintln!("{}: {e}", path.display());
+            return 1;
+        }
+    };
+    let diagnostics = validate::validate(&src);
+    for d in &diagnostics {
+        println!("{}: {d}", path.display());
+    }
+    if diagnostics.is_empty() {
+        println!("{}: ok", path.display());
+    }
+    i32::from(validate::has_errors(&diagnostics))
+}
+
 fn main() {
     env_logger::init();
 
+    let cli = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{e}\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(path) = &cli.validate {
+        std::process::exit(validate_bundle(path));
+    }
+
+    if let Some(spec) = &cli.zoom_video {
+        if let Err(e) = zoom_video::run(spec, cli.startup.as_deref(), cli.max_iterations) {
+            eprintln!("zoom video: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(spec) = &cli.animation {
+        if let Err(e) = animation::run(spec, cli.startup.as_deref()) {
+            eprintln!("animation: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let event_loop = EventLoop::new().expect("failed to create event loop");
     event_loop.set_control_flow(ControlFlow::Poll);
 
     let mut handler = Handler {
         window: None,
         app: None,
+        cli,
     };
     event_loop.run_app(&mut handler).expect("event loop error");
 }
@@ -0,0 +1,421 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+
+use fractal_core::{versioning::Format, Params};
+use serde::{Deserialize, Serialize};
+
+/// The controller-map file.  No layout change has needed a migration yet.
+pub const FORMAT: Format = Format::new("controller map", &[]);
+
+// ---------------------------------------------------------------------------
+// ControlChange — the one MIDI message we act on
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlChange {
+    /// 0-based channel (shown to users as 1–16).
+    pub channel: u8,
+    pub controller: u8,
+    pub value: u8,
+}
+
+impl ControlChange {
+    /// Parse a raw MIDI message; anything but a control change is `None`.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        match *bytes {
+            [status, controller, value, ..]
+                if status & 0xF0 == 0xB0 && controller < 0x80 && value < 0x80 =>
+            {
+                Some(Self {
+                    channel: status & 0x0F,
+                    controller,
+                    value,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ControllerMap — CC → Params key bindings, persisted as JSON
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CcBinding {
+    pub channel: u8,
+    pub controller: u8,
+    /// `Params` field the controller drives.
+    pub key: String,
+    /// Values at CC 0 and CC 127; `min > max` inverts the knob.
+    pub min: f32,
+    pub max: f32,
+}
+
+impl CcBinding {
+    pub fn matches(&self, cc: ControlChange) -> bool {
+        self.channel == cc.channel && self.controller == cc.controller
+    }
+
+    /// The parameter value for a CC value.
+    pub fn value(&self, cc: u8) -> f32 {
+        self.min + (self.max - self.min) * f32::from(cc.min(127)) / 127.0
+    }
+
+    /// Short label such as `CC 74 · ch 1`.
+    pub fn label(&self) -> String {
+        format!("CC {} · ch {}", self.controller, self.channel + 1)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControllerMap {
+    pub bindings: Vec<CcBinding>,
+}
+
+impl ControllerMap {
+    pub fn binding_for(&self, key: &str) -> Option<&CcBinding> {
+        self.bindings.iter().find(|b| b.key == key)
+    }
+
+    /// Add `binding`, dropping any older binding of the same controller or
+    /// the same key: one knob drives one parameter.
+    pub fn bind(&mut self, binding: CcBinding) {
+        self.bindings.retain(|b| {
+            b.key != binding.key
+                && (b.channel, b.controller) != (binding.channel, binding.controller)
+        });
+        self.bindings.push(binding);
+    }
+
+    /// Remove the binding of `key`; returns whether there was one.
+    pub fn unbind(&mut self, key: &str) -> bool {
+        let before = self.bindings.len();
+        self.bindings.retain(|b| b.key != key);
+        self.bindings.len() != before
+    }
+
+    /// Load from `path`; a missing file yields an empty map.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        FORMAT.load(path)
+    }
+
+    /// Write to `path`, creating parent directories as needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        FORMAT.save(path, self)
+    }
+}
+
+/// Range a newly learned knob sweeps, guessed from the parameter's current
+/// value: unit-range values get 0–1, anything else 0 to twice its size
+/// (mirrored for negatives), so the current value sits mid-travel.
+pub fn learn_range(value: f32) -> (f32, f32) {
+    if (0.0..=1.0).contains(&value) {
+        (0.0, 1.0)
+    } else if value > 0.0 {
+        (0.0, 2.0 * value)
+    } else {
+        (2.0 * value, 0.0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MidiControl — learn workflow and the latest knob values
+// ---------------------------------------------------------------------------
+
+/// Pseudo-param a controller can be learned to: instead of moving a value,
+/// a press (CC value 64 or more) fires the patch's strobe flash.
+pub const FLASH_KEY: &str = "flash";
+
+/// Keys the HUD doesn't offer for learning: analysers and triggers write
+/// them, not the user.
+const UNLEARNABLE_PREFIXES: [&str; 3] = ["audio_", "onset_", "lfo_retrigger"];
+
+pub fn is_learnable(key: &str) -> bool {
+    !UNLEARNABLE_PREFIXES.iter().any(|p| key.starts_with(p))
+}
+
+#[derive(Debug, Default)]
+pub struct MidiControl {
+    pub map: ControllerMap,
+    /// Key waiting for the next control change to bind it.
+    learning: Option<String>,
+    /// Latest value per bound key.  Re-applied every frame after the
+    /// modulators run, so a knob holds its parameter where it was left.
+    values: BTreeMap<String, f32>,
+}
+
+impl MidiControl {
+    pub fn new(map: ControllerMap) -> Self {
+        Self {
+            map,
+            ..Self::default()
+        }
+    }
+
+    /// Arm learning for `key`; arming the key already waiting cancels.
+    pub fn toggle_learn(&mut self, key: &str) {
+        if self.learning.as_deref() == Some(key) {
+            self.learning = None;
+        } else {
+            self.learning = Some(key.to_string());
+        }
+    }
+
+    pub fn learning(&self) -> Option<&str> {
+        self.learning.as_deref()
+    }
+
+    pub fn unbind(&mut self, key: &str) -> bool {
+        self.values.remove(key);
+        self.map.unbind(key)
+    }
+
+    /// Act on one control change.  While learning, it binds the armed key
+    /// with a range guessed from the key's value in `params` and returns the
+    /// new binding (the map then needs saving); otherwise it moves whatever
+    /// parameter the controller is bound to.  See [`MidiControl::fires_flash`]
+    /// for [`FLASH_KEY`] bindings.
+    pub fn handle(&mut self, cc: ControlChange, params: &Params) -> Option<CcBinding> {
+        if let Some(key) = self.learning.take() {
+            let (min, max) = learn_range(params.get(&key));
+            let binding = CcBinding {
+                channel: cc.channel,
+                controller: cc.controller,
+                key,
+                min,
+                max,
+            };
+            self.map.bind(binding.clone());
+            self.values.retain(|k, _| self.map.binding_for(k).is_some());
+            return Some(binding);
+        }
+        if let Some(binding) = self.map.bindings.iter().find(|b| b.matches(cc)) {
+            self.values
+                .insert(binding.key.clone(), binding.value(cc.value));
+        }
+        None
+    }
+
+    /// Whether `cc` is a press on a controller bound to [`FLASH_KEY`].
+    pub fn fires_flash(&self, cc: ControlChange) -> bool {
+        self.learning.is_none()
+            && cc.value >= 64
+            && self
+                .map
+                .bindings
+                .iter()
+                .any(|b| b.key == FLASH_KEY && b.matches(cc))
+    }
+
+    /// Write the latest knob values into `params`.  Keys the current patch
+    /// doesn't have are skipped rather than created.
+    pub fn apply(&self, params: &mut Params) {
+        for (key, &value) in &self.values {
+            if let Some(field) = params.fields.get_mut(key) {
+                *field = value;
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MidiInput — raw messages from the first MIDI input port
+// ---------------------------------------------------------------------------
+
+/// An open MIDI input.  Messages arrive on midir's thread and queue up
+/// until the render loop drains them.
+#[cfg_attr(not(feature = "midi"), allow(dead_code))]
+pub struct MidiInput {
+    port_name: String,
+    rx: Receiver<Vec<u8>>,
+    #[cfg(feature = "midi")]
+    _connection: midir::MidiInputConnection<()>,
+}
+
+impl MidiInput {
+    /// Connect to the first available input port.
+    #[cfg(feature = "midi")]
+    pub fn open() -> Result<Self, String> {
+        let input = midir::MidiInput::new("fractal-explorer").map_err(|e| e.to_string())?;
+        let port = input
+            .ports()
+            .into_iter()
+            .next()
+            .ok_or_else(|| "no MIDI input ports".to_string())?;
+        let port_name = input.port_name(&port).map_err(|e| e.to_string())?;
+        let (tx, rx) = std::sync::mpsc::channel();
+        let connection = input
+            .connect(
+                &port,
+                "fractal-explorer-in",
+                move |_stamp, bytes, _| {
+                    let _ = tx.send(bytes.to_vec());
+                },
+                (),
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            port_name,
+            rx,
+            _connection: connection,
+        })
+    }
+
+    #[cfg(not(feature = "midi"))]
+    pub fn open() -> Result<Self, String> {
+        Err("built without the `midi` feature".to_string())
+    }
+
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+
+    /// Messages received since the last call.
+    pub fn drain(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.rx.try_iter()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cc(controller: u8, value: u8) -> ControlChange {
+        ControlChange {
+            channel: 0,
+            controller,
+            value,
+        }
+    }
+
+    fn params_with(key: &str, value: f32) -> Params {
+        let mut p = Params::default();
+        p.set(key, value);
+        p
+    }
+
+    #[test]
+    fn parses_only_control_changes() {
+        assert_eq!(
+            ControlChange::parse(&[0xB3, 74, 100]),
+            Some(ControlChange {
+                channel: 3,
+                controller: 74,
+                value: 100
+            })
+        );
+        assert_eq!(ControlChange::parse(&[0x90, 60, 100]), None); // note on
+        assert_eq!(ControlChange::parse(&[0xB0, 74]), None);
+        assert_eq!(ControlChange::parse(&[0xB0, 0x80, 0]), None);
+    }
+
+    #[test]
+    fn learn_range_centres_the_current_value() {
+        assert_eq!(learn_range(0.3), (0.0, 1.0));
+        assert_eq!(learn_range(12.0), (0.0, 24.0));
+        assert_eq!(learn_range(-0.5), (-1.0, 0.0));
+    }
+
+    #[test]
+    fn learn_binds_the_next_knob() {
+        let mut p = params_with("ripple_amplitude", 5.0);
+        let mut midi = MidiControl::default();
+        midi.toggle_learn("ripple_amplitude");
+        assert_eq!(midi.learning(), Some("ripple_amplitude"));
+
+        let bound = midi.handle(cc(21, 64), &p).unwrap();
+        assert_eq!((bound.controller, bound.min, bound.max), (21, 0.0, 10.0));
+        assert_eq!(midi.learning(), None);
+
+        // The learning message itself doesn't move the parameter; later ones do.
+        midi.apply(&mut p);
+        assert_eq!(p.get("ripple_amplitude"), 5.0);
+        assert_eq!(midi.handle(cc(21, 127), &p), None);
+        midi.apply(&mut p);
+        assert_eq!(p.get("ripple_amplitude"), 10.0);
+    }
+
+    #[test]
+    fn toggling_the_armed_key_cancels() {
+        let mut midi = MidiControl::default();
+        midi.toggle_learn("hue_shift");
+        midi.toggle_learn("hue_shift");
+        assert_eq!(midi.learning(), None);
+    }
+
+    #[test]
+    fn one_knob_drives_one_parameter() {
+        let p = params_with("a", 0.5);
+        let mut midi = MidiControl::default();
+        midi.toggle_learn("a");
+        midi.handle(cc(1, 0), &p);
+        midi.toggle_learn("b");
+        midi.handle(cc(1, 0), &p);
+        assert_eq!(midi.map.bindings.len(), 1);
+        assert_eq!(midi.map.bindings[0].key, "b");
+    }
+
+    #[test]
+    fn apply_skips_keys_the_patch_lacks() {
+        let mut midi = MidiControl::new(ControllerMap {
+            bindings: vec![CcBinding {
+                channel: 0,
+                controller: 7,
+                key: "echo_decay".into(),
+                min: 0.0,
+                max: 1.0,
+            }],
+        });
+        midi.handle(cc(7, 127), &Params::default());
+        let mut p = Params::default();
+        midi.apply(&mut p);
+        assert!(!p.fields.contains_key("echo_decay"));
+    }
+
+    #[test]
+    fn flash_binding_fires_on_press() {
+        let mut midi = MidiControl::default();
+        midi.toggle_learn(FLASH_KEY);
+        assert!(
+            !midi.fires_flash(cc(40, 127)),
+            "the learning press only binds"
+        );
+        midi.handle(cc(40, 127), &Params::default());
+        assert!(midi.fires_flash(cc(40, 127)));
+        assert!(!midi.fires_flash(cc(40, 0)), "release");
+        assert!(!midi.fires_flash(cc(41, 127)), "other controller");
+    }
+
+    #[test]
+    fn audio_inputs_are_not_learnable() {
+        assert!(!is_learnable("audio_bass"));
+        assert!(!is_learnable("onset_time"));
+        assert!(is_learnable("hue_shift"));
+    }
+
+    #[test]
+    fn map_save_then_load() {
+        let path = std::env::temp_dir()
+            .join(format!("fractal-midi-{}", std::process::id()))
+            .join("controller_map.json");
+        let mut map = ControllerMap::default();
+        map.bind(CcBinding {
+            channel: 9,
+            controller: 74,
+            key: "zoom_speed".into(),
+            min: 1.0,
+            max: 0.0,
+        });
+        map.save(&path).unwrap();
+        assert_eq!(ControllerMap::load(&path).unwrap(), map);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,329 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwapOption;
+use fractal_core::{patch::Patch, Modulator, Params};
+
+// ---------------------------------------------------------------------------
+// ModulationThread — modulators evaluated at a fixed rate off the render loop
+// ---------------------------------------------------------------------------
+//
+// When the patch's modulators change, the render thread opens a `Session`
+// with a copy of the params and the time they were taken; from then on the
+// worker keeps its own copy and its own clock, running the modulators
+// `TICK_HZ` times a second.  Each frame the render thread only stores the
+// few values the modulators read (their `inputs` and the mouse) and loads
+// what they last wrote, all through per-key atomics, so a GPU hitch neither
+// stalls modulation nor makes it jump by the hitch's length, an expensive
+// modulator costs the render thread nothing, and neither side waits on or
+// allocates for the other.
+
+/// Modulator evaluations per second on the worker.
+pub const TICK_HZ: f32 = 240.0;
+
+/// An `f32` shared between the threads; NaN stands for an unset key.
+struct Slot(AtomicU32);
+
+impl Slot {
+    fn unset() -> Self {
+        Self(AtomicU32::new(f32::NAN.to_bits()))
+    }
+
+    fn store(&self, value: Option<f32>) {
+        let value = value.unwrap_or(f32::NAN);
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    fn load(&self) -> Option<f32> {
+        Some(f32::from_bits(self.0.load(Ordering::Relaxed))).filter(|v| !v.is_nan())
+    }
+}
+
+/// One patch's modulators and the values passed each way while it runs.
+struct Session {
+    modulators: Arc<[Arc<dyn Modulator>]>,
+    /// Params when the session opened; the worker starts its copy here.
+    params: Params,
+    /// Wall-clock moment `params.time` was taken, for the worker's clock.
+    opened: Instant,
+    /// Keys the modulators read and the render thread may change, and
+    /// their latest values.
+    input_keys: Vec<String>,
+    inputs: Vec<Slot>,
+    mouse: [Slot; 2],
+    /// Keys the modulators write and their latest values.
+    target_keys: Vec<String>,
+    targets: Vec<Slot>,
+    /// Set once the worker has filled `targets`.
+    ready: AtomicBool,
+}
+
+impl Session {
+    fn open(patch: &Patch) -> Self {
+        let target_keys: Vec<String> = patch.modulators.iter().flat_map(|m| m.targets()).collect();
+        let mut input_keys: Vec<String> = patch
+            .modulators
+            .iter()
+            .flat_map(|m| m.inputs())
+            .filter(|k| !target_keys.contains(k))
+            .collect();
+        input_keys.sort();
+        input_keys.dedup();
+        Self {
+            modulators: patch.modulators.iter().cloned().collect(),
+            params: patch.params.clone(),
+            opened: Instant::now(),
+            inputs: input_keys.iter().map(|_| Slot::unset()).collect(),
+            input_keys,
+            mouse: [Slot::unset(), Slot::unset()],
+            targets: target_keys.iter().map(|_| Slot::unset()).collect(),
+            target_keys,
+            ready: AtomicBool::new(false),
+        }
+    }
+
+    fn runs(&self, patch: &Patch) -> bool {
+        self.modulators.len() == patch.modulators.len()
+            && self
+                .modulators
+                .iter()
+                .zip(&patch.modulators)
+                .all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+
+    /// Render thread: publish what the modulators read from `params`.
+    fn feed(&self, params: &Params) {
+        for (key, slot) in self.input_keys.iter().zip(&self.inputs) {
+            slot.store(params.fields.get(key).copied());
+        }
+        self.mouse[0].store(Some(params.mouse_x));
+        self.mouse[1].store(Some(params.mouse_y));
+    }
+
+    /// Worker: one evaluation of the modulators on the worker's `params`.
+    fn tick(&self, params: &mut Params) {
+        params.time = self.params.time + self.opened.elapsed().as_secs_f32();
+        params.mouse_x = self.mouse[0].load().unwrap_or(self.params.mouse_x);
+        params.mouse_y = self.mouse[1].load().unwrap_or(self.params.mouse_y);
+        for (key, slot) in self.input_keys.iter().zip(&self.inputs) {
+            match slot.load() {
+                Some(value) => params.set(key.as_str(), value),
+                None => {
+                    params.fields.remove(key);
+                }
+            }
+        }
+        for m in self.modulators.iter() {
+            m.modulate(params);
+        }
+        for (key, slot) in self.target_keys.iter().zip(&self.targets) {
+            slot.store(params.fields.get(key).copied());
+        }
+        self.ready.store(true, Ordering::Release);
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    session: ArcSwapOption<Session>,
+    stop: AtomicBool,
+}
+
+/// Runs one patch's modulators on a worker thread; stops when dropped.
+pub struct ModulationThread {
+    shared: Arc<Shared>,
+    worker: Option<JoinHandle<()>>,
+    session: Option<Arc<Session>>,
+}
+
+impl ModulationThread {
+    pub fn start() -> std::io::Result<Self> {
+        let shared = Arc::new(Shared::default());
+        let worker = {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name("modulation".into())
+                .spawn(move || run(&shared))?
+        };
+        Ok(Self {
+            shared,
+            worker: Some(worker),
+            session: None,
+        })
+    }
+
+    /// Stand-in for [`Patch::modulate`]: hand the worker this frame's
+    /// modulator inputs and copy its latest values for the modulators'
+    /// targets into the patch.  Until the worker has caught up with a new
+    /// patch the modulators run here instead, so a preset load never shows
+    /// a frame of unmodulated or stale values.
+    pub fn modulate(&mut self, patch: &mut Patch) {
+        let session = match &self.session {
+            Some(session) if session.runs(patch) => session,
+            _ => {
+                let session = Arc::new(Session::open(patch));
+                self.shared.session.store(Some(Arc::clone(&session)));
+                self.session.insert(session)
+            }
+        };
+        session.feed(&patch.params);
+
+        if session.ready.load(Ordering::Acquire) {
+            for (key, slot) in session.target_keys.iter().zip(&session.targets) {
+                if let Some(value) = slot.load() {
+                    patch.params.set(key.as_str(), value);
+                }
+            }
+        } else {
+            patch.modulate();
+        }
+    }
+}
+
+impl Drop for ModulationThread {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Evaluate the current session every tick until stopped.  After a stall
+/// the missed ticks are skipped rather than run back to back.
+fn run(shared: &Shared) {
+    let period = Duration::from_secs_f32(1.0 / TICK_HZ);
+    let mut next = Instant::now();
+    let mut current: Option<(Arc<Session>, Params)> = None;
+    while !shared.stop.load(Ordering::Relaxed) {
+        if let Some(session) = shared.session.load_full() {
+            if !current
+                .as_ref()
+                .is_some_and(|(s, _)| Arc::ptr_eq(s, &session))
+            {
+                current = Some((Arc::clone(&session), session.params.clone()));
+            }
+            if let Some((session, params)) = &mut current {
+                session.tick(params);
+            }
+        }
+        next += period;
+        let now = Instant::now();
+        if next > now {
+            thread::sleep(next - now);
+        } else {
+            next = now;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fractal_core::modulators::{Lfo, MouseModulator, Ramp, Waveform};
+    use fractal_core::presets::Preset;
+    use fractal_core::MandelbrotGen;
+
+    fn ramp_patch() -> Patch {
+        Patch::new(Box::new(MandelbrotGen), Params::default()).add_modulator(Box::new(Ramp {
+            target: "v",
+            from: 0.0,
+            to: 1.0,
+            duration: 1000.0,
+        }))
+    }
+
+    /// Tick like `AppState` does until the worker's result is applied.
+    fn tick_until_threaded(thread: &mut ModulationThread, patch: &mut Patch) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            patch.advance(0.0);
+            thread.modulate(patch);
+            let caught_up = thread
+                .session
+                .as_ref()
+                .is_some_and(|s| s.ready.load(Ordering::Acquire));
+            if caught_up {
+                return;
+            }
+            assert!(Instant::now() < deadline, "worker never produced a result");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn first_frame_of_a_patch_modulates_synchronously() {
+        let mut thread = ModulationThread::start().unwrap();
+        let mut patch = Preset::PsychedelicJulia.build();
+        patch.advance(0.5);
+        thread.modulate(&mut patch);
+        let mut reference = Preset::PsychedelicJulia.build();
+        reference.advance(0.5);
+        reference.modulate();
+        assert_eq!(
+            patch.params.get("hue_shift_amount"),
+            reference.params.get("hue_shift_amount")
+        );
+    }
+
+    #[test]
+    fn worker_keeps_time_moving_between_frames() {
+        let mut thread = ModulationThread::start().unwrap();
+        let mut patch = ramp_patch();
+        tick_until_threaded(&mut thread, &mut patch);
+        // The patch clock is frozen at 0, yet the worker's clock ran on
+        // from the hand-over.
+        thread::sleep(Duration::from_millis(20));
+        thread.modulate(&mut patch);
+        assert!(patch.params.get("v") > 0.0);
+    }
+
+    #[test]
+    fn worker_reads_inputs_the_render_thread_changes() {
+        let mut thread = ModulationThread::start().unwrap();
+        let mut patch = Patch::new(Box::new(MandelbrotGen), Params::default()).add_modulator(
+            Box::new(MouseModulator {
+                target_x: Some("v"),
+                target_y: None,
+            }),
+        );
+        tick_until_threaded(&mut thread, &mut patch);
+        patch.params.mouse_x = 1.0;
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            thread.modulate(&mut patch);
+            if patch.params.get("v") == 1.0 {
+                break;
+            }
+            assert!(Instant::now() < deadline, "mouse never reached the worker");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn new_patch_ignores_results_for_the_old_one() {
+        let mut thread = ModulationThread::start().unwrap();
+        let mut old = ramp_patch();
+        tick_until_threaded(&mut thread, &mut old);
+        let session = Arc::clone(thread.session.as_ref().unwrap());
+
+        let mut new =
+            Patch::new(Box::new(MandelbrotGen), Params::default()).add_modulator(Box::new(Lfo {
+                target: "v",
+                waveform: Waveform::Sine,
+                frequency: 0.0,
+                amplitude: 0.0,
+                offset: -3.0,
+                phase: 0.0,
+            }));
+        thread.modulate(&mut new);
+        assert!(!Arc::ptr_eq(thread.session.as_ref().unwrap(), &session));
+        assert_eq!(new.params.get("v"), -3.0);
+    }
+}
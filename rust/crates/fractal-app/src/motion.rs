@@ -0,0 +1,166 @@
+use fractal_core::EffectKind;
+
+// ---------------------------------------------------------------------------
+// ViewMotion — how fast the user is moving the view
+// ---------------------------------------------------------------------------
+
+/// Speed (view heights or zoom doublings per second) above which the view
+/// counts as moving and the effects drop to draft quality.
+const MOVING_SPEED: f32 = 0.05;
+
+/// Speed below which a moving view counts as settled again.  Lower than
+/// `MOVING_SPEED` so a slow drag doesn't flicker between the two.
+const SETTLED_SPEED: f32 = 0.02;
+
+/// How quickly the tracked speed follows the input, per second.  A single
+/// 2× click-zoom holds draft quality for about half a second.
+const SPEED_SMOOTHING: f32 = 12.0;
+
+/// Tracks the user's pans and zooms so the effect chain can run cheaper
+/// while the view is in motion and return to full quality once it settles.
+/// Modulators moving the view don't count: only input reported through
+/// [`moved`](Self::moved).
+#[derive(Debug, Default)]
+pub struct ViewMotion {
+    /// Movement reported since the last `tick`.
+    pending: f32,
+    /// Smoothed speed, view heights (pans) plus zoom doublings per second.
+    speed: f32,
+    moving: bool,
+}
+
+impl ViewMotion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The user moved the view by `amount`: view heights panned, or zoom
+    /// doublings in either direction.
+    pub fn moved(&mut self, amount: f32) {
+        if amount.is_finite() {
+            self.pending += amount.abs();
+        }
+    }
+
+    /// Advance one frame of `dt` seconds.
+    pub fn tick(&mut self, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        let rate = std::mem::take(&mut self.pending) / dt;
+        let k = 1.0 - (-SPEED_SMOOTHING * dt).exp();
+        self.speed += (rate - self.speed) * k;
+        let threshold = if self.moving {
+            SETTLED_SPEED
+        } else {
+            MOVING_SPEED
+        };
+        self.moving = self.speed > threshold;
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.moving
+    }
+}
+
+/// A cheaper version of `kind` for frames drawn while the view moves: echo
+/// keeps half its layers, spaced twice as far with squared decay so the
+/// trail spans the same distance.  Effects with a fixed cost come back
+/// unchanged; the blurs are made cheaper by running at half resolution.
+pub fn draft_kind(kind: &EffectKind) -> EffectKind {
+    match *kind {
+        EffectKind::Echo {
+            layers,
+            offset,
+            decay,
+        } if layers > 1 => EffectKind::Echo {
+            layers: layers.div_ceil(2),
+            offset: offset * 2.0,
+            decay: decay * decay,
+        },
+        _ => kind.clone(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn still_view_is_not_moving() {
+        let mut m = ViewMotion::new();
+        for _ in 0..60 {
+            m.tick(DT);
+        }
+        assert!(!m.is_moving());
+    }
+
+    #[test]
+    fn a_zoom_click_drafts_briefly_then_settles() {
+        let mut m = ViewMotion::new();
+        m.moved(1.0);
+        m.tick(DT);
+        assert!(m.is_moving());
+        let mut frames = 0;
+        while m.is_moving() {
+            m.tick(DT);
+            frames += 1;
+        }
+        assert!((10..60).contains(&frames), "settled after {frames} frames");
+    }
+
+    #[test]
+    fn a_steady_drag_stays_moving() {
+        let mut m = ViewMotion::new();
+        for _ in 0..120 {
+            // A tenth of a view height per second.
+            m.moved(0.1 * DT);
+            m.tick(DT);
+            assert!(m.is_moving() || m.speed < MOVING_SPEED);
+        }
+        assert!(m.is_moving());
+    }
+
+    #[test]
+    fn non_finite_movement_is_ignored() {
+        let mut m = ViewMotion::new();
+        m.moved(f32::NAN);
+        m.tick(DT);
+        assert!(!m.is_moving());
+    }
+
+    #[test]
+    fn draft_echo_halves_layers_over_the_same_span() {
+        let echo = EffectKind::Echo {
+            layers: 5,
+            offset: 2.0,
+            decay: 0.8,
+        };
+        let EffectKind::Echo {
+            layers,
+            offset,
+            decay,
+        } = draft_kind(&echo)
+        else {
+            panic!("draft of echo is echo");
+        };
+        assert_eq!(layers, 3);
+        assert_eq!(offset, 4.0);
+        assert!((decay - 0.64).abs() < 1e-6);
+        let single = EffectKind::Echo {
+            layers: 1,
+            offset: 2.0,
+            decay: 0.8,
+        };
+        assert!(matches!(
+            draft_kind(&single),
+            EffectKind::Echo { layers: 1, offset, .. } if offset == 2.0
+        ));
+    }
+}
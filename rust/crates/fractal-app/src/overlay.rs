@@ -0,0 +1,314 @@
+use std::io;
+use std::path::PathBuf;
+
+use ab_glyph::{point, Font, FontRef, Glyph, PxScale, ScaleFont};
+use fractal_gpu::overlay_pipeline::OverlayParams;
+use serde::{Deserialize, Serialize};
+
+use crate::video::{load_image, Frame};
+
+// ---------------------------------------------------------------------------
+// Overlay — event titles and watermarks drawn over the output
+// ---------------------------------------------------------------------------
+
+/// Pixel height text is rasterized at; the GPU scales it from there.
+pub const TEXT_PX: f32 = 128.0;
+
+/// egui's built-in proportional font, so no font file ships with the app.
+const TEXT_FONT: &str = "Ubuntu-Light";
+
+pub const MIN_OVERLAY_SCALE: f32 = 0.02;
+pub const MAX_OVERLAY_SCALE: f32 = 1.0;
+/// Largest beat swell, as a fraction of the overlay's size.
+pub const MAX_OVERLAY_PULSE: f32 = 1.0;
+
+/// Where the overlay sits and how it moves.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlayLayout {
+    /// Centre, 0–1 across and down the frame.
+    pub x: f32,
+    pub y: f32,
+    /// Height as a fraction of the frame height.
+    pub scale: f32,
+    pub opacity: f32,
+    /// How much the overlay swells on each beat of the tapped tempo; 0 = off.
+    pub pulse: f32,
+}
+
+impl Default for OverlayLayout {
+    fn default() -> Self {
+        Self {
+            x: 0.5,
+            y: 0.88,
+            scale: 0.08,
+            opacity: 0.85,
+            pulse: 0.0,
+        }
+    }
+}
+
+impl OverlayLayout {
+    /// Overlay pass params at `beat_phase` through the current beat (`None`
+    /// without a tempo): the size jumps by `pulse` on the beat and eases
+    /// back.
+    pub fn params(&self, beat_phase: Option<f32>) -> OverlayParams {
+        let swell = beat_phase.map_or(0.0, |phase| (1.0 - phase).powi(2));
+        OverlayParams::new(
+            [self.x, self.y],
+            self.scale * (1.0 + self.pulse * swell),
+            self.opacity,
+        )
+    }
+
+    /// Clamp values that may have been hand-edited out of range.
+    pub fn sanitized(self) -> Self {
+        let default = Self::default();
+        let clamp = |v: f32, lo: f32, hi: f32, fallback: f32| {
+            if v.is_finite() {
+                v.clamp(lo, hi)
+            } else {
+                fallback
+            }
+        };
+        Self {
+            x: clamp(self.x, 0.0, 1.0, default.x),
+            y: clamp(self.y, 0.0, 1.0, default.y),
+            scale: clamp(
+                self.scale,
+                MIN_OVERLAY_SCALE,
+                MAX_OVERLAY_SCALE,
+                default.scale,
+            ),
+            opacity: clamp(self.opacity, 0.0, 1.0, default.opacity),
+            pulse: clamp(self.pulse, 0.0, MAX_OVERLAY_PULSE, default.pulse),
+        }
+    }
+}
+
+/// What the overlay shows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OverlayContent {
+    Text(String),
+    /// A PNG, e.g. a sponsor logo with a transparent background.
+    Logo(PathBuf),
+}
+
+impl OverlayContent {
+    /// Rasterize into the RGBA image the overlay pass draws, no side longer
+    /// than `max_side` (the device's texture size limit).
+    pub fn render(&self, max_side: u32) -> io::Result<Frame> {
+        match self {
+            OverlayContent::Text(text) => Ok(render_text(text, TEXT_PX, max_side)),
+            OverlayContent::Logo(path) => Ok(load_image(path)?.fit(max_side)),
+        }
+    }
+}
+
+/// Persisted overlay configuration, edited in the HUD's *Overlay* panel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    pub enabled: bool,
+    pub text: String,
+    /// Shown instead of the text when set.
+    pub logo: Option<PathBuf>,
+    pub layout: OverlayLayout,
+}
+
+impl OverlaySettings {
+    /// What to draw, or `None` when the overlay is off or has nothing to
+    /// show.
+    pub fn content(&self) -> Option<OverlayContent> {
+        if !self.enabled {
+            return None;
+        }
+        match &self.logo {
+            Some(path) => Some(OverlayContent::Logo(path.clone())),
+            None if self.text.trim().is_empty() => None,
+            None => Some(OverlayContent::Text(self.text.clone())),
+        }
+    }
+}
+
+/// Rasterize `text` as white, centred lines `px` pixels high, with coverage
+/// in alpha.  Text that would come out wider or taller than `max_side` is
+/// set smaller so it fits; the GPU scales it back up on screen.
+pub fn render_text(text: &str, px: f32, max_side: u32) -> Frame {
+    let fonts = egui::FontDefinitions::default();
+    let font = FontRef::try_from_slice(&fonts.font_data[TEXT_FONT].font)
+        .expect("egui's built-in font parses");
+    let (mut lines, mut width, mut height) = layout_text(&font, text, px);
+    let mut px = px;
+    let longest = width.max(height);
+    if longest > max_side {
+        // Glyph extents scale with the size; the floor leaves room for
+        // rounding each line's width up.
+        px = (px * (max_side - 1) as f32 / longest as f32).max(1.0);
+        (lines, width, height) = layout_text(&font, text, px);
+        (width, height) = (width.min(max_side), height.min(max_side));
+    }
+    let font = font.as_scaled(PxScale::from(px));
+
+    let mut alpha = vec![0u8; (width * height) as usize];
+    for (glyphs, line_width) in lines {
+        let indent = ((width as f32 - line_width) / 2.0).floor();
+        for mut glyph in glyphs {
+            glyph.position.x += indent;
+            let Some(outline) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            outline.draw(|x, y, coverage| {
+                let (x, y) = (
+                    bounds.min.x as i32 + x as i32,
+                    bounds.min.y as i32 + y as i32,
+                );
+                if (0..width as i32).contains(&x) && (0..height as i32).contains(&y) {
+                    let a = &mut alpha[(y as u32 * width + x as u32) as usize];
+                    *a = (*a).max((coverage.clamp(0.0, 1.0) * 255.0).round() as u8);
+                }
+            });
+        }
+    }
+    Frame {
+        width,
+        height,
+        rgba: alpha.into_iter().flat_map(|a| [255, 255, 255, a]).collect(),
+    }
+}
+
+/// One laid-out line: its glyphs and width.
+type Line = (Vec<Glyph>, f32);
+
+/// Lay out each line of `text` from x = 0 at `px` pixels high: the glyphs
+/// and width of every line, and the image size that holds them all.
+fn layout_text(font: &FontRef, text: &str, px: f32) -> (Vec<Line>, u32, u32) {
+    let font = font.as_scaled(PxScale::from(px));
+    let line_height = font.height() + font.line_gap();
+    let lines: Vec<Line> = text
+        .lines()
+        .enumerate()
+        .map(|(row, line)| {
+            let baseline = row as f32 * line_height + font.ascent();
+            let mut caret = 0.0;
+            let mut prev = None;
+            let glyphs = line
+                .chars()
+                .map(|c| {
+                    let id = font.glyph_id(c);
+                    if let Some(prev) = prev {
+                        caret += font.kern(prev, id);
+                    }
+                    let glyph = id.with_scale_and_position(px, point(caret, baseline));
+                    caret += font.h_advance(id);
+                    prev = Some(id);
+                    glyph
+                })
+                .collect();
+            (glyphs, caret)
+        })
+        .collect();
+    let width = lines
+        .iter()
+        .map(|(_, w)| w.ceil() as u32)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let height = ((lines.len() as f32 * line_height).ceil() as u32).max(1);
+    (lines, width, height)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coverage(frame: &Frame) -> usize {
+        frame.rgba.chunks_exact(4).filter(|p| p[3] > 128).count()
+    }
+
+    #[test]
+    fn text_renders_white_with_coverage_in_alpha() {
+        let frame = render_text("Hello", 32.0, 8192);
+        assert!(frame.width > frame.height, "a word is wider than tall");
+        assert_eq!(frame.rgba.len(), (frame.width * frame.height * 4) as usize);
+        assert!(coverage(&frame) > 0);
+        assert!(frame.rgba.chunks_exact(4).all(|p| p[..3] == [255; 3]));
+    }
+
+    #[test]
+    fn each_line_adds_height() {
+        let one = render_text("Main stage", 32.0, 8192);
+        let two = render_text("Main stage\n22:00", 32.0, 8192);
+        assert_eq!(one.width, two.width, "the longer line sets the width");
+        assert!(two.height > one.height);
+    }
+
+    #[test]
+    fn long_lines_are_set_smaller_to_fit() {
+        let line = "The quick brown fox jumps over the lazy dog. ".repeat(8);
+        let full = render_text(&line, TEXT_PX, u32::MAX);
+        assert!(full.width > 8192, "{} px wide", full.width);
+        let fitted = render_text(&line, TEXT_PX, 8192);
+        assert!(
+            fitted.width <= 8192 && fitted.width > 8000,
+            "{} px wide",
+            fitted.width
+        );
+        assert!(fitted.height < full.height);
+        assert!(coverage(&fitted) > 0);
+    }
+
+    #[test]
+    fn logo_wins_over_text_and_disabled_shows_nothing() {
+        let mut s = OverlaySettings {
+            enabled: true,
+            text: "Welcome".into(),
+            ..Default::default()
+        };
+        assert_eq!(s.content(), Some(OverlayContent::Text("Welcome".into())));
+        s.logo = Some("logo.png".into());
+        assert_eq!(s.content(), Some(OverlayContent::Logo("logo.png".into())));
+        s.enabled = false;
+        assert_eq!(s.content(), None);
+        let blank = OverlaySettings {
+            enabled: true,
+            text: "  ".into(),
+            ..Default::default()
+        };
+        assert_eq!(blank.content(), None);
+    }
+
+    #[test]
+    fn pulse_swells_on_the_beat_and_eases_back() {
+        let layout = OverlayLayout {
+            scale: 0.1,
+            pulse: 0.5,
+            ..Default::default()
+        };
+        assert!((layout.params(Some(0.0)).scale - 0.15).abs() < 1e-6);
+        assert!((layout.params(Some(0.5)).scale - 0.1125).abs() < 1e-6);
+        assert_eq!(layout.params(None).scale, 0.1);
+    }
+
+    #[test]
+    fn sanitized_clamps_layout() {
+        let layout = OverlayLayout {
+            x: 2.0,
+            y: f32::NAN,
+            scale: 0.0,
+            opacity: -1.0,
+            pulse: 9.0,
+        }
+        .sanitized();
+        assert_eq!(layout.x, 1.0);
+        assert_eq!(layout.y, OverlayLayout::default().y);
+        assert_eq!(layout.scale, MIN_OVERLAY_SCALE);
+        assert_eq!(layout.opacity, 0.0);
+        assert_eq!(layout.pulse, MAX_OVERLAY_PULSE);
+    }
+}
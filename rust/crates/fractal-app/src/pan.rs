@@ -0,0 +1,193 @@
+use crate::input::VIEW_SPAN;
+
+// ---------------------------------------------------------------------------
+// Inertial panning — drag the view, let it glide to a stop on release
+// ---------------------------------------------------------------------------
+
+/// Default exponential decay rate of the glide velocity, per second.
+pub const DEFAULT_FRICTION: f32 = 4.0;
+
+/// Friction range offered in the settings panel.
+pub const MIN_FRICTION: f32 = 0.5;
+pub const MAX_FRICTION: f32 = 20.0;
+
+/// Glides slower than this (physical pixels / second) stop.
+const STOP_SPEED: f32 = 5.0;
+
+/// How quickly the tracked velocity follows the pointer, per second.  Higher
+/// values react faster; lower values smooth out jittery mice.
+const VELOCITY_SMOOTHING: f32 = 30.0;
+
+#[derive(Debug, Default)]
+pub struct PanState {
+    /// Pointer position while the pan button is held.
+    grab: Option<(f32, f32)>,
+    /// Pointer movement since the last `tick`.
+    pending: (f32, f32),
+    /// Smoothed pointer velocity, physical pixels / second.
+    velocity: (f32, f32),
+}
+
+impl PanState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pan button pressed at `pos`; grabbing stops any glide.
+    pub fn grab(&mut self, pos: (f32, f32)) {
+        self.grab = Some(pos);
+        self.pending = (0.0, 0.0);
+        self.velocity = (0.0, 0.0);
+    }
+
+    /// Pan button released; the view keeps the tracked velocity.
+    pub fn release(&mut self) {
+        self.grab = None;
+    }
+
+    pub fn is_grabbed(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    pub fn on_move(&mut self, pos: (f32, f32)) {
+        if let Some(last) = &mut self.grab {
+            self.pending.0 += pos.0 - last.0;
+            self.pending.1 += pos.1 - last.1;
+            *last = pos;
+        }
+    }
+
+    /// Advance one frame and return how far the view content should move,
+    /// in physical pixels.  `friction` is the glide decay rate per second.
+    pub fn tick(&mut self, dt: f32, friction: f32) -> (f32, f32) {
+        if dt <= 0.0 {
+            return (0.0, 0.0);
+        }
+        if self.grab.is_some() {
+            let delta = std::mem::take(&mut self.pending);
+            let k = 1.0 - (-VELOCITY_SMOOTHING * dt).exp();
+            self.velocity.0 += (delta.0 / dt - self.velocity.0) * k;
+            self.velocity.1 += (delta.1 / dt - self.velocity.1) * k;
+            return delta;
+        }
+        let delta = (self.velocity.0 * dt, self.velocity.1 * dt);
+        let decay = (-friction * dt).exp();
+        self.velocity.0 *= decay;
+        self.velocity.1 *= decay;
+        if self.velocity.0.hypot(self.velocity.1) < STOP_SPEED {
+            self.velocity = (0.0, 0.0);
+        }
+        delta
+    }
+}
+
+/// Convert a screen-space drag (physical pixels) into a view-centre offset.
+/// One window height spans `VIEW_SPAN / zoom` in the complex plane.
+pub fn pixels_to_plane(delta: (f32, f32), zoom: f32, height: f32) -> (f32, f32) {
+    let per_pixel = VIEW_SPAN / zoom / height;
+    (-delta.0 * per_pixel, -delta.1 * per_pixel)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    /// Drag at a steady `speed` px/s for half a second, then release.
+    fn fling(speed: f32) -> PanState {
+        let mut p = PanState::new();
+        let mut x = 0.0;
+        p.grab((x, 0.0));
+        for _ in 0..30 {
+            x += speed * DT;
+            p.on_move((x, 0.0));
+            p.tick(DT, DEFAULT_FRICTION);
+        }
+        p.release();
+        p
+    }
+
+    /// Frames until the glide stops, and the distance covered.
+    fn glide(mut p: PanState, friction: f32) -> (usize, f32) {
+        let mut dist = 0.0;
+        for frame in 0..10_000 {
+            if p.velocity == (0.0, 0.0) {
+                return (frame, dist);
+            }
+            dist += p.tick(DT, friction).0;
+        }
+        panic!("glide never stopped");
+    }
+
+    #[test]
+    fn drag_moves_with_pointer() {
+        let mut p = PanState::new();
+        p.grab((10.0, 10.0));
+        p.on_move((15.0, 7.0));
+        p.on_move((20.0, 4.0));
+        assert_eq!(p.tick(DT, DEFAULT_FRICTION), (10.0, -6.0));
+        assert_eq!(p.tick(DT, DEFAULT_FRICTION), (0.0, 0.0));
+    }
+
+    #[test]
+    fn moves_without_grab_are_ignored() {
+        let mut p = PanState::new();
+        p.on_move((50.0, 50.0));
+        assert_eq!(p.tick(DT, DEFAULT_FRICTION), (0.0, 0.0));
+        assert_eq!(p.velocity, (0.0, 0.0));
+    }
+
+    #[test]
+    fn velocity_tracks_pointer() {
+        let p = fling(600.0);
+        assert!((p.velocity.0 - 600.0).abs() < 10.0, "{:?}", p.velocity);
+    }
+
+    #[test]
+    fn glide_comes_to_rest() {
+        let (frames, dist) = glide(fling(600.0), DEFAULT_FRICTION);
+        assert!(frames > 10);
+        // Exponential decay travels about v / friction (plus half a frame).
+        let limit = 600.0 / DEFAULT_FRICTION + 600.0 * DT;
+        assert!(dist > 0.0 && dist < limit, "{dist}");
+    }
+
+    #[test]
+    fn higher_friction_stops_sooner() {
+        let (slow, _) = glide(fling(600.0), 2.0);
+        let (fast, _) = glide(fling(600.0), 10.0);
+        assert!(fast < slow);
+    }
+
+    #[test]
+    fn holding_still_before_release_does_not_glide() {
+        let mut p = fling(600.0);
+        p.grab((0.0, 0.0));
+        for _ in 0..30 {
+            p.tick(DT, DEFAULT_FRICTION);
+        }
+        p.release();
+        let (_, dist) = glide(p, DEFAULT_FRICTION);
+        assert!(dist < 1.0, "{dist}");
+    }
+
+    #[test]
+    fn grab_stops_glide() {
+        let mut p = fling(600.0);
+        p.grab((0.0, 0.0));
+        assert_eq!(p.tick(DT, DEFAULT_FRICTION), (0.0, 0.0));
+    }
+
+    #[test]
+    fn pixels_to_plane_scales_with_zoom() {
+        let (dx, dy) = pixels_to_plane((100.0, 50.0), 1.0, 400.0);
+        assert!((dx + 0.5).abs() < 1e-6 && (dy + 0.25).abs() < 1e-6);
+        let (dx, _) = pixels_to_plane((100.0, 0.0), 4.0, 400.0);
+        assert!((dx + 0.125).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,256 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::input::InputAction;
+
+// ---------------------------------------------------------------------------
+// Input sessions — timestamped InputActions in a JSON Lines file
+// ---------------------------------------------------------------------------
+
+/// Bumped when the file layout changes incompatibly.
+const SESSION_VERSION: u32 = 1;
+
+/// Shortest loop period, so a session whose actions all share one
+/// timestamp can't replay endlessly within a single frame.
+const MIN_LOOP_SECONDS: f64 = 1.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionHeader {
+    pub version: u32,
+    /// View code of the view the recording started from.
+    pub view: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEvent {
+    /// Seconds since the recording started.
+    pub t: f64,
+    pub action: InputAction,
+}
+
+/// A recorded session.  Line 1 of the file is the header and every further
+/// line one event; appending a line per action keeps a recording intact if
+/// the app dies mid-session, which is when a bug report needs it most.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub header: SessionHeader,
+    pub events: Vec<SessionEvent>,
+}
+
+impl Session {
+    /// Parse a session file; errors name the offending line.
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let mut lines = src
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let (_, first) = lines.next().ok_or("empty session file")?;
+        let header: SessionHeader =
+            serde_json::from_str(first).map_err(|e| format!("line 1: {e}"))?;
+        if header.version != SESSION_VERSION {
+            return Err(format!(
+                "unsupported session version {} (expected {SESSION_VERSION})",
+                header.version
+            ));
+        }
+        let events = lines
+            .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {e}", i + 1)))
+            .collect::<Result<Vec<SessionEvent>, _>>()?;
+        Ok(Self { header, events })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let src = std::fs::read_to_string(path)?;
+        Self::parse(&src).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Recorder — appends actions as they happen
+// ---------------------------------------------------------------------------
+
+pub struct Recorder {
+    out: BufWriter<File>,
+    path: PathBuf,
+    start: Instant,
+}
+
+impl Recorder {
+    /// Create `path` (and its parent directories) and write the header.
+    pub fn create(path: PathBuf, view: String) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut out = BufWriter::new(File::create(&path)?);
+        let header = SessionHeader {
+            version: SESSION_VERSION,
+            view,
+        };
+        write_line(&mut out, &header)?;
+        Ok(Self {
+            out,
+            path,
+            start: Instant::now(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `action`, stamped with the time since recording started.
+    pub fn record(&mut self, action: &InputAction) -> io::Result<()> {
+        let event = SessionEvent {
+            t: self.start.elapsed().as_secs_f64(),
+            action: action.clone(),
+        };
+        write_line(&mut self.out, &event)
+    }
+}
+
+/// Write one JSON line and flush, so the file is complete after each action.
+fn write_line(out: &mut impl Write, value: &impl Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value).map_err(io::Error::from)?;
+    out.write_all(b"\n")?;
+    out.flush()
+}
+
+// ---------------------------------------------------------------------------
+// Replay — hands recorded actions back out on their original schedule
+// ---------------------------------------------------------------------------
+
+pub struct Replay {
+    events: Vec<SessionEvent>,
+    next: usize,
+    /// Added to every timestamp; grows by one period per completed loop.
+    offset: f64,
+    looping: bool,
+}
+
+impl Replay {
+    /// With `looping` the session restarts when it ends and recorded `Quit`
+    /// actions are skipped, for unattended soak tests.
+    pub fn new(events: Vec<SessionEvent>, looping: bool) -> Self {
+        Self {
+            events,
+            next: 0,
+            offset: 0.0,
+            looping,
+        }
+    }
+
+    /// Every action due at `elapsed` seconds since the replay started.
+    pub fn due(&mut self, elapsed: f64) -> Vec<InputAction> {
+        let mut due = Vec::new();
+        loop {
+            match self.events.get(self.next) {
+                Some(event) if event.t + self.offset <= elapsed => {
+                    self.next += 1;
+                    if !(self.looping && event.action == InputAction::Quit) {
+                        due.push(event.action.clone());
+                    }
+                }
+                None if self.looping && !self.events.is_empty() => {
+                    self.offset += self.period();
+                    self.next = 0;
+                }
+                _ => return due,
+            }
+        }
+    }
+
+    /// A non-looping replay has handed out every action.
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.next >= self.events.len()
+    }
+
+    fn period(&self) -> f64 {
+        let last = self.events.last().map_or(0.0, |e| e.t);
+        last.max(MIN_LOOP_SECONDS)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fractal_core::presets::Preset;
+
+    fn event(t: f64, action: InputAction) -> SessionEvent {
+        SessionEvent { t, action }
+    }
+
+    fn sample() -> Vec<SessionEvent> {
+        vec![
+            event(0.5, InputAction::LoadPreset(Preset::ShipStorm)),
+            event(
+                1.0,
+                InputAction::MouseZoom {
+                    norm_x: 0.25,
+                    norm_y: 0.75,
+                },
+            ),
+            event(2.0, InputAction::Quit),
+        ]
+    }
+
+    #[test]
+    fn replay_follows_timestamps() {
+        let mut r = Replay::new(sample(), false);
+        assert!(r.due(0.4).is_empty());
+        assert_eq!(r.due(0.5), vec![InputAction::LoadPreset(Preset::ShipStorm)]);
+        assert_eq!(r.due(5.0).len(), 2);
+        assert!(r.is_finished());
+        assert!(r.due(10.0).is_empty());
+    }
+
+    #[test]
+    fn looping_replay_restarts_and_skips_quit() {
+        let mut r = Replay::new(sample(), true);
+        assert_eq!(r.due(2.0).len(), 2);
+        // Second pass starts one period (2 s) later.
+        assert_eq!(r.due(2.5), vec![InputAction::LoadPreset(Preset::ShipStorm)]);
+        assert!(!r.is_finished());
+    }
+
+    #[test]
+    fn looping_same_instant_session_terminates() {
+        let mut r = Replay::new(vec![event(0.0, InputAction::Reset)], true);
+        assert_eq!(r.due(2.5).len(), 3);
+    }
+
+    #[test]
+    fn parse_reports_bad_lines() {
+        let header = r#"{"version":1,"view":"abc"}"#;
+        let err = Session::parse(&format!("{header}\n{{\"t\":1}}\n")).unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+        let err = Session::parse(r#"{"version":9,"view":""}"#).unwrap_err();
+        assert!(err.contains("version 9"), "{err}");
+        assert!(Session::parse("\n").is_err());
+    }
+
+    #[test]
+    fn record_then_load() {
+        let path = std::env::temp_dir()
+            .join(format!("fractal-session-{}", std::process::id()))
+            .join("session.jsonl");
+        let mut rec = Recorder::create(path.clone(), "view-code".into()).unwrap();
+        for e in sample() {
+            rec.record(&e.action).unwrap();
+        }
+        let session = Session::load(&path).unwrap();
+        assert_eq!(session.header.view, "view-code");
+        let actions: Vec<_> = session.events.iter().map(|e| e.action.clone()).collect();
+        let expected: Vec<_> = sample().into_iter().map(|e| e.action).collect();
+        assert_eq!(actions, expected);
+        assert!(session.events.windows(2).all(|w| w[0].t <= w[1].t));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
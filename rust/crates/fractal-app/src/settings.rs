@@ -0,0 +1,714 @@
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use fractal_core::{
+    audio::Quantize, modulators::Waveform, projection::Projection, versioning::Format,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::cursor::{DEFAULT_HIDE_AFTER, MAX_HIDE_AFTER};
+use crate::i18n::{Language, Msg};
+use crate::input::{clamp_ceiling, DEFAULT_MAX_ITERATIONS, MAX_ITERATIONS, MIN_ITERATIONS};
+use crate::overlay::OverlaySettings;
+use crate::pan::{DEFAULT_FRICTION, MAX_FRICTION, MIN_FRICTION};
+use crate::state::{DEFAULT_FLASH_FRAMES, MAX_FLASH_FRAMES};
+use crate::stream::{DEFAULT_PORT, MIN_PORT};
+use crate::watchdog::{self, MAX_TIMEOUT, MIN_TIMEOUT};
+
+/// The settings file.  No layout change has needed a migration yet.
+pub const FORMAT: Format = Format::new("settings", &[]);
+
+/// Default ceiling for zoom-aware iteration scaling.
+pub const DEFAULT_AUTO_ITERATIONS_CAP: u32 = 2000;
+
+/// Smallest / largest UI scale offered in the settings panel.
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+
+// ---------------------------------------------------------------------------
+// HudTheme — colour scheme for every egui panel
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HudTheme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl HudTheme {
+    pub const ALL: [HudTheme; 3] = [HudTheme::Dark, HudTheme::Light, HudTheme::HighContrast];
+
+    /// Catalog key of the theme's display name.
+    pub fn label(self) -> Msg {
+        match self {
+            HudTheme::Dark => Msg::ThemeDark,
+            HudTheme::Light => Msg::ThemeLight,
+            HudTheme::HighContrast => Msg::ThemeHighContrast,
+        }
+    }
+
+    pub fn visuals(self) -> egui::Visuals {
+        match self {
+            HudTheme::Dark => egui::Visuals::dark(),
+            HudTheme::Light => egui::Visuals::light(),
+            HudTheme::HighContrast => {
+                let mut v = egui::Visuals::dark();
+                v.override_text_color = Some(egui::Color32::WHITE);
+                v.panel_fill = egui::Color32::BLACK;
+                v.window_fill = egui::Color32::BLACK;
+                v.window_stroke = egui::Stroke::new(2.0, egui::Color32::YELLOW);
+                v.selection.bg_fill = egui::Color32::from_rgb(255, 210, 0);
+                v.selection.stroke = egui::Stroke::new(2.0, egui::Color32::BLACK);
+                v.hyperlink_color = egui::Color32::from_rgb(0, 255, 255);
+                for w in [
+                    &mut v.widgets.noninteractive,
+                    &mut v.widgets.inactive,
+                    &mut v.widgets.hovered,
+                    &mut v.widgets.active,
+                ] {
+                    w.fg_stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+                    w.bg_stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+                }
+                v.widgets.inactive.bg_fill = egui::Color32::BLACK;
+                v.widgets.inactive.weak_bg_fill = egui::Color32::BLACK;
+                v.widgets.hovered.bg_stroke = egui::Stroke::new(2.0, egui::Color32::YELLOW);
+                v
+            }
+        }
+    }
+
+    /// Fill of the translucent HUD window drawn over the fractal.
+    pub fn hud_fill(self) -> egui::Color32 {
+        match self {
+            HudTheme::Dark => egui::Color32::from_rgba_unmultiplied(0, 0, 0, 200),
+            HudTheme::Light => egui::Color32::from_rgba_unmultiplied(245, 245, 245, 220),
+            HudTheme::HighContrast => egui::Color32::BLACK,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CrosshairStyle — pointer drawn over the fractal
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrosshairStyle {
+    #[default]
+    Cross,
+    LargeCross,
+    Dot,
+    /// No crosshair; the regular OS cursor is shown.
+    None,
+}
+
+impl CrosshairStyle {
+    pub const ALL: [CrosshairStyle; 4] = [
+        CrosshairStyle::Cross,
+        CrosshairStyle::LargeCross,
+        CrosshairStyle::Dot,
+        CrosshairStyle::None,
+    ];
+
+    /// Catalog key of the style's display name.
+    pub fn label(self) -> Msg {
+        match self {
+            CrosshairStyle::Cross => Msg::CrosshairCross,
+            CrosshairStyle::LargeCross => Msg::CrosshairLargeCross,
+            CrosshairStyle::Dot => Msg::CrosshairDot,
+            CrosshairStyle::None => Msg::CrosshairNone,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// FrameAspect — fixed output shape, independent of the window
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrameAspect {
+    /// Fill the window, whatever its shape.
+    #[default]
+    Window,
+    Widescreen,
+    Classic,
+    Square,
+    Portrait,
+}
+
+impl FrameAspect {
+    pub const ALL: [FrameAspect; 5] = [
+        FrameAspect::Window,
+        FrameAspect::Widescreen,
+        FrameAspect::Classic,
+        FrameAspect::Square,
+        FrameAspect::Portrait,
+    ];
+
+    /// Width / height of the frame, or `None` to follow the window.
+    pub fn ratio(self) -> Option<f32> {
+        match self {
+            FrameAspect::Window => None,
+            FrameAspect::Widescreen => Some(16.0 / 9.0),
+            FrameAspect::Classic => Some(4.0 / 3.0),
+            FrameAspect::Square => Some(1.0),
+            FrameAspect::Portrait => Some(9.0 / 16.0),
+        }
+    }
+
+    /// Catalog key of the aspect's display name.
+    pub fn label(self) -> Msg {
+        match self {
+            FrameAspect::Window => Msg::FrameAspectWindow,
+            FrameAspect::Widescreen => Msg::FrameAspectWidescreen,
+            FrameAspect::Classic => Msg::FrameAspectClassic,
+            FrameAspect::Square => Msg::FrameAspectSquare,
+            FrameAspect::Portrait => Msg::FrameAspectPortrait,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Antialias — supersampling of the escape-time generators
+// ---------------------------------------------------------------------------
+
+/// Jittered samples per pixel the escape-time generators average.  Costs
+/// that many times the generator pass, so it is off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Antialias {
+    #[default]
+    Off,
+    X2,
+    X4,
+    X8,
+}
+
+impl Antialias {
+    pub const ALL: [Antialias; 4] = [Antialias::Off, Antialias::X2, Antialias::X4, Antialias::X8];
+
+    /// Samples per pixel, for `Uniforms::samples`.
+    pub fn samples(self) -> u32 {
+        match self {
+            Antialias::Off => 1,
+            Antialias::X2 => 2,
+            Antialias::X4 => 4,
+            Antialias::X8 => 8,
+        }
+    }
+
+    /// Catalog key of the mode's display name.
+    pub fn label(self) -> Msg {
+        match self {
+            Antialias::Off => Msg::AntialiasOff,
+            Antialias::X2 => Msg::AntialiasX2,
+            Antialias::X4 => Msg::AntialiasX4,
+            Antialias::X8 => Msg::AntialiasX8,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// LfoRateUnit / LfoDepth — how the LFO panel shows speed and swing
+// ---------------------------------------------------------------------------
+
+/// Slowest / fastest LFO the panel's rate slider reaches.
+pub const MIN_LFO_HZ: f32 = 0.001;
+pub const MAX_LFO_HZ: f32 = 20.0;
+
+/// Unit of the LFO panel's rate slider.  The LFO itself always runs in Hz.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LfoRateUnit {
+    #[default]
+    Hertz,
+    /// Seconds per cycle.
+    Seconds,
+    /// Beats of the tapped tempo per cycle; Hz until a tempo is tapped.
+    Beats,
+}
+
+impl LfoRateUnit {
+    pub const ALL: [LfoRateUnit; 3] =
+        [LfoRateUnit::Hertz, LfoRateUnit::Seconds, LfoRateUnit::Beats];
+
+    /// Catalog key of the unit's display name.
+    pub fn label(self) -> Msg {
+        match self {
+            LfoRateUnit::Hertz => Msg::LfoRateHertz,
+            LfoRateUnit::Seconds => Msg::LfoRateSeconds,
+            LfoRateUnit::Beats => Msg::LfoRateBeats,
+        }
+    }
+
+    /// The unit actually shown at tempo `bpm`.
+    pub fn effective(self, bpm: Option<f32>) -> LfoRateUnit {
+        match (self, bpm) {
+            (LfoRateUnit::Beats, None) => LfoRateUnit::Hertz,
+            (unit, _) => unit,
+        }
+    }
+
+    /// `hz` expressed in this unit.  Call on [`effective`](Self::effective) units.
+    pub fn in_unit(self, hz: f32, bpm: Option<f32>) -> f32 {
+        match self {
+            LfoRateUnit::Hertz => hz,
+            LfoRateUnit::Seconds => 1.0 / hz,
+            LfoRateUnit::Beats => bpm.unwrap_or(60.0) / 60.0 / hz,
+        }
+    }
+
+    /// Hz of `value` in this unit; the inverse of [`in_unit`](Self::in_unit).
+    pub fn to_hz(self, value: f32, bpm: Option<f32>) -> f32 {
+        match self {
+            LfoRateUnit::Hertz => value,
+            LfoRateUnit::Seconds => 1.0 / value,
+            LfoRateUnit::Beats => bpm.unwrap_or(60.0) / 60.0 / value,
+        }
+    }
+
+    /// Slider range covering [`MIN_LFO_HZ`] to [`MAX_LFO_HZ`] in this unit.
+    pub fn range(self, bpm: Option<f32>) -> std::ops::RangeInclusive<f32> {
+        let (a, b) = (self.in_unit(MIN_LFO_HZ, bpm), self.in_unit(MAX_LFO_HZ, bpm));
+        a.min(b)..=a.max(b)
+    }
+}
+
+/// How the LFO panel edits an LFO's swing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LfoDepth {
+    /// Centre and distance either side, as the LFO stores them.
+    #[default]
+    AmplitudeOffset,
+    /// Lowest and highest value swept.
+    MinMax,
+}
+
+impl LfoDepth {
+    pub const ALL: [LfoDepth; 2] = [LfoDepth::AmplitudeOffset, LfoDepth::MinMax];
+
+    /// Catalog key of the mode's display name.
+    pub fn label(self) -> Msg {
+        match self {
+            LfoDepth::AmplitudeOffset => Msg::LfoDepthAmplitudeOffset,
+            LfoDepth::MinMax => Msg::LfoDepthMinMax,
+        }
+    }
+}
+
+/// Catalog key of a projection's display name.
+pub fn projection_label(projection: Projection) -> Msg {
+    match projection {
+        Projection::Flat => Msg::ProjectionFlat,
+        Projection::Dome => Msg::ProjectionDome,
+        Projection::ExpMap => Msg::ProjectionExpMap,
+    }
+}
+
+/// Catalog key of an LFO waveform's display name.
+pub fn waveform_label(waveform: &Waveform) -> Msg {
+    match waveform {
+        Waveform::Sine => Msg::WaveSine,
+        Waveform::Triangle => Msg::WaveTriangle,
+        Waveform::Square => Msg::WaveSquare,
+        Waveform::Saw => Msg::WaveSaw,
+        Waveform::SmoothRandom => Msg::WaveSmoothRandom,
+        Waveform::Exponential => Msg::WaveExponential,
+        Waveform::Table(_) => Msg::WaveTable,
+    }
+}
+
+/// Catalog key of a quantize mode's display name.
+pub fn quantize_label(quantize: Quantize) -> Msg {
+    match quantize {
+        Quantize::Off => Msg::QuantizeOff,
+        Quantize::Beat => Msg::QuantizeBeat,
+        Quantize::Bar => Msg::QuantizeBar,
+    }
+}
+
+/// Catalog key of an LFO retrigger mode's display name.
+pub fn retrigger_label(retrigger: Quantize) -> Msg {
+    match retrigger {
+        Quantize::Off => Msg::RetriggerOff,
+        Quantize::Beat => Msg::RetriggerBeat,
+        Quantize::Bar => Msg::RetriggerBar,
+    }
+}
+
+/// Catalog key of an automatic flash mode's display name.
+pub fn flash_every_label(every: Quantize) -> Msg {
+    match every {
+        Quantize::Off => Msg::FlashEveryOff,
+        Quantize::Beat => Msg::FlashEveryBeat,
+        Quantize::Bar => Msg::FlashEveryBar,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Settings — persisted user preferences for the app shell
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: HudTheme,
+    /// egui zoom factor, applied on top of (not instead of) the OS DPI scale.
+    pub ui_scale: f32,
+    pub language: Language,
+    /// Written as the author of exported bundles that have none.
+    pub author: String,
+    pub crosshair: CrosshairStyle,
+    /// Seconds of inactivity before the cursor hides in fullscreen; 0 = never.
+    pub cursor_hide_after: f32,
+    /// Keep the box-zoom selection at the window's aspect ratio.
+    pub lock_box_aspect: bool,
+    /// Decay rate (per second) of the glide after a pan drag is released.
+    pub pan_friction: f32,
+    /// Output projection; `Dome` renders a domemaster for planetariums.
+    pub projection: Projection,
+    /// Letterbox/pillarbox the output to a fixed aspect; only the framed
+    /// region is rendered.
+    pub frame_aspect: FrameAspect,
+    /// Hold preset switches and momentary triggers until the next beat or
+    /// bar of the tapped tempo.
+    pub quantize: Quantize,
+    /// Restart every LFO's cycle on each beat or bar of the tapped tempo;
+    /// `Off` lets them run free.
+    pub lfo_retrigger: Quantize,
+    /// Units the LFO panel shows rates and swings in.
+    pub lfo_rate_unit: LfoRateUnit,
+    pub lfo_depth: LfoDepth,
+    /// Frames a strobe flash holds the patch's flash targets for.
+    pub flash_frames: u32,
+    /// Also flash on each beat or bar of the tapped tempo.
+    pub flash_every: Quantize,
+    /// Normalise audio band energies to their recent peak, so audio routes
+    /// need no re-tuning between quiet and loud rooms.
+    pub audio_auto_gain: bool,
+    /// Iteration ceiling for +/- and the zoom schedule.
+    pub max_iterations: u32,
+    /// Raise iterations as the zoom deepens, up to `auto_iterations_cap`.
+    pub auto_iterations: bool,
+    pub auto_iterations_cap: u32,
+    /// Colour escape-time fractals by the smoothed escape count rather than
+    /// the integer one, which bands at low iteration counts.
+    pub smooth_iterations: bool,
+    /// Supersample the escape-time generators, against aliasing along the
+    /// set's boundary.
+    pub antialias: Antialias,
+    /// Run the blur effects at half resolution and upsample them; softer,
+    /// but much lighter on weak GPUs.
+    pub half_res_effects: bool,
+    /// Draw cheaper effects (fewer echo layers, half-resolution blurs)
+    /// while the view is being panned or zoomed.
+    pub effect_lod: bool,
+    /// Evaluate modulators on a fixed-rate worker thread instead of once per
+    /// frame, so GPU hitches don't stall or jolt them.
+    pub threaded_modulation: bool,
+    /// Seconds without a presented frame before the GPU state is rebuilt;
+    /// 0 turns the watchdog off.
+    pub watchdog_timeout: f32,
+    /// Serve an MJPEG preview of the output over HTTP.
+    pub stream_enabled: bool,
+    pub stream_port: u16,
+    /// Accept preview viewers from other machines, not just this one.
+    pub stream_lan: bool,
+    /// Text or logo drawn over the output.
+    pub overlay: OverlaySettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: HudTheme::Dark,
+            ui_scale: 1.0,
+            language: Language::English,
+            author: String::new(),
+            crosshair: CrosshairStyle::Cross,
+            cursor_hide_after: DEFAULT_HIDE_AFTER,
+            lock_box_aspect: true,
+            pan_friction: DEFAULT_FRICTION,
+            projection: Projection::Flat,
+            frame_aspect: FrameAspect::Window,
+            quantize: Quantize::Off,
+            lfo_retrigger: Quantize::Off,
+            lfo_rate_unit: LfoRateUnit::Hertz,
+            lfo_depth: LfoDepth::AmplitudeOffset,
+            flash_frames: DEFAULT_FLASH_FRAMES,
+            flash_every: Quantize::Off,
+            audio_auto_gain: false,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            auto_iterations: false,
+            auto_iterations_cap: DEFAULT_AUTO_ITERATIONS_CAP,
+            smooth_iterations: true,
+            antialias: Antialias::Off,
+            half_res_effects: false,
+            effect_lod: true,
+            threaded_modulation: false,
+            watchdog_timeout: watchdog::DEFAULT_TIMEOUT,
+            stream_enabled: false,
+            stream_port: DEFAULT_PORT,
+            stream_lan: false,
+            overlay: OverlaySettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Clamp values that may have been hand-edited out of range.
+    pub fn sanitized(mut self) -> Self {
+        self.ui_scale = if self.ui_scale.is_finite() {
+            self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+        } else {
+            1.0
+        };
+        self.cursor_hide_after = if self.cursor_hide_after.is_finite() {
+            self.cursor_hide_after.clamp(0.0, MAX_HIDE_AFTER)
+        } else {
+            DEFAULT_HIDE_AFTER
+        };
+        self.pan_friction = if self.pan_friction.is_finite() {
+            self.pan_friction.clamp(MIN_FRICTION, MAX_FRICTION)
+        } else {
+            DEFAULT_FRICTION
+        };
+        self.watchdog_timeout = if !self.watchdog_timeout.is_finite() {
+            watchdog::DEFAULT_TIMEOUT
+        } else if self.watchdog_timeout <= 0.0 {
+            0.0
+        } else {
+            self.watchdog_timeout.clamp(MIN_TIMEOUT, MAX_TIMEOUT)
+        };
+        self.flash_frames = self.flash_frames.clamp(1, MAX_FLASH_FRAMES);
+        self.max_iterations = clamp_ceiling(self.max_iterations);
+        self.auto_iterations_cap = self
+            .auto_iterations_cap
+            .clamp(MIN_ITERATIONS, MAX_ITERATIONS);
+        if self.stream_port < MIN_PORT {
+            self.stream_port = DEFAULT_PORT;
+        }
+        self.overlay.layout = self.overlay.layout.sanitized();
+        self
+    }
+
+    /// Cap for `AppState::auto_iterations`, or `None` when scaling is off.
+    pub fn auto_iterations_cap(&self) -> Option<u32> {
+        self.auto_iterations.then_some(self.auto_iterations_cap)
+    }
+
+    /// How long a frame may hang before the watchdog rebuilds the GPU
+    /// state, or `None` when it is off.
+    pub fn watchdog_timeout(&self) -> Option<Duration> {
+        (self.watchdog_timeout > 0.0)
+            .then(|| Duration::from_secs_f32(self.watchdog_timeout.max(MIN_TIMEOUT)))
+    }
+
+    /// Push theme and scale into an egui context.
+    pub fn apply(&self, ctx: &egui::Context) {
+        ctx.set_visuals(self.theme.visuals());
+        ctx.set_zoom_factor(self.ui_scale);
+    }
+
+    /// Load from `path`; a missing file yields defaults.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        FORMAT.load(path).map(Self::sanitized)
+    }
+
+    /// Write to `path`, creating parent directories as needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        FORMAT.save(path, self)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overlay::OverlayLayout;
+    use std::path::PathBuf;
+
+    #[test]
+    fn defaults_are_dark_at_unit_scale() {
+        let s = Settings::default();
+        assert_eq!(s.theme, HudTheme::Dark);
+        assert_eq!(s.ui_scale, 1.0);
+        assert_eq!(s.language, Language::English);
+    }
+
+    #[test]
+    fn sanitized_clamps_scale() {
+        let s = Settings {
+            ui_scale: 10.0,
+            ..Settings::default()
+        };
+        assert_eq!(s.sanitized().ui_scale, MAX_UI_SCALE);
+        let s = Settings {
+            ui_scale: f32::NAN,
+            ..Settings::default()
+        };
+        assert_eq!(s.sanitized().ui_scale, 1.0);
+    }
+
+    #[test]
+    fn sanitized_clamps_watchdog_timeout() {
+        let timeout = |t: f32| {
+            Settings {
+                watchdog_timeout: t,
+                ..Settings::default()
+            }
+            .sanitized()
+            .watchdog_timeout
+        };
+        assert_eq!(timeout(-1.0), 0.0);
+        assert_eq!(timeout(0.01), MIN_TIMEOUT);
+        assert_eq!(timeout(1e9), MAX_TIMEOUT);
+        assert_eq!(timeout(f32::NAN), watchdog::DEFAULT_TIMEOUT);
+        let off = Settings {
+            watchdog_timeout: 0.0,
+            ..Settings::default()
+        };
+        assert_eq!(off.watchdog_timeout(), None);
+        assert!(Settings::default().watchdog_timeout().is_some());
+    }
+
+    #[test]
+    fn sanitized_clamps_hide_timeout() {
+        let s = Settings {
+            cursor_hide_after: -1.0,
+            ..Settings::default()
+        };
+        assert_eq!(s.sanitized().cursor_hide_after, 0.0);
+        let s = Settings {
+            cursor_hide_after: 1e9,
+            ..Settings::default()
+        };
+        assert_eq!(s.sanitized().cursor_hide_after, MAX_HIDE_AFTER);
+    }
+
+    #[test]
+    fn sanitized_replaces_privileged_port() {
+        let s = Settings {
+            stream_port: 80,
+            ..Settings::default()
+        };
+        assert_eq!(s.sanitized().stream_port, DEFAULT_PORT);
+    }
+
+    #[test]
+    fn sanitized_clamps_auto_iteration_cap() {
+        let s = Settings {
+            auto_iterations_cap: u32::MAX,
+            ..Settings::default()
+        };
+        assert_eq!(s.sanitized().auto_iterations_cap, MAX_ITERATIONS);
+        let s = Settings {
+            max_iterations: 0,
+            ..Settings::default()
+        };
+        assert_eq!(s.sanitized().max_iterations, MIN_ITERATIONS);
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let s: Settings = serde_json::from_str(r#"{ "theme": "Light" }"#).unwrap();
+        assert_eq!(s.theme, HudTheme::Light);
+        assert_eq!(s.ui_scale, 1.0);
+    }
+
+    #[test]
+    fn save_then_load() {
+        let path = std::env::temp_dir()
+            .join(format!("fractal-settings-{}", std::process::id()))
+            .join("settings.json");
+        let s = Settings {
+            theme: HudTheme::HighContrast,
+            ui_scale: 1.75,
+            language: Language::German,
+            author: "cmh".into(),
+            crosshair: CrosshairStyle::Dot,
+            cursor_hide_after: 0.0,
+            lock_box_aspect: false,
+            pan_friction: 12.0,
+            projection: Projection::Dome,
+            frame_aspect: FrameAspect::Square,
+            quantize: Quantize::Bar,
+            lfo_retrigger: Quantize::Beat,
+            lfo_rate_unit: LfoRateUnit::Beats,
+            lfo_depth: LfoDepth::MinMax,
+            flash_frames: 12,
+            flash_every: Quantize::Bar,
+            audio_auto_gain: true,
+            max_iterations: 50_000,
+            auto_iterations: true,
+            auto_iterations_cap: 4000,
+            smooth_iterations: false,
+            antialias: Antialias::X4,
+            half_res_effects: true,
+            effect_lod: false,
+            threaded_modulation: true,
+            watchdog_timeout: 30.0,
+            stream_enabled: true,
+            stream_port: 9000,
+            stream_lan: true,
+            overlay: OverlaySettings {
+                enabled: true,
+                text: "Main stage".into(),
+                logo: Some(PathBuf::from("logo.png")),
+                layout: OverlayLayout {
+                    pulse: 0.3,
+                    ..Default::default()
+                },
+            },
+        };
+        s.save(&path).unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["version"], FORMAT.version());
+        assert_eq!(Settings::load(&path).unwrap(), s);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn theme_names_are_unique() {
+        let names: std::collections::HashSet<_> = HudTheme::ALL
+            .iter()
+            .map(|t| crate::i18n::tr(Language::English, t.label()))
+            .collect();
+        assert_eq!(names.len(), HudTheme::ALL.len());
+    }
+
+    #[test]
+    fn antialias_modes_use_the_counts_the_generators_support() {
+        assert_eq!(Antialias::Off.samples(), 1);
+        let counts: Vec<u32> = Antialias::ALL[1..].iter().map(|a| a.samples()).collect();
+        assert_eq!(counts, fractal_gpu::context::SUPERSAMPLE_COUNTS);
+    }
+
+    #[test]
+    fn frame_aspects_are_landscape_or_portrait_as_named() {
+        assert_eq!(FrameAspect::Window.ratio(), None);
+        assert!(FrameAspect::Widescreen.ratio().unwrap() > 1.0);
+        assert!(FrameAspect::Portrait.ratio().unwrap() < 1.0);
+        assert_eq!(FrameAspect::Square.ratio(), Some(1.0));
+    }
+
+    #[test]
+    fn lfo_rate_units_round_trip() {
+        let bpm = Some(120.0);
+        for unit in LfoRateUnit::ALL {
+            let shown = unit.in_unit(0.25, bpm);
+            assert!((unit.to_hz(shown, bpm) - 0.25).abs() < 1e-6, "{unit:?}");
+            let range = unit.range(bpm);
+            assert!(range.start() < range.end(), "{unit:?}");
+        }
+        assert_eq!(LfoRateUnit::Seconds.in_unit(0.25, None), 4.0);
+        // A cycle every 8 beats at 120 BPM takes 4 s.
+        assert_eq!(LfoRateUnit::Beats.in_unit(0.25, bpm), 8.0);
+        assert_eq!(LfoRateUnit::Beats.effective(None), LfoRateUnit::Hertz);
+        assert_eq!(LfoRateUnit::Beats.effective(bpm), LfoRateUnit::Beats);
+    }
+}
@@ -0,0 +1,361 @@
+use std::mem::discriminant;
+
+use fractal_core::{EffectKind, GeneratorKind};
+use fractal_gpu::{
+    effect_pipeline::EffectPass, generator_pipeline::GeneratorPass, shader::ShaderError,
+};
+
+use crate::i18n::{tr, trf, Language, Msg};
+
+// ---------------------------------------------------------------------------
+// ShaderTarget — which compute pipeline the editor is bound to
+// ---------------------------------------------------------------------------
+
+// The editor holds one of these; the audio overlay's inline samples make
+// the effect variant large, which is harmless here.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum ShaderTarget {
+    Generator(GeneratorKind),
+    /// Any value of the variant; only the discriminant matters.
+    Effect(EffectKind),
+}
+
+impl ShaderTarget {
+    /// Same pipeline?  Compares by variant, ignoring effect values and
+    /// hybrid schedules.
+    pub fn same_as(&self, other: &ShaderTarget) -> bool {
+        match (self, other) {
+            (ShaderTarget::Generator(a), ShaderTarget::Generator(b)) => {
+                discriminant(a) == discriminant(b)
+            }
+            (ShaderTarget::Effect(a), ShaderTarget::Effect(b)) => {
+                discriminant(a) == discriminant(b)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn default_source(&self) -> &'static str {
+        match self {
+            ShaderTarget::Generator(kind) => GeneratorPass::default_source(*kind),
+            ShaderTarget::Effect(kind) => EffectPass::default_source(kind),
+        }
+    }
+}
+
+/// What the editor wants the app to do with the GPU passes.
+#[derive(Debug, Clone)]
+pub enum EditorRequest {
+    /// Recompile `target` from the given source.
+    Compile(ShaderTarget, String),
+    /// Restore `target`'s built-in source.
+    Revert(ShaderTarget),
+}
+
+// ---------------------------------------------------------------------------
+// ShaderEditor — egui window with live recompile
+// ---------------------------------------------------------------------------
+
+/// In-app WGSL playground.  Every edit is recompiled immediately; the last
+/// good pipeline keeps running while the source has errors.
+#[derive(Default)]
+pub struct ShaderEditor {
+    pub open: bool,
+    target: Option<ShaderTarget>,
+    source: String,
+    error: Option<ShaderError>,
+    /// Sources that compiled successfully, by target.  Re-applied when the
+    /// generator pass is rebuilt (e.g. on resize).
+    edits: Vec<(ShaderTarget, String)>,
+    /// Stops the colour map's edit was generated from, while it is still a
+    /// bundle's gradient rather than hand-edited.
+    gradient: Option<Vec<[f32; 3]>>,
+}
+
+impl ShaderEditor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The source currently live for `target`: the user's edit if any,
+    /// otherwise the built-in shader.
+    fn live_source(&self, target: &ShaderTarget) -> String {
+        self.edit_for(target)
+            .unwrap_or_else(|| target.default_source())
+            .to_string()
+    }
+
+    /// Bind the editor to `target`, loading its live source.
+    fn select(&mut self, target: ShaderTarget) {
+        self.source = self.live_source(&target);
+        self.target = Some(target);
+        self.error = None;
+    }
+
+    /// Record the outcome of a [`EditorRequest::Compile`].
+    pub fn report_compile(
+        &mut self,
+        target: &ShaderTarget,
+        source: String,
+        result: Result<(), ShaderError>,
+    ) {
+        match result {
+            Ok(()) => {
+                self.remember(target, source);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Record a source compiled from outside the editor, e.g. a bundle's,
+    /// showing it if the editor is bound to `target`.  `gradient` is the
+    /// stops a colour map source was generated from.
+    pub fn report_loaded(
+        &mut self,
+        target: &ShaderTarget,
+        source: String,
+        gradient: Option<Vec<[f32; 3]>>,
+    ) {
+        if self.target.as_ref().is_some_and(|t| t.same_as(target)) {
+            self.source = source.clone();
+            self.error = None;
+        }
+        self.remember(target, source);
+        if gradient.is_some() {
+            self.gradient = gradient;
+        }
+    }
+
+    fn remember(&mut self, target: &ShaderTarget, source: String) {
+        self.forget(target);
+        self.edits.push((target.clone(), source));
+    }
+
+    fn forget(&mut self, target: &ShaderTarget) {
+        self.edits.retain(|(t, _)| !t.same_as(target));
+        if matches!(target, ShaderTarget::Effect(EffectKind::ColorMap { .. })) {
+            self.gradient = None;
+        }
+    }
+
+    /// Record that `target` was reverted to its built-in source.
+    pub fn report_revert(&mut self, target: &ShaderTarget) {
+        self.forget(target);
+        if self.target.as_ref().is_some_and(|t| t.same_as(target)) {
+            self.source = target.default_source().to_string();
+            self.error = None;
+        }
+    }
+
+    /// The edited source live for `target`, if any.
+    pub fn edit_for(&self, target: &ShaderTarget) -> Option<&str> {
+        self.edits
+            .iter()
+            .find(|(t, _)| t.same_as(target))
+            .map(|(_, s)| s.as_str())
+    }
+
+    /// The bundle gradient the colour map is running, unless its source has
+    /// since been edited or reverted.
+    pub fn gradient(&self) -> Option<&[[f32; 3]]> {
+        self.gradient.as_deref()
+    }
+
+    /// Edited generator sources, for re-applying after a rebuild.
+    pub fn generator_edits(&self) -> impl Iterator<Item = (GeneratorKind, &str)> {
+        self.edits.iter().filter_map(|(t, s)| match t {
+            ShaderTarget::Generator(kind) => Some((*kind, s.as_str())),
+            ShaderTarget::Effect(_) => None,
+        })
+    }
+
+    /// Edited effect sources, for re-applying after a GPU rebuild.
+    pub fn effect_edits(&self) -> impl Iterator<Item = (&EffectKind, &str)> {
+        self.edits.iter().filter_map(|(t, s)| match t {
+            ShaderTarget::Generator(_) => None,
+            ShaderTarget::Effect(kind) => Some((kind, s.as_str())),
+        })
+    }
+
+    /// Draw the editor window.  `targets` lists the pipelines used by the
+    /// current patch, with display labels.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        lang: Language,
+        targets: &[(String, ShaderTarget)],
+    ) -> Option<EditorRequest> {
+        if !self.open || targets.is_empty() {
+            return None;
+        }
+        // Follow preset changes: fall back to the first target.
+        let bound = self
+            .target
+            .as_ref()
+            .is_some_and(|cur| targets.iter().any(|(_, t)| t.same_as(cur)));
+        if !bound {
+            self.select(targets[0].1.clone());
+        }
+
+        let mut request = None;
+        let mut open = self.open;
+        egui::Window::new(tr(lang, Msg::ShaderEditor))
+            .id(egui::Id::new("shader_editor"))
+            .open(&mut open)
+            .default_size([520.0, 420.0])
+            .show(ctx, |ui| {
+                let current = self.target.clone().expect("bound above");
+                ui.horizontal(|ui| {
+                    let label = targets
+                        .iter()
+                        .find(|(_, t)| t.same_as(&current))
+                        .map(|(l, _)| l.as_str())
+                        .unwrap_or_default();
+                    egui::ComboBox::from_id_salt("shader_target")
+                        .selected_text(label)
+                        .show_ui(ui, |ui| {
+                            for (label, target) in targets {
+                                if ui
+                                    .selectable_label(target.same_as(&current), label)
+                                    .clicked()
+                                {
+                                    self.select(target.clone());
+                                }
+                            }
+                        });
+                    if ui.button(tr(lang, Msg::Revert)).clicked() {
+                        request = Some(EditorRequest::Revert(current.clone()));
+                    }
+                });
+
+                match &self.error {
+                    Some(e) => {
+                        let text = match e.line {
+                            Some(line) => trf(lang, Msg::CompileFailedAt, &[&line]),
+                            None => tr(lang, Msg::CompileFailed).to_string(),
+                        };
+                        ui.colored_label(egui::Color32::from_rgb(240, 90, 80), text);
+                        egui::ScrollArea::vertical()
+                            .id_salt("shader_error")
+                            .max_height(100.0)
+                            .show(ui, |ui| {
+                                ui.label(egui::RichText::new(&e.message).monospace().small());
+                            });
+                    }
+                    None => {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(120, 200, 120),
+                            tr(lang, Msg::Compiled),
+                        );
+                    }
+                }
+
+                egui::ScrollArea::vertical()
+                    .id_salt("shader_source")
+                    .show(ui, |ui| {
+                        let edit = egui::TextEdit::multiline(&mut self.source)
+                            .code_editor()
+                            .desired_width(f32::INFINITY)
+                            .desired_rows(24);
+                        if ui.add(edit).changed() {
+                            request = Some(EditorRequest::Compile(current, self.source.clone()));
+                        }
+                    });
+            });
+        self.open = open;
+        request
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hue(amount: f32) -> ShaderTarget {
+        ShaderTarget::Effect(EffectKind::HueShift { amount })
+    }
+
+    #[test]
+    fn effects_compare_by_variant() {
+        assert!(hue(0.1).same_as(&hue(0.9)));
+        assert!(
+            !hue(0.1).same_as(&ShaderTarget::Effect(EffectKind::BrightnessContrast {
+                brightness: 0.0,
+                contrast: 1.0,
+            }))
+        );
+        assert!(!hue(0.1).same_as(&ShaderTarget::Generator(GeneratorKind::Julia)));
+    }
+
+    #[test]
+    fn successful_compile_is_remembered() {
+        let mut ed = ShaderEditor::new();
+        let t = ShaderTarget::Generator(GeneratorKind::Julia);
+        ed.report_compile(&t, "// v1".into(), Ok(()));
+        ed.report_compile(&t, "// v2".into(), Ok(()));
+        let edits: Vec<_> = ed.generator_edits().collect();
+        assert_eq!(edits, vec![(GeneratorKind::Julia, "// v2")]);
+        assert_eq!(ed.live_source(&t), "// v2");
+    }
+
+    #[test]
+    fn failed_compile_keeps_last_good_source() {
+        let mut ed = ShaderEditor::new();
+        let t = hue(0.0);
+        ed.report_compile(&t, "// good".into(), Ok(()));
+        let err = ShaderError {
+            message: "boom".into(),
+            line: Some(3),
+            column: Some(1),
+        };
+        ed.report_compile(&t, "// bad".into(), Err(err.clone()));
+        assert_eq!(ed.live_source(&t), "// good");
+        assert_eq!(ed.error, Some(err));
+    }
+
+    #[test]
+    fn revert_restores_default_source() {
+        let mut ed = ShaderEditor::new();
+        let t = ShaderTarget::Generator(GeneratorKind::Mandelbrot);
+        ed.select(t.clone());
+        ed.report_compile(&t, "// mine".into(), Ok(()));
+        ed.report_revert(&t);
+        assert_eq!(ed.generator_edits().count(), 0);
+        assert_eq!(ed.source, t.default_source());
+    }
+
+    #[test]
+    fn effect_edits_are_not_generator_edits() {
+        let mut ed = ShaderEditor::new();
+        ed.report_compile(&hue(0.0), "// fx".into(), Ok(()));
+        assert_eq!(ed.generator_edits().count(), 0);
+        assert_eq!(ed.effect_edits().count(), 1);
+    }
+
+    #[test]
+    fn gradient_lasts_until_the_colour_map_is_edited() {
+        let mut ed = ShaderEditor::new();
+        let map = ShaderTarget::Effect(EffectKind::ColorMap {
+            scheme: fractal_core::ColorScheme::Ocean,
+            orbit_trap: false,
+            edge_glow: false,
+        });
+        ed.select(map.clone());
+        let stops = vec![[0.0; 3], [1.0; 3]];
+        ed.report_loaded(&map, "// gradient".into(), Some(stops.clone()));
+        assert_eq!(ed.source, "// gradient");
+        assert_eq!(ed.gradient(), Some(stops.as_slice()));
+
+        ed.report_compile(&hue(0.0), "// fx".into(), Ok(()));
+        assert!(ed.gradient().is_some());
+        ed.report_compile(&map, "// by hand".into(), Ok(()));
+        assert_eq!(ed.gradient(), None);
+        assert_eq!(ed.edit_for(&map), Some("// by hand"));
+    }
+}
@@ -0,0 +1,1330 @@
+use std::cell::RefCell;
+use std::mem::discriminant;
+
+use fractal_core::{
+    audio::{AutoGain, BeatClock, Onset, Quantize},
+    modulators::Lfo,
+    overrides::OverrideStore,
+    patch::Patch,
+    perturbation::Refresh,
+    presets::Preset,
+    projection::Projection,
+    viewcode::{ViewCodeError, ViewState},
+    EffectKind, GeneratorKind, Params,
+};
+use fractal_gpu::{
+    backend::{Deck, FrameBackend},
+    context::{GeneratorUniforms, Uniforms},
+};
+
+use crate::i18n::{trf, Language, Msg};
+use crate::input::{
+    apply_box_zoom, apply_zoom, auto_iterations, clamp_iterations, InputAction,
+    DEFAULT_MAX_ITERATIONS,
+};
+use crate::midi::{CcBinding, ControlChange, MidiControl};
+use crate::modulation::ModulationThread;
+use crate::motion::{draft_kind, ViewMotion};
+use crate::overlay::OverlayLayout;
+use crate::pan::pixels_to_plane;
+
+/// Iterations added / removed per `IterationsUp` / `IterationsDown`.
+const ITERATION_STEP: u32 = 10;
+
+/// Step of the coarse (Shift) variants.
+const COARSE_ITERATION_STEP: u32 = ITERATION_STEP * 10;
+
+/// Frames a strobe flash holds its targets for, by default and at most.
+pub const DEFAULT_FLASH_FRAMES: u32 = 4;
+pub const MAX_FLASH_FRAMES: u32 = 60;
+
+// ---------------------------------------------------------------------------
+// AppState — the patch being shown and how input changes it
+// ---------------------------------------------------------------------------
+
+/// Everything that decides what the next frame looks like, kept apart from
+/// windows, egui and wgpu so it can be tested against a `MockBackend`.
+pub struct AppState {
+    pub patch: Patch,
+    /// Index into `Preset::ALL` of the preset `patch` came from.
+    pub current_preset_idx: usize,
+    /// Per-preset user overrides applied whenever a preset is loaded.
+    pub overrides: OverrideStore,
+    /// Tempo and phase from tapped beats.
+    pub beat: BeatClock,
+    /// Where preset switches and momentary triggers land.
+    pub quantize: Quantize,
+    /// Beat / bar boundaries that restart the LFOs; `Off` = free-running.
+    pub lfo_retrigger: Quantize,
+    /// Frames each strobe flash lasts.
+    pub flash_frames: u32,
+    /// Beat / bar boundaries that fire a flash; `Off` = only on demand.
+    pub flash_every: Quantize,
+    /// Gain control for audio band energies; `None` passes them through.
+    /// Lives here rather than in the patch so the learned room level
+    /// survives preset switches.
+    agc: Option<AutoGain>,
+    /// Worker evaluating deck A's modulators at a fixed rate; `None` runs
+    /// them on the render thread each frame.
+    modulation: Option<ModulationThread>,
+    /// Cap for zoom-aware iteration scaling; `None` renders exactly
+    /// `max_iter`.  `max_iter` itself stays the zoom-1 base, so +/- and saved
+    /// tweaks keep meaning the same thing.
+    pub auto_iterations: Option<u32>,
+    /// Highest iteration count +/- and the zoom schedule may reach.
+    pub max_iterations: u32,
+    /// Store the smoothed escape count, not the integer one.
+    pub smooth_iterations: bool,
+    /// Jittered samples per pixel of the escape-time generators.
+    pub samples: u32,
+    /// MIDI-learned controller bindings and the knobs' latest values.
+    pub midi: MidiControl,
+    /// Draw cheaper effects while the user pans or zooms.
+    pub effect_lod: bool,
+    /// How fast the user is moving the view.
+    motion: ViewMotion,
+    /// Patch cued on deck B, running alongside `patch` so the crossfader
+    /// can blend it in.  Input, MIDI and audio gain only touch deck A.
+    pub deck_b: Option<(Preset, Patch)>,
+    /// How far the output has faded from deck A to deck B (0–1).
+    pub crossfade: f32,
+    /// Text / logo overlay drawn over whatever the decks show; `None` = off.
+    pub overlay: Option<OverlayLayout>,
+    /// Seconds since start; the beat clock's time base (the patch clock
+    /// restarts with every preset).
+    clock: f32,
+    /// A patch switch (preset or cue) waiting for its beat / bar boundary.
+    pending_patch: Option<(f32, Preset, Patch)>,
+    /// A momentary trigger (strength) waiting for its boundary.
+    pending_trigger: Option<(f32, f32)>,
+    /// Output size in physical pixels.
+    width: u32,
+    height: u32,
+    /// The effect chain handed to the backend, rebuilt in place each frame
+    /// so encoding a frame allocates nothing once it has grown.
+    chain: RefCell<Vec<EffectKind>>,
+}
+
+impl AppState {
+    /// Start on the first preset with `overrides` applied.
+    pub fn new(overrides: OverrideStore, width: u32, height: u32) -> Self {
+        let patch = overrides.build(Preset::ALL[0]);
+        Self {
+            patch,
+            current_preset_idx: 0,
+            overrides,
+            beat: BeatClock::new(),
+            quantize: Quantize::Off,
+            lfo_retrigger: Quantize::Off,
+            flash_frames: DEFAULT_FLASH_FRAMES,
+            flash_every: Quantize::Off,
+            agc: None,
+            modulation: None,
+            auto_iterations: None,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            smooth_iterations: true,
+            samples: 1,
+            midi: MidiControl::default(),
+            effect_lod: false,
+            motion: ViewMotion::new(),
+            deck_b: None,
+            crossfade: 0.0,
+            overlay: None,
+            clock: 0.0,
+            pending_patch: None,
+            pending_trigger: None,
+            width: width.max(1),
+            height: height.max(1),
+            chain: RefCell::new(Vec::with_capacity(Patch::MAX_EFFECTS)),
+        }
+    }
+
+    pub fn preset(&self) -> Preset {
+        Preset::ALL[self.current_preset_idx]
+    }
+
+    /// Main window title: the patch's own title, or its preset's name, and
+    /// its author when it has one.
+    pub fn window_title(&self, lang: Language) -> String {
+        let meta = &self.patch.meta;
+        let title = meta.title_or(self.preset().name());
+        match meta.author() {
+            Some(author) => trf(lang, Msg::MainWindowTitleBy, &[&title, &author]),
+            None => trf(lang, Msg::MainWindowTitle, &[&title]),
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width.max(1);
+        self.height = height.max(1);
+    }
+
+    fn aspect(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+
+    /// Apply a view / patch action.  Returns `false` (doing nothing) for
+    /// actions that need the app shell, such as saving or window toggles.
+    pub fn handle(&mut self, action: &InputAction) -> bool {
+        match *action {
+            InputAction::LoadPreset(preset) => {
+                log::info!("Loading preset: {}", preset.name());
+                self.switch_preset(preset);
+            }
+            InputAction::CycleNextPreset => {
+                // Step on from a queued switch so repeated presses advance.
+                let from = self.pending_preset().unwrap_or(self.preset());
+                let idx = Preset::ALL.iter().position(|&p| p == from).unwrap_or(0);
+                let preset = Preset::ALL[(idx + 1) % Preset::ALL.len()];
+                log::info!("Cycling to preset: {}", preset.name());
+                self.switch_preset(preset);
+            }
+            InputAction::TapTempo => {
+                self.beat.beat(self.clock);
+                if let Some(bpm) = self.beat.bpm() {
+                    log::debug!("tempo → {bpm:.1} BPM");
+                }
+                self.trigger(1.0);
+            }
+            InputAction::RetriggerLfos => {
+                log::debug!("LFOs retriggered");
+                Lfo::retrigger(&mut self.patch.params);
+            }
+            InputAction::Flash => {
+                if !self.flash() {
+                    log::info!("{} has no flash targets", self.preset().name());
+                }
+            }
+            InputAction::LoadDeckB(preset) => {
+                log::info!("Cueing on deck B: {}", preset.name());
+                self.deck_b = Some((preset, self.overrides.build(preset)));
+            }
+            InputAction::CloseDeckB => {
+                self.deck_b = None;
+                self.crossfade = 0.0;
+            }
+            InputAction::TakeDeckB => {
+                if !self.take_deck_b() {
+                    log::info!("nothing cued on deck B");
+                }
+            }
+            InputAction::IterationsUp => self.step_iterations(ITERATION_STEP as i32),
+            InputAction::IterationsDown => self.step_iterations(-(ITERATION_STEP as i32)),
+            InputAction::IterationsUpCoarse => self.step_iterations(COARSE_ITERATION_STEP as i32),
+            InputAction::IterationsDownCoarse => {
+                self.step_iterations(-(COARSE_ITERATION_STEP as i32))
+            }
+            InputAction::MouseZoom { norm_x, norm_y } => {
+                let aspect = self.aspect();
+                // Zoom about the origin for the pan alone, so the patch can
+                // apply it at full precision when zoomed deep.
+                let (dx, dy, zoom) =
+                    apply_zoom(0.0, 0.0, self.patch.params.zoom, norm_x, norm_y, aspect);
+                self.patch.pan(dx, dy);
+                let p = &mut self.patch.params;
+                p.zoom = zoom;
+                self.motion.moved(1.0);
+                log::debug!(
+                    "Zoom → {:.4}  center ({:.6}, {:.6})",
+                    zoom,
+                    p.center_x,
+                    p.center_y
+                );
+            }
+            InputAction::BoxZoom {
+                x0,
+                y0,
+                x1,
+                y1,
+                zoom_out,
+            } => {
+                let aspect = self.aspect();
+                let (dx, dy, zoom) = apply_box_zoom(
+                    0.0,
+                    0.0,
+                    self.patch.params.zoom,
+                    (x0, y0),
+                    (x1, y1),
+                    aspect,
+                    zoom_out,
+                );
+                self.patch.pan(dx, dy);
+                let p = &mut self.patch.params;
+                let doublings = (zoom / p.zoom).log2();
+                p.zoom = zoom;
+                self.motion.moved(doublings);
+                log::debug!(
+                    "Box zoom → {:.4}  center ({:.6}, {:.6})",
+                    zoom,
+                    p.center_x,
+                    p.center_y
+                );
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Load `preset` now, or queue it for the next beat / bar when
+    /// quantizing with a known tempo.  A later switch replaces a queued one.
+    pub fn switch_preset(&mut self, preset: Preset) {
+        self.queue_patch(preset, self.overrides.build(preset), self.quantize);
+    }
+
+    /// Show `patch` now, or at the next boundary `quantize` asks for once a
+    /// tempo is known.  Cues pass their own transition here instead of the
+    /// global quantize setting.
+    pub fn queue_patch(&mut self, preset: Preset, patch: Patch, quantize: Quantize) {
+        match self.beat.next_boundary(self.clock, quantize) {
+            Some(due) if due > self.clock => self.pending_patch = Some((due, preset, patch)),
+            _ => {
+                self.pending_patch = None;
+                self.set_patch(preset, patch);
+            }
+        }
+    }
+
+    /// Fire onset-triggered envelopes at `strength`, quantized like preset
+    /// switches.
+    pub fn trigger(&mut self, strength: f32) {
+        match self.beat.next_boundary(self.clock, self.quantize) {
+            Some(due) if due > self.clock => self.pending_trigger = Some((due, strength)),
+            _ => self.fire(strength),
+        }
+    }
+
+    /// Spike both decks' flash targets for `flash_frames` frames.  Returns
+    /// `false` when neither patch declares any.
+    pub fn flash(&mut self) -> bool {
+        let frames = self.flash_frames;
+        let cued = self
+            .deck_b
+            .as_mut()
+            .is_some_and(|(_, patch)| patch.flash(frames));
+        self.patch.flash(frames) | cued
+    }
+
+    /// Move the crossfader: 0 shows deck A, 1 deck B.
+    pub fn set_crossfade(&mut self, amount: f32) {
+        self.crossfade = amount.clamp(0.0, 1.0);
+    }
+
+    /// Make deck B's patch the live one, mid-flight, and pull the fader
+    /// back to A.  Returns `false` when nothing is cued.
+    pub fn take_deck_b(&mut self) -> bool {
+        let Some((preset, patch)) = self.deck_b.take() else {
+            return false;
+        };
+        self.pending_patch = None;
+        self.set_patch(preset, patch);
+        self.crossfade = 0.0;
+        true
+    }
+
+    fn fire(&mut self, strength: f32) {
+        Onset {
+            time: self.clock,
+            strength,
+        }
+        .apply(&mut self.patch.params);
+    }
+
+    /// Turn audio auto-gain on or off.  Turning it off forgets the learned
+    /// peaks; calling this with it already on keeps them.
+    pub fn set_auto_gain(&mut self, enabled: bool) {
+        if enabled != self.agc.is_some() {
+            self.agc = enabled.then(AutoGain::new);
+        }
+    }
+
+    /// Evaluate deck A's modulators on a worker thread (see
+    /// [`ModulationThread`]) or, when `enabled` is false, every frame here.
+    pub fn set_threaded_modulation(&mut self, enabled: bool) {
+        if enabled == self.modulation.is_some() {
+            return;
+        }
+        self.modulation = if enabled {
+            ModulationThread::start()
+                .map_err(|e| log::warn!("modulation thread not started: {e}"))
+                .ok()
+        } else {
+            None
+        };
+    }
+
+    /// The preset of the queued switch, if any.
+    pub fn pending_preset(&self) -> Option<Preset> {
+        self.pending_patch.as_ref().map(|&(_, p, _)| p)
+    }
+
+    /// Switch to `preset` with the user's overrides applied.
+    pub fn load_preset(&mut self, preset: Preset) {
+        self.set_patch(preset, self.overrides.build(preset));
+    }
+
+    /// Show `patch`, remembering `preset` as where it came from.
+    pub fn set_patch(&mut self, preset: Preset, patch: Patch) {
+        if let Some(idx) = Preset::ALL.iter().position(|&p| p == preset) {
+            self.current_preset_idx = idx;
+        }
+        for key in patch.unconsumed_targets() {
+            log::warn!(
+                "{}: modulation target {key:?} is not read by any stage",
+                preset.name()
+            );
+        }
+        for key in patch.feedback_keys() {
+            log::warn!(
+                "{}: audio routes feed back into {key:?}; its routes run in declared order",
+                preset.name()
+            );
+        }
+        self.patch = patch;
+    }
+
+    /// Drop the current preset's overrides and rebuild it from factory
+    /// defaults.  Returns `true` if there were overrides to drop.
+    pub fn reset(&mut self) -> bool {
+        let preset = self.preset();
+        let cleared = self.overrides.clear(preset);
+        self.patch = preset.build();
+        cleared
+    }
+
+    /// Move the view by a screen-space drag in physical pixels.
+    pub fn pan_pixels(&mut self, delta: (f32, f32)) {
+        let (dx, dy) = pixels_to_plane(delta, self.patch.params.zoom, self.height as f32);
+        self.patch.pan(dx, dy);
+        self.motion
+            .moved(delta.0.hypot(delta.1) / self.height as f32);
+    }
+
+    /// The effect chains are drawn at draft quality this frame: the view is
+    /// moving and effect LOD is on.
+    pub fn drafting_effects(&self) -> bool {
+        self.effect_lod && self.motion.is_moving()
+    }
+
+    /// Feed the pointer position (physical pixels) to mouse modulators.
+    pub fn set_pointer(&mut self, x: f64, y: f64) {
+        self.patch.params.mouse_x = (x / self.width as f64) as f32;
+        self.patch.params.mouse_y = (y / self.height as f64) as f32;
+    }
+
+    /// Share code for the current view.
+    pub fn view_code(&self) -> String {
+        ViewState::capture(self.patch.generator.kind(), &self.patch.params).encode()
+    }
+
+    /// Jump to the location encoded in a view code.  Switches preset only if
+    /// the current one uses a different generator; a hybrid keeps its own
+    /// schedule, which view codes don't carry.
+    pub fn apply_view_code(&mut self, code: &str) -> Result<(), ViewCodeError> {
+        let view = ViewState::decode(code)?;
+        if discriminant(&self.patch.generator.kind()) != discriminant(&view.generator) {
+            self.load_preset(view.preset());
+        }
+        view.apply(&mut self.patch.params);
+        Ok(())
+    }
+
+    /// Advance both clocks, applying queued changes that have come due.
+    pub fn tick(&mut self, dt: f32) {
+        let last = self.clock;
+        self.clock += dt;
+        self.motion.tick(dt);
+        let crossed = |quantize| {
+            self.beat
+                .next_boundary(last, quantize)
+                .is_some_and(|due| due > last && due <= self.clock)
+        };
+        let (retrigger, flash) = (crossed(self.lfo_retrigger), crossed(self.flash_every));
+        // A fresh patch starts its LFOs at time 0, so preset loads already
+        // restart them; this adds the beat / bar boundaries in between.
+        if retrigger {
+            Lfo::retrigger(&mut self.patch.params);
+            if let Some((_, patch)) = &mut self.deck_b {
+                Lfo::retrigger(&mut patch.params);
+            }
+        }
+        if flash {
+            self.flash();
+        }
+        if self
+            .pending_patch
+            .as_ref()
+            .is_some_and(|&(due, _, _)| due <= self.clock)
+        {
+            if let Some((_, preset, patch)) = self.pending_patch.take() {
+                self.set_patch(preset, patch);
+            }
+        }
+        if let Some((_, strength)) = self.pending_trigger.filter(|&(due, _)| due <= self.clock) {
+            self.pending_trigger = None;
+            self.fire(strength);
+        }
+        // Normalise the analyser's band energies before the patch's audio
+        // routes read them.
+        if let Some(agc) = &mut self.agc {
+            agc.process(self.clock, &mut self.patch.params);
+        }
+        self.patch.advance(dt);
+        match &mut self.modulation {
+            Some(thread) => thread.modulate(&mut self.patch),
+            None => self.patch.modulate(),
+        }
+        self.patch.apply_controls();
+        self.midi.apply(&mut self.patch.params);
+        for r in self.patch.sanitize() {
+            log::warn!(
+                "{}: {} was {}, reset to {}",
+                self.preset().name(),
+                r.key,
+                r.was,
+                r.now
+            );
+        }
+        self.patch
+            .update_deep(self.effective_max_iter(), Refresh::Background);
+        // Deck B's reference orbit has to match the iterations it renders
+        // with, which `Patch::tick` would take from its params alone.
+        if let Some((preset, mut patch)) = self.deck_b.take() {
+            patch.advance(dt);
+            patch.modulate();
+            patch.apply_controls();
+            patch.sanitize();
+            patch.update_deep(self.max_iter_for(&patch.params), Refresh::Background);
+            self.deck_b = Some((preset, patch));
+        }
+    }
+
+    /// Move the current patch's macro `name` to `value` (0–1); the patch
+    /// fans it out on the next tick.  Unknown names are ignored.
+    pub fn set_macro(&mut self, name: &str, value: f32) {
+        if let Some(key) = self
+            .patch
+            .macros
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| m.key())
+        {
+            self.patch.params.set(key, value.clamp(0.0, 1.0));
+        }
+    }
+
+    /// Handle one raw MIDI message.  Returns the binding a MIDI-learn just
+    /// made, so the caller can persist the controller map.
+    pub fn midi_message(&mut self, bytes: &[u8]) -> Option<CcBinding> {
+        let cc = ControlChange::parse(bytes)?;
+        if self.midi.fires_flash(cc) {
+            self.flash();
+        }
+        self.midi.handle(cc, &self.patch.params)
+    }
+
+    /// The effect chain as configured by the current params.
+    pub fn effect_kinds(&self) -> Vec<EffectKind> {
+        effect_kinds(&self.patch)
+    }
+
+    fn step_iterations(&mut self, delta: i32) {
+        let p = &mut self.patch.params;
+        p.max_iter = clamp_iterations(p.max_iter.saturating_add_signed(delta), self.max_iterations);
+        log::debug!("max_iter → {}", p.max_iter);
+    }
+
+    /// Iterations the generator actually runs at the current zoom.
+    pub fn effective_max_iter(&self) -> u32 {
+        self.max_iter_for(&self.patch.params)
+    }
+
+    fn max_iter_for(&self, p: &Params) -> u32 {
+        let iter = match self.auto_iterations {
+            Some(cap) => auto_iterations(p.max_iter, p.zoom, cap),
+            None => p.max_iter,
+        };
+        iter.min(clamp_iterations(u32::MAX, self.max_iterations))
+    }
+
+    /// Where render pixel `px` lands on deck A's complex plane; `None` off
+    /// the dome.
+    pub fn plane_point(&self, px: (f32, f32), projection: Projection) -> Option<[f32; 2]> {
+        let p = &self.patch.params;
+        let res = (self.width as f32, self.height as f32);
+        let (dx, dy) = projection.plane_offset(px, res)?;
+        Some([p.center_x + dx / p.zoom, p.center_y + dy / p.zoom])
+    }
+
+    /// Deck A's generator with the uniforms and extension block it renders
+    /// with, for side passes like the cursor probe that must agree with it.
+    pub fn generator_inputs(
+        &self,
+        projection: Projection,
+    ) -> (GeneratorKind, Uniforms, GeneratorUniforms) {
+        let kind = self.patch.generator.kind();
+        (
+            kind,
+            self.uniforms(&self.patch.params, projection),
+            GeneratorUniforms::from_params(kind, &self.patch.params),
+        )
+    }
+
+    /// Uniforms for a deck showing `params`.
+    fn uniforms(&self, params: &Params, projection: Projection) -> Uniforms {
+        let mut uniforms = Uniforms::from_params(params, self.width, self.height)
+            .with_projection(projection)
+            .with_smoothing(self.smooth_iterations)
+            .with_samples(self.samples);
+        uniforms.max_iter = self.max_iter_for(params);
+        uniforms
+    }
+
+    /// Issue this frame's GPU work: generator and effect chain for each
+    /// visible deck, the crossfade when both are, then present.
+    pub fn encode_frame(&self, gpu: &mut impl FrameBackend, projection: Projection) {
+        match &self.deck_b {
+            Some((_, deck_b)) if self.crossfade > 0.0 => {
+                let both = self.crossfade < 1.0;
+                if both {
+                    self.encode_deck(gpu, &self.patch, projection);
+                }
+                gpu.select_deck(Deck::B);
+                self.encode_deck(gpu, deck_b, projection);
+                if both {
+                    gpu.crossfade(self.crossfade);
+                }
+            }
+            _ => self.encode_deck(gpu, &self.patch, projection),
+        }
+        if let Some(overlay) = &self.overlay {
+            gpu.overlay(&overlay.params(self.beat.phase(self.clock)));
+        }
+        gpu.present(projection);
+    }
+
+    /// Dispatch `patch`'s generator and effect chain without presenting, so
+    /// their shaders have run once before the patch is loaded.
+    pub fn encode_warm_up(
+        &self,
+        gpu: &mut impl FrameBackend,
+        patch: &Patch,
+        projection: Projection,
+    ) {
+        self.encode_deck(gpu, patch, projection);
+    }
+
+    fn encode_deck(&self, gpu: &mut impl FrameBackend, patch: &Patch, projection: Projection) {
+        let uniforms = self
+            .uniforms(&patch.params, projection)
+            .with_deep(patch.deep.as_ref());
+        if let Some(orbit) = patch.deep.as_ref().and_then(|deep| deep.orbit.as_ref()) {
+            gpu.upload_orbit(orbit);
+        }
+        let extension = GeneratorUniforms::for_patch(patch, &uniforms);
+        gpu.dispatch_generator(patch.generator.kind(), &uniforms, &extension);
+        let drafting = self.drafting_effects();
+        let mut chain = self.chain.borrow_mut();
+        chain.clear();
+        chain.extend(patch.effects.iter().map(|e| {
+            let kind = e.kind(&patch.params);
+            if drafting {
+                draft_kind(&kind)
+            } else {
+                kind
+            }
+        }));
+        gpu.dispatch_chain(&chain, &uniforms);
+    }
+}
+
+/// `patch`'s effect chain as configured by its current params.
+fn effect_kinds(patch: &Patch) -> Vec<EffectKind> {
+    patch
+        .effects
+        .iter()
+        .map(|e| e.kind(&patch.params))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fractal_core::modulators::LFO_RETRIGGER_KEY;
+    use fractal_core::perturbation::DeepZoom;
+    use fractal_gpu::backend::{GpuCall, MockBackend};
+
+    fn state() -> AppState {
+        AppState::new(OverrideStore::default(), 800, 600)
+    }
+
+    fn frame(state: &AppState) -> MockBackend {
+        let mut gpu = MockBackend::new();
+        state.encode_frame(&mut gpu, Projection::Flat);
+        gpu
+    }
+
+    /// Records nothing, so a frame's own allocations are all that count.
+    struct NullBackend;
+
+    impl FrameBackend for NullBackend {
+        fn dispatch_generator(&mut self, _: GeneratorKind, _: &Uniforms, _: &GeneratorUniforms) {}
+        fn upload_orbit(&mut self, _: &fractal_core::perturbation::ReferenceOrbit) {}
+        fn dispatch_chain(&mut self, _: &[EffectKind], _: &Uniforms) {}
+        fn select_deck(&mut self, _: Deck) {}
+        fn crossfade(&mut self, _: f32) {}
+        fn overlay(&mut self, _: &fractal_gpu::overlay_pipeline::OverlayParams) {}
+        fn present(&mut self, _: Projection) {}
+    }
+
+    #[test]
+    fn steady_state_frames_do_not_allocate() {
+        for preset in Preset::ALL {
+            let mut s = state();
+            s.load_preset(preset);
+            for _ in 0..3 {
+                s.tick(1.0 / 60.0);
+                s.encode_frame(&mut NullBackend, Projection::Flat);
+            }
+            crate::alloc_audit::take_frame();
+            s.tick(1.0 / 60.0);
+            s.encode_frame(&mut NullBackend, Projection::Flat);
+            let stats = crate::alloc_audit::take_frame().unwrap();
+            assert_eq!(stats.allocations, 0, "{}: {stats:?}", preset.name());
+        }
+    }
+
+    #[test]
+    fn frame_dispatches_generator_chain_present_in_order() {
+        let s = state();
+        let gpu = frame(&s);
+        assert!(matches!(
+            gpu.calls.as_slice(),
+            [
+                GpuCall::Generator {
+                    kind: GeneratorKind::Mandelbrot,
+                    ..
+                },
+                GpuCall::Chain(_),
+                GpuCall::Present(Projection::Flat),
+            ]
+        ));
+        let u = gpu.last_uniforms().unwrap();
+        assert_eq!(u.resolution, [800.0, 600.0]);
+        assert_eq!(u.max_iter, s.patch.params.max_iter);
+    }
+
+    #[test]
+    fn preset_switch_changes_dispatched_generator() {
+        let mut s = state();
+        assert!(s.handle(&InputAction::LoadPreset(Preset::ShipStorm)));
+        assert_eq!(s.preset(), Preset::ShipStorm);
+        let gpu = frame(&s);
+        assert!(matches!(
+            gpu.calls[0],
+            GpuCall::Generator {
+                kind: GeneratorKind::BurningShip,
+                ..
+            }
+        ));
+        let GpuCall::Chain(effects) = &gpu.calls[1] else {
+            panic!("expected the effect chain second");
+        };
+        assert_eq!(effects.len(), s.patch.effects.len());
+    }
+
+    fn echo_layers(gpu: &MockBackend) -> u32 {
+        let GpuCall::Chain(effects) = &gpu.calls[1] else {
+            panic!("expected the effect chain second");
+        };
+        effects
+            .iter()
+            .find_map(|e| match e {
+                EffectKind::Echo { layers, .. } => Some(*layers),
+                _ => None,
+            })
+            .expect("an echo effect")
+    }
+
+    #[test]
+    fn zooming_drafts_effects_until_the_view_settles() {
+        let mut s = state();
+        assert!(s.handle(&InputAction::LoadPreset(Preset::EchoChamber)));
+        s.effect_lod = true;
+        assert_eq!(echo_layers(&frame(&s)), 5);
+
+        s.handle(&InputAction::MouseZoom {
+            norm_x: 0.5,
+            norm_y: 0.5,
+        });
+        s.tick(1.0 / 60.0);
+        assert!(s.drafting_effects());
+        assert_eq!(echo_layers(&frame(&s)), 3);
+
+        for _ in 0..120 {
+            s.tick(1.0 / 60.0);
+        }
+        assert!(!s.drafting_effects());
+        assert_eq!(echo_layers(&frame(&s)), 5);
+    }
+
+    #[test]
+    fn effect_lod_off_keeps_full_quality_while_moving() {
+        let mut s = state();
+        assert!(s.handle(&InputAction::LoadPreset(Preset::EchoChamber)));
+        s.pan_pixels((200.0, 0.0));
+        s.tick(1.0 / 60.0);
+        assert!(!s.drafting_effects());
+        assert_eq!(echo_layers(&frame(&s)), 5);
+    }
+
+    #[test]
+    fn warm_up_dispatches_without_presenting() {
+        let s = state();
+        let patch = Preset::EchoChamber.build();
+        let mut gpu = MockBackend::new();
+        s.encode_warm_up(&mut gpu, &patch, Projection::Flat);
+        assert_eq!(gpu.calls.len(), 2);
+        assert!(matches!(
+            gpu.calls[0],
+            GpuCall::Generator { kind, .. } if kind == patch.generator.kind()
+        ));
+        assert!(matches!(&gpu.calls[1], GpuCall::Chain(e) if e.len() == patch.effects.len()));
+    }
+
+    #[test]
+    fn julia_preset_dispatches_its_extension_block() {
+        let mut s = state();
+        assert!(s.handle(&InputAction::LoadPreset(Preset::PsychedelicJulia)));
+        let gpu = frame(&s);
+        let GpuCall::Generator {
+            kind: GeneratorKind::Julia,
+            extension: GeneratorUniforms::Julia(julia),
+            ..
+        } = &gpu.calls[0]
+        else {
+            panic!(
+                "expected a Julia dispatch with its c, got {:?}",
+                gpu.calls[0]
+            );
+        };
+        let p = &s.patch.params;
+        assert_eq!(julia.c, [p.get("julia_cx"), p.get("julia_cy")]);
+    }
+
+    #[test]
+    fn cycling_wraps_around() {
+        let mut s = state();
+        for _ in 0..Preset::ALL.len() {
+            s.handle(&InputAction::CycleNextPreset);
+        }
+        assert_eq!(s.current_preset_idx, 0);
+        s.handle(&InputAction::CycleNextPreset);
+        assert_eq!(s.preset(), Preset::ALL[1]);
+    }
+
+    #[test]
+    fn iterations_step_and_clamp() {
+        let mut s = state();
+        let before = s.patch.params.max_iter;
+        s.handle(&InputAction::IterationsUp);
+        assert_eq!(s.patch.params.max_iter, before + ITERATION_STEP);
+        for _ in 0..10_000 {
+            s.handle(&InputAction::IterationsDown);
+        }
+        assert_eq!(
+            s.patch.params.max_iter,
+            clamp_iterations(0, s.max_iterations)
+        );
+    }
+
+    #[test]
+    fn coarse_steps_and_configured_ceiling() {
+        let mut s = state();
+        let before = s.patch.params.max_iter;
+        s.handle(&InputAction::IterationsUpCoarse);
+        assert_eq!(s.patch.params.max_iter, before + COARSE_ITERATION_STEP);
+        s.handle(&InputAction::IterationsDownCoarse);
+        assert_eq!(s.patch.params.max_iter, before);
+
+        s.max_iterations = 100_000;
+        for _ in 0..2_000 {
+            s.handle(&InputAction::IterationsUpCoarse);
+        }
+        assert_eq!(s.patch.params.max_iter, 100_000);
+        // Lowering the ceiling caps what is rendered straight away.
+        s.max_iterations = 500;
+        assert_eq!(s.effective_max_iter(), 500);
+    }
+
+    #[test]
+    fn smooth_iterations_reach_uniforms() {
+        let mut s = state();
+        assert_eq!(s.uniforms(&s.patch.params, Projection::Flat).smoothing, 1);
+        s.smooth_iterations = false;
+        assert_eq!(s.uniforms(&s.patch.params, Projection::Flat).smoothing, 0);
+    }
+
+    #[test]
+    fn sample_count_reaches_uniforms() {
+        let mut s = state();
+        assert_eq!(s.uniforms(&s.patch.params, Projection::Flat).samples, 1);
+        s.samples = 4;
+        assert_eq!(s.uniforms(&s.patch.params, Projection::Flat).samples, 4);
+    }
+
+    #[test]
+    fn auto_iterations_follow_zoom_into_uniforms() {
+        let mut s = state();
+        let base = s.patch.params.max_iter;
+        s.patch.params.zoom = 1024.0;
+        assert_eq!(s.uniforms(&s.patch.params, Projection::Flat).max_iter, base);
+
+        s.auto_iterations = Some(u32::MAX);
+        let deep = s.uniforms(&s.patch.params, Projection::Flat).max_iter;
+        assert!(deep > base, "{deep} <= {base}");
+        // The stored count is untouched, so overrides still save the base.
+        assert_eq!(s.patch.params.max_iter, base);
+
+        s.auto_iterations = Some(base + 5);
+        assert_eq!(s.effective_max_iter(), base + 5);
+    }
+
+    #[test]
+    fn plane_point_follows_center_and_zoom() {
+        let mut s = state();
+        s.patch.params.center_x = -0.5;
+        s.patch.params.center_y = 0.25;
+        s.patch.params.zoom = 2.0;
+        let mid = s.plane_point((400.0, 300.0), Projection::Flat).unwrap();
+        assert_eq!(mid, [-0.5, 0.25]);
+        // Half the height right of centre is one unit at zoom 1, half at 2.
+        let right = s.plane_point((700.0, 300.0), Projection::Flat).unwrap();
+        assert!((right[0] - 0.0).abs() < 1e-6, "{right:?}");
+        assert_eq!(s.plane_point((0.0, 0.0), Projection::Dome), None);
+    }
+
+    #[test]
+    fn click_zoom_doubles_and_reaches_uniforms() {
+        let mut s = state();
+        let zoom = s.patch.params.zoom;
+        s.handle(&InputAction::MouseZoom {
+            norm_x: 0.5,
+            norm_y: 0.5,
+        });
+        assert!((s.patch.params.zoom - zoom * 2.0).abs() < 1e-6);
+        assert_eq!(frame(&s).last_uniforms().unwrap().zoom, s.patch.params.zoom);
+    }
+
+    #[test]
+    fn deep_zoom_uploads_the_orbit_and_perturbs() {
+        let mut s = state();
+        s.patch.params.zoom = DeepZoom::THRESHOLD * 1e6;
+        s.tick(0.016);
+        s.handle(&InputAction::MouseZoom {
+            norm_x: 0.75,
+            norm_y: 0.5,
+        });
+        let gpu = frame(&s);
+        let deep = s.patch.deep.as_ref().unwrap();
+        let generation = deep.orbit.as_ref().unwrap().generation;
+        assert!(matches!(gpu.calls[0], GpuCall::Orbit(g) if g == generation));
+        let GpuCall::Generator {
+            kind, extension, ..
+        } = &gpu.calls[1]
+        else {
+            panic!("expected the generator dispatch, got {:?}", gpu.calls[1]);
+        };
+        assert_eq!(*kind, GeneratorKind::Mandelbrot);
+        let GeneratorUniforms::Perturbation(u) = extension else {
+            panic!("expected the perturbation block, got {extension:?}");
+        };
+        assert!(u.offset[0] > 0.0, "the click moved right of the reference");
+    }
+
+    #[test]
+    fn deck_b_orbit_runs_the_effective_iterations() {
+        let mut s = state();
+        s.auto_iterations = Some(u32::MAX);
+        let mut patch = s.preset().build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Mandelbrot);
+        patch.params.zoom = DeepZoom::THRESHOLD * 1e6;
+        s.deck_b = Some((s.preset(), patch));
+        s.tick(0.016);
+        let (_, b) = s.deck_b.as_ref().unwrap();
+        let orbit = b.deep.as_ref().unwrap().orbit.as_ref().unwrap();
+        assert_eq!(orbit.max_iter, s.max_iter_for(&b.params));
+        assert!(orbit.max_iter > b.params.max_iter);
+    }
+
+    #[test]
+    fn box_zoom_out_shrinks_zoom() {
+        let mut s = state();
+        let zoom = s.patch.params.zoom;
+        s.handle(&InputAction::BoxZoom {
+            x0: 0.25,
+            y0: 0.25,
+            x1: 0.75,
+            y1: 0.75,
+            zoom_out: true,
+        });
+        assert!(s.patch.params.zoom < zoom);
+    }
+
+    #[test]
+    fn shell_actions_are_not_handled() {
+        let mut s = state();
+        assert!(!s.handle(&InputAction::SaveOverrides));
+        assert!(!s.handle(&InputAction::Quit));
+        assert_eq!(s.current_preset_idx, 0);
+    }
+
+    #[test]
+    fn reset_drops_overrides() {
+        let mut s = state();
+        s.handle(&InputAction::IterationsUp);
+        let preset = s.preset();
+        assert!(s.overrides.capture(preset, &s.patch.params));
+        s.load_preset(preset);
+        assert_ne!(s.patch.params.max_iter, preset.build().params.max_iter);
+        assert!(s.reset());
+        assert_eq!(s.patch.params.max_iter, preset.build().params.max_iter);
+        assert!(!s.reset());
+    }
+
+    #[test]
+    fn view_code_round_trips_across_presets() {
+        let mut s = state();
+        s.handle(&InputAction::LoadPreset(Preset::ShipStorm));
+        s.handle(&InputAction::MouseZoom {
+            norm_x: 0.3,
+            norm_y: 0.6,
+        });
+        let code = s.view_code();
+        let (cx, zoom) = (s.patch.params.center_x, s.patch.params.zoom);
+
+        let mut other = state();
+        other.apply_view_code(&code).unwrap();
+        assert_eq!(other.patch.generator.kind(), GeneratorKind::BurningShip);
+        assert_eq!(other.patch.params.center_x, cx);
+        assert_eq!(other.patch.params.zoom, zoom);
+    }
+
+    /// Tap beats 0–2 of a bar at 120 BPM and wait 0.1 s: the next beat is
+    /// 0.4 s away and the next downbeat 0.9 s.
+    fn tapped(quantize: Quantize) -> AppState {
+        let mut s = state();
+        s.quantize = quantize;
+        for dt in [0.5, 0.5, 0.1] {
+            s.handle(&InputAction::TapTempo);
+            s.tick(dt);
+        }
+        s
+    }
+
+    #[test]
+    fn switch_waits_for_next_beat() {
+        let mut s = tapped(Quantize::Beat);
+        s.handle(&InputAction::LoadPreset(Preset::ShipStorm));
+        assert_eq!(s.pending_preset(), Some(Preset::ShipStorm));
+        assert_eq!(s.current_preset_idx, 0);
+        s.tick(0.3);
+        assert_eq!(s.current_preset_idx, 0);
+        s.tick(0.15);
+        assert_eq!(s.preset(), Preset::ShipStorm);
+        assert_eq!(s.pending_preset(), None);
+    }
+
+    #[test]
+    fn bar_quantize_waits_for_downbeat() {
+        let mut s = tapped(Quantize::Bar);
+        s.handle(&InputAction::CycleNextPreset);
+        s.handle(&InputAction::CycleNextPreset);
+        assert_eq!(s.pending_preset(), Some(Preset::ALL[2]));
+        s.tick(0.7);
+        assert_eq!(s.current_preset_idx, 0);
+        s.tick(0.25);
+        assert_eq!(s.current_preset_idx, 2);
+    }
+
+    #[test]
+    fn no_tempo_or_quantize_off_switches_at_once() {
+        let mut s = state();
+        s.quantize = Quantize::Bar;
+        s.handle(&InputAction::LoadPreset(Preset::ShipStorm));
+        assert_eq!(s.preset(), Preset::ShipStorm);
+
+        let mut s = tapped(Quantize::Off);
+        s.handle(&InputAction::LoadPreset(Preset::ShipStorm));
+        assert_eq!(s.preset(), Preset::ShipStorm);
+    }
+
+    #[test]
+    fn queued_patch_uses_its_own_quantize() {
+        let mut s = tapped(Quantize::Off);
+        let patch = Preset::NoiseBloom.build();
+        s.queue_patch(Preset::NoiseBloom, patch, Quantize::Bar);
+        assert_eq!(s.pending_preset(), Some(Preset::NoiseBloom));
+        s.tick(0.85); // 1.95 s
+        assert_eq!(s.current_preset_idx, 0);
+        s.tick(0.1); // downbeat at 2.0 s
+        assert_eq!(s.preset(), Preset::NoiseBloom);
+        assert_eq!(s.pending_preset(), None);
+    }
+
+    #[test]
+    fn retrigger_action_restarts_lfos_now() {
+        let mut s = state();
+        s.tick(1.25);
+        assert!(s.handle(&InputAction::RetriggerLfos));
+        assert_eq!(s.patch.params.get(LFO_RETRIGGER_KEY), s.patch.params.time);
+    }
+
+    #[test]
+    fn lfos_retrigger_on_each_beat() {
+        let mut s = tapped(Quantize::Off);
+        s.lfo_retrigger = Quantize::Beat;
+        s.tick(0.3);
+        assert!(!s.patch.params.fields.contains_key(LFO_RETRIGGER_KEY));
+        s.tick(0.15);
+        let at = s.patch.params.fields.get(LFO_RETRIGGER_KEY).copied();
+        assert!(at.is_some(), "the beat at 1.5 s retriggers");
+        s.tick(0.1);
+        assert_eq!(s.patch.params.fields.get(LFO_RETRIGGER_KEY).copied(), at);
+    }
+
+    #[test]
+    fn flash_action_spikes_for_flash_frames() {
+        let mut s = state();
+        s.load_preset(Preset::EchoChamber);
+        s.flash_frames = 2;
+        assert!(s.handle(&InputAction::Flash));
+        s.tick(0.016);
+        s.tick(0.016);
+        assert_eq!(s.patch.params.get("brightness_amount"), 0.5);
+        s.tick(0.016);
+        assert!(s.patch.params.get("brightness_amount") <= 0.1);
+    }
+
+    #[test]
+    fn flashes_fire_on_each_bar() {
+        let mut s = tapped(Quantize::Off);
+        s.load_preset(Preset::EchoChamber);
+        s.flash_every = Quantize::Bar;
+        s.tick(0.45); // beat at 1.5 s: not a downbeat
+        assert!(s.patch.audio.routes.is_empty());
+        s.tick(0.5); // downbeat at 2.0 s
+        assert_eq!(s.patch.params.get("brightness_amount"), 0.5);
+    }
+
+    // --- Overlay ---
+
+    #[test]
+    fn overlay_composites_last_before_present() {
+        let mut s = cued(0.5);
+        s.overlay = Some(OverlayLayout::default());
+        let gpu = frame(&s);
+        let n = gpu.calls.len();
+        assert!(matches!(gpu.calls[n - 3], GpuCall::Crossfade(_)));
+        assert!(matches!(gpu.calls[n - 2], GpuCall::Overlay(_)));
+        assert!(matches!(gpu.calls[n - 1], GpuCall::Present(_)));
+        assert!(!frame(&state())
+            .calls
+            .iter()
+            .any(|c| matches!(c, GpuCall::Overlay(_))));
+    }
+
+    #[test]
+    fn overlay_pulses_with_the_tapped_tempo() {
+        let mut s = tapped(Quantize::Off); // 0.1 s past a beat at 120 BPM
+        s.overlay = Some(OverlayLayout {
+            scale: 0.1,
+            pulse: 1.0,
+            ..Default::default()
+        });
+        let gpu = frame(&s);
+        let scale = gpu
+            .calls
+            .iter()
+            .find_map(|c| match c {
+                GpuCall::Overlay(p) => Some(p.scale),
+                _ => None,
+            })
+            .unwrap();
+        // Phase 0.2: swell (1 − 0.2)² = 0.64.
+        assert!((scale - 0.164).abs() < 1e-4, "{scale}");
+    }
+
+    // --- Decks ---
+
+    fn cued(crossfade: f32) -> AppState {
+        let mut s = state();
+        assert!(s.handle(&InputAction::LoadDeckB(Preset::ShipStorm)));
+        s.set_crossfade(crossfade);
+        s
+    }
+
+    fn generators(gpu: &MockBackend) -> Vec<GeneratorKind> {
+        gpu.calls
+            .iter()
+            .filter_map(|c| match c {
+                GpuCall::Generator { kind, .. } => Some(*kind),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn crossfade_renders_both_decks_then_blends() {
+        let gpu = frame(&cued(0.25));
+        assert_eq!(
+            generators(&gpu),
+            [GeneratorKind::Mandelbrot, GeneratorKind::BurningShip]
+        );
+        assert!(matches!(
+            gpu.calls.as_slice(),
+            [
+                GpuCall::Generator { .. },
+                GpuCall::Chain(_),
+                GpuCall::SelectDeck(Deck::B),
+                GpuCall::Generator { .. },
+                GpuCall::Chain(_),
+                GpuCall::Crossfade(0.25),
+                GpuCall::Present(_),
+            ]
+        ));
+    }
+
+    #[test]
+    fn fader_at_either_end_renders_one_deck() {
+        let gpu = frame(&cued(0.0));
+        assert_eq!(generators(&gpu), [GeneratorKind::Mandelbrot]);
+        assert!(!gpu
+            .calls
+            .iter()
+            .any(|c| matches!(c, GpuCall::SelectDeck(_))));
+
+        let gpu = frame(&cued(1.0));
+        assert_eq!(generators(&gpu), [GeneratorKind::BurningShip]);
+        assert!(matches!(gpu.calls[0], GpuCall::SelectDeck(Deck::B)));
+        assert!(!gpu.calls.iter().any(|c| matches!(c, GpuCall::Crossfade(_))));
+    }
+
+    #[test]
+    fn take_deck_b_keeps_it_running() {
+        let mut s = cued(0.8);
+        s.tick(0.5);
+        s.tick(0.5);
+        assert!(s.handle(&InputAction::TakeDeckB));
+        assert_eq!(s.preset(), Preset::ShipStorm);
+        assert_eq!(s.patch.params.time, 1.0, "deck B's clock carries on");
+        assert_eq!(s.crossfade, 0.0);
+        assert!(s.deck_b.is_none());
+        assert!(!s.take_deck_b(), "nothing left to take");
+    }
+
+    #[test]
+    fn closing_deck_b_returns_to_deck_a() {
+        let mut s = cued(0.5);
+        assert!(s.handle(&InputAction::CloseDeckB));
+        assert!(s.deck_b.is_none());
+        assert_eq!(generators(&frame(&s)), [GeneratorKind::Mandelbrot]);
+    }
+
+    #[test]
+    fn macro_slider_moves_its_targets_on_tick() {
+        let mut s = state();
+        s.load_preset(Preset::NoiseBloom);
+        s.set_macro("detail", 1.0);
+        s.set_macro("no_such_macro", 1.0);
+        s.tick(0.016);
+        assert_eq!(s.patch.params.get("noise_scale"), 3.0);
+        assert_eq!(s.patch.params.get("noise_octaves"), 10.0);
+        assert!(!s.patch.params.fields.contains_key("macro_no_such_macro"));
+    }
+
+    #[test]
+    fn trigger_is_quantized() {
+        let mut s = tapped(Quantize::Beat);
+        let key = fractal_core::audio::ONSET_TIME_KEY;
+        let before = s.patch.params.fields.get(key).copied();
+        s.trigger(0.7);
+        assert_eq!(s.patch.params.fields.get(key).copied(), before);
+        s.tick(0.45);
+        assert_eq!(
+            s.patch.params.get(fractal_core::audio::ONSET_STRENGTH_KEY),
+            0.7
+        );
+        assert_ne!(s.patch.params.fields.get(key).copied(), before);
+    }
+
+    #[test]
+    fn auto_gain_normalises_bands_before_routes() {
+        let key = fractal_core::audio::AudioBand::Bass.key();
+        let mut s = state();
+        s.patch.params.set(key, 0.2);
+        s.tick(0.1);
+        assert_eq!(s.patch.params.get(key), 0.2);
+
+        s.set_auto_gain(true);
+        s.patch.params.set(key, 0.2);
+        s.tick(0.1);
+        assert_eq!(s.patch.params.get(key), 1.0);
+        s.patch.params.set(key, 0.1);
+        s.tick(0.1);
+        assert_eq!(s.patch.params.get(key), 0.5);
+
+        // Learned peaks carry across a preset switch.
+        s.handle(&InputAction::LoadPreset(Preset::ShipStorm));
+        s.patch.params.set(key, 0.1);
+        s.tick(0.1);
+        assert_eq!(s.patch.params.get(key), 0.5);
+    }
+
+    #[test]
+    fn midi_knob_wins_over_modulators() {
+        // ShipStorm sweeps motion_blur_angle with an LFO.
+        let mut s = state();
+        s.handle(&InputAction::LoadPreset(Preset::ShipStorm));
+        let key = "motion_blur_angle";
+        s.midi.toggle_learn(key);
+        assert!(s.midi_message(&[0xB0, 16, 0]).is_some());
+        s.midi_message(&[0xB0, 16, 0]);
+        s.tick(0.5);
+        let binding = s.midi.map.binding_for(key).unwrap();
+        assert_eq!(s.patch.params.get(key), binding.min);
+        // Other messages are ignored.
+        assert_eq!(s.midi_message(&[0x90, 60, 100]), None);
+    }
+
+    #[test]
+    fn window_title_follows_the_patchs_meta() {
+        let mut s = state();
+        assert_eq!(
+            s.window_title(Language::English),
+            "Classic Mandelbrot — Fractal Explorer"
+        );
+        s.patch.meta.title = Some("Bulb".into());
+        s.patch.meta.author = Some("cmh".into());
+        assert_eq!(
+            s.window_title(Language::German),
+            "Bulb von cmh — Fraktal-Explorer"
+        );
+        s.handle(&InputAction::LoadPreset(Preset::PopArt));
+        assert_eq!(
+            s.window_title(Language::English),
+            "Pop Art — Fractal Explorer"
+        );
+    }
+
+    #[test]
+    fn pan_moves_against_the_drag() {
+        let mut s = state();
+        let cx = s.patch.params.center_x;
+        s.pan_pixels((100.0, 0.0));
+        assert!(s.patch.params.center_x < cx);
+    }
+}
@@ -0,0 +1,580 @@
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use fractal_core::snapshot::{ParamsReader, ParamsSnapshot};
+
+// ---------------------------------------------------------------------------
+// Preview stream — MJPEG over HTTP for remote monitoring from a browser
+// ---------------------------------------------------------------------------
+
+/// Port offered by default in the settings panel.
+pub const DEFAULT_PORT: u16 = 8090;
+
+/// Lowest port offered in the settings panel (avoids privileged ports).
+pub const MIN_PORT: u16 = 1024;
+
+/// Frames per second sent to viewers.
+pub const STREAM_FPS: f32 = 10.0;
+
+/// Width of streamed frames; the height follows the window's aspect ratio.
+pub const STREAM_WIDTH: u32 = 480;
+
+const JPEG_QUALITY: u8 = 70;
+
+/// Multipart boundary between frames of `/stream`.
+const BOUNDARY: &str = "frame";
+
+/// Connections served at once; more are turned away with a 503, so a flood
+/// of clients can't exhaust threads.
+pub const MAX_CLIENTS: usize = 8;
+
+/// Requests with a longer head than this are dropped.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// How often idle threads check whether the server is shutting down.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Socket timeout for slow or vanished clients.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long `/frame.jpg` waits for a fresh frame before serving the last one.
+const SNAPSHOT_WAIT: Duration = Duration::from_secs(2);
+
+const INDEX_HTML: &str = "<!DOCTYPE html>\n\
+<html><head><title>Fractal Explorer</title>\n\
+<style>body{margin:0;background:#000;display:flex;height:100vh}\
+img{margin:auto;max-width:100%;max-height:100%}</style></head>\n\
+<body><img src=\"/stream\" alt=\"live output\"></body></html>\n";
+
+/// Encode tightly packed RGBA8 as a baseline JPEG.
+pub fn encode_jpeg(rgba: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let (w, h) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => return Err(format!("{width}×{height} is too large for JPEG")),
+    };
+    let mut out = Vec::new();
+    jpeg_encoder::Encoder::new(&mut out, JPEG_QUALITY)
+        .encode(rgba, w, h, jpeg_encoder::ColorType::Rgba)
+        .map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// Path of a `GET` request line, without any query string.
+fn request_path(head: &str) -> Option<&str> {
+    let mut parts = head.lines().next()?.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    let target = parts.next()?;
+    Some(target.split('?').next().unwrap_or(target))
+}
+
+/// `/params.json`: the view and every named field of the latest frame.
+fn params_json(snapshot: &ParamsSnapshot) -> Vec<u8> {
+    let p = &snapshot.params;
+    serde_json::to_vec(&serde_json::json!({
+        "seq": snapshot.seq,
+        "frame": p.frame,
+        "time": p.time,
+        "center": [p.center_x, p.center_y],
+        "zoom": p.zoom,
+        "max_iter": p.max_iter,
+        "fields": p.fields,
+    }))
+    .unwrap_or_default()
+}
+
+/// Headers introducing one JPEG part of the multipart stream.
+fn part_header(len: usize) -> String {
+    format!("--{BOUNDARY}\r\nContent-Type: image/jpeg\r\nContent-Length: {len}\r\n\r\n")
+}
+
+/// Latest published frame; `seq` counts publishes so waiters spot new ones.
+#[derive(Default)]
+struct Latest {
+    seq: u64,
+    jpeg: Option<Arc<Vec<u8>>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    latest: Mutex<Latest>,
+    fresh: Condvar,
+    /// Connections waiting for frames; the app only captures while non-zero.
+    viewers: AtomicUsize,
+    /// Connections with a thread, at most [`MAX_CLIENTS`].
+    clients: AtomicUsize,
+    stop: AtomicBool,
+}
+
+impl Shared {
+    /// Block until a frame newer than `after` is published.  Returns `None`
+    /// on shutdown or once `deadline` passes.
+    fn wait_frame(&self, after: u64, deadline: Option<Instant>) -> Option<(u64, Arc<Vec<u8>>)> {
+        let mut latest = self.latest.lock().unwrap();
+        loop {
+            if self.stop.load(Ordering::Relaxed) {
+                return None;
+            }
+            if latest.seq > after {
+                if let Some(jpeg) = &latest.jpeg {
+                    return Some((latest.seq, Arc::clone(jpeg)));
+                }
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return None;
+            }
+            latest = self.fresh.wait_timeout(latest, POLL_INTERVAL).unwrap().0;
+        }
+    }
+}
+
+/// Counts a connection as a viewer for as long as it lives.
+struct ViewerGuard<'a>(&'a AtomicUsize);
+
+impl<'a> ViewerGuard<'a> {
+    fn new(viewers: &'a AtomicUsize) -> Self {
+        viewers.fetch_add(1, Ordering::Relaxed);
+        Self(viewers)
+    }
+}
+
+impl Drop for ViewerGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Holds one of the [`MAX_CLIENTS`] connection slots until dropped.
+struct ClientSlot(Arc<Shared>);
+
+impl ClientSlot {
+    /// Take a slot, unless all are in use.
+    fn take(shared: &Arc<Shared>) -> Option<Self> {
+        shared
+            .clients
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                (n < MAX_CLIENTS).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Self(Arc::clone(shared)))
+    }
+}
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.clients.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// StreamServer — accept thread plus a thread per connection, up to a cap
+// ---------------------------------------------------------------------------
+
+/// Serves `/` (a viewer page), `/stream` (MJPEG), `/frame.jpg` (single
+/// snapshot) and `/params.json` (the latest frame's parameters).  Shuts
+/// down when dropped.
+pub struct StreamServer {
+    shared: Arc<Shared>,
+    addr: SocketAddr,
+    accept: Option<JoinHandle<()>>,
+}
+
+impl StreamServer {
+    /// Listen on `port` of this machine only or, with `lan`, of every
+    /// interface, so the output can be watched from other machines.
+    /// `params` feeds `/params.json`.
+    pub fn start(port: u16, lan: bool, params: ParamsReader) -> io::Result<Self> {
+        Self::bind(SocketAddr::from((bind_ip(lan), port)), params)
+    }
+
+    fn bind(addr: SocketAddr, params: ParamsReader) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+        let accept = {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name("stream-accept".into())
+                .spawn(move || accept_loop(listener, shared, params))?
+        };
+        log::info!("Preview stream listening on {addr}");
+        Ok(Self {
+            shared,
+            addr,
+            accept: Some(accept),
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// Whether other machines can connect.
+    pub fn lan(&self) -> bool {
+        self.addr.ip() == bind_ip(true)
+    }
+
+    /// Connections currently waiting for frames.
+    pub fn viewers(&self) -> usize {
+        self.shared.viewers.load(Ordering::Relaxed)
+    }
+
+    /// Replace the frame sent to viewers.
+    pub fn publish(&self, jpeg: Vec<u8>) {
+        let mut latest = self.shared.latest.lock().unwrap();
+        latest.seq += 1;
+        latest.jpeg = Some(Arc::new(jpeg));
+        self.shared.fresh.notify_all();
+    }
+}
+
+impl Drop for StreamServer {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        self.shared.fresh.notify_all();
+        if let Some(accept) = self.accept.take() {
+            let _ = accept.join();
+        }
+        log::info!("Preview stream on {} stopped", self.addr);
+    }
+}
+
+/// Loopback, or every interface with `lan`.
+fn bind_ip(lan: bool) -> IpAddr {
+    if lan {
+        Ipv4Addr::UNSPECIFIED.into()
+    } else {
+        Ipv4Addr::LOCALHOST.into()
+    }
+}
+
+fn accept_loop(listener: TcpListener, shared: Arc<Shared>, params: ParamsReader) {
+    while !shared.stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                let Some(slot) = ClientSlot::take(&shared) else {
+                    log::debug!("preview client {peer} turned away: {MAX_CLIENTS} connected");
+                    let _ = turn_away(stream);
+                    continue;
+                };
+                let params = params.clone();
+                let spawned =
+                    thread::Builder::new()
+                        .name("stream-client".into())
+                        .spawn(move || {
+                            if let Err(e) = serve(stream, &slot.0, &params) {
+                                log::debug!("preview client {peer}: {e}");
+                            }
+                        });
+                if let Err(e) = spawned {
+                    log::warn!("failed to spawn preview client thread: {e}");
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                log::warn!("preview stream accept failed: {e}");
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// Answer a connection past [`MAX_CLIENTS`] on the accept thread.  The
+/// request is read, waiting at most [`POLL_INTERVAL`], only so closing the
+/// socket doesn't reset it before the client sees the 503.
+fn turn_away(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    stream.set_write_timeout(Some(POLL_INTERVAL))?;
+    let _ = read_head(&mut stream);
+    respond(
+        &mut stream,
+        "503 Service Unavailable",
+        "text/plain",
+        b"too many viewers\n",
+    )
+}
+
+/// Answer one request.  Streams until the client hangs up or the server stops.
+fn serve(mut stream: TcpStream, shared: &Shared, params: &ParamsReader) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let head = read_head(&mut stream)?;
+    match request_path(&head) {
+        Some("/") => respond(&mut stream, "200 OK", "text/html", INDEX_HTML.as_bytes()),
+        Some("/stream") => {
+            let _viewer = ViewerGuard::new(&shared.viewers);
+            stream.write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\n\
+                     Content-Type: multipart/x-mixed-replace; boundary={BOUNDARY}\r\n\
+                     Cache-Control: no-cache\r\nConnection: close\r\n\r\n"
+                )
+                .as_bytes(),
+            )?;
+            let mut seq = 0;
+            while let Some((next, jpeg)) = shared.wait_frame(seq, None) {
+                seq = next;
+                stream.write_all(part_header(jpeg.len()).as_bytes())?;
+                stream.write_all(&jpeg)?;
+                stream.write_all(b"\r\n")?;
+                stream.flush()?;
+            }
+            Ok(())
+        }
+        Some("/frame.jpg") => {
+            // Ask the app for a fresh capture; fall back to the last one.
+            let current = {
+                let _viewer = ViewerGuard::new(&shared.viewers);
+                let seq = shared.latest.lock().unwrap().seq;
+                shared.wait_frame(seq, Some(Instant::now() + SNAPSHOT_WAIT))
+            };
+            let jpeg = current
+                .map(|(_, jpeg)| jpeg)
+                .or_else(|| shared.latest.lock().unwrap().jpeg.clone());
+            match jpeg {
+                Some(jpeg) => respond(&mut stream, "200 OK", "image/jpeg", &jpeg),
+                None => respond(
+                    &mut stream,
+                    "503 Service Unavailable",
+                    "text/plain",
+                    b"no frame yet\n",
+                ),
+            }
+        }
+        Some("/params.json") => respond(
+            &mut stream,
+            "200 OK",
+            "application/json",
+            &params_json(&params.load()),
+        ),
+        Some(_) => respond(&mut stream, "404 Not Found", "text/plain", b"not found\n"),
+        None => respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"only GET is supported\n",
+        ),
+    }
+}
+
+/// Read up to the blank line ending the request head.
+fn read_head(stream: &mut TcpStream) -> io::Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_HEAD {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too long",
+            ));
+        }
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> io::Result<()> {
+    stream.write_all(
+        format!(
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\n\
+             Content-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+            body.len()
+        )
+        .as_bytes(),
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fractal_core::snapshot::ParamsPublisher;
+
+    /// Server on an ephemeral loopback port.
+    fn local_server() -> StreamServer {
+        local_server_with(&ParamsPublisher::new())
+    }
+
+    fn local_server_with(params: &ParamsPublisher) -> StreamServer {
+        StreamServer::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)), params.reader()).unwrap()
+    }
+
+    fn get(server: &StreamServer, path: &str) -> TcpStream {
+        let mut s = TcpStream::connect((Ipv4Addr::LOCALHOST, server.port())).unwrap();
+        s.set_read_timeout(Some(IO_TIMEOUT)).unwrap();
+        write!(s, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        s
+    }
+
+    fn wait_for_viewers(server: &StreamServer, n: usize) {
+        let deadline = Instant::now() + IO_TIMEOUT;
+        while server.viewers() != n {
+            assert!(
+                Instant::now() < deadline,
+                "viewer count stuck at {}",
+                server.viewers()
+            );
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn parses_get_paths() {
+        assert_eq!(request_path("GET / HTTP/1.1\r\nHost: x\r\n"), Some("/"));
+        assert_eq!(
+            request_path("GET /stream?t=1 HTTP/1.1\r\n"),
+            Some("/stream")
+        );
+        assert_eq!(request_path("POST / HTTP/1.1\r\n"), None);
+        assert_eq!(request_path(""), None);
+    }
+
+    #[test]
+    fn part_header_announces_length() {
+        assert_eq!(
+            part_header(42),
+            "--frame\r\nContent-Type: image/jpeg\r\nContent-Length: 42\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn encodes_jpeg() {
+        let rgba = [200u8, 30, 60, 255].repeat(16 * 8);
+        let jpeg = encode_jpeg(&rgba, 16, 8).unwrap();
+        assert_eq!(&jpeg[..2], &[0xff, 0xd8]);
+        assert_eq!(&jpeg[jpeg.len() - 2..], &[0xff, 0xd9]);
+        assert!(encode_jpeg(&rgba, 70_000, 1).is_err());
+    }
+
+    #[test]
+    fn serves_index_and_404() {
+        let server = local_server();
+        let mut body = String::new();
+        get(&server, "/").read_to_string(&mut body).unwrap();
+        assert!(body.starts_with("HTTP/1.1 200 OK"));
+        assert!(body.contains("<img src=\"/stream\""));
+
+        body.clear();
+        get(&server, "/nope").read_to_string(&mut body).unwrap();
+        assert!(body.starts_with("HTTP/1.1 404"));
+    }
+
+    #[test]
+    fn serves_the_latest_params() {
+        let mut publisher = ParamsPublisher::new();
+        let server = local_server_with(&publisher);
+        let mut p = fractal_core::Params {
+            zoom: 4.0,
+            ..Default::default()
+        };
+        p.set("hue_shift_amount", 1.5);
+        publisher.publish(&p);
+
+        let mut response = String::new();
+        get(&server, "/params.json")
+            .read_to_string(&mut response)
+            .unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("application/json"));
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["seq"], 1);
+        assert_eq!(json["zoom"], 4.0);
+        assert_eq!(json["fields"]["hue_shift_amount"], 1.5);
+    }
+
+    #[test]
+    fn snapshot_waits_for_a_fresh_frame() {
+        let server = local_server();
+        let mut s = get(&server, "/frame.jpg");
+        wait_for_viewers(&server, 1);
+        server.publish(vec![0xff, 0xd8, 1, 2, 0xff, 0xd9]);
+        let mut body = Vec::new();
+        s.read_to_end(&mut body).unwrap();
+        assert!(body.starts_with(b"HTTP/1.1 200 OK"));
+        assert!(body.ends_with(&[0xff, 0xd8, 1, 2, 0xff, 0xd9]));
+        wait_for_viewers(&server, 0);
+    }
+
+    #[test]
+    fn binds_loopback_unless_asked_for_the_lan() {
+        let publisher = ParamsPublisher::new();
+        let server = StreamServer::start(0, false, publisher.reader()).unwrap();
+        assert!(!server.lan());
+        assert_eq!(server.addr.ip(), IpAddr::from(Ipv4Addr::LOCALHOST));
+    }
+
+    #[test]
+    fn connections_past_the_cap_are_turned_away() {
+        let server = local_server();
+        let streams: Vec<_> = (0..MAX_CLIENTS).map(|_| get(&server, "/stream")).collect();
+        wait_for_viewers(&server, MAX_CLIENTS);
+        let mut body = String::new();
+        get(&server, "/").read_to_string(&mut body).unwrap();
+        assert!(body.starts_with("HTTP/1.1 503"), "{body}");
+        assert_eq!(server.viewers(), MAX_CLIENTS);
+
+        // A slot frees up once a viewer leaves.
+        drop(streams);
+        let deadline = Instant::now() + IO_TIMEOUT;
+        while server.shared.clients.load(Ordering::Relaxed) > 0 {
+            assert!(Instant::now() < deadline, "client slots never freed");
+            server.publish(b"next".to_vec());
+            thread::sleep(Duration::from_millis(10));
+        }
+        body.clear();
+        get(&server, "/").read_to_string(&mut body).unwrap();
+        assert!(body.starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[test]
+    fn stream_sends_published_frames() {
+        let server = local_server();
+        let mut s = get(&server, "/stream");
+        wait_for_viewers(&server, 1);
+        server.publish(b"first".to_vec());
+
+        let mut received = Vec::new();
+        let mut buf = [0u8; 256];
+        while !received.ends_with(b"first\r\n") {
+            let n = s.read(&mut buf).unwrap();
+            assert!(n > 0, "stream closed early");
+            received.extend_from_slice(&buf[..n]);
+        }
+        let text = String::from_utf8_lossy(&received);
+        assert!(text.contains("multipart/x-mixed-replace; boundary=frame"));
+        assert!(text.contains(&part_header(5)));
+
+        // A hung-up viewer is noticed on the next write.
+        drop(s);
+        let deadline = Instant::now() + IO_TIMEOUT;
+        while server.viewers() > 0 {
+            assert!(Instant::now() < deadline, "viewer never dropped");
+            server.publish(b"next".to_vec());
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+// ---------------------------------------------------------------------------
+// PNG I/O for cached preset thumbnails (square, 8-bit sRGB RGBA)
+// ---------------------------------------------------------------------------
+
+/// Write `rgba` (`size * size * 4` bytes) as a PNG, creating parent
+/// directories as needed.
+pub fn save_png(path: &Path, size: u32, rgba: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), size, size);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(rgba).map_err(io::Error::other)
+}
+
+/// Load a cached thumbnail.  Returns `None` if the file is missing, not a
+/// PNG, or not a `size`×`size` RGBA8 image — the caller re-renders it.
+pub fn load_png(path: &Path, size: u32) -> Option<Vec<u8>> {
+    let decoder = png::Decoder::new(BufReader::new(File::open(path).ok()?));
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).ok()?;
+    let ok = info.width == size
+        && info.height == size
+        && info.color_type == png::ColorType::Rgba
+        && info.bit_depth == png::BitDepth::Eight;
+    ok.then(|| {
+        buf.truncate(info.buffer_size());
+        buf
+    })
+}
+
+// ---------------------------------------------------------------------------
+// ThumbnailTextures — the thumbnails uploaded into one egui context
+// ---------------------------------------------------------------------------
+//
+// Textures belong to the egui context that created them, so each window
+// showing the preset browser keeps its own set, re-uploaded whenever the
+// app bumps its thumbnail generation.
+
+#[derive(Default)]
+pub struct ThumbnailTextures {
+    generation: Option<u64>,
+    handles: Vec<Option<egui::TextureHandle>>,
+}
+
+impl ThumbnailTextures {
+    /// Upload `images` into `ctx` if `generation` changed since the last
+    /// call, and return the texture ids (`None` where no image exists).
+    pub fn sync(
+        &mut self,
+        ctx: &egui::Context,
+        images: &[Option<egui::ColorImage>],
+        generation: u64,
+    ) -> Vec<Option<egui::TextureId>> {
+        if self.generation != Some(generation) {
+            self.handles = images
+                .iter()
+                .enumerate()
+                .map(|(i, image)| {
+                    image.as_ref().map(|image| {
+                        ctx.load_texture(
+                            format!("thumb-{i}"),
+                            image.clone(),
+                            egui::TextureOptions::LINEAR,
+                        )
+                    })
+                })
+                .collect();
+            self.generation = Some(generation);
+        }
+        self.handles
+            .iter()
+            .map(|h| h.as_ref().map(|h| h.id()))
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir()
+            .join(format!("fractal-thumbs-{}", std::process::id()))
+            .join(name)
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let path = temp_path("round_trip.png");
+        let rgba: Vec<u8> = (0..4 * 4 * 4).map(|i| i as u8).collect();
+        save_png(&path, 4, &rgba).unwrap();
+        assert_eq!(load_png(&path, 4), Some(rgba));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn load_rejects_wrong_size() {
+        let path = temp_path("wrong_size.png");
+        save_png(&path, 2, &[0; 2 * 2 * 4]).unwrap();
+        assert_eq!(load_png(&path, 4), None);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn load_missing_is_none() {
+        assert_eq!(load_png(&temp_path("missing.png"), 4), None);
+    }
+
+    #[test]
+    fn textures_reupload_only_on_new_generation() {
+        let ctx = egui::Context::default();
+        let image = egui::ColorImage::new([2, 2], egui::Color32::RED);
+        let images = vec![Some(image), None];
+        let mut textures = ThumbnailTextures::default();
+
+        let first = textures.sync(&ctx, &images, 0);
+        assert!(first[0].is_some());
+        assert_eq!(first[1], None);
+        assert_eq!(textures.sync(&ctx, &images, 0), first);
+        assert_ne!(textures.sync(&ctx, &images, 1)[0], first[0]);
+    }
+}
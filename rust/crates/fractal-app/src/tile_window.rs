@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use fractal_core::tiling::Tile;
+use fractal_gpu::renderer::OutputPass;
+use winit::window::{Window, WindowId};
+
+// ---------------------------------------------------------------------------
+// TileWindow — an output window showing one tile of the canvas
+// ---------------------------------------------------------------------------
+//
+// With a tile layout loaded the patch renders once at the canvas size, and
+// each tile window draws its crop of that frame through its own output pass,
+// so every projector or LED processor gets its own warp and edge blend.  The
+// windows share the device and queue with the main window, which keeps the
+// HUD and shows the whole canvas.
+
+pub struct TileWindow {
+    window: Arc<Window>,
+    /// Which tile of the layout it shows.
+    index: usize,
+    surface: wgpu::Surface<'static>,
+    config: wgpu::SurfaceConfiguration,
+    output: OutputPass,
+}
+
+impl TileWindow {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        window: Arc<Window>,
+        index: usize,
+        instance: &wgpu::Instance,
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        tile: &Tile,
+        canvas: [u32; 2],
+    ) -> Self {
+        let surface = instance
+            .create_surface(Arc::clone(&window))
+            .expect("failed to create tile window surface");
+        let caps = surface.get_capabilities(adapter);
+        let format = caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(caps.formats[0]);
+        let size = window.inner_size();
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: caps.alpha_modes[0],
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        surface.configure(device, &config);
+
+        let mut output = OutputPass::new(device, format);
+        output.set_correction(device, queue, &tile.correction);
+        output.set_crop(queue, tile.crop(canvas));
+
+        Self {
+            window,
+            index,
+            surface,
+            config,
+            output,
+        }
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    /// Release the surface, keeping the window and its tile index for a
+    /// new one on a rebuilt device.
+    pub fn into_parts(self) -> (usize, Arc<Window>) {
+        (self.index, self.window)
+    }
+
+    pub fn output(&self) -> &OutputPass {
+        &self.output
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.config.width = width;
+        self.config.height = height;
+        self.surface.configure(device, &self.config);
+    }
+
+    /// The next swapchain texture, or `None` to skip this window for a
+    /// frame (a lost surface is reconfigured for the next one).
+    pub fn acquire(&mut self, device: &wgpu::Device) -> Option<wgpu::SurfaceTexture> {
+        match self.surface.get_current_texture() {
+            Ok(texture) => Some(texture),
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                self.surface.configure(device, &self.config);
+                None
+            }
+            Err(e) => {
+                log::warn!("tile window: {e:?}");
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::VecDeque;
+
+/// Seconds a toast stays on screen.
+pub const TOAST_LIFETIME: f32 = 4.0;
+
+/// Seconds over which a toast fades out at the end of its life.
+const FADE_TIME: f32 = 0.5;
+
+/// Oldest toasts are dropped beyond this many.
+const MAX_TOASTS: usize = 5;
+
+// ---------------------------------------------------------------------------
+// Toast — one short on-screen notification
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub level: ToastLevel,
+    pub text: String,
+    /// Toasts sharing a tag replace each other instead of stacking (e.g. a
+    /// shader that fails to compile on every keystroke).
+    pub tag: Option<&'static str>,
+    /// Seconds left before the toast disappears.
+    pub remaining: f32,
+}
+
+impl Toast {
+    /// Opacity in \[0, 1\]: fully opaque until the last [`FADE_TIME`] seconds.
+    pub fn alpha(&self) -> f32 {
+        (self.remaining / FADE_TIME).clamp(0.0, 1.0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Toasts — the queue, rendered bottom-right by egui
+// ---------------------------------------------------------------------------
+
+/// Short-lived user-facing notifications, so actions give feedback beyond
+/// the console log.
+#[derive(Default)]
+pub struct Toasts {
+    queue: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, level: ToastLevel, text: impl Into<String>, tag: Option<&'static str>) {
+        if tag.is_some() {
+            self.queue.retain(|t| t.tag != tag);
+        }
+        if self.queue.len() == MAX_TOASTS {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(Toast {
+            level,
+            text: text.into(),
+            tag,
+            remaining: TOAST_LIFETIME,
+        });
+    }
+
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(ToastLevel::Info, text, None);
+    }
+
+    pub fn warn(&mut self, text: impl Into<String>) {
+        self.push(ToastLevel::Warning, text, None);
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push(ToastLevel::Error, text, None);
+    }
+
+    /// Remove any toast carrying `tag` (e.g. once the problem is fixed).
+    pub fn dismiss(&mut self, tag: &'static str) {
+        self.queue.retain(|t| t.tag != Some(tag));
+    }
+
+    /// Age every toast by `dt` seconds and drop expired ones.
+    pub fn tick(&mut self, dt: f32) {
+        for t in &mut self.queue {
+            t.remaining -= dt;
+        }
+        self.queue.retain(|t| t.remaining > 0.0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.queue.iter()
+    }
+
+    /// Draw the queue stacked in the bottom-right corner, newest at the bottom.
+    pub fn show(&self, ctx: &egui::Context) {
+        if self.queue.is_empty() {
+            return;
+        }
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -12.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                for toast in self.iter() {
+                    let a = toast.alpha();
+                    let accent = match toast.level {
+                        ToastLevel::Info => egui::Color32::from_rgb(120, 180, 240),
+                        ToastLevel::Warning => egui::Color32::from_rgb(230, 180, 60),
+                        ToastLevel::Error => egui::Color32::from_rgb(240, 90, 80),
+                    };
+                    egui::Frame::none()
+                        .fill(egui::Color32::from_black_alpha((210.0 * a) as u8))
+                        .stroke(egui::Stroke::new(1.0, accent.gamma_multiply(a)))
+                        .rounding(4.0)
+                        .inner_margin(8.0)
+                        .show(ui, |ui| {
+                            ui.colored_label(
+                                egui::Color32::from_gray(230).gamma_multiply(a),
+                                &toast.text,
+                            );
+                        });
+                    ui.add_space(4.0);
+                }
+            });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toasts_expire_after_lifetime() {
+        let mut t = Toasts::new();
+        t.info("saved");
+        t.tick(TOAST_LIFETIME - 0.1);
+        assert_eq!(t.iter().count(), 1);
+        t.tick(0.2);
+        assert_eq!(t.iter().count(), 0);
+    }
+
+    #[test]
+    fn alpha_fades_at_end_of_life() {
+        let mut t = Toasts::new();
+        t.info("x");
+        assert_eq!(t.iter().next().unwrap().alpha(), 1.0);
+        t.tick(TOAST_LIFETIME - FADE_TIME / 2.0);
+        let a = t.iter().next().unwrap().alpha();
+        assert!((a - 0.5).abs() < 1e-4, "alpha = {a}");
+    }
+
+    #[test]
+    fn queue_is_capped_dropping_oldest() {
+        let mut t = Toasts::new();
+        for i in 0..MAX_TOASTS + 2 {
+            t.info(format!("{i}"));
+        }
+        let texts: Vec<_> = t.iter().map(|t| t.text.clone()).collect();
+        assert_eq!(texts.len(), MAX_TOASTS);
+        assert_eq!(texts[0], "2");
+    }
+
+    #[test]
+    fn tagged_toasts_replace_each_other() {
+        let mut t = Toasts::new();
+        t.info("unrelated");
+        t.push(ToastLevel::Error, "error 1", Some("shader"));
+        t.push(ToastLevel::Error, "error 2", Some("shader"));
+        let texts: Vec<_> = t.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["unrelated", "error 2"]);
+    }
+
+    #[test]
+    fn dismiss_removes_tagged_only() {
+        let mut t = Toasts::new();
+        t.warn("keep");
+        t.push(ToastLevel::Error, "gone", Some("shader"));
+        t.dismiss("shader");
+        assert_eq!(t.iter().count(), 1);
+        assert_eq!(t.iter().next().unwrap().level, ToastLevel::Warning);
+    }
+}
@@ -0,0 +1,302 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// ---------------------------------------------------------------------------
+// Video input — webcam or file frames decoded by an ffmpeg subprocess
+// ---------------------------------------------------------------------------
+
+/// Every source is scaled and letterboxed to this size before upload.
+pub const VIDEO_WIDTH: u32 = 960;
+pub const VIDEO_HEIGHT: u32 = 540;
+
+const FRAME_BYTES: usize = (VIDEO_WIDTH * VIDEO_HEIGHT * 4) as usize;
+
+/// Whether `source` is a V4L2 capture device such as `/dev/video0`.
+fn is_capture_device(source: &Path) -> bool {
+    source.to_str().is_some_and(|s| s.starts_with("/dev/video"))
+}
+
+/// Arguments for ffmpeg decoding `source` to raw RGBA on stdout.  Capture
+/// devices are read live; files play in real time and loop.
+pub fn ffmpeg_args(source: &Path) -> Vec<String> {
+    let mut args: Vec<String> = ["-hide_banner", "-loglevel", "error"]
+        .map(String::from)
+        .into();
+    if is_capture_device(source) {
+        args.extend(["-f", "v4l2"].map(String::from));
+    } else {
+        args.extend(["-re", "-stream_loop", "-1"].map(String::from));
+    }
+    args.push("-i".into());
+    args.push(source.display().to_string());
+    args.push("-vf".into());
+    args.push(format!(
+        "scale={VIDEO_WIDTH}:{VIDEO_HEIGHT}:force_original_aspect_ratio=decrease,\
+         pad={VIDEO_WIDTH}:{VIDEO_HEIGHT}:(ow-iw)/2:(oh-ih)/2"
+    ));
+    args.extend(["-f", "rawvideo", "-pix_fmt", "rgba", "-"].map(String::from));
+    args
+}
+
+/// One RGBA8 image for the generators' source texture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Frame {
+    /// Shrink by the smallest whole factor that brings both sides within
+    /// `max_side`, averaging each factor×factor block; a frame that already
+    /// fits is returned as is.  Textures past the device's
+    /// `max_texture_dimension_2d` fail wgpu validation.
+    pub fn fit(self, max_side: u32) -> Self {
+        let factor = self.width.max(self.height).div_ceil(max_side.max(1));
+        if factor <= 1 {
+            return self;
+        }
+        let (w, h) = (self.width.div_ceil(factor), self.height.div_ceil(factor));
+        let mut rgba = Vec::with_capacity((w * h * 4) as usize);
+        for y in 0..h {
+            let rows = y * factor..((y + 1) * factor).min(self.height);
+            for x in 0..w {
+                let cols = x * factor..((x + 1) * factor).min(self.width);
+                let mut sum = [0u32; 4];
+                for sy in rows.clone() {
+                    for sx in cols.clone() {
+                        let i = ((sy * self.width + sx) * 4) as usize;
+                        for (s, &v) in sum.iter_mut().zip(&self.rgba[i..i + 4]) {
+                            *s += u32::from(v);
+                        }
+                    }
+                }
+                let n = rows.len() as u32 * cols.len() as u32;
+                rgba.extend(sum.map(|s| ((s + n / 2) / n) as u8));
+            }
+        }
+        Self {
+            width: w,
+            height: h,
+            rgba,
+        }
+    }
+}
+
+/// Read `frame_bytes`-sized frames from `reader` until it ends, keeping only
+/// the newest in `latest`.  A trailing partial frame is dropped.
+fn read_frames(
+    mut reader: impl Read,
+    frame_bytes: usize,
+    latest: &Mutex<Option<Vec<u8>>>,
+) -> io::Result<()> {
+    loop {
+        let mut frame = vec![0; frame_bytes];
+        match reader.read_exact(&mut frame) {
+            Ok(()) => *latest.lock().unwrap() = Some(frame),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A running ffmpeg decoder.  Frames arrive on a reader thread; the render
+/// loop takes the newest one, so a slow frame rate never builds a backlog.
+pub struct VideoInput {
+    child: Child,
+    latest: Arc<Mutex<Option<Vec<u8>>>>,
+    source: PathBuf,
+}
+
+impl VideoInput {
+    /// Start decoding `source`, a video file or `/dev/video*` device.
+    /// Needs `ffmpeg` on `PATH`.
+    pub fn open(source: &Path) -> io::Result<Self> {
+        let mut child = Command::new("ffmpeg")
+            .args(ffmpeg_args(source))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => io::Error::new(e.kind(), "ffmpeg not found on PATH"),
+                _ => e,
+            })?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let latest = Arc::new(Mutex::new(None));
+        let spawned = {
+            let latest = Arc::clone(&latest);
+            let name = source.display().to_string();
+            thread::Builder::new()
+                .name("video-input".into())
+                .spawn(move || match read_frames(stdout, FRAME_BYTES, &latest) {
+                    Ok(()) => log::info!("video input {name} ended"),
+                    Err(e) => log::warn!("video input {name}: {e}"),
+                })
+        };
+        if let Err(e) = spawned {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(e);
+        }
+        log::info!("Video input {}", source.display());
+        Ok(Self {
+            child,
+            latest,
+            source: source.to_path_buf(),
+        })
+    }
+
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// The newest frame since the last call.
+    pub fn take_frame(&self) -> Option<Frame> {
+        let rgba = self.latest.lock().unwrap().take()?;
+        Some(Frame {
+            width: VIDEO_WIDTH,
+            height: VIDEO_HEIGHT,
+            rgba,
+        })
+    }
+}
+
+impl Drop for VideoInput {
+    fn drop(&mut self) {
+        // Closing the pipe ends the reader thread once ffmpeg is gone.
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Still images for the Image generator
+// ---------------------------------------------------------------------------
+
+/// Decode a PNG of any colour type and bit depth into an RGBA8 [`Frame`].
+pub fn load_image(path: &Path) -> io::Result<Frame> {
+    let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(io::Error::other)?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(io::Error::other)?;
+    buf.truncate(info.buffer_size());
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "indexed PNG was not expanded",
+            ))
+        }
+    };
+    Ok(Frame {
+        width: info.width,
+        height: info.height,
+        rgba,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn files_loop_in_real_time() {
+        let args = ffmpeg_args(Path::new("clip.mp4"));
+        let input = args.iter().position(|a| a == "-i").unwrap();
+        assert_eq!(args[input + 1], "clip.mp4");
+        assert!(args[..input].contains(&"-re".to_string()));
+        assert!(args[..input].contains(&"-stream_loop".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some("-"));
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "-pix_fmt" && w[1] == "rgba"));
+    }
+
+    #[test]
+    fn devices_are_captured_with_v4l2() {
+        let args = ffmpeg_args(Path::new("/dev/video0"));
+        assert!(args.windows(2).any(|w| w[0] == "-f" && w[1] == "v4l2"));
+        assert!(!args.contains(&"-stream_loop".to_string()));
+    }
+
+    #[test]
+    fn scale_filter_matches_the_upload_size() {
+        let args = ffmpeg_args(Path::new("clip.mp4"));
+        let vf = args.iter().position(|a| a == "-vf").unwrap();
+        assert!(args[vf + 1].starts_with("scale=960:540:"));
+        assert!(args[vf + 1].contains("pad=960:540:"));
+    }
+
+    fn write_png(path: &Path, color: png::ColorType, data: &[u8], width: u32) {
+        let mut encoder = png::Encoder::new(File::create(path).unwrap(), width, 1);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(data)
+            .unwrap();
+    }
+
+    #[test]
+    fn fit_shrinks_only_frames_past_the_limit() {
+        let frame = Frame {
+            width: 3,
+            height: 2,
+            rgba: [[0, 0, 0, 255], [100, 0, 0, 255], [200, 0, 0, 255]]
+                .repeat(2)
+                .concat(),
+        };
+        assert_eq!(frame.clone().fit(3), frame);
+        let half = frame.fit(2);
+        assert_eq!((half.width, half.height), (2, 1));
+        // The last column stands alone: the width doesn't divide evenly.
+        assert_eq!(half.rgba, [50, 0, 0, 255, 200, 0, 0, 255]);
+    }
+
+    #[test]
+    fn load_image_expands_to_rgba() {
+        let dir = std::env::temp_dir().join(format!("fractal-image-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let rgb = dir.join("rgb.png");
+        write_png(&rgb, png::ColorType::Rgb, &[10, 20, 30, 40, 50, 60], 2);
+        let frame = load_image(&rgb).unwrap();
+        assert_eq!((frame.width, frame.height), (2, 1));
+        assert_eq!(frame.rgba, [10, 20, 30, 255, 40, 50, 60, 255]);
+
+        let gray = dir.join("gray.png");
+        write_png(&gray, png::ColorType::GrayscaleAlpha, &[7, 128], 1);
+        assert_eq!(load_image(&gray).unwrap().rgba, [7, 7, 7, 128]);
+
+        assert!(load_image(&dir.join("missing.png")).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn reader_keeps_the_newest_whole_frame() {
+        let bytes: Vec<u8> = [1u8; 4].into_iter().chain([2; 4]).chain([3; 2]).collect();
+        let latest = Mutex::new(None);
+        read_frames(Cursor::new(bytes), 4, &latest).unwrap();
+        assert_eq!(latest.lock().unwrap().as_deref(), Some(&[2u8; 4][..]));
+    }
+}
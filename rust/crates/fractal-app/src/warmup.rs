@@ -0,0 +1,142 @@
+use std::collections::VecDeque;
+use std::mem::{discriminant, Discriminant};
+
+use fractal_core::{presets::Preset, EffectKind, GeneratorKind};
+
+// ---------------------------------------------------------------------------
+// WarmUp — drawing each preset's shaders once before the user asks for them
+// ---------------------------------------------------------------------------
+//
+// Every pipeline is created up front by the passes' `new`, but drivers may
+// still finish compiling a shader on its first dispatch, and the
+// half-resolution textures are only made on first use.  The app draws one
+// queued preset per frame through the live passes at startup — into
+// textures the real frame overwrites — so switching presets later never
+// pays for either.
+
+/// The presets still to be drawn once, in order, and how many were queued.
+#[derive(Debug, Default)]
+pub struct WarmUp {
+    queue: VecDeque<Preset>,
+    total: usize,
+}
+
+impl WarmUp {
+    /// Queue the fewest presets that between them dispatch every generator
+    /// and effect shader `current` (already on screen) doesn't.
+    pub fn new(current: Preset) -> Self {
+        let queue: VecDeque<Preset> = plan(&Preset::ALL, current).into();
+        Self {
+            total: queue.len(),
+            queue,
+        }
+    }
+
+    /// The next preset to draw, or `None` once everything is warm.
+    pub fn next(&mut self) -> Option<Preset> {
+        self.queue.pop_front()
+    }
+
+    /// Presets drawn so far and the total queued.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.total - self.queue.len(), self.total)
+    }
+}
+
+/// The shaders a preset dispatches: its generator and each effect variant.
+struct Shaders {
+    generator: Discriminant<GeneratorKind>,
+    effects: Vec<Discriminant<EffectKind>>,
+}
+
+impl Shaders {
+    fn of(preset: Preset) -> Self {
+        let patch = preset.build();
+        Self {
+            generator: discriminant(&patch.generator.kind()),
+            effects: patch
+                .effects
+                .iter()
+                .map(|e| discriminant(&e.kind(&patch.params)))
+                .collect(),
+        }
+    }
+}
+
+/// The presets among `presets` that first dispatch a shader not already
+/// covered by `current` or an earlier pick.
+fn plan(presets: &[Preset], current: Preset) -> Vec<Preset> {
+    let first = Shaders::of(current);
+    let mut generators = vec![first.generator];
+    let mut effects = first.effects;
+    presets
+        .iter()
+        .copied()
+        .filter(|&preset| {
+            let shaders = Shaders::of(preset);
+            let mut new = false;
+            if !generators.contains(&shaders.generator) {
+                generators.push(shaders.generator);
+                new = true;
+            }
+            for e in shaders.effects {
+                if !effects.contains(&e) {
+                    effects.push(e);
+                    new = true;
+                }
+            }
+            new
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn covered(presets: &[Preset]) -> (usize, usize) {
+        let mut generators = Vec::new();
+        let mut effects = Vec::new();
+        for &p in presets {
+            let s = Shaders::of(p);
+            if !generators.contains(&s.generator) {
+                generators.push(s.generator);
+            }
+            for e in s.effects {
+                if !effects.contains(&e) {
+                    effects.push(e);
+                }
+            }
+        }
+        (generators.len(), effects.len())
+    }
+
+    #[test]
+    fn plan_covers_every_shader_the_presets_use() {
+        let current = Preset::ClassicMandelbrot;
+        let mut warmed = plan(&Preset::ALL, current);
+        warmed.push(current);
+        assert_eq!(covered(&warmed), covered(&Preset::ALL));
+    }
+
+    #[test]
+    fn plan_skips_presets_adding_nothing_new() {
+        let warmed = plan(&Preset::ALL, Preset::ClassicMandelbrot);
+        assert!(warmed.len() < Preset::ALL.len());
+        assert!(!warmed.contains(&Preset::ClassicMandelbrot));
+    }
+
+    #[test]
+    fn progress_counts_presets_drawn() {
+        let mut w = WarmUp::new(Preset::ClassicMandelbrot);
+        let (done, total) = w.progress();
+        assert_eq!(done, 0);
+        assert!(total > 0);
+        while w.next().is_some() {}
+        assert_eq!(w.progress(), (total, total));
+    }
+}
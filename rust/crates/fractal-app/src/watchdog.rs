@@ -0,0 +1,255 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// ---------------------------------------------------------------------------
+// Watchdog — keeps unattended installations alive through driver hiccups
+// ---------------------------------------------------------------------------
+//
+// The render loop holds a [`FrameGuard`] from acquiring the surface texture
+// until the frame is presented.  A worker thread checks it
+// `POLLS_PER_SECOND` times a second; once one frame has been in flight for
+// `timeout` it logs the hang, with the GPU diagnostics the app left it, and
+// raises the trip flag.  A device-lost callback raises the same flag
+// through a [`Tripwire`].  The render loop takes the flag before each frame
+// and rebuilds its GPU state, so a frame wedged inside the driver is
+// recovered from as soon as the driver lets go of it.
+//
+// Only time inside a frame counts: a minimized or occluded window that gets
+// no redraws, or a surface that times out before a frame starts, leaves
+// nothing in flight and so never looks like a hang.
+
+/// Seconds one frame may be in flight before it counts as hung.
+pub const DEFAULT_TIMEOUT: f32 = 5.0;
+
+/// Shortest timeout the settings accept; a busy frame may take a while.
+pub const MIN_TIMEOUT: f32 = 1.0;
+
+/// Longest timeout the settings accept.
+pub const MAX_TIMEOUT: f32 = 120.0;
+
+const POLLS_PER_SECOND: u32 = 20;
+
+/// `frame_start` while no frame is in flight.
+const IDLE: u64 = u64::MAX;
+
+struct Shared {
+    started: Instant,
+    /// Milliseconds after `started` the current frame began, or [`IDLE`].
+    frame_start: AtomicU64,
+    tripped: AtomicBool,
+    stop: AtomicBool,
+    /// Adapter description logged with a hang.
+    diagnostics: Mutex<String>,
+}
+
+impl Shared {
+    fn now_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+}
+
+/// Watches the render loop for hung frames; stops when dropped.
+pub struct Watchdog {
+    shared: Arc<Shared>,
+    worker: Option<JoinHandle<()>>,
+    timeout: Duration,
+}
+
+impl Watchdog {
+    pub fn start(timeout: Duration) -> std::io::Result<Self> {
+        let shared = Arc::new(Shared {
+            started: Instant::now(),
+            frame_start: AtomicU64::new(IDLE),
+            tripped: AtomicBool::new(false),
+            stop: AtomicBool::new(false),
+            diagnostics: Mutex::new(String::new()),
+        });
+        let worker = {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name("watchdog".into())
+                .spawn(move || run(&shared, timeout))?
+        };
+        Ok(Self {
+            shared,
+            worker: Some(worker),
+            timeout,
+        })
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// A frame has acquired its surface texture; it is in flight until the
+    /// guard is dropped after the present (or an error ends it early).
+    pub fn frame(&self) -> FrameGuard {
+        self.shared
+            .frame_start
+            .store(self.shared.now_ms(), Ordering::Relaxed);
+        FrameGuard(Arc::clone(&self.shared))
+    }
+
+    /// Describe the GPU for the hang report; set after each (re)build.
+    pub fn set_diagnostics(&self, diagnostics: String) {
+        *self.shared.diagnostics.lock().unwrap() = diagnostics;
+    }
+
+    /// A handle that trips this watchdog from another thread.
+    pub fn tripwire(&self) -> Tripwire {
+        Tripwire(Arc::clone(&self.shared))
+    }
+
+    /// Whether a hang or lost device was reported since the last call.
+    pub fn take_tripped(&self) -> bool {
+        self.shared.tripped.swap(false, Ordering::Relaxed)
+    }
+}
+
+/// Marks one frame in flight for the [`Watchdog`]; see [`Watchdog::frame`].
+pub struct FrameGuard(Arc<Shared>);
+
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        self.0.frame_start.store(IDLE, Ordering::Relaxed);
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Trips the [`Watchdog`] it came from; for the device-lost callback.
+#[derive(Clone)]
+pub struct Tripwire(Arc<Shared>);
+
+impl Tripwire {
+    pub fn trip(&self) {
+        self.0.tripped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Hang detection for one stalled frame: reports once when it has been in
+/// flight past the timeout, then stays quiet until that frame ends.
+#[derive(Debug, Default)]
+struct Monitor {
+    reported: bool,
+}
+
+impl Monitor {
+    /// `true` the first time `in_flight` exceeds `timeout` in a stall.
+    fn poll(&mut self, in_flight: Duration, timeout: Duration) -> bool {
+        if in_flight <= timeout {
+            self.reported = false;
+            return false;
+        }
+        !std::mem::replace(&mut self.reported, true)
+    }
+}
+
+fn run(shared: &Shared, timeout: Duration) {
+    let period = Duration::from_secs(1) / POLLS_PER_SECOND;
+    let mut monitor = Monitor::default();
+    while !shared.stop.load(Ordering::Relaxed) {
+        thread::sleep(period);
+        let start = shared.frame_start.load(Ordering::Relaxed);
+        let in_flight = match start {
+            IDLE => Duration::ZERO,
+            start => Duration::from_millis(shared.now_ms().saturating_sub(start)),
+        };
+        if monitor.poll(in_flight, timeout) {
+            log::error!(
+                "watchdog: a frame has been in flight for {:.1} s; GPU state will be rebuilt ({})",
+                in_flight.as_secs_f32(),
+                shared.diagnostics.lock().unwrap()
+            );
+            shared.tripped.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// One-line description of the adapter for hang and rebuild reports.
+pub fn describe_adapter(info: &wgpu::AdapterInfo) -> String {
+    format!(
+        "{} ({:?}, {:?}), driver {} {}",
+        info.name, info.backend, info.device_type, info.driver, info.driver_info
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    // --- Monitor ---
+
+    #[test]
+    fn monitor_reports_a_stall_once() {
+        let mut m = Monitor::default();
+        assert!(!m.poll(SECOND, 5 * SECOND));
+        assert!(m.poll(6 * SECOND, 5 * SECOND));
+        assert!(!m.poll(7 * SECOND, 5 * SECOND));
+        assert!(!m.poll(60 * SECOND, 5 * SECOND));
+    }
+
+    #[test]
+    fn monitor_rearms_once_frames_resume() {
+        let mut m = Monitor::default();
+        assert!(m.poll(6 * SECOND, 5 * SECOND));
+        assert!(!m.poll(Duration::ZERO, 5 * SECOND));
+        assert!(m.poll(6 * SECOND, 5 * SECOND));
+    }
+
+    // --- Watchdog ---
+
+    #[test]
+    fn a_stalled_frame_trips_the_watchdog() {
+        let dog = Watchdog::start(Duration::from_millis(100)).unwrap();
+        let _frame = dog.frame();
+        let deadline = Instant::now() + 5 * SECOND;
+        while !dog.take_tripped() {
+            assert!(Instant::now() < deadline, "watchdog never tripped");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn short_frames_keep_it_quiet() {
+        let dog = Watchdog::start(Duration::from_millis(100)).unwrap();
+        for _ in 0..30 {
+            let _frame = dog.frame();
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!dog.take_tripped());
+    }
+
+    #[test]
+    fn no_frames_at_all_is_not_a_hang() {
+        // A minimized window gets no redraws, so nothing is in flight.
+        let dog = Watchdog::start(Duration::from_millis(100)).unwrap();
+        drop(dog.frame());
+        thread::sleep(Duration::from_millis(400));
+        assert!(!dog.take_tripped());
+    }
+
+    #[test]
+    fn tripwire_trips_from_another_thread() {
+        let dog = Watchdog::start(60 * SECOND).unwrap();
+        let wire = dog.tripwire();
+        thread::spawn(move || wire.trip()).join().unwrap();
+        assert!(dog.take_tripped());
+        assert!(!dog.take_tripped(), "taking the flag clears it");
+    }
+}
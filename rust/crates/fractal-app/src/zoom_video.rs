@@ -0,0 +1,221 @@
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use fractal_core::{
+    bundle::{PatchBundle, BUNDLE_EXTENSION},
+    patch::Patch,
+    presets::Preset,
+    viewcode::ViewState,
+    zoomvideo::{compose_frame, Keyframe, ZoomPath, ZoomVideoSpec},
+};
+use fractal_gpu::{
+    context::{GpuContext, Uniforms},
+    thumbnail::ThumbnailRenderer,
+};
+
+use crate::input::{auto_iterations, clamp_iterations, DEFAULT_MAX_ITERATIONS};
+
+// ---------------------------------------------------------------------------
+// Zoom video — offline render of a keyframed dive, encoded by ffmpeg
+// ---------------------------------------------------------------------------
+//
+// `--zoom-video SPEC.json` skips the window: the startup patch is frozen at
+// time 0, keyframes are rendered headless at growing depth (see
+// `fractal_core::zoomvideo`), and the frames reprojected from them are piped
+// to ffmpeg as raw RGBA.
+
+/// The patch the video starts from: the startup bundle or view code, or the
+/// first preset without one.
+pub fn startup_patch(arg: Option<&str>) -> Result<Patch, String> {
+    let Some(arg) = arg else {
+        return Ok(Preset::ALL[0].build());
+    };
+    let path = Path::new(arg);
+    if path.extension().is_some_and(|e| e == BUNDLE_EXTENSION) || path.is_file() {
+        return PatchBundle::load(path)
+            .and_then(|bundle| bundle.build())
+            .map_err(|e| format!("{arg}: {e}"));
+    }
+    let view = ViewState::decode(arg)
+        .map_err(|e| format!("argument is neither a bundle nor a view code: {e}"))?;
+    let mut patch = view.preset().build();
+    view.apply(&mut patch.params);
+    Ok(patch)
+}
+
+/// Arguments for ffmpeg encoding raw `size` RGBA frames at `fps` from stdin
+/// into `output` (overwritten), in a pixel format every player handles.
+pub fn ffmpeg_args(size: [u32; 2], fps: u32, output: &Path) -> Vec<String> {
+    let [w, h] = size;
+    let mut args: Vec<String> = ["-hide_banner", "-loglevel", "error", "-y"]
+        .map(String::from)
+        .into();
+    args.extend(["-f", "rawvideo", "-pix_fmt", "rgba", "-s"].map(String::from));
+    args.push(format!("{w}x{h}"));
+    args.push("-r".into());
+    args.push(fps.to_string());
+    args.extend(["-i", "-", "-pix_fmt", "yuv420p"].map(String::from));
+    args.push(output.display().to_string());
+    args
+}
+
+/// Spawn ffmpeg with [`ffmpeg_args`] and let `write_frames` feed it raw
+/// RGBA frames.  A write fails when ffmpeg has quit, so its exit status is
+/// checked before the write error is returned: it says why.
+pub fn encode(
+    size: [u32; 2],
+    fps: u32,
+    output: &Path,
+    write_frames: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut ffmpeg = Command::new("ffmpeg")
+        .args(ffmpeg_args(size, fps, output))
+        .stdin(Stdio::piped())
+        .spawn()?;
+    let mut stdin = ffmpeg.stdin.take().expect("ffmpeg stdin is piped");
+    let written = write_frames(&mut stdin);
+    drop(stdin);
+
+    let status = ffmpeg.wait()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("ffmpeg exited with {status}")));
+    }
+    written
+}
+
+/// Render the video described by `spec_path`, starting from the startup
+/// argument's patch.  Iterations grow with each keyframe's zoom as in the
+/// live view's auto iterations, up to `max_iterations` (the default ceiling
+/// when `None`).
+pub fn run(
+    spec_path: &Path,
+    startup: Option<&str>,
+    max_iterations: Option<u32>,
+) -> Result<(), String> {
+    let spec =
+        ZoomVideoSpec::load(spec_path).map_err(|e| format!("{}: {e}", spec_path.display()))?;
+    let patch = startup_patch(startup)?;
+    render(
+        &spec,
+        patch,
+        max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS),
+    )
+    .map_err(|e| format!("{}: {e}", spec.output.display()))
+}
+
+fn render(spec: &ZoomVideoSpec, mut patch: Patch, ceiling: u32) -> io::Result<()> {
+    let ctx = pollster::block_on(GpuContext::new_headless());
+    let [kw, kh] = spec.keyframe_size();
+    let mut renderer = ThumbnailRenderer::with_size(&ctx.device, kw, kh);
+
+    patch.tick(0.0);
+    let path = ZoomPath {
+        start: patch.params.zoom,
+        end: spec.zoom,
+        frames: spec.frame_count(),
+    };
+    log::info!(
+        "zoom video: {} frames from {} keyframes → {}",
+        path.frames,
+        path.keyframe_count(),
+        spec.output.display()
+    );
+
+    encode(spec.size, spec.fps, &spec.output, |out| {
+        // The frames only ever need the current pair of keyframes.
+        let mut keyframes: Vec<(usize, Vec<u8>)> = Vec::new();
+        for i in 0..path.frames {
+            let (k, scale) = path.keyframe_for(path.frame_zoom(i));
+            keyframes.retain(|(n, _)| *n == k || *n == k + 1);
+            for n in [k, k + 1] {
+                if keyframes.iter().any(|(m, _)| *m == n) {
+                    continue;
+                }
+                log::info!(
+                    "zoom video: keyframe {} of {}",
+                    n + 1,
+                    path.keyframe_count()
+                );
+                patch.params.zoom = path.keyframe_zoom(n);
+                let mut uniforms = Uniforms::from_params(&patch.params, kw, kh);
+                uniforms.max_iter = clamp_iterations(
+                    auto_iterations(patch.params.max_iter, uniforms.zoom, ceiling),
+                    ceiling,
+                );
+                keyframes.push((
+                    n,
+                    renderer.capture(&ctx.device, &ctx.queue, &patch, &uniforms),
+                ));
+            }
+
+            let keyframe = |n: usize| {
+                keyframes
+                    .iter()
+                    .find(|(m, _)| *m == n)
+                    .map(|(_, rgba)| Keyframe {
+                        rgba,
+                        width: kw,
+                        height: kh,
+                    })
+            };
+            let outer = keyframe(k).expect("outer keyframe was just rendered");
+            let frame = compose_frame(
+                &outer,
+                keyframe(k + 1).as_ref(),
+                scale,
+                spec.size[0],
+                spec.size[1],
+            );
+            out.write_all(&frame)?;
+        }
+        Ok(())
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ffmpeg_reads_raw_frames_of_the_spec_size() {
+        let spec = ZoomVideoSpec::from_json(
+            r#"{"output": "dive.mp4", "size": [640, 360], "fps": 25, "seconds": 4, "zoom": 100}"#,
+        )
+        .unwrap();
+        let args = ffmpeg_args(spec.size, spec.fps, &spec.output);
+        let joined = args.join(" ");
+        assert!(
+            joined.contains("-f rawvideo -pix_fmt rgba -s 640x360 -r 25 -i -"),
+            "{joined}"
+        );
+        assert_eq!(args.last().map(String::as_str), Some("dive.mp4"));
+    }
+
+    #[test]
+    fn startup_patch_defaults_to_the_first_preset() {
+        let patch = startup_patch(None).unwrap();
+        assert_eq!(
+            patch.generator.kind(),
+            Preset::ALL[0].build().generator.kind()
+        );
+    }
+
+    #[test]
+    fn startup_patch_applies_a_view_code() {
+        let mut params = Preset::ALL[0].build().params;
+        params.zoom = 123.0;
+        let code = ViewState::capture(fractal_core::GeneratorKind::BurningShip, &params).encode();
+        let patch = startup_patch(Some(&code)).unwrap();
+        assert_eq!(
+            patch.generator.kind(),
+            fractal_core::GeneratorKind::BurningShip
+        );
+        assert_eq!(patch.params.zoom, 123.0);
+        assert!(startup_patch(Some("not a code")).is_err());
+    }
+}
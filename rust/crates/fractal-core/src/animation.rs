@@ -0,0 +1,246 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{tiling::MAX_CANVAS_SIDE, zoomvideo::MAX_FRAME_PIXELS, Params};
+
+// ---------------------------------------------------------------------------
+// Animation — a patch rendered frame by frame on a fixed clock
+// ---------------------------------------------------------------------------
+//
+// Parameter sweeps and LFO plots have to come out the same on every run, so
+// frame `i` is always rendered at `start + i / fps` however long it takes.
+// The frames go either to ffmpeg or, for a `.npy` output, into one stacked
+// `frames × height × width × 4` array that `numpy.load` reads as is.
+
+fn default_fps() -> u32 {
+    30
+}
+
+/// A param moved linearly from `from` on the first frame to `to` on the
+/// last, before modulation: an LFO on the same key still moves around it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sweep {
+    pub key: String,
+    pub from: f32,
+    pub to: f32,
+}
+
+/// What to render, e.g. ten seconds of the Julia `c` drifting:
+/// `{"output": "sweep.npy", "size": [320, 240], "seconds": 10,
+///   "sweep": [{"key": "julia_cx", "from": -0.8, "to": -0.7}]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationSpec {
+    /// A `.npy` array, or a video file whose extension picks ffmpeg's
+    /// container.
+    pub output: PathBuf,
+    /// Frame width and height; both even, as most encoders need.
+    pub size: [u32; 2],
+    #[serde(default = "default_fps")]
+    pub fps: u32,
+    pub seconds: f32,
+    /// Patch time of the first frame.
+    #[serde(default)]
+    pub start: f32,
+    #[serde(default)]
+    pub sweep: Vec<Sweep>,
+}
+
+impl AnimationSpec {
+    pub fn validate(&self) -> Result<(), String> {
+        let [w, h] = self.size;
+        let max = MAX_CANVAS_SIDE;
+        if !(2..=max).contains(&w) || !(2..=max).contains(&h) || w % 2 != 0 || h % 2 != 0 {
+            return Err(format!(
+                "frame size is {w}×{h}; each side must be even and 2–{max}"
+            ));
+        }
+        if w * h > MAX_FRAME_PIXELS {
+            return Err(format!("frame size {w}×{h} is larger than 4K UHD"));
+        }
+        if !(1..=240).contains(&self.fps) {
+            return Err(format!("fps is {}; it must be 1–240", self.fps));
+        }
+        if !(self.seconds > 0.0 && self.seconds <= 3600.0) {
+            return Err(format!(
+                "length is {} s; it must be above 0 and at most an hour",
+                self.seconds
+            ));
+        }
+        if !self.start.is_finite() {
+            return Err(format!("start time {} must be a number", self.start));
+        }
+        for s in &self.sweep {
+            if !(s.from.is_finite() && s.to.is_finite()) {
+                return Err(format!("sweep of {} must run between numbers", s.key));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn from_json(src: &str) -> io::Result<Self> {
+        let spec: Self =
+            serde_json::from_str(src).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        spec.validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(spec)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        ((self.seconds * self.fps as f32).round() as u32).max(1)
+    }
+
+    /// Patch time of every frame.
+    pub fn frame_times(&self) -> impl Iterator<Item = f32> {
+        frame_times(self.start, self.frame_count(), self.fps as f32)
+    }
+
+    /// Set every swept param to its value at frame `i`.
+    pub fn apply_sweeps(&self, i: u32, params: &mut Params) {
+        let frames = self.frame_count();
+        let t = if frames > 1 {
+            i as f32 / (frames - 1) as f32
+        } else {
+            0.0
+        };
+        for s in &self.sweep {
+            params.set(s.key.as_str(), s.from + (s.to - s.from) * t);
+        }
+    }
+
+    /// Whether the frames are written as a stacked `.npy` array rather than
+    /// encoded by ffmpeg.
+    pub fn writes_array(&self) -> bool {
+        self.output.extension().is_some_and(|e| e == "npy")
+    }
+}
+
+/// Patch times of `frames` frames at `fps` from `start`, computed from the
+/// frame index so long sequences don't drift.
+///
+/// # Panics
+///
+/// If `fps` is not a positive number.
+pub fn frame_times(start: f32, frames: u32, fps: f32) -> impl Iterator<Item = f32> {
+    assert!(
+        fps.is_finite() && fps > 0.0,
+        "fps must be positive, not {fps}"
+    );
+    (0..frames).map(move |i| start + i as f32 / fps)
+}
+
+/// Header of a NumPy `.npy` (format 1.0) file holding a C-order `u8` array
+/// of `shape`; the array's bytes follow it directly.
+pub fn npy_header(shape: &[usize]) -> Vec<u8> {
+    let dims: Vec<String> = shape.iter().map(usize::to_string).collect();
+    let dims = match dims.as_slice() {
+        [one] => format!("{one},"),
+        _ => dims.join(", "),
+    };
+    let mut dict = format!("{{'descr': '|u1', 'fortran_order': False, 'shape': ({dims}), }}");
+    // Magic, version and length take 10 bytes; the whole header is padded
+    // with spaces to a multiple of 64 and ends in a newline.
+    let padded = (10 + dict.len() + 1).div_ceil(64) * 64;
+    dict.push_str(&" ".repeat(padded - 10 - dict.len() - 1));
+    dict.push('\n');
+
+    let mut header = b"\x93NUMPY\x01\x00".to_vec();
+    header.extend((dict.len() as u16).to_le_bytes());
+    header.extend(dict.into_bytes());
+    header
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(json: &str) -> io::Result<AnimationSpec> {
+        AnimationSpec::from_json(json)
+    }
+
+    #[test]
+    fn spec_parses_with_defaults() {
+        let s = spec(r#"{"output": "lfo.mp4", "size": [64, 48], "seconds": 2}"#).unwrap();
+        assert_eq!(s.fps, 30);
+        assert_eq!(s.start, 0.0);
+        assert!(s.sweep.is_empty());
+        assert_eq!(s.frame_count(), 60);
+        assert!(!s.writes_array());
+    }
+
+    #[test]
+    fn spec_rejects_odd_sizes_and_bad_numbers() {
+        for bad in [
+            r#"{"output": "a.mp4", "size": [63, 48], "seconds": 1}"#,
+            r#"{"output": "a.mp4", "size": [64, 48], "seconds": 1, "fps": 0}"#,
+            r#"{"output": "a.mp4", "size": [64, 48], "seconds": 0}"#,
+            r#"{"output": "a.mp4", "size": [8000, 8000], "seconds": 1}"#,
+        ] {
+            let err = spec(bad).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{bad}");
+        }
+    }
+
+    #[test]
+    fn frame_times_step_at_the_frame_rate() {
+        let times: Vec<_> = frame_times(1.0, 4, 2.0).collect();
+        assert_eq!(times, vec![1.0, 1.5, 2.0, 2.5]);
+        assert_eq!(frame_times(0.0, 0, 30.0).count(), 0);
+        let last = frame_times(0.0, 3001, 30.0).last().unwrap();
+        assert!((last - 100.0).abs() < 1e-4, "{last}");
+    }
+
+    #[test]
+    #[should_panic(expected = "fps must be positive")]
+    fn frame_times_reject_a_zero_frame_rate() {
+        let _ = frame_times(0.0, 10, 0.0);
+    }
+
+    #[test]
+    fn sweeps_run_from_the_first_frame_to_the_last() {
+        let s = spec(
+            r#"{"output": "s.npy", "size": [4, 4], "fps": 2, "seconds": 2.5, "start": 1,
+                "sweep": [{"key": "julia_cx", "from": -1, "to": 1}]}"#,
+        )
+        .unwrap();
+        assert!(s.writes_array());
+        let times: Vec<_> = s.frame_times().collect();
+        assert_eq!(times, vec![1.0, 1.5, 2.0, 2.5, 3.0]);
+        let mut params = Params::default();
+        let values: Vec<_> = (0..s.frame_count())
+            .map(|i| {
+                s.apply_sweeps(i, &mut params);
+                params.get("julia_cx")
+            })
+            .collect();
+        assert_eq!(values, vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn npy_header_is_aligned_and_describes_the_shape() {
+        let header = npy_header(&[3, 240, 320, 4]);
+        assert_eq!(header.len() % 64, 0);
+        assert_eq!(&header[..8], b"\x93NUMPY\x01\x00");
+        let len = u16::from_le_bytes([header[8], header[9]]) as usize;
+        assert_eq!(header.len(), 10 + len);
+        let dict = std::str::from_utf8(&header[10..]).unwrap();
+        assert!(
+            dict.starts_with(
+                "{'descr': '|u1', 'fortran_order': False, 'shape': (3, 240, 320, 4), }"
+            ),
+            "{dict}"
+        );
+        assert!(dict.ends_with(" \n"));
+        let one = npy_header(&[5]);
+        assert!(String::from_utf8_lossy(&one[10..]).contains("'shape': (5,)"));
+    }
+}
@@ -0,0 +1,567 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Params;
+
+/// `Params` key holding the patch time of the latest onset.  Absent until
+/// the first onset, so envelopes stay silent before any audio arrives.
+pub const ONSET_TIME_KEY: &str = "onset_time";
+
+/// `Params` key holding the strength (0–1) of the latest onset.
+pub const ONSET_STRENGTH_KEY: &str = "onset_strength";
+
+/// Magnitudes are compressed with `ln(1 + γ·m)` before differencing so quiet
+/// passages still produce usable flux.
+const LOG_COMPRESSION: f32 = 10.0;
+
+/// Added to the adaptive threshold so silence (flux ≈ 0) never triggers.
+const FLUX_FLOOR: f32 = 0.05;
+
+/// Tempo range the beat clock accepts; beats closer together than
+/// `60 / MAX_BPM` are ignored as double triggers and a gap longer than
+/// `60 / MIN_BPM` starts a new tempo.
+pub const MIN_BPM: f32 = 40.0;
+pub const MAX_BPM: f32 = 240.0;
+
+/// Beat intervals the tempo estimate takes its median over.
+const TEMPO_HISTORY: usize = 8;
+
+// ---------------------------------------------------------------------------
+// AudioBand — frequency bands published into Params
+// ---------------------------------------------------------------------------
+
+/// A frequency band whose normalised energy (0–1) an analyser writes into
+/// `Params` under [`AudioBand::key`] every frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioBand {
+    /// 20–250 Hz: kick drum, bass line.
+    Bass,
+    /// 250–2000 Hz: vocals, snare body, most instruments.
+    Mid,
+    /// 2–20 kHz: hats, cymbals, sibilance.
+    Treble,
+    /// The whole spectrum.
+    Level,
+}
+
+impl AudioBand {
+    pub const ALL: [AudioBand; 4] = [
+        AudioBand::Bass,
+        AudioBand::Mid,
+        AudioBand::Treble,
+        AudioBand::Level,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            AudioBand::Bass => "audio_bass",
+            AudioBand::Mid => "audio_mid",
+            AudioBand::Treble => "audio_treble",
+            AudioBand::Level => "audio_level",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Onset — one detected hit
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Onset {
+    /// Analysis-clock time of the spectrum frame the hit peaked in.
+    pub time: f32,
+    /// How far the flux peak cleared the threshold, mapped to (0, 1].
+    pub strength: f32,
+}
+
+impl Onset {
+    /// Publish this onset as the latest trigger, stamped with the patch clock
+    /// so `Envelope` modulators measure their age against `params.time`.
+    pub fn apply(&self, params: &mut Params) {
+        params.set(ONSET_TIME_KEY, params.time);
+        params.set(ONSET_STRENGTH_KEY, self.strength);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OnsetDetector — spectral flux with an adaptive threshold
+// ---------------------------------------------------------------------------
+
+/// Finds rhythmic hits in a stream of magnitude spectra (one per FFT hop).
+///
+/// Each frame's spectral flux — the summed rise in log magnitude across
+/// bins — is compared with the mean flux of the last `window` frames scaled
+/// by `sensitivity`.  A frame that is a local flux maximum above that
+/// threshold is an onset, reported one frame late once the peak is known.
+pub struct OnsetDetector {
+    /// Threshold multiplier over the local mean flux; higher is stricter.
+    pub sensitivity: f32,
+    /// Shortest gap between onsets in seconds, so one hit's ringing
+    /// doesn't re-trigger.
+    pub min_interval: f32,
+    window: usize,
+    prev_spectrum: Vec<f32>,
+    history: VecDeque<f32>,
+    /// Flux and time of the previous frame — the onset candidate.
+    prev: (f32, f32),
+    /// Flux of the frame before that, for peak picking.
+    prev_prev_flux: f32,
+    last_onset: Option<f32>,
+}
+
+impl OnsetDetector {
+    pub const DEFAULT_SENSITIVITY: f32 = 1.5;
+    pub const DEFAULT_MIN_INTERVAL: f32 = 0.1;
+    /// About half a second of history at a 512-sample hop and 44.1 kHz.
+    pub const DEFAULT_WINDOW: usize = 43;
+
+    pub fn new() -> Self {
+        Self::with_window(Self::DEFAULT_WINDOW)
+    }
+
+    /// A detector averaging its threshold over `window` frames (at least 1).
+    pub fn with_window(window: usize) -> Self {
+        Self {
+            sensitivity: Self::DEFAULT_SENSITIVITY,
+            min_interval: Self::DEFAULT_MIN_INTERVAL,
+            window: window.max(1),
+            prev_spectrum: Vec::new(),
+            history: VecDeque::new(),
+            prev: (0.0, 0.0),
+            prev_prev_flux: 0.0,
+            last_onset: None,
+        }
+    }
+
+    /// Feed the magnitude spectrum of the frame at `time` seconds.  Returns
+    /// the onset that peaked in the previous frame, if any.
+    pub fn process(&mut self, time: f32, magnitudes: &[f32]) -> Option<Onset> {
+        let flux = self.flux(magnitudes);
+        let (peak, peak_time) = self.prev;
+        let threshold = self.threshold();
+
+        let is_peak = peak > self.prev_prev_flux && peak >= flux && peak > threshold;
+        let rested = self
+            .last_onset
+            .is_none_or(|t| peak_time - t >= self.min_interval);
+        let onset = (is_peak && rested).then(|| {
+            self.last_onset = Some(peak_time);
+            Onset {
+                time: peak_time,
+                strength: 1.0 - threshold / peak,
+            }
+        });
+
+        self.history.push_back(flux);
+        if self.history.len() > self.window {
+            self.history.pop_front();
+        }
+        self.prev_prev_flux = peak;
+        self.prev = (flux, time);
+        onset
+    }
+
+    /// Half-wave rectified rise in log magnitude since the last frame.  A
+    /// change in bin count (e.g. a new FFT size) restarts from zero.
+    fn flux(&mut self, magnitudes: &[f32]) -> f32 {
+        let current: Vec<f32> = magnitudes
+            .iter()
+            .map(|&m| (1.0 + LOG_COMPRESSION * m.max(0.0)).ln())
+            .collect();
+        let flux = if self.prev_spectrum.len() == current.len() {
+            current
+                .iter()
+                .zip(&self.prev_spectrum)
+                .map(|(c, p)| (c - p).max(0.0))
+                .sum()
+        } else {
+            0.0
+        };
+        self.prev_spectrum = current;
+        flux
+    }
+
+    fn threshold(&self) -> f32 {
+        let mean = if self.history.is_empty() {
+            0.0
+        } else {
+            self.history.iter().sum::<f32>() / self.history.len() as f32
+        };
+        mean * self.sensitivity + FLUX_FLOOR
+    }
+}
+
+impl Default for OnsetDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// BeatClock — tempo and phase from beat events
+// ---------------------------------------------------------------------------
+
+/// Where a quantized change lands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Quantize {
+    /// Apply changes immediately.
+    #[default]
+    Off,
+    /// Wait for the next beat.
+    Beat,
+    /// Wait for the next bar's downbeat.
+    Bar,
+}
+
+impl Quantize {
+    pub const ALL: [Quantize; 3] = [Quantize::Off, Quantize::Beat, Quantize::Bar];
+}
+
+/// Tracks tempo and beat phase from beat events (onsets or taps) and
+/// predicts upcoming beat / bar boundaries, so live changes can wait for
+/// them.  The first beat of a run is the first downbeat; after a pause
+/// longer than `60 / MIN_BPM` seconds the next beat starts a new run.
+pub struct BeatClock {
+    pub beats_per_bar: u32,
+    intervals: VecDeque<f32>,
+    /// Time of the latest beat.
+    last: Option<f32>,
+    /// Beats between the start of the run and `last`.
+    index: u64,
+}
+
+impl BeatClock {
+    pub fn new() -> Self {
+        Self {
+            beats_per_bar: 4,
+            intervals: VecDeque::new(),
+            last: None,
+            index: 0,
+        }
+    }
+
+    /// Register a beat at `time` seconds.
+    pub fn beat(&mut self, time: f32) {
+        if let Some(last) = self.last {
+            let gap = time - last;
+            if gap < 60.0 / MAX_BPM {
+                return;
+            }
+            if gap > 60.0 / MIN_BPM {
+                self.intervals.clear();
+                self.index = 0;
+            } else {
+                // A gap spanning several beats (missed hits) still advances
+                // the bar position by the right count.
+                let beats = self.period().map_or(1.0, |p| (gap / p).round().max(1.0));
+                self.index += beats as u64;
+                self.intervals.push_back(gap / beats);
+                if self.intervals.len() > TEMPO_HISTORY {
+                    self.intervals.pop_front();
+                }
+            }
+        }
+        self.last = Some(time);
+    }
+
+    /// Seconds per beat (median of recent intervals), once two beats are in.
+    pub fn period(&self) -> Option<f32> {
+        let mut sorted: Vec<f32> = self.intervals.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        sorted.get(sorted.len() / 2).copied()
+    }
+
+    pub fn bpm(&self) -> Option<f32> {
+        self.period().map(|p| 60.0 / p)
+    }
+
+    /// How far `now` is through the current beat, 0 on the beat to just
+    /// under 1 before the next.  `None` until a tempo is known.
+    pub fn phase(&self, now: f32) -> Option<f32> {
+        let (period, last) = (self.period()?, self.last?);
+        Some(((now - last) / period).rem_euclid(1.0))
+    }
+
+    /// Time of the first beat (or bar downbeat) at or after `now`.  `None`
+    /// when quantizing is off or no tempo is known yet.
+    pub fn next_boundary(&self, now: f32, quantize: Quantize) -> Option<f32> {
+        let step = match quantize {
+            Quantize::Off => return None,
+            Quantize::Beat => 1,
+            Quantize::Bar => u64::from(self.beats_per_bar.max(1)),
+        };
+        let (period, last) = (self.period()?, self.last?);
+        let ahead = ((now - last) / period).ceil().max(0.0) as u64;
+        let target = (self.index + ahead).div_ceil(step) * step;
+        Some(last + (target - self.index) as f32 * period)
+    }
+}
+
+impl Default for BeatClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AutoGain — band energies normalised to the recent peak
+// ---------------------------------------------------------------------------
+
+/// Automatic gain control for the band energies in `Params`: each band is
+/// divided by its loudest value over the last `window` seconds, so audio
+/// routes span their full min..max range whether the room is quiet or loud.
+///
+/// Run it after the analyser has written this frame's raw energies and
+/// before the patch's audio routes read them.
+pub struct AutoGain {
+    /// Length of the sliding peak window in seconds.
+    pub window: f32,
+    /// Peaks below this count as silence and are not boosted, so the noise
+    /// floor between songs doesn't get amplified to full scale.
+    pub floor: f32,
+    /// Per band (in `AudioBand::ALL` order), the (time, energy) samples that
+    /// can still become the window maximum, in decreasing energy order.
+    peaks: [VecDeque<(f32, f32)>; 4],
+}
+
+impl AutoGain {
+    pub const DEFAULT_WINDOW: f32 = 10.0;
+    pub const DEFAULT_FLOOR: f32 = 0.02;
+
+    pub fn new() -> Self {
+        Self {
+            window: Self::DEFAULT_WINDOW,
+            floor: Self::DEFAULT_FLOOR,
+            peaks: Default::default(),
+        }
+    }
+
+    /// Rescale every band present in `params` in place, at `time` seconds.
+    /// Bands the analyser hasn't written are left absent.
+    pub fn process(&mut self, time: f32, params: &mut Params) {
+        for (band, peaks) in AudioBand::ALL.into_iter().zip(&mut self.peaks) {
+            let Some(&energy) = params.fields.get(band.key()) else {
+                continue;
+            };
+            let energy = energy.max(0.0);
+            while peaks.back().is_some_and(|&(_, e)| e <= energy) {
+                peaks.pop_back();
+            }
+            peaks.push_back((time, energy));
+            while peaks.front().is_some_and(|&(t, _)| t < time - self.window) {
+                peaks.pop_front();
+            }
+            let peak = peaks.front().map_or(energy, |&(_, e)| e);
+            params.set(band.key(), (energy / peak.max(self.floor)).min(1.0));
+        }
+    }
+}
+
+impl Default for AutoGain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOP: f32 = 512.0 / 44_100.0;
+
+    /// A quiet 16-bin spectrum with a broadband hit every `period` frames.
+    fn spectrum(frame: usize, period: usize) -> Vec<f32> {
+        let level = if frame.is_multiple_of(period) {
+            1.0
+        } else {
+            0.01
+        };
+        vec![level; 16]
+    }
+
+    fn run(det: &mut OnsetDetector, frames: usize, period: usize) -> Vec<Onset> {
+        (1..=frames)
+            .filter_map(|i| det.process(i as f32 * HOP, &spectrum(i, period)))
+            .collect()
+    }
+
+    #[test]
+    fn silence_never_triggers() {
+        let mut det = OnsetDetector::new();
+        for i in 0..200 {
+            assert_eq!(det.process(i as f32 * HOP, &[0.0; 16]), None);
+        }
+    }
+
+    #[test]
+    fn steady_tone_never_triggers() {
+        let mut det = OnsetDetector::new();
+        for i in 0..200 {
+            assert_eq!(det.process(i as f32 * HOP, &[0.5; 16]), None);
+        }
+    }
+
+    #[test]
+    fn detects_each_hit_one_frame_late() {
+        let mut det = OnsetDetector::new();
+        let onsets = run(&mut det, 210, 20);
+        assert_eq!(onsets.len(), 10);
+        for (n, onset) in onsets.iter().enumerate() {
+            let frame = (n + 1) * 20;
+            assert!((onset.time - frame as f32 * HOP).abs() < 1e-4);
+            assert!(onset.strength > 0.0 && onset.strength <= 1.0);
+        }
+    }
+
+    #[test]
+    fn min_interval_suppresses_fast_hits() {
+        let mut det = OnsetDetector::new();
+        det.min_interval = 10.0 * HOP;
+        // Hits every 4 frames but the detector may only fire every 10.
+        let onsets = run(&mut det, 80, 4);
+        assert!(!onsets.is_empty());
+        assert!(onsets
+            .windows(2)
+            .all(|w| w[1].time - w[0].time >= det.min_interval - 1e-4));
+    }
+
+    #[test]
+    fn bin_count_change_restarts_flux() {
+        let mut det = OnsetDetector::with_window(4);
+        det.process(0.0, &[0.0; 8]);
+        // Louder but differently sized: no flux can be measured yet.
+        assert_eq!(det.process(HOP, &[1.0; 16]), None);
+        assert_eq!(det.process(2.0 * HOP, &[1.0; 16]), None);
+    }
+
+    // --- BeatClock ------------------------------------------------------------
+
+    /// A clock that heard `n` beats at 120 BPM starting from t = 10.
+    fn clock_at_120(n: usize) -> BeatClock {
+        let mut clock = BeatClock::new();
+        for i in 0..n {
+            clock.beat(10.0 + i as f32 * 0.5);
+        }
+        clock
+    }
+
+    #[test]
+    fn tempo_needs_two_beats() {
+        let clock = clock_at_120(1);
+        assert_eq!(clock.bpm(), None);
+        assert_eq!(clock.next_boundary(10.2, Quantize::Beat), None);
+        assert!((clock_at_120(3).bpm().unwrap() - 120.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn quantize_off_never_defers() {
+        assert_eq!(clock_at_120(4).next_boundary(11.6, Quantize::Off), None);
+    }
+
+    #[test]
+    fn next_beat_and_bar() {
+        // Beats at 10.0, 10.5, 11.0 (index 2); bars start every 4 beats.
+        let clock = clock_at_120(3);
+        assert_eq!(clock.next_boundary(11.2, Quantize::Beat), Some(11.5));
+        assert_eq!(clock.next_boundary(11.0, Quantize::Beat), Some(11.0));
+        assert_eq!(clock.next_boundary(11.2, Quantize::Bar), Some(12.0));
+        // Past the next downbeat, the one after follows a bar later.
+        assert_eq!(clock.next_boundary(12.1, Quantize::Bar), Some(14.0));
+    }
+
+    #[test]
+    fn phase_runs_from_beat_to_beat() {
+        assert_eq!(clock_at_120(1).phase(10.2), None);
+        let clock = clock_at_120(3); // last beat at 11.0
+        assert_eq!(clock.phase(11.0), Some(0.0));
+        assert!((clock.phase(11.125).unwrap() - 0.25).abs() < 1e-4);
+        // Predicted beats keep the phase running after the taps stop.
+        assert!((clock.phase(12.25).unwrap() - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn missed_beats_keep_bar_phase() {
+        let mut clock = clock_at_120(2); // beats 0, 1
+        clock.beat(11.5); // beat 3; beat 2 at 11.0 was missed
+        assert!((clock.bpm().unwrap() - 120.0).abs() < 1e-3);
+        assert_eq!(clock.next_boundary(11.6, Quantize::Bar), Some(12.0));
+    }
+
+    #[test]
+    fn double_triggers_and_pauses() {
+        let mut clock = clock_at_120(3);
+        clock.beat(11.05); // too soon after 11.0 — ignored
+        assert_eq!(clock.next_boundary(11.1, Quantize::Beat), Some(11.5));
+        clock.beat(30.0); // long pause: new run, tempo unknown again
+        assert_eq!(clock.bpm(), None);
+    }
+
+    #[test]
+    fn apply_stamps_patch_time() {
+        let mut p = Params {
+            time: 12.5,
+            ..Default::default()
+        };
+        Onset {
+            time: 0.3,
+            strength: 0.8,
+        }
+        .apply(&mut p);
+        assert_eq!(p.get(ONSET_TIME_KEY), 12.5);
+        assert_eq!(p.get(ONSET_STRENGTH_KEY), 0.8);
+    }
+
+    // --- AutoGain -------------------------------------------------------------
+
+    /// Run `bass` energies (one per 0.1 s) through a fresh AGC.
+    fn gained(bass: &[f32]) -> Vec<f32> {
+        let mut agc = AutoGain::new();
+        let mut p = Params::default();
+        bass.iter()
+            .enumerate()
+            .map(|(i, &e)| {
+                p.set(AudioBand::Bass.key(), e);
+                agc.process(i as f32 * 0.1, &mut p);
+                p.get(AudioBand::Bass.key())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quiet_and_loud_rooms_normalise_alike() {
+        let quiet = gained(&[0.05, 0.1, 0.025, 0.1, 0.05]);
+        let loud = gained(&[0.4, 0.8, 0.2, 0.8, 0.4]);
+        for (q, l) in quiet.iter().zip(&loud) {
+            assert!((q - l).abs() < 1e-6, "{quiet:?} vs {loud:?}");
+        }
+        assert_eq!(loud[1..], [1.0, 0.25, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn old_peaks_leave_the_window() {
+        // A loud hit, then 12 s of a steady quieter signal.
+        let mut bass = vec![0.8];
+        bass.extend(std::iter::repeat_n(0.2, 120));
+        let out = gained(&bass);
+        assert!((out[50] - 0.25).abs() < 1e-6);
+        assert_eq!(*out.last().unwrap(), 1.0);
+    }
+
+    #[test]
+    fn silence_is_not_boosted() {
+        let out = gained(&[0.005, 0.01]);
+        assert!((out[1] - 0.01 / AutoGain::DEFAULT_FLOOR).abs() < 1e-6);
+    }
+
+    #[test]
+    fn absent_bands_stay_absent() {
+        let mut p = Params::default();
+        AutoGain::new().process(0.0, &mut p);
+        assert!(!p.fields.contains_key(AudioBand::Mid.key()));
+    }
+}
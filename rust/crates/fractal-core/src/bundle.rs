@@ -0,0 +1,512 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    formula::{Formula, FormulaError},
+    macros::Macro,
+    meta::PatchMeta,
+    modulators::{AudioRoute, FlashTarget, Wavetable},
+    patch::Patch,
+    presets::Preset,
+    versioning::{Format, Migration},
+    Params,
+};
+
+/// File extension for patch bundles.
+pub const BUNDLE_EXTENSION: &str = "fractalpatch";
+
+/// Current bundle format version; bumped on incompatible changes, each of
+/// which adds a step to [`MIGRATIONS`].
+pub const BUNDLE_VERSION: u32 = 2;
+
+// ---------------------------------------------------------------------------
+// ParamsSnapshot — the persistent subset of Params
+// ---------------------------------------------------------------------------
+
+/// Everything in [`Params`] that defines a look.  Per-frame state (`time`,
+/// `frame`, mouse) is deliberately left out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamsSnapshot {
+    pub center_x: f32,
+    pub center_y: f32,
+    pub zoom: f32,
+    pub max_iter: u32,
+    #[serde(default)]
+    pub fields: BTreeMap<String, f32>,
+}
+
+impl ParamsSnapshot {
+    pub fn capture(params: &Params) -> Self {
+        Self {
+            center_x: params.center_x,
+            center_y: params.center_y,
+            zoom: params.zoom,
+            max_iter: params.max_iter,
+            fields: params.fields.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+        }
+    }
+
+    /// Overwrite the persistent fields of `params` with this snapshot.
+    pub fn apply(&self, params: &mut Params) {
+        params.center_x = self.center_x;
+        params.center_y = self.center_y;
+        params.zoom = self.zoom;
+        params.max_iter = self.max_iter;
+        for (k, v) in &self.fields {
+            params.set(k.clone(), *v);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PatchBundle — a self-contained `.fractalpatch` file
+// ---------------------------------------------------------------------------
+
+/// A complete look in one JSON file, suitable for sending to another user.
+///
+/// The generator, effect chain and modulators come from the base `preset`;
+/// `params` pins every value on top of it.  `audio_routes`, `macros` and
+/// `flash`, when present, replace the preset's own, and each of
+/// `wavetables` replaces the waveform of the preset's LFO on its key.
+/// `meta`, when present, replaces the preset's title, author and so on.
+/// `gradient`, `wgsl` and `effect_wgsl` are shader state rather than patch
+/// state: the app compiles them into its passes on import, as if typed into
+/// the shader editor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatchBundle {
+    pub version: u32,
+    /// The base preset, as returned by [`Preset::id`].
+    pub preset: String,
+    pub params: ParamsSnapshot,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_routes: Option<Vec<AudioRoute>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub macros: Option<Vec<Macro>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flash: Option<Vec<FlashTarget>>,
+    /// Custom LFO cycles, by the LFO's target param.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wavetables: Option<BTreeMap<String, Wavetable>>,
+    /// Optional colour gradient as linear RGB stops, replacing the colour
+    /// map's palette.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gradient: Option<Vec<[f32; 3]>>,
+    /// Optional custom WGSL generator source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wgsl: Option<String>,
+    /// Custom WGSL effect sources, by [`EffectKind::id`](crate::EffectKind::id).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effect_wgsl: Option<BTreeMap<String, String>>,
+    /// Step of a custom formula generator, as typed (see [`Formula`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formula: Option<String>,
+    /// Attribution and usage hints, when they differ from the preset's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PatchMeta>,
+}
+
+/// Why a bundle could not be loaded.
+#[derive(Debug)]
+pub enum BundleError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+    UnknownPreset(String),
+    Formula(FormulaError),
+}
+
+impl std::fmt::Display for BundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleError::Io(e) => write!(f, "{e}"),
+            BundleError::Json(e) => write!(f, "invalid bundle: {e}"),
+            BundleError::UnsupportedVersion(v) => {
+                write!(
+                    f,
+                    "unsupported bundle version {v} (expected 1..={BUNDLE_VERSION})"
+                )
+            }
+            BundleError::UnknownPreset(name) => write!(f, "unknown base preset: {name}"),
+            BundleError::Formula(e) => write!(f, "formula: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+impl From<io::Error> for BundleError {
+    fn from(e: io::Error) -> Self {
+        BundleError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for BundleError {
+    fn from(e: serde_json::Error) -> Self {
+        BundleError::Json(e)
+    }
+}
+
+impl PatchBundle {
+    /// Bundle the current state of a patch built from `preset`.
+    pub fn capture(preset: Preset, params: &Params) -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            preset: preset.id(),
+            params: ParamsSnapshot::capture(params),
+            audio_routes: None,
+            macros: None,
+            flash: None,
+            wavetables: None,
+            gradient: None,
+            wgsl: None,
+            effect_wgsl: None,
+            formula: None,
+            meta: None,
+        }
+    }
+
+    /// Bundle the current state of `patch`, audio routes, macros, flash
+    /// targets, wavetables, formula and metadata included.
+    pub fn capture_patch(preset: Preset, patch: &Patch) -> Self {
+        let routes = patch.audio.audio_routes();
+        let wavetables = patch.wavetables();
+        Self {
+            audio_routes: (!routes.is_empty()).then_some(routes),
+            macros: (!patch.macros.is_empty()).then(|| patch.macros.clone()),
+            flash: (!patch.flash.is_empty()).then(|| patch.flash.clone()),
+            wavetables: (!wavetables.is_empty()).then_some(wavetables),
+            formula: patch.generator.formula().map(|f| f.source().to_string()),
+            meta: (patch.meta != preset.meta()).then(|| patch.meta.clone()),
+            ..Self::capture(preset, &patch.params)
+        }
+    }
+
+    /// Resolve the base preset named in the bundle.
+    pub fn base_preset(&self) -> Result<Preset, BundleError> {
+        Preset::from_id(&self.preset).ok_or_else(|| BundleError::UnknownPreset(self.preset.clone()))
+    }
+
+    /// Rebuild the patch: base preset with the bundled params applied.
+    pub fn build(&self) -> Result<Patch, BundleError> {
+        let mut patch = self.base_preset()?.build();
+        self.params.apply(&mut patch.params);
+        if let Some(routes) = &self.audio_routes {
+            patch = patch.with_audio_routes(routes);
+        }
+        if let Some(macros) = &self.macros {
+            patch = patch.with_macros(macros);
+        }
+        if let Some(flash) = &self.flash {
+            patch = patch.with_flash(flash);
+        }
+        if let Some(tables) = &self.wavetables {
+            patch = patch.with_wavetables(tables);
+        }
+        if let Some(src) = &self.formula {
+            patch.set_formula(Formula::parse(src).map_err(BundleError::Formula)?);
+        }
+        if let Some(meta) = &self.meta {
+            patch = patch.with_meta(meta.clone());
+        }
+        Ok(patch)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("PatchBundle is always serialisable")
+    }
+
+    /// Parse a bundle saved by this or any earlier format version.
+    pub fn from_json(src: &str) -> Result<Self, BundleError> {
+        let value = migrate(serde_json::from_str(src)?)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BundleError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Write to `path`, creating parent directories as needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_json())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Migration — older bundles are upgraded as JSON before deserializing
+// ---------------------------------------------------------------------------
+
+/// `MIGRATIONS[n - 1]` rewrites a version-`n` bundle into version `n + 1`.
+/// Steps work on the raw JSON object, so the structs only ever describe the
+/// current format.
+const MIGRATIONS: [Migration; BUNDLE_VERSION as usize - 1] = [v1_preset_ids];
+
+const FORMAT: Format = Format::new("bundle", &MIGRATIONS);
+
+/// Upgrade a bundle's JSON to [`BUNDLE_VERSION`].  Anything that isn't an
+/// object with an integer `version` is passed through for serde to reject
+/// with a proper message; versions from the future are an error.
+pub fn migrate(mut value: Value) -> Result<Value, BundleError> {
+    if value.get("version").and_then(Value::as_u64).is_none() {
+        return Ok(value);
+    }
+    FORMAT
+        .upgrade(&mut value)
+        .map_err(|e| BundleError::UnsupportedVersion(e.found))?;
+    Ok(value)
+}
+
+/// v1 → v2: the base preset is stored by [`Preset::id`] instead of its
+/// display name.  Unknown names are kept so the load error can quote them.
+fn v1_preset_ids(bundle: &mut Map<String, Value>) {
+    if let Some(Value::String(preset)) = bundle.get_mut("preset") {
+        if let Some(p) = Preset::from_name(preset) {
+            *preset = p.id();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweaked_julia() -> Params {
+        let mut p = Preset::PsychedelicJulia.build().params;
+        p.zoom = 12.0;
+        p.center_x = 0.1;
+        p.max_iter = 420;
+        p.set("julia_cx", -0.75);
+        p.time = 5.0;
+        p
+    }
+
+    #[test]
+    fn snapshot_skips_per_frame_state() {
+        let snap = ParamsSnapshot::capture(&tweaked_julia());
+        let mut p = Params::default();
+        snap.apply(&mut p);
+        assert_eq!(p.zoom, 12.0);
+        assert_eq!(p.max_iter, 420);
+        assert_eq!(p.get("julia_cx"), -0.75);
+        assert_eq!(p.time, 0.0);
+    }
+
+    #[test]
+    fn capture_then_build_reproduces_params() {
+        let bundle = PatchBundle::capture(Preset::PsychedelicJulia, &tweaked_julia());
+        let patch = bundle.build().unwrap();
+        assert_eq!(patch.params.zoom, 12.0);
+        assert_eq!(patch.params.center_x, 0.1);
+        assert_eq!(patch.params.get("julia_cx"), -0.75);
+        assert_eq!(
+            patch.effects.len(),
+            Preset::PsychedelicJulia.build().effects.len()
+        );
+    }
+
+    #[test]
+    fn json_round_trip_with_optional_parts() {
+        let mut bundle =
+            PatchBundle::capture(Preset::NoiseField, &Preset::NoiseField.build().params);
+        bundle.gradient = Some(vec![[0.0, 0.0, 0.0], [1.0, 0.5, 0.0]]);
+        bundle.wgsl = Some("// custom".into());
+        bundle.effect_wgsl = Some(BTreeMap::from([("ripple".into(), "// waves".into())]));
+        let back = PatchBundle::from_json(&bundle.to_json()).unwrap();
+        assert_eq!(back, bundle);
+    }
+
+    #[test]
+    fn optional_parts_are_omitted_when_absent() {
+        let bundle = PatchBundle::capture(Preset::ClassicMandelbrot, &Params::default());
+        let json = bundle.to_json();
+        assert!(!json.contains("gradient"));
+        assert!(!json.contains("wgsl"));
+    }
+
+    #[test]
+    fn audio_routes_travel_with_the_bundle() {
+        let routes = vec![AudioRoute {
+            band: crate::audio::AudioBand::Bass,
+            target: "ripple_amplitude".into(),
+            smoothing: 0.15,
+            min: 2.0,
+            max: 20.0,
+            quantize: None,
+            when: None,
+        }];
+        let patch = Preset::OceanRippleJulia.build().with_audio_routes(&routes);
+        let bundle = PatchBundle::capture_patch(Preset::OceanRippleJulia, &patch);
+        let back = PatchBundle::from_json(&bundle.to_json()).unwrap();
+        assert_eq!(back.build().unwrap().audio.audio_routes(), routes);
+        // Without routes the field is left out and the preset's stand.
+        let plain = PatchBundle::capture_patch(
+            Preset::ClassicMandelbrot,
+            &Preset::ClassicMandelbrot.build(),
+        );
+        assert!(!plain.to_json().contains("audio_routes"));
+    }
+
+    #[test]
+    fn macros_travel_with_the_bundle() {
+        let mut patch = Preset::NoiseBloom.build();
+        assert!(!patch.macros.is_empty());
+        patch.params.set(patch.macros[0].key(), 0.9);
+        let bundle = PatchBundle::capture_patch(Preset::NoiseBloom, &patch);
+        let back = PatchBundle::from_json(&bundle.to_json())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(back.macros, patch.macros);
+        assert_eq!(back.params.get(&patch.macros[0].key()), 0.9);
+        let plain = PatchBundle::capture_patch(
+            Preset::ClassicMandelbrot,
+            &Preset::ClassicMandelbrot.build(),
+        );
+        assert!(!plain.to_json().contains("macros"));
+    }
+
+    #[test]
+    fn flash_targets_travel_with_the_bundle() {
+        let mut bundle = PatchBundle::capture(Preset::ClassicMandelbrot, &Params::default());
+        let flash = vec![FlashTarget {
+            key: "brightness_amount".into(),
+            value: 0.5,
+        }];
+        bundle.flash = Some(flash.clone());
+        let back = PatchBundle::from_json(&bundle.to_json()).unwrap();
+        assert_eq!(back.build().unwrap().flash, flash);
+    }
+
+    #[test]
+    fn wavetables_travel_with_the_bundle() {
+        let json = r#"{
+            "version": 2,
+            "preset": "BarnsleyFern",
+            "params": { "center_x": 0.0, "center_y": 0.0, "zoom": 1.0, "max_iter": 64 },
+            "wavetables": { "ifs1_b": { "points": [0, 1, 0, -1], "interpolation": "Smooth" } }
+        }"#;
+        let patch = PatchBundle::from_json(json).unwrap().build().unwrap();
+        let tables = patch.wavetables();
+        assert_eq!(tables["ifs1_b"].points, [0.0, 1.0, 0.0, -1.0]);
+        let back = PatchBundle::capture_patch(Preset::BarnsleyFern, &patch);
+        assert_eq!(back.wavetables, Some(tables));
+        let plain = PatchBundle::capture_patch(
+            Preset::ClassicMandelbrot,
+            &Preset::ClassicMandelbrot.build(),
+        );
+        assert!(!plain.to_json().contains("wavetables"));
+    }
+
+    #[test]
+    fn formula_travels_with_the_bundle() {
+        let mut patch = Preset::SineFormula.build();
+        patch.set_formula(Formula::parse("z^4 - c").unwrap());
+        let bundle = PatchBundle::capture_patch(Preset::SineFormula, &patch);
+        assert_eq!(bundle.formula.as_deref(), Some("z^4 - c"));
+        let back = PatchBundle::from_json(&bundle.to_json())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(back.generator.formula().unwrap().source(), "z^4 - c");
+        let plain = PatchBundle::capture_patch(
+            Preset::ClassicMandelbrot,
+            &Preset::ClassicMandelbrot.build(),
+        );
+        assert!(!plain.to_json().contains("formula"));
+    }
+
+    #[test]
+    fn meta_travels_only_when_it_differs_from_the_presets() {
+        let plain = PatchBundle::capture_patch(Preset::PopArt, &Preset::PopArt.build());
+        assert_eq!(plain.meta, None);
+        assert!(!plain.to_json().contains("meta"));
+        assert_eq!(plain.build().unwrap().meta, Preset::PopArt.meta());
+
+        let mut patch = Preset::PopArt.build();
+        patch.meta = patch.meta.with_author("cmh");
+        patch.meta.title = Some("Warhol Loop".into());
+        let bundle = PatchBundle::capture_patch(Preset::PopArt, &patch);
+        let back = PatchBundle::from_json(&bundle.to_json())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(back.meta, patch.meta);
+        assert_eq!(back.meta.title_or(Preset::PopArt.name()), "Warhol Loop");
+    }
+
+    #[test]
+    fn unparsable_formula_is_an_error() {
+        let mut bundle = PatchBundle::capture(Preset::SineFormula, &Params::default());
+        bundle.formula = Some("z^2 + x".into());
+        let Err(BundleError::Formula(e)) = bundle.build() else {
+            panic!("expected a formula error");
+        };
+        assert_eq!(e.column, 7);
+    }
+
+    #[test]
+    fn unknown_preset_is_an_error() {
+        let mut bundle = PatchBundle::capture(Preset::ClassicMandelbrot, &Params::default());
+        bundle.preset = "Nope".into();
+        assert!(matches!(bundle.build(), Err(BundleError::UnknownPreset(_))));
+    }
+
+    #[test]
+    fn unsupported_version_is_an_error() {
+        let mut bundle = PatchBundle::capture(Preset::ClassicMandelbrot, &Params::default());
+        bundle.version = BUNDLE_VERSION + 1;
+        assert!(matches!(
+            PatchBundle::from_json(&bundle.to_json()),
+            Err(BundleError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn version_1_bundles_still_load() {
+        let json = r#"{
+            "version": 1,
+            "preset": "Psychedelic Julia",
+            "params": { "center_x": 0.1, "center_y": 0.0, "zoom": 12.0, "max_iter": 420,
+                        "fields": { "julia_cx": -0.75 } }
+        }"#;
+        let bundle = PatchBundle::from_json(json).unwrap();
+        assert_eq!(bundle.version, BUNDLE_VERSION);
+        assert_eq!(bundle.base_preset().unwrap(), Preset::PsychedelicJulia);
+        assert_eq!(bundle.build().unwrap().params.get("julia_cx"), -0.75);
+        // An unknown name survives migration for the error to report.
+        let json = json.replace("Psychedelic Julia", "Nope");
+        let bundle = PatchBundle::from_json(&json).unwrap();
+        assert!(matches!(bundle.build(), Err(BundleError::UnknownPreset(p)) if p == "Nope"));
+    }
+
+    #[test]
+    fn every_version_has_a_migration() {
+        assert_eq!(FORMAT.version(), BUNDLE_VERSION);
+        let v0 = serde_json::json!({ "version": 0, "preset": "ClassicMandelbrot" });
+        assert!(matches!(
+            migrate(v0),
+            Err(BundleError::UnsupportedVersion(0))
+        ));
+    }
+
+    #[test]
+    fn save_then_load() {
+        let path = std::env::temp_dir()
+            .join(format!("fractal-bundle-{}", std::process::id()))
+            .join(format!("look.{BUNDLE_EXTENSION}"));
+        let bundle = PatchBundle::capture(Preset::ShipStorm, &Preset::ShipStorm.build().params);
+        bundle.save(&path).unwrap();
+        assert_eq!(PatchBundle::load(&path).unwrap(), bundle);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,266 @@
+use crate::Params;
+
+// ---------------------------------------------------------------------------
+// Coloring — how the escape-time generators turn an orbit into `t`
+// ---------------------------------------------------------------------------
+//
+// Mandelbrot, Julia, Burning Ship (with its Celtic and Buffalo variants),
+// Multibrot and Hybrid read the mode from `coloring` in `Params::fields` (0
+// escape time, 1 Pickover stalks, 2 orbit trap, 3 edge glow), the stalk
+// thickness from `stalk_width` and the orbit trap from the `trap_*` keys
+// (see [`Trap`]), so a patch picks the look in its params and an LFO, macro
+// or audio route can animate it.
+//
+// Mandelbrot and Julia can also estimate each pixel's distance to the set's
+// boundary and write it to the alpha channel: always for edge glow, and in
+// any mode while `distance_estimate` is set.
+
+/// Params keys every escape-time generator reads.
+pub const COLORING_KEYS: [&str; 8] = [
+    "coloring",
+    "distance_estimate",
+    "stalk_width",
+    "trap_shape",
+    "trap_x",
+    "trap_y",
+    "trap_angle",
+    "trap_radius",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Coloring {
+    /// Smoothed escape count; interior points are 0.
+    #[default]
+    EscapeTime,
+    /// Orbit trap on the coordinate axes: wherever the orbit passes within
+    /// `stalk_width` of either axis, `t` rises towards 1 over the
+    /// escape-time shading, drawing thin stalks through the set and halo.
+    PickoverStalks,
+    /// The generator also writes the orbit's closest approach to a
+    /// [`Trap`] to the green channel, and the colour map paints that
+    /// distance instead of the escape count.
+    OrbitTrap,
+    /// The generator writes its distance estimate to the alpha channel,
+    /// and the colour map paints that instead, brightest on the boundary.
+    /// Only Mandelbrot and Julia estimate; elsewhere it shades flat.
+    EdgeGlow,
+}
+
+impl Coloring {
+    pub const ALL: [Coloring; 4] = [
+        Coloring::EscapeTime,
+        Coloring::PickoverStalks,
+        Coloring::OrbitTrap,
+        Coloring::EdgeGlow,
+    ];
+
+    /// Trap thickness used when `stalk_width` is unset.
+    pub const DEFAULT_STALK_WIDTH: f32 = 0.02;
+
+    /// The mode `params` selects; unknown values fall back to escape time.
+    pub fn from_params(params: &Params) -> Self {
+        match params.get("coloring").round() as i32 {
+            1 => Coloring::PickoverStalks,
+            2 => Coloring::OrbitTrap,
+            3 => Coloring::EdgeGlow,
+            _ => Coloring::EscapeTime,
+        }
+    }
+
+    /// Whether the generator should write its distance estimate to alpha:
+    /// for edge glow, or whenever `distance_estimate` is set.
+    pub fn distance_estimate(params: &Params) -> bool {
+        Self::from_params(params) == Coloring::EdgeGlow || params.get("distance_estimate") > 0.5
+    }
+
+    /// Select this mode in `params`, seeding `stalk_width` if it is unset.
+    pub fn set(self, params: &mut Params) {
+        params.set("coloring", self.gpu_index() as f32);
+        if !params.fields.contains_key("stalk_width") {
+            params.set("stalk_width", Self::DEFAULT_STALK_WIDTH);
+        }
+    }
+
+    /// Value of the `coloring` field in the generator uniforms.
+    pub fn gpu_index(self) -> u32 {
+        match self {
+            Coloring::EscapeTime => 0,
+            Coloring::PickoverStalks => 1,
+            Coloring::OrbitTrap => 2,
+            Coloring::EdgeGlow => 3,
+        }
+    }
+
+    /// Trap thickness from `stalk_width`, never negative.
+    pub fn stalk_width(params: &Params) -> f32 {
+        params
+            .fields
+            .get("stalk_width")
+            .copied()
+            .unwrap_or(Self::DEFAULT_STALK_WIDTH)
+            .max(0.0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Trap — the shape orbit-trap coloring measures the orbit against
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrapShape {
+    /// Distance to `(trap_x, trap_y)`.
+    #[default]
+    Point,
+    /// Distance to the line through the point at `trap_angle` radians.
+    Line,
+    /// Distance to the circle of `trap_radius` around the point.
+    Circle,
+}
+
+impl TrapShape {
+    pub const ALL: [TrapShape; 3] = [TrapShape::Point, TrapShape::Line, TrapShape::Circle];
+
+    /// Value of `trap_shape` and of the shader's `shape`.
+    pub fn gpu_index(self) -> u32 {
+        match self {
+            TrapShape::Point => 0,
+            TrapShape::Line => 1,
+            TrapShape::Circle => 2,
+        }
+    }
+}
+
+/// The orbit trap of [`Coloring::OrbitTrap`], read from the `trap_*`
+/// params every frame so its position, angle and radius can be animated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trap {
+    pub shape: TrapShape,
+    pub point: [f32; 2],
+    /// Direction of a line trap, radians from the real axis.
+    pub angle: f32,
+    /// Radius of a circle trap.
+    pub radius: f32,
+}
+
+impl Trap {
+    /// Circle radius used when `trap_radius` is unset.
+    pub const DEFAULT_RADIUS: f32 = 0.5;
+
+    /// The trap `params` describe; unknown shapes fall back to a point.
+    pub fn from_params(params: &Params) -> Self {
+        let shape = match params.get("trap_shape").round() as i32 {
+            1 => TrapShape::Line,
+            2 => TrapShape::Circle,
+            _ => TrapShape::Point,
+        };
+        Self {
+            shape,
+            point: [params.get("trap_x"), params.get("trap_y")],
+            angle: params.get("trap_angle"),
+            radius: params
+                .fields
+                .get("trap_radius")
+                .copied()
+                .unwrap_or(Self::DEFAULT_RADIUS)
+                .max(0.0),
+        }
+    }
+
+    /// Write this trap to the `trap_*` params.
+    pub fn set(self, params: &mut Params) {
+        params.set("trap_shape", self.shape.gpu_index() as f32);
+        params.set("trap_x", self.point[0]);
+        params.set("trap_y", self.point[1]);
+        params.set("trap_angle", self.angle);
+        params.set("trap_radius", self.radius);
+    }
+
+    /// Distance from `z` to the trap.  Mirrors `trap_distance` in the
+    /// escape-time shaders.
+    pub fn distance(&self, z: [f32; 2]) -> f32 {
+        let d = [z[0] - self.point[0], z[1] - self.point[1]];
+        match self.shape {
+            TrapShape::Point => d[0].hypot(d[1]),
+            TrapShape::Line => (d[0] * self.angle.sin() - d[1] * self.angle.cos()).abs(),
+            TrapShape::Circle => (d[0].hypot(d[1]) - self.radius).abs(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_time_is_the_default() {
+        let p = Params::default();
+        assert_eq!(Coloring::from_params(&p), Coloring::EscapeTime);
+        assert_eq!(Coloring::stalk_width(&p), Coloring::DEFAULT_STALK_WIDTH);
+    }
+
+    #[test]
+    fn set_round_trips_and_keeps_an_existing_width() {
+        for mode in Coloring::ALL {
+            let mut p = Params::default();
+            p.set("stalk_width", 0.1);
+            mode.set(&mut p);
+            assert_eq!(Coloring::from_params(&p), mode);
+            assert_eq!(Coloring::stalk_width(&p), 0.1);
+        }
+    }
+
+    #[test]
+    fn unknown_modes_and_negative_widths_are_clamped() {
+        let mut p = Params::default();
+        p.set("coloring", 7.0);
+        p.set("stalk_width", -1.0);
+        assert_eq!(Coloring::from_params(&p), Coloring::EscapeTime);
+        assert_eq!(Coloring::stalk_width(&p), 0.0);
+    }
+
+    #[test]
+    fn edge_glow_always_estimates_distance() {
+        let mut p = Params::default();
+        assert!(!Coloring::distance_estimate(&p));
+        p.set("distance_estimate", 1.0);
+        assert!(Coloring::distance_estimate(&p));
+        p.set("distance_estimate", 0.0);
+        Coloring::EdgeGlow.set(&mut p);
+        assert!(Coloring::distance_estimate(&p));
+    }
+
+    #[test]
+    fn trap_round_trips_through_params() {
+        let trap = Trap {
+            shape: TrapShape::Circle,
+            point: [0.25, -0.5],
+            angle: 1.0,
+            radius: 0.75,
+        };
+        let mut p = Params::default();
+        trap.set(&mut p);
+        assert_eq!(Trap::from_params(&p), trap);
+        p.set("trap_shape", 9.0);
+        assert_eq!(Trap::from_params(&p).shape, TrapShape::Point);
+    }
+
+    #[test]
+    fn trap_distances() {
+        let mut trap = Trap {
+            shape: TrapShape::Point,
+            point: [1.0, 0.0],
+            angle: std::f32::consts::FRAC_PI_2,
+            radius: 2.0,
+        };
+        assert!((trap.distance([4.0, 4.0]) - 5.0).abs() < 1e-6);
+        // A vertical line through x = 1.
+        trap.shape = TrapShape::Line;
+        assert!((trap.distance([4.0, 4.0]) - 3.0).abs() < 1e-6);
+        trap.shape = TrapShape::Circle;
+        assert!((trap.distance([1.0, 0.5]) - 1.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,371 @@
+use std::f32::consts::PI;
+
+use crate::{Generator, GeneratorKind, IfsMap, Params};
+
+// ---------------------------------------------------------------------------
+// Flame — fractal flames in the style of flam3
+// ---------------------------------------------------------------------------
+//
+// A flame is an IFS whose maps are each followed by a weighted blend of
+// non-linear *variations*, so the attractor curls and folds instead of
+// tiling copies of itself.  Every transform also carries a colour
+// coordinate: a walker's colour moves halfway towards it at each step, and
+// the GPU accumulates both hits and colour per pixel.  The resolve pass
+// tone-maps the log of the hit density with gamma and vibrancy, as flam3
+// does, and colours it from a hue wheel turned by `flame_hue`.
+//
+// Transform `i`'s affine coefficients, pick weight, colour and variation
+// weights are the Params fields `flame{i}_a` … `flame{i}_f`,
+// `flame{i}_weight`, `flame{i}_color` and `flame{i}_<variation>`, so every
+// one of them can be modulated.
+
+/// A non-linear function applied after a transform's affine map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variation {
+    Linear,
+    Sinusoidal,
+    Spherical,
+    Swirl,
+    Horseshoe,
+    Polar,
+}
+
+impl Variation {
+    pub const COUNT: usize = 6;
+    /// In the order of [`FlameTransform::variations`] and the shader.
+    pub const ALL: [Variation; Self::COUNT] = [
+        Variation::Linear,
+        Variation::Sinusoidal,
+        Variation::Spherical,
+        Variation::Swirl,
+        Variation::Horseshoe,
+        Variation::Polar,
+    ];
+
+    /// The variation applied to `(x, y)`; a CPU reference for `flame.wgsl`.
+    pub fn apply(self, x: f32, y: f32) -> (f32, f32) {
+        let r2 = x * x + y * y;
+        let r = r2.sqrt();
+        match self {
+            Variation::Linear => (x, y),
+            Variation::Sinusoidal => (x.sin(), y.sin()),
+            Variation::Spherical => {
+                let k = 1.0 / r2.max(1e-10);
+                (x * k, y * k)
+            }
+            Variation::Swirl => {
+                let (s, c) = r2.sin_cos();
+                (x * s - y * c, x * c + y * s)
+            }
+            Variation::Horseshoe => {
+                let k = 1.0 / r.max(1e-10);
+                ((x - y) * (x + y) * k, 2.0 * x * y * k)
+            }
+            // flam3 measures this angle from the y axis.
+            Variation::Polar => (x.atan2(y) / PI, r - 1.0),
+        }
+    }
+}
+
+/// One flame transform: an affine map (with its pick weight), the blend of
+/// variations applied after it, and its colour coordinate in \[0, 1\].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FlameTransform {
+    pub map: IfsMap,
+    pub color: f32,
+    /// Weight of each of [`Variation::ALL`].
+    pub variations: [f32; Variation::COUNT],
+}
+
+impl FlameTransform {
+    /// A transform that is pure `variation` after `map`.
+    pub const fn new(map: IfsMap, color: f32, variation: Variation) -> Self {
+        let mut variations = [0.0; Variation::COUNT];
+        variations[variation as usize] = 1.0;
+        Self {
+            map,
+            color,
+            variations,
+        }
+    }
+
+    /// `self` with `variation` weighted `weight` in the blend.
+    pub const fn with(mut self, variation: Variation, weight: f32) -> Self {
+        self.variations[variation as usize] = weight;
+        self
+    }
+
+    /// Where a walker at `(x, y)` lands; a CPU reference for `flame.wgsl`.
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let m = &self.map;
+        let (ax, ay) = (m.a * x + m.b * y + m.e, m.c * x + m.d * y + m.f);
+        Variation::ALL
+            .iter()
+            .zip(self.variations)
+            .filter(|&(_, w)| w != 0.0)
+            .fold((0.0, 0.0), |(sx, sy), (v, w)| {
+                let (vx, vy) = v.apply(ax, ay);
+                (sx + w * vx, sy + w * vy)
+            })
+    }
+}
+
+/// Params keys of each transform — `a` … `f`, `weight`, `color`, then one
+/// per variation — followed by the tone-mapping keys.
+const FLAME_KEYS: [&str; FlameGen::MAX_TRANSFORMS * KEYS_PER_TRANSFORM + 4] = [
+    "flame0_a",
+    "flame0_b",
+    "flame0_c",
+    "flame0_d",
+    "flame0_e",
+    "flame0_f",
+    "flame0_weight",
+    "flame0_color",
+    "flame0_linear",
+    "flame0_sinusoidal",
+    "flame0_spherical",
+    "flame0_swirl",
+    "flame0_horseshoe",
+    "flame0_polar",
+    "flame1_a",
+    "flame1_b",
+    "flame1_c",
+    "flame1_d",
+    "flame1_e",
+    "flame1_f",
+    "flame1_weight",
+    "flame1_color",
+    "flame1_linear",
+    "flame1_sinusoidal",
+    "flame1_spherical",
+    "flame1_swirl",
+    "flame1_horseshoe",
+    "flame1_polar",
+    "flame2_a",
+    "flame2_b",
+    "flame2_c",
+    "flame2_d",
+    "flame2_e",
+    "flame2_f",
+    "flame2_weight",
+    "flame2_color",
+    "flame2_linear",
+    "flame2_sinusoidal",
+    "flame2_spherical",
+    "flame2_swirl",
+    "flame2_horseshoe",
+    "flame2_polar",
+    "flame3_a",
+    "flame3_b",
+    "flame3_c",
+    "flame3_d",
+    "flame3_e",
+    "flame3_f",
+    "flame3_weight",
+    "flame3_color",
+    "flame3_linear",
+    "flame3_sinusoidal",
+    "flame3_spherical",
+    "flame3_swirl",
+    "flame3_horseshoe",
+    "flame3_polar",
+    "flame_exposure",
+    "flame_gamma",
+    "flame_vibrancy",
+    "flame_hue",
+];
+
+const KEYS_PER_TRANSFORM: usize = 8 + Variation::COUNT;
+
+/// Fractal flame drawn by the chaos game on the GPU, like [`IfsGen`](crate::IfsGen)
+/// but with variations, per-transform colour and flam3-style tone mapping.
+/// `max_iter` is the number of steps per walker; `flame_exposure` is how
+/// many hits make a pixel full bright, `flame_gamma` and `flame_vibrancy`
+/// shape the tone curve, and `flame_hue` turns the colour wheel.
+pub struct FlameGen;
+
+impl FlameGen {
+    pub const MAX_TRANSFORMS: usize = 4;
+    pub const DEFAULT_EXPOSURE: f32 = 40.0;
+    pub const DEFAULT_GAMMA: f32 = 2.5;
+    pub const DEFAULT_VIBRANCY: f32 = 1.0;
+
+    /// A Sierpinski gasket whose corners swirl, bulge and ripple, coloured
+    /// red, green and blue from the first corner round.
+    pub const SWIRL: [FlameTransform; 3] = [
+        FlameTransform::new(IfsMap::toward(0.0, -1.0, 0.5), 0.0, Variation::Swirl)
+            .with(Variation::Linear, 0.5),
+        FlameTransform::new(IfsMap::toward(-0.87, 0.5, 0.5), 0.33, Variation::Spherical)
+            .with(Variation::Linear, 0.6)
+            .with(Variation::Spherical, 0.4),
+        FlameTransform::new(IfsMap::toward(0.87, 0.5, 0.5), 0.67, Variation::Sinusoidal),
+    ];
+
+    /// Write `transforms` into `params`, zeroing the weight of every slot
+    /// past them.  At most [`MAX_TRANSFORMS`](Self::MAX_TRANSFORMS) are
+    /// written.
+    pub fn set_transforms(params: &mut Params, transforms: &[FlameTransform]) {
+        for i in 0..Self::MAX_TRANSFORMS {
+            let t = transforms.get(i).copied().unwrap_or_default();
+            let m = t.map;
+            let values = [m.a, m.b, m.c, m.d, m.e, m.f, m.weight, t.color]
+                .into_iter()
+                .chain(t.variations);
+            let keys = &FLAME_KEYS[i * KEYS_PER_TRANSFORM..(i + 1) * KEYS_PER_TRANSFORM];
+            for (key, value) in keys.iter().zip(values) {
+                params.set(*key, value);
+            }
+        }
+    }
+
+    /// Seed the tone-mapping keys with their defaults.
+    pub fn set_defaults(params: &mut Params) {
+        params.set("flame_exposure", Self::DEFAULT_EXPOSURE);
+        params.set("flame_gamma", Self::DEFAULT_GAMMA);
+        params.set("flame_vibrancy", Self::DEFAULT_VIBRANCY);
+        params.set("flame_hue", 0.0);
+    }
+
+    /// Every transform slot as currently set in `params`.  Non-finite
+    /// values read as 0, weights are clamped to be non-negative and colours
+    /// to \[0, 1\], so a wild modulator can't stall or blank the walkers.
+    pub fn transforms(params: &Params) -> [FlameTransform; Self::MAX_TRANSFORMS] {
+        std::array::from_fn(|i| {
+            let v = |j: usize| {
+                let x = params.get(FLAME_KEYS[i * KEYS_PER_TRANSFORM + j]);
+                if x.is_finite() {
+                    x
+                } else {
+                    0.0
+                }
+            };
+            FlameTransform {
+                map: IfsMap {
+                    a: v(0),
+                    b: v(1),
+                    c: v(2),
+                    d: v(3),
+                    e: v(4),
+                    f: v(5),
+                    weight: v(6).max(0.0),
+                },
+                color: v(7).clamp(0.0, 1.0),
+                variations: std::array::from_fn(|k| v(8 + k)),
+            }
+        })
+    }
+
+    /// `flame_gamma`, at least 0.1 so the tone curve stays finite.
+    pub fn gamma(params: &Params) -> f32 {
+        let g = params.get("flame_gamma");
+        if g.is_finite() {
+            g.max(0.1)
+        } else {
+            Self::DEFAULT_GAMMA
+        }
+    }
+
+    /// `flame_vibrancy` clamped to \[0, 1\].
+    pub fn vibrancy(params: &Params) -> f32 {
+        let v = params.get("flame_vibrancy");
+        if v.is_finite() {
+            v.clamp(0.0, 1.0)
+        } else {
+            Self::DEFAULT_VIBRANCY
+        }
+    }
+}
+
+impl Generator for FlameGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Flame
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &FLAME_KEYS
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-5 && (a.1 - b.1).abs() < 1e-5
+    }
+
+    #[test]
+    fn variations_match_flam3() {
+        let (x, y) = (0.6, -0.8); // r = 1
+        assert_eq!(Variation::Linear.apply(x, y), (x, y));
+        assert!(close(Variation::Spherical.apply(x, y), (x, y)));
+        assert!(close(
+            Variation::Sinusoidal.apply(x, y),
+            (0.6_f32.sin(), (-0.8_f32).sin())
+        ));
+        assert!(close(
+            Variation::Horseshoe.apply(x, y),
+            ((x - y) * (x + y), 2.0 * x * y)
+        ));
+        assert!(close(Variation::Polar.apply(0.0, 2.0), (0.0, 1.0)));
+        let (s, c) = 1.0_f32.sin_cos();
+        assert!(close(
+            Variation::Swirl.apply(x, y),
+            (x * s - y * c, x * c + y * s)
+        ));
+    }
+
+    #[test]
+    fn spherical_survives_the_origin() {
+        let (x, y) = Variation::Spherical.apply(0.0, 0.0);
+        assert!(x.is_finite() && y.is_finite());
+    }
+
+    #[test]
+    fn transform_blends_variations_after_the_map() {
+        let map = IfsMap::toward(1.0, 0.0, 0.5);
+        let t = FlameTransform::new(map, 0.0, Variation::Linear).with(Variation::Sinusoidal, 0.5);
+        let (ax, ay): (f32, f32) = (0.5 * 0.2 + 0.5, 0.5 * 0.4);
+        let expected = (ax + 0.5 * ax.sin(), ay + 0.5 * ay.sin());
+        assert!(close(t.apply(0.2, 0.4), expected));
+    }
+
+    #[test]
+    fn transforms_round_trip_and_sanitize() {
+        let transforms = [
+            FlameTransform::new(IfsMap::toward(0.0, -1.0, 0.5), 0.25, Variation::Swirl),
+            FlameTransform::new(IfsMap::toward(1.0, 1.0, 0.5), 1.0, Variation::Polar)
+                .with(Variation::Linear, 0.3),
+        ];
+        let mut p = Params::default();
+        FlameGen::set_transforms(&mut p, &transforms);
+        let read = FlameGen::transforms(&p);
+        assert_eq!(read[..2], transforms);
+        assert_eq!(read[2].map.weight, 0.0);
+
+        p.set("flame0_color", 3.0);
+        p.set("flame1_weight", -2.0);
+        p.set("flame1_swirl", f32::INFINITY);
+        let read = FlameGen::transforms(&p);
+        assert_eq!(read[0].color, 1.0);
+        assert_eq!(read[1].map.weight, 0.0);
+        assert_eq!(read[1].variations[Variation::Swirl as usize], 0.0);
+    }
+
+    #[test]
+    fn tone_keys_are_read_with_limits() {
+        let mut p = Params::default();
+        FlameGen::set_defaults(&mut p);
+        assert_eq!(FlameGen::gamma(&p), FlameGen::DEFAULT_GAMMA);
+        assert_eq!(FlameGen::vibrancy(&p), FlameGen::DEFAULT_VIBRANCY);
+        p.set("flame_gamma", 0.0);
+        p.set("flame_vibrancy", 4.0);
+        assert_eq!(FlameGen::gamma(&p), 0.1);
+        assert_eq!(FlameGen::vibrancy(&p), 1.0);
+        let keys = FlameGen.gen_param_keys();
+        assert!(p.fields.keys().all(|k| keys.contains(&k.as_str())));
+    }
+}
@@ -0,0 +1,543 @@
+use std::fmt;
+
+// ---------------------------------------------------------------------------
+// Formula — a user-typed iteration step, compiled to WGSL
+// ---------------------------------------------------------------------------
+//
+// The language is complex arithmetic on a handful of names:
+//
+//   z      the orbit point            c      the pixel (or the Julia constant)
+//   a, b   the `formula_a` / `formula_b` params, real, so LFOs can sweep them
+//   t      time in seconds            i      the imaginary unit
+//   pi, e  the constants
+//
+// with `+ - * / ^`, unary minus, parentheses and the functions in `FUNCS`.
+// `abs` folds both components (the Burning Ship's fold) and `re` / `im`
+// take a part as a real number.  A non-negative integer power is taken by
+// repeated squaring, so `z^3` is exact; any other power goes through
+// exp(w·log z), with log's branch cut along the negative real axis.
+
+/// Formula a fresh Custom Formula patch starts with: the Mandelbrot set.
+pub const DEFAULT_FORMULA: &str = "z^2 + c";
+
+/// Longest formula accepted, in bytes.
+pub const MAX_FORMULA_LEN: usize = 256;
+
+/// Deepest nesting of parentheses, calls and operators accepted, so a
+/// pathological formula can't overflow the parser or bloat the shader.
+const MAX_DEPTH: usize = 32;
+
+/// Largest integer exponent taken by repeated squaring.
+const MAX_INT_POWER: u32 = 64;
+
+/// Why a formula didn't parse, with the 1-based column it went wrong at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormulaError {
+    pub message: String,
+    pub column: usize,
+}
+
+impl fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for FormulaError {}
+
+/// A parsed formula and the text it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula {
+    source: String,
+    expr: Expr,
+}
+
+impl Formula {
+    pub fn parse(source: &str) -> Result<Self, FormulaError> {
+        if source.len() > MAX_FORMULA_LEN {
+            return Err(FormulaError {
+                message: format!("longer than {MAX_FORMULA_LEN} characters"),
+                column: MAX_FORMULA_LEN + 1,
+            });
+        }
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.expr()?;
+        let (token, column) = parser.peek();
+        if token != Token::End {
+            return Err(FormulaError {
+                message: format!("unexpected {token}"),
+                column,
+            });
+        }
+        Ok(Self {
+            source: source.trim().to_string(),
+            expr,
+        })
+    }
+
+    /// The formula as typed, trimmed.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the formula reads `t`, so it changes from frame to frame.
+    pub fn uses_time(&self) -> bool {
+        self.expr.reads(Var::T)
+    }
+
+    /// The WGSL function `formula(z, c)` computing one step.  It reads `a`
+    /// and `b` from the template's `fp` block and `t` from `u.time`, and
+    /// calls the `c_*` helpers `formula.wgsl` defines.
+    pub fn to_wgsl(&self) -> String {
+        format!(
+            "fn formula(z: vec2<f32>, c: vec2<f32>) -> vec2<f32> {{\n    return {};\n}}\n",
+            self.expr.to_wgsl()
+        )
+    }
+}
+
+impl Default for Formula {
+    fn default() -> Self {
+        Self::parse(DEFAULT_FORMULA).expect("DEFAULT_FORMULA parses")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Syntax tree and WGSL generation
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Var {
+    Z,
+    C,
+    A,
+    B,
+    T,
+    I,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+/// Functions by name; the WGSL helper is `c_<name>`.
+const FUNCS: [&str; 13] = [
+    "sin", "cos", "tan", "sinh", "cosh", "tanh", "exp", "log", "sqrt", "abs", "conj", "re", "im",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    /// A real number.
+    Num(f32),
+    Var(Var),
+    Neg(Box<Expr>),
+    Bin(Op, Box<Expr>, Box<Expr>),
+    /// A power with a small non-negative integer exponent.
+    PowInt(Box<Expr>, u32),
+    /// One of [`FUNCS`].
+    Call(&'static str, Box<Expr>),
+}
+
+impl Expr {
+    fn reads(&self, var: Var) -> bool {
+        match self {
+            Expr::Num(_) => false,
+            Expr::Var(v) => *v == var,
+            Expr::Neg(x) | Expr::PowInt(x, _) | Expr::Call(_, x) => x.reads(var),
+            Expr::Bin(_, l, r) => l.reads(var) || r.reads(var),
+        }
+    }
+
+    fn to_wgsl(&self) -> String {
+        match self {
+            Expr::Num(x) => format!("vec2<f32>({x:?}, 0.0)"),
+            Expr::Var(v) => match v {
+                Var::Z => "z".into(),
+                Var::C => "c".into(),
+                Var::A => "vec2<f32>(fp.a, 0.0)".into(),
+                Var::B => "vec2<f32>(fp.b, 0.0)".into(),
+                Var::T => "vec2<f32>(u.time, 0.0)".into(),
+                Var::I => "vec2<f32>(0.0, 1.0)".into(),
+            },
+            Expr::Neg(x) => format!("(-{})", x.to_wgsl()),
+            Expr::Bin(op, l, r) => {
+                let (l, r) = (l.to_wgsl(), r.to_wgsl());
+                match op {
+                    Op::Add => format!("({l} + {r})"),
+                    Op::Sub => format!("({l} - {r})"),
+                    Op::Mul => format!("c_mul({l}, {r})"),
+                    Op::Div => format!("c_div({l}, {r})"),
+                    Op::Pow => format!("c_pow({l}, {r})"),
+                }
+            }
+            Expr::PowInt(x, n) => format!("c_powi({}, {n}u)", x.to_wgsl()),
+            Expr::Call(name, x) => format!("c_{name}({})", x.to_wgsl()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f32),
+    Ident(String),
+    /// One of `+ - * / ^ ( )`.
+    Sym(char),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Num(x) => write!(f, "number {x}"),
+            Token::Ident(name) => write!(f, "`{name}`"),
+            Token::Sym(c) => write!(f, "`{c}`"),
+            Token::End => f.write_str("end of formula"),
+        }
+    }
+}
+
+/// Split `src` into tokens, each with its 1-based column.
+fn tokenize(src: &str) -> Result<Vec<(Token, usize)>, FormulaError> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        let column = i + 1;
+        if ch.is_whitespace() {
+            i += 1;
+        } else if ch.is_ascii_digit() || ch == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // An exponent, e.g. 1e-3; a bare `e` after a number is an error
+            // rather than the constant.
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                i += 1;
+                if i < chars.len() && matches!(chars[i], '+' | '-') {
+                    i += 1;
+                }
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            match text.parse::<f32>() {
+                Ok(x) if x.is_finite() => tokens.push((Token::Num(x), column)),
+                _ => {
+                    return Err(FormulaError {
+                        message: format!("invalid number `{text}`"),
+                        column,
+                    })
+                }
+            }
+        } else if ch.is_ascii_alphabetic() || ch == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), column));
+        } else if "+-*/^()".contains(ch) {
+            tokens.push((Token::Sym(ch), column));
+            i += 1;
+        } else {
+            return Err(FormulaError {
+                message: format!("unexpected character `{ch}`"),
+                column,
+            });
+        }
+    }
+    tokens.push((Token::End, chars.len() + 1));
+    Ok(tokens)
+}
+
+// ---------------------------------------------------------------------------
+// Parser — recursive descent, lowest precedence first
+// ---------------------------------------------------------------------------
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Current nesting, checked against [`MAX_DEPTH`].
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> (Token, usize) {
+        self.tokens[self.pos].clone()
+    }
+
+    fn next(&mut self) -> (Token, usize) {
+        let token = self.peek();
+        if token.0 != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, sym: char) -> bool {
+        let found = self.peek().0 == Token::Sym(sym);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn enter(&mut self) -> Result<(), FormulaError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(FormulaError {
+                message: format!("nested deeper than {MAX_DEPTH} levels"),
+                column: self.peek().1,
+            });
+        }
+        Ok(())
+    }
+
+    /// expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<Expr, FormulaError> {
+        self.enter()?;
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                Op::Add
+            } else if self.eat('-') {
+                Op::Sub
+            } else {
+                break;
+            };
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.term()?));
+        }
+        self.depth -= 1;
+        Ok(lhs)
+    }
+
+    /// term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> Result<Expr, FormulaError> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                Op::Mul
+            } else if self.eat('/') {
+                Op::Div
+            } else {
+                break;
+            };
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    /// unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Result<Expr, FormulaError> {
+        self.enter()?;
+        let expr = if self.eat('-') {
+            Expr::Neg(Box::new(self.unary()?))
+        } else if self.eat('+') {
+            self.unary()?
+        } else {
+            self.power()?
+        };
+        self.depth -= 1;
+        Ok(expr)
+    }
+
+    /// power := atom ('^' unary)?, so `z^-1` works and `-z^2` is −(z²).
+    fn power(&mut self) -> Result<Expr, FormulaError> {
+        let base = self.atom()?;
+        if !self.eat('^') {
+            return Ok(base);
+        }
+        Ok(match self.unary()? {
+            Expr::Num(n) if n >= 0.0 && n.fract() == 0.0 && n <= MAX_INT_POWER as f32 => {
+                Expr::PowInt(Box::new(base), n as u32)
+            }
+            exponent => Expr::Bin(Op::Pow, Box::new(base), Box::new(exponent)),
+        })
+    }
+
+    /// atom := number | name | name '(' expr ')' | '(' expr ')'
+    fn atom(&mut self) -> Result<Expr, FormulaError> {
+        let (token, column) = self.next();
+        match token {
+            Token::Num(x) => Ok(Expr::Num(x)),
+            Token::Sym('(') => {
+                let inner = self.expr()?;
+                self.close(column)?;
+                Ok(inner)
+            }
+            Token::Ident(name) if self.peek().0 == Token::Sym('(') => {
+                let Some(func) = FUNCS.iter().find(|&&f| f == name) else {
+                    return Err(FormulaError {
+                        message: format!("unknown function `{name}`; try {}", FUNCS.join(", ")),
+                        column,
+                    });
+                };
+                self.pos += 1;
+                let arg = self.expr()?;
+                self.close(column)?;
+                Ok(Expr::Call(func, Box::new(arg)))
+            }
+            Token::Ident(name) => match name.as_str() {
+                "z" => Ok(Expr::Var(Var::Z)),
+                "c" => Ok(Expr::Var(Var::C)),
+                "a" => Ok(Expr::Var(Var::A)),
+                "b" => Ok(Expr::Var(Var::B)),
+                "t" => Ok(Expr::Var(Var::T)),
+                "i" => Ok(Expr::Var(Var::I)),
+                "pi" => Ok(Expr::Num(std::f32::consts::PI)),
+                "e" => Ok(Expr::Num(std::f32::consts::E)),
+                _ => Err(FormulaError {
+                    message: format!(
+                        "unknown name `{name}`; use z, c, a, b, t, i, pi or e{}",
+                        if FUNCS.contains(&name.as_str()) {
+                            ", or call it with ( )"
+                        } else {
+                            ""
+                        }
+                    ),
+                    column,
+                }),
+            },
+            Token::End => Err(FormulaError {
+                message: "formula ends too soon".into(),
+                column,
+            }),
+            token => Err(FormulaError {
+                message: format!("unexpected {token}"),
+                column,
+            }),
+        }
+    }
+
+    /// Expect the `)` closing the `(` at `open`.
+    fn close(&mut self, open: usize) -> Result<(), FormulaError> {
+        if self.eat(')') {
+            return Ok(());
+        }
+        let (token, column) = self.peek();
+        Err(FormulaError {
+            message: format!("expected `)` to close column {open}, found {token}"),
+            column,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rust mirror of the `c_*` helpers in formula.wgsl, in f64.
+    fn eval(e: &Expr, z: [f64; 2], c: [f64; 2], ab: [f64; 2]) -> [f64; 2] {
+        let mul = |[a, b]: [f64; 2], [x, y]: [f64; 2]| [a * x - b * y, a * y + b * x];
+        let ev = |e: &Expr| eval(e, z, c, ab);
+        match e {
+            Expr::Num(x) => [*x as f64, 0.0],
+            Expr::Var(Var::Z) => z,
+            Expr::Var(Var::C) => c,
+            Expr::Var(Var::A) => [ab[0], 0.0],
+            Expr::Var(Var::B) => [ab[1], 0.0],
+            Expr::Var(Var::T) => [0.0, 0.0],
+            Expr::Var(Var::I) => [0.0, 1.0],
+            Expr::Neg(x) => ev(x).map(|v| -v),
+            Expr::Bin(Op::Add, l, r) => [ev(l)[0] + ev(r)[0], ev(l)[1] + ev(r)[1]],
+            Expr::Bin(Op::Sub, l, r) => [ev(l)[0] - ev(r)[0], ev(l)[1] - ev(r)[1]],
+            Expr::Bin(Op::Mul, l, r) => mul(ev(l), ev(r)),
+            Expr::PowInt(x, n) => (0..*n).fold([1.0, 0.0], |acc, _| mul(acc, ev(x))),
+            Expr::Call("sin", x) => {
+                let [a, b] = ev(x);
+                [a.sin() * b.cosh(), a.cos() * b.sinh()]
+            }
+            Expr::Call("abs", x) => ev(x).map(f64::abs),
+            other => unimplemented!("{other:?}"),
+        }
+    }
+
+    fn parse(src: &str) -> Expr {
+        Formula::parse(src).unwrap().expr
+    }
+
+    fn error(src: &str) -> FormulaError {
+        Formula::parse(src).unwrap_err()
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        let (z, c) = ([0.3, -0.2], [-0.7, 0.25]);
+        let at = |src: &str| eval(&parse(src), z, c, [2.0, 0.5]);
+        assert_eq!(at("z^2 + c"), at("z*z + c"));
+        assert_eq!(at("-z^2"), at("-(z*z)"));
+        assert_eq!(at("z - c - c"), at("(z - c) - c"));
+        assert_eq!(at("a*z + b"), [2.0 * 0.3 + 0.5, 2.0 * -0.2]);
+        assert_eq!(at("i*i"), [-1.0, 0.0]);
+        assert_eq!(at("abs(z)^2 + c"), at("abs(z)*abs(z) + c"));
+    }
+
+    #[test]
+    fn the_request_example_parses() {
+        let e = parse("z*z*z + c*sin(z)");
+        let [x, y] = eval(&e, [0.1, 0.2], [0.5, 0.0], [0.0; 2]);
+        assert!((x - (-0.011 + 0.5 * 0.1f64.sin() * 0.2f64.cosh())).abs() < 1e-9);
+        assert!((y - (-0.002 + 0.5 * 0.1f64.cos() * 0.2f64.sinh())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn integer_powers_square_and_others_use_pow() {
+        assert!(matches!(parse("z^3"), Expr::PowInt(_, 3)));
+        assert!(matches!(parse("z^2.5"), Expr::Bin(Op::Pow, ..)));
+        assert!(matches!(parse("z^-1"), Expr::Bin(Op::Pow, ..)));
+        assert!(matches!(parse("z^a"), Expr::Bin(Op::Pow, ..)));
+        assert!(matches!(parse("z^1000"), Expr::Bin(Op::Pow, ..)));
+    }
+
+    #[test]
+    fn wgsl_calls_the_template_helpers() {
+        let f = Formula::parse(" z^3 + c*sin(z) - a ").unwrap();
+        assert_eq!(f.source(), "z^3 + c*sin(z) - a");
+        assert!(f
+            .to_wgsl()
+            .contains("return ((c_powi(z, 3u) + c_mul(c, c_sin(z))) - vec2<f32>(fp.a, 0.0));"));
+        assert_eq!(Formula::default().source(), DEFAULT_FORMULA);
+        assert!(!f.uses_time());
+        assert!(Formula::parse("z^2 + c*exp(i*t)").unwrap().uses_time());
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        assert_eq!(error("z^2 + x").column, 7);
+        assert!(error("z^2 + x").message.starts_with("unknown name `x`"));
+        assert!(error("foo(z)")
+            .message
+            .starts_with("unknown function `foo`"));
+        assert!(error("sin").message.contains("call it"));
+        assert_eq!(
+            error("(z + c").message,
+            "expected `)` to close column 1, found end of formula"
+        );
+        assert_eq!(error("z + ").message, "formula ends too soon");
+        assert_eq!(error("z c").message, "unexpected `c`");
+        assert_eq!(error("z $ c").column, 3);
+        assert!(error("1.2.3").message.starts_with("invalid number"));
+        assert!(error("").message.contains("ends too soon"));
+        assert!(error(&"(".repeat(40)).message.starts_with("nested deeper"));
+        assert!(error(&"z+".repeat(200)).message.starts_with("longer than"));
+    }
+}
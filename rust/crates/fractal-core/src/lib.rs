@@ -1,6 +1,25 @@
+pub mod animation;
+pub mod audio;
+pub mod bundle;
+pub mod coloring;
+pub mod flame;
+pub mod formula;
+pub mod macros;
+pub mod meta;
 pub mod modulators;
+pub mod overrides;
 pub mod patch;
+pub mod perturbation;
 pub mod presets;
+pub mod projection;
+pub mod sanitize;
+pub mod snapshot;
+pub mod tiling;
+pub mod validate;
+pub mod versioning;
+pub mod viewcode;
+pub mod warp;
+pub mod zoomvideo;
 
 use std::collections::HashMap;
 
@@ -19,6 +38,10 @@ pub struct Params {
     pub max_iter: u32,
     pub mouse_x: f32,
     pub mouse_y: f32,
+    /// Latest audio analysis frame: magnitude spectrum (low → high, 0–1)
+    /// and waveform samples (−1–1).  Empty while there is no audio input.
+    pub spectrum: Vec<f32>,
+    pub waveform: Vec<f32>,
 }
 
 impl Default for Params {
@@ -33,6 +56,8 @@ impl Default for Params {
             max_iter: 100,
             mouse_x: 0.0,
             mouse_y: 0.0,
+            spectrum: Vec::new(),
+            waveform: Vec::new(),
         }
     }
 }
@@ -42,8 +67,15 @@ impl Params {
         *self.fields.get(key).unwrap_or(&0.0)
     }
 
-    pub fn set(&mut self, key: impl Into<String>, value: f32) {
-        self.fields.insert(key.into(), value);
+    /// Set `key`, allocating its name only the first time it is set:
+    /// modulators write the same keys every frame.
+    pub fn set(&mut self, key: impl AsRef<str> + Into<String>, value: f32) {
+        match self.fields.get_mut(key.as_ref()) {
+            Some(slot) => *slot = value,
+            None => {
+                self.fields.insert(key.into(), value);
+            }
+        }
     }
 }
 
@@ -58,13 +90,51 @@ pub enum GeneratorKind {
     Julia,
     BurningShip,
     NoiseField,
+    /// Ridged / turbulent simplex FBM; see [`SimplexFieldGen`].
+    SimplexField,
+    /// Cellular (Worley) noise; see [`WorleyFieldGen`].
+    WorleyField,
+    /// Gray–Scott reaction–diffusion, stepped across frames; see
+    /// [`ReactionDiffusionGen`].
+    ReactionDiffusion,
+    /// Noise FBM warped through itself; see [`DomainWarpGen`].
+    DomainWarp,
+    /// Summed, drifting sine waves; see [`PlasmaGen`].
+    Plasma,
+    Video,
+    Image,
+    TestPattern,
+    InverseJulia,
+    Multibrot,
+    Celtic,
+    Buffalo,
+    Ifs,
+    Flame,
+    Kleinian,
+    /// Mandelbrot and Burning Ship steps interleaved on a schedule; see
+    /// [`HybridGen`].
+    Hybrid(HybridSchedule),
+    /// A user-typed iteration step; the formula itself is on the
+    /// [`CustomFormulaGen`] so this stays `Copy`.
+    CustomFormula,
 }
 
-/// Describes which effect to apply and its configuration.
+/// Describes which effect to apply and its configuration.  The audio
+/// overlay's samples are inline rather than boxed: the chain is rebuilt
+/// every frame and must not allocate.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum EffectKind {
     ColorMap {
         scheme: ColorScheme,
+        /// Paint the orbit-trap distance the generator wrote to the green
+        /// channel instead of the escape count (see
+        /// [`Coloring::OrbitTrap`](coloring::Coloring::OrbitTrap)).
+        orbit_trap: bool,
+        /// Paint the distance estimate the generator wrote to the alpha
+        /// channel, brightest on the set's boundary (see
+        /// [`Coloring::EdgeGlow`](coloring::Coloring::EdgeGlow)).
+        edge_glow: bool,
     },
     Ripple {
         frequency: f32,
@@ -83,11 +153,77 @@ pub enum EffectKind {
         brightness: f32,
         contrast: f32,
     },
+    /// Quantise each channel to `levels` steps.
+    Posterize {
+        levels: f32,
+    },
+    /// Blend towards the negative: `0.0` leaves the image alone, `1.0`
+    /// inverts it fully.
+    Invert {
+        amount: f32,
+    },
     MotionBlur {
         opacity: f32,
+        /// Unit vector (screen space) the streak is smeared along.
+        direction: [f32; 2],
+        /// Streak length in pixels; `0.0` disables the directional smear.
+        length: f32,
     },
+    AudioOverlay {
+        mode: OverlayMode,
+        /// Top-left corner as a fraction of the output size.
+        position: [f32; 2],
+        /// Width and height as a fraction of the output size.
+        size: [f32; 2],
+        scheme: ColorScheme,
+        /// Spectrum bins in \[0, 1\] or waveform samples in \[-1, 1\].
+        /// A fixed array, so building the chain each frame allocates nothing.
+        samples: [f32; OVERLAY_SAMPLES],
+    },
+}
+
+impl EffectKind {
+    /// Stable name of the variant, as its shader file is named; keys a
+    /// bundle's edited effect shaders.
+    pub fn id(&self) -> &'static str {
+        match self {
+            EffectKind::ColorMap { .. } => "color_map",
+            EffectKind::Ripple { .. } => "ripple",
+            EffectKind::Echo { .. } => "echo",
+            EffectKind::HueShift { .. } => "hue_shift",
+            EffectKind::BrightnessContrast { .. } => "brightness_contrast",
+            EffectKind::Posterize { .. } => "posterize",
+            EffectKind::Invert { .. } => "invert",
+            EffectKind::MotionBlur { .. } => "motion_blur",
+            EffectKind::AudioOverlay { .. } => "audio_overlay",
+        }
+    }
+
+    /// Whether the effect maps each pixel's colour on its own, reading no
+    /// neighbours, position or history.  Runs of these can share one pass.
+    pub fn is_pointwise(&self) -> bool {
+        matches!(
+            self,
+            EffectKind::HueShift { .. }
+                | EffectKind::BrightnessContrast { .. }
+                | EffectKind::Posterize { .. }
+                | EffectKind::Invert { .. }
+        )
+    }
+}
+
+/// What an audio overlay draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayMode {
+    /// Magnitude spectrum as bars, low frequencies on the left.
+    Spectrum,
+    /// Waveform as a line through the middle of the box.
+    Waveform,
 }
 
+/// Samples an audio overlay hands the GPU, whatever the analysis size.
+pub const OVERLAY_SAMPLES: usize = 64;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ColorScheme {
     Classic,
@@ -100,30 +236,67 @@ pub trait Generator: Send + Sync {
     fn kind(&self) -> GeneratorKind;
     /// Which Params fields affect the generator output (used for cache invalidation).
     fn gen_param_keys(&self) -> &[&'static str];
+    /// The generator's iteration step, if it is user-typed (see
+    /// [`CustomFormulaGen`]); the GPU layer compiles it into the shader.
+    fn formula(&self) -> Option<&formula::Formula> {
+        None
+    }
 }
 
 pub trait Effect: Send + Sync {
     /// Return the GPU-ready descriptor for this effect, optionally reading
     /// dynamic parameters from `params` (e.g. an LFO-driven hue amount).
     fn kind(&self, params: &Params) -> EffectKind;
+    /// Which Params fields the effect reads (used to validate modulator
+    /// targets).
+    fn param_keys(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
 }
 
 pub trait Modulator: Send + Sync {
     fn modulate(&self, params: &mut Params);
+    /// What `modulate` would write to `target` at `params`, or `None` if it
+    /// doesn't write `target`.  A [`ModMatrix`](modulators::ModMatrix)
+    /// route reads its source through this every frame; the default runs
+    /// `modulate` on a copy of `params`, so modulators with a single
+    /// output should compute it directly instead.
+    fn output(&self, params: &Params, target: &str) -> Option<f32> {
+        let mut scratch = params.clone();
+        self.modulate(&mut scratch);
+        scratch.fields.get(target).copied()
+    }
+    /// Which Params fields the modulator writes; each should be read by the
+    /// patch's generator or an effect (see `Patch::unconsumed_targets`).
+    fn targets(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// Which Params fields `modulate` reads, besides `time` and the mouse.
+    /// A modulator evaluated off the render thread is sent just these.
+    fn inputs(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// The modulator as an [`Lfo`](modulators::Lfo), so a control panel
+    /// can show its settings and swap in an edited copy (see
+    /// [`Patch::set_lfo`](patch::Patch::set_lfo)).
+    fn as_lfo(&self) -> Option<&modulators::Lfo> {
+        None
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Concrete generator implementations
 // ---------------------------------------------------------------------------
 
-/// Mandelbrot set — z_{n+1} = z_n² + c, z_0 = 0.
+/// Mandelbrot set — z_{n+1} = z_n² + c, z_0 = 0.  Coloured by
+/// [`Coloring`](coloring::Coloring), like the other escape-time generators.
 pub struct MandelbrotGen;
 impl Generator for MandelbrotGen {
     fn kind(&self) -> GeneratorKind {
         GeneratorKind::Mandelbrot
     }
     fn gen_param_keys(&self) -> &[&'static str] {
-        &[]
+        &coloring::COLORING_KEYS
     }
 }
 
@@ -137,7 +310,211 @@ impl Generator for JuliaGen {
         GeneratorKind::Julia
     }
     fn gen_param_keys(&self) -> &[&'static str] {
-        &["julia_cx", "julia_cy"]
+        &[
+            "julia_cx",
+            "julia_cy",
+            "coloring",
+            "stalk_width",
+            "trap_shape",
+            "trap_x",
+            "trap_y",
+            "trap_angle",
+            "trap_radius",
+        ]
+    }
+}
+
+/// Julia set drawn by inverse iteration: random walkers follow the backward
+/// orbit z → ±√(z − c), which settles onto the Julia set, and every point
+/// they land on brightens its pixel.  Only the boundary lights up, as dust,
+/// instead of the escape-time fill.  `max_iter` is the number of backward
+/// steps per walker; `julia_cx` / `julia_cy` are shared with [`JuliaGen`],
+/// and `inverse_julia_exposure` is how many hits make a pixel full bright.
+pub struct InverseJuliaGen;
+
+impl InverseJuliaGen {
+    pub const DEFAULT_EXPOSURE: f32 = 12.0;
+}
+
+impl Generator for InverseJuliaGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::InverseJulia
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &["julia_cx", "julia_cy", "inverse_julia_exposure"]
+    }
+}
+
+/// One affine map of an iterated function system,
+/// (x, y) → (a·x + b·y + e, c·x + d·y + f), picked with probability
+/// proportional to `weight`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct IfsMap {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+    pub weight: f32,
+}
+
+impl IfsMap {
+    /// Scale by `scale` towards `(x, y)` — one corner of a Sierpinski-style
+    /// gasket.
+    pub const fn toward(x: f32, y: f32, scale: f32) -> Self {
+        Self {
+            a: scale,
+            b: 0.0,
+            c: 0.0,
+            d: scale,
+            e: x * (1.0 - scale),
+            f: y * (1.0 - scale),
+            weight: 1.0,
+        }
+    }
+}
+
+/// Iterated function system drawn by the chaos game: random walkers apply
+/// one of up to [`IfsGen::MAX_MAPS`] affine maps, picked by weight, at every
+/// step, and every point they land on brightens its pixel, as for
+/// [`InverseJuliaGen`].  Map `i`'s coefficients are the Params fields
+/// `ifs{i}_a` … `ifs{i}_f` and `ifs{i}_weight`, so patches define their own
+/// systems and LFOs can bend them; a map with weight 0 is never picked.
+/// `max_iter` is the number of steps per walker, and `ifs_exposure` is how
+/// many hits make a pixel full bright.
+pub struct IfsGen;
+
+/// Params keys of each map's coefficients, `a` … `f` then `weight`, followed
+/// by `ifs_exposure`.
+const IFS_KEYS: [&str; IfsGen::MAX_MAPS * 7 + 1] = [
+    "ifs0_a",
+    "ifs0_b",
+    "ifs0_c",
+    "ifs0_d",
+    "ifs0_e",
+    "ifs0_f",
+    "ifs0_weight",
+    "ifs1_a",
+    "ifs1_b",
+    "ifs1_c",
+    "ifs1_d",
+    "ifs1_e",
+    "ifs1_f",
+    "ifs1_weight",
+    "ifs2_a",
+    "ifs2_b",
+    "ifs2_c",
+    "ifs2_d",
+    "ifs2_e",
+    "ifs2_f",
+    "ifs2_weight",
+    "ifs3_a",
+    "ifs3_b",
+    "ifs3_c",
+    "ifs3_d",
+    "ifs3_e",
+    "ifs3_f",
+    "ifs3_weight",
+    "ifs_exposure",
+];
+
+impl IfsGen {
+    pub const MAX_MAPS: usize = 4;
+    pub const DEFAULT_EXPOSURE: f32 = 24.0;
+
+    /// Barnsley's fern, flipped to grow up the screen (plane y points
+    /// down).  It spans x ∈ \[-2.2, 2.7\], y ∈ \[-10, 0\].
+    pub const BARNSLEY_FERN: [IfsMap; 4] = [
+        IfsMap {
+            a: 0.0,
+            b: 0.0,
+            c: 0.0,
+            d: 0.16,
+            e: 0.0,
+            f: 0.0,
+            weight: 0.01,
+        },
+        IfsMap {
+            a: 0.85,
+            b: -0.04,
+            c: 0.04,
+            d: 0.85,
+            e: 0.0,
+            f: -1.6,
+            weight: 0.85,
+        },
+        IfsMap {
+            a: 0.2,
+            b: 0.26,
+            c: -0.23,
+            d: 0.22,
+            e: 0.0,
+            f: -1.6,
+            weight: 0.07,
+        },
+        IfsMap {
+            a: -0.15,
+            b: -0.28,
+            c: -0.26,
+            d: 0.24,
+            e: 0.0,
+            f: -0.44,
+            weight: 0.07,
+        },
+    ];
+
+    /// Sierpinski triangle on the unit circle, apex up.
+    pub const SIERPINSKI: [IfsMap; 3] = [
+        IfsMap::toward(0.0, -1.0, 0.5),
+        IfsMap::toward(-0.866_025_4, 0.5, 0.5),
+        IfsMap::toward(0.866_025_4, 0.5, 0.5),
+    ];
+
+    /// Write `maps` into `params`, zeroing the weight of every map slot
+    /// past them.  At most [`MAX_MAPS`](Self::MAX_MAPS) are written.
+    pub fn set_maps(params: &mut Params, maps: &[IfsMap]) {
+        for i in 0..Self::MAX_MAPS {
+            let m = maps.get(i).copied().unwrap_or_default();
+            let keys = &IFS_KEYS[i * 7..i * 7 + 7];
+            for (key, value) in keys.iter().zip([m.a, m.b, m.c, m.d, m.e, m.f, m.weight]) {
+                params.set(*key, value);
+            }
+        }
+    }
+
+    /// Every map slot as currently set in `params`.  Weights are clamped to
+    /// be non-negative and non-finite coefficients read as 0, so a wild
+    /// modulator can't stall or blank the walkers.
+    pub fn maps(params: &Params) -> [IfsMap; Self::MAX_MAPS] {
+        std::array::from_fn(|i| {
+            let v = |j: usize| {
+                let x = params.get(IFS_KEYS[i * 7 + j]);
+                if x.is_finite() {
+                    x
+                } else {
+                    0.0
+                }
+            };
+            IfsMap {
+                a: v(0),
+                b: v(1),
+                c: v(2),
+                d: v(3),
+                e: v(4),
+                f: v(5),
+                weight: v(6).max(0.0),
+            }
+        })
+    }
+}
+
+impl Generator for IfsGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Ifs
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &IFS_KEYS
     }
 }
 
@@ -148,21 +525,694 @@ impl Generator for BurningShipGen {
         GeneratorKind::BurningShip
     }
     fn gen_param_keys(&self) -> &[&'static str] {
-        &[]
+        &coloring::COLORING_KEYS
+    }
+}
+
+/// Celtic fractal — Mandelbrot with |Re(z²)| each iteration.  Drawn by
+/// the Burning Ship shader with its `fold` set to 1.
+pub struct CelticGen;
+impl Generator for CelticGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Celtic
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &coloring::COLORING_KEYS
+    }
+}
+
+/// Buffalo fractal — Mandelbrot with |Re(z²)| and |Im(z²)| each iteration.
+/// Drawn by the Burning Ship shader with its `fold` set to 2.
+pub struct BuffaloGen;
+impl Generator for BuffaloGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Buffalo
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &coloring::COLORING_KEYS
+    }
+}
+
+/// Multibrot set — z_{n+1} = z_n^d + c, z_0 = 0, for a real exponent `d`
+/// read from `Params::fields["multibrot_power"]` each frame, so an LFO can
+/// sweep it between integer powers.  `d` = 2 is the Mandelbrot set; the
+/// iteration runs in polar form, so fractional powers show the branch cut
+/// along the negative real axis.  Coloured like the other escape-time
+/// generators.
+pub struct MultibrotGen;
+
+impl MultibrotGen {
+    pub const DEFAULT_POWER: f32 = 3.0;
+    /// Exponent range the shader is given; beyond it the orbit escapes or
+    /// collapses in a step or two and nothing is left to see.
+    pub const MIN_POWER: f32 = 1.0;
+    pub const MAX_POWER: f32 = 16.0;
+
+    /// Exponent from `multibrot_power`, clamped to \[MIN_POWER, MAX_POWER\];
+    /// unset or non-finite values fall back to the default.
+    pub fn power(params: &Params) -> f32 {
+        match params.fields.get("multibrot_power") {
+            Some(d) if d.is_finite() => d.clamp(Self::MIN_POWER, Self::MAX_POWER),
+            _ => Self::DEFAULT_POWER,
+        }
+    }
+}
+
+impl Generator for MultibrotGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Multibrot
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &[
+            "multibrot_power",
+            "coloring",
+            "stalk_width",
+            "trap_shape",
+            "trap_x",
+            "trap_y",
+            "trap_angle",
+            "trap_radius",
+        ]
+    }
+}
+
+/// The repeating pattern of formulas a [`HybridGen`] orbit steps through,
+/// written as a string of `M` (Mandelbrot, z² + c) and `B` (Burning Ship,
+/// (|Re z| + i·|Im z|)² + c): "MMBB" takes two Mandelbrot steps, then two
+/// Burning Ship steps, then starts over.  Stored as a bitmask so
+/// [`GeneratorKind`] stays `Copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HybridSchedule {
+    /// Bit i set: step i of the period is a Burning Ship step.
+    ship_steps: u32,
+    period: u32,
+}
+
+impl HybridSchedule {
+    /// Longest pattern the bitmask holds.
+    pub const MAX_PERIOD: usize = 32;
+    /// "MMBB".
+    pub const DEFAULT: Self = Self {
+        ship_steps: 0b1100,
+        period: 4,
+    };
+
+    /// Parse a pattern of `M` and `B` (either case), 1 to
+    /// [`MAX_PERIOD`](Self::MAX_PERIOD) steps long; `None` for anything else.
+    pub fn parse(pattern: &str) -> Option<Self> {
+        if pattern.is_empty() || pattern.len() > Self::MAX_PERIOD {
+            return None;
+        }
+        let mut ship_steps = 0;
+        for (i, step) in pattern.chars().enumerate() {
+            match step.to_ascii_uppercase() {
+                'M' => {}
+                'B' => ship_steps |= 1 << i,
+                _ => return None,
+            }
+        }
+        Some(Self {
+            ship_steps,
+            period: pattern.len() as u32,
+        })
+    }
+
+    /// Steps before the pattern repeats.
+    pub fn period(&self) -> u32 {
+        self.period
+    }
+
+    /// Bit i set: step i of the period is a Burning Ship step.
+    pub fn ship_steps(&self) -> u32 {
+        self.ship_steps
+    }
+
+    /// Whether orbit step `step` (counting from 0) is a Burning Ship step.
+    pub fn is_ship(&self, step: u32) -> bool {
+        self.ship_steps & (1 << (step % self.period)) != 0
+    }
+}
+
+impl Default for HybridSchedule {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl std::fmt::Display for HybridSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for i in 0..self.period {
+            f.write_str(if self.is_ship(i) { "B" } else { "M" })?;
+        }
+        Ok(())
+    }
+}
+
+/// Hybrid escape-time fractal: the orbit of c starts at 0 and alternates
+/// between the Mandelbrot and Burning Ship formulas on a
+/// [`HybridSchedule`], so the shader branches per iteration.  The schedule
+/// is part of the [`GeneratorKind`] rather than a param because it is
+/// structural, not something an LFO sweeps.  Coloured like the other
+/// escape-time generators.
+pub struct HybridGen {
+    pub schedule: HybridSchedule,
+}
+
+impl Generator for HybridGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Hybrid(self.schedule)
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &coloring::COLORING_KEYS
+    }
+}
+
+/// Kleinian group limit set in Maskit's slice, drawn per pixel in the style
+/// of Jos Leys and knighty: each point is folded into the group's
+/// fundamental strip and pushed through the generator `a` until it leaves
+/// the strip (outside the limit set), settles into a 2-cycle (inside one of
+/// the tangent discs), or runs out of iterations (on the limit set, drawn
+/// brightest).  The trace of `a` is `kleinian_trace_re` + i·`kleinian_trace_im`:
+/// the real part is the height of the strip, the imaginary part shears it,
+/// and sweeping either morphs the whole circle packing.
+pub struct KleinianGen;
+
+impl KleinianGen {
+    /// A just-broken Apollonian-like packing with a gentle twist.
+    pub const DEFAULT_TRACE: [f32; 2] = [1.95, 0.02];
+    /// Range of each trace component the shader is given; outside it the
+    /// slice leaves the region the folding heuristics were tuned for.
+    pub const MIN_TRACE: [f32; 2] = [1.6, -0.5];
+    pub const MAX_TRACE: [f32; 2] = [2.2, 0.5];
+
+    /// The trace from `kleinian_trace_re` / `kleinian_trace_im`, each
+    /// clamped to its range; unset or non-finite parts fall back to the
+    /// default.
+    pub fn trace(params: &Params) -> [f32; 2] {
+        let part = |key: &str, i: usize| match params.fields.get(key) {
+            Some(v) if v.is_finite() => v.clamp(Self::MIN_TRACE[i], Self::MAX_TRACE[i]),
+            _ => Self::DEFAULT_TRACE[i],
+        };
+        [part("kleinian_trace_re", 0), part("kleinian_trace_im", 1)]
+    }
+
+    /// Seed the trace keys with their defaults.
+    pub fn set_defaults(params: &mut Params) {
+        params.set("kleinian_trace_re", Self::DEFAULT_TRACE[0]);
+        params.set("kleinian_trace_im", Self::DEFAULT_TRACE[1]);
+    }
+}
+
+impl Generator for KleinianGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Kleinian
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &["kleinian_trace_re", "kleinian_trace_im"]
+    }
+}
+
+/// Escape-time fractal from a user-typed step such as `z*z*z + c*sin(z)`
+/// (see [`formula`] for the language), compiled to WGSL at runtime.  The
+/// orbit starts at `c`, the pixel, so formulas that vanish at zero still
+/// move; with `formula_julia` above ½ it starts at the pixel instead and
+/// `c` is the constant `julia_cx` + i·`julia_cy`.  `formula_a` and
+/// `formula_b` are the formula's `a` and `b`, free for LFOs to sweep.
+/// Colour is the smoothed escape count, like the Mandelbrot.
+pub struct CustomFormulaGen {
+    pub formula: formula::Formula,
+}
+
+impl CustomFormulaGen {
+    /// Whether `formula_julia` asks for the Julia form.
+    pub fn julia(params: &Params) -> bool {
+        params.get("formula_julia") > 0.5
+    }
+}
+
+impl Generator for CustomFormulaGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::CustomFormula
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &[
+            "formula_a",
+            "formula_b",
+            "formula_julia",
+            "julia_cx",
+            "julia_cy",
+        ]
+    }
+    fn formula(&self) -> Option<&formula::Formula> {
+        Some(&self.formula)
     }
 }
 
-/// Noise field — 4-octave FBM animated with `time`.
+/// Noise field — FBM over 3-D simplex noise, sliced at `noise_z`.
+///
+/// The FBM structure is read from `Params::fields` each frame so LFOs can
+/// breathe it: `noise_scale`, `noise_octaves`, `noise_lacunarity`,
+/// `noise_gain` and `noise_seed`. [`NoiseFieldGen::set_defaults`] seeds them
+/// with the values the shader used to hard-code.
+///
+/// The z coordinate of the slice comes from `noise_z` when that key is set
+/// (e.g. by an audio route) and falls back to `time` otherwise, giving
+/// volumetric evolution rather than a scroll.
 pub struct NoiseFieldGen;
+
+impl NoiseFieldGen {
+    pub const DEFAULT_SCALE: f32 = 3.0;
+    pub const DEFAULT_OCTAVES: u32 = 4;
+    pub const DEFAULT_LACUNARITY: f32 = 2.0;
+    pub const DEFAULT_GAIN: f32 = 0.5;
+    /// Upper bound on FBM octaves — the shader loop is capped to match.
+    pub const MAX_OCTAVES: u32 = 8;
+
+    /// Write the default noise parameters into `params`.
+    pub fn set_defaults(params: &mut Params) {
+        params.set("noise_scale", Self::DEFAULT_SCALE);
+        params.set("noise_octaves", Self::DEFAULT_OCTAVES as f32);
+        params.set("noise_lacunarity", Self::DEFAULT_LACUNARITY);
+        params.set("noise_gain", Self::DEFAULT_GAIN);
+        params.set("noise_seed", 0.0);
+    }
+
+    /// Octave count from `noise_octaves`, rounded and clamped to
+    /// \[1, MAX_OCTAVES\] so a modulated value can't stall the shader.
+    pub fn octaves(params: &Params) -> u32 {
+        (params.get("noise_octaves").round().max(1.0) as u32).min(Self::MAX_OCTAVES)
+    }
+
+    /// Z coordinate of the noise slice: `noise_z` if present, else `time`.
+    pub fn z_slice(params: &Params) -> f32 {
+        params.fields.get("noise_z").copied().unwrap_or(params.time)
+    }
+}
+
 impl Generator for NoiseFieldGen {
     fn kind(&self) -> GeneratorKind {
         GeneratorKind::NoiseField
     }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &[
+            "noise_scale",
+            "noise_octaves",
+            "noise_lacunarity",
+            "noise_gain",
+            "noise_seed",
+            "noise_z",
+        ]
+    }
+}
+
+/// Simplex field — the same 3-D simplex FBM as [`NoiseFieldGen`], read
+/// from the same `noise_*` keys, but with every octave folded at zero so
+/// the field forms creases instead of smooth hills.  `simplex_ridge` blends
+/// from billowy turbulence, |n| (0), to sharp ridges, (1 − |n|)² (1): smoke
+/// and cloud banks at one end, marble veins and lightning at the other.
+pub struct SimplexFieldGen;
+
+impl SimplexFieldGen {
+    pub const DEFAULT_RIDGE: f32 = 1.0;
+
+    /// Write the noise defaults plus `simplex_ridge` into `params`.
+    pub fn set_defaults(params: &mut Params) {
+        NoiseFieldGen::set_defaults(params);
+        params.set("simplex_ridge", Self::DEFAULT_RIDGE);
+    }
+
+    /// The ridge blend from `simplex_ridge`, clamped to \[0, 1\].
+    pub fn ridge(params: &Params) -> f32 {
+        let ridge = params.get("simplex_ridge");
+        if ridge.is_finite() {
+            ridge.clamp(0.0, 1.0)
+        } else {
+            Self::DEFAULT_RIDGE
+        }
+    }
+}
+
+impl Generator for SimplexFieldGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::SimplexField
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &[
+            "noise_scale",
+            "noise_octaves",
+            "noise_lacunarity",
+            "noise_gain",
+            "noise_seed",
+            "noise_z",
+            "simplex_ridge",
+        ]
+    }
+}
+
+/// Worley field — cellular noise: the distance from each point to the
+/// nearest of a jittered lattice of feature points in 3-D, sliced at
+/// `noise_z` like [`NoiseFieldGen`], so the cells drift and split over time.
+/// `noise_scale` sets the cell density and `noise_seed` the layout.
+/// `worley_jitter` scatters the feature points from a regular grid (0) to
+/// fully random (1); `worley_edge` blends from round cells, F1 (0), to
+/// their borders, F2 − F1 (1).
+pub struct WorleyFieldGen;
+
+impl WorleyFieldGen {
+    pub const DEFAULT_SCALE: f32 = 6.0;
+    pub const DEFAULT_JITTER: f32 = 1.0;
+
+    /// Write the default cell parameters into `params`.
+    pub fn set_defaults(params: &mut Params) {
+        params.set("noise_scale", Self::DEFAULT_SCALE);
+        params.set("noise_seed", 0.0);
+        params.set("worley_jitter", Self::DEFAULT_JITTER);
+        params.set("worley_edge", 0.0);
+    }
+
+    /// Feature point scatter from `worley_jitter`, clamped to \[0, 1\] so
+    /// points stay inside their cell and the 3×3×3 search finds the nearest.
+    pub fn jitter(params: &Params) -> f32 {
+        unit(params.get("worley_jitter"))
+    }
+
+    /// Cell / border blend from `worley_edge`, clamped to \[0, 1\].
+    pub fn edge(params: &Params) -> f32 {
+        unit(params.get("worley_edge"))
+    }
+}
+
+/// `v` clamped to \[0, 1\], with non-finite values as 0.
+fn unit(v: f32) -> f32 {
+    if v.is_finite() {
+        v.clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+impl Generator for WorleyFieldGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::WorleyField
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &[
+            "noise_scale",
+            "noise_seed",
+            "noise_z",
+            "worley_jitter",
+            "worley_edge",
+        ]
+    }
+}
+
+/// Gray–Scott reaction–diffusion: two chemicals, U fed in and V feeding on
+/// it, diffuse over a grid of output pixels and react, growing spots,
+/// stripes, coral and mitosis depending on the feed and kill rates.  Unlike
+/// the other generators its image is state: the GPU keeps the chemical
+/// concentrations in a texture pair it ping-pongs across frames, running
+/// `reaction_steps` simulation steps per frame.  `reaction_feed` and
+/// `reaction_kill` are read every step, so LFOs can push the pattern between
+/// regimes while it grows; `reaction_seed` picks the layout of the V spots it
+/// starts from.  The simulation runs in output pixels, so `center` / `zoom`
+/// have no effect.  V lands in the red channel for the colour map.
+pub struct ReactionDiffusionGen;
+
+impl ReactionDiffusionGen {
+    /// Feed and kill rates of the "coral" regime.
+    pub const DEFAULT_FEED: f32 = 0.0545;
+    pub const DEFAULT_KILL: f32 = 0.062;
+    /// Range of each rate the shader is given; the interesting regimes all
+    /// sit well inside it, and past it V dies out or floods the grid.
+    pub const MAX_RATE: f32 = 0.1;
+    pub const DEFAULT_STEPS: u32 = 8;
+    /// Upper bound on steps per frame, so a modulated value can't stall the
+    /// GPU.
+    pub const MAX_STEPS: u32 = 32;
+
+    /// Write the default rates and step count into `params`.
+    pub fn set_defaults(params: &mut Params) {
+        params.set("reaction_feed", Self::DEFAULT_FEED);
+        params.set("reaction_kill", Self::DEFAULT_KILL);
+        params.set("reaction_steps", Self::DEFAULT_STEPS as f32);
+        params.set("reaction_seed", 0.0);
+    }
+
+    /// Feed rate from `reaction_feed`, clamped to \[0, MAX_RATE\]; unset or
+    /// non-finite values fall back to the default.
+    pub fn feed(params: &Params) -> f32 {
+        Self::rate(params, "reaction_feed", Self::DEFAULT_FEED)
+    }
+
+    /// Kill rate from `reaction_kill`, clamped like [`feed`](Self::feed).
+    pub fn kill(params: &Params) -> f32 {
+        Self::rate(params, "reaction_kill", Self::DEFAULT_KILL)
+    }
+
+    fn rate(params: &Params, key: &str, default: f32) -> f32 {
+        match params.fields.get(key) {
+            Some(v) if v.is_finite() => v.clamp(0.0, Self::MAX_RATE),
+            _ => default,
+        }
+    }
+
+    /// Steps per frame from `reaction_steps`, rounded and clamped to
+    /// \[1, MAX_STEPS\].
+    pub fn steps(params: &Params) -> u32 {
+        match params.fields.get("reaction_steps") {
+            Some(v) if v.is_finite() => (v.round().max(1.0) as u32).min(Self::MAX_STEPS),
+            _ => Self::DEFAULT_STEPS,
+        }
+    }
+}
+
+impl Generator for ReactionDiffusionGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::ReactionDiffusion
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &[
+            "reaction_feed",
+            "reaction_kill",
+            "reaction_steps",
+            "reaction_seed",
+        ]
+    }
+}
+
+/// Domain-warped noise — the noise field's FBM looked up at a point pushed
+/// around by the FBM itself, two or three times over (after Inigo Quilez):
+/// each layer samples two FBMs at the point the previous layer displaced,
+/// and their values become the next displacement.  Where the flat
+/// [`NoiseFieldGen`] gives blobs, this gives folded, marbled currents that
+/// flow as `noise_z` advances.  The FBM reads the `noise_*` keys;
+/// `warp_strength` scales each displacement (in noise-lattice units),
+/// `warp_rotation` turns it by that many radians per layer, and
+/// `warp_layers` picks two or three layers.  Strength and rotation are read
+/// every frame, so LFOs can stir the currents.
+pub struct DomainWarpGen;
+
+impl DomainWarpGen {
+    pub const DEFAULT_STRENGTH: f32 = 4.0;
+    /// Upper bound on `warp_strength`; far past it neighbouring pixels land
+    /// on unrelated noise and the field turns to static.
+    pub const MAX_STRENGTH: f32 = 16.0;
+    pub const DEFAULT_LAYERS: u32 = 2;
+    pub const MIN_LAYERS: u32 = 2;
+    /// Upper bound on layers — the shader's offset table has this many.
+    pub const MAX_LAYERS: u32 = 3;
+
+    /// Write the noise defaults plus the warp parameters into `params`.
+    pub fn set_defaults(params: &mut Params) {
+        NoiseFieldGen::set_defaults(params);
+        params.set("warp_strength", Self::DEFAULT_STRENGTH);
+        params.set("warp_rotation", 0.0);
+        params.set("warp_layers", Self::DEFAULT_LAYERS as f32);
+    }
+
+    /// Displacement scale from `warp_strength`, clamped to
+    /// \[0, MAX_STRENGTH\]; unset or non-finite values fall back to the
+    /// default.
+    pub fn strength(params: &Params) -> f32 {
+        match params.fields.get("warp_strength") {
+            Some(v) if v.is_finite() => v.clamp(0.0, Self::MAX_STRENGTH),
+            _ => Self::DEFAULT_STRENGTH,
+        }
+    }
+
+    /// Turn per layer from `warp_rotation`, wrapped to \[0, 2π) so a
+    /// ramping value keeps its precision; non-finite values are 0.
+    pub fn rotation(params: &Params) -> f32 {
+        let rotation = params.get("warp_rotation");
+        if rotation.is_finite() {
+            rotation.rem_euclid(std::f32::consts::TAU)
+        } else {
+            0.0
+        }
+    }
+
+    /// Layer count from `warp_layers`, rounded and clamped to
+    /// \[MIN_LAYERS, MAX_LAYERS\].
+    pub fn layers(params: &Params) -> u32 {
+        match params.fields.get("warp_layers") {
+            Some(v) if v.is_finite() => {
+                (v.round().max(Self::MIN_LAYERS as f32) as u32).min(Self::MAX_LAYERS)
+            }
+            _ => Self::DEFAULT_LAYERS,
+        }
+    }
+}
+
+impl Generator for DomainWarpGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::DomainWarp
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &[
+            "noise_scale",
+            "noise_octaves",
+            "noise_lacunarity",
+            "noise_gain",
+            "noise_seed",
+            "noise_z",
+            "warp_strength",
+            "warp_rotation",
+            "warp_layers",
+        ]
+    }
+}
+
+/// Live frames from a webcam or video file, streamed in by the app.  The
+/// frame spans y ∈ \[-1, 1\] of the plane around the origin at its own
+/// aspect ratio, so `center` / `zoom` pan and zoom it like a fractal; outside
+/// it is black.  Colour lands in RGB, ready for the effect chain (a colour
+/// map reads only the red channel).
+pub struct VideoGen;
+impl Generator for VideoGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Video
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &[]
+    }
+}
+
+/// A still image loaded by the app — a photo or logo.  Placed on the plane
+/// like [`VideoGen`]'s frames, so `center` / `zoom` pan and zoom it; colour
+/// is premultiplied by the image's alpha, so transparent areas are black.
+pub struct ImageGen;
+impl Generator for ImageGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Image
+    }
     fn gen_param_keys(&self) -> &[&'static str] {
         &[]
     }
 }
 
+/// A fixed alignment chart for projectors and LED walls — grid, colour
+/// bars, aspect markers, safe areas and convergence crosses.  Drawn in
+/// output pixels, so `center` / `zoom` have no effect.  Colour lands in RGB.
+pub struct TestPatternGen;
+impl Generator for TestPatternGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::TestPattern
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &[]
+    }
+}
+
+/// Keys [`PlasmaGen`] reads: the wave count, then each wave's frequency and
+/// phase speed.
+const PLASMA_KEYS: [&str; PlasmaGen::MAX_WAVES * 2 + 1] = [
+    "plasma_waves",
+    "plasma0_freq",
+    "plasma0_speed",
+    "plasma1_freq",
+    "plasma1_speed",
+    "plasma2_freq",
+    "plasma2_speed",
+    "plasma3_freq",
+    "plasma3_speed",
+];
+
+/// Demoscene plasma: sine waves summed over the plane — a horizontal, a
+/// vertical, a diagonal and a radial one, in that order — whose
+/// interference rolls on forever.  `plasma_waves` sums the first one to
+/// [`MAX_WAVES`](Self::MAX_WAVES) of them; wave `i` has spatial frequency
+/// `plasma{i}_freq` (radians per plane unit) and drifts at
+/// `plasma{i}_speed` radians of phase per second of patch time, so the
+/// pattern moves with no modulators at all — a cheap background layer.
+/// The normalised sum lands in the red channel for the colour map.
+pub struct PlasmaGen;
+
+impl PlasmaGen {
+    pub const MAX_WAVES: usize = 4;
+    pub const DEFAULT_FREQUENCIES: [f32; Self::MAX_WAVES] = [3.0, 4.0, 2.5, 5.0];
+    pub const DEFAULT_SPEEDS: [f32; Self::MAX_WAVES] = [1.0, -0.7, 0.5, 1.3];
+    /// Upper bound on a frequency; past it a wave is finer than the pixels
+    /// at zoom 1 and only aliases.
+    pub const MAX_FREQUENCY: f32 = 64.0;
+    /// Upper bound on a phase speed either way, in radians per second.
+    pub const MAX_SPEED: f32 = 32.0;
+
+    /// Write the wave count and every wave's defaults into `params`.
+    pub fn set_defaults(params: &mut Params) {
+        params.set("plasma_waves", Self::MAX_WAVES as f32);
+        for i in 0..Self::MAX_WAVES {
+            params.set(PLASMA_KEYS[1 + i * 2], Self::DEFAULT_FREQUENCIES[i]);
+            params.set(PLASMA_KEYS[2 + i * 2], Self::DEFAULT_SPEEDS[i]);
+        }
+    }
+
+    /// Wave count from `plasma_waves`, rounded and clamped to
+    /// \[1, MAX_WAVES\]; unset or non-finite values sum all of them.
+    pub fn waves(params: &Params) -> u32 {
+        match params.fields.get("plasma_waves") {
+            Some(v) if v.is_finite() => (v.round().max(1.0) as u32).min(Self::MAX_WAVES as u32),
+            _ => Self::MAX_WAVES as u32,
+        }
+    }
+
+    /// Each wave's frequency from `plasma{i}_freq`, clamped to
+    /// \[0, MAX_FREQUENCY\]; unset or non-finite values fall back to the
+    /// default.
+    pub fn frequencies(params: &Params) -> [f32; Self::MAX_WAVES] {
+        std::array::from_fn(|i| match params.fields.get(PLASMA_KEYS[1 + i * 2]) {
+            Some(v) if v.is_finite() => v.clamp(0.0, Self::MAX_FREQUENCY),
+            _ => Self::DEFAULT_FREQUENCIES[i],
+        })
+    }
+
+    /// Each wave's phase speed from `plasma{i}_speed`, clamped to
+    /// ±MAX_SPEED and defaulted like [`frequencies`](Self::frequencies).
+    pub fn speeds(params: &Params) -> [f32; Self::MAX_WAVES] {
+        std::array::from_fn(|i| match params.fields.get(PLASMA_KEYS[2 + i * 2]) {
+            Some(v) if v.is_finite() => v.clamp(-Self::MAX_SPEED, Self::MAX_SPEED),
+            _ => Self::DEFAULT_SPEEDS[i],
+        })
+    }
+
+    /// Each wave's phase at `params.time`, wrapped to \[0, 2π) on the CPU
+    /// so the shader's sines keep their precision however long the show
+    /// runs.
+    pub fn phases(params: &Params) -> [f32; Self::MAX_WAVES] {
+        let time = params.time as f64;
+        Self::speeds(params)
+            .map(|speed| (time * speed as f64).rem_euclid(std::f64::consts::TAU) as f32)
+    }
+}
+
+impl Generator for PlasmaGen {
+    fn kind(&self) -> GeneratorKind {
+        GeneratorKind::Plasma
+    }
+    fn gen_param_keys(&self) -> &[&'static str] {
+        &PLASMA_KEYS
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Concrete effect implementations
 // ---------------------------------------------------------------------------
@@ -170,8 +1220,16 @@ impl Generator for NoiseFieldGen {
 /// Apply a fixed color-map scheme to the escape-time value.
 pub struct ColorMapEffect(pub ColorScheme);
 impl Effect for ColorMapEffect {
-    fn kind(&self, _: &Params) -> EffectKind {
-        EffectKind::ColorMap { scheme: self.0 }
+    fn kind(&self, params: &Params) -> EffectKind {
+        let coloring = coloring::Coloring::from_params(params);
+        EffectKind::ColorMap {
+            scheme: self.0,
+            orbit_trap: coloring == coloring::Coloring::OrbitTrap,
+            edge_glow: coloring == coloring::Coloring::EdgeGlow,
+        }
+    }
+    fn param_keys(&self) -> Vec<&'static str> {
+        vec!["coloring"]
     }
 }
 
@@ -184,6 +1242,10 @@ impl Effect for HueShiftEffect {
             amount: params.get(self.0),
         }
     }
+
+    fn param_keys(&self) -> Vec<&'static str> {
+        vec![self.0]
+    }
 }
 
 /// UV-warp ripple distortion whose amplitude is read from a `Params` key each
@@ -201,6 +1263,10 @@ impl Effect for RippleEffect {
             speed: self.speed,
         }
     }
+
+    fn param_keys(&self) -> Vec<&'static str> {
+        vec![self.amplitude_key]
+    }
 }
 
 /// Multi-layer echo / smear with fixed parameters.
@@ -219,11 +1285,26 @@ impl Effect for EchoEffect {
     }
 }
 
-/// Motion-blur trail with a fixed opacity.
-pub struct MotionBlurEffect(pub f32);
+/// Motion-blur trail with a fixed opacity plus a directional streak whose
+/// angle (radians) and length (pixels) are read from `Params` keys each frame,
+/// so camera pans can drive the smear direction.
+pub struct MotionBlurEffect {
+    pub opacity: f32,
+    pub angle_key: &'static str,
+    pub length_key: &'static str,
+}
 impl Effect for MotionBlurEffect {
-    fn kind(&self, _: &Params) -> EffectKind {
-        EffectKind::MotionBlur { opacity: self.0 }
+    fn kind(&self, params: &Params) -> EffectKind {
+        let angle = params.get(self.angle_key);
+        EffectKind::MotionBlur {
+            opacity: self.opacity,
+            direction: [angle.cos(), angle.sin()],
+            length: params.get(self.length_key),
+        }
+    }
+
+    fn param_keys(&self) -> Vec<&'static str> {
+        vec![self.angle_key, self.length_key]
     }
 }
 
@@ -240,6 +1321,94 @@ impl Effect for BrightnessContrastEffect {
             contrast: self.contrast,
         }
     }
+
+    fn param_keys(&self) -> Vec<&'static str> {
+        vec![self.brightness_key]
+    }
+}
+
+/// Posterize with a level count read from a `Params` key each frame, so an
+/// LFO can step the banding coarser and finer.
+pub struct PosterizeEffect(pub &'static str);
+impl PosterizeEffect {
+    pub const MIN_LEVELS: f32 = 2.0;
+    pub const MAX_LEVELS: f32 = 64.0;
+}
+impl Effect for PosterizeEffect {
+    fn kind(&self, params: &Params) -> EffectKind {
+        let levels = params.get(self.0);
+        EffectKind::Posterize {
+            levels: if levels.is_finite() {
+                levels.round().clamp(Self::MIN_LEVELS, Self::MAX_LEVELS)
+            } else {
+                Self::MIN_LEVELS
+            },
+        }
+    }
+
+    fn param_keys(&self) -> Vec<&'static str> {
+        vec![self.0]
+    }
+}
+
+/// Colour inversion mixed in by an amount read from a `Params` key each
+/// frame, e.g. a strobe flash target.
+pub struct InvertEffect(pub &'static str);
+impl Effect for InvertEffect {
+    fn kind(&self, params: &Params) -> EffectKind {
+        let amount = params.get(self.0);
+        EffectKind::Invert {
+            amount: if amount.is_finite() {
+                amount.clamp(0.0, 1.0)
+            } else {
+                0.0
+            },
+        }
+    }
+
+    fn param_keys(&self) -> Vec<&'static str> {
+        vec![self.0]
+    }
+}
+
+/// Live spectrum or waveform drawn in a box over the image, coloured from a
+/// palette, for music-visualiser output.  Draws an empty box (flat bars or
+/// a flat line) while `Params` carries no audio.
+pub struct AudioOverlayEffect {
+    pub mode: OverlayMode,
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub scheme: ColorScheme,
+}
+impl Effect for AudioOverlayEffect {
+    fn kind(&self, params: &Params) -> EffectKind {
+        let source = match self.mode {
+            OverlayMode::Spectrum => &params.spectrum,
+            OverlayMode::Waveform => &params.waveform,
+        };
+        EffectKind::AudioOverlay {
+            mode: self.mode,
+            position: self.position,
+            size: self.size,
+            scheme: self.scheme,
+            samples: resample(source),
+        }
+    }
+}
+
+/// Linearly resample `src` to `N` values spanning the same range; all zeros
+/// when `src` is empty.
+pub fn resample<const N: usize>(src: &[f32]) -> [f32; N] {
+    match src.len() {
+        0 => [0.0; N],
+        1 => [src[0]; N],
+        len => std::array::from_fn(|i| {
+            let x = i as f32 * (len - 1) as f32 / (N.max(2) - 1) as f32;
+            let (lo, t) = (x.floor() as usize, x.fract());
+            let hi = (lo + 1).min(len - 1);
+            src[lo] + (src[hi] - src[lo]) * t
+        }),
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -269,8 +1438,8 @@ mod tests {
     #[test]
     fn params_set_and_get() {
         let mut p = Params::default();
-        p.set("foo", 3.14);
-        assert!((p.get("foo") - 3.14).abs() < 1e-6);
+        p.set("foo", 2.5);
+        assert!((p.get("foo") - 2.5).abs() < 1e-6);
     }
 
     #[test]
@@ -296,6 +1465,243 @@ mod tests {
         assert_ne!(GeneratorKind::NoiseField, GeneratorKind::Mandelbrot);
     }
 
+    // --- NoiseFieldGen ---------------------------------------------------------
+
+    #[test]
+    fn noise_field_set_defaults() {
+        let mut p = Params::default();
+        NoiseFieldGen::set_defaults(&mut p);
+        assert_eq!(p.get("noise_scale"), NoiseFieldGen::DEFAULT_SCALE);
+        assert_eq!(NoiseFieldGen::octaves(&p), NoiseFieldGen::DEFAULT_OCTAVES);
+        assert_eq!(p.get("noise_lacunarity"), NoiseFieldGen::DEFAULT_LACUNARITY);
+        assert_eq!(p.get("noise_gain"), NoiseFieldGen::DEFAULT_GAIN);
+        assert_eq!(p.get("noise_seed"), 0.0);
+    }
+
+    #[test]
+    fn noise_field_octaves_clamped() {
+        let mut p = Params::default();
+        p.set("noise_octaves", 0.0);
+        assert_eq!(NoiseFieldGen::octaves(&p), 1);
+        p.set("noise_octaves", 2.6);
+        assert_eq!(NoiseFieldGen::octaves(&p), 3);
+        p.set("noise_octaves", 100.0);
+        assert_eq!(NoiseFieldGen::octaves(&p), NoiseFieldGen::MAX_OCTAVES);
+    }
+
+    // --- SimplexFieldGen / WorleyFieldGen ---------------------------------------
+
+    #[test]
+    fn simplex_field_seeds_the_noise_keys_and_its_ridge() {
+        let mut p = Params::default();
+        SimplexFieldGen::set_defaults(&mut p);
+        assert_eq!(p.get("noise_scale"), NoiseFieldGen::DEFAULT_SCALE);
+        assert_eq!(SimplexFieldGen::ridge(&p), SimplexFieldGen::DEFAULT_RIDGE);
+        p.set("simplex_ridge", -3.0);
+        assert_eq!(SimplexFieldGen::ridge(&p), 0.0);
+        p.set("simplex_ridge", f32::NAN);
+        assert_eq!(SimplexFieldGen::ridge(&p), SimplexFieldGen::DEFAULT_RIDGE);
+    }
+
+    #[test]
+    fn worley_field_clamps_jitter_and_edge() {
+        let mut p = Params::default();
+        WorleyFieldGen::set_defaults(&mut p);
+        assert_eq!(p.get("noise_scale"), WorleyFieldGen::DEFAULT_SCALE);
+        assert_eq!(WorleyFieldGen::jitter(&p), WorleyFieldGen::DEFAULT_JITTER);
+        assert_eq!(WorleyFieldGen::edge(&p), 0.0);
+        p.set("worley_jitter", 4.0);
+        p.set("worley_edge", f32::INFINITY);
+        assert_eq!(WorleyFieldGen::jitter(&p), 1.0);
+        assert_eq!(WorleyFieldGen::edge(&p), 0.0);
+    }
+
+    // --- DomainWarpGen -----------------------------------------------------------
+
+    #[test]
+    fn domain_warp_clamps_strength_rotation_and_layers() {
+        let mut p = Params::default();
+        assert_eq!(DomainWarpGen::strength(&p), DomainWarpGen::DEFAULT_STRENGTH);
+        assert_eq!(DomainWarpGen::layers(&p), DomainWarpGen::DEFAULT_LAYERS);
+        DomainWarpGen::set_defaults(&mut p);
+        assert_eq!(p.get("noise_scale"), NoiseFieldGen::DEFAULT_SCALE);
+        assert_eq!(DomainWarpGen::rotation(&p), 0.0);
+        p.set("warp_strength", -1.0);
+        p.set("warp_layers", 7.0);
+        p.set("warp_rotation", -std::f32::consts::FRAC_PI_2);
+        assert_eq!(DomainWarpGen::strength(&p), 0.0);
+        assert_eq!(DomainWarpGen::layers(&p), DomainWarpGen::MAX_LAYERS);
+        let turn = DomainWarpGen::rotation(&p);
+        assert!((turn - 1.5 * std::f32::consts::PI).abs() < 1e-5, "{turn}");
+        p.set("warp_strength", f32::NAN);
+        p.set("warp_layers", 0.0);
+        p.set("warp_rotation", f32::INFINITY);
+        assert_eq!(DomainWarpGen::strength(&p), DomainWarpGen::DEFAULT_STRENGTH);
+        assert_eq!(DomainWarpGen::layers(&p), DomainWarpGen::MIN_LAYERS);
+        assert_eq!(DomainWarpGen::rotation(&p), 0.0);
+    }
+
+    // --- PlasmaGen ----------------------------------------------------------------
+
+    #[test]
+    fn plasma_defaults_sum_every_wave() {
+        let mut p = Params::default();
+        PlasmaGen::set_defaults(&mut p);
+        assert_eq!(PlasmaGen::waves(&p), PlasmaGen::MAX_WAVES as u32);
+        assert_eq!(PlasmaGen::frequencies(&p), PlasmaGen::DEFAULT_FREQUENCIES);
+        assert_eq!(PlasmaGen::speeds(&p), PlasmaGen::DEFAULT_SPEEDS);
+        let keys = PlasmaGen.gen_param_keys();
+        assert!(p.fields.keys().all(|k| keys.contains(&k.as_str())));
+    }
+
+    #[test]
+    fn plasma_clamps_its_waves() {
+        let mut p = Params::default();
+        p.set("plasma_waves", 0.0);
+        p.set("plasma0_freq", -3.0);
+        p.set("plasma1_freq", f32::NAN);
+        p.set("plasma2_freq", 1e4);
+        p.set("plasma3_speed", -1e4);
+        assert_eq!(PlasmaGen::waves(&p), 1);
+        let freq = PlasmaGen::frequencies(&p);
+        assert_eq!(freq[0], 0.0);
+        assert_eq!(freq[1], PlasmaGen::DEFAULT_FREQUENCIES[1]);
+        assert_eq!(freq[2], PlasmaGen::MAX_FREQUENCY);
+        assert_eq!(PlasmaGen::speeds(&p)[3], -PlasmaGen::MAX_SPEED);
+        p.set("plasma_waves", 9.0);
+        assert_eq!(PlasmaGen::waves(&p), PlasmaGen::MAX_WAVES as u32);
+    }
+
+    #[test]
+    fn plasma_phases_advance_with_time_and_stay_wrapped() {
+        let mut p = Params::default();
+        assert_eq!(PlasmaGen::phases(&p), [0.0; PlasmaGen::MAX_WAVES]);
+        p.time = 0.5;
+        assert!((PlasmaGen::phases(&p)[0] - 0.5).abs() < 1e-6);
+        // A negative speed runs backwards from 2π.
+        let back = PlasmaGen::phases(&p)[1];
+        assert!(
+            (back - (std::f32::consts::TAU - 0.35)).abs() < 1e-5,
+            "{back}"
+        );
+        p.time = 1e6;
+        for phase in PlasmaGen::phases(&p) {
+            assert!((0.0..std::f32::consts::TAU).contains(&phase), "{phase}");
+        }
+    }
+
+    // --- ReactionDiffusionGen ----------------------------------------------------
+
+    #[test]
+    fn reaction_rates_and_steps_are_clamped() {
+        let mut p = Params::default();
+        assert_eq!(
+            ReactionDiffusionGen::feed(&p),
+            ReactionDiffusionGen::DEFAULT_FEED
+        );
+        assert_eq!(
+            ReactionDiffusionGen::steps(&p),
+            ReactionDiffusionGen::DEFAULT_STEPS
+        );
+        p.set("reaction_feed", 0.5);
+        p.set("reaction_kill", -1.0);
+        p.set("reaction_steps", 1000.0);
+        assert_eq!(
+            ReactionDiffusionGen::feed(&p),
+            ReactionDiffusionGen::MAX_RATE
+        );
+        assert_eq!(ReactionDiffusionGen::kill(&p), 0.0);
+        assert_eq!(
+            ReactionDiffusionGen::steps(&p),
+            ReactionDiffusionGen::MAX_STEPS
+        );
+        p.set("reaction_steps", 0.0);
+        assert_eq!(ReactionDiffusionGen::steps(&p), 1);
+    }
+
+    // --- MultibrotGen ----------------------------------------------------------
+
+    #[test]
+    fn multibrot_power_defaults_and_clamps() {
+        let mut p = Params::default();
+        assert_eq!(MultibrotGen::power(&p), MultibrotGen::DEFAULT_POWER);
+        p.set("multibrot_power", 4.5);
+        assert_eq!(MultibrotGen::power(&p), 4.5);
+        p.set("multibrot_power", -2.0);
+        assert_eq!(MultibrotGen::power(&p), MultibrotGen::MIN_POWER);
+        p.set("multibrot_power", 100.0);
+        assert_eq!(MultibrotGen::power(&p), MultibrotGen::MAX_POWER);
+        p.set("multibrot_power", f32::NAN);
+        assert_eq!(MultibrotGen::power(&p), MultibrotGen::DEFAULT_POWER);
+    }
+
+    // --- HybridGen ---------------------------------------------------------------
+
+    #[test]
+    fn hybrid_schedule_parses_and_prints() {
+        let s = HybridSchedule::parse("mmBb").unwrap();
+        assert_eq!(s, HybridSchedule::DEFAULT);
+        assert_eq!(s.to_string(), "MMBB");
+        assert_eq!(s.period(), 4);
+        let steps: Vec<bool> = (0..6).map(|i| s.is_ship(i)).collect();
+        assert_eq!(steps, [false, false, true, true, false, false]);
+        assert_eq!(HybridSchedule::parse(""), None);
+        assert_eq!(HybridSchedule::parse("MXB"), None);
+        assert_eq!(HybridSchedule::parse(&"B".repeat(33)), None);
+        let long = HybridSchedule::parse(&"B".repeat(32)).unwrap();
+        assert_eq!(long.ship_steps(), u32::MAX);
+    }
+
+    // --- KleinianGen ------------------------------------------------------------
+
+    #[test]
+    fn kleinian_trace_defaults_and_clamps() {
+        let mut p = Params::default();
+        assert_eq!(KleinianGen::trace(&p), KleinianGen::DEFAULT_TRACE);
+        p.set("kleinian_trace_re", 1.9);
+        p.set("kleinian_trace_im", -0.1);
+        assert_eq!(KleinianGen::trace(&p), [1.9, -0.1]);
+        p.set("kleinian_trace_re", 9.0);
+        p.set("kleinian_trace_im", f32::INFINITY);
+        assert_eq!(
+            KleinianGen::trace(&p),
+            [KleinianGen::MAX_TRACE[0], KleinianGen::DEFAULT_TRACE[1]]
+        );
+    }
+
+    // --- IfsGen ----------------------------------------------------------------
+
+    #[test]
+    fn ifs_maps_round_trip_and_zero_unused_slots() {
+        let mut p = Params::default();
+        IfsGen::set_maps(&mut p, &IfsGen::BARNSLEY_FERN);
+        assert_eq!(IfsGen::maps(&p), IfsGen::BARNSLEY_FERN);
+        IfsGen::set_maps(&mut p, &IfsGen::SIERPINSKI);
+        let maps = IfsGen::maps(&p);
+        assert_eq!(maps[..3], IfsGen::SIERPINSKI);
+        assert_eq!(maps[3].weight, 0.0);
+    }
+
+    #[test]
+    fn ifs_maps_sanitize_modulated_values() {
+        let mut p = Params::default();
+        IfsGen::set_maps(&mut p, &IfsGen::SIERPINSKI);
+        p.set("ifs0_weight", -1.0);
+        p.set("ifs1_e", f32::NAN);
+        let maps = IfsGen::maps(&p);
+        assert_eq!(maps[0].weight, 0.0);
+        assert_eq!(maps[1].e, 0.0);
+    }
+
+    #[test]
+    fn ifs_keys_cover_every_coefficient() {
+        let mut p = Params::default();
+        IfsGen::set_maps(&mut p, &IfsGen::BARNSLEY_FERN);
+        let keys = IfsGen.gen_param_keys();
+        assert!(p.fields.keys().all(|k| keys.contains(&k.as_str())));
+        assert!(keys.contains(&"ifs_exposure"));
+    }
+
     // --- EffectKind ------------------------------------------------------------
 
     #[test]
@@ -332,6 +1738,120 @@ mod tests {
         }
     }
 
+    #[test]
+    fn motion_blur_effect_reads_direction_and_length() {
+        let mut p = Params::default();
+        p.set("blur_angle", std::f32::consts::FRAC_PI_2);
+        p.set("blur_length", 12.0);
+        let e = MotionBlurEffect {
+            opacity: 0.5,
+            angle_key: "blur_angle",
+            length_key: "blur_length",
+        };
+        if let EffectKind::MotionBlur {
+            opacity,
+            direction,
+            length,
+        } = e.kind(&p)
+        {
+            assert!((opacity - 0.5).abs() < 1e-6);
+            assert!(direction[0].abs() < 1e-6);
+            assert!((direction[1] - 1.0).abs() < 1e-6);
+            assert!((length - 12.0).abs() < 1e-6);
+        } else {
+            panic!("wrong variant");
+        }
+    }
+
+    #[test]
+    fn only_colour_maps_per_pixel_are_pointwise() {
+        assert!(EffectKind::HueShift { amount: 1.0 }.is_pointwise());
+        assert!(EffectKind::Posterize { levels: 4.0 }.is_pointwise());
+        assert!(EffectKind::Invert { amount: 1.0 }.is_pointwise());
+        assert!(!EffectKind::Ripple {
+            frequency: 0.1,
+            amplitude: 5.0,
+            speed: 1.0,
+        }
+        .is_pointwise());
+        assert!(!EffectKind::ColorMap {
+            scheme: ColorScheme::Classic,
+            orbit_trap: false,
+            edge_glow: true,
+        }
+        .is_pointwise());
+    }
+
+    // --- PosterizeEffect / InvertEffect -----------------------------------------
+
+    #[test]
+    fn posterize_rounds_and_clamps_its_levels() {
+        let mut p = Params::default();
+        let levels = |p: &Params| match PosterizeEffect("levels").kind(p) {
+            EffectKind::Posterize { levels } => levels,
+            other => panic!("wrong variant {other:?}"),
+        };
+        p.set("levels", 5.4);
+        assert_eq!(levels(&p), 5.0);
+        p.set("levels", 0.0);
+        assert_eq!(levels(&p), PosterizeEffect::MIN_LEVELS);
+        p.set("levels", 1e6);
+        assert_eq!(levels(&p), PosterizeEffect::MAX_LEVELS);
+        p.set("levels", f32::NAN);
+        assert_eq!(levels(&p), PosterizeEffect::MIN_LEVELS);
+    }
+
+    #[test]
+    fn invert_amount_stays_in_unit_range() {
+        let mut p = Params::default();
+        let amount = |p: &Params| match InvertEffect("invert").kind(p) {
+            EffectKind::Invert { amount } => amount,
+            other => panic!("wrong variant {other:?}"),
+        };
+        p.set("invert", 0.25);
+        assert_eq!(amount(&p), 0.25);
+        p.set("invert", 3.0);
+        assert_eq!(amount(&p), 1.0);
+        p.set("invert", f32::NAN);
+        assert_eq!(amount(&p), 0.0);
+    }
+
+    // --- AudioOverlayEffect ----------------------------------------------------
+
+    #[test]
+    fn resample_keeps_endpoints_and_interpolates() {
+        assert_eq!(resample(&[0.0, 1.0]), [0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(resample(&[]), [0.0; 3]);
+        assert_eq!(resample(&[0.4]), [0.4, 0.4]);
+        let down: [f32; 64] = resample(&(0..512).map(|i| i as f32).collect::<Vec<_>>());
+        assert_eq!((down[0], down[63]), (0.0, 511.0));
+    }
+
+    #[test]
+    fn audio_overlay_reads_the_chosen_signal() {
+        let p = Params {
+            spectrum: vec![1.0; 8],
+            waveform: vec![-0.5; 8],
+            ..Default::default()
+        };
+        let effect = |mode| AudioOverlayEffect {
+            mode,
+            position: [0.1, 0.7],
+            size: [0.8, 0.2],
+            scheme: ColorScheme::Fire,
+        };
+        let EffectKind::AudioOverlay { samples, .. } = effect(OverlayMode::Spectrum).kind(&p)
+        else {
+            panic!("wrong variant");
+        };
+        assert_eq!(samples, [1.0; OVERLAY_SAMPLES]);
+        let EffectKind::AudioOverlay { samples, .. } = effect(OverlayMode::Waveform).kind(&p)
+        else {
+            panic!("wrong variant");
+        };
+        assert_eq!(samples, [-0.5; OVERLAY_SAMPLES]);
+    }
+
     // --- ColorScheme -----------------------------------------------------------
 
     #[test]
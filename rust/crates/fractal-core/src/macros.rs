@@ -0,0 +1,168 @@
+use std::cell::RefCell;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Params;
+
+/// Prefix of the `Params` key holding a macro's value.
+pub const MACRO_KEY_PREFIX: &str = "macro_";
+
+thread_local! {
+    /// Where [`Macro::value`] spells the key out, so reading a macro every
+    /// frame allocates nothing once the buffer has grown.
+    static KEY: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+// ---------------------------------------------------------------------------
+// Curve — how a target follows the macro value
+// ---------------------------------------------------------------------------
+
+/// Shaping applied to the macro value before it is mapped onto a target's
+/// range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Curve {
+    #[default]
+    Linear,
+    /// Slow start: `t²`.
+    EaseIn,
+    /// Slow finish: `1 − (1 − t)²`.
+    EaseOut,
+    /// Slow at both ends (smoothstep).
+    SCurve,
+}
+
+impl Curve {
+    /// Shape `t` in \[0, 1\]; the end points stay fixed.
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Curve::Linear => t,
+            Curve::EaseIn => t * t,
+            Curve::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Curve::SCurve => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Macro — one value fanned out to several params
+// ---------------------------------------------------------------------------
+
+/// One param a macro drives: the macro's 0–1 value, shaped by `curve`,
+/// sweeps it from `min` to `max` (`min > max` inverts it).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacroTarget {
+    pub key: String,
+    pub min: f32,
+    pub max: f32,
+    #[serde(default)]
+    pub curve: Curve,
+}
+
+impl MacroTarget {
+    pub fn value(&self, t: f32) -> f32 {
+        self.min + (self.max - self.min) * self.curve.apply(t)
+    }
+}
+
+/// A named control whose value lives in `Params` under [`Macro::key`], so
+/// the HUD, MIDI and overrides move it like any other param.  Each frame
+/// the patch writes every target from it, after the modulators.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Macro {
+    pub name: String,
+    pub targets: Vec<MacroTarget>,
+}
+
+impl Macro {
+    /// `Params` key of the macro's value, e.g. `macro_detail`.
+    pub fn key(&self) -> String {
+        format!("{MACRO_KEY_PREFIX}{}", self.name)
+    }
+
+    /// The macro's current value, clamped to \[0, 1\].
+    pub fn value(&self, params: &Params) -> f32 {
+        KEY.with_borrow_mut(|key| {
+            key.clear();
+            key.push_str(MACRO_KEY_PREFIX);
+            key.push_str(&self.name);
+            params.get(key)
+        })
+        .clamp(0.0, 1.0)
+    }
+
+    /// Write every target from the macro's current value.
+    pub fn apply(&self, params: &mut Params) {
+        let t = self.value(params);
+        for target in &self.targets {
+            params.set(target.key.as_str(), target.value(t));
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detail() -> Macro {
+        Macro {
+            name: "detail".into(),
+            targets: vec![
+                MacroTarget {
+                    key: "noise_scale".into(),
+                    min: 1.0,
+                    max: 3.0,
+                    curve: Curve::Linear,
+                },
+                MacroTarget {
+                    key: "noise_gain".into(),
+                    min: 0.8,
+                    max: 0.2,
+                    curve: Curve::EaseIn,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn curves_keep_their_end_points() {
+        for curve in [Curve::Linear, Curve::EaseIn, Curve::EaseOut, Curve::SCurve] {
+            assert_eq!(curve.apply(0.0), 0.0);
+            assert_eq!(curve.apply(1.0), 1.0);
+        }
+        assert_eq!(Curve::EaseIn.apply(0.5), 0.25);
+        assert_eq!(Curve::EaseOut.apply(0.5), 0.75);
+        assert_eq!(Curve::SCurve.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn apply_fans_out_to_every_target() {
+        let m = detail();
+        let mut p = Params::default();
+        p.set(m.key(), 0.5);
+        m.apply(&mut p);
+        assert_eq!(p.get("noise_scale"), 2.0);
+        // Inverted range, eased in: 0.8 + (0.2 − 0.8) · 0.25.
+        assert!((p.get("noise_gain") - 0.65).abs() < 1e-6);
+    }
+
+    #[test]
+    fn value_is_clamped() {
+        let m = detail();
+        let mut p = Params::default();
+        p.set(m.key(), 7.0);
+        m.apply(&mut p);
+        assert_eq!(p.get("noise_scale"), 3.0);
+        assert_eq!(m.key(), "macro_detail");
+    }
+
+    #[test]
+    fn curve_defaults_to_linear_in_json() {
+        let target: MacroTarget =
+            serde_json::from_str(r#"{"key":"zoom_speed","min":0.0,"max":2.0}"#).unwrap();
+        assert_eq!(target.curve, Curve::Linear);
+    }
+}
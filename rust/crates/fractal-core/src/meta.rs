@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+use crate::audio::{MAX_BPM, MIN_BPM};
+
+// ---------------------------------------------------------------------------
+// PatchMeta — who made a look and how to play it
+// ---------------------------------------------------------------------------
+//
+// None of this changes a pixel.  Presets fill in a description and tags,
+// bundles carry whatever their author wrote, and the app shows it in the
+// HUD and the window title, so a patch passed around keeps its attribution.
+
+/// Descriptive metadata of a patch.  Every field is optional; an empty
+/// `PatchMeta` serialises to `{}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PatchMeta {
+    /// Display title, in place of the base preset's name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// A line or two on what the patch does and how to play it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Tempo the patch's LFOs and flashes were tuned for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bpm: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl PatchMeta {
+    /// Metadata with a description and tags, as the built-in presets have.
+    pub fn new(description: &str, tags: &[&str]) -> Self {
+        Self {
+            description: Some(description.to_string()),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_bpm(mut self, bpm: f32) -> Self {
+        self.bpm = Some(bpm);
+        self
+    }
+
+    pub fn with_author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The title to show: [`title`](Self::title) if set and not blank,
+    /// otherwise `fallback` (the base preset's name).
+    pub fn title_or<'a>(&'a self, fallback: &'a str) -> &'a str {
+        non_blank(&self.title).unwrap_or(fallback)
+    }
+
+    /// The author, unless missing or blank.
+    pub fn author(&self) -> Option<&str> {
+        non_blank(&self.author)
+    }
+
+    /// The description, unless missing or blank.
+    pub fn description(&self) -> Option<&str> {
+        non_blank(&self.description)
+    }
+
+    /// The recommended tempo, when it is one a beat clock could follow
+    /// (`MIN_BPM..=MAX_BPM`).
+    pub fn bpm(&self) -> Option<f32> {
+        self.bpm.filter(|b| (MIN_BPM..=MAX_BPM).contains(b))
+    }
+}
+
+fn non_blank(s: &Option<String>) -> Option<&str> {
+    s.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- Serialisation ---
+
+    #[test]
+    fn empty_meta_serialises_to_an_empty_object() {
+        let meta = PatchMeta::default();
+        assert!(meta.is_empty());
+        assert_eq!(serde_json::to_string(&meta).unwrap(), "{}");
+        assert_eq!(serde_json::from_str::<PatchMeta>("{}").unwrap(), meta);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let meta = PatchMeta::new("Slow drift", &["julia", "ambient"])
+            .with_bpm(96.0)
+            .with_author("cmh");
+        let json = serde_json::to_string(&meta).unwrap();
+        assert_eq!(serde_json::from_str::<PatchMeta>(&json).unwrap(), meta);
+        assert!(!meta.is_empty());
+    }
+
+    // --- Accessors ---
+
+    #[test]
+    fn blank_fields_count_as_missing() {
+        let meta = PatchMeta {
+            title: Some("  ".into()),
+            author: Some(String::new()),
+            description: Some(" Ripples ".into()),
+            ..PatchMeta::default()
+        };
+        assert_eq!(meta.title_or("Ocean Ripple Julia"), "Ocean Ripple Julia");
+        assert_eq!(meta.author(), None);
+        assert_eq!(meta.description(), Some("Ripples"));
+    }
+
+    #[test]
+    fn bpm_outside_what_a_beat_clock_follows_is_dropped() {
+        assert_eq!(PatchMeta::default().with_bpm(128.0).bpm(), Some(128.0));
+        assert_eq!(PatchMeta::default().with_bpm(MAX_BPM + 1.0).bpm(), None);
+        assert_eq!(PatchMeta::default().with_bpm(0.0).bpm(), None);
+        assert_eq!(PatchMeta::default().with_bpm(f32::NAN).bpm(), None);
+    }
+}
@@ -1,45 +1,197 @@
+use crate::audio::{AudioBand, ONSET_STRENGTH_KEY, ONSET_TIME_KEY};
 use crate::{Modulator, Params};
+use serde::{Deserialize, Serialize};
 use std::f32::consts::TAU;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 
 // ---------------------------------------------------------------------------
 // LFO
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Waveform {
     Sine,
     Triangle,
     Square,
     Saw,
+    /// A new random level every cycle, eased into from the last.
+    SmoothRandom,
+    /// Slow start, fast finish: an exponential rise over each cycle.
+    Exponential,
+    /// A user-drawn cycle (see [`Wavetable`]).
+    Table(Arc<Wavetable>),
+}
+
+impl Waveform {
+    /// The built-in shapes; a [`Table`](Waveform::Table) comes from a
+    /// patch file.
+    pub const ALL: [Waveform; 6] = [
+        Waveform::Sine,
+        Waveform::Triangle,
+        Waveform::Square,
+        Waveform::Saw,
+        Waveform::SmoothRandom,
+        Waveform::Exponential,
+    ];
+
+    /// The wave (−1–1) after `cycles` cycles.  `seed` picks the
+    /// `SmoothRandom` sequence.
+    pub fn sample(&self, cycles: f32, seed: u32) -> f32 {
+        let t = cycles - cycles.floor();
+        match self {
+            Waveform::Sine => (cycles * TAU).sin(),
+            Waveform::Triangle => 2.0 * (cycles - (cycles + 0.5).floor()).abs() * 2.0 - 1.0,
+            Waveform::Square => {
+                if (cycles * TAU).sin() >= 0.0 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Saw => 2.0 * t - 1.0,
+            Waveform::SmoothRandom => {
+                let n = cycles.floor() as i32;
+                let (a, b) = (random_level(n, seed), random_level(n + 1, seed));
+                a + (b - a) * smoothstep(t)
+            }
+            Waveform::Exponential => {
+                const K: f32 = 4.0;
+                2.0 * ((K * t).exp() - 1.0) / (K.exp() - 1.0) - 1.0
+            }
+            Waveform::Table(table) => table.sample(t),
+        }
+    }
+}
+
+/// Hermite ease from 0 to 1 over `t` ∈ \[0, 1\].
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
 }
 
+/// Level (−1–1) of `SmoothRandom` cycle `n`, hashed from `n` and `seed`.
+fn random_level(n: i32, seed: u32) -> f32 {
+    let mut h = (n as u32).wrapping_mul(0x9e37_79b9) ^ seed;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+/// How a [`Wavetable`] fills in between its points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Interpolation {
+    /// Hold each point until the next.
+    Step,
+    #[default]
+    Linear,
+    /// Ease in and out of each point.
+    Smooth,
+}
+
+/// One LFO cycle drawn as evenly spaced points (nominally −1–1), wrapping
+/// from the last point back to the first.  Patch files carry them per LFO
+/// target (see [`PatchBundle`](crate::bundle::PatchBundle)).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Wavetable {
+    pub points: Vec<f32>,
+    #[serde(default)]
+    pub interpolation: Interpolation,
+}
+
+impl Wavetable {
+    /// The table at `t` ∈ \[0, 1) of the cycle; 0 when it has no points.
+    pub fn sample(&self, t: f32) -> f32 {
+        let n = self.points.len();
+        if n == 0 {
+            return 0.0;
+        }
+        let pos = (t - t.floor()) * n as f32;
+        let i = (pos as usize).min(n - 1);
+        let (a, b) = (self.points[i], self.points[(i + 1) % n]);
+        let f = pos - i as f32;
+        match self.interpolation {
+            Interpolation::Step => a,
+            Interpolation::Linear => a + (b - a) * f,
+            Interpolation::Smooth => a + (b - a) * smoothstep(f),
+        }
+    }
+}
+
+/// Patch time of the latest LFO retrigger; every `Lfo` measures its cycle
+/// from here (from 0, i.e. the preset load, when unset).
+pub const LFO_RETRIGGER_KEY: &str = "lfo_retrigger_time";
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Lfo {
     pub target: &'static str,
     pub waveform: Waveform,
     pub frequency: f32,
     pub amplitude: f32,
     pub offset: f32,
+    /// Start of the cycle in cycles (0.25 = a quarter period ahead), so
+    /// several LFOs can run deliberately out of step.
+    pub phase: f32,
+}
+
+impl Lfo {
+    /// Restart every LFO's cycle at the current patch time.
+    pub fn retrigger(params: &mut Params) {
+        params.set(LFO_RETRIGGER_KEY, params.time);
+    }
+
+    /// `offset` plus the waveform scaled by `amplitude`, sampled at the
+    /// cycle reached since the last retrigger.
+    pub fn value(&self, params: &Params) -> f32 {
+        let age = params.time - params.get(LFO_RETRIGGER_KEY);
+        let cycles = age * self.frequency + self.phase;
+        self.offset + self.waveform.sample(cycles, seed_for(self.target)) * self.amplitude
+    }
+
+    /// Lowest and highest value the LFO sweeps.
+    pub fn range(&self) -> (f32, f32) {
+        let a = self.amplitude.abs();
+        (self.offset - a, self.offset + a)
+    }
+
+    /// Sweep between `low` and `high` instead, keeping the wave's direction.
+    pub fn set_range(&mut self, low: f32, high: f32) {
+        let sign = if self.amplitude < 0.0 { -1.0 } else { 1.0 };
+        self.offset = (low + high) * 0.5;
+        self.amplitude = sign * (high - low).abs() * 0.5;
+    }
 }
 
 impl Modulator for Lfo {
     fn modulate(&self, params: &mut Params) {
-        let phase = params.time * self.frequency * TAU;
-        let raw = match self.waveform {
-            Waveform::Sine => phase.sin(),
-            Waveform::Triangle => {
-                2.0 * (phase / TAU - (phase / TAU + 0.5).floor()).abs() * 2.0 - 1.0
-            }
-            Waveform::Square => {
-                if phase.sin() >= 0.0 {
-                    1.0
-                } else {
-                    -1.0
-                }
-            }
-            Waveform::Saw => 2.0 * (phase / TAU - (phase / TAU).floor()) - 1.0,
-        };
-        params.set(self.target, self.offset + raw * self.amplitude);
+        params.set(self.target, self.value(params));
+    }
+
+    fn output(&self, params: &Params, target: &str) -> Option<f32> {
+        (target == self.target).then(|| self.value(params))
     }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.target.to_string()]
+    }
+
+    fn inputs(&self) -> Vec<String> {
+        vec![LFO_RETRIGGER_KEY.to_string()]
+    }
+
+    fn as_lfo(&self) -> Option<&Lfo> {
+        Some(self)
+    }
+}
+
+/// FNV-1a of `target`, so `SmoothRandom` LFOs on different params wander
+/// independently.
+fn seed_for(target: &str) -> u32 {
+    target.bytes().fold(0x811c_9dc5, |h, b| {
+        (h ^ u32::from(b)).wrapping_mul(0x0100_0193)
+    })
 }
 
 // ---------------------------------------------------------------------------
@@ -53,11 +205,25 @@ pub struct RandomWalk {
     // mutability; left simple here as a placeholder.
 }
 
+impl RandomWalk {
+    /// A slow sine drift in \[-0.5, 0.5\], its rate set by `speed`.
+    pub fn value(&self, params: &Params) -> f32 {
+        // Placeholder: smooth drift using a sine of a large prime offset
+        (params.time * self.speed * 0.37 + 1.618).sin() * 0.5
+    }
+}
+
 impl Modulator for RandomWalk {
     fn modulate(&self, params: &mut Params) {
-        // Placeholder: smooth drift using a sine of a large prime offset
-        let drift = (params.time * self.speed * 0.37 + 1.618).sin() * 0.5;
-        params.set(self.target, drift);
+        params.set(self.target, self.value(params));
+    }
+
+    fn output(&self, params: &Params, target: &str) -> Option<f32> {
+        (target == self.target).then(|| self.value(params))
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.target.to_string()]
     }
 }
 
@@ -79,34 +245,614 @@ impl Modulator for MouseModulator {
             params.set(key, params.mouse_y * 2.0 - 1.0);
         }
     }
+
+    fn output(&self, params: &Params, target: &str) -> Option<f32> {
+        if self.target_x == Some(target) {
+            Some(params.mouse_x * 2.0 - 1.0)
+        } else if self.target_y == Some(target) {
+            Some(params.mouse_y * 2.0 - 1.0)
+        } else {
+            None
+        }
+    }
+
+    fn targets(&self) -> Vec<String> {
+        [self.target_x, self.target_y]
+            .into_iter()
+            .flatten()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// JuliaPath  — sweeps the Julia constant c along a parametric curve
+// ---------------------------------------------------------------------------
+
+/// Curve traced by [`JuliaPath`] in the complex plane.
+#[derive(Debug, Clone, Copy)]
+pub enum JuliaPathShape {
+    /// Circle of `radius` around the origin — `0.7885` gives the classic
+    /// morphing-Julia loop.
+    Circle { radius: f32 },
+    /// The main cardioid boundary `c = e^{it}/2 − e^{2it}/4`, scaled by
+    /// `scale`.  Values slightly above 1 hover just outside the Mandelbrot
+    /// set, where the Julia sets are most intricate.
+    Cardioid { scale: f32 },
+    /// Lemniscate of Gerono centred on `center` with half-width `size`.
+    FigureEight { center: [f32; 2], size: f32 },
+}
+
+impl JuliaPathShape {
+    /// Point on the curve at parameter `t` (radians; one loop per `TAU`).
+    pub fn point(self, t: f32) -> (f32, f32) {
+        match self {
+            JuliaPathShape::Circle { radius } => (radius * t.cos(), radius * t.sin()),
+            JuliaPathShape::Cardioid { scale } => {
+                let x = 0.5 * t.cos() - 0.25 * (2.0 * t).cos();
+                let y = 0.5 * t.sin() - 0.25 * (2.0 * t).sin();
+                (scale * x, scale * y)
+            }
+            JuliaPathShape::FigureEight { center, size } => (
+                center[0] + size * t.sin(),
+                center[1] + size * t.sin() * t.cos(),
+            ),
+        }
+    }
+}
+
+/// Moves the Julia constant along a [`JuliaPathShape`] at `rate` loops per
+/// second, writing the result to `julia_cx` / `julia_cy`.
+pub struct JuliaPath {
+    pub shape: JuliaPathShape,
+    pub rate: f32,
+}
+
+impl Modulator for JuliaPath {
+    fn modulate(&self, params: &mut Params) {
+        let (cx, cy) = self.shape.point(params.time * self.rate * TAU);
+        params.set("julia_cx", cx);
+        params.set("julia_cy", cy);
+    }
+
+    fn output(&self, params: &Params, target: &str) -> Option<f32> {
+        let (cx, cy) = self.shape.point(params.time * self.rate * TAU);
+        match target {
+            "julia_cx" => Some(cx),
+            "julia_cy" => Some(cy),
+            _ => None,
+        }
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec!["julia_cx".to_string(), "julia_cy".to_string()]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Envelope  — attack / release shape fired by audio onsets
+// ---------------------------------------------------------------------------
+
+/// Attack / release envelope retriggered by each onset published with
+/// [`crate::audio::Onset::apply`].  Writes `amount × strength × level` to
+/// `target`, where level ramps 0 → 1 over `attack` seconds and then decays
+/// exponentially with time constant `release`.  Drive an effect key with it
+/// for momentary hits (a brightness flash, a ripple kick).
+pub struct Envelope {
+    pub target: &'static str,
+    pub attack: f32,
+    pub release: f32,
+    pub amount: f32,
+}
+
+impl Envelope {
+    /// Level `age` seconds after the trigger, in \[0, 1\].
+    pub fn level(&self, age: f32) -> f32 {
+        if age < 0.0 {
+            0.0
+        } else if age < self.attack {
+            age / self.attack
+        } else {
+            (-(age - self.attack) / self.release.max(f32::EPSILON)).exp()
+        }
+    }
+}
+
+impl Envelope {
+    /// Value written to `target`: the latest onset's strength scaled by
+    /// the envelope level since it fired, 0 before any onset.
+    pub fn value(&self, params: &Params) -> f32 {
+        match params.fields.get(ONSET_TIME_KEY) {
+            Some(&fired) => {
+                self.amount * params.get(ONSET_STRENGTH_KEY) * self.level(params.time - fired)
+            }
+            None => 0.0,
+        }
+    }
+}
+
+impl Modulator for Envelope {
+    fn modulate(&self, params: &mut Params) {
+        params.set(self.target, self.value(params));
+    }
+
+    fn output(&self, params: &Params, target: &str) -> Option<f32> {
+        (target == self.target).then(|| self.value(params))
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.target.to_string()]
+    }
+
+    fn inputs(&self) -> Vec<String> {
+        vec![ONSET_TIME_KEY.to_string(), ONSET_STRENGTH_KEY.to_string()]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OneShot / Ramp  — single passes timed from the preset load
+// ---------------------------------------------------------------------------
+
+/// One attack / hold / release pass fired when the preset loads: after
+/// `delay` seconds `target` moves linearly from `from` to `to` over
+/// `attack`, stays there for `hold`, returns to `from` over `release` and
+/// then rests.  Unlike [`Envelope`] it never retriggers — use it for intro
+/// flourishes (a flash, a ripple swell) on load.
+pub struct OneShot {
+    pub target: &'static str,
+    pub from: f32,
+    pub to: f32,
+    pub delay: f32,
+    pub attack: f32,
+    pub hold: f32,
+    pub release: f32,
+}
+
+impl OneShot {
+    /// Level `age` seconds after the load, in \[0, 1\].
+    pub fn level(&self, age: f32) -> f32 {
+        let t = age - self.delay;
+        if t < 0.0 {
+            0.0
+        } else if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.hold {
+            1.0
+        } else {
+            let t = t - self.attack - self.hold;
+            (1.0 - t / self.release.max(f32::EPSILON)).max(0.0)
+        }
+    }
+
+    /// `from` blended towards `to` by the [`level`](Self::level) of the
+    /// pass at `params.time`.
+    pub fn value(&self, params: &Params) -> f32 {
+        self.from + (self.to - self.from) * self.level(params.time)
+    }
+}
+
+impl Modulator for OneShot {
+    fn modulate(&self, params: &mut Params) {
+        params.set(self.target, self.value(params));
+    }
+
+    fn output(&self, params: &Params, target: &str) -> Option<f32> {
+        (target == self.target).then(|| self.value(params))
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.target.to_string()]
+    }
+}
+
+/// Linear ramp of `target` from `from` to `to` over `duration` seconds after
+/// the preset loads, holding `to` afterwards — a zoom-in or fade-up intro.
+pub struct Ramp {
+    pub target: &'static str,
+    pub from: f32,
+    pub to: f32,
+    pub duration: f32,
+}
+
+impl Ramp {
+    /// The share of `duration` elapsed, taken from `from` to `to`; `to`
+    /// once it has run out.
+    pub fn value(&self, params: &Params) -> f32 {
+        let t = (params.time / self.duration.max(f32::EPSILON)).clamp(0.0, 1.0);
+        self.from + (self.to - self.from) * t
+    }
+}
+
+impl Modulator for Ramp {
+    fn modulate(&self, params: &mut Params) {
+        params.set(self.target, self.value(params));
+    }
+
+    fn output(&self, params: &Params, target: &str) -> Option<f32> {
+        (target == self.target).then(|| self.value(params))
+    }
+
+    fn targets(&self) -> Vec<String> {
+        vec![self.target.to_string()]
+    }
 }
 
 // ---------------------------------------------------------------------------
 // ModMatrix  — routes multiple modulators to params with min/max scaling
 // ---------------------------------------------------------------------------
 
+/// What drives a [`Route`].
+pub enum RouteSource {
+    /// A modulator whose raw \[-1, 1\] output is read back from the
+    /// route's target.
+    Modulator(Box<dyn Modulator>),
+    /// The smoothed \[0, 1\] level of an audio band.
+    Audio(AudioSource),
+    /// A strobe hit injected by [`ModMatrix::flash`]: full level until
+    /// frame `until` (exclusive), then dropped by
+    /// [`ModMatrix::expire_flashes`].
+    Flash { until: u64 },
+}
+
 pub struct Route {
-    pub modulator: Box<dyn Modulator>,
-    pub target: &'static str,
+    pub source: RouteSource,
+    pub target: String,
     pub min: f32,
     pub max: f32,
+    /// Snap the output to discrete values; `None` sweeps continuously.
+    pub quantize: Option<Quantizer>,
+    /// Only drive the target while the gate is open; `None` always does.
+    pub when: Option<Gate>,
+}
+
+/// Snaps a route's output to discrete values, so modulation can jump
+/// between, say, palette offsets or iteration counts instead of gliding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Quantizer {
+    /// `n` evenly spaced values from the route's `min` to its `max`, both
+    /// included; fewer than 2 holds the output at `min`.
+    Steps(u32),
+    /// The listed value nearest the unquantized output; an empty list
+    /// leaves it unquantized.
+    Values(Vec<f32>),
+}
+
+impl Quantizer {
+    /// The route output for `unit` ∈ \[0, 1\] of the way from `min` to
+    /// `max`, snapped.
+    pub fn apply(&self, unit: f32, min: f32, max: f32) -> f32 {
+        match self {
+            Quantizer::Steps(n) => {
+                let steps = n.saturating_sub(1) as f32;
+                let snapped = if steps > 0.0 {
+                    (unit.clamp(0.0, 1.0) * steps).round() / steps
+                } else {
+                    0.0
+                };
+                min + snapped * (max - min)
+            }
+            Quantizer::Values(values) => {
+                let scaled = min + unit * (max - min);
+                values
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| (a - scaled).abs().total_cmp(&(b - scaled).abs()))
+                    .unwrap_or(scaled)
+            }
+        }
+    }
+}
+
+/// Opens a route while another key exceeds a threshold — e.g. glitch only
+/// while `audio_bass` is above 0.7.  Once open it stays open until the key
+/// falls below `above - hysteresis`, so a level hovering at the threshold
+/// does not flicker the route on and off.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Condition {
+    pub key: String,
+    pub above: f32,
+    #[serde(default)]
+    pub hysteresis: f32,
+}
+
+/// A [`Condition`] plus whether it is currently open.  The state sits in an
+/// atomic because `Modulator::modulate` takes `&self`.
+#[derive(Debug)]
+pub struct Gate {
+    pub condition: Condition,
+    open: AtomicBool,
+}
+
+impl Gate {
+    pub fn new(condition: Condition) -> Self {
+        Self {
+            condition,
+            open: AtomicBool::new(false),
+        }
+    }
+
+    /// Step the gate against the key's current value and report whether
+    /// the route should run.
+    pub fn is_open(&self, params: &Params) -> bool {
+        let Condition {
+            key,
+            above,
+            hysteresis,
+        } = &self.condition;
+        let value = params.get(key);
+        let open = if self.open.load(Ordering::Relaxed) {
+            value >= above - hysteresis.max(0.0)
+        } else {
+            value > *above
+        };
+        self.open.store(open, Ordering::Relaxed);
+        open
+    }
+}
+
+impl Route {
+    /// Build the route an [`AudioRoute`] declares.
+    pub fn audio(spec: &AudioRoute) -> Self {
+        Self {
+            source: RouteSource::Audio(AudioSource::new(spec.band, spec.smoothing)),
+            target: spec.target.clone(),
+            min: spec.min,
+            max: spec.max,
+            quantize: spec.quantize.clone(),
+            when: spec.when.clone().map(Gate::new),
+        }
+    }
+
+    /// The serialisable declaration of an audio route; `None` for
+    /// modulator routes, which only exist in code.
+    pub fn audio_spec(&self) -> Option<AudioRoute> {
+        match &self.source {
+            RouteSource::Audio(source) => Some(AudioRoute {
+                band: source.band,
+                target: self.target.clone(),
+                smoothing: source.smoothing,
+                min: self.min,
+                max: self.max,
+                quantize: self.quantize.clone(),
+                when: self.when.as_ref().map(|g| g.condition.clone()),
+            }),
+            RouteSource::Modulator(_) | RouteSource::Flash { .. } => None,
+        }
+    }
+
+    /// Keys the route reads before writing its target: its gate's key, its
+    /// audio band's level, and the target itself when a modulator source
+    /// doesn't write it (see [`ModMatrix::modulate`]).
+    pub fn reads(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.when.iter().map(|g| g.condition.key.clone()).collect();
+        match &self.source {
+            RouteSource::Modulator(m) if !m.targets().contains(&self.target) => {
+                keys.push(self.target.clone());
+            }
+            RouteSource::Audio(source) => keys.push(source.band.key().to_string()),
+            RouteSource::Modulator(_) | RouteSource::Flash { .. } => {}
+        }
+        keys
+    }
 }
 
 pub struct ModMatrix {
     pub routes: Vec<Route>,
 }
 
+impl ModMatrix {
+    /// The matrix of a patch file's audio routes, in
+    /// [`sorted`](Self::sort_routes) order.
+    pub fn from_audio_routes(specs: &[AudioRoute]) -> Self {
+        let mut matrix = Self {
+            routes: specs.iter().map(Route::audio).collect(),
+        };
+        matrix.sort_routes();
+        matrix
+    }
+
+    /// Reorder the routes so every key is written before the routes that
+    /// read it, keeping declared order otherwise.  Routes on a feedback
+    /// cycle have no such order and stay in declared order after
+    /// everything that feeds them.
+    pub fn sort_routes(&mut self) {
+        let mut rest = std::mem::take(&mut self.routes);
+        while !rest.is_empty() {
+            let waits = |i: usize| {
+                rest[i].reads().iter().any(|key| {
+                    rest.iter()
+                        .enumerate()
+                        .any(|(j, r)| j != i && r.target == *key)
+                })
+            };
+            let next = (0..rest.len()).find(|&i| !waits(i)).unwrap_or(0);
+            self.routes.push(rest.remove(next));
+        }
+    }
+
+    /// Keys on a feedback cycle — written by a route that, through a chain
+    /// of routes reading and writing keys, depends on its own output —
+    /// sorted.  A route reading its own target counts.
+    pub fn feedback_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .routes
+            .iter()
+            .map(|r| r.target.clone())
+            .filter(|target| self.reaches(target, target))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// Whether writing `from` changes, through one or more routes, what
+    /// is written to `to`.
+    fn reaches(&self, from: &str, to: &str) -> bool {
+        let mut seen: Vec<&str> = Vec::new();
+        let mut stack = vec![from];
+        while let Some(key) = stack.pop() {
+            for route in &self.routes {
+                if !route.reads().iter().any(|k| k == key) {
+                    continue;
+                }
+                if route.target == to {
+                    return true;
+                }
+                if !seen.contains(&route.target.as_str()) {
+                    seen.push(&route.target);
+                    stack.push(&route.target);
+                }
+            }
+        }
+        false
+    }
+
+    /// Declarations of every audio route, in order.
+    pub fn audio_routes(&self) -> Vec<AudioRoute> {
+        self.routes.iter().filter_map(Route::audio_spec).collect()
+    }
+
+    /// Hold each target at its flash value for the `frames` frames after
+    /// `params.frame`.  A key already flashing restarts its flash.
+    pub fn flash(&mut self, targets: &[FlashTarget], frames: u32, params: &Params) {
+        let until = params.frame + u64::from(frames.max(1)) + 1;
+        self.routes.retain(|r| {
+            !matches!(r.source, RouteSource::Flash { .. })
+                || targets.iter().all(|t| t.key != r.target)
+        });
+        self.routes.extend(targets.iter().map(|t| Route {
+            source: RouteSource::Flash { until },
+            target: t.key.clone(),
+            min: t.value,
+            max: t.value,
+            quantize: None,
+            when: None,
+        }));
+    }
+
+    /// Drop flashes that are over by `frame`, handing their keys back to
+    /// whatever wrote them before.
+    pub fn expire_flashes(&mut self, frame: u64) {
+        self.routes
+            .retain(|r| !matches!(r.source, RouteSource::Flash { until } if until <= frame));
+    }
+}
+
 impl Modulator for ModMatrix {
     fn modulate(&self, params: &mut Params) {
         for route in &self.routes {
-            // Run the inner modulator into a temporary params, read back the
-            // raw [-1, 1] output, then scale to [min, max].
-            let mut tmp = params.clone();
-            route.modulator.modulate(&mut tmp);
-            let raw = tmp.get(route.target);
-            let scaled = route.min + (raw * 0.5 + 0.5) * (route.max - route.min);
-            params.set(route.target, scaled);
+            // Gates read params as earlier routes left them, so one route
+            // can open another.
+            if route.when.as_ref().is_some_and(|g| !g.is_open(params)) {
+                continue;
+            }
+            let unit = match &route.source {
+                RouteSource::Modulator(modulator) => {
+                    // The inner modulator's raw [-1, 1] output for the
+                    // target (its current value if it writes elsewhere),
+                    // mapped to [0, 1].
+                    let raw = modulator
+                        .output(params, &route.target)
+                        .unwrap_or_else(|| params.get(&route.target));
+                    raw * 0.5 + 0.5
+                }
+                RouteSource::Audio(source) => source.level(params),
+                RouteSource::Flash { .. } => 1.0,
+            };
+            let mut value = match &route.quantize {
+                Some(q) => q.apply(unit, route.min, route.max),
+                None => route.min + unit * (route.max - route.min),
+            };
+            // Hold the output to the route's range: a modulator can swing
+            // past [-1, 1], a route rescaling its own output each frame can
+            // grow without bound, and `min + unit * (max - min)` can round
+            // just past either end.  Listed quantizer values are kept as
+            // written.
+            if !matches!(&route.quantize, Some(Quantizer::Values(v)) if !v.is_empty()) {
+                value = value.clamp(route.min.min(route.max), route.min.max(route.max));
+            }
+            if value.is_finite() {
+                params.set(route.target.as_str(), value);
+            }
+        }
+    }
+
+    fn targets(&self) -> Vec<String> {
+        self.routes.iter().map(|r| r.target.clone()).collect()
+    }
+
+    fn inputs(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.routes.iter().flat_map(Route::reads).collect();
+        for route in &self.routes {
+            if let RouteSource::Modulator(m) = &route.source {
+                keys.extend(m.inputs());
+            }
         }
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Audio routes and flashes  — data declared in patch files
+// ---------------------------------------------------------------------------
+
+/// An audio-band → parameter route as written in a patch file.  `smoothing`
+/// is the time constant (seconds) of an exponential follower on the band
+/// level; 0 follows it directly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioRoute {
+    pub band: AudioBand,
+    pub target: String,
+    #[serde(default)]
+    pub smoothing: f32,
+    pub min: f32,
+    pub max: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantize: Option<Quantizer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<Condition>,
+}
+
+/// One param a strobe hit spikes, and the value it is held at.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlashTarget {
+    pub key: String,
+    pub value: f32,
+}
+
+/// Follows an audio band's level.  The follower state sits behind a lock
+/// because `Modulator::modulate` takes `&self`.
+pub struct AudioSource {
+    pub band: AudioBand,
+    pub smoothing: f32,
+    /// Patch time and level of the last step.
+    state: Mutex<Option<(f32, f32)>>,
+}
+
+impl AudioSource {
+    pub fn new(band: AudioBand, smoothing: f32) -> Self {
+        Self {
+            band,
+            smoothing,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Smoothed band level in \[0, 1\] at `params.time`.
+    pub fn level(&self, params: &Params) -> f32 {
+        let input = params.get(self.band.key()).clamp(0.0, 1.0);
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let level = match *state {
+            Some((time, prev)) if self.smoothing > 0.0 => {
+                let dt = (params.time - time).max(0.0);
+                prev + (input - prev) * (1.0 - (-dt / self.smoothing).exp())
+            }
+            _ => input,
+        };
+        *state = Some((params.time, level));
+        level
     }
 }
 
@@ -117,10 +863,13 @@ impl Modulator for ModMatrix {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
     fn params_at(time: f32) -> Params {
-        let mut p = Params::default();
-        p.time = time;
-        p
+        Params {
+            time,
+            ..Default::default()
+        }
     }
 
     // --- Lfo::Sine ------------------------------------------------------------
@@ -134,6 +883,7 @@ mod tests {
             frequency: 1.0,
             amplitude: 2.0,
             offset: 5.0,
+            phase: 0.0,
         };
         let mut p = params_at(0.0);
         lfo.modulate(&mut p);
@@ -149,6 +899,7 @@ mod tests {
             frequency: 1.0,
             amplitude: 1.0,
             offset: 0.0,
+            phase: 0.0,
         };
         let mut p = params_at(0.25);
         lfo.modulate(&mut p);
@@ -164,6 +915,7 @@ mod tests {
             frequency: 1.0,
             amplitude: 1.0,
             offset: 0.0,
+            phase: 0.0,
         };
         let mut p = params_at(0.75);
         lfo.modulate(&mut p);
@@ -179,12 +931,48 @@ mod tests {
             frequency: 1.0,
             amplitude: 3.0,
             offset: 10.0,
+            phase: 0.0,
         };
         let mut p = params_at(0.25);
         lfo.modulate(&mut p);
         assert!((p.get("v") - 13.0).abs() < 1e-4, "got {}", p.get("v"));
     }
 
+    #[test]
+    fn lfo_phase_offsets_the_cycle() {
+        // A quarter-cycle offset puts the sine peak at t = 0.
+        let lfo = Lfo {
+            target: "v",
+            waveform: Waveform::Sine,
+            frequency: 1.0,
+            amplitude: 1.0,
+            offset: 0.0,
+            phase: 0.25,
+        };
+        let mut p = params_at(0.0);
+        lfo.modulate(&mut p);
+        assert!((p.get("v") - 1.0).abs() < 1e-5, "got {}", p.get("v"));
+    }
+
+    #[test]
+    fn lfo_retrigger_restarts_the_cycle() {
+        let lfo = Lfo {
+            target: "v",
+            waveform: Waveform::Saw,
+            frequency: 0.5,
+            amplitude: 1.0,
+            offset: 0.0,
+            phase: 0.0,
+        };
+        let mut p = params_at(3.3);
+        Lfo::retrigger(&mut p);
+        lfo.modulate(&mut p);
+        assert!((p.get("v") - (-1.0)).abs() < 1e-5, "got {}", p.get("v"));
+        p.time += 1.0;
+        lfo.modulate(&mut p);
+        assert!(p.get("v").abs() < 1e-5, "got {}", p.get("v"));
+    }
+
     // --- Lfo::Square ----------------------------------------------------------
 
     #[test]
@@ -196,6 +984,7 @@ mod tests {
             frequency: 1.0,
             amplitude: 1.0,
             offset: 0.0,
+            phase: 0.0,
         };
         let mut p = params_at(0.1);
         lfo.modulate(&mut p);
@@ -211,6 +1000,7 @@ mod tests {
             frequency: 1.0,
             amplitude: 1.0,
             offset: 0.0,
+            phase: 0.0,
         };
         let mut p = params_at(0.75);
         lfo.modulate(&mut p);
@@ -228,6 +1018,7 @@ mod tests {
             frequency: 1.0,
             amplitude: 1.0,
             offset: 0.0,
+            phase: 0.0,
         };
         let mut p = params_at(0.5);
         lfo.modulate(&mut p);
@@ -245,12 +1036,88 @@ mod tests {
             frequency: 1.0,
             amplitude: 1.0,
             offset: 0.0,
+            phase: 0.0,
         };
         let mut p = params_at(0.5);
         lfo.modulate(&mut p);
         assert!((p.get("v") - 1.0).abs() < 1e-5, "got {}", p.get("v"));
     }
 
+    // --- Lfo::SmoothRandom / Exponential / Table ------------------------------
+
+    #[test]
+    fn smooth_random_is_bounded_continuous_and_varied() {
+        let w = Waveform::SmoothRandom;
+        let levels: Vec<f32> = (0..8).map(|n| w.sample(n as f32, 7)).collect();
+        assert!(levels.iter().all(|v| (-1.0..=1.0).contains(v)));
+        assert!(levels.windows(2).any(|p| p[0] != p[1]));
+        // Crossing a cycle boundary doesn't jump.
+        assert!((w.sample(2.999, 7) - w.sample(3.0, 7)).abs() < 1e-3);
+        assert_ne!(w.sample(0.5, 7), w.sample(0.5, 8));
+    }
+
+    #[test]
+    fn exponential_rises_slowly_then_fast() {
+        let w = Waveform::Exponential;
+        assert!((w.sample(0.0, 0) + 1.0).abs() < 1e-6);
+        assert!((w.sample(0.9999, 0) - 1.0).abs() < 1e-3);
+        // Below the midpoint of the sweep halfway through the cycle.
+        assert!(w.sample(0.5, 0) < -0.5);
+    }
+
+    #[test]
+    fn wavetable_interpolates_and_wraps() {
+        let mut table = Wavetable {
+            points: vec![0.0, 1.0, -1.0, 0.5],
+            interpolation: Interpolation::Linear,
+        };
+        assert_eq!(table.sample(0.125), 0.5);
+        // The last point blends back into the first.
+        assert_eq!(table.sample(0.875), 0.25);
+        assert_eq!(table.sample(1.25), 1.0);
+        table.interpolation = Interpolation::Step;
+        assert_eq!(table.sample(0.49), 1.0);
+        table.interpolation = Interpolation::Smooth;
+        assert_eq!(table.sample(0.375), 0.0);
+        assert!(table.sample(0.3) > 0.0 && table.sample(0.3) < 1.0);
+        table.points.clear();
+        assert_eq!(table.sample(0.3), 0.0);
+    }
+
+    #[test]
+    fn wavetable_reads_from_json_with_linear_default() {
+        let table: Wavetable = serde_json::from_str(r#"{ "points": [0, 1] }"#).unwrap();
+        assert_eq!(table.interpolation, Interpolation::Linear);
+        let lfo = Lfo {
+            target: "v",
+            waveform: Waveform::Table(Arc::new(table)),
+            frequency: 1.0,
+            amplitude: 2.0,
+            offset: 1.0,
+            phase: 0.25,
+        };
+        assert_eq!(lfo.value(&params_at(0.0)), 2.0);
+    }
+
+    // --- Lfo range ------------------------------------------------------------
+
+    #[test]
+    fn lfo_range_round_trips_and_keeps_direction() {
+        let mut lfo = Lfo {
+            target: "v",
+            waveform: Waveform::Sine,
+            frequency: 0.5,
+            amplitude: -0.25,
+            offset: 1.0,
+            phase: 0.0,
+        };
+        assert_eq!(lfo.range(), (0.75, 1.25));
+        lfo.set_range(2.0, 0.0);
+        assert_eq!(lfo.range(), (0.0, 2.0));
+        assert_eq!((lfo.offset, lfo.amplitude), (1.0, -1.0));
+        assert_eq!(lfo.as_lfo(), Some(&lfo));
+    }
+
     // --- MouseModulator -------------------------------------------------------
 
     #[test]
@@ -259,8 +1126,10 @@ mod tests {
             target_x: Some("mx"),
             target_y: None,
         };
-        let mut p = Params::default();
-        p.mouse_x = 1.0; // → 1.0*2 - 1 = 1.0
+        let mut p = Params {
+            mouse_x: 1.0, // → 1.0*2 - 1 = 1.0
+            ..Default::default()
+        };
         mm.modulate(&mut p);
         assert!((p.get("mx") - 1.0).abs() < 1e-6);
     }
@@ -271,8 +1140,10 @@ mod tests {
             target_x: None,
             target_y: Some("my"),
         };
-        let mut p = Params::default();
-        p.mouse_y = 0.5; // → 0.5*2 - 1 = 0.0
+        let mut p = Params {
+            mouse_y: 0.5, // → 0.5*2 - 1 = 0.0
+            ..Default::default()
+        };
         mm.modulate(&mut p);
         assert!((p.get("my")).abs() < 1e-6);
     }
@@ -296,12 +1167,160 @@ mod tests {
             target: "drift",
             speed: 1.0,
         };
-        let mut p = Params::default();
-        p.time = 1.0;
+        let mut p = params_at(1.0);
         rw.modulate(&mut p);
         // Value is deterministic — just check it's in [-0.5, 0.5]
         let v = p.get("drift");
-        assert!(v >= -0.5 && v <= 0.5, "out of range: {v}");
+        assert!((-0.5..=0.5).contains(&v), "out of range: {v}");
+    }
+
+    // --- JuliaPath ------------------------------------------------------------
+
+    #[test]
+    fn julia_path_circle_stays_on_radius() {
+        let jp = JuliaPath {
+            shape: JuliaPathShape::Circle { radius: 0.7885 },
+            rate: 0.1,
+        };
+        for t in [0.0, 1.3, 2.7, 8.0] {
+            let mut p = params_at(t);
+            jp.modulate(&mut p);
+            let r = p.get("julia_cx").hypot(p.get("julia_cy"));
+            assert!((r - 0.7885).abs() < 1e-5, "t={t} r={r}");
+        }
+    }
+
+    #[test]
+    fn julia_path_cardioid_cusp_at_zero() {
+        // t = 0 is the cardioid's cusp: 1/2 − 1/4 = 0.25 on the real axis.
+        let (x, y) = JuliaPathShape::Cardioid { scale: 1.0 }.point(0.0);
+        assert!((x - 0.25).abs() < 1e-6);
+        assert!(y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn julia_path_figure_eight_crosses_center() {
+        let shape = JuliaPathShape::FigureEight {
+            center: [-0.5, 0.1],
+            size: 0.2,
+        };
+        // sin(0) = sin(π) = 0 → both lobes meet at the centre.
+        for t in [0.0, std::f32::consts::PI] {
+            let (x, y) = shape.point(t);
+            assert!((x - (-0.5)).abs() < 1e-5 && (y - 0.1).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn julia_path_rate_sets_loop_period() {
+        // rate = 0.5 loops/s → back to the start after 2 s.
+        let jp = JuliaPath {
+            shape: JuliaPathShape::Circle { radius: 0.5 },
+            rate: 0.5,
+        };
+        let mut p0 = params_at(0.0);
+        let mut p2 = params_at(2.0);
+        jp.modulate(&mut p0);
+        jp.modulate(&mut p2);
+        assert!((p0.get("julia_cx") - p2.get("julia_cx")).abs() < 1e-5);
+        assert!((p0.get("julia_cy") - p2.get("julia_cy")).abs() < 1e-5);
+    }
+
+    // --- OneShot / Ramp ---------------------------------------------------------
+
+    fn swell() -> OneShot {
+        OneShot {
+            target: "ripple",
+            from: 1.0,
+            to: 5.0,
+            delay: 0.5,
+            attack: 1.0,
+            hold: 2.0,
+            release: 1.0,
+        }
+    }
+
+    #[test]
+    fn one_shot_passes_once() {
+        let s = swell();
+        assert_eq!(s.level(0.25), 0.0);
+        assert!((s.level(1.0) - 0.5).abs() < 1e-6);
+        assert_eq!(s.level(2.0), 1.0);
+        assert!((s.level(4.0) - 0.5).abs() < 1e-6);
+        assert_eq!(s.level(10.0), 0.0);
+    }
+
+    #[test]
+    fn one_shot_maps_level_between_from_and_to() {
+        let mut p = params_at(2.0);
+        swell().modulate(&mut p);
+        assert_eq!(p.get("ripple"), 5.0);
+        let mut p = params_at(60.0);
+        swell().modulate(&mut p);
+        assert_eq!(p.get("ripple"), 1.0);
+    }
+
+    #[test]
+    fn ramp_runs_from_a_to_b_then_holds() {
+        let ramp = Ramp {
+            target: "zoom_in",
+            from: 2.0,
+            to: 4.0,
+            duration: 4.0,
+        };
+        let at = |time| {
+            let mut p = params_at(time);
+            ramp.modulate(&mut p);
+            p.get("zoom_in")
+        };
+        assert_eq!(at(0.0), 2.0);
+        assert_eq!(at(1.0), 2.5);
+        assert_eq!(at(4.0), 4.0);
+        assert_eq!(at(30.0), 4.0);
+    }
+
+    // --- Envelope -------------------------------------------------------------
+
+    fn kick() -> Envelope {
+        Envelope {
+            target: "flash",
+            attack: 0.1,
+            release: 0.2,
+            amount: 2.0,
+        }
+    }
+
+    #[test]
+    fn envelope_silent_before_first_onset() {
+        let mut p = params_at(5.0);
+        kick().modulate(&mut p);
+        assert_eq!(p.get("flash"), 0.0);
+    }
+
+    #[test]
+    fn envelope_attacks_then_releases() {
+        let env = kick();
+        assert!((env.level(0.05) - 0.5).abs() < 1e-6);
+        assert!((env.level(0.1) - 1.0).abs() < 1e-6);
+        assert!((env.level(0.3) - (-1.0f32).exp()).abs() < 1e-6);
+        assert_eq!(env.level(-1.0), 0.0);
+    }
+
+    #[test]
+    fn envelope_scales_by_onset_strength() {
+        let mut p = params_at(3.0);
+        crate::audio::Onset {
+            time: 0.0,
+            strength: 0.5,
+        }
+        .apply(&mut p);
+        p.time += 0.1;
+        kick().modulate(&mut p);
+        assert!(
+            (p.get("flash") - 1.0).abs() < 1e-5,
+            "got {}",
+            p.get("flash")
+        );
     }
 
     // --- ModMatrix ------------------------------------------------------------
@@ -311,16 +1330,19 @@ mod tests {
         // Inner Lfo outputs +1.0 at t=0.25  →  raw=1.0  →  scaled = min + (1.0*0.5+0.5)*(max-min) = min + 1*(max-min) = max
         let matrix = ModMatrix {
             routes: vec![Route {
-                modulator: Box::new(Lfo {
+                source: RouteSource::Modulator(Box::new(Lfo {
                     target: "v",
                     waveform: Waveform::Sine,
                     frequency: 1.0,
                     amplitude: 1.0,
                     offset: 0.0,
-                }),
-                target: "v",
+                    phase: 0.0,
+                })),
+                target: "v".into(),
                 min: 10.0,
                 max: 20.0,
+                quantize: None,
+                when: None,
             }],
         };
         let mut p = params_at(0.25);
@@ -328,21 +1350,192 @@ mod tests {
         assert!((p.get("v") - 20.0).abs() < 1e-4, "got {}", p.get("v"));
     }
 
+    #[test]
+    fn quantizer_steps_snap_to_even_divisions() {
+        let q = Quantizer::Steps(3);
+        assert_eq!(q.apply(0.2, 0.0, 10.0), 0.0);
+        assert_eq!(q.apply(0.3, 0.0, 10.0), 5.0);
+        assert_eq!(q.apply(0.9, 0.0, 10.0), 10.0);
+        assert_eq!(Quantizer::Steps(1).apply(0.9, 2.0, 10.0), 2.0);
+    }
+
+    #[test]
+    fn quantizer_values_pick_nearest() {
+        let q = Quantizer::Values(vec![0.0, 0.25, 0.8]);
+        assert_eq!(q.apply(0.2, 0.0, 1.0), 0.25);
+        assert_eq!(q.apply(0.6, 0.0, 1.0), 0.8);
+        assert_eq!(Quantizer::Values(vec![]).apply(0.5, 0.0, 4.0), 2.0);
+    }
+
+    #[test]
+    fn mod_matrix_applies_route_quantizer() {
+        // Sine at t=0.1 → raw≈0.588 → unit≈0.794 → snapped to the upper of 2 steps.
+        let matrix = ModMatrix {
+            routes: vec![Route {
+                source: RouteSource::Modulator(Box::new(Lfo {
+                    target: "v",
+                    waveform: Waveform::Sine,
+                    frequency: 1.0,
+                    amplitude: 1.0,
+                    offset: 0.0,
+                    phase: 0.0,
+                })),
+                target: "v".into(),
+                min: 10.0,
+                max: 20.0,
+                quantize: Some(Quantizer::Steps(2)),
+                when: None,
+            }],
+        };
+        let mut p = params_at(0.1);
+        matrix.modulate(&mut p);
+        assert_eq!(p.get("v"), 20.0);
+    }
+
+    fn bass_gate() -> Gate {
+        Gate::new(Condition {
+            key: "audio_bass".into(),
+            above: 0.7,
+            hysteresis: 0.2,
+        })
+    }
+
+    #[test]
+    fn gate_opens_above_threshold_and_closes_with_hysteresis() {
+        let gate = bass_gate();
+        let mut p = params_at(0.0);
+        for (bass, open) in [(0.6, false), (0.75, true), (0.55, true), (0.45, false)] {
+            p.set("audio_bass", bass);
+            assert_eq!(gate.is_open(&p), open, "bass {bass}");
+        }
+    }
+
+    #[test]
+    fn mod_matrix_skips_routes_whose_gate_is_closed() {
+        let matrix = ModMatrix {
+            routes: vec![Route {
+                source: RouteSource::Flash { until: u64::MAX },
+                target: "glitch".into(),
+                min: 1.0,
+                max: 1.0,
+                quantize: None,
+                when: Some(bass_gate()),
+            }],
+        };
+        let mut p = params_at(0.0);
+        p.set("audio_bass", 0.5);
+        matrix.modulate(&mut p);
+        assert_eq!(p.get("glitch"), 0.0);
+        p.set("audio_bass", 0.9);
+        matrix.modulate(&mut p);
+        assert_eq!(p.get("glitch"), 1.0);
+    }
+
+    /// A full-level route to `target`, gated on `key` rising above 0.5.
+    fn gated(target: &str, key: &str) -> Route {
+        Route {
+            source: RouteSource::Flash { until: u64::MAX },
+            target: target.into(),
+            min: 1.0,
+            max: 1.0,
+            quantize: None,
+            when: Some(Gate::new(Condition {
+                key: key.into(),
+                above: 0.5,
+                hysteresis: 0.0,
+            })),
+        }
+    }
+
+    #[test]
+    fn sort_routes_runs_writers_before_readers() {
+        let mut matrix = ModMatrix {
+            routes: vec![gated("b", "a"), gated("a", "trigger")],
+        };
+        assert!(matrix.feedback_keys().is_empty());
+        matrix.sort_routes();
+        let order: Vec<&str> = matrix.routes.iter().map(|r| r.target.as_str()).collect();
+        assert_eq!(order, ["a", "b"]);
+        // Sorted, one frame carries the trigger all the way through.
+        let mut p = params_at(0.0);
+        p.set("trigger", 1.0);
+        matrix.modulate(&mut p);
+        assert_eq!(p.get("b"), 1.0);
+    }
+
+    #[test]
+    fn feedback_keys_finds_cycles_and_self_reads() {
+        let mut matrix = ModMatrix {
+            routes: vec![gated("a", "b"), gated("b", "a"), gated("c", "a")],
+        };
+        assert_eq!(matrix.feedback_keys(), ["a", "b"]);
+        matrix.sort_routes();
+        let order: Vec<&str> = matrix.routes.iter().map(|r| r.target.as_str()).collect();
+        assert_eq!(order, ["a", "b", "c"]);
+
+        // A modulator that doesn't write the target reads it back.
+        let echo = ModMatrix {
+            routes: vec![Route {
+                source: RouteSource::Modulator(Box::new(Ramp {
+                    target: "elsewhere",
+                    from: 0.0,
+                    to: 1.0,
+                    duration: 1.0,
+                })),
+                target: "v".into(),
+                min: -10.0,
+                max: 10.0,
+                quantize: None,
+                when: None,
+            }],
+        };
+        assert_eq!(echo.feedback_keys(), ["v"]);
+    }
+
+    #[test]
+    fn self_feeding_route_is_held_to_its_range() {
+        // v ← -10 + (v/2 + 0.5)·20 = 10v, tenfold a frame unguarded.
+        let matrix = ModMatrix {
+            routes: vec![Route {
+                source: RouteSource::Modulator(Box::new(Ramp {
+                    target: "elsewhere",
+                    from: 0.0,
+                    to: 1.0,
+                    duration: 1.0,
+                })),
+                target: "v".into(),
+                min: -10.0,
+                max: 10.0,
+                quantize: None,
+                when: None,
+            }],
+        };
+        let mut p = params_at(0.0);
+        p.set("v", 0.5);
+        for _ in 0..100 {
+            matrix.modulate(&mut p);
+        }
+        assert_eq!(p.get("v"), 10.0);
+    }
+
     #[test]
     fn mod_matrix_scales_min_at_negative_one() {
         // Lfo Sine at t=0.75  →  raw=-1.0  →  scaled = min + (-1*0.5+0.5)*(max-min) = min + 0 = min
         let matrix = ModMatrix {
             routes: vec![Route {
-                modulator: Box::new(Lfo {
+                source: RouteSource::Modulator(Box::new(Lfo {
                     target: "v",
                     waveform: Waveform::Sine,
                     frequency: 1.0,
                     amplitude: 1.0,
                     offset: 0.0,
-                }),
-                target: "v",
+                    phase: 0.0,
+                })),
+                target: "v".into(),
                 min: 10.0,
                 max: 20.0,
+                quantize: None,
+                when: None,
             }],
         };
         let mut p = params_at(0.75);
@@ -356,28 +1549,34 @@ mod tests {
         let matrix = ModMatrix {
             routes: vec![
                 Route {
-                    modulator: Box::new(Lfo {
+                    source: RouteSource::Modulator(Box::new(Lfo {
                         target: "a",
                         waveform: Waveform::Sine,
                         frequency: 1.0,
                         amplitude: 1.0,
                         offset: 0.0,
-                    }),
-                    target: "a",
+                        phase: 0.0,
+                    })),
+                    target: "a".into(),
                     min: 0.0,
                     max: 1.0,
+                    quantize: None,
+                    when: None,
                 },
                 Route {
-                    modulator: Box::new(Lfo {
+                    source: RouteSource::Modulator(Box::new(Lfo {
                         target: "b",
                         waveform: Waveform::Sine,
                         frequency: 1.0,
                         amplitude: 1.0,
                         offset: 0.0,
-                    }),
-                    target: "b",
+                        phase: 0.0,
+                    })),
+                    target: "b".into(),
                     min: 5.0,
                     max: 10.0,
+                    quantize: None,
+                    when: None,
                 },
             ],
         };
@@ -386,4 +1585,391 @@ mod tests {
         assert!((p.get("a") - 1.0).abs() < 1e-4);
         assert!((p.get("b") - 10.0).abs() < 1e-4);
     }
+
+    /// `output` must report exactly what `modulate` writes, and nothing for
+    /// keys it leaves alone.
+    fn assert_output_matches(m: &dyn Modulator, p: &Params, keys: &[&str]) {
+        let mut written = p.clone();
+        m.modulate(&mut written);
+        for &key in keys {
+            assert_eq!(m.output(p, key), Some(written.get(key)), "{key}");
+        }
+        assert_eq!(m.output(p, "unrelated"), None);
+    }
+
+    #[test]
+    fn output_matches_modulate() {
+        let mut p = params_at(0.4);
+        p.mouse_x = 0.3;
+        p.mouse_y = 0.8;
+        let lfo = Lfo {
+            target: "v",
+            waveform: Waveform::Triangle,
+            frequency: 0.7,
+            amplitude: 2.0,
+            offset: 0.5,
+            phase: 0.1,
+        };
+        assert_output_matches(&lfo, &p, &["v"]);
+        let walk = RandomWalk {
+            target: "v",
+            speed: 1.5,
+        };
+        assert_output_matches(&walk, &p, &["v"]);
+        let mouse = MouseModulator {
+            target_x: Some("x"),
+            target_y: Some("y"),
+        };
+        assert_output_matches(&mouse, &p, &["x", "y"]);
+        let path = JuliaPath {
+            shape: JuliaPathShape::Circle { radius: 0.7885 },
+            rate: 0.2,
+        };
+        assert_output_matches(&path, &p, &["julia_cx", "julia_cy"]);
+        let ramp = Ramp {
+            target: "v",
+            from: 1.0,
+            to: 3.0,
+            duration: 2.0,
+        };
+        assert_output_matches(&ramp, &p, &["v"]);
+    }
+
+    #[test]
+    fn mod_matrix_route_from_other_target_holds_value() {
+        // The inner Lfo writes "a", so a route to "v" reads v's own value.
+        let matrix = ModMatrix {
+            routes: vec![Route {
+                source: RouteSource::Modulator(Box::new(Lfo {
+                    target: "a",
+                    waveform: Waveform::Sine,
+                    frequency: 1.0,
+                    amplitude: 1.0,
+                    offset: 0.0,
+                    phase: 0.0,
+                })),
+                target: "v".into(),
+                min: 0.0,
+                max: 4.0,
+                quantize: None,
+                when: None,
+            }],
+        };
+        let mut p = params_at(0.25);
+        p.set("v", 0.5);
+        matrix.modulate(&mut p);
+        assert!((p.get("v") - 3.0).abs() < 1e-4, "got {}", p.get("v"));
+    }
+
+    // --- Flash ----------------------------------------------------------------
+
+    fn strobe() -> Vec<FlashTarget> {
+        vec![FlashTarget {
+            key: "brightness".into(),
+            value: 0.9,
+        }]
+    }
+
+    #[test]
+    fn flash_holds_its_targets_for_n_frames() {
+        let mut matrix = ModMatrix::from_audio_routes(&[bass_to_zoom(0.0)]);
+        let mut p = params_at(0.0);
+        p.frame = 10;
+        matrix.flash(&strobe(), 2, &p);
+        for frame in [11, 12] {
+            p.frame = frame;
+            p.set("brightness", 0.1);
+            matrix.expire_flashes(frame);
+            matrix.modulate(&mut p);
+            assert_eq!(p.get("brightness"), 0.9, "frame {frame}");
+        }
+        p.frame = 13;
+        p.set("brightness", 0.1);
+        matrix.expire_flashes(13);
+        matrix.modulate(&mut p);
+        assert_eq!(p.get("brightness"), 0.1);
+        // Only the flash went; the audio route stays.
+        assert_eq!(matrix.routes.len(), 1);
+    }
+
+    #[test]
+    fn reflash_restarts_instead_of_stacking() {
+        let mut matrix = ModMatrix { routes: Vec::new() };
+        let p = params_at(0.0);
+        matrix.flash(&strobe(), 4, &p);
+        matrix.flash(&strobe(), 4, &p);
+        assert_eq!(matrix.targets(), ["brightness"]);
+        assert!(matrix.audio_routes().is_empty());
+    }
+
+    // --- Audio routes ---------------------------------------------------------
+
+    fn bass_to_zoom(smoothing: f32) -> AudioRoute {
+        AudioRoute {
+            band: AudioBand::Bass,
+            target: "zoom_kick".into(),
+            smoothing,
+            min: 1.0,
+            max: 3.0,
+            quantize: None,
+            when: None,
+        }
+    }
+
+    #[test]
+    fn matrix_inputs_cover_bands_gates_and_inner_modulators() {
+        let mut gated = bass_to_zoom(0.0);
+        gated.when = Some(Condition {
+            key: "audio_high".into(),
+            above: 0.5,
+            hysteresis: 0.0,
+        });
+        let mut matrix = ModMatrix::from_audio_routes(&[gated]);
+        matrix.routes.push(Route {
+            source: RouteSource::Modulator(Box::new(Lfo {
+                target: "hue",
+                waveform: Waveform::Sine,
+                frequency: 1.0,
+                amplitude: 1.0,
+                offset: 0.0,
+                phase: 0.0,
+            })),
+            target: "hue".into(),
+            min: 0.0,
+            max: 1.0,
+            quantize: None,
+            when: None,
+        });
+        assert_eq!(
+            matrix.inputs(),
+            [AudioBand::Bass.key(), "audio_high", LFO_RETRIGGER_KEY]
+        );
+    }
+
+    #[test]
+    fn audio_route_scales_band_level() {
+        let matrix = ModMatrix::from_audio_routes(&[bass_to_zoom(0.0)]);
+        let mut p = params_at(0.0);
+        p.set(AudioBand::Bass.key(), 0.5);
+        matrix.modulate(&mut p);
+        assert!((p.get("zoom_kick") - 2.0).abs() < 1e-6);
+        // Out-of-range levels are clamped.
+        p.set(AudioBand::Bass.key(), 7.0);
+        matrix.modulate(&mut p);
+        assert!((p.get("zoom_kick") - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn audio_route_smooths_over_time() {
+        let matrix = ModMatrix::from_audio_routes(&[bass_to_zoom(0.1)]);
+        let mut p = params_at(0.0);
+        matrix.modulate(&mut p); // settles at silence
+        p.set(AudioBand::Bass.key(), 1.0);
+        p.time = 0.1; // one time constant later: 1 - 1/e of the way
+        matrix.modulate(&mut p);
+        let expected = 1.0 + 2.0 * (1.0 - (-1.0f32).exp());
+        assert!((p.get("zoom_kick") - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn audio_routes_round_trip_through_matrix() {
+        let specs = vec![bass_to_zoom(0.2), {
+            let mut r = bass_to_zoom(0.0);
+            r.band = AudioBand::Treble;
+            r
+        }];
+        let mut matrix = ModMatrix::from_audio_routes(&specs);
+        matrix.routes.push(Route {
+            source: RouteSource::Modulator(Box::new(Lfo {
+                target: "v",
+                waveform: Waveform::Sine,
+                frequency: 1.0,
+                amplitude: 1.0,
+                offset: 0.0,
+                phase: 0.0,
+            })),
+            target: "v".into(),
+            min: 0.0,
+            max: 1.0,
+            quantize: None,
+            when: None,
+        });
+        assert_eq!(matrix.audio_routes(), specs);
+    }
+
+    #[test]
+    fn audio_route_json_defaults_smoothing() {
+        let r: AudioRoute =
+            serde_json::from_str(r#"{"band":"Mid","target":"x","min":0,"max":1}"#).unwrap();
+        assert_eq!(r.band, AudioBand::Mid);
+        assert_eq!(r.smoothing, 0.0);
+    }
+
+    #[test]
+    fn audio_route_json_carries_quantizer() {
+        let r: AudioRoute = serde_json::from_str(
+            r#"{"band":"Bass","target":"x","min":0,"max":1,"quantize":{"Values":[0,0.5]}}"#,
+        )
+        .unwrap();
+        assert_eq!(r.quantize, Some(Quantizer::Values(vec![0.0, 0.5])));
+        let plain: AudioRoute =
+            serde_json::from_str(r#"{"band":"Mid","target":"x","min":0,"max":1}"#).unwrap();
+        assert_eq!(plain.quantize, None);
+        assert!(!serde_json::to_string(&plain).unwrap().contains("quantize"));
+    }
+
+    #[test]
+    fn audio_route_json_carries_condition() {
+        let r: AudioRoute = serde_json::from_str(
+            r#"{"band":"Treble","target":"x","min":0,"max":1,"when":{"key":"audio_bass","above":0.7}}"#,
+        )
+        .unwrap();
+        let condition = r.when.clone().unwrap();
+        assert_eq!(condition.key, "audio_bass");
+        assert_eq!(condition.hysteresis, 0.0);
+        assert_eq!(Route::audio(&r).audio_spec(), Some(r));
+    }
+
+    // --- Properties -----------------------------------------------------------
+
+    fn waveform() -> impl Strategy<Value = Waveform> {
+        let table =
+            (prop::collection::vec(-1.0f32..=1.0, 0..8), 0..3usize).prop_map(|(points, i)| {
+                let interpolation = [
+                    Interpolation::Step,
+                    Interpolation::Linear,
+                    Interpolation::Smooth,
+                ][i];
+                Waveform::Table(Arc::new(Wavetable {
+                    points,
+                    interpolation,
+                }))
+            });
+        prop_oneof![
+            (0..Waveform::ALL.len()).prop_map(|i| Waveform::ALL[i].clone()),
+            table,
+        ]
+    }
+
+    /// An LFO whose raw output stays within \[-1, 1\]: `|amplitude| +
+    /// |offset|` is at most 1.
+    fn unit_lfo() -> impl Strategy<Value = Lfo> {
+        (
+            waveform(),
+            0.0f32..20.0,
+            -1.0f32..=1.0,
+            0.0f32..=1.0,
+            -4.0f32..4.0,
+        )
+            .prop_map(|(waveform, frequency, amplitude, share, phase)| Lfo {
+                target: "v",
+                waveform,
+                frequency,
+                amplitude,
+                offset: (1.0 - amplitude.abs()) * (share * 2.0 - 1.0),
+                phase,
+            })
+    }
+
+    fn quantizer() -> impl Strategy<Value = Option<Quantizer>> {
+        prop::option::of((0u32..12).prop_map(Quantizer::Steps))
+    }
+
+    proptest! {
+        #[test]
+        fn waveforms_stay_within_unit_range(
+            waveform in waveform(),
+            cycles in -1e4f32..1e4,
+            seed in any::<u32>(),
+        ) {
+            let v = waveform.sample(cycles, seed);
+            prop_assert!((-1.0..=1.0).contains(&v), "{waveform:?} at {cycles}: {v}");
+        }
+
+        #[test]
+        fn lfo_is_bounded_by_amplitude_and_offset(
+            waveform in waveform(),
+            frequency in 0.0f32..50.0,
+            amplitude in -100.0f32..100.0,
+            offset in -100.0f32..100.0,
+            phase in -4.0f32..4.0,
+            time in 0.0f32..1e4,
+        ) {
+            let lfo = Lfo { target: "v", waveform, frequency, amplitude, offset, phase };
+            let v = lfo.value(&params_at(time));
+            let bound = amplitude.abs() + offset.abs();
+            prop_assert!(v.abs() <= bound * (1.0 + 1e-6), "{v} exceeds {bound}");
+            let (low, high) = lfo.range();
+            prop_assert!(low - 1e-4 <= v && v <= high + 1e-4, "{v} outside {low}–{high}");
+        }
+
+        #[test]
+        fn mod_matrix_output_stays_within_route_range(
+            lfo in unit_lfo(),
+            min in -1e4f32..1e4,
+            max in -1e4f32..1e4,
+            quantize in quantizer(),
+            time in 0.0f32..1e3,
+        ) {
+            let matrix = ModMatrix {
+                routes: vec![Route {
+                    source: RouteSource::Modulator(Box::new(lfo)),
+                    target: "v".into(),
+                    min,
+                    max,
+                    quantize,
+                    when: None,
+                }],
+            };
+            let mut p = params_at(time);
+            matrix.modulate(&mut p);
+            let v = p.get("v");
+            prop_assert!(min.min(max) <= v && v <= min.max(max), "{v} outside {min}–{max}");
+        }
+
+        #[test]
+        fn mod_matrix_holds_overshooting_sources_to_range(
+            amplitude in -50.0f32..50.0,
+            offset in -50.0f32..50.0,
+            min in -1e4f32..1e4,
+            max in -1e4f32..1e4,
+            level in -10.0f32..10.0,
+            time in 0.0f32..1e3,
+        ) {
+            // A modulator past [-1, 1] beside an audio band past [0, 1].
+            let lfo = Lfo {
+                target: "v",
+                waveform: Waveform::Sine,
+                frequency: 1.0,
+                amplitude,
+                offset,
+                phase: 0.0,
+            };
+            let mut audio = AudioRoute {
+                band: AudioBand::Bass,
+                target: "w".into(),
+                smoothing: 0.0,
+                min,
+                max,
+                quantize: None,
+                when: None,
+            };
+            let mut matrix = ModMatrix::from_audio_routes(std::slice::from_mut(&mut audio));
+            matrix.routes.push(Route {
+                source: RouteSource::Modulator(Box::new(lfo)),
+                target: "v".into(),
+                min,
+                max,
+                quantize: None,
+                when: None,
+            });
+            let mut p = params_at(time);
+            p.set(AudioBand::Bass.key(), level);
+            matrix.modulate(&mut p);
+            for key in ["v", "w"] {
+                let v = p.get(key);
+                prop_assert!(min.min(max) <= v && v <= min.max(max), "{key}={v} outside {min}–{max}");
+            }
+        }
+    }
 }
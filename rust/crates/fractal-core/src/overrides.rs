@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    modulators::LFO_RETRIGGER_KEY,
+    patch::Patch,
+    presets::Preset,
+    versioning::{Format, Migration},
+    Params,
+};
+
+/// The overrides file; version 2 keys presets by [`Preset::id`].
+pub const FORMAT: Format = Format::new("overrides", &MIGRATIONS);
+
+const MIGRATIONS: [Migration; 1] = [v1_preset_ids];
+
+// ---------------------------------------------------------------------------
+// PresetOverride — the user's tweaks to one preset, stored as a diff
+// ---------------------------------------------------------------------------
+
+/// Values the user changed relative to a preset's factory defaults.
+///
+/// Only fields that differ are stored, so a later change to the factory
+/// preset still shows through for everything the user didn't touch.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PresetOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub center_x: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub center_y: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_iter: Option<u32>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, f32>,
+}
+
+impl PresetOverride {
+    /// Diff `current` against the `factory` patch's params, keeping only the
+    /// values that differ.  Per-frame state (`time`, `frame`, mouse, LFO
+    /// retrigger time) is never captured, nor is anything the patch's
+    /// modulators, routes or macros drive.
+    pub fn diff(factory: &Patch, current: &Params) -> Self {
+        let driven = factory.driven_keys();
+        let factory = &factory.params;
+        let changed = |f: f32, c: f32| (f != c).then_some(c);
+        let fields = current
+            .fields
+            .iter()
+            .filter(|(k, _)| k.as_str() != LFO_RETRIGGER_KEY && !driven.contains(*k))
+            .filter(|(k, v)| factory.fields.get(*k) != Some(*v))
+            .map(|(k, v)| (k.clone(), *v))
+            .collect();
+        Self {
+            center_x: changed(factory.center_x, current.center_x),
+            center_y: changed(factory.center_y, current.center_y),
+            zoom: changed(factory.zoom, current.zoom),
+            max_iter: (factory.max_iter != current.max_iter).then_some(current.max_iter),
+            fields,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Layer the stored values on top of `params`.
+    pub fn apply(&self, params: &mut Params) {
+        if let Some(v) = self.center_x {
+            params.center_x = v;
+        }
+        if let Some(v) = self.center_y {
+            params.center_y = v;
+        }
+        if let Some(v) = self.zoom {
+            params.zoom = v;
+        }
+        if let Some(v) = self.max_iter {
+            params.max_iter = v;
+        }
+        for (k, v) in &self.fields {
+            params.set(k.clone(), *v);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OverrideStore — all presets' overrides, persisted as JSON
+// ---------------------------------------------------------------------------
+
+/// Per-preset user overrides, keyed by [`Preset::id`] so retitling a
+/// preset keeps them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OverrideStore {
+    #[serde(default)]
+    pub presets: BTreeMap<String, PresetOverride>,
+}
+
+impl OverrideStore {
+    pub fn get(&self, preset: Preset) -> Option<&PresetOverride> {
+        self.presets.get(&preset.id())
+    }
+
+    /// Record how `current` differs from `preset`'s factory defaults.
+    /// Returns `false` (and drops any stale entry) if nothing differs.
+    pub fn capture(&mut self, preset: Preset, current: &Params) -> bool {
+        let diff = PresetOverride::diff(&preset.build(), current);
+        if diff.is_empty() {
+            self.presets.remove(&preset.id());
+            false
+        } else {
+            self.presets.insert(preset.id(), diff);
+            true
+        }
+    }
+
+    /// Forget the user's tweaks for `preset`.  Returns `true` if any existed.
+    pub fn clear(&mut self, preset: Preset) -> bool {
+        self.presets.remove(&preset.id()).is_some()
+    }
+
+    /// Build `preset` with the user's overrides (if any) applied.
+    pub fn build(&self, preset: Preset) -> Patch {
+        let mut patch = preset.build();
+        if let Some(ov) = self.get(preset) {
+            ov.apply(&mut patch.params);
+        }
+        patch
+    }
+
+    pub fn to_json(&self) -> String {
+        FORMAT.to_json(self)
+    }
+
+    /// Parse a store saved by this or any earlier format version.
+    pub fn from_json(src: &str) -> io::Result<Self> {
+        FORMAT.from_json(src)
+    }
+
+    /// Load from `path`; a missing file yields an empty store.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        FORMAT.load(path)
+    }
+
+    /// Write to `path`, creating parent directories as needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        FORMAT.save(path, self)
+    }
+}
+
+/// v1 → v2: presets are keyed by [`Preset::id`] instead of their display
+/// name.  Entries for names no preset has are kept as they were.
+fn v1_preset_ids(store: &mut Map<String, Value>) {
+    let Some(Value::Object(presets)) = store.get_mut("presets") else {
+        return;
+    };
+    *presets = std::mem::take(presets)
+        .into_iter()
+        .map(|(name, ov)| match Preset::from_name(&name) {
+            Some(p) => (p.id(), ov),
+            None => (name, ov),
+        })
+        .collect();
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_of_identical_params_is_empty() {
+        let patch = Preset::ClassicMandelbrot.build();
+        assert!(PresetOverride::diff(&patch, &patch.params).is_empty());
+    }
+
+    #[test]
+    fn diff_keeps_only_changed_values() {
+        let patch = Preset::PsychedelicJulia.build();
+        let mut current = patch.params.clone();
+        current.zoom = 8.0;
+        current.max_iter = 300;
+        current.set("julia_cx", -0.8);
+        current.time = 12.0; // never captured
+        current.set(LFO_RETRIGGER_KEY, 11.5); // nor is this
+        let ov = PresetOverride::diff(&patch, &current);
+        assert_eq!(ov.zoom, Some(8.0));
+        assert_eq!(ov.max_iter, Some(300));
+        assert_eq!(ov.center_x, None);
+        assert_eq!(ov.fields.len(), 1);
+        assert_eq!(ov.fields.get("julia_cx"), Some(&-0.8));
+    }
+
+    #[test]
+    fn diff_skips_what_the_lfo_drives() {
+        // Psychedelic Julia's LFO sweeps hue_shift_amount every frame.
+        let mut patch = Preset::PsychedelicJulia.build();
+        for _ in 0..30 {
+            patch.tick(1.0 / 30.0);
+        }
+        assert_ne!(patch.params.get("hue_shift_amount"), 0.0);
+        patch.params.set("julia_cy", 0.3);
+        let ov = PresetOverride::diff(&Preset::PsychedelicJulia.build(), &patch.params);
+        assert_eq!(ov.fields.keys().collect::<Vec<_>>(), ["julia_cy"]);
+    }
+
+    #[test]
+    fn capture_then_build_restores_tweaks() {
+        let mut store = OverrideStore::default();
+        let mut p = Preset::ClassicMandelbrot.build().params;
+        p.center_x = 0.3;
+        p.max_iter = 450;
+        assert!(store.capture(Preset::ClassicMandelbrot, &p));
+
+        let patch = store.build(Preset::ClassicMandelbrot);
+        assert_eq!(patch.params.center_x, 0.3);
+        assert_eq!(patch.params.max_iter, 450);
+        // Other presets are unaffected.
+        let other = store.build(Preset::SeahorseValley);
+        assert_eq!(other.params.max_iter, 300);
+    }
+
+    #[test]
+    fn capture_unchanged_drops_entry() {
+        let mut store = OverrideStore::default();
+        let mut p = Preset::ClassicMandelbrot.build().params;
+        p.zoom = 2.0;
+        store.capture(Preset::ClassicMandelbrot, &p);
+        let factory = Preset::ClassicMandelbrot.build().params;
+        assert!(!store.capture(Preset::ClassicMandelbrot, &factory));
+        assert!(store.get(Preset::ClassicMandelbrot).is_none());
+    }
+
+    #[test]
+    fn clear_restores_factory_defaults() {
+        let mut store = OverrideStore::default();
+        let mut p = Preset::ClassicMandelbrot.build().params;
+        p.zoom = 16.0;
+        store.capture(Preset::ClassicMandelbrot, &p);
+        assert!(store.clear(Preset::ClassicMandelbrot));
+        assert!(!store.clear(Preset::ClassicMandelbrot));
+        assert_eq!(store.build(Preset::ClassicMandelbrot).params.zoom, 1.0);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut store = OverrideStore::default();
+        let mut p = Preset::NoiseField.build().params;
+        p.set("noise_scale", 5.5);
+        store.capture(Preset::NoiseField, &p);
+        let back = OverrideStore::from_json(&store.to_json()).unwrap();
+        assert_eq!(back, store);
+    }
+
+    #[test]
+    fn unversioned_files_are_rekeyed_by_id() {
+        let name = Preset::SeahorseValley.name();
+        let v1 = format!(r#"{{ "presets": {{ "{name}": {{ "zoom": 9.0 }} }} }}"#);
+        let store = OverrideStore::from_json(&v1).unwrap();
+        assert_eq!(store.presets.keys().collect::<Vec<_>>(), ["SeahorseValley"]);
+        assert_eq!(store.build(Preset::SeahorseValley).params.zoom, 9.0);
+
+        let json: Value = serde_json::from_str(&store.to_json()).unwrap();
+        assert_eq!(json["version"], FORMAT.version());
+    }
+
+    #[test]
+    fn load_missing_file_is_empty() {
+        let path = std::env::temp_dir().join("fractal-overrides-does-not-exist.json");
+        assert_eq!(
+            OverrideStore::load(&path).unwrap(),
+            OverrideStore::default()
+        );
+    }
+
+    #[test]
+    fn save_then_load() {
+        let path = std::env::temp_dir()
+            .join(format!("fractal-overrides-{}", std::process::id()))
+            .join("overrides.json");
+        let mut store = OverrideStore::default();
+        let mut p = Preset::ShipStorm.build().params;
+        p.zoom = 64.0;
+        store.capture(Preset::ShipStorm, &p);
+        store.save(&path).unwrap();
+        assert_eq!(OverrideStore::load(&path).unwrap(), store);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
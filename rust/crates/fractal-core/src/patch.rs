@@ -1,45 +1,316 @@
-use crate::{Effect, Generator, Modulator, Params};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use crate::formula::Formula;
+use crate::macros::Macro;
+use crate::meta::PatchMeta;
+use crate::modulators::{AudioRoute, FlashTarget, Lfo, ModMatrix, Waveform, Wavetable};
+use crate::perturbation::{DeepKind, DeepPoint, DeepZoom, Family, Refresh};
+use crate::sanitize::{self, Repair};
+use crate::{CustomFormulaGen, Effect, Generator, GeneratorKind, Modulator, Params};
 
 pub struct Patch {
     pub generator: Box<dyn Generator>,
     pub effects: Vec<Box<dyn Effect>>,
-    pub modulators: Vec<Box<dyn Modulator>>,
+    /// Shared so a modulation thread can evaluate the same set (see
+    /// [`Patch::modulate`]).
+    pub modulators: Vec<Arc<dyn Modulator>>,
+    /// Audio-band routes the patch declares, plus any strobe flash in
+    /// progress.  Unlike `modulators` these are data, so bundles carry
+    /// them; they run last.
+    pub audio: ModMatrix,
+    /// Named controls fanned out to several params; they run after the
+    /// modulators and before the audio routes.
+    pub macros: Vec<Macro>,
+    /// Params a strobe hit ([`Patch::flash`]) spikes, and to what.
+    pub flash: Vec<FlashTarget>,
     pub params: Params,
     /// Snapshot of generator-relevant params from the last frame, used to
     /// decide whether the GPU generator pass can be skipped.
     pub last_gen_params: Option<Vec<(String, f32)>>,
+    /// Keys [`Patch::sanitize`] has had to repair since the patch loaded.
+    pub repaired: BTreeSet<String>,
+    /// High-precision centre, and the reference orbit of a Mandelbrot or
+    /// Julia, while the view is zoomed past f32 precision (see
+    /// [`Patch::update_deep`]).
+    pub deep: Option<DeepZoom>,
+    /// Title, author, description, tempo and tags; shown, never rendered.
+    pub meta: PatchMeta,
 }
 
 impl Patch {
+    /// Longest effect chain a patch may carry.  Each effect costs a
+    /// full-frame pass (the GPU layer fuses runs of pointwise ones), so a
+    /// chain past this is a mistake, not a look.
+    pub const MAX_EFFECTS: usize = 16;
+
     pub fn new(generator: Box<dyn Generator>, params: Params) -> Self {
         Self {
             generator,
             effects: Vec::new(),
             modulators: Vec::new(),
+            audio: ModMatrix { routes: Vec::new() },
+            macros: Vec::new(),
+            flash: Vec::new(),
             params,
             last_gen_params: None,
+            repaired: BTreeSet::new(),
+            deep: None,
+            meta: PatchMeta::default(),
         }
     }
 
+    /// Append `effect` to the chain.
+    ///
+    /// # Panics
+    ///
+    /// If the chain already holds [`MAX_EFFECTS`](Self::MAX_EFFECTS).
     pub fn add_effect(mut self, effect: Box<dyn Effect>) -> Self {
+        assert!(
+            self.effects.len() < Self::MAX_EFFECTS,
+            "a patch carries at most {} effects",
+            Self::MAX_EFFECTS
+        );
         self.effects.push(effect);
         self
     }
 
     pub fn add_modulator(mut self, modulator: Box<dyn Modulator>) -> Self {
-        self.modulators.push(modulator);
+        self.modulators.push(Arc::from(modulator));
+        self
+    }
+
+    /// Every LFO in `modulators`, with its index there.
+    pub fn lfos(&self) -> Vec<(usize, Lfo)> {
+        self.modulators
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| m.as_lfo().map(|lfo| (i, lfo.clone())))
+            .collect()
+    }
+
+    /// Replace the LFO at `index` in `modulators` with `lfo`, keeping the
+    /// old one's target.  Returns `false` (and changes nothing) when no LFO
+    /// sits there.  The set is shared by value, so an edit swaps in a new
+    /// modulator rather than changing one a worker may be reading.
+    pub fn set_lfo(&mut self, index: usize, lfo: Lfo) -> bool {
+        let Some(target) = self
+            .modulators
+            .get(index)
+            .and_then(|m| m.as_lfo())
+            .map(|old| old.target)
+        else {
+            return false;
+        };
+        self.modulators[index] = Arc::new(Lfo { target, ..lfo });
+        true
+    }
+
+    /// Swap in `formula` as the step of a custom formula generator.
+    /// Returns `false` (and changes nothing) when the generator isn't one.
+    /// The GPU pipeline is rebuilt from the new formula, so the next frame
+    /// is dispatched whatever the params say.
+    pub fn set_formula(&mut self, formula: Formula) -> bool {
+        if self.generator.formula().is_none() {
+            return false;
+        }
+        self.generator = Box::new(CustomFormulaGen { formula });
+        self.last_gen_params = None;
+        true
+    }
+
+    /// Play each of `tables` on the LFOs whose target is its key.
+    pub fn with_wavetables(mut self, tables: &BTreeMap<String, Wavetable>) -> Self {
+        for (i, lfo) in self.lfos() {
+            if let Some(table) = tables.get(lfo.target) {
+                let waveform = Waveform::Table(Arc::new(table.clone()));
+                self.set_lfo(i, Lfo { waveform, ..lfo });
+            }
+        }
+        self
+    }
+
+    /// The wavetable each table-driven LFO plays, by target.
+    pub fn wavetables(&self) -> BTreeMap<String, Wavetable> {
+        self.lfos()
+            .into_iter()
+            .filter_map(|(_, lfo)| match lfo.waveform {
+                Waveform::Table(table) => Some((lfo.target.to_string(), (*table).clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replace the patch's audio routes.
+    pub fn with_audio_routes(mut self, routes: &[AudioRoute]) -> Self {
+        self.audio = ModMatrix::from_audio_routes(routes);
         self
     }
 
-    /// Apply all modulators, advancing params by one frame.
+    /// Add `m`, starting it at `value` (0–1).
+    pub fn add_macro(mut self, m: Macro, value: f32) -> Self {
+        self.params.set(m.key(), value);
+        self.macros.push(m);
+        self
+    }
+
+    /// Replace the patch's macros.  Values already in `params` are kept;
+    /// new macros start at 0.
+    pub fn with_macros(mut self, macros: &[Macro]) -> Self {
+        for m in macros {
+            if !self.params.fields.contains_key(&m.key()) {
+                self.params.set(m.key(), 0.0);
+            }
+        }
+        self.macros = macros.to_vec();
+        self
+    }
+
+    /// Replace the params a strobe hit spikes.
+    pub fn with_flash(mut self, targets: &[FlashTarget]) -> Self {
+        self.flash = targets.to_vec();
+        self
+    }
+
+    /// Replace the patch's metadata.
+    pub fn with_meta(mut self, meta: PatchMeta) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    /// Hold the flash targets at their flash values for the next `frames`
+    /// frames.  Returns `false` when the patch declares no flash targets.
+    pub fn flash(&mut self, frames: u32) -> bool {
+        self.audio.flash(&self.flash, frames, &self.params);
+        !self.flash.is_empty()
+    }
+
+    /// Apply all modulators, advancing params by one frame.  The deep view
+    /// renders `params.max_iter` iterations and waits for any new reference
+    /// orbit; the app runs the steps itself to use its zoom-aware count.
     pub fn tick(&mut self, dt: f32) {
+        self.advance(dt);
+        self.modulate();
+        self.apply_controls();
+        self.sanitize();
+        self.update_deep(self.params.max_iter, Refresh::Blocking);
+    }
+
+    /// Advance the patch clock and frame count.  The first step of
+    /// [`tick`](Self::tick).
+    pub fn advance(&mut self, dt: f32) {
         self.params.time += dt;
         self.params.frame += 1;
+    }
+
+    /// Run the modulators, in order.  The second step of
+    /// [`tick`](Self::tick); a caller evaluating them elsewhere copies
+    /// their targets into `params` instead.
+    pub fn modulate(&mut self) {
         for m in &self.modulators {
             m.modulate(&mut self.params);
         }
     }
 
+    /// Fan out the macros, then run flashes and audio routes.  The third
+    /// step of [`tick`](Self::tick).
+    pub fn apply_controls(&mut self) {
+        for m in &self.macros {
+            m.apply(&mut self.params);
+        }
+        self.audio.expire_flashes(self.params.frame);
+        self.audio.modulate(&mut self.params);
+    }
+
+    /// Reset non-finite params and clamp registered ones into range (see
+    /// [`sanitize::sanitize`]).  The fourth step of [`tick`](Self::tick),
+    /// so uniforms are never built from a NaN.  Returns the repairs to keys
+    /// not repaired before; the rest are fixed silently.
+    pub fn sanitize(&mut self) -> Vec<Repair> {
+        let mut repairs = sanitize::sanitize(&mut self.params);
+        repairs.retain(|r| !self.repaired.contains(&r.key));
+        self.repaired.extend(repairs.iter().map(|r| r.key.clone()));
+        repairs
+    }
+
+    /// Start, follow or stop the deep-zoom view (see [`DeepZoom::update`])
+    /// for rendering `max_iter` iterations.  The last step of
+    /// [`tick`](Self::tick).
+    pub fn update_deep(&mut self, max_iter: u32, refresh: Refresh) {
+        let kind = match self.generator.kind() {
+            GeneratorKind::Mandelbrot => Some(DeepKind::Perturbed(Family::Mandelbrot)),
+            GeneratorKind::Julia => Some(DeepKind::Perturbed(Family::Julia([
+                self.params.get("julia_cx"),
+                self.params.get("julia_cy"),
+            ]))),
+            // The generators with f64 kernels; see `GeneratorPass::precise_for`.
+            GeneratorKind::BurningShip
+            | GeneratorKind::Celtic
+            | GeneratorKind::Buffalo
+            | GeneratorKind::Hybrid(_) => Some(DeepKind::Precise),
+            _ => None,
+        };
+        DeepZoom::update(&mut self.deep, kind, &mut self.params, max_iter, refresh);
+    }
+
+    /// Centre the view on `center` at full precision (see [`DeepZoom::at`]).
+    pub fn set_deep_center(&mut self, center: DeepPoint) {
+        self.deep = Some(DeepZoom::at(center, &mut self.params));
+    }
+
+    /// Move the view centre by `(dx, dy)` on the plane — at full precision
+    /// while zoomed deep, where the move may be too small for f32.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        match &mut self.deep {
+            Some(deep) => deep.pan(dx, dy, &mut self.params),
+            None => {
+                self.params.center_x += dx;
+                self.params.center_y += dy;
+            }
+        }
+    }
+
+    /// Keys modulators, audio routes or macros rewrite every frame, so
+    /// whatever value they hold was not set by hand.
+    pub fn driven_keys(&self) -> BTreeSet<String> {
+        self.modulators
+            .iter()
+            .flat_map(|m| m.targets())
+            .chain(self.audio.targets())
+            .chain(
+                self.macros
+                    .iter()
+                    .flat_map(|m| m.targets.iter().map(|t| t.key.clone())),
+            )
+            .collect()
+    }
+
+    /// Keys modulators, audio routes, macros or flashes write that neither
+    /// the generator nor any effect reads, sorted — usually a typo in a
+    /// target name.
+    pub fn unconsumed_targets(&self) -> Vec<String> {
+        let consumed: BTreeSet<&str> = self
+            .generator
+            .gen_param_keys()
+            .iter()
+            .copied()
+            .chain(self.effects.iter().flat_map(|e| e.param_keys()))
+            .collect();
+        let mut written = self.driven_keys();
+        written.extend(self.flash.iter().map(|t| t.key.clone()));
+        written
+            .into_iter()
+            .filter(|key| !consumed.contains(key.as_str()))
+            .collect()
+    }
+
+    /// Keys the audio routes feed back into (see
+    /// [`ModMatrix::feedback_keys`]) — a route chain that depends on its
+    /// own output, usually a mistake in a patch file.
+    pub fn feedback_keys(&self) -> Vec<String> {
+        self.audio.feedback_keys()
+    }
+
     /// Returns true if the generator-relevant params have changed since the
     /// last call — i.e. the GPU compute pass must be re-dispatched.
     pub fn generator_dirty(&mut self) -> bool {
@@ -58,6 +329,10 @@ impl Patch {
         ];
         let mut full: Vec<(String, f32)> = current;
         full.extend_from_slice(&structural);
+        // A formula that reads `t` changes every frame.
+        if self.generator.formula().is_some_and(Formula::uses_time) {
+            full.push(("time".to_string(), self.params.time));
+        }
 
         let dirty = self.last_gen_params.as_deref() != Some(&full);
         if dirty {
@@ -74,6 +349,7 @@ impl Patch {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modulators::Interpolation;
     use crate::{Effect, EffectKind, Generator, GeneratorKind};
 
     // --- Minimal stubs --------------------------------------------------------
@@ -95,6 +371,9 @@ mod tests {
         fn kind(&self, _: &Params) -> EffectKind {
             EffectKind::HueShift { amount: 0.0 }
         }
+        fn param_keys(&self) -> Vec<&'static str> {
+            vec!["hue"]
+        }
     }
 
     struct StubMod {
@@ -105,6 +384,20 @@ mod tests {
         fn modulate(&self, params: &mut Params) {
             params.set(self.key, self.value);
         }
+        fn targets(&self) -> Vec<String> {
+            vec![self.key.to_string()]
+        }
+    }
+
+    fn hue_lfo() -> Lfo {
+        Lfo {
+            target: "hue",
+            waveform: Waveform::Sine,
+            frequency: 0.5,
+            amplitude: 1.0,
+            offset: 0.0,
+            phase: 0.0,
+        }
     }
 
     fn make_patch() -> Patch {
@@ -141,6 +434,63 @@ mod tests {
         assert_eq!(patch.params.get("val"), 99.0);
     }
 
+    #[test]
+    fn tick_resets_nan_and_reports_each_key_once() {
+        let mut patch = make_patch().add_modulator(Box::new(StubMod {
+            key: "val",
+            value: f32::NAN,
+        }));
+        patch.tick(0.016);
+        assert_eq!(patch.params.get("val"), 0.0);
+        assert!(patch.repaired.contains("val"));
+        // Already reported: the next frame's repair is silent.
+        patch.params.set("val", f32::NAN);
+        assert!(patch.sanitize().is_empty());
+        assert_eq!(patch.params.get("val"), 0.0);
+    }
+
+    #[test]
+    fn deep_zoom_pans_below_f32_resolution() {
+        let mut patch = Patch::new(
+            Box::new(crate::MandelbrotGen),
+            Params {
+                zoom: 1e12,
+                ..Params::default()
+            },
+        );
+        patch.tick(0.016);
+        assert!(patch.deep.is_some());
+        patch.pan(1e-9, 0.0);
+        patch.pan(1e-9, 0.0);
+        assert_eq!(patch.params.center_x, -0.5, "f32 can't see the move");
+        let [dx, _] = patch.deep.as_ref().unwrap().offset();
+        assert!((dx - 2e-9).abs() < 1e-15);
+        patch.params.zoom = 1.0;
+        patch.tick(0.016);
+        assert!(patch.deep.is_none());
+        patch.pan(0.25, 0.0);
+        assert_eq!(patch.params.center_x, -0.25);
+    }
+
+    #[test]
+    fn restored_deep_centre_keeps_its_digits() {
+        let mut patch = Patch::new(
+            Box::new(crate::MandelbrotGen),
+            Params {
+                zoom: 1e12,
+                ..Params::default()
+            },
+        );
+        let center =
+            DeepPoint::parse("-0.743643887037158704752", "0.131825904205311970493").unwrap();
+        patch.set_deep_center(center);
+        assert_eq!(patch.params.center_x, -0.743_643_9);
+        patch.tick(0.016);
+        let deep = patch.deep.as_ref().unwrap();
+        assert_eq!(deep.center, center);
+        assert_eq!(deep.orbit.as_ref().unwrap().center, center);
+    }
+
     // --- generator_dirty ------------------------------------------------------
 
     #[test]
@@ -204,6 +554,30 @@ mod tests {
         assert!(patch.generator_dirty());
     }
 
+    #[test]
+    fn generator_dirty_follows_time_only_in_a_formula_that_reads_it() {
+        let mut patch = Patch::new(
+            Box::new(CustomFormulaGen {
+                formula: Formula::default(),
+            }),
+            Params::default(),
+        );
+        patch.generator_dirty();
+        patch.params.time += 1.0;
+        assert!(!patch.generator_dirty());
+        assert!(patch.set_formula(Formula::parse("z^2 + c + 0.01*t").unwrap()));
+        assert!(patch.generator_dirty(), "a new formula redraws");
+        patch.params.time += 1.0;
+        assert!(patch.generator_dirty());
+    }
+
+    #[test]
+    fn set_formula_needs_a_formula_generator() {
+        let mut patch = make_patch();
+        assert!(!patch.set_formula(Formula::default()));
+        assert!(patch.generator.formula().is_none());
+    }
+
     // --- add_effect / add_modulator -------------------------------------------
 
     #[test]
@@ -214,6 +588,19 @@ mod tests {
         assert_eq!(patch.effects.len(), 2);
     }
 
+    #[test]
+    fn add_effect_fills_up_to_the_maximum() {
+        let patch =
+            (0..Patch::MAX_EFFECTS).fold(make_patch(), |p, _| p.add_effect(Box::new(StubEffect)));
+        assert_eq!(patch.effects.len(), Patch::MAX_EFFECTS);
+    }
+
+    #[test]
+    #[should_panic(expected = "at most")]
+    fn add_effect_past_the_maximum_panics() {
+        (0..=Patch::MAX_EFFECTS).fold(make_patch(), |p, _| p.add_effect(Box::new(StubEffect)));
+    }
+
     #[test]
     fn add_modulator_appends() {
         let patch = make_patch()
@@ -227,4 +614,165 @@ mod tests {
             }));
         assert_eq!(patch.modulators.len(), 2);
     }
+
+    // --- macros ---------------------------------------------------------------
+
+    fn hue_macro() -> Macro {
+        use crate::macros::{Curve, MacroTarget};
+        Macro {
+            name: "mood".into(),
+            targets: vec![MacroTarget {
+                key: "hue".into(),
+                min: 0.0,
+                max: 10.0,
+                curve: Curve::Linear,
+            }],
+        }
+    }
+
+    #[test]
+    fn tick_applies_macros_over_modulators() {
+        let mut patch = make_patch()
+            .add_modulator(Box::new(StubMod {
+                key: "hue",
+                value: 99.0,
+            }))
+            .add_macro(hue_macro(), 0.3);
+        patch.tick(0.016);
+        assert!((patch.params.get("hue") - 3.0).abs() < 1e-6);
+        patch.params.set("macro_mood", 1.0);
+        patch.tick(0.016);
+        assert_eq!(patch.params.get("hue"), 10.0);
+    }
+
+    #[test]
+    fn flash_lands_on_top_of_macros() {
+        let mut patch = make_patch()
+            .add_macro(hue_macro(), 0.3)
+            .with_flash(&[FlashTarget {
+                key: "hue".into(),
+                value: 50.0,
+            }]);
+        assert!(patch.flash(1));
+        patch.tick(0.016);
+        assert_eq!(patch.params.get("hue"), 50.0);
+        patch.tick(0.016);
+        assert!((patch.params.get("hue") - 3.0).abs() < 1e-6);
+        assert!(!make_patch().flash(1));
+    }
+
+    #[test]
+    fn with_macros_keeps_existing_values() {
+        let mut params = Params::default();
+        params.set("macro_mood", 0.7);
+        let patch = Patch::new(Box::new(StubGen { keys: &[] }), params).with_macros(&[hue_macro()]);
+        assert_eq!(patch.params.get("macro_mood"), 0.7);
+        assert_eq!(patch.macros.len(), 1);
+    }
+
+    // --- unconsumed_targets ---------------------------------------------------
+
+    #[test]
+    fn targets_read_by_generator_or_effect_are_consumed() {
+        let patch = Patch::new(
+            Box::new(StubGen {
+                keys: &["julia_cx"],
+            }),
+            Params::default(),
+        )
+        .add_effect(Box::new(StubEffect))
+        .add_modulator(Box::new(StubMod {
+            key: "julia_cx",
+            value: 0.0,
+        }))
+        .add_modulator(Box::new(StubMod {
+            key: "hue",
+            value: 0.0,
+        }));
+        assert!(patch.unconsumed_targets().is_empty());
+    }
+
+    #[test]
+    fn set_lfo_swaps_in_an_edited_copy() {
+        let lfo = hue_lfo();
+        let mut patch = make_patch()
+            .add_modulator(Box::new(StubMod {
+                key: "zoom_speed",
+                value: 1.0,
+            }))
+            .add_modulator(Box::new(lfo.clone()));
+        assert_eq!(patch.lfos(), [(1, lfo.clone())]);
+        let before = Arc::clone(&patch.modulators[1]);
+
+        let edited = Lfo {
+            target: "julia_cx",
+            waveform: Waveform::Square,
+            frequency: 2.0,
+            ..lfo
+        };
+        assert!(patch.set_lfo(1, edited.clone()));
+        assert!(!Arc::ptr_eq(&before, &patch.modulators[1]));
+        let (_, now) = &patch.lfos()[0];
+        assert_eq!(now.target, "hue");
+        assert_eq!((&now.waveform, now.frequency), (&Waveform::Square, 2.0));
+
+        assert!(!patch.set_lfo(0, edited.clone()));
+        assert!(!patch.set_lfo(5, edited));
+    }
+
+    #[test]
+    fn wavetables_attach_by_target_and_read_back() {
+        let table = Wavetable {
+            points: vec![-1.0, 1.0, 0.0],
+            interpolation: Interpolation::Step,
+        };
+        let tables = BTreeMap::from([
+            ("hue".to_string(), table.clone()),
+            ("no_such_lfo".to_string(), table.clone()),
+        ]);
+        let patch = make_patch()
+            .add_modulator(Box::new(hue_lfo()))
+            .with_wavetables(&tables);
+        assert_eq!(
+            patch.wavetables(),
+            BTreeMap::from([("hue".to_string(), table)])
+        );
+        let mut params = Params {
+            time: 0.5,
+            ..Default::default()
+        };
+        for m in &patch.modulators {
+            m.modulate(&mut params);
+        }
+        // Half a second at 0.5 Hz is a quarter cycle: still the first point.
+        assert_eq!(params.get("hue"), -1.0);
+    }
+
+    #[test]
+    fn misspelled_targets_are_reported_once() {
+        use crate::{audio::AudioBand, modulators::AudioRoute};
+        let route = AudioRoute {
+            band: AudioBand::Bass,
+            target: "hue_ammount".into(),
+            smoothing: 0.0,
+            min: 0.0,
+            max: 1.0,
+            quantize: None,
+            when: None,
+        };
+        let patch = make_patch()
+            .add_effect(Box::new(StubEffect))
+            .add_modulator(Box::new(StubMod {
+                key: "zz_typo",
+                value: 0.0,
+            }))
+            .add_modulator(Box::new(StubMod {
+                key: "zz_typo",
+                value: 1.0,
+            }))
+            .with_audio_routes(&[route]);
+        assert_eq!(patch.unconsumed_targets(), ["hue_ammount", "zz_typo"]);
+        let patch = make_patch().add_macro(hue_macro(), 0.0);
+        assert_eq!(patch.unconsumed_targets(), ["hue"]);
+    }
 }
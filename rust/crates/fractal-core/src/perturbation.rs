@@ -0,0 +1,978 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
+
+use crate::Params;
+
+// ---------------------------------------------------------------------------
+// Perturbation deep zoom
+// ---------------------------------------------------------------------------
+//
+// Past a zoom of about 1e4 neighbouring pixels round to the same f32 point
+// and the image turns to blocks.  Perturbation sidesteps that: one
+// reference orbit Z_n is iterated on the CPU in high precision, and the GPU
+// iterates only each pixel's small difference from it,
+//
+//     δ_{n+1} = 2·Z_n·δ_n + δ_n² + δc
+//
+// which f32 holds accurately for as long as δ is representable — down to
+// zooms around 1e30.  Early on δ is a smooth function of the pixel, so a
+// cubic series in its offset stands in for the first iterations: pixels
+// start where the series stops being accurate instead of at 0.  The view centre is tracked to the same precision in
+// a [`DeepZoom`], since an f32 centre can't be panned at that depth; the
+// generators perturbation doesn't cover track only the centre, for the f64
+// kernels of devices that have them.
+
+/// Limbs of a [`BigFixed`]: one integer limb and five fractional ones,
+/// 160 bits — enough to place a pixel at any zoom an f32 can hold.
+pub const LIMBS: usize = 6;
+
+/// A signed fixed-point number with 32 integer and 160 fractional bits.
+/// `Copy` and heap-free, so a reference orbit costs no allocation per step.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BigFixed {
+    neg: bool,
+    /// Magnitude, least significant limb first; the last is the integer
+    /// part.
+    mag: [u32; LIMBS],
+}
+
+const INT: usize = LIMBS - 1;
+
+impl BigFixed {
+    pub const ZERO: BigFixed = BigFixed {
+        neg: false,
+        mag: [0; LIMBS],
+    };
+
+    /// `v`, exactly unless it has bits below 2⁻¹⁶⁰; the integer part
+    /// saturates at `u32::MAX`.
+    pub fn from_f64(v: f64) -> Self {
+        if !v.is_finite() {
+            return Self::ZERO;
+        }
+        let mut a = v.abs();
+        let mut mag = [0; LIMBS];
+        let int = a.floor().min(u32::MAX as f64);
+        mag[INT] = int as u32;
+        a -= int;
+        for limb in mag[..INT].iter_mut().rev() {
+            a *= 4_294_967_296.0;
+            let digit = a.floor();
+            *limb = digit as u32;
+            a -= digit;
+        }
+        Self::normalized(v < 0.0, mag)
+    }
+
+    /// The nearest f64.
+    pub fn to_f64(&self) -> f64 {
+        let mut v = 0.0;
+        for &limb in &self.mag[..INT] {
+            v = (v + limb as f64) / 4_294_967_296.0;
+        }
+        v += self.mag[INT] as f64;
+        if self.neg {
+            -v
+        } else {
+            v
+        }
+    }
+
+    fn normalized(neg: bool, mag: [u32; LIMBS]) -> Self {
+        Self {
+            neg: neg && mag != [0; LIMBS],
+            mag,
+        }
+    }
+
+    fn cmp_mag(a: &[u32; LIMBS], b: &[u32; LIMBS]) -> Ordering {
+        a.iter().rev().cmp(b.iter().rev())
+    }
+
+    fn add_mag(a: &[u32; LIMBS], b: &[u32; LIMBS]) -> [u32; LIMBS] {
+        let mut out = [0; LIMBS];
+        let mut carry = 0u64;
+        for i in 0..LIMBS {
+            let sum = a[i] as u64 + b[i] as u64 + carry;
+            out[i] = sum as u32;
+            carry = sum >> 32;
+        }
+        out
+    }
+
+    /// `a - b` for `a >= b`.
+    fn sub_mag(a: &[u32; LIMBS], b: &[u32; LIMBS]) -> [u32; LIMBS] {
+        let mut out = [0; LIMBS];
+        let mut borrow = 0i64;
+        for i in 0..LIMBS {
+            let diff = a[i] as i64 - b[i] as i64 - borrow;
+            out[i] = diff as u32;
+            borrow = (diff < 0) as i64;
+        }
+        out
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        if self.neg == other.neg {
+            return Self::normalized(self.neg, Self::add_mag(&self.mag, &other.mag));
+        }
+        match Self::cmp_mag(&self.mag, &other.mag) {
+            Ordering::Less => Self::normalized(other.neg, Self::sub_mag(&other.mag, &self.mag)),
+            _ => Self::normalized(self.neg, Self::sub_mag(&self.mag, &other.mag)),
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::normalized(!self.neg, self.mag)
+    }
+
+    /// Product, truncated to 160 fractional bits.
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut wide = [0u32; 2 * LIMBS];
+        for (i, &a) in self.mag.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.mag.iter().enumerate() {
+                let t = a as u64 * b as u64 + wide[i + j] as u64 + carry;
+                wide[i + j] = t as u32;
+                carry = t >> 32;
+            }
+            wide[i + LIMBS] = carry as u32;
+        }
+        let mut mag = [0; LIMBS];
+        mag.copy_from_slice(&wide[INT..INT + LIMBS]);
+        Self::normalized(self.neg != other.neg, mag)
+    }
+
+    /// Magnitude divided by a small `d`, remainder dropped.
+    fn div_small(mag: &mut [u32; LIMBS], d: u32) {
+        let mut rem = 0u64;
+        for limb in mag.iter_mut().rev() {
+            let cur = (rem << 32) | *limb as u64;
+            *limb = (cur / d as u64) as u32;
+            rem = cur % d as u64;
+        }
+    }
+
+    /// Magnitude times a small `m`, returning what overflowed the integer
+    /// limb.
+    fn mul_small(mag: &mut [u32; LIMBS], m: u32) -> u32 {
+        let mut carry = 0u64;
+        for limb in mag.iter_mut() {
+            let t = *limb as u64 * m as u64 + carry;
+            *limb = t as u32;
+            carry = t >> 32;
+        }
+        carry as u32
+    }
+}
+
+/// Error parsing a [`BigFixed`] from a decimal string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigFixedError(pub String);
+
+impl fmt::Display for ParseBigFixedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a decimal number: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseBigFixedError {}
+
+impl FromStr for BigFixed {
+    type Err = ParseBigFixedError;
+
+    /// A plain decimal such as `-0.743643887037158704752191506114774`;
+    /// digits past the 160-bit fraction are dropped.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseBigFixedError(s.to_string());
+        let (neg, digits) = match s.trim().strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.trim().strip_prefix('+').unwrap_or(s.trim())),
+        };
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (int.is_empty() && frac.is_empty()) || !is_digits(int) || !is_digits(frac) {
+            return Err(err());
+        }
+        let mut mag = [0; LIMBS];
+        // Fold the fraction in from its last digit: x = (x + d) / 10.
+        for d in frac.bytes().rev() {
+            mag[INT] += (d - b'0') as u32;
+            Self::div_small(&mut mag, 10);
+        }
+        let mut int_mag = [0; LIMBS];
+        for d in int.bytes() {
+            if Self::mul_small(&mut int_mag, 10) != 0 {
+                return Err(err());
+            }
+            int_mag[INT] = int_mag[INT]
+                .checked_add((d - b'0') as u32)
+                .ok_or_else(err)?;
+        }
+        Ok(Self::normalized(neg, Self::add_mag(&mag, &int_mag)))
+    }
+}
+
+/// Fractional digits [`BigFixed`] prints: a few short of the 48 its 160
+/// bits resolve, so a parsed decimal prints back as written.
+const DISPLAY_DIGITS: usize = 45;
+
+impl fmt::Display for BigFixed {
+    /// Plain decimal rounded to 45 fractional digits, trailing zeros
+    /// trimmed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Half a unit in the last printed digit.
+        let mut half = [0; LIMBS];
+        half[INT] = 5;
+        for _ in 0..=DISPLAY_DIGITS {
+            Self::div_small(&mut half, 10);
+        }
+        let mut frac = Self::add_mag(&self.mag, &half);
+        let int = frac[INT];
+        frac[INT] = 0;
+        let mut digits = String::new();
+        for _ in 0..DISPLAY_DIGITS {
+            // Times ten, the next digit lands in the integer limb.
+            Self::mul_small(&mut frac, 10);
+            digits.push(char::from(b'0' + frac[INT] as u8));
+            frac[INT] = 0;
+        }
+        let sign = if self.neg { "-" } else { "" };
+        let digits = digits.trim_end_matches('0');
+        if digits.is_empty() {
+            write!(f, "{sign}{int}")
+        } else {
+            write!(f, "{sign}{int}.{digits}")
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DeepPoint — a point of the complex plane to 160 bits
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeepPoint {
+    pub re: BigFixed,
+    pub im: BigFixed,
+}
+
+impl DeepPoint {
+    pub fn from_f64(re: f64, im: f64) -> Self {
+        Self {
+            re: BigFixed::from_f64(re),
+            im: BigFixed::from_f64(im),
+        }
+    }
+
+    /// Two decimal strings, e.g. a location copied from another renderer.
+    pub fn parse(re: &str, im: &str) -> Result<Self, ParseBigFixedError> {
+        Ok(Self {
+            re: re.parse()?,
+            im: im.parse()?,
+        })
+    }
+
+    /// Move by `(dx, dy)`, added at full precision however small.
+    pub fn shift(&mut self, dx: f64, dy: f64) {
+        self.re = self.re.add(&BigFixed::from_f64(dx));
+        self.im = self.im.add(&BigFixed::from_f64(dy));
+    }
+
+    /// `self - other`, rounded to f64 — exact enough while the two are
+    /// close, which is all perturbation needs.
+    pub fn offset_from(&self, other: &Self) -> [f64; 2] {
+        [
+            self.re.sub(&other.re).to_f64(),
+            self.im.sub(&other.im).to_f64(),
+        ]
+    }
+
+    pub fn to_f32(&self) -> [f32; 2] {
+        [self.re.to_f64() as f32, self.im.to_f64() as f32]
+    }
+
+    pub fn to_f64(&self) -> [f64; 2] {
+        [self.re.to_f64(), self.im.to_f64()]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ReferenceOrbit — the high-precision orbit pixels are perturbed from
+// ---------------------------------------------------------------------------
+
+/// Escape radius² of the reference orbit; matches the shaders' bailout.
+pub const ESCAPE_RADIUS_SQ: f64 = 4.0;
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// What a reference orbit iterates: z² + c from 0 with c the point, or
+/// from the point with a fixed c.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Family {
+    Mandelbrot,
+    Julia([f32; 2]),
+}
+
+/// How a generator renders past [`DeepZoom::THRESHOLD`]: perturbed from a
+/// reference orbit, or iterated directly in f64 from the precise centre.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeepKind {
+    Perturbed(Family),
+    Precise,
+}
+
+/// Where [`DeepZoom::update`] computes a reference orbit that only has to
+/// catch up with a moving Julia `c`.  Any other new orbit — entering the
+/// deep view, drifting off the reference, a new family or iteration count —
+/// is always computed on the spot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refresh {
+    /// On the calling thread, so offline renders come out the same every
+    /// run.
+    Blocking,
+    /// On a worker thread, while the old orbit keeps rendering; for the
+    /// live view, where a full orbit would stall the frame.
+    Background,
+}
+
+/// How far the cubic term, the last the series keeps, may grow against the
+/// linear one before the series is no longer trusted.  What it drops is
+/// then around this squared, far below f32's precision.
+pub const SERIES_TOLERANCE: f64 = 1e-6;
+
+/// Z_0 … Z_n of one point iterated at [`BigFixed`] precision, rounded to
+/// f32 for the GPU.  Ends at `max_iter` steps or once the orbit escapes
+/// (but never before Z_1);
+/// pixels that outlive it rebase onto its start (see `perturbation.wgsl`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceOrbit {
+    /// Where the orbit starts: c for the Mandelbrot set, z_0 for a Julia.
+    pub center: DeepPoint,
+    pub family: Family,
+    pub max_iter: u32,
+    pub points: Vec<[f32; 2]>,
+    /// `[A_n, B_n, C_n]` for each point, with `δ_n ≈ A_n·d + B_n·d² +
+    /// C_n·d³` for a pixel `d` from the orbit's start (see
+    /// [`series_skip`](Self::series_skip)).
+    pub series: Vec<[[f64; 2]; 3]>,
+    /// Unique per computed orbit, so the GPU copy is refreshed only when
+    /// it changes.
+    pub generation: u64,
+}
+
+impl ReferenceOrbit {
+    /// Longest orbit kept, in points; the GPU orbit buffer holds this many.
+    pub const MAX_POINTS: usize = 1 << 17;
+
+    pub fn compute(center: DeepPoint, family: Family, max_iter: u32) -> Self {
+        let (mut z, c) = match family {
+            Family::Julia([x, y]) => (center, DeepPoint::from_f64(x as f64, y as f64)),
+            Family::Mandelbrot => (DeepPoint::default(), center),
+        };
+        // A Mandelbrot pixel's d is its δc, and δ_0 = 0; a Julia's is δ_0.
+        let (mut abc, dc) = match family {
+            Family::Mandelbrot => ([[0.0; 2]; 3], [1.0, 0.0]),
+            Family::Julia(_) => ([[1.0, 0.0], [0.0; 2], [0.0; 2]], [0.0; 2]),
+        };
+        // At least Z_0 and Z_1, so a pixel always has a step to take.
+        let len = (max_iter as usize + 1).clamp(2, Self::MAX_POINTS);
+        let mut points = Vec::with_capacity(len);
+        let mut series = Vec::with_capacity(len);
+        points.push(z.to_f32());
+        series.push(abc);
+        while points.len() < len {
+            let [x, y] = [z.re.to_f64(), z.im.to_f64()];
+            if points.len() > 1 && x * x + y * y > ESCAPE_RADIUS_SQ {
+                break;
+            }
+            // Matching powers of d in δ_{n+1} = 2·Z_n·δ_n + δ_n² + δc.
+            let [sa, sb, sc] = abc;
+            let two_z = [2.0 * x, 2.0 * y];
+            abc = [
+                cadd(cmul(two_z, sa), dc),
+                cadd(cmul(two_z, sb), cmul(sa, sa)),
+                cadd(cmul(two_z, sc), cmul([2.0 * sa[0], 2.0 * sa[1]], sb)),
+            ];
+            series.push(abc);
+            // z² + c, with re = (x + y)(x − y) and im = 2xy.
+            let re = z.re.add(&z.im).mul(&z.re.sub(&z.im));
+            let xy = z.re.mul(&z.im);
+            z = DeepPoint {
+                re: re.add(&c.re),
+                im: xy.add(&xy).add(&c.im),
+            };
+            points.push(z.to_f32());
+        }
+        Self {
+            center,
+            family,
+            max_iter,
+            points,
+            series,
+            generation: GENERATION.fetch_add(1, AtomicOrdering::Relaxed),
+        }
+    }
+
+    /// The furthest iteration, up to `max_iter`, that pixels within
+    /// `radius` of the orbit's start can jump to by the series: the last
+    /// before its cubic term outgrows [`SERIES_TOLERANCE`] of the linear
+    /// one at that radius.  `None` when no step can be skipped.
+    pub fn series_skip(&self, radius: f64, max_iter: u32) -> Option<SeriesSkip> {
+        // A pixel needs a next point to step to after the jump.
+        let last = (self.series.len().saturating_sub(2)).min(max_iter as usize);
+        let r2 = radius * radius;
+        let n = self.series[..=last]
+            .iter()
+            .skip(1)
+            .take_while(|[a, _, c]| {
+                let (a, c) = (a[0].hypot(a[1]), c[0].hypot(c[1]));
+                a.is_finite() && c.is_finite() && c * r2 <= SERIES_TOLERANCE * a
+            })
+            .count();
+        (n > 0).then(|| SeriesSkip {
+            n: n as u32,
+            coefficients: self.series[n],
+        })
+    }
+
+    /// `family`'s `c` relative to the one this orbit iterated with; zero
+    /// unless both are Julias.  The shader adds it to every step, so the
+    /// orbit stays a valid reference while `c` moves.
+    pub fn c_offset(&self, family: Family) -> [f32; 2] {
+        match (self.family, family) {
+            (Family::Julia([x0, y0]), Family::Julia([x, y])) => [x - x0, y - y0],
+            _ => [0.0; 2],
+        }
+    }
+}
+
+/// Where [`ReferenceOrbit::series_skip`] lets pixels start: iteration `n`,
+/// with `δ_n` from `[A_n, B_n, C_n]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeriesSkip {
+    pub n: u32,
+    pub coefficients: [[f64; 2]; 3],
+}
+
+fn cmul(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0] * b[0] - a[1] * b[1], a[0] * b[1] + a[1] * b[0]]
+}
+
+fn cadd(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+/// A reference orbit computed on a worker thread; empty until it is done.
+#[derive(Debug, Clone)]
+struct Pending(Arc<Mutex<Option<ReferenceOrbit>>>);
+
+impl Pending {
+    fn spawn(center: DeepPoint, family: Family, max_iter: u32) -> Self {
+        let slot = Arc::new(Mutex::new(None));
+        let done = Arc::clone(&slot);
+        std::thread::Builder::new()
+            .name("reference-orbit".into())
+            .spawn(move || {
+                let orbit = ReferenceOrbit::compute(center, family, max_iter);
+                *done.lock().unwrap_or_else(|e| e.into_inner()) = Some(orbit);
+            })
+            .expect("failed to spawn the reference orbit thread");
+        Self(slot)
+    }
+
+    fn take(&self) -> Option<ReferenceOrbit> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DeepZoom — a patch's high-precision view
+// ---------------------------------------------------------------------------
+
+/// A view past [`DeepZoom::THRESHOLD`]: its centre at full precision and,
+/// for a [`DeepKind::Perturbed`] one, the reference orbit it renders from.
+/// The patch's `center_x` / `center_y` keep the centre rounded to f32 for
+/// everything else that reads them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeepZoom {
+    pub center: DeepPoint,
+    pub orbit: Option<ReferenceOrbit>,
+    /// The family the view renders now; a Julia's `c` may have moved on
+    /// from the orbit's (see [`c_offset`](Self::c_offset)).
+    pub family: Option<Family>,
+    /// The orbit that will replace `orbit`, still being computed.
+    pending: Option<Pending>,
+    /// The rounded centre last written to params; a different value there
+    /// means something else moved the view (a view code, a bundle), and
+    /// the deep centre follows it.
+    synced: [f32; 2],
+}
+
+impl DeepZoom {
+    /// Zoom from which Mandelbrot and Julia render by perturbation, and
+    /// the f64 kernels take over where there are any.
+    pub const THRESHOLD: f32 = 1e4;
+
+    /// How far the view may drift from the reference, in view heights,
+    /// before a new reference is computed at the centre.
+    pub const MAX_DRIFT: f64 = 2.0;
+
+    /// Bring `slot` in line with `params`: start tracking when a view of
+    /// `kind` passes the threshold, drop it below, and recompute the
+    /// reference when the view has drifted from it or the family /
+    /// `max_iter` changed.  A Julia's `c` may move as far as the view may
+    /// drift before its orbit is recomputed, as `refresh` says.  `kind` is
+    /// `None` for generators that can't go deep at all.
+    pub fn update(
+        slot: &mut Option<Self>,
+        kind: Option<DeepKind>,
+        params: &mut Params,
+        max_iter: u32,
+        refresh: Refresh,
+    ) {
+        let Some(kind) = kind.filter(|_| params.zoom >= Self::THRESHOLD) else {
+            *slot = None;
+            return;
+        };
+        let family = match kind {
+            DeepKind::Perturbed(family) => Some(family),
+            DeepKind::Precise => None,
+        };
+        let rounded = [params.center_x, params.center_y];
+        let deep = slot.get_or_insert_with(|| Self {
+            center: DeepPoint::from_f64(rounded[0] as f64, rounded[1] as f64),
+            orbit: None,
+            family,
+            pending: None,
+            synced: rounded,
+        });
+        deep.family = family;
+        if rounded != deep.synced {
+            deep.center = DeepPoint::from_f64(rounded[0] as f64, rounded[1] as f64);
+        }
+        if let Some(orbit) = deep.pending.as_ref().and_then(Pending::take) {
+            deep.orbit = Some(orbit);
+            deep.pending = None;
+        }
+        // One view height spans 2 / zoom.
+        let in_views = |[dx, dy]: [f32; 2]| dx.hypot(dy) as f64 * params.zoom as f64 / 2.0;
+        let (stale, c_moved) = match (&deep.orbit, family) {
+            (Some(orbit), Some(family)) => {
+                let same_kind =
+                    std::mem::discriminant(&orbit.family) == std::mem::discriminant(&family);
+                (
+                    in_views(deep.offset()) > Self::MAX_DRIFT
+                        || !same_kind
+                        || orbit.max_iter != max_iter,
+                    in_views(orbit.c_offset(family)) > Self::MAX_DRIFT,
+                )
+            }
+            (None, None) => (false, false),
+            _ => (true, false),
+        };
+        if stale {
+            deep.orbit =
+                family.map(|family| ReferenceOrbit::compute(deep.center, family, max_iter));
+            deep.pending = None;
+        } else if let (true, Some(family)) = (c_moved, family) {
+            match refresh {
+                Refresh::Blocking => {
+                    deep.orbit = Some(ReferenceOrbit::compute(deep.center, family, max_iter));
+                }
+                Refresh::Background if deep.pending.is_none() => {
+                    deep.pending = Some(Pending::spawn(deep.center, family, max_iter));
+                }
+                Refresh::Background => {}
+            }
+        }
+        deep.sync(params);
+    }
+
+    /// Track a view centred on `center`, e.g. one restored from a save,
+    /// and round it into `params`.  The next [`update`](Self::update)
+    /// computes the reference orbit, or drops the view if it isn't deep.
+    pub fn at(center: DeepPoint, params: &mut Params) -> Self {
+        let mut deep = Self {
+            center,
+            orbit: None,
+            family: None,
+            pending: None,
+            synced: [0.0; 2],
+        };
+        deep.sync(params);
+        deep
+    }
+
+    /// Move the centre by `(dx, dy)` at full precision and round it into
+    /// `params`.
+    pub fn pan(&mut self, dx: f32, dy: f32, params: &mut Params) {
+        self.center.shift(dx as f64, dy as f64);
+        self.sync(params);
+    }
+
+    /// The view centre relative to the reference orbit's start; zero
+    /// without one.
+    pub fn offset(&self) -> [f32; 2] {
+        let Some(orbit) = &self.orbit else {
+            return [0.0; 2];
+        };
+        let [x, y] = self.center.offset_from(&orbit.center);
+        [x as f32, y as f32]
+    }
+
+    /// The current Julia `c` relative to the reference orbit's (see
+    /// [`ReferenceOrbit::c_offset`]).
+    pub fn c_offset(&self) -> [f32; 2] {
+        match (&self.orbit, self.family) {
+            (Some(orbit), Some(family)) => orbit.c_offset(family),
+            _ => [0.0; 2],
+        }
+    }
+
+    fn sync(&mut self, params: &mut Params) {
+        self.synced = self.center.to_f32();
+        [params.center_x, params.center_y] = self.synced;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigFixed {
+        s.parse().unwrap()
+    }
+
+    // --- BigFixed -----------------------------------------------------------
+
+    #[test]
+    fn big_fixed_round_trips_f64_and_decimal() {
+        for v in [0.0, 1.5, -0.75, -1.999_999_999_999, 1e-20, 3.0e9] {
+            assert_eq!(BigFixed::from_f64(v).to_f64(), v, "{v}");
+        }
+        let s = "-0.743643887037158704752191506114774";
+        assert_eq!(big(s).to_string(), s);
+        assert_eq!(big("+2").to_string(), "2");
+        assert!("1.2.3".parse::<BigFixed>().is_err());
+        assert!("".parse::<BigFixed>().is_err());
+    }
+
+    #[test]
+    fn big_fixed_arithmetic_keeps_digits_f64_loses() {
+        let a = big("0.1000000000000000000000000000001");
+        let b = big("0.1");
+        assert_eq!(a.sub(&b).to_string(), "0.0000000000000000000000000000001");
+        assert_eq!(b.sub(&a).to_string(), "-0.0000000000000000000000000000001");
+        assert_eq!(big("-1.5").mul(&big("2.25")).to_string(), "-3.375");
+        assert_eq!(big("-1.5").add(&big("1.5")), BigFixed::ZERO);
+    }
+
+    // --- ReferenceOrbit -----------------------------------------------------
+
+    #[test]
+    fn reference_orbit_matches_f64_iteration_and_stops_on_escape() {
+        let c = DeepPoint::from_f64(-0.75, 0.1);
+        let orbit = ReferenceOrbit::compute(c, Family::Mandelbrot, 50);
+        let (mut x, mut y) = (0.0f64, 0.0f64);
+        for p in &orbit.points[..10] {
+            assert!((p[0] as f64 - x).abs() < 1e-6 && (p[1] as f64 - y).abs() < 1e-6);
+            (x, y) = (x * x - y * y - 0.75, 2.0 * x * y + 0.1);
+        }
+        let outside =
+            ReferenceOrbit::compute(DeepPoint::from_f64(1.0, 1.0), Family::Mandelbrot, 50);
+        assert_eq!(outside.points.len(), 3, "0 → 1+i → 1+3i escapes");
+        assert_ne!(orbit.generation, outside.generation);
+        let julia = ReferenceOrbit::compute(c, Family::Julia([0.0, 0.0]), 4);
+        assert_eq!(julia.points.len(), 5);
+        assert_eq!(julia.points[0], [-0.75, 0.1]);
+        let escaped = DeepPoint::from_f64(3.0, 0.0);
+        let short = ReferenceOrbit::compute(escaped, Family::Julia([0.0, 0.0]), 50);
+        assert_eq!(
+            short.points,
+            [[3.0, 0.0], [9.0, 0.0]],
+            "always takes a step"
+        );
+    }
+
+    /// δ_n of a pixel `d` from the orbit's start, iterated directly in f64
+    /// next to the reference.
+    fn direct_delta(start: [f64; 2], family: Family, d: [f64; 2], n: u32) -> [f64; 2] {
+        let (mut z, mut w, c, cw) = match family {
+            Family::Mandelbrot => ([0.0; 2], [0.0; 2], start, cadd(start, d)),
+            Family::Julia([x, y]) => (
+                start,
+                cadd(start, d),
+                [x as f64, y as f64],
+                [x as f64, y as f64],
+            ),
+        };
+        for _ in 0..n {
+            z = cadd(cmul(z, z), c);
+            w = cadd(cmul(w, w), cw);
+        }
+        [w[0] - z[0], w[1] - z[1]]
+    }
+
+    #[test]
+    fn series_skip_predicts_the_delta_it_jumps_to() {
+        let d = [3e-7, -4e-7];
+        for family in [Family::Mandelbrot, Family::Julia([-0.4, 0.6])] {
+            let start = [-0.75, 0.1];
+            let orbit = ReferenceOrbit::compute(DeepPoint::from_f64(-0.75, 0.1), family, 500);
+            let skip = orbit.series_skip(5e-7, 500).expect("some steps to skip");
+            assert!(skip.n > 5, "{family:?} skips only {}", skip.n);
+            let [a, b, c] = skip.coefficients;
+            let d2 = cmul(d, d);
+            let series = cadd(cadd(cmul(a, d), cmul(b, d2)), cmul(c, cmul(d2, d)));
+            let direct = direct_delta(start, family, d, skip.n);
+            let err = (series[0] - direct[0]).hypot(series[1] - direct[1]);
+            let size = direct[0].hypot(direct[1]);
+            assert!(
+                err <= size * 1e-5,
+                "{family:?} at {}: {series:?} vs {direct:?}",
+                skip.n
+            );
+        }
+    }
+
+    #[test]
+    fn series_skip_shrinks_with_the_radius_and_stays_in_the_orbit() {
+        let orbit =
+            ReferenceOrbit::compute(DeepPoint::from_f64(-0.75, 0.1), Family::Mandelbrot, 1000);
+        assert_eq!(orbit.series.len(), orbit.points.len());
+        let near = orbit.series_skip(1e-12, 1000).unwrap().n;
+        let far = orbit.series_skip(1e-4, 1000).map_or(0, |s| s.n);
+        assert!(near > far, "{near} <= {far}");
+        assert!((near as usize) < orbit.points.len() - 1);
+        assert_eq!(
+            orbit.series_skip(1e-12, 3).unwrap().n,
+            3,
+            "capped by max_iter"
+        );
+        // The series is exact for the first two steps, at any radius.
+        assert_eq!(orbit.series_skip(1.0, 1000).unwrap().n, 2);
+        assert_eq!(orbit.series_skip(1e-12, 0), None);
+    }
+
+    // --- DeepZoom -----------------------------------------------------------
+
+    #[test]
+    fn deep_zoom_tracks_only_past_the_threshold() {
+        let mut p = Params {
+            zoom: 10.0,
+            ..Params::default()
+        };
+        let mut deep = None;
+        DeepZoom::update(
+            &mut deep,
+            Some(DeepKind::Perturbed(Family::Mandelbrot)),
+            &mut p,
+            100,
+            Refresh::Blocking,
+        );
+        assert!(deep.is_none());
+        p.zoom = 1e6;
+        DeepZoom::update(&mut deep, None, &mut p, 100, Refresh::Blocking);
+        assert!(deep.is_none(), "not a deep-zoom family");
+        DeepZoom::update(
+            &mut deep,
+            Some(DeepKind::Perturbed(Family::Mandelbrot)),
+            &mut p,
+            100,
+            Refresh::Blocking,
+        );
+        assert_eq!(deep.unwrap().orbit.unwrap().points.len(), 101);
+    }
+
+    #[test]
+    fn deep_pan_moves_below_f32_resolution() {
+        let mut p = Params {
+            zoom: 1e20,
+            center_x: -0.75,
+            center_y: 0.1,
+            ..Params::default()
+        };
+        let mut slot = None;
+        DeepZoom::update(
+            &mut slot,
+            Some(DeepKind::Perturbed(Family::Mandelbrot)),
+            &mut p,
+            100,
+            Refresh::Blocking,
+        );
+        let deep = slot.as_mut().unwrap();
+        for _ in 0..1000 {
+            deep.pan(1e-18, 0.0, &mut p);
+        }
+        assert_eq!(p.center_x, -0.75, "f32 can't see the move");
+        let [dx, _] = deep.offset();
+        assert!((dx - 1e-15).abs() < 1e-20, "{dx}");
+        let generation = deep.orbit.as_ref().unwrap().generation;
+        DeepZoom::update(
+            &mut slot,
+            Some(DeepKind::Perturbed(Family::Mandelbrot)),
+            &mut p,
+            100,
+            Refresh::Blocking,
+        );
+        let deep = slot.as_ref().unwrap();
+        assert_ne!(
+            deep.orbit.as_ref().unwrap().generation,
+            generation,
+            "drifted off the reference"
+        );
+        assert_eq!(deep.offset(), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn moving_params_elsewhere_reseeds_the_deep_centre() {
+        let mut p = Params {
+            zoom: 1e6,
+            ..Params::default()
+        };
+        let mut slot = None;
+        DeepZoom::update(
+            &mut slot,
+            Some(DeepKind::Perturbed(Family::Julia([0.3, 0.5]))),
+            &mut p,
+            100,
+            Refresh::Blocking,
+        );
+        p.center_x = 0.25;
+        DeepZoom::update(
+            &mut slot,
+            Some(DeepKind::Perturbed(Family::Julia([0.3, 0.5]))),
+            &mut p,
+            100,
+            Refresh::Blocking,
+        );
+        let deep = slot.unwrap();
+        assert_eq!(deep.center.to_f32(), [0.25, 0.0]);
+        let orbit = deep.orbit.unwrap();
+        assert_eq!(orbit.family, Family::Julia([0.3, 0.5]));
+        assert_eq!(orbit.points[0], [0.25, 0.0]);
+    }
+
+    #[test]
+    fn a_moving_julia_c_keeps_its_orbit_until_it_leaves_the_view() {
+        let mut p = Params {
+            zoom: 1e6,
+            ..Params::default()
+        };
+        let julia = |c| Some(DeepKind::Perturbed(Family::Julia(c)));
+        let mut slot = None;
+        DeepZoom::update(&mut slot, julia([0.3, 0.5]), &mut p, 100, Refresh::Blocking);
+        let generation = slot.as_ref().unwrap().orbit.as_ref().unwrap().generation;
+
+        // Half a view height: rendered from the same orbit, offset by δc.
+        DeepZoom::update(
+            &mut slot,
+            julia([0.3, 0.500_001]),
+            &mut p,
+            100,
+            Refresh::Blocking,
+        );
+        let deep = slot.as_ref().unwrap();
+        assert_eq!(deep.orbit.as_ref().unwrap().generation, generation);
+        let [dx, dy] = deep.c_offset();
+        assert_eq!(dx, 0.0);
+        assert!((dy - 1e-6).abs() < 1e-7, "{dy}");
+
+        // Further, a blocking update recomputes at once …
+        DeepZoom::update(
+            &mut slot,
+            julia([0.3, 0.51]),
+            &mut p,
+            100,
+            Refresh::Blocking,
+        );
+        let deep = slot.as_ref().unwrap();
+        assert_eq!(
+            deep.orbit.as_ref().unwrap().family,
+            Family::Julia([0.3, 0.51])
+        );
+        assert_eq!(deep.c_offset(), [0.0; 2]);
+
+        // … and a background one keeps the old orbit until the new is done.
+        DeepZoom::update(
+            &mut slot,
+            julia([0.3, 0.52]),
+            &mut p,
+            100,
+            Refresh::Background,
+        );
+        let deep = slot.as_ref().unwrap();
+        assert_eq!(
+            deep.orbit.as_ref().unwrap().family,
+            Family::Julia([0.3, 0.51])
+        );
+        let started = std::time::Instant::now();
+        loop {
+            DeepZoom::update(
+                &mut slot,
+                julia([0.3, 0.52]),
+                &mut p,
+                100,
+                Refresh::Background,
+            );
+            let orbit = slot.as_ref().unwrap().orbit.as_ref().unwrap();
+            if orbit.family == Family::Julia([0.3, 0.52]) {
+                break;
+            }
+            assert!(
+                started.elapsed().as_secs() < 10,
+                "the new orbit never arrived"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(slot.unwrap().c_offset(), [0.0; 2]);
+    }
+
+    #[test]
+    fn precise_views_track_the_centre_without_an_orbit() {
+        let mut p = Params {
+            zoom: 1e12,
+            center_x: -1.75,
+            ..Params::default()
+        };
+        let mut slot = None;
+        let perturbed = Some(DeepKind::Perturbed(Family::Mandelbrot));
+        DeepZoom::update(&mut slot, perturbed, &mut p, 100, Refresh::Blocking);
+        DeepZoom::update(
+            &mut slot,
+            Some(DeepKind::Precise),
+            &mut p,
+            100,
+            Refresh::Blocking,
+        );
+        let deep = slot.as_mut().unwrap();
+        assert!(deep.orbit.is_none(), "switched to an f64 kernel");
+        deep.pan(1e-10, 0.0, &mut p);
+        assert_eq!(p.center_x, -1.75, "f32 rounds the move away");
+        assert_eq!(deep.center.to_f64()[0], -1.75 + 1e-10);
+        assert_eq!(deep.offset(), [0.0, 0.0]);
+        DeepZoom::update(&mut slot, perturbed, &mut p, 100, Refresh::Blocking);
+        assert_eq!(
+            slot.unwrap().orbit.unwrap().center.to_f64()[0],
+            -1.75 + 1e-10
+        );
+    }
+}
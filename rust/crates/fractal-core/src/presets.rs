@@ -1,29 +1,101 @@
 use std::f32::consts::TAU;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    modulators::{Lfo, ModMatrix, Route, Waveform},
+    coloring::Coloring,
+    flame::FlameGen,
+    formula::Formula,
+    macros::{Curve, Macro, MacroTarget},
+    meta::PatchMeta,
+    modulators::{
+        FlashTarget, JuliaPath, JuliaPathShape, Lfo, ModMatrix, Route, RouteSource, Waveform,
+    },
     patch::Patch,
-    BrightnessContrastEffect, BurningShipGen, ColorMapEffect, ColorScheme, EchoEffect,
-    HueShiftEffect, JuliaGen, MandelbrotGen, MotionBlurEffect, NoiseFieldGen, Params, RippleEffect,
+    BrightnessContrastEffect, BuffaloGen, BurningShipGen, CelticGen, ColorMapEffect, ColorScheme,
+    CustomFormulaGen, DomainWarpGen, EchoEffect, HueShiftEffect, HybridGen, HybridSchedule, IfsGen,
+    ImageGen, InverseJuliaGen, InvertEffect, JuliaGen, KleinianGen, MandelbrotGen,
+    MotionBlurEffect, MultibrotGen, NoiseFieldGen, Params, PlasmaGen, PosterizeEffect,
+    ReactionDiffusionGen, RippleEffect, SimplexFieldGen, TestPatternGen, VideoGen, WorleyFieldGen,
 };
 
-/// Preset names, matching the five from the original Clojure implementation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Preset names — the first five match the original Clojure implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Preset {
     ClassicMandelbrot,
     PsychedelicJulia,
     TrippyMandelbrot,
     BurningShipTrails,
     NoiseField,
+    MorphingJulia,
+    OceanRippleJulia,
+    ShipStorm,
+    NoiseBloom,
+    SeahorseValley,
+    ElephantValley,
+    DendriteJulia,
+    NoiseTide,
+    FigureEightJulia,
+    ClassicJuliaLoop,
+    EchoChamber,
+    LiveVideo,
+    StillImage,
+    TestPattern,
+    StardustJulia,
+    PickoverStalks,
+    MultibrotMorph,
+    CelticKnots,
+    BuffaloHerd,
+    BarnsleyFern,
+    SwirlFlame,
+    KleinianDrift,
+    HybridShip,
+    SineFormula,
+    MarbleVeins,
+    CellBloom,
+    CoralGrowth,
+    SilkCurrents,
+    PopArt,
+    DemoPlasma,
 }
 
 impl Preset {
-    pub const ALL: [Preset; 5] = [
+    pub const ALL: [Preset; 35] = [
         Preset::ClassicMandelbrot,
         Preset::PsychedelicJulia,
         Preset::TrippyMandelbrot,
         Preset::BurningShipTrails,
         Preset::NoiseField,
+        Preset::MorphingJulia,
+        Preset::OceanRippleJulia,
+        Preset::ShipStorm,
+        Preset::NoiseBloom,
+        Preset::SeahorseValley,
+        Preset::ElephantValley,
+        Preset::DendriteJulia,
+        Preset::NoiseTide,
+        Preset::FigureEightJulia,
+        Preset::ClassicJuliaLoop,
+        Preset::EchoChamber,
+        Preset::LiveVideo,
+        Preset::StillImage,
+        Preset::TestPattern,
+        Preset::StardustJulia,
+        Preset::PickoverStalks,
+        Preset::MultibrotMorph,
+        Preset::CelticKnots,
+        Preset::BuffaloHerd,
+        Preset::BarnsleyFern,
+        Preset::SwirlFlame,
+        Preset::KleinianDrift,
+        Preset::HybridShip,
+        Preset::SineFormula,
+        Preset::MarbleVeins,
+        Preset::CellBloom,
+        Preset::CoralGrowth,
+        Preset::SilkCurrents,
+        Preset::PopArt,
+        Preset::DemoPlasma,
     ];
 
     pub fn name(self) -> &'static str {
@@ -33,13 +105,206 @@ impl Preset {
             Preset::TrippyMandelbrot => "Trippy Mandelbrot",
             Preset::BurningShipTrails => "Burning Ship Trails",
             Preset::NoiseField => "Noise Field",
+            Preset::MorphingJulia => "Morphing Julia",
+            Preset::OceanRippleJulia => "Ocean Ripple Julia",
+            Preset::ShipStorm => "Ship Storm",
+            Preset::NoiseBloom => "Noise Bloom",
+            Preset::SeahorseValley => "Seahorse Valley",
+            Preset::ElephantValley => "Elephant Valley",
+            Preset::DendriteJulia => "Dendrite Julia",
+            Preset::NoiseTide => "Noise Tide",
+            Preset::FigureEightJulia => "Figure-Eight Julia",
+            Preset::ClassicJuliaLoop => "Classic Julia Loop",
+            Preset::EchoChamber => "Echo Chamber",
+            Preset::LiveVideo => "Live Video",
+            Preset::StillImage => "Still Image",
+            Preset::TestPattern => "Test Pattern",
+            Preset::StardustJulia => "Stardust Julia",
+            Preset::PickoverStalks => "Pickover Stalks",
+            Preset::MultibrotMorph => "Multibrot Morph",
+            Preset::CelticKnots => "Celtic Knots",
+            Preset::BuffaloHerd => "Buffalo Herd",
+            Preset::BarnsleyFern => "Barnsley Fern",
+            Preset::SwirlFlame => "Swirl Flame",
+            Preset::KleinianDrift => "Kleinian Drift",
+            Preset::HybridShip => "Hybrid Ship",
+            Preset::SineFormula => "Sine Formula",
+            Preset::MarbleVeins => "Marble Veins",
+            Preset::CellBloom => "Cell Bloom",
+            Preset::CoralGrowth => "Coral Growth",
+            Preset::SilkCurrents => "Silk Currents",
+            Preset::PopArt => "Pop Art",
+            Preset::DemoPlasma => "Demo Plasma",
+        }
+    }
+
+    /// Look up a preset by its display [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    /// Stable identifier for saved files — the variant name, as serde writes
+    /// it, which unlike [`name`](Self::name) survives a preset being retitled.
+    pub fn id(self) -> String {
+        format!("{self:?}")
+    }
+
+    /// Look up a preset by its [`id`](Self::id).
+    pub fn from_id(id: &str) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|p| p.id() == id)
+    }
+
+    /// Description, tags and, for presets with beat-tuned LFOs or flashes,
+    /// the tempo they suit.  [`build`](Self::build) stamps it on the patch.
+    pub fn meta(self) -> PatchMeta {
+        match self {
+            Preset::ClassicMandelbrot => PatchMeta::new(
+                "The whole Mandelbrot set, as drawn since 1980.",
+                &["mandelbrot", "static"],
+            ),
+            Preset::PsychedelicJulia => PatchMeta::new(
+                "A Julia set whose colours cycle every two seconds.",
+                &["julia", "colour-cycle"],
+            ),
+            Preset::TrippyMandelbrot => PatchMeta::new(
+                "Mandelbrot rippled and echoed; the ripple swells and fades.",
+                &["mandelbrot", "ripple", "echo"],
+            ),
+            Preset::BurningShipTrails => PatchMeta::new(
+                "Burning Ship in fire colours; pan it to streak the trails.",
+                &["burning-ship", "motion-blur"],
+            ),
+            Preset::NoiseField => PatchMeta::new(
+                "Fractal noise whose brightness pulses slowly.",
+                &["noise", "ambient"],
+            ),
+            Preset::MorphingJulia => PatchMeta::new(
+                "Julia sets traced around the cardioid, one loop every 40 s.",
+                &["julia", "morph"],
+            ),
+            Preset::OceanRippleJulia => PatchMeta::new(
+                "A watery Julia set under a swelling ripple.",
+                &["julia", "ripple", "ambient"],
+            ),
+            Preset::ShipStorm => PatchMeta::new(
+                "The Burning Ship's mast, echoed, with a spinning motion blur.",
+                &["burning-ship", "echo", "motion-blur"],
+            ),
+            Preset::NoiseBloom => PatchMeta::new(
+                "Breathing fire-coloured noise; the detail macro sweeps it from smooth to gritty.",
+                &["noise", "macro"],
+            ),
+            Preset::SeahorseValley => PatchMeta::new(
+                "A deep view into the seahorse valley.",
+                &["mandelbrot", "static", "deep"],
+            ),
+            Preset::ElephantValley => PatchMeta::new(
+                "The elephant valley with a slow hue cycle.",
+                &["mandelbrot", "colour-cycle"],
+            ),
+            Preset::DendriteJulia => {
+                PatchMeta::new("The dendrite Julia set, c = i.", &["julia", "static"])
+            }
+            Preset::NoiseTide => PatchMeta::new(
+                "Noise that ebbs in and out like a tide, gently rippled.",
+                &["noise", "ripple", "ambient"],
+            ),
+            Preset::FigureEightJulia => PatchMeta::new(
+                "Julia sets on a figure-eight around the Douady rabbit.",
+                &["julia", "morph", "echo"],
+            ),
+            Preset::ClassicJuliaLoop => PatchMeta::new(
+                "The textbook morphing Julia: c around the 0.7885 circle.",
+                &["julia", "morph"],
+            ),
+            Preset::EchoChamber => PatchMeta::new(
+                "Deep echoes of the Mandelbrot set, brightness pulsing once a bar at 120 BPM.",
+                &["mandelbrot", "echo", "pulse"],
+            )
+            .with_bpm(120.0),
+            Preset::LiveVideo => PatchMeta::new(
+                "A webcam or video file, rippled and hue-drifted; black until a video is opened.",
+                &["video", "ripple"],
+            ),
+            Preset::StillImage => PatchMeta::new(
+                "A photo or logo that flashes on the beat; black until an image is loaded.",
+                &["image", "pulse"],
+            )
+            .with_bpm(120.0),
+            Preset::TestPattern => PatchMeta::new(
+                "Alignment chart for projectors and LED walls.",
+                &["utility", "static"],
+            ),
+            Preset::StardustJulia => PatchMeta::new(
+                "The Morphing Julia's sets drawn as dust by inverse iteration.",
+                &["julia", "morph", "points"],
+            ),
+            Preset::PickoverStalks => PatchMeta::new(
+                "The seahorse valley with breathing orbit-trap stalks.",
+                &["mandelbrot", "orbit-trap"],
+            ),
+            Preset::MultibrotMorph => PatchMeta::new(
+                "A multibrot growing from one-fold to five-fold symmetry and back.",
+                &["multibrot", "morph"],
+            ),
+            Preset::CelticKnots => PatchMeta::new(
+                "The Celtic fractal with a slow hue drift.",
+                &["celtic", "colour-cycle"],
+            ),
+            Preset::BuffaloHerd => PatchMeta::new(
+                "The Buffalo fractal with orbit-trap stalks.",
+                &["buffalo", "orbit-trap"],
+            ),
+            Preset::BarnsleyFern => {
+                PatchMeta::new("Barnsley's fern, its frond swaying.", &["ifs", "points"])
+            }
+            Preset::SwirlFlame => PatchMeta::new(
+                "A swirling flame gasket in its own turning colours.",
+                &["flame", "points"],
+            ),
+            Preset::KleinianDrift => PatchMeta::new(
+                "A Maskit limit set whose trace drifts and breathes.",
+                &["kleinian", "morph"],
+            ),
+            Preset::HybridShip => PatchMeta::new(
+                "Alternating Mandelbrot and Burning Ship steps.",
+                &["hybrid", "mandelbrot", "burning-ship"],
+            ),
+            Preset::SineFormula => PatchMeta::new(
+                "The custom formula z^3 + c*sin(z) + a; edit it live in the Formula panel.",
+                &["formula", "morph"],
+            ),
+            Preset::MarbleVeins => PatchMeta::new(
+                "Ridged simplex noise, from smoky turbulence to sharp marble veins.",
+                &["noise", "ambient"],
+            ),
+            Preset::CellBloom => PatchMeta::new(
+                "Worley cells blooming into their borders.",
+                &["noise", "cells"],
+            ),
+            Preset::CoralGrowth => PatchMeta::new(
+                "Reaction–diffusion growing coral; reset it to start again.",
+                &["simulation", "ambient"],
+            ),
+            Preset::SilkCurrents => PatchMeta::new(
+                "Domain-warped noise flowing like silk.",
+                &["noise", "warp", "ambient"],
+            ),
+            Preset::PopArt => PatchMeta::new(
+                "Posterized, hue-cycling Mandelbrot with an inverting strobe.",
+                &["mandelbrot", "posterize", "strobe"],
+            )
+            .with_bpm(128.0),
+            Preset::DemoPlasma => {
+                PatchMeta::new("An old-school demoscene plasma.", &["plasma", "retro"])
+            }
         }
     }
 
     /// Construct a fully-configured [`Patch`] for this preset, mirroring the
     /// corresponding Clojure patch factory in `presets.clj`.
     pub fn build(self) -> Patch {
-        match self {
+        let patch = match self {
             // -----------------------------------------------------------------
             // 1. Classic Mandelbrot
             //    Clojure: mandelbrot + color-mapper(:classic) + no modulators
@@ -63,7 +328,7 @@ impl Preset {
             //             hue-shift driven by LFO(0.5 Hz, sine) → [0, TAU].
             //
             //    julia_cx / julia_cy are stored in Params::fields so the GPU
-            //    layer can read them into Uniforms::julia_c each frame.
+            //    layer can read them into its Julia uniform block each frame.
             // -----------------------------------------------------------------
             Preset::PsychedelicJulia => {
                 let mut params = Params {
@@ -82,16 +347,19 @@ impl Preset {
                     .add_effect(Box::new(HueShiftEffect("hue_shift_amount")))
                     .add_modulator(Box::new(ModMatrix {
                         routes: vec![Route {
-                            modulator: Box::new(Lfo {
+                            source: RouteSource::Modulator(Box::new(Lfo {
                                 target: "hue_shift_amount",
                                 waveform: Waveform::Sine,
                                 frequency: 0.5,
                                 amplitude: 1.0,
                                 offset: 0.0,
-                            }),
-                            target: "hue_shift_amount",
+                                phase: 0.0,
+                            })),
+                            target: "hue_shift_amount".into(),
                             min: 0.0,
                             max: TAU,
+                            quantize: None,
+                            when: None,
                         }],
                     }))
             }
@@ -127,16 +395,19 @@ impl Preset {
                     // ParticleSystem effect deferred to Phase 7 (GPU compute particles).
                     .add_modulator(Box::new(ModMatrix {
                         routes: vec![Route {
-                            modulator: Box::new(Lfo {
+                            source: RouteSource::Modulator(Box::new(Lfo {
                                 target: "ripple_amplitude",
                                 waveform: Waveform::Sine,
                                 frequency: 0.3,
                                 amplitude: 1.0,
                                 offset: 0.0,
-                            }),
-                            target: "ripple_amplitude",
+                                phase: 0.0,
+                            })),
+                            target: "ripple_amplitude".into(),
                             min: 5.0,
                             max: 15.0,
+                            quantize: None,
+                            when: None,
                         }],
                     }))
             }
@@ -144,19 +415,28 @@ impl Preset {
             // -----------------------------------------------------------------
             // 4. Burning Ship Trails
             //    Clojure: burning-ship + fire color-map + motion-blur(0.15)
+            //
+            //    The directional streak starts disabled (length 0); pans or
+            //    modulators can write `motion_blur_angle` / `_length`.
             // -----------------------------------------------------------------
             Preset::BurningShipTrails => {
-                let params = Params {
+                let mut params = Params {
                     center_x: -0.5,
                     center_y: -0.5,
                     zoom: 1.0,
                     max_iter: 100,
                     ..Default::default()
                 };
+                params.set("motion_blur_angle", 0.0_f32);
+                params.set("motion_blur_length", 0.0_f32);
 
                 Patch::new(Box::new(BurningShipGen), params)
                     .add_effect(Box::new(ColorMapEffect(ColorScheme::Fire)))
-                    .add_effect(Box::new(MotionBlurEffect(0.15)))
+                    .add_effect(Box::new(MotionBlurEffect {
+                        opacity: 0.15,
+                        angle_key: "motion_blur_angle",
+                        length_key: "motion_blur_length",
+                    }))
             }
 
             // -----------------------------------------------------------------
@@ -171,6 +451,7 @@ impl Preset {
             // -----------------------------------------------------------------
             Preset::NoiseField => {
                 let mut params = Params::default();
+                NoiseFieldGen::set_defaults(&mut params);
                 // Initial midpoint ≈ Clojure's brightness=20 on 0-255 scale
                 params.set("brightness_amount", 20.0_f32 / 255.0);
 
@@ -182,334 +463,1965 @@ impl Preset {
                     }))
                     .add_modulator(Box::new(ModMatrix {
                         routes: vec![Route {
-                            modulator: Box::new(Lfo {
+                            source: RouteSource::Modulator(Box::new(Lfo {
                                 target: "brightness_amount",
                                 waveform: Waveform::Sine,
                                 frequency: 0.2,
                                 amplitude: 1.0,
                                 offset: 0.0,
-                            }),
-                            target: "brightness_amount",
+                                phase: 0.0,
+                            })),
+                            target: "brightness_amount".into(),
                             min: 0.0,
                             max: 40.0 / 255.0,
+                            quantize: None,
+                            when: None,
                         }],
                     }))
+                    .with_flash(&flash(&[("brightness_amount", 0.6)]))
             }
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{EffectKind, GeneratorKind};
 
-    // Helper: get the EffectKind slice for a built patch at its initial params.
-    fn effect_kinds(preset: Preset) -> Vec<EffectKind> {
-        let patch = preset.build();
-        patch
-            .effects
-            .iter()
-            .map(|e| e.kind(&patch.params))
-            .collect()
-    }
+            // -----------------------------------------------------------------
+            // 6. Morphing Julia (Rust-only)
+            //    julia with c swept around the main cardioid, just outside
+            //    the boundary, one loop every 40 s + classic color-map.
+            // -----------------------------------------------------------------
+            Preset::MorphingJulia => {
+                let shape = JuliaPathShape::Cardioid { scale: 1.05 };
+                let (cx, cy) = shape.point(0.0);
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 0.8,
+                    max_iter: 200,
+                    ..Default::default()
+                };
+                params.set("julia_cx", cx);
+                params.set("julia_cy", cy);
 
-    // --- Enum basics ----------------------------------------------------------
+                Patch::new(Box::new(JuliaGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Classic)))
+                    .add_modulator(Box::new(JuliaPath { shape, rate: 0.025 }))
+            }
 
-    #[test]
-    fn all_contains_five_presets() {
-        assert_eq!(Preset::ALL.len(), 5);
-    }
+            // -----------------------------------------------------------------
+            // 7. Ocean Ripple Julia
+            //    julia(0.285, 0.01) + ocean color-map + ripple whose amplitude
+            //    swells with LFO(0.15 Hz) → [2, 12] + slow hue drift.
+            // -----------------------------------------------------------------
+            Preset::OceanRippleJulia => {
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 1.0,
+                    max_iter: 150,
+                    ..Default::default()
+                };
+                params.set("julia_cx", 0.285_f32);
+                params.set("julia_cy", 0.01_f32);
+                params.set("ripple_amplitude", 7.0_f32);
+                params.set("hue_shift_amount", 0.0_f32);
 
-    #[test]
-    fn all_names_are_nonempty() {
-        for p in Preset::ALL {
-            assert!(!p.name().is_empty(), "{p:?} has empty name");
-        }
-    }
+                Patch::new(Box::new(JuliaGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Ocean)))
+                    .add_effect(Box::new(RippleEffect {
+                        frequency: 0.03,
+                        amplitude_key: "ripple_amplitude",
+                        speed: 1.0,
+                    }))
+                    .add_effect(Box::new(HueShiftEffect("hue_shift_amount")))
+                    .add_modulator(Box::new(ModMatrix {
+                        routes: vec![
+                            Route {
+                                source: RouteSource::Modulator(Box::new(Lfo {
+                                    target: "ripple_amplitude",
+                                    waveform: Waveform::Sine,
+                                    frequency: 0.15,
+                                    amplitude: 1.0,
+                                    offset: 0.0,
+                                    phase: 0.0,
+                                })),
+                                target: "ripple_amplitude".into(),
+                                min: 2.0,
+                                max: 12.0,
+                                quantize: None,
+                                when: None,
+                            },
+                            Route {
+                                source: RouteSource::Modulator(Box::new(Lfo {
+                                    target: "hue_shift_amount",
+                                    waveform: Waveform::Triangle,
+                                    frequency: 0.05,
+                                    amplitude: 1.0,
+                                    offset: 0.0,
+                                    phase: 0.0,
+                                })),
+                                target: "hue_shift_amount".into(),
+                                min: 0.0,
+                                max: 1.0,
+                                quantize: None,
+                                when: None,
+                            },
+                        ],
+                    }))
+                    .with_flash(&flash(&[
+                        ("ripple_amplitude", 30.0),
+                        ("hue_shift_amount", 0.5),
+                    ]))
+            }
 
-    #[test]
-    fn all_names_are_unique() {
-        let names: Vec<_> = Preset::ALL.iter().map(|p| p.name()).collect();
-        let mut seen = std::collections::HashSet::new();
-        for name in &names {
-            assert!(seen.insert(*name), "duplicate preset name: {name}");
-        }
-    }
+            // -----------------------------------------------------------------
+            // 8. Ship Storm
+            //    burning-ship zoomed onto the ship's mast + fire color-map +
+            //    echo + directional motion blur whose angle spins with a
+            //    LFO(0.1 Hz, saw) → [0, TAU].
+            // -----------------------------------------------------------------
+            Preset::ShipStorm => {
+                let mut params = Params {
+                    center_x: -1.762,
+                    center_y: -0.028,
+                    zoom: 20.0,
+                    max_iter: 200,
+                    ..Default::default()
+                };
+                params.set("motion_blur_angle", 0.0_f32);
+                params.set("motion_blur_length", 8.0_f32);
 
-    #[test]
-    fn preset_eq() {
-        assert_eq!(Preset::ClassicMandelbrot, Preset::ClassicMandelbrot);
-        assert_ne!(Preset::ClassicMandelbrot, Preset::NoiseField);
-    }
+                Patch::new(Box::new(BurningShipGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Fire)))
+                    .add_effect(Box::new(EchoEffect {
+                        layers: 2,
+                        offset: 3.0,
+                        decay: 0.6,
+                    }))
+                    .add_effect(Box::new(MotionBlurEffect {
+                        opacity: 0.3,
+                        angle_key: "motion_blur_angle",
+                        length_key: "motion_blur_length",
+                    }))
+                    .add_modulator(Box::new(ModMatrix {
+                        routes: vec![Route {
+                            source: RouteSource::Modulator(Box::new(Lfo {
+                                target: "motion_blur_angle",
+                                waveform: Waveform::Saw,
+                                frequency: 0.1,
+                                amplitude: 1.0,
+                                offset: 0.0,
+                                phase: 0.0,
+                            })),
+                            target: "motion_blur_angle".into(),
+                            min: 0.0,
+                            max: TAU,
+                            quantize: None,
+                            when: None,
+                        }],
+                    }))
+            }
 
-    #[test]
-    fn preset_names_match_expected() {
-        assert_eq!(Preset::ClassicMandelbrot.name(), "Classic Mandelbrot");
-        assert_eq!(Preset::PsychedelicJulia.name(), "Psychedelic Julia");
-        assert_eq!(Preset::TrippyMandelbrot.name(), "Trippy Mandelbrot");
-        assert_eq!(Preset::BurningShipTrails.name(), "Burning Ship Trails");
-        assert_eq!(Preset::NoiseField.name(), "Noise Field");
-    }
+            // -----------------------------------------------------------------
+            // 9. Noise Bloom
+            //    noise field whose FBM gain breathes with LFO(0.1 Hz) →
+            //    [0.35, 0.7] + fire color-map + brightness/contrast(1.3).
+            //    A "detail" macro sweeps scale, octaves and brightness
+            //    together; at 0.5 it reproduces the values below.
+            // -----------------------------------------------------------------
+            Preset::NoiseBloom => {
+                let mut params = Params::default();
+                NoiseFieldGen::set_defaults(&mut params);
+                params.set("noise_scale", 2.0_f32);
+                params.set("noise_octaves", 6.0_f32);
+                params.set("brightness_amount", 0.05_f32);
 
-    // --- ClassicMandelbrot ---------------------------------------------------
+                Patch::new(Box::new(NoiseFieldGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Fire)))
+                    .add_effect(Box::new(BrightnessContrastEffect {
+                        brightness_key: "brightness_amount",
+                        contrast: 1.3,
+                    }))
+                    .add_modulator(Box::new(ModMatrix {
+                        routes: vec![Route {
+                            source: RouteSource::Modulator(Box::new(Lfo {
+                                target: "noise_gain",
+                                waveform: Waveform::Sine,
+                                frequency: 0.1,
+                                amplitude: 1.0,
+                                offset: 0.0,
+                                phase: 0.0,
+                            })),
+                            target: "noise_gain".into(),
+                            min: 0.35,
+                            max: 0.7,
+                            quantize: None,
+                            when: None,
+                        }],
+                    }))
+                    .add_macro(
+                        Macro {
+                            name: "detail".into(),
+                            targets: vec![
+                                MacroTarget {
+                                    key: "noise_scale".into(),
+                                    min: 1.0,
+                                    max: 3.0,
+                                    curve: Curve::Linear,
+                                },
+                                MacroTarget {
+                                    key: "noise_octaves".into(),
+                                    min: 2.0,
+                                    max: 10.0,
+                                    curve: Curve::Linear,
+                                },
+                                MacroTarget {
+                                    key: "brightness_amount".into(),
+                                    min: -0.15,
+                                    max: 0.25,
+                                    curve: Curve::SCurve,
+                                },
+                            ],
+                        },
+                        0.5,
+                    )
+                    .with_flash(&flash(&[("brightness_amount", 0.6)]))
+            }
 
-    #[test]
-    fn classic_mandelbrot_generator() {
-        let patch = Preset::ClassicMandelbrot.build();
-        assert_eq!(patch.generator.kind(), GeneratorKind::Mandelbrot);
-    }
+            // -----------------------------------------------------------------
+            // 10. Seahorse Valley
+            //     mandelbrot zoomed into the seahorse valley + classic
+            //     color-map, deeper iterations.
+            // -----------------------------------------------------------------
+            Preset::SeahorseValley => {
+                let params = Params {
+                    center_x: -0.745,
+                    center_y: 0.113,
+                    zoom: 40.0,
+                    max_iter: 300,
+                    ..Default::default()
+                };
 
-    #[test]
-    fn classic_mandelbrot_effects() {
-        let kinds = effect_kinds(Preset::ClassicMandelbrot);
-        assert_eq!(kinds.len(), 1);
-        assert!(matches!(
-            kinds[0],
-            EffectKind::ColorMap {
-                scheme: ColorScheme::Classic
+                Patch::new(Box::new(MandelbrotGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Classic)))
             }
-        ));
-    }
-
-    #[test]
-    fn classic_mandelbrot_no_modulators() {
-        assert_eq!(Preset::ClassicMandelbrot.build().modulators.len(), 0);
-    }
 
-    #[test]
-    fn classic_mandelbrot_params() {
-        let p = Preset::ClassicMandelbrot.build().params;
-        assert!((p.center_x - (-0.5)).abs() < 1e-6);
-        assert!(p.center_y.abs() < 1e-6);
-        assert!((p.zoom - 1.0).abs() < 1e-6);
-        assert_eq!(p.max_iter, 100);
-    }
+            // -----------------------------------------------------------------
+            // 11. Elephant Valley
+            //     mandelbrot zoomed into the elephant valley + psychedelic
+            //     color-map + hue shift driven by LFO(0.2 Hz) → [0, TAU].
+            // -----------------------------------------------------------------
+            Preset::ElephantValley => {
+                let mut params = Params {
+                    center_x: 0.282,
+                    center_y: 0.01,
+                    zoom: 60.0,
+                    max_iter: 250,
+                    ..Default::default()
+                };
+                params.set("hue_shift_amount", 0.0_f32);
 
-    // --- PsychedelicJulia ----------------------------------------------------
+                Patch::new(Box::new(MandelbrotGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Psychedelic)))
+                    .add_effect(Box::new(HueShiftEffect("hue_shift_amount")))
+                    .add_modulator(Box::new(ModMatrix {
+                        routes: vec![Route {
+                            source: RouteSource::Modulator(Box::new(Lfo {
+                                target: "hue_shift_amount",
+                                waveform: Waveform::Sine,
+                                frequency: 0.2,
+                                amplitude: 1.0,
+                                offset: 0.0,
+                                phase: 0.0,
+                            })),
+                            target: "hue_shift_amount".into(),
+                            min: 0.0,
+                            max: TAU,
+                            quantize: None,
+                            when: None,
+                        }],
+                    }))
+            }
 
-    #[test]
-    fn psychedelic_julia_generator() {
-        let patch = Preset::PsychedelicJulia.build();
-        assert_eq!(patch.generator.kind(), GeneratorKind::Julia);
-    }
+            // -----------------------------------------------------------------
+            // 12. Dendrite Julia
+            //     julia(0, 1) — the dendrite — + fire color-map, static.
+            // -----------------------------------------------------------------
+            Preset::DendriteJulia => {
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 0.9,
+                    max_iter: 200,
+                    ..Default::default()
+                };
+                params.set("julia_cx", 0.0_f32);
+                params.set("julia_cy", 1.0_f32);
 
-    #[test]
-    fn psychedelic_julia_gen_param_keys_include_julia_c() {
-        let patch = Preset::PsychedelicJulia.build();
-        let keys = patch.generator.gen_param_keys();
-        assert!(keys.contains(&"julia_cx"), "missing julia_cx");
-        assert!(keys.contains(&"julia_cy"), "missing julia_cy");
-    }
+                Patch::new(Box::new(JuliaGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Fire)))
+            }
 
-    #[test]
-    fn psychedelic_julia_c_values() {
-        let p = Preset::PsychedelicJulia.build().params;
-        assert!((p.get("julia_cx") - (-0.7)).abs() < 1e-5);
-        assert!((p.get("julia_cy") - 0.27015).abs() < 1e-5);
-    }
+            // -----------------------------------------------------------------
+            // 13. Noise Tide
+            //     noise field whose scale ebbs with LFO(0.05 Hz, triangle) →
+            //     [1.5, 4.5] + ocean color-map + gentle ripple.
+            // -----------------------------------------------------------------
+            Preset::NoiseTide => {
+                let mut params = Params::default();
+                NoiseFieldGen::set_defaults(&mut params);
+                params.set("noise_seed", 3.0_f32);
+                params.set("ripple_amplitude", 4.0_f32);
 
-    #[test]
-    fn psychedelic_julia_effects() {
-        let kinds = effect_kinds(Preset::PsychedelicJulia);
-        assert_eq!(kinds.len(), 2);
-        assert!(matches!(
-            kinds[0],
-            EffectKind::ColorMap {
-                scheme: ColorScheme::Psychedelic
+                Patch::new(Box::new(NoiseFieldGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Ocean)))
+                    .add_effect(Box::new(RippleEffect {
+                        frequency: 0.02,
+                        amplitude_key: "ripple_amplitude",
+                        speed: 0.5,
+                    }))
+                    .add_modulator(Box::new(ModMatrix {
+                        routes: vec![Route {
+                            source: RouteSource::Modulator(Box::new(Lfo {
+                                target: "noise_scale",
+                                waveform: Waveform::Triangle,
+                                frequency: 0.05,
+                                amplitude: 1.0,
+                                offset: 0.0,
+                                phase: 0.0,
+                            })),
+                            target: "noise_scale".into(),
+                            min: 1.5,
+                            max: 4.5,
+                            quantize: None,
+                            when: None,
+                        }],
+                    }))
             }
-        ));
-        assert!(matches!(kinds[1], EffectKind::HueShift { .. }));
-    }
 
-    #[test]
-    fn psychedelic_julia_has_one_modulator() {
-        assert_eq!(Preset::PsychedelicJulia.build().modulators.len(), 1);
-    }
+            // -----------------------------------------------------------------
+            // 14. Figure-Eight Julia
+            //     julia with c on a figure-eight around the Douady-rabbit
+            //     region + psychedelic color-map + echo.
+            // -----------------------------------------------------------------
+            Preset::FigureEightJulia => {
+                let shape = JuliaPathShape::FigureEight {
+                    center: [-0.12, 0.75],
+                    size: 0.1,
+                };
+                let (cx, cy) = shape.point(0.0);
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 0.9,
+                    max_iter: 150,
+                    ..Default::default()
+                };
+                params.set("julia_cx", cx);
+                params.set("julia_cy", cy);
 
-    #[test]
-    fn psychedelic_julia_hue_shift_driven_by_lfo() {
-        // After one tick the LFO should change hue_shift_amount away from 0.
-        let mut patch = Preset::PsychedelicJulia.build();
-        let before = patch.params.get("hue_shift_amount");
-        patch.tick(0.5); // half second — LFO at 0.5 Hz should be near TAU/4
+                Patch::new(Box::new(JuliaGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Psychedelic)))
+                    .add_effect(Box::new(EchoEffect {
+                        layers: 3,
+                        offset: 2.0,
+                        decay: 0.5,
+                    }))
+                    .add_modulator(Box::new(JuliaPath { shape, rate: 0.05 }))
+            }
+
+            // -----------------------------------------------------------------
+            // 15. Classic Julia Loop
+            //     julia with c on the 0.7885 circle — the textbook morphing
+            //     Julia animation — + ocean color-map.
+            // -----------------------------------------------------------------
+            Preset::ClassicJuliaLoop => {
+                let shape = JuliaPathShape::Circle { radius: 0.7885 };
+                let (cx, cy) = shape.point(0.0);
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 0.9,
+                    max_iter: 150,
+                    ..Default::default()
+                };
+                params.set("julia_cx", cx);
+                params.set("julia_cy", cy);
+
+                Patch::new(Box::new(JuliaGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Ocean)))
+                    .add_modulator(Box::new(JuliaPath { shape, rate: 0.02 }))
+            }
+
+            // -----------------------------------------------------------------
+            // 16. Echo Chamber
+            //     mandelbrot + classic color-map + deep echo + brightness
+            //     pulsing with LFO(0.5 Hz, square) → [0, 0.1].
+            // -----------------------------------------------------------------
+            Preset::EchoChamber => {
+                let mut params = Params {
+                    center_x: -0.5,
+                    center_y: 0.0,
+                    zoom: 1.0,
+                    max_iter: 100,
+                    ..Default::default()
+                };
+                params.set("brightness_amount", 0.0_f32);
+
+                Patch::new(Box::new(MandelbrotGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Classic)))
+                    .add_effect(Box::new(EchoEffect {
+                        layers: 5,
+                        offset: 4.0,
+                        decay: 0.7,
+                    }))
+                    .add_effect(Box::new(BrightnessContrastEffect {
+                        brightness_key: "brightness_amount",
+                        contrast: 1.1,
+                    }))
+                    .add_modulator(Box::new(ModMatrix {
+                        routes: vec![Route {
+                            source: RouteSource::Modulator(Box::new(Lfo {
+                                target: "brightness_amount",
+                                waveform: Waveform::Square,
+                                frequency: 0.5,
+                                amplitude: 1.0,
+                                offset: 0.0,
+                                phase: 0.0,
+                            })),
+                            target: "brightness_amount".into(),
+                            min: 0.0,
+                            max: 0.1,
+                            quantize: None,
+                            when: None,
+                        }],
+                    }))
+                    .with_flash(&flash(&[("brightness_amount", 0.5)]))
+            }
+
+            // -----------------------------------------------------------------
+            // 17. Live Video
+            //     webcam / video file + gentle ripple + hue drift
+            //     LFO(0.03 Hz, triangle) → [0, 0.6].  Black until the app
+            //     opens a video input.
+            // -----------------------------------------------------------------
+            Preset::LiveVideo => {
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 1.0,
+                    ..Default::default()
+                };
+                params.set("ripple_amplitude", 3.0_f32);
+                params.set("hue_shift_amount", 0.0_f32);
+
+                Patch::new(Box::new(VideoGen), params)
+                    .add_effect(Box::new(RippleEffect {
+                        frequency: 0.03,
+                        amplitude_key: "ripple_amplitude",
+                        speed: 1.0,
+                    }))
+                    .add_effect(Box::new(HueShiftEffect("hue_shift_amount")))
+                    .add_modulator(Box::new(ModMatrix {
+                        routes: vec![Route {
+                            source: RouteSource::Modulator(Box::new(Lfo {
+                                target: "hue_shift_amount",
+                                waveform: Waveform::Triangle,
+                                frequency: 0.03,
+                                amplitude: 1.0,
+                                offset: 0.0,
+                                phase: 0.0,
+                            })),
+                            target: "hue_shift_amount".into(),
+                            min: 0.0,
+                            max: 0.6,
+                            quantize: None,
+                            when: None,
+                        }],
+                    }))
+                    .with_flash(&flash(&[("ripple_amplitude", 20.0)]))
+            }
+
+            // -----------------------------------------------------------------
+            // 18. Still Image
+            //     photo / logo + brightness that flashes on the beat.
+            //     Black until the app loads an image.
+            // -----------------------------------------------------------------
+            Preset::StillImage => {
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 1.0,
+                    ..Default::default()
+                };
+                params.set("brightness_amount", 0.0_f32);
+
+                Patch::new(Box::new(ImageGen), params)
+                    .add_effect(Box::new(BrightnessContrastEffect {
+                        brightness_key: "brightness_amount",
+                        contrast: 1.0,
+                    }))
+                    .with_flash(&flash(&[("brightness_amount", 0.4)]))
+            }
+
+            // -----------------------------------------------------------------
+            // 19. Test Pattern
+            //     projector / LED-wall alignment chart, no effects
+            // -----------------------------------------------------------------
+            Preset::TestPattern => Patch::new(Box::new(TestPatternGen), Params::default()),
+
+            // -----------------------------------------------------------------
+            // 20. Stardust Julia
+            //     inverse-iteration julia walking the cardioid + ocean
+            //     color-map: the same sets as Morphing Julia, drawn as dust
+            // -----------------------------------------------------------------
+            Preset::StardustJulia => {
+                let shape = JuliaPathShape::Cardioid { scale: 1.02 };
+                let (cx, cy) = shape.point(0.0);
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 0.8,
+                    max_iter: 64,
+                    ..Default::default()
+                };
+                params.set("julia_cx", cx);
+                params.set("julia_cy", cy);
+                params.set("inverse_julia_exposure", InverseJuliaGen::DEFAULT_EXPOSURE);
+
+                Patch::new(Box::new(InverseJuliaGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Ocean)))
+                    .add_modulator(Box::new(JuliaPath { shape, rate: 0.02 }))
+            }
+
+            // -----------------------------------------------------------------
+            // 21. Pickover Stalks
+            //     seahorse valley with orbit-trap stalks + fire color-map;
+            //     stalk width breathes on LFO(0.1 Hz) → [0.005, 0.035].
+            // -----------------------------------------------------------------
+            Preset::PickoverStalks => {
+                let mut params = Params {
+                    center_x: -0.745,
+                    center_y: 0.113,
+                    zoom: 40.0,
+                    max_iter: 200,
+                    ..Default::default()
+                };
+                Coloring::PickoverStalks.set(&mut params);
+
+                Patch::new(Box::new(MandelbrotGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Fire)))
+                    .add_modulator(Box::new(Lfo {
+                        target: "stalk_width",
+                        waveform: Waveform::Sine,
+                        frequency: 0.1,
+                        amplitude: 0.015,
+                        offset: 0.02,
+                        phase: 0.0,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 22. Multibrot Morph
+            //     multibrot whose exponent sweeps LFO(0.04 Hz) → [2, 6], so
+            //     the bulbs grow from the Mandelbrot's one-fold symmetry to
+            //     five-fold and back + psychedelic color-map.
+            // -----------------------------------------------------------------
+            Preset::MultibrotMorph => {
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 0.8,
+                    max_iter: 120,
+                    ..Default::default()
+                };
+                params.set("multibrot_power", MultibrotGen::DEFAULT_POWER);
+
+                Patch::new(Box::new(MultibrotGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Psychedelic)))
+                    .add_modulator(Box::new(Lfo {
+                        target: "multibrot_power",
+                        waveform: Waveform::Triangle,
+                        frequency: 0.04,
+                        amplitude: 2.0,
+                        offset: 4.0,
+                        phase: 0.0,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 23. Celtic Knots
+            //     celtic + ocean color-map; hue drifts on LFO(0.05 Hz)
+            //     → [0, TAU].
+            // -----------------------------------------------------------------
+            Preset::CelticKnots => {
+                let mut params = Params {
+                    center_x: -0.5,
+                    center_y: 0.0,
+                    zoom: 0.9,
+                    max_iter: 120,
+                    ..Default::default()
+                };
+                params.set("hue_shift_amount", 0.0_f32);
+
+                Patch::new(Box::new(CelticGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Ocean)))
+                    .add_effect(Box::new(HueShiftEffect("hue_shift_amount")))
+                    .add_modulator(Box::new(Lfo {
+                        target: "hue_shift_amount",
+                        waveform: Waveform::Sine,
+                        frequency: 0.05,
+                        amplitude: TAU / 2.0,
+                        offset: TAU / 2.0,
+                        phase: 0.0,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 24. Buffalo Herd
+            //     buffalo framed like the burning ship + fire color-map with
+            //     orbit-trap stalks of a fixed width.
+            // -----------------------------------------------------------------
+            Preset::BuffaloHerd => {
+                let mut params = Params {
+                    center_x: -0.5,
+                    center_y: -0.5,
+                    zoom: 0.9,
+                    max_iter: 150,
+                    ..Default::default()
+                };
+                Coloring::PickoverStalks.set(&mut params);
+
+                Patch::new(Box::new(BuffaloGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Fire)))
+            }
+
+            // -----------------------------------------------------------------
+            // 25. Barnsley Fern
+            //     IFS chaos game + ocean color-map; the main frond's curl
+            //     (ifs1_b) sways on LFO(0.1 Hz) → [-0.07, -0.01].
+            // -----------------------------------------------------------------
+            Preset::BarnsleyFern => {
+                let mut params = Params {
+                    center_x: 0.25,
+                    center_y: -5.0,
+                    zoom: 0.19,
+                    max_iter: 64,
+                    ..Default::default()
+                };
+                IfsGen::set_maps(&mut params, &IfsGen::BARNSLEY_FERN);
+                params.set("ifs_exposure", IfsGen::DEFAULT_EXPOSURE);
+
+                Patch::new(Box::new(IfsGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Ocean)))
+                    .add_modulator(Box::new(Lfo {
+                        target: "ifs1_b",
+                        waveform: Waveform::Sine,
+                        frequency: 0.1,
+                        amplitude: 0.03,
+                        offset: -0.04,
+                        phase: 0.0,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 26. Swirl Flame
+            //     fractal flame of a swirling gasket in its own colours; the
+            //     hue wheel turns on LFO(0.02 Hz) and the top corner's swirl
+            //     (flame0_swirl) breathes on LFO(0.08 Hz) → [0.4, 1.2].
+            // -----------------------------------------------------------------
+            Preset::SwirlFlame => {
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 0.8,
+                    max_iter: 128,
+                    ..Default::default()
+                };
+                FlameGen::set_transforms(&mut params, &FlameGen::SWIRL);
+                FlameGen::set_defaults(&mut params);
+
+                Patch::new(Box::new(FlameGen), params)
+                    .add_modulator(Box::new(Lfo {
+                        target: "flame_hue",
+                        waveform: Waveform::Saw,
+                        frequency: 0.02,
+                        amplitude: 0.5,
+                        offset: 0.5,
+                        phase: 0.0,
+                    }))
+                    .add_modulator(Box::new(Lfo {
+                        target: "flame0_swirl",
+                        waveform: Waveform::Sine,
+                        frequency: 0.08,
+                        amplitude: 0.4,
+                        offset: 0.8,
+                        phase: 0.0,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 27. Kleinian Drift
+            //     Maskit limit set + psychedelic color-map; the trace's shear
+            //     (kleinian_trace_im) drifts on LFO(0.03 Hz) → [-0.06, 0.06]
+            //     and its height breathes on LFO(0.05 Hz) → [1.9, 2.0].
+            // -----------------------------------------------------------------
+            Preset::KleinianDrift => {
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 1.0,
+                    zoom: 0.8,
+                    max_iter: 80,
+                    ..Default::default()
+                };
+                KleinianGen::set_defaults(&mut params);
+
+                Patch::new(Box::new(KleinianGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Psychedelic)))
+                    .add_modulator(Box::new(Lfo {
+                        target: "kleinian_trace_im",
+                        waveform: Waveform::Sine,
+                        frequency: 0.03,
+                        amplitude: 0.06,
+                        offset: 0.0,
+                        phase: 0.0,
+                    }))
+                    .add_modulator(Box::new(Lfo {
+                        target: "kleinian_trace_re",
+                        waveform: Waveform::Triangle,
+                        frequency: 0.05,
+                        amplitude: 0.05,
+                        offset: 1.95,
+                        phase: 0.0,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 28. Hybrid Ship
+            //     two Mandelbrot steps then two Burning Ship steps ("MMBB"),
+            //     framed to show both parents + ocean color-map.
+            // -----------------------------------------------------------------
+            Preset::HybridShip => {
+                let params = Params {
+                    center_x: -0.5,
+                    center_y: -0.25,
+                    zoom: 0.9,
+                    max_iter: 200,
+                    ..Default::default()
+                };
+
+                Patch::new(
+                    Box::new(HybridGen {
+                        schedule: HybridSchedule::DEFAULT,
+                    }),
+                    params,
+                )
+                .add_effect(Box::new(ColorMapEffect(ColorScheme::Ocean)))
+            }
+
+            // -----------------------------------------------------------------
+            // 29. Sine Formula
+            //     custom formula z³ + c·sin(z) + a, with `a` nudged by
+            //     LFO(0.04 Hz) → [-0.1, 0.1] + fire color-map.
+            // -----------------------------------------------------------------
+            Preset::SineFormula => {
+                let mut params = Params {
+                    center_x: 0.0,
+                    center_y: 0.0,
+                    zoom: 0.6,
+                    max_iter: 100,
+                    ..Default::default()
+                };
+                params.set("formula_a", 0.0);
+                params.set("formula_b", 0.0);
+
+                Patch::new(
+                    Box::new(CustomFormulaGen {
+                        formula: Formula::parse("z^3 + c*sin(z) + a")
+                            .expect("preset formula parses"),
+                    }),
+                    params,
+                )
+                .add_effect(Box::new(ColorMapEffect(ColorScheme::Fire)))
+                .add_modulator(Box::new(Lfo {
+                    target: "formula_a",
+                    waveform: Waveform::Sine,
+                    frequency: 0.04,
+                    amplitude: 0.1,
+                    offset: 0.0,
+                    phase: 0.0,
+                }))
+            }
+
+            // -----------------------------------------------------------------
+            // 30. Marble Veins
+            //     ridged simplex field + ocean color-map; the ridge blend
+            //     (simplex_ridge) breathes on LFO(0.03 Hz) → [0.4, 1.0], from
+            //     smoky turbulence to sharp veins.
+            // -----------------------------------------------------------------
+            Preset::MarbleVeins => {
+                let mut params = Params::default();
+                SimplexFieldGen::set_defaults(&mut params);
+                params.set("noise_scale", 2.0_f32);
+                params.set("noise_octaves", 5.0_f32);
+
+                Patch::new(Box::new(SimplexFieldGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Ocean)))
+                    .add_modulator(Box::new(Lfo {
+                        target: "simplex_ridge",
+                        waveform: Waveform::Sine,
+                        frequency: 0.03,
+                        amplitude: 0.3,
+                        offset: 0.7,
+                        phase: 0.0,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 31. Cell Bloom
+            //     Worley cells + fire color-map; worley_edge sweeps on
+            //     LFO(0.05 Hz, triangle) → [0, 1], from round cells to their
+            //     borders.
+            // -----------------------------------------------------------------
+            Preset::CellBloom => {
+                let mut params = Params::default();
+                WorleyFieldGen::set_defaults(&mut params);
+
+                Patch::new(Box::new(WorleyFieldGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Fire)))
+                    .add_modulator(Box::new(Lfo {
+                        target: "worley_edge",
+                        waveform: Waveform::Triangle,
+                        frequency: 0.05,
+                        amplitude: 0.5,
+                        offset: 0.5,
+                        phase: 0.0,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 32. Coral Growth
+            //     Gray–Scott reaction–diffusion in the coral regime + ocean
+            //     color-map; the feed rate wanders on LFO(0.02 Hz) →
+            //     [0.0525, 0.0565], between coral and spots.
+            // -----------------------------------------------------------------
+            Preset::CoralGrowth => {
+                let mut params = Params::default();
+                ReactionDiffusionGen::set_defaults(&mut params);
+
+                Patch::new(Box::new(ReactionDiffusionGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Ocean)))
+                    .add_modulator(Box::new(Lfo {
+                        target: "reaction_feed",
+                        waveform: Waveform::Sine,
+                        frequency: 0.02,
+                        amplitude: 0.002,
+                        offset: 0.0545,
+                        phase: 0.0,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 33. Silk Currents
+            //     three-layer domain-warped noise + psychedelic color-map;
+            //     the warp strength swells on LFO(0.04 Hz) → [2.5, 5.5] while
+            //     its rotation sways on LFO(0.015 Hz) → [-0.6, 0.6] rad.
+            // -----------------------------------------------------------------
+            Preset::SilkCurrents => {
+                let mut params = Params::default();
+                DomainWarpGen::set_defaults(&mut params);
+                params.set("noise_scale", 1.5_f32);
+                params.set("warp_layers", 3.0_f32);
+
+                Patch::new(Box::new(DomainWarpGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Psychedelic)))
+                    .add_modulator(Box::new(Lfo {
+                        target: "warp_strength",
+                        waveform: Waveform::Sine,
+                        frequency: 0.04,
+                        amplitude: 1.5,
+                        offset: 4.0,
+                        phase: 0.0,
+                    }))
+                    .add_modulator(Box::new(Lfo {
+                        target: "warp_rotation",
+                        waveform: Waveform::Sine,
+                        frequency: 0.015,
+                        amplitude: 0.6,
+                        offset: 0.0,
+                        phase: 0.25,
+                    }))
+            }
+
+            // -----------------------------------------------------------------
+            // 34. Pop Art
+            //     seahorse valley + classic color-map, then four pointwise
+            //     effects the GPU fuses into one pass: hue-shift on
+            //     LFO(0.1 Hz) → [0, TAU], posterize stepping through
+            //     LFO(0.05 Hz) → [3, 8] levels, a contrast boost, and an
+            //     inversion that flashes on the beat.
+            // -----------------------------------------------------------------
+            Preset::PopArt => {
+                let mut params = Params {
+                    center_x: -0.745,
+                    center_y: 0.1,
+                    zoom: 40.0,
+                    max_iter: 200,
+                    ..Default::default()
+                };
+                params.set("hue_amount", 0.0_f32);
+                params.set("posterize_levels", 5.0_f32);
+                params.set("brightness_amount", 0.0_f32);
+                params.set("invert_amount", 0.0_f32);
+
+                Patch::new(Box::new(MandelbrotGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Classic)))
+                    .add_effect(Box::new(HueShiftEffect("hue_amount")))
+                    .add_effect(Box::new(PosterizeEffect("posterize_levels")))
+                    .add_effect(Box::new(BrightnessContrastEffect {
+                        brightness_key: "brightness_amount",
+                        contrast: 1.4,
+                    }))
+                    .add_effect(Box::new(InvertEffect("invert_amount")))
+                    .add_modulator(Box::new(Lfo {
+                        target: "hue_amount",
+                        waveform: Waveform::Sine,
+                        frequency: 0.1,
+                        amplitude: TAU / 2.0,
+                        offset: TAU / 2.0,
+                        phase: 0.0,
+                    }))
+                    .add_modulator(Box::new(Lfo {
+                        target: "posterize_levels",
+                        waveform: Waveform::Triangle,
+                        frequency: 0.05,
+                        amplitude: 2.5,
+                        offset: 5.5,
+                        phase: 0.0,
+                    }))
+                    .with_flash(&flash(&[("invert_amount", 1.0)]))
+            }
+
+            // -----------------------------------------------------------------
+            // 35. Demo Plasma
+            //     all four plasma waves + psychedelic color-map, drifting on
+            //     their own phase speeds; the radial wave's frequency
+            //     breathes on LFO(0.03 Hz) → [3, 7].
+            // -----------------------------------------------------------------
+            Preset::DemoPlasma => {
+                let mut params = Params::default();
+                PlasmaGen::set_defaults(&mut params);
+
+                Patch::new(Box::new(PlasmaGen), params)
+                    .add_effect(Box::new(ColorMapEffect(ColorScheme::Psychedelic)))
+                    .add_modulator(Box::new(Lfo {
+                        target: "plasma3_freq",
+                        waveform: Waveform::Sine,
+                        frequency: 0.03,
+                        amplitude: 2.0,
+                        offset: 5.0,
+                        phase: 0.0,
+                    }))
+            }
+        };
+        patch.with_meta(self.meta())
+    }
+}
+
+/// Flash targets from `(key, value)` pairs.
+fn flash(targets: &[(&str, f32)]) -> Vec<FlashTarget> {
+    targets
+        .iter()
+        .map(|&(key, value)| FlashTarget {
+            key: key.to_string(),
+            value,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EffectKind, GeneratorKind};
+
+    // Helper: get the EffectKind slice for a built patch at its initial params.
+    fn effect_kinds(preset: Preset) -> Vec<EffectKind> {
+        let patch = preset.build();
+        patch
+            .effects
+            .iter()
+            .map(|e| e.kind(&patch.params))
+            .collect()
+    }
+
+    // --- Enum basics ----------------------------------------------------------
+
+    #[test]
+    fn all_contains_thirty_five_presets() {
+        assert_eq!(Preset::ALL.len(), 35);
+    }
+
+    #[test]
+    fn all_names_are_nonempty() {
+        for p in Preset::ALL {
+            assert!(!p.name().is_empty(), "{p:?} has empty name");
+        }
+    }
+
+    #[test]
+    fn from_name_round_trips() {
+        for p in Preset::ALL {
+            assert_eq!(Preset::from_name(p.name()), Some(p));
+        }
+        assert_eq!(Preset::from_name("No Such Preset"), None);
+    }
+
+    #[test]
+    fn all_names_are_unique() {
+        let names: Vec<_> = Preset::ALL.iter().map(|p| p.name()).collect();
+        let mut seen = std::collections::HashSet::new();
+        for name in &names {
+            assert!(seen.insert(*name), "duplicate preset name: {name}");
+        }
+    }
+
+    #[test]
+    fn preset_eq() {
+        assert_eq!(Preset::ClassicMandelbrot, Preset::ClassicMandelbrot);
+        assert_ne!(Preset::ClassicMandelbrot, Preset::NoiseField);
+    }
+
+    #[test]
+    fn preset_names_match_expected() {
+        assert_eq!(Preset::ClassicMandelbrot.name(), "Classic Mandelbrot");
+        assert_eq!(Preset::PsychedelicJulia.name(), "Psychedelic Julia");
+        assert_eq!(Preset::TrippyMandelbrot.name(), "Trippy Mandelbrot");
+        assert_eq!(Preset::BurningShipTrails.name(), "Burning Ship Trails");
+        assert_eq!(Preset::NoiseField.name(), "Noise Field");
+        assert_eq!(Preset::MorphingJulia.name(), "Morphing Julia");
+        assert_eq!(Preset::OceanRippleJulia.name(), "Ocean Ripple Julia");
+        assert_eq!(Preset::ShipStorm.name(), "Ship Storm");
+        assert_eq!(Preset::NoiseBloom.name(), "Noise Bloom");
+        assert_eq!(Preset::SeahorseValley.name(), "Seahorse Valley");
+        assert_eq!(Preset::ElephantValley.name(), "Elephant Valley");
+        assert_eq!(Preset::DendriteJulia.name(), "Dendrite Julia");
+        assert_eq!(Preset::NoiseTide.name(), "Noise Tide");
+        assert_eq!(Preset::FigureEightJulia.name(), "Figure-Eight Julia");
+        assert_eq!(Preset::ClassicJuliaLoop.name(), "Classic Julia Loop");
+        assert_eq!(Preset::EchoChamber.name(), "Echo Chamber");
+        assert_eq!(Preset::LiveVideo.name(), "Live Video");
+        assert_eq!(Preset::StillImage.name(), "Still Image");
+        assert_eq!(Preset::TestPattern.name(), "Test Pattern");
+        assert_eq!(Preset::StardustJulia.name(), "Stardust Julia");
+        assert_eq!(Preset::PickoverStalks.name(), "Pickover Stalks");
+        assert_eq!(Preset::MultibrotMorph.name(), "Multibrot Morph");
+        assert_eq!(Preset::CelticKnots.name(), "Celtic Knots");
+        assert_eq!(Preset::BuffaloHerd.name(), "Buffalo Herd");
+        assert_eq!(Preset::BarnsleyFern.name(), "Barnsley Fern");
+        assert_eq!(Preset::SwirlFlame.name(), "Swirl Flame");
+        assert_eq!(Preset::KleinianDrift.name(), "Kleinian Drift");
+        assert_eq!(Preset::HybridShip.name(), "Hybrid Ship");
+        assert_eq!(Preset::SineFormula.name(), "Sine Formula");
+        assert_eq!(Preset::MarbleVeins.name(), "Marble Veins");
+        assert_eq!(Preset::CellBloom.name(), "Cell Bloom");
+        assert_eq!(Preset::CoralGrowth.name(), "Coral Growth");
+        assert_eq!(Preset::SilkCurrents.name(), "Silk Currents");
+        assert_eq!(Preset::PopArt.name(), "Pop Art");
+        assert_eq!(Preset::DemoPlasma.name(), "Demo Plasma");
+    }
+
+    // --- ClassicMandelbrot ---------------------------------------------------
+
+    #[test]
+    fn classic_mandelbrot_generator() {
+        let patch = Preset::ClassicMandelbrot.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Mandelbrot);
+    }
+
+    #[test]
+    fn classic_mandelbrot_effects() {
+        let kinds = effect_kinds(Preset::ClassicMandelbrot);
+        assert_eq!(kinds.len(), 1);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Classic,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn classic_mandelbrot_no_modulators() {
+        assert_eq!(Preset::ClassicMandelbrot.build().modulators.len(), 0);
+    }
+
+    #[test]
+    fn classic_mandelbrot_params() {
+        let p = Preset::ClassicMandelbrot.build().params;
+        assert!((p.center_x - (-0.5)).abs() < 1e-6);
+        assert!(p.center_y.abs() < 1e-6);
+        assert!((p.zoom - 1.0).abs() < 1e-6);
+        assert_eq!(p.max_iter, 100);
+    }
+
+    // --- PsychedelicJulia ----------------------------------------------------
+
+    #[test]
+    fn psychedelic_julia_generator() {
+        let patch = Preset::PsychedelicJulia.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Julia);
+    }
+
+    #[test]
+    fn psychedelic_julia_gen_param_keys_include_julia_c() {
+        let patch = Preset::PsychedelicJulia.build();
+        let keys = patch.generator.gen_param_keys();
+        assert!(keys.contains(&"julia_cx"), "missing julia_cx");
+        assert!(keys.contains(&"julia_cy"), "missing julia_cy");
+    }
+
+    #[test]
+    fn psychedelic_julia_c_values() {
+        let p = Preset::PsychedelicJulia.build().params;
+        assert!((p.get("julia_cx") - (-0.7)).abs() < 1e-5);
+        assert!((p.get("julia_cy") - 0.27015).abs() < 1e-5);
+    }
+
+    #[test]
+    fn psychedelic_julia_effects() {
+        let kinds = effect_kinds(Preset::PsychedelicJulia);
+        assert_eq!(kinds.len(), 2);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Psychedelic,
+                ..
+            }
+        ));
+        assert!(matches!(kinds[1], EffectKind::HueShift { .. }));
+    }
+
+    #[test]
+    fn psychedelic_julia_has_one_modulator() {
+        assert_eq!(Preset::PsychedelicJulia.build().modulators.len(), 1);
+    }
+
+    #[test]
+    fn psychedelic_julia_hue_shift_driven_by_lfo() {
+        // After one tick the LFO should change hue_shift_amount away from 0.
+        let mut patch = Preset::PsychedelicJulia.build();
+        let before = patch.params.get("hue_shift_amount");
+        patch.tick(0.5); // half second — LFO at 0.5 Hz should be near TAU/4
         let after = patch.params.get("hue_shift_amount");
         assert!(
-            (after - before).abs() > 1e-3,
-            "hue_shift_amount did not change after tick"
+            (after - before).abs() > 1e-3,
+            "hue_shift_amount did not change after tick"
+        );
+        // Value must be within the [0, TAU] range.
+        assert!((0.0..=TAU + 1e-4).contains(&after), "out of range: {after}");
+    }
+
+    // --- TrippyMandelbrot ----------------------------------------------------
+
+    #[test]
+    fn trippy_mandelbrot_generator() {
+        let patch = Preset::TrippyMandelbrot.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Mandelbrot);
+    }
+
+    #[test]
+    fn trippy_mandelbrot_effects() {
+        let kinds = effect_kinds(Preset::TrippyMandelbrot);
+        assert_eq!(kinds.len(), 3);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Ocean,
+                ..
+            }
+        ));
+        assert!(matches!(kinds[1], EffectKind::Ripple { .. }));
+        assert!(matches!(kinds[2], EffectKind::Echo { .. }));
+    }
+
+    #[test]
+    fn trippy_mandelbrot_ripple_initial_amplitude() {
+        let kinds = effect_kinds(Preset::TrippyMandelbrot);
+        if let EffectKind::Ripple {
+            frequency,
+            amplitude,
+            speed,
+        } = kinds[1]
+        {
+            assert!((frequency - 0.05).abs() < 1e-6);
+            assert!((amplitude - 10.0).abs() < 1e-6);
+            assert!((speed - 2.0).abs() < 1e-6);
+        } else {
+            panic!("expected Ripple");
+        }
+    }
+
+    #[test]
+    fn trippy_mandelbrot_echo_params() {
+        let kinds = effect_kinds(Preset::TrippyMandelbrot);
+        if let EffectKind::Echo {
+            layers,
+            offset,
+            decay,
+        } = kinds[2]
+        {
+            assert_eq!(layers, 3);
+            assert!((offset - 5.0).abs() < 1e-6);
+            assert!((decay - 2.0).abs() < 1e-6);
+        } else {
+            panic!("expected Echo");
+        }
+    }
+
+    #[test]
+    fn trippy_mandelbrot_ripple_driven_by_lfo() {
+        let mut patch = Preset::TrippyMandelbrot.build();
+        let before = patch.params.get("ripple_amplitude");
+        patch.tick(1.0);
+        let after = patch.params.get("ripple_amplitude");
+        assert!(
+            (after - before).abs() > 1e-3,
+            "ripple_amplitude did not change"
+        );
+        assert!(
+            (5.0 - 1e-4..=15.0 + 1e-4).contains(&after),
+            "ripple_amplitude out of [5, 15]: {after}"
+        );
+    }
+
+    #[test]
+    fn trippy_mandelbrot_has_one_modulator() {
+        assert_eq!(Preset::TrippyMandelbrot.build().modulators.len(), 1);
+    }
+
+    // --- BurningShipTrails ---------------------------------------------------
+
+    #[test]
+    fn burning_ship_trails_generator() {
+        let patch = Preset::BurningShipTrails.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::BurningShip);
+    }
+
+    #[test]
+    fn burning_ship_trails_effects() {
+        let kinds = effect_kinds(Preset::BurningShipTrails);
+        assert_eq!(kinds.len(), 2);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Fire,
+                ..
+            }
+        ));
+        assert!(
+            matches!(kinds[1], EffectKind::MotionBlur { opacity, length, .. }
+                if (opacity - 0.15).abs() < 1e-6 && length == 0.0)
+        );
+    }
+
+    #[test]
+    fn burning_ship_trails_no_modulators() {
+        assert_eq!(Preset::BurningShipTrails.build().modulators.len(), 0);
+    }
+
+    #[test]
+    fn burning_ship_trails_center() {
+        let p = Preset::BurningShipTrails.build().params;
+        assert!((p.center_x - (-0.5)).abs() < 1e-6);
+        assert!((p.center_y - (-0.5)).abs() < 1e-6);
+    }
+
+    // --- NoiseField ----------------------------------------------------------
+
+    #[test]
+    fn noise_field_generator() {
+        let patch = Preset::NoiseField.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::NoiseField);
+    }
+
+    #[test]
+    fn noise_field_effects() {
+        let kinds = effect_kinds(Preset::NoiseField);
+        assert_eq!(kinds.len(), 2);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Psychedelic,
+                ..
+            }
+        ));
+        assert!(matches!(kinds[1], EffectKind::BrightnessContrast { .. }));
+    }
+
+    #[test]
+    fn noise_field_brightness_contrast_params() {
+        let kinds = effect_kinds(Preset::NoiseField);
+        if let EffectKind::BrightnessContrast {
+            brightness,
+            contrast,
+        } = kinds[1]
+        {
+            // Initial brightness ≈ 20/255 ≈ 0.078
+            assert!((0.0..=40.0 / 255.0 + 1e-4).contains(&brightness));
+            assert!((contrast - 1.5).abs() < 1e-6);
+        } else {
+            panic!("expected BrightnessContrast");
+        }
+    }
+
+    #[test]
+    fn noise_field_brightness_driven_by_lfo() {
+        let mut patch = Preset::NoiseField.build();
+        let before = patch.params.get("brightness_amount");
+        patch.tick(1.25); // enough for LFO at 0.2 Hz to move
+        let after = patch.params.get("brightness_amount");
+        assert!(
+            (after - before).abs() > 1e-4,
+            "brightness_amount did not change"
+        );
+        assert!(
+            (0.0 - 1e-4..=40.0 / 255.0 + 1e-4).contains(&after),
+            "brightness_amount out of range: {after}"
+        );
+    }
+
+    #[test]
+    fn noise_field_params_seeded() {
+        let patch = Preset::NoiseField.build();
+        // `noise_z` is optional — it falls back to `time` when unset.
+        for key in patch
+            .generator
+            .gen_param_keys()
+            .iter()
+            .filter(|k| **k != "noise_z")
+        {
+            assert!(
+                patch.params.fields.contains_key(*key),
+                "missing noise param {key}"
+            );
+        }
+    }
+
+    #[test]
+    fn noise_field_has_one_modulator() {
+        assert_eq!(Preset::NoiseField.build().modulators.len(), 1);
+    }
+
+    // --- MorphingJulia --------------------------------------------------------
+
+    #[test]
+    fn morphing_julia_generator() {
+        let patch = Preset::MorphingJulia.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Julia);
+    }
+
+    #[test]
+    fn morphing_julia_effects() {
+        let kinds = effect_kinds(Preset::MorphingJulia);
+        assert_eq!(kinds.len(), 1);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Classic,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn morphing_julia_has_one_modulator() {
+        assert_eq!(Preset::MorphingJulia.build().modulators.len(), 1);
+    }
+
+    #[test]
+    fn morphing_julia_c_moves_on_tick() {
+        let mut patch = Preset::MorphingJulia.build();
+        let before = (patch.params.get("julia_cx"), patch.params.get("julia_cy"));
+        patch.tick(5.0);
+        let after = (patch.params.get("julia_cx"), patch.params.get("julia_cy"));
+        assert!(
+            (after.0 - before.0).abs() + (after.1 - before.1).abs() > 1e-3,
+            "julia c did not move: {before:?} → {after:?}"
+        );
+    }
+
+    // --- OceanRippleJulia -----------------------------------------------------
+
+    #[test]
+    fn ocean_ripple_julia_generator() {
+        let patch = Preset::OceanRippleJulia.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Julia);
+    }
+
+    #[test]
+    fn ocean_ripple_julia_effects() {
+        let kinds = effect_kinds(Preset::OceanRippleJulia);
+        assert_eq!(kinds.len(), 3);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Ocean,
+                ..
+            }
+        ));
+        assert!(matches!(kinds[1], EffectKind::Ripple { .. }));
+        assert!(matches!(kinds[2], EffectKind::HueShift { .. }));
+    }
+
+    #[test]
+    fn ocean_ripple_julia_ripple_driven_by_lfo() {
+        let mut patch = Preset::OceanRippleJulia.build();
+        patch.tick(1.5);
+        let after = patch.params.get("ripple_amplitude");
+        assert!(
+            (2.0 - 1e-4..=12.0 + 1e-4).contains(&after),
+            "ripple_amplitude out of [2, 12]: {after}"
+        );
+    }
+
+    // --- ShipStorm -----------------------------------------------------------
+
+    #[test]
+    fn ship_storm_generator() {
+        let patch = Preset::ShipStorm.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::BurningShip);
+    }
+
+    #[test]
+    fn ship_storm_effects() {
+        let kinds = effect_kinds(Preset::ShipStorm);
+        assert_eq!(kinds.len(), 3);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Fire,
+                ..
+            }
+        ));
+        assert!(matches!(kinds[1], EffectKind::Echo { .. }));
+        assert!(
+            matches!(kinds[2], EffectKind::MotionBlur { length, .. } if (length - 8.0).abs() < 1e-6)
         );
-        // Value must be within the [0, TAU] range.
-        assert!(after >= 0.0 && after <= TAU + 1e-4, "out of range: {after}");
     }
 
-    // --- TrippyMandelbrot ----------------------------------------------------
+    #[test]
+    fn ship_storm_blur_angle_driven_by_lfo() {
+        let mut patch = Preset::ShipStorm.build();
+        patch.tick(2.5);
+        let after = patch.params.get("motion_blur_angle");
+        assert!(after > 1e-3, "motion_blur_angle did not change");
+        assert!((0.0..=TAU + 1e-4).contains(&after), "out of range: {after}");
+    }
+
+    // --- NoiseBloom ----------------------------------------------------------
 
     #[test]
-    fn trippy_mandelbrot_generator() {
-        let patch = Preset::TrippyMandelbrot.build();
-        assert_eq!(patch.generator.kind(), GeneratorKind::Mandelbrot);
+    fn noise_bloom_generator() {
+        let patch = Preset::NoiseBloom.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::NoiseField);
     }
 
     #[test]
-    fn trippy_mandelbrot_effects() {
-        let kinds = effect_kinds(Preset::TrippyMandelbrot);
-        assert_eq!(kinds.len(), 3);
+    fn noise_bloom_effects() {
+        let kinds = effect_kinds(Preset::NoiseBloom);
+        assert_eq!(kinds.len(), 2);
         assert!(matches!(
             kinds[0],
             EffectKind::ColorMap {
-                scheme: ColorScheme::Ocean
+                scheme: ColorScheme::Fire,
+                ..
             }
         ));
-        assert!(matches!(kinds[1], EffectKind::Ripple { .. }));
-        assert!(matches!(kinds[2], EffectKind::Echo { .. }));
+        assert!(matches!(kinds[1], EffectKind::BrightnessContrast { .. }));
     }
 
     #[test]
-    fn trippy_mandelbrot_ripple_initial_amplitude() {
-        let kinds = effect_kinds(Preset::TrippyMandelbrot);
-        if let EffectKind::Ripple {
-            frequency,
-            amplitude,
-            speed,
-        } = kinds[1]
-        {
-            assert!((frequency - 0.05).abs() < 1e-6);
-            assert!((amplitude - 10.0).abs() < 1e-6);
-            assert!((speed - 2.0).abs() < 1e-6);
-        } else {
-            panic!("expected Ripple");
-        }
+    fn noise_bloom_gain_driven_by_lfo() {
+        let mut patch = Preset::NoiseBloom.build();
+        patch.tick(2.5);
+        let after = patch.params.get("noise_gain");
+        assert!(
+            (0.35 - 1e-4..=0.7 + 1e-4).contains(&after),
+            "noise_gain out of [0.35, 0.7]: {after}"
+        );
     }
 
+    // --- SeahorseValley ------------------------------------------------------
+
     #[test]
-    fn trippy_mandelbrot_echo_params() {
-        let kinds = effect_kinds(Preset::TrippyMandelbrot);
-        if let EffectKind::Echo {
-            layers,
-            offset,
-            decay,
-        } = kinds[2]
-        {
-            assert_eq!(layers, 3);
-            assert!((offset - 5.0).abs() < 1e-6);
-            assert!((decay - 2.0).abs() < 1e-6);
-        } else {
-            panic!("expected Echo");
-        }
+    fn seahorse_valley_generator() {
+        let patch = Preset::SeahorseValley.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Mandelbrot);
     }
 
     #[test]
-    fn trippy_mandelbrot_ripple_driven_by_lfo() {
-        let mut patch = Preset::TrippyMandelbrot.build();
-        let before = patch.params.get("ripple_amplitude");
-        patch.tick(1.0);
-        let after = patch.params.get("ripple_amplitude");
-        assert!(
-            (after - before).abs() > 1e-3,
-            "ripple_amplitude did not change"
-        );
-        assert!(
-            after >= 5.0 - 1e-4 && after <= 15.0 + 1e-4,
-            "ripple_amplitude out of [5, 15]: {after}"
-        );
+    fn seahorse_valley_no_modulators() {
+        assert_eq!(Preset::SeahorseValley.build().modulators.len(), 0);
     }
 
     #[test]
-    fn trippy_mandelbrot_has_one_modulator() {
-        assert_eq!(Preset::TrippyMandelbrot.build().modulators.len(), 1);
+    fn seahorse_valley_params() {
+        let p = Preset::SeahorseValley.build().params;
+        assert!((p.center_x - (-0.745)).abs() < 1e-6);
+        assert!((p.center_y - 0.113).abs() < 1e-6);
+        assert_eq!(p.max_iter, 300);
     }
 
-    // --- BurningShipTrails ---------------------------------------------------
+    // --- ElephantValley ------------------------------------------------------
 
     #[test]
-    fn burning_ship_trails_generator() {
-        let patch = Preset::BurningShipTrails.build();
-        assert_eq!(patch.generator.kind(), GeneratorKind::BurningShip);
+    fn elephant_valley_generator() {
+        let patch = Preset::ElephantValley.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Mandelbrot);
     }
 
     #[test]
-    fn burning_ship_trails_effects() {
-        let kinds = effect_kinds(Preset::BurningShipTrails);
+    fn elephant_valley_effects() {
+        let kinds = effect_kinds(Preset::ElephantValley);
         assert_eq!(kinds.len(), 2);
         assert!(matches!(
             kinds[0],
             EffectKind::ColorMap {
-                scheme: ColorScheme::Fire
+                scheme: ColorScheme::Psychedelic,
+                ..
             }
         ));
-        assert!(
-            matches!(kinds[1], EffectKind::MotionBlur { opacity } if (opacity - 0.15).abs() < 1e-6)
-        );
+        assert!(matches!(kinds[1], EffectKind::HueShift { .. }));
     }
 
     #[test]
-    fn burning_ship_trails_no_modulators() {
-        assert_eq!(Preset::BurningShipTrails.build().modulators.len(), 0);
+    fn elephant_valley_has_one_modulator() {
+        assert_eq!(Preset::ElephantValley.build().modulators.len(), 1);
     }
 
+    // --- DendriteJulia -------------------------------------------------------
+
     #[test]
-    fn burning_ship_trails_center() {
-        let p = Preset::BurningShipTrails.build().params;
-        assert!((p.center_x - (-0.5)).abs() < 1e-6);
-        assert!((p.center_y - (-0.5)).abs() < 1e-6);
+    fn dendrite_julia_c_values() {
+        let patch = Preset::DendriteJulia.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Julia);
+        assert!(patch.params.get("julia_cx").abs() < 1e-6);
+        assert!((patch.params.get("julia_cy") - 1.0).abs() < 1e-6);
     }
 
-    // --- NoiseField ----------------------------------------------------------
+    #[test]
+    fn dendrite_julia_no_modulators() {
+        assert_eq!(Preset::DendriteJulia.build().modulators.len(), 0);
+    }
+
+    // --- NoiseTide -----------------------------------------------------------
 
     #[test]
-    fn noise_field_generator() {
-        let patch = Preset::NoiseField.build();
+    fn noise_tide_generator() {
+        let patch = Preset::NoiseTide.build();
         assert_eq!(patch.generator.kind(), GeneratorKind::NoiseField);
     }
 
     #[test]
-    fn noise_field_effects() {
-        let kinds = effect_kinds(Preset::NoiseField);
+    fn noise_tide_effects() {
+        let kinds = effect_kinds(Preset::NoiseTide);
         assert_eq!(kinds.len(), 2);
         assert!(matches!(
             kinds[0],
             EffectKind::ColorMap {
-                scheme: ColorScheme::Psychedelic
+                scheme: ColorScheme::Ocean,
+                ..
             }
         ));
-        assert!(matches!(kinds[1], EffectKind::BrightnessContrast { .. }));
+        assert!(matches!(kinds[1], EffectKind::Ripple { .. }));
     }
 
     #[test]
-    fn noise_field_brightness_contrast_params() {
-        let kinds = effect_kinds(Preset::NoiseField);
-        if let EffectKind::BrightnessContrast {
-            brightness,
-            contrast,
-        } = kinds[1]
-        {
-            // Initial brightness ≈ 20/255 ≈ 0.078
-            assert!(brightness >= 0.0 && brightness <= 40.0 / 255.0 + 1e-4);
-            assert!((contrast - 1.5).abs() < 1e-6);
-        } else {
-            panic!("expected BrightnessContrast");
+    fn noise_tide_scale_driven_by_lfo() {
+        let mut patch = Preset::NoiseTide.build();
+        patch.tick(3.0);
+        let after = patch.params.get("noise_scale");
+        assert!(
+            (1.5 - 1e-4..=4.5 + 1e-4).contains(&after),
+            "noise_scale out of [1.5, 4.5]: {after}"
+        );
+    }
+
+    // --- FigureEightJulia ----------------------------------------------------
+
+    #[test]
+    fn figure_eight_julia_generator() {
+        let patch = Preset::FigureEightJulia.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Julia);
+    }
+
+    #[test]
+    fn figure_eight_julia_effects() {
+        let kinds = effect_kinds(Preset::FigureEightJulia);
+        assert_eq!(kinds.len(), 2);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Psychedelic,
+                ..
+            }
+        ));
+        assert!(matches!(kinds[1], EffectKind::Echo { .. }));
+    }
+
+    #[test]
+    fn figure_eight_julia_has_one_modulator() {
+        assert_eq!(Preset::FigureEightJulia.build().modulators.len(), 1);
+    }
+
+    // --- ClassicJuliaLoop ----------------------------------------------------
+
+    #[test]
+    fn classic_julia_loop_starts_on_circle() {
+        let p = Preset::ClassicJuliaLoop.build().params;
+        let r = p.get("julia_cx").hypot(p.get("julia_cy"));
+        assert!((r - 0.7885).abs() < 1e-5, "r={r}");
+    }
+
+    #[test]
+    fn classic_julia_loop_has_one_modulator() {
+        assert_eq!(Preset::ClassicJuliaLoop.build().modulators.len(), 1);
+    }
+
+    // --- EchoChamber ---------------------------------------------------------
+
+    #[test]
+    fn echo_chamber_effects() {
+        let kinds = effect_kinds(Preset::EchoChamber);
+        assert_eq!(kinds.len(), 3);
+        assert!(matches!(
+            kinds[0],
+            EffectKind::ColorMap {
+                scheme: ColorScheme::Classic,
+                ..
+            }
+        ));
+        assert!(matches!(kinds[1], EffectKind::Echo { layers: 5, .. }));
+        assert!(matches!(kinds[2], EffectKind::BrightnessContrast { .. }));
+    }
+
+    #[test]
+    fn echo_chamber_has_one_modulator() {
+        assert_eq!(Preset::EchoChamber.build().modulators.len(), 1);
+    }
+
+    // --- LiveVideo -------------------------------------------------------------
+
+    #[test]
+    fn live_video_runs_footage_through_ripple_and_hue() {
+        let patch = Preset::LiveVideo.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Video);
+        let kinds = effect_kinds(Preset::LiveVideo);
+        assert!(matches!(kinds[0], EffectKind::Ripple { .. }));
+        assert!(matches!(kinds[1], EffectKind::HueShift { .. }));
+        assert_eq!((patch.params.center_x, patch.params.center_y), (0.0, 0.0));
+    }
+
+    // --- StillImage ------------------------------------------------------------
+
+    #[test]
+    fn still_image_is_centred_at_unit_zoom() {
+        let patch = Preset::StillImage.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Image);
+        assert_eq!((patch.params.center_x, patch.params.center_y), (0.0, 0.0));
+        assert_eq!(patch.params.zoom, 1.0);
+        assert!(matches!(
+            effect_kinds(Preset::StillImage)[0],
+            EffectKind::BrightnessContrast { .. }
+        ));
+    }
+
+    // --- TestPattern -----------------------------------------------------------
+
+    #[test]
+    fn test_pattern_is_unprocessed() {
+        let patch = Preset::TestPattern.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::TestPattern);
+        assert!(patch.effects.is_empty());
+        assert!(patch.modulators.is_empty());
+    }
+
+    // --- StardustJulia ---------------------------------------------------------
+
+    #[test]
+    fn stardust_julia_walks_the_cardioid() {
+        let mut patch = Preset::StardustJulia.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::InverseJulia);
+        assert_eq!(
+            patch.params.get("inverse_julia_exposure"),
+            InverseJuliaGen::DEFAULT_EXPOSURE
+        );
+        let start = patch.params.get("julia_cx");
+        patch.tick(5.0);
+        assert_ne!(patch.params.get("julia_cx"), start);
+    }
+
+    // --- PickoverStalks ----------------------------------------------------------
+
+    #[test]
+    fn pickover_stalks_animates_the_trap_width() {
+        let mut patch = Preset::PickoverStalks.build();
+        assert_eq!(
+            Coloring::from_params(&patch.params),
+            Coloring::PickoverStalks
+        );
+        patch.tick(2.5);
+        let width = Coloring::stalk_width(&patch.params);
+        assert!((0.005..=0.035).contains(&width), "stalk_width = {width}");
+        assert_ne!(width, Coloring::DEFAULT_STALK_WIDTH);
+    }
+
+    // --- MultibrotMorph ----------------------------------------------------------
+
+    #[test]
+    fn multibrot_morph_sweeps_the_power() {
+        let mut patch = Preset::MultibrotMorph.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Multibrot);
+        assert!(patch
+            .generator
+            .gen_param_keys()
+            .contains(&"multibrot_power"));
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            patch.tick(2.5);
+            let d = MultibrotGen::power(&patch.params);
+            assert!((2.0..=6.0).contains(&d), "multibrot_power = {d}");
+            seen.push(d);
         }
+        assert!(seen.windows(2).all(|w| w[0] != w[1]), "{seen:?}");
     }
 
+    // --- CelticKnots / BuffaloHerd ---------------------------------------------
+
     #[test]
-    fn noise_field_brightness_driven_by_lfo() {
-        let mut patch = Preset::NoiseField.build();
-        let before = patch.params.get("brightness_amount");
-        patch.tick(1.25); // enough for LFO at 0.2 Hz to move
-        let after = patch.params.get("brightness_amount");
-        assert!(
-            (after - before).abs() > 1e-4,
-            "brightness_amount did not change"
+    fn celtic_knots_drifts_its_hue() {
+        let mut patch = Preset::CelticKnots.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Celtic);
+        assert!(matches!(
+            effect_kinds(Preset::CelticKnots)[..],
+            [EffectKind::ColorMap { .. }, EffectKind::HueShift { .. }]
+        ));
+        patch.tick(5.0);
+        let hue = patch.params.get("hue_shift_amount");
+        assert!(hue > 0.0 && hue <= TAU, "hue_shift_amount = {hue}");
+    }
+
+    #[test]
+    fn buffalo_herd_uses_stalks() {
+        let patch = Preset::BuffaloHerd.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Buffalo);
+        assert_eq!(
+            Coloring::from_params(&patch.params),
+            Coloring::PickoverStalks
+        );
+    }
+
+    // --- BarnsleyFern ------------------------------------------------------------
+
+    #[test]
+    fn barnsley_fern_sways_its_frond() {
+        let mut patch = Preset::BarnsleyFern.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Ifs);
+        assert_eq!(IfsGen::maps(&patch.params), IfsGen::BARNSLEY_FERN);
+        assert!(patch.unconsumed_targets().is_empty());
+        patch.tick(2.5);
+        let curl = patch.params.get("ifs1_b");
+        assert!((-0.07..=-0.01).contains(&curl), "ifs1_b = {curl}");
+        assert_ne!(curl, IfsGen::BARNSLEY_FERN[1].b);
+    }
+
+    // --- SwirlFlame --------------------------------------------------------------
+
+    #[test]
+    fn swirl_flame_turns_its_hue_and_swirl() {
+        let mut patch = Preset::SwirlFlame.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Flame);
+        assert_eq!(FlameGen::transforms(&patch.params)[..3], FlameGen::SWIRL);
+        assert!(patch.unconsumed_targets().is_empty());
+        patch.tick(3.0);
+        let hue = patch.params.get("flame_hue");
+        assert!((0.0..=1.0).contains(&hue), "flame_hue = {hue}");
+        let swirl = patch.params.get("flame0_swirl");
+        assert!((0.4..=1.2).contains(&swirl), "flame0_swirl = {swirl}");
+        assert_ne!(swirl, 1.0);
+    }
+
+    // --- KleinianDrift -----------------------------------------------------------
+
+    #[test]
+    fn kleinian_drift_sweeps_its_trace() {
+        let mut patch = Preset::KleinianDrift.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Kleinian);
+        assert_eq!(effect_kinds(Preset::KleinianDrift).len(), 1);
+        assert!(patch.unconsumed_targets().is_empty());
+        patch.tick(4.0);
+        let [re, im] = KleinianGen::trace(&patch.params);
+        assert!((1.9..=2.0).contains(&re), "kleinian_trace_re = {re}");
+        assert!((-0.06..=0.06).contains(&im), "kleinian_trace_im = {im}");
+        assert_ne!(im, 0.0);
+    }
+
+    // --- HybridShip ---------------------------------------------------------------
+
+    #[test]
+    fn hybrid_ship_alternates_mandelbrot_and_ship_steps() {
+        let patch = Preset::HybridShip.build();
+        assert_eq!(
+            patch.generator.kind(),
+            GeneratorKind::Hybrid(HybridSchedule::DEFAULT)
         );
+        assert_eq!(effect_kinds(Preset::HybridShip).len(), 1);
+    }
+
+    // --- SineFormula ----------------------------------------------------------------
+
+    #[test]
+    fn sine_formula_sweeps_a() {
+        let mut patch = Preset::SineFormula.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::CustomFormula);
+        assert_eq!(
+            patch.generator.formula().unwrap().source(),
+            "z^3 + c*sin(z) + a"
+        );
+        assert!(patch.unconsumed_targets().is_empty());
+        patch.tick(3.0);
+        let a = patch.params.get("formula_a");
+        assert!(a > 0.0 && a <= 0.1, "formula_a = {a}");
+    }
+
+    // --- MarbleVeins / CellBloom ----------------------------------------------------
+
+    #[test]
+    fn marble_veins_breathes_its_ridge() {
+        let mut patch = Preset::MarbleVeins.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::SimplexField);
+        assert!(patch.unconsumed_targets().is_empty());
+        patch.tick(5.0);
+        let ridge = SimplexFieldGen::ridge(&patch.params);
+        assert!((0.4..=1.0).contains(&ridge), "simplex_ridge = {ridge}");
+        assert_ne!(ridge, 0.7);
+    }
+
+    #[test]
+    fn cell_bloom_sweeps_to_the_borders() {
+        let mut patch = Preset::CellBloom.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::WorleyField);
+        assert!(patch.unconsumed_targets().is_empty());
+        patch.tick(5.0);
+        let edge = WorleyFieldGen::edge(&patch.params);
+        assert!(edge > 0.0 && edge <= 1.0, "worley_edge = {edge}");
+    }
+
+    // --- CoralGrowth ------------------------------------------------------------------
+
+    #[test]
+    fn coral_growth_wanders_its_feed_rate() {
+        let mut patch = Preset::CoralGrowth.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::ReactionDiffusion);
+        assert!(patch.unconsumed_targets().is_empty());
+        patch.tick(10.0);
+        let feed = ReactionDiffusionGen::feed(&patch.params);
+        assert!((0.0525..=0.0565).contains(&feed), "reaction_feed = {feed}");
+        assert_ne!(feed, ReactionDiffusionGen::DEFAULT_FEED);
+    }
+
+    // --- SilkCurrents -----------------------------------------------------------------
+
+    #[test]
+    fn silk_currents_stirs_a_three_layer_warp() {
+        let mut patch = Preset::SilkCurrents.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::DomainWarp);
+        assert!(patch.unconsumed_targets().is_empty());
+        assert_eq!(DomainWarpGen::layers(&patch.params), 3);
+        patch.tick(5.0);
+        let strength = DomainWarpGen::strength(&patch.params);
         assert!(
-            after >= 0.0 - 1e-4 && after <= 40.0 / 255.0 + 1e-4,
-            "brightness_amount out of range: {after}"
+            (2.5..=5.5).contains(&strength),
+            "warp_strength = {strength}"
         );
+        assert_ne!(strength, DomainWarpGen::DEFAULT_STRENGTH);
+        assert_ne!(patch.params.get("warp_rotation"), 0.0);
     }
 
+    // --- PopArt ----------------------------------------------------------------------
+
     #[test]
-    fn noise_field_has_one_modulator() {
-        assert_eq!(Preset::NoiseField.build().modulators.len(), 1);
+    fn pop_art_chains_four_pointwise_effects_after_the_color_map() {
+        let mut patch = Preset::PopArt.build();
+        assert!(patch.unconsumed_targets().is_empty());
+        let kinds = effect_kinds(Preset::PopArt);
+        assert!(matches!(kinds[0], EffectKind::ColorMap { .. }));
+        assert_eq!(kinds.len(), 5);
+        assert!(kinds[1..].iter().all(EffectKind::is_pointwise));
+        patch.tick(4.0);
+        let levels = patch.params.get("posterize_levels");
+        assert!((3.0..=8.0).contains(&levels), "posterize_levels = {levels}");
+        assert_ne!(patch.params.get("hue_amount"), 0.0);
+    }
+
+    // --- DemoPlasma ------------------------------------------------------------------
+
+    #[test]
+    fn demo_plasma_sums_every_wave_and_breathes_the_radial_one() {
+        let mut patch = Preset::DemoPlasma.build();
+        assert_eq!(patch.generator.kind(), GeneratorKind::Plasma);
+        assert!(patch.unconsumed_targets().is_empty());
+        assert_eq!(PlasmaGen::waves(&patch.params), 4);
+        patch.tick(5.0);
+        let freq = PlasmaGen::frequencies(&patch.params)[3];
+        assert!((3.0..=7.0).contains(&freq), "plasma3_freq = {freq}");
+        assert_ne!(freq, PlasmaGen::DEFAULT_FREQUENCIES[3]);
+        assert_ne!(PlasmaGen::phases(&patch.params)[0], 0.0);
+    }
+
+    // --- Every preset ticks without producing non-finite params ---------------
+
+    #[test]
+    fn all_presets_tick_to_finite_params() {
+        for preset in Preset::ALL {
+            let mut patch = preset.build();
+            for _ in 0..10 {
+                patch.tick(0.37);
+            }
+            for (k, v) in &patch.params.fields {
+                assert!(v.is_finite(), "{preset:?}: {k} = {v}");
+            }
+        }
+    }
+
+    // --- Every modulator / route target is read by something ----------------
+
+    #[test]
+    fn all_preset_targets_are_consumed() {
+        for preset in Preset::ALL {
+            assert_eq!(
+                preset.build().unconsumed_targets(),
+                Vec::<String>::new(),
+                "{preset:?}"
+            );
+        }
+    }
+
+    // --- Metadata ------------------------------------------------------------
+
+    #[test]
+    fn every_preset_is_described_and_tagged() {
+        for preset in Preset::ALL {
+            let meta = preset.meta();
+            assert!(meta.description().is_some(), "{preset:?}");
+            assert!(!meta.tags.is_empty(), "{preset:?}");
+            assert_eq!(meta.bpm, meta.bpm(), "{preset:?}: tempo out of range");
+            assert_eq!(meta.title_or(preset.name()), preset.name());
+        }
+    }
+
+    #[test]
+    fn build_stamps_the_presets_meta() {
+        let patch = Preset::PopArt.build();
+        assert_eq!(patch.meta, Preset::PopArt.meta());
+        assert_eq!(patch.meta.bpm(), Some(128.0));
     }
 
     // --- build() is idempotent (returns a fresh Patch each call) -------------
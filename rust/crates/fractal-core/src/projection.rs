@@ -0,0 +1,190 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use serde::{Deserialize, Serialize};
+
+/// Largest view angle (from the zenith) the dome mapping reaches.  The
+/// gnomonic mapping goes to infinity at the horizon, so the rim is capped
+/// just short of it.
+pub const DOME_MAX_ANGLE: f32 = 85.0 * std::f32::consts::PI / 180.0;
+
+/// Radius, at zoom 1, of the exp map's top row.
+pub const EXP_MAP_OUTER_RADIUS: f32 = 2.0;
+
+// ---------------------------------------------------------------------------
+// Projection — how output pixels map onto the fractal plane
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Projection {
+    /// Ordinary flat view; one window height spans 2 units at zoom 1.
+    #[default]
+    Flat,
+    /// Domemaster: a 180° equidistant fisheye in the circle inscribed in
+    /// the output, looking straight up at the plane.  Pixels outside the
+    /// circle are black.
+    Dome,
+    /// Exponential (log-polar) map around the view centre: x sweeps one
+    /// full turn and y steps inwards in log radius at the same rate, so a
+    /// whole zoom sequence unrolls into a strip — each frame spans a zoom
+    /// factor of e^(2π·height/width) — and conformal shapes stay undistorted.
+    ExpMap,
+}
+
+impl Projection {
+    pub const ALL: [Projection; 3] = [Projection::Flat, Projection::Dome, Projection::ExpMap];
+
+    /// Value of the `projection` field in the shader uniforms.
+    pub fn gpu_index(self) -> u32 {
+        match self {
+            Projection::Flat => 0,
+            Projection::Dome => 1,
+            Projection::ExpMap => 2,
+        }
+    }
+
+    /// Offset from the view centre, at zoom 1, of pixel `px` in a
+    /// `res`-sized output.  `None` for pixels outside the dome.
+    ///
+    /// CPU mirror of `plane_uv` in the generators' view.wgsl; keep them in sync.
+    pub fn plane_offset(self, px: (f32, f32), res: (f32, f32)) -> Option<(f32, f32)> {
+        let d = (px.0 - res.0 * 0.5, px.1 - res.1 * 0.5);
+        match self {
+            Projection::ExpMap => {
+                let k = TAU / res.0;
+                let r = EXP_MAP_OUTER_RADIUS * (-px.1 * k).exp();
+                let (sin, cos) = (px.0 * k).sin_cos();
+                Some((cos * r, sin * r))
+            }
+            Projection::Flat => {
+                let half_h = res.1 * 0.5;
+                Some((d.0 / half_h, d.1 / half_h))
+            }
+            Projection::Dome => {
+                let radius = res.0.min(res.1) * 0.5;
+                let len = d.0.hypot(d.1);
+                let r = len / radius;
+                if r > 1.0 {
+                    return None;
+                }
+                if len < 1e-6 {
+                    return Some((0.0, 0.0));
+                }
+                // Equidistant fisheye: angle from the zenith grows linearly
+                // with radius.  Scaled so the centre matches the flat view.
+                let theta = (r * FRAC_PI_2).min(DOME_MAX_ANGLE);
+                let k = theta.tan() / FRAC_PI_2 / len;
+                Some((d.0 * k, d.1 * k))
+            }
+        }
+    }
+
+    /// The largest offset [`plane_offset`](Self::plane_offset) gives any
+    /// pixel of a `res`-sized output, at zoom 1: the corners of a flat
+    /// view, the dome's rim and the exp map's top row.
+    pub fn max_plane_offset(self, res: (f32, f32)) -> f32 {
+        match self {
+            Projection::ExpMap => EXP_MAP_OUTER_RADIUS,
+            Projection::Flat => res.0.hypot(res.1) / res.1,
+            Projection::Dome => DOME_MAX_ANGLE.tan() / FRAC_PI_2,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RES: (f32, f32) = (800.0, 600.0);
+
+    #[test]
+    fn flat_spans_two_units_vertically() {
+        let top = Projection::Flat.plane_offset((400.0, 0.0), RES).unwrap();
+        let bottom = Projection::Flat.plane_offset((400.0, 600.0), RES).unwrap();
+        assert_eq!(top, (0.0, -1.0));
+        assert_eq!(bottom, (0.0, 1.0));
+    }
+
+    #[test]
+    fn dome_centre_matches_flat() {
+        let px = (401.0, 299.0);
+        let flat = Projection::Flat.plane_offset(px, RES).unwrap();
+        let dome = Projection::Dome.plane_offset(px, RES).unwrap();
+        assert!((flat.0 - dome.0).abs() < 1e-4 && (flat.1 - dome.1).abs() < 1e-4);
+        assert_eq!(
+            Projection::Dome.plane_offset((400.0, 300.0), RES),
+            Some((0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn max_plane_offset_bounds_every_pixel() {
+        for projection in Projection::ALL {
+            let max = projection.max_plane_offset(RES);
+            let mut reached = 0.0f32;
+            for y in 0..=60 {
+                for x in 0..=80 {
+                    let px = (x as f32 * 10.0, y as f32 * 10.0);
+                    if let Some((dx, dy)) = projection.plane_offset(px, RES) {
+                        reached = reached.max(dx.hypot(dy));
+                    }
+                }
+            }
+            assert!(reached <= max * 1.0001, "{projection:?}: {reached} > {max}");
+            assert!(reached > max * 0.9, "{projection:?}: {reached} ≪ {max}");
+        }
+    }
+
+    #[test]
+    fn dome_masks_outside_inscribed_circle() {
+        // The circle's radius is half the short side (300 px).
+        assert!(Projection::Dome.plane_offset((400.0, 1.0), RES).is_some());
+        assert!(Projection::Dome.plane_offset((60.0, 300.0), RES).is_none());
+        assert!(Projection::Dome.plane_offset((0.0, 0.0), RES).is_none());
+    }
+
+    #[test]
+    fn dome_stretches_towards_the_rim() {
+        let near = Projection::Dome
+            .plane_offset((430.0, 300.0), RES)
+            .unwrap()
+            .0;
+        let far = Projection::Dome
+            .plane_offset((670.0, 300.0), RES)
+            .unwrap()
+            .0;
+        // Equal pixel steps cover ever more of the plane towards the horizon.
+        assert!(far / near > 270.0 / 30.0);
+        let rim = Projection::Dome
+            .plane_offset((700.0, 300.0), RES)
+            .unwrap()
+            .0;
+        assert!(rim.is_finite() && rim > far);
+    }
+
+    #[test]
+    fn exp_map_rows_are_rings_shrinking_geometrically() {
+        let r = |px: (f32, f32)| {
+            let (x, y) = Projection::ExpMap.plane_offset(px, RES).unwrap();
+            x.hypot(y)
+        };
+        assert!((r((0.0, 0.0)) - EXP_MAP_OUTER_RADIUS).abs() < 1e-5);
+        assert!((r((123.0, 40.0)) - r((456.0, 40.0))).abs() < 1e-5);
+        // Square pixels: one row down shrinks the radius as much as one
+        // column turns the angle, 2π / width.
+        let step = r((0.0, 101.0)) / r((0.0, 100.0));
+        assert!((step - (-TAU / RES.0).exp()).abs() < 1e-5);
+        // A quarter of the width is a quarter turn.
+        let (x, y) = Projection::ExpMap.plane_offset((200.0, 0.0), RES).unwrap();
+        assert!(x.abs() < 1e-5 && (y - EXP_MAP_OUTER_RADIUS).abs() < 1e-5);
+    }
+
+    #[test]
+    fn gpu_indices_are_distinct() {
+        let indices: Vec<u32> = Projection::ALL.iter().map(|p| p.gpu_index()).collect();
+        assert_eq!(indices, [0, 1, 2]);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::coloring::{Coloring, Trap};
+use crate::flame::FlameGen;
+use crate::{IfsGen, InverseJuliaGen, KleinianGen, MultibrotGen, NoiseFieldGen, Params};
+
+// ---------------------------------------------------------------------------
+// Bounds — the range a param is held to after modulation
+// ---------------------------------------------------------------------------
+//
+// A single NaN from a modulator, macro or audio route would otherwise reach
+// the uniforms and blank the image without a word.  [`sanitize`] runs after
+// modulation: non-finite values reset, and keys in [`REGISTRY`] are clamped
+// into their range as well.
+
+/// The range a param is clamped to and the value a non-finite one resets to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: f32,
+    pub max: f32,
+    pub reset: f32,
+}
+
+impl Bounds {
+    pub const fn new(min: f32, max: f32, reset: f32) -> Self {
+        Self { min, max, reset }
+    }
+
+    /// Any finite value; non-finite ones reset to 0.
+    pub const ANY: Bounds = Bounds::new(f32::MIN, f32::MAX, 0.0);
+
+    /// `value` clamped into range, or `reset` if it isn't finite.
+    pub fn apply(&self, value: f32) -> f32 {
+        if value.is_finite() {
+            value.clamp(self.min, self.max)
+        } else {
+            self.reset
+        }
+    }
+}
+
+/// Keys with a narrower range than "any finite value": the ones a shader
+/// divides by, takes a power or log of, or loops over.  `noise_octaves` is
+/// left out: [`NoiseFieldGen::octaves`] already clamps it when read.
+pub const REGISTRY: [(&str, Bounds); 16] = [
+    ("zoom", Bounds::new(1e-6, f32::MAX, 1.0)),
+    (
+        "multibrot_power",
+        Bounds::new(
+            MultibrotGen::MIN_POWER,
+            MultibrotGen::MAX_POWER,
+            MultibrotGen::DEFAULT_POWER,
+        ),
+    ),
+    (
+        "kleinian_trace_re",
+        Bounds::new(
+            KleinianGen::MIN_TRACE[0],
+            KleinianGen::MAX_TRACE[0],
+            KleinianGen::DEFAULT_TRACE[0],
+        ),
+    ),
+    (
+        "kleinian_trace_im",
+        Bounds::new(
+            KleinianGen::MIN_TRACE[1],
+            KleinianGen::MAX_TRACE[1],
+            KleinianGen::DEFAULT_TRACE[1],
+        ),
+    ),
+    (
+        "noise_scale",
+        Bounds::new(0.0, f32::MAX, NoiseFieldGen::DEFAULT_SCALE),
+    ),
+    (
+        "stalk_width",
+        Bounds::new(0.0, f32::MAX, Coloring::DEFAULT_STALK_WIDTH),
+    ),
+    (
+        "trap_radius",
+        Bounds::new(0.0, f32::MAX, Trap::DEFAULT_RADIUS),
+    ),
+    (
+        "ifs_exposure",
+        Bounds::new(0.0, f32::MAX, IfsGen::DEFAULT_EXPOSURE),
+    ),
+    (
+        "inverse_julia_exposure",
+        Bounds::new(0.0, f32::MAX, InverseJuliaGen::DEFAULT_EXPOSURE),
+    ),
+    (
+        "flame_exposure",
+        Bounds::new(0.0, f32::MAX, FlameGen::DEFAULT_EXPOSURE),
+    ),
+    (
+        "flame_gamma",
+        Bounds::new(0.1, f32::MAX, FlameGen::DEFAULT_GAMMA),
+    ),
+    (
+        "flame_vibrancy",
+        Bounds::new(0.0, 1.0, FlameGen::DEFAULT_VIBRANCY),
+    ),
+    ("audio_bass", Bounds::new(0.0, 1.0, 0.0)),
+    ("audio_mid", Bounds::new(0.0, 1.0, 0.0)),
+    ("audio_treble", Bounds::new(0.0, 1.0, 0.0)),
+    ("audio_level", Bounds::new(0.0, 1.0, 0.0)),
+];
+
+/// Bounds of `key`: its [`REGISTRY`] entry, or [`Bounds::ANY`].
+pub fn bounds(key: &str) -> Bounds {
+    REGISTRY
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map_or(Bounds::ANY, |&(_, b)| b)
+}
+
+// ---------------------------------------------------------------------------
+// sanitize — the pass itself
+// ---------------------------------------------------------------------------
+
+/// One value [`sanitize`] replaced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    pub key: String,
+    pub was: f32,
+    pub now: f32,
+}
+
+/// Reset non-finite values and clamp registered keys into their bounds,
+/// view fields (`zoom`, `center_x`, `center_y`) included.  Returns what
+/// changed, sorted by key; a healthy frame allocates nothing.
+pub fn sanitize(params: &mut Params) -> Vec<Repair> {
+    let mut repairs = Vec::new();
+    let mut fix = |key: &str, value: &mut f32| {
+        let now = bounds(key).apply(*value);
+        // NaN != NaN, so compare bits to keep a healthy frame quiet.
+        if now.to_bits() != value.to_bits() {
+            repairs.push(Repair {
+                key: key.to_string(),
+                was: *value,
+                now,
+            });
+            *value = now;
+        }
+    };
+    fix("zoom", &mut params.zoom);
+    fix("center_x", &mut params.center_x);
+    fix("center_y", &mut params.center_y);
+    for (key, value) in params.fields.iter_mut() {
+        fix(key, value);
+    }
+    repairs.sort_by(|a, b| a.key.cmp(&b.key));
+    repairs
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn healthy_params_are_untouched() {
+        let mut p = Params::default();
+        p.set("multibrot_power", 4.0);
+        p.set("hue_shift_amount", -20.0);
+        let before = p.clone();
+        assert!(sanitize(&mut p).is_empty());
+        assert_eq!(p.fields, before.fields);
+        assert_eq!(p.zoom, before.zoom);
+    }
+
+    #[test]
+    fn non_finite_values_reset_and_registered_keys_clamp() {
+        let mut p = Params {
+            zoom: f32::NAN,
+            center_x: f32::INFINITY,
+            ..Params::default()
+        };
+        p.set("multibrot_power", 99.0);
+        p.set("hue_shift_amount", f32::NEG_INFINITY);
+        p.set("flame_gamma", f32::NAN);
+        let repairs = sanitize(&mut p);
+        let keys: Vec<&str> = repairs.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "center_x",
+                "flame_gamma",
+                "hue_shift_amount",
+                "multibrot_power",
+                "zoom"
+            ]
+        );
+        assert_eq!(p.zoom, 1.0);
+        assert_eq!(p.center_x, 0.0);
+        assert_eq!(p.get("multibrot_power"), MultibrotGen::MAX_POWER);
+        assert_eq!(p.get("hue_shift_amount"), 0.0);
+        assert_eq!(p.get("flame_gamma"), FlameGen::DEFAULT_GAMMA);
+        assert!(repairs[2].was.is_infinite());
+    }
+
+    #[test]
+    fn unregistered_keys_accept_any_finite_value() {
+        assert_eq!(bounds("nonexistent"), Bounds::ANY);
+        assert_eq!(Bounds::ANY.apply(-1e30), -1e30);
+        assert_eq!(bounds("audio_bass").apply(1.5), 1.0);
+    }
+}
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::Params;
+
+// ---------------------------------------------------------------------------
+// ParamsSnapshot — the last frame's Params for threads off the render loop
+// ---------------------------------------------------------------------------
+//
+// The render thread owns the live `Params` and mutates them every frame.
+// Background threads (the preview server today) read a copy published once
+// per frame instead: publishing swaps one pointer and reading clones one
+// `Arc`, so neither side ever waits on the other, and a reader always sees
+// a whole frame's state, never one that is half-way through `tick`.
+
+/// One frame's parameters as published by [`ParamsPublisher::publish`].
+#[derive(Debug, Clone, Default)]
+pub struct ParamsSnapshot {
+    /// Publishes so far; 0 until the first frame, so readers can spot
+    /// fresh state.
+    pub seq: u64,
+    pub params: Params,
+}
+
+/// The render thread's end.  Publishes only while a [`ParamsReader`] is
+/// alive, so an unwatched frame pays nothing.
+pub struct ParamsPublisher {
+    shared: Arc<ArcSwap<ParamsSnapshot>>,
+    seq: u64,
+}
+
+/// A cheap, cloneable handle for background threads.
+#[derive(Clone)]
+pub struct ParamsReader {
+    shared: Arc<ArcSwap<ParamsSnapshot>>,
+}
+
+impl Default for ParamsPublisher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParamsPublisher {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(ArcSwap::from_pointee(ParamsSnapshot::default())),
+            seq: 0,
+        }
+    }
+
+    /// A new reader of this publisher's snapshots.
+    pub fn reader(&self) -> ParamsReader {
+        ParamsReader {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Whether any reader is still alive.
+    pub fn has_readers(&self) -> bool {
+        Arc::strong_count(&self.shared) > 1
+    }
+
+    /// Replace the snapshot with a copy of `params`; a no-op without readers.
+    pub fn publish(&mut self, params: &Params) {
+        if !self.has_readers() {
+            return;
+        }
+        self.seq += 1;
+        self.shared.store(Arc::new(ParamsSnapshot {
+            seq: self.seq,
+            params: params.clone(),
+        }));
+    }
+}
+
+impl ParamsReader {
+    /// The latest published snapshot.  Holding it doesn't hold up the
+    /// publisher; the next publish simply replaces it for later loads.
+    pub fn load(&self) -> Arc<ParamsSnapshot> {
+        self.shared.load_full()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params_at(time: f32) -> Params {
+        Params {
+            time,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn publishes_only_while_read() {
+        let mut publisher = ParamsPublisher::new();
+        publisher.publish(&params_at(1.0));
+        let reader = publisher.reader();
+        assert_eq!(reader.load().seq, 0);
+
+        publisher.publish(&params_at(2.0));
+        let snapshot = reader.load();
+        assert_eq!(snapshot.seq, 1);
+        assert_eq!(snapshot.params.time, 2.0);
+
+        drop(reader);
+        assert!(!publisher.has_readers());
+    }
+
+    #[test]
+    fn held_snapshot_survives_later_publishes() {
+        let mut publisher = ParamsPublisher::new();
+        let reader = publisher.reader();
+        publisher.publish(&params_at(1.0));
+        let held = reader.load();
+        publisher.publish(&params_at(2.0));
+        assert_eq!(held.params.time, 1.0);
+        assert_eq!(reader.load().params.time, 2.0);
+    }
+
+    #[test]
+    fn readers_on_other_threads_see_whole_frames() {
+        let mut publisher = ParamsPublisher::new();
+        let reader = publisher.reader();
+        let watcher = std::thread::spawn(move || {
+            let mut last = 0;
+            while last < 100 {
+                let snapshot = reader.load();
+                // Each frame sets `frame` and `time` together.
+                assert_eq!(snapshot.params.frame as f32, snapshot.params.time);
+                assert!(snapshot.seq >= last);
+                last = snapshot.seq;
+            }
+        });
+        for frame in 1..=100u64 {
+            let mut p = params_at(frame as f32);
+            p.frame = frame;
+            publisher.publish(&p);
+        }
+        watcher.join().unwrap();
+    }
+}
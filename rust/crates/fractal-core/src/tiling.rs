@@ -0,0 +1,174 @@
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::warp::OutputCorrection;
+
+// ---------------------------------------------------------------------------
+// Tiling — one virtual canvas split across several output windows
+// ---------------------------------------------------------------------------
+//
+// For LED walls and multi-projector rigs the patch renders once at the size
+// of the whole canvas, and each output window shows its own rectangle of it.
+
+/// Largest canvas side: wgpu's default limit for a 2D texture.
+pub const MAX_CANVAS_SIDE: u32 = 8192;
+
+/// One output window's part of the canvas, in canvas pixels from the
+/// top-left.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tile {
+    /// Shown in the window title.
+    #[serde(default)]
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Monitor (in the order the OS lists them) the window goes fullscreen
+    /// on; `None` opens a normal window.
+    #[serde(default)]
+    pub monitor: Option<usize>,
+    /// Warp and edge blend for this output alone.
+    #[serde(default)]
+    pub correction: OutputCorrection,
+}
+
+impl Tile {
+    /// The tile as a fraction of a `canvas`-sized frame: x, y, width,
+    /// height, top-left origin.
+    pub fn crop(&self, canvas: [u32; 2]) -> [f32; 4] {
+        let [w, h] = canvas.map(|v| v.max(1) as f32);
+        [
+            self.x as f32 / w,
+            self.y as f32 / h,
+            self.width as f32 / w,
+            self.height as f32 / h,
+        ]
+    }
+}
+
+/// The canvas size and the tiles cut from it, e.g. two side-by-side
+/// projectors overlapping by 192 pixels:
+/// `{"canvas": [3648, 1080], "tiles": [{"x": 0, "y": 0, "width": 1920,
+/// "height": 1080}, {"x": 1728, "y": 0, "width": 1920, "height": 1080}]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileLayout {
+    /// Width and height of the rendered frame.
+    pub canvas: [u32; 2],
+    pub tiles: Vec<Tile>,
+}
+
+impl TileLayout {
+    pub fn validate(&self) -> Result<(), String> {
+        let [cw, ch] = self.canvas;
+        if !(1..=MAX_CANVAS_SIDE).contains(&cw) || !(1..=MAX_CANVAS_SIDE).contains(&ch) {
+            return Err(format!(
+                "canvas is {cw}×{ch}; each side must be 1–{MAX_CANVAS_SIDE}"
+            ));
+        }
+        if self.tiles.is_empty() {
+            return Err("tile layout has no tiles".into());
+        }
+        for (i, tile) in self.tiles.iter().enumerate() {
+            let label = if tile.name.is_empty() {
+                format!("tile {}", i + 1)
+            } else {
+                format!("tile {:?}", tile.name)
+            };
+            if tile.width == 0 || tile.height == 0 {
+                return Err(format!("{label} is empty"));
+            }
+            if tile.x.saturating_add(tile.width) > cw || tile.y.saturating_add(tile.height) > ch {
+                return Err(format!("{label} reaches outside the {cw}×{ch} canvas"));
+            }
+            tile.correction
+                .validate()
+                .map_err(|e| format!("{label}: {e}"))?;
+        }
+        Ok(())
+    }
+
+    pub fn from_json(src: &str) -> io::Result<Self> {
+        let layout: Self =
+            serde_json::from_str(src).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        layout
+            .validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(layout)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Width / height of the canvas.
+    pub fn aspect(&self) -> f32 {
+        self.canvas[0].max(1) as f32 / self.canvas[1].max(1) as f32
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIDE_BY_SIDE: &str = r#"{
+        "canvas": [3648, 1080],
+        "tiles": [
+            {"name": "left", "x": 0, "y": 0, "width": 1920, "height": 1080,
+             "monitor": 1, "correction": {"blend": {"right": 0.1}}},
+            {"name": "right", "x": 1728, "y": 0, "width": 1920, "height": 1080}
+        ]
+    }"#;
+
+    #[test]
+    fn layout_parses_with_defaults() {
+        let layout = TileLayout::from_json(SIDE_BY_SIDE).unwrap();
+        assert_eq!(layout.tiles.len(), 2);
+        assert_eq!(layout.tiles[0].monitor, Some(1));
+        assert_eq!(layout.tiles[0].correction.blend.right, 0.1);
+        assert_eq!(layout.tiles[1].monitor, None);
+        assert_eq!(layout.tiles[1].correction, OutputCorrection::default());
+    }
+
+    #[test]
+    fn crop_is_the_tile_as_a_fraction_of_the_canvas() {
+        let layout = TileLayout::from_json(SIDE_BY_SIDE).unwrap();
+        let crop = layout.tiles[1].crop(layout.canvas);
+        assert!((crop[0] - 1728.0 / 3648.0).abs() < 1e-6);
+        assert_eq!(crop[1], 0.0);
+        assert!((crop[2] - 1920.0 / 3648.0).abs() < 1e-6);
+        assert_eq!(crop[3], 1.0);
+    }
+
+    #[test]
+    fn tiles_must_fit_the_canvas() {
+        let mut layout = TileLayout::from_json(SIDE_BY_SIDE).unwrap();
+        layout.tiles[1].x = 1800;
+        let err = layout.validate().unwrap_err();
+        assert!(err.contains("\"right\""), "{err}");
+
+        layout.tiles[1].x = 0;
+        layout.tiles[1].width = 0;
+        assert!(layout.validate().is_err());
+
+        layout.tiles.clear();
+        assert!(layout.validate().is_err());
+
+        let huge = r#"{"canvas": [20000, 1080], "tiles": []}"#;
+        assert!(TileLayout::from_json(huge).is_err());
+    }
+
+    #[test]
+    fn tile_corrections_are_validated() {
+        let bad = r#"{"canvas": [100, 100], "tiles": [
+            {"x": 0, "y": 0, "width": 100, "height": 100,
+             "correction": {"blend": {"left": 0.9}}}]}"#;
+        assert!(TileLayout::from_json(bad).is_err());
+    }
+}
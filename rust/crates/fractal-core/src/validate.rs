@@ -0,0 +1,484 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::audio::{MAX_BPM, MIN_BPM, ONSET_STRENGTH_KEY, ONSET_TIME_KEY};
+use crate::bundle::{self, BundleError, PatchBundle};
+use crate::flame::FlameGen;
+use crate::formula::Formula;
+use crate::macros::MACRO_KEY_PREFIX;
+use crate::modulators::LFO_RETRIGGER_KEY;
+use crate::patch::Patch;
+use crate::sanitize::{self, Bounds};
+use crate::{IfsGen, Modulator};
+
+// ---------------------------------------------------------------------------
+// Patch validation — lint a `.fractalpatch` before it reaches a show
+// ---------------------------------------------------------------------------
+//
+// Loading is forgiving: serde drops unknown keys, sanitizing clamps wild
+// values every frame, and routes to params nothing reads simply do nothing.
+// That keeps a show running but hides an author's typos, so [`validate`]
+// reports all of it against the serialized form.  Errors stop the bundle
+// loading at all; warnings load but won't do what the file says.
+
+/// How bad a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Loads, but part of the file is ignored, clamped or unused.
+    Warning,
+    /// The bundle won't load.
+    Error,
+}
+
+/// One finding of [`validate`]: where in the file, and what's wrong.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Dotted path into the JSON, e.g. `params.fields.julia_cx` or
+    /// `macros[0].targets[1].key`; empty for the file as a whole.
+    pub path: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        if self.path.is_empty() {
+            write!(f, "{severity}: {}", self.message)
+        } else {
+            write!(f, "{severity}: {}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Whether any of `diagnostics` stops the bundle loading.
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+/// Keys of a bundle's top-level object and of its `params`.
+const BUNDLE_KEYS: [&str; 12] = [
+    "version",
+    "preset",
+    "params",
+    "audio_routes",
+    "macros",
+    "flash",
+    "wavetables",
+    "gradient",
+    "wgsl",
+    "effect_wgsl",
+    "formula",
+    "meta",
+];
+const PARAMS_KEYS: [&str; 5] = ["center_x", "center_y", "zoom", "max_iter", "fields"];
+const META_KEYS: [&str; 5] = ["title", "author", "description", "bpm", "tags"];
+
+/// Keys the running app writes into params itself, so captured bundles
+/// carry them without any preset declaring them.
+const RUNTIME_KEYS: [&str; 3] = [ONSET_TIME_KEY, ONSET_STRENGTH_KEY, LFO_RETRIGGER_KEY];
+
+/// Check the `.fractalpatch` JSON in `src` and report, in file order:
+/// syntax and format errors, unknown keys, params nothing reads or outside
+/// their [`sanitize`] bounds, routes, macros and flash targets aimed at such
+/// params, wavetables with no LFO to play them, malformed gradients,
+/// formulas that don't parse or have no generator to run them, IFS maps
+/// or flame transforms past what the generators have room for, and a
+/// recommended tempo no beat clock follows.
+pub fn validate(src: &str) -> Vec<Diagnostic> {
+    let mut out = Vec::new();
+    let value: Value = match serde_json::from_str(src) {
+        Ok(value) => value,
+        Err(e) => return vec![Diagnostic::error("", format!("not valid JSON: {e}"))],
+    };
+    let Some(object) = value.as_object() else {
+        return vec![Diagnostic::error("", "expected a JSON object")];
+    };
+    for key in object.keys() {
+        if !BUNDLE_KEYS.contains(&key.as_str()) {
+            out.push(Diagnostic::warning(key.as_str(), "unknown key; ignored"));
+        }
+    }
+    for (section, keys) in [("params", &PARAMS_KEYS), ("meta", &META_KEYS)] {
+        let Some(fields) = object.get(section).and_then(Value::as_object) else {
+            continue;
+        };
+        for key in fields.keys() {
+            if !keys.contains(&key.as_str()) {
+                out.push(Diagnostic::warning(
+                    format!("{section}.{key}"),
+                    "unknown key; ignored",
+                ));
+            }
+        }
+    }
+
+    let value = match bundle::migrate(value) {
+        Ok(value) => value,
+        Err(BundleError::UnsupportedVersion(v)) => {
+            out.push(Diagnostic::error(
+                "version",
+                format!(
+                    "unsupported version {v} (this build reads up to {})",
+                    bundle::BUNDLE_VERSION
+                ),
+            ));
+            return out;
+        }
+        Err(e) => {
+            out.push(Diagnostic::error("", e.to_string()));
+            return out;
+        }
+    };
+    let bundle: PatchBundle = match serde_json::from_value(value) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            out.push(Diagnostic::error("", format!("not a patch bundle: {e}")));
+            return out;
+        }
+    };
+    let Ok(preset) = bundle.base_preset() else {
+        out.push(Diagnostic::error(
+            "preset",
+            format!("unknown base preset {:?}", bundle.preset),
+        ));
+        return out;
+    };
+    let mut base = preset.build();
+    // One frame, so the preset's modulators have written their targets.
+    base.tick(0.0);
+    let known = known_keys(&base, &bundle);
+
+    check_params(&bundle, &known, &mut out);
+    check_targets(&bundle, &known, &mut out);
+    check_wavetables(&bundle, &base, &mut out);
+    check_gradient(&bundle, &mut out);
+    check_formula(&bundle, &base, &mut out);
+    check_meta(&bundle, &mut out);
+    out
+}
+
+/// Every key something in a patch built from `bundle` reads or writes.
+fn known_keys(base: &Patch, bundle: &PatchBundle) -> BTreeSet<String> {
+    let mut known: BTreeSet<String> = base.params.fields.keys().cloned().collect();
+    known.extend(
+        base.generator
+            .gen_param_keys()
+            .iter()
+            .map(|k| k.to_string()),
+    );
+    known.extend(
+        base.effects
+            .iter()
+            .flat_map(|e| e.param_keys())
+            .map(str::to_string),
+    );
+    known.extend(base.modulators.iter().flat_map(|m| m.targets()));
+    known.extend(base.audio.targets());
+    known.extend(sanitize::REGISTRY.iter().map(|(k, _)| k.to_string()));
+    known.extend(RUNTIME_KEYS.iter().map(|k| k.to_string()));
+    known.extend(bundle.macros.iter().flatten().map(|m| m.key()));
+    known
+}
+
+/// The IFS map or flame transform `key` belongs to, with the most there is
+/// room for, if it's past the last.
+fn over_capacity(key: &str) -> Option<(&'static str, usize)> {
+    let indexed = |prefix: &str| {
+        let rest = key.strip_prefix(prefix)?;
+        let digits = rest.find('_').filter(|&n| n > 0)?;
+        rest[..digits].parse::<usize>().ok()
+    };
+    match (indexed("ifs"), indexed("flame")) {
+        (Some(i), _) if i >= IfsGen::MAX_MAPS => Some(("IFS maps", IfsGen::MAX_MAPS)),
+        (_, Some(i)) if i >= FlameGen::MAX_TRANSFORMS => {
+            Some(("flame transforms", FlameGen::MAX_TRANSFORMS))
+        }
+        _ => None,
+    }
+}
+
+/// A warning when `value` is outside the bounds of `key`.
+fn out_of_range(path: String, key: &str, value: f32) -> Option<Diagnostic> {
+    let bounds = sanitize::bounds(key);
+    (bounds.apply(value) != value).then(|| {
+        let Bounds { min, max, .. } = bounds;
+        let range = if max == f32::MAX {
+            format!(">= {min}")
+        } else {
+            format!("in {min}..={max}")
+        };
+        Diagnostic::warning(
+            path,
+            format!("{value} is out of range (must be {range}); clamped when played"),
+        )
+    })
+}
+
+fn check_params(bundle: &PatchBundle, known: &BTreeSet<String>, out: &mut Vec<Diagnostic>) {
+    let params = &bundle.params;
+    out.extend(out_of_range("params.zoom".into(), "zoom", params.zoom));
+    if params.max_iter == 0 {
+        out.push(Diagnostic::warning(
+            "params.max_iter",
+            "0 iterations renders nothing",
+        ));
+    }
+    for (key, &value) in &params.fields {
+        let path = format!("params.fields.{key}");
+        if let Some((what, max)) = over_capacity(key) {
+            out.push(Diagnostic::warning(
+                path,
+                format!("only {max} {what} are supported; ignored"),
+            ));
+        } else if !known.contains(key) {
+            out.push(Diagnostic::warning(path, "unknown param; nothing reads it"));
+        } else {
+            out.extend(out_of_range(path, key, value));
+        }
+    }
+}
+
+/// Audio routes, macro targets and flash targets: each names a param that
+/// must exist, and the values it writes must be in its bounds.
+fn check_targets(bundle: &PatchBundle, known: &BTreeSet<String>, out: &mut Vec<Diagnostic>) {
+    let mut target = |path: String, key: &str, values: &[f32]| {
+        if !known.contains(key) {
+            out.push(Diagnostic::warning(
+                path,
+                format!("targets unknown param {key:?}"),
+            ));
+            return;
+        }
+        for &value in values {
+            if let Some(d) = out_of_range(path.clone(), key, value) {
+                out.push(d);
+                return;
+            }
+        }
+    };
+    for (i, route) in bundle.audio_routes.iter().flatten().enumerate() {
+        target(
+            format!("audio_routes[{i}]"),
+            &route.target,
+            &[route.min, route.max],
+        );
+    }
+    for (i, m) in bundle.macros.iter().flatten().enumerate() {
+        for (j, t) in m.targets.iter().enumerate() {
+            target(format!("macros[{i}].targets[{j}]"), &t.key, &[t.min, t.max]);
+        }
+    }
+    for (i, flash) in bundle.flash.iter().flatten().enumerate() {
+        target(format!("flash[{i}]"), &flash.key, &[flash.value]);
+    }
+    for (i, m) in bundle.macros.iter().flatten().enumerate() {
+        if m.targets.is_empty() {
+            out.push(Diagnostic::warning(
+                format!("macros[{i}]"),
+                format!("{MACRO_KEY_PREFIX}{} moves nothing", m.name),
+            ));
+        }
+    }
+}
+
+/// Wavetables replace the waveform of the preset LFO on their key; one
+/// with no such LFO is never played.
+fn check_wavetables(bundle: &PatchBundle, base: &Patch, out: &mut Vec<Diagnostic>) {
+    let lfos: BTreeSet<&str> = base.lfos().into_iter().map(|(_, lfo)| lfo.target).collect();
+    for (key, table) in bundle.wavetables.iter().flatten() {
+        let path = format!("wavetables.{key}");
+        if !lfos.contains(key.as_str()) {
+            out.push(Diagnostic::warning(
+                path,
+                format!("no LFO of preset {:?} targets {key:?}", bundle.preset),
+            ));
+        } else if table.points.is_empty() {
+            out.push(Diagnostic::warning(path, "no points; the LFO holds at 0"));
+        }
+    }
+}
+
+/// A gradient needs two stops to blend between, each a linear RGB colour.
+fn check_gradient(bundle: &PatchBundle, out: &mut Vec<Diagnostic>) {
+    let Some(stops) = &bundle.gradient else {
+        return;
+    };
+    if stops.len() < 2 {
+        out.push(Diagnostic::warning(
+            "gradient",
+            format!("{} stop(s); a gradient needs at least 2", stops.len()),
+        ));
+    }
+    for (i, stop) in stops.iter().enumerate() {
+        if stop.iter().any(|c| !(0.0..=1.0).contains(c)) {
+            out.push(Diagnostic::warning(
+                format!("gradient[{i}]"),
+                "colour components must be in 0..=1",
+            ));
+        }
+    }
+}
+
+/// A recommended tempo must be one the beat clock can follow.
+fn check_meta(bundle: &PatchBundle, out: &mut Vec<Diagnostic>) {
+    let Some(meta) = &bundle.meta else {
+        return;
+    };
+    if let (Some(bpm), None) = (meta.bpm, meta.bpm()) {
+        out.push(Diagnostic::warning(
+            "meta.bpm",
+            format!("{bpm} BPM is out of range (must be in {MIN_BPM}..={MAX_BPM}); ignored"),
+        ));
+    }
+}
+
+/// A formula must parse, and only a custom formula generator runs one.
+fn check_formula(bundle: &PatchBundle, base: &Patch, out: &mut Vec<Diagnostic>) {
+    let Some(src) = &bundle.formula else {
+        return;
+    };
+    if let Err(e) = Formula::parse(src) {
+        out.push(Diagnostic::error("formula", e.to_string()));
+    } else if base.generator.formula().is_none() {
+        out.push(Diagnostic::warning(
+            "formula",
+            format!("preset {:?} has no custom formula; ignored", bundle.preset),
+        ));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets::Preset;
+    use crate::Params;
+
+    fn paths(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.path.as_str()).collect()
+    }
+
+    #[test]
+    fn captured_bundles_are_clean() {
+        for preset in Preset::ALL {
+            let mut patch = preset.build();
+            for _ in 0..3 {
+                patch.tick(0.016);
+            }
+            let bundle = PatchBundle::capture_patch(preset, &patch);
+            let diagnostics = validate(&bundle.to_json());
+            assert!(diagnostics.is_empty(), "{}: {diagnostics:?}", preset.name());
+        }
+    }
+
+    #[test]
+    fn broken_files_are_errors() {
+        assert!(has_errors(&validate("{")));
+        assert!(has_errors(&validate("[]")));
+        let d = validate(
+            r#"{"version": 1, "preset": "Nope", "params": {"center_x": 0, "center_y": 0, "zoom": 1, "max_iter": 9}}"#,
+        );
+        assert_eq!(paths(&d), ["preset"]);
+        let d = validate(r#"{"version": 7, "preset": "ClassicMandelbrot"}"#);
+        assert_eq!(paths(&d), ["version"]);
+        let d = validate(r#"{"version": 2, "preset": "ClassicMandelbrot"}"#);
+        assert_eq!(d.len(), 1, "{d:?}");
+        assert!(d[0].message.starts_with("not a patch bundle"));
+    }
+
+    #[test]
+    fn typos_ranges_and_dangling_targets_are_warnings() {
+        let mut bundle = PatchBundle::capture(Preset::ClassicMandelbrot, &Params::default());
+        bundle.params.fields.insert("julia_xc".into(), 0.3);
+        bundle.params.fields.insert("multibrot_power".into(), 99.0);
+        bundle.params.fields.insert("ifs7_a".into(), 1.0);
+        bundle.flash = Some(vec![crate::modulators::FlashTarget {
+            key: "brightnes_amount".into(),
+            value: 0.5,
+        }]);
+        bundle.gradient = Some(vec![[2.0, 0.0, 0.0]]);
+        let mut json: Value = serde_json::from_str(&bundle.to_json()).unwrap();
+        json["colour"] = Value::Bool(true);
+        let d = validate(&json.to_string());
+        assert!(!has_errors(&d), "{d:?}");
+        assert_eq!(
+            paths(&d),
+            [
+                "colour",
+                "params.fields.ifs7_a",
+                "params.fields.julia_xc",
+                "params.fields.multibrot_power",
+                "flash[0]",
+                "gradient",
+                "gradient[0]",
+            ]
+        );
+        assert_eq!(
+            d[1].to_string(),
+            "warning: params.fields.ifs7_a: only 4 IFS maps are supported; ignored"
+        );
+    }
+
+    #[test]
+    fn wavetables_need_an_lfo_to_replace() {
+        let json = r#"{"version": 1, "preset": "Classic Mandelbrot",
+            "params": {"center_x": 0, "center_y": 0, "zoom": 1, "max_iter": 100},
+            "wavetables": {"nobody": {"points": [0, 1]}}}"#;
+        assert_eq!(paths(&validate(json)), ["wavetables.nobody"]);
+    }
+
+    #[test]
+    fn meta_typos_and_wild_tempos_are_warnings() {
+        let mut bundle = PatchBundle::capture(Preset::ClassicMandelbrot, &Params::default());
+        bundle.meta = Some(crate::meta::PatchMeta::default().with_bpm(900.0));
+        let mut json: Value = serde_json::from_str(&bundle.to_json()).unwrap();
+        json["meta"]["autor"] = Value::String("cmh".into());
+        let d = validate(&json.to_string());
+        assert!(!has_errors(&d), "{d:?}");
+        assert_eq!(paths(&d), ["meta.autor", "meta.bpm"]);
+    }
+
+    #[test]
+    fn formulas_must_parse_and_have_a_generator() {
+        let mut bundle = PatchBundle::capture(Preset::SineFormula, &Params::default());
+        bundle.formula = Some("z^2 + (c".into());
+        let d = validate(&bundle.to_json());
+        assert!(has_errors(&d));
+        assert_eq!(
+            d.last().unwrap().to_string(),
+            "error: formula: column 9: expected `)` to close column 7, found end of formula"
+        );
+        bundle.preset = Preset::ClassicMandelbrot.id();
+        bundle.formula = Some("z^2 + c".into());
+        let d = validate(&bundle.to_json());
+        assert!(!has_errors(&d), "{d:?}");
+        assert_eq!(paths(&d).last(), Some(&"formula"));
+    }
+}
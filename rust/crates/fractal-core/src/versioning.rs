@@ -0,0 +1,188 @@
+use std::io;
+use std::path::Path;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+// ---------------------------------------------------------------------------
+// Format — a versioned JSON file and the steps that upgrade older copies
+// ---------------------------------------------------------------------------
+//
+// Every file the app writes carries a top-level `version`.  Older files are
+// upgraded as raw JSON before serde sees them, so the structs only ever
+// describe the current layout; a file with no `version` predates versioning
+// and counts as version 1.
+
+/// Rewrites a version-`n` file, as its JSON object, into version `n + 1`.
+pub type Migration = fn(&mut Map<String, Value>);
+
+/// One kind of persisted file.  Its current version is one past the last
+/// of its [`migrations`](Self::migrations).
+#[derive(Debug, Clone, Copy)]
+pub struct Format {
+    /// What the file holds, for error messages: `"settings"`.
+    pub name: &'static str,
+    /// `migrations[n - 1]` upgrades version `n` to `n + 1`.
+    pub migrations: &'static [Migration],
+}
+
+/// A file written by a newer build, or with a version that never existed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedVersion {
+    pub format: &'static str,
+    pub found: u32,
+    pub current: u32,
+}
+
+impl std::fmt::Display for UnsupportedVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unsupported {} version {} (expected 1..={})",
+            self.format, self.found, self.current
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedVersion {}
+
+impl From<UnsupportedVersion> for io::Error {
+    fn from(e: UnsupportedVersion) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+impl Format {
+    pub const fn new(name: &'static str, migrations: &'static [Migration]) -> Self {
+        Self { name, migrations }
+    }
+
+    pub const fn version(&self) -> u32 {
+        self.migrations.len() as u32 + 1
+    }
+
+    /// Write the current version into `value`, if it is an object.
+    pub fn stamp(&self, value: &mut Value) {
+        if let Some(object) = value.as_object_mut() {
+            object.insert("version".into(), self.version().into());
+        }
+    }
+
+    /// Upgrade `value` to the current version in place.  Anything that isn't
+    /// an object is left for serde to reject with a proper message.
+    pub fn upgrade(&self, value: &mut Value) -> Result<(), UnsupportedVersion> {
+        let Some(object) = value.as_object_mut() else {
+            return Ok(());
+        };
+        let found = match object.get("version") {
+            None => 1,
+            Some(v) => v
+                .as_u64()
+                .map_or(0, |v| u32::try_from(v).unwrap_or(u32::MAX)),
+        };
+        if found == 0 || found > self.version() {
+            return Err(UnsupportedVersion {
+                format: self.name,
+                found,
+                current: self.version(),
+            });
+        }
+        for step in &self.migrations[found as usize - 1..] {
+            step(object);
+        }
+        self.stamp(value);
+        Ok(())
+    }
+
+    /// Serialize `data` with the current version stamped on it.
+    pub fn to_json<T: Serialize>(&self, data: &T) -> String {
+        let mut value = serde_json::to_value(data)
+            .unwrap_or_else(|e| panic!("{} is not serialisable: {e}", self.name));
+        self.stamp(&mut value);
+        serde_json::to_string_pretty(&value).expect("a JSON value is always serialisable")
+    }
+
+    /// Parse a file saved by this or any earlier version.
+    pub fn from_json<T: DeserializeOwned>(&self, src: &str) -> io::Result<T> {
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+        let mut value: Value = serde_json::from_str(src).map_err(invalid)?;
+        self.upgrade(&mut value)?;
+        serde_json::from_value(value).map_err(invalid)
+    }
+
+    /// Load from `path`; a missing file yields `T::default()`.
+    pub fn load<T: DeserializeOwned + Default>(&self, path: &Path) -> io::Result<T> {
+        match std::fs::read_to_string(path) {
+            Ok(src) => self.from_json(&src),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write to `path`, creating parent directories as needed.
+    pub fn save<T: Serialize>(&self, path: &Path, data: &T) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_json(data))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Doc {
+        size: u32,
+    }
+
+    /// v1 → v2: `width` was renamed `size`.
+    fn v1_size(doc: &mut Map<String, Value>) {
+        if let Some(width) = doc.remove("width") {
+            doc.insert("size".into(), width);
+        }
+    }
+
+    const DOC: Format = Format::new("doc", &[v1_size]);
+
+    #[test]
+    fn round_trip_is_stamped_with_the_current_version() {
+        let json = DOC.to_json(&Doc { size: 3 });
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], 2);
+        assert_eq!(DOC.from_json::<Doc>(&json).unwrap(), Doc { size: 3 });
+    }
+
+    #[test]
+    fn unversioned_files_are_version_one() {
+        let doc: Doc = DOC.from_json(r#"{ "width": 7 }"#).unwrap();
+        assert_eq!(doc, Doc { size: 7 });
+        let mut v1 = json!({ "version": 1, "width": 2 });
+        DOC.upgrade(&mut v1).unwrap();
+        assert_eq!(v1, json!({ "version": 2, "size": 2 }));
+    }
+
+    #[test]
+    fn future_and_zero_versions_are_rejected() {
+        for found in [0, 3] {
+            let mut value = json!({ "version": found, "size": 1 });
+            assert_eq!(
+                DOC.upgrade(&mut value),
+                Err(UnsupportedVersion {
+                    format: "doc",
+                    found,
+                    current: 2
+                })
+            );
+        }
+        let err = DOC.from_json::<Doc>(r#"{ "version": 9 }"#).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,290 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+
+use std::mem::discriminant;
+
+use crate::{presets::Preset, GeneratorKind, HybridSchedule, Params};
+
+/// Current encoding version, stored in the first byte of the payload.
+const VIEW_CODE_VERSION: u8 = 1;
+
+/// Payload size: version, generator, center x/y, zoom, max_iter.
+const PAYLOAD_LEN: usize = 1 + 1 + 4 + 4 + 4 + 4;
+
+// ---------------------------------------------------------------------------
+// ViewState — an exact location, shareable as a short string
+// ---------------------------------------------------------------------------
+
+/// Generator, center, zoom and iteration count — enough to reproduce a
+/// location.  Encodes to a 24-character URL-safe base64 string.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewState {
+    pub generator: GeneratorKind,
+    pub center_x: f32,
+    pub center_y: f32,
+    pub zoom: f32,
+    pub max_iter: u32,
+}
+
+/// Why a view code could not be decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViewCodeError {
+    InvalidBase64,
+    WrongLength(usize),
+    UnsupportedVersion(u8),
+    UnknownGenerator(u8),
+}
+
+impl std::fmt::Display for ViewCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ViewCodeError::InvalidBase64 => write!(f, "not a valid view code"),
+            ViewCodeError::WrongLength(n) => {
+                write!(f, "view code payload is {n} bytes (expected {PAYLOAD_LEN})")
+            }
+            ViewCodeError::UnsupportedVersion(v) => write!(f, "unsupported view code version {v}"),
+            ViewCodeError::UnknownGenerator(g) => write!(f, "unknown generator id {g}"),
+        }
+    }
+}
+
+impl std::error::Error for ViewCodeError {}
+
+fn generator_id(kind: GeneratorKind) -> u8 {
+    match kind {
+        GeneratorKind::Mandelbrot => 0,
+        GeneratorKind::Julia => 1,
+        GeneratorKind::BurningShip => 2,
+        GeneratorKind::NoiseField => 3,
+        GeneratorKind::Video => 4,
+        GeneratorKind::Image => 5,
+        GeneratorKind::TestPattern => 6,
+        GeneratorKind::InverseJulia => 7,
+        GeneratorKind::Multibrot => 8,
+        GeneratorKind::Celtic => 9,
+        GeneratorKind::Buffalo => 10,
+        GeneratorKind::Ifs => 11,
+        GeneratorKind::Flame => 12,
+        GeneratorKind::Kleinian => 13,
+        GeneratorKind::Hybrid(_) => 14,
+        GeneratorKind::CustomFormula => 15,
+        GeneratorKind::SimplexField => 16,
+        GeneratorKind::WorleyField => 17,
+        GeneratorKind::ReactionDiffusion => 18,
+        GeneratorKind::DomainWarp => 19,
+        GeneratorKind::Plasma => 20,
+    }
+}
+
+fn generator_from_id(id: u8) -> Option<GeneratorKind> {
+    match id {
+        0 => Some(GeneratorKind::Mandelbrot),
+        1 => Some(GeneratorKind::Julia),
+        2 => Some(GeneratorKind::BurningShip),
+        3 => Some(GeneratorKind::NoiseField),
+        4 => Some(GeneratorKind::Video),
+        5 => Some(GeneratorKind::Image),
+        6 => Some(GeneratorKind::TestPattern),
+        7 => Some(GeneratorKind::InverseJulia),
+        8 => Some(GeneratorKind::Multibrot),
+        9 => Some(GeneratorKind::Celtic),
+        10 => Some(GeneratorKind::Buffalo),
+        11 => Some(GeneratorKind::Ifs),
+        12 => Some(GeneratorKind::Flame),
+        13 => Some(GeneratorKind::Kleinian),
+        // The payload has no room for a schedule, so a hybrid comes back
+        // with the default one.
+        14 => Some(GeneratorKind::Hybrid(HybridSchedule::DEFAULT)),
+        // Nor for the formula, so it comes back as the preset's.
+        15 => Some(GeneratorKind::CustomFormula),
+        16 => Some(GeneratorKind::SimplexField),
+        17 => Some(GeneratorKind::WorleyField),
+        18 => Some(GeneratorKind::ReactionDiffusion),
+        19 => Some(GeneratorKind::DomainWarp),
+        20 => Some(GeneratorKind::Plasma),
+        _ => None,
+    }
+}
+
+impl ViewState {
+    pub fn capture(generator: GeneratorKind, params: &Params) -> Self {
+        Self {
+            generator,
+            center_x: params.center_x,
+            center_y: params.center_y,
+            zoom: params.zoom,
+            max_iter: params.max_iter,
+        }
+    }
+
+    /// Overwrite the view fields of `params`.
+    pub fn apply(&self, params: &mut Params) {
+        params.center_x = self.center_x;
+        params.center_y = self.center_y;
+        params.zoom = self.zoom;
+        params.max_iter = self.max_iter;
+    }
+
+    /// First preset in [`Preset::ALL`] that uses this view's generator,
+    /// whatever its hybrid schedule.
+    pub fn preset(&self) -> Preset {
+        let generator = discriminant(&self.generator);
+        Preset::ALL
+            .into_iter()
+            .find(|p| discriminant(&p.build().generator.kind()) == generator)
+            .expect("every generator is used by at least one preset")
+    }
+
+    pub fn encode(&self) -> String {
+        let mut buf = Vec::with_capacity(PAYLOAD_LEN);
+        buf.push(VIEW_CODE_VERSION);
+        buf.push(generator_id(self.generator));
+        buf.extend_from_slice(&self.center_x.to_le_bytes());
+        buf.extend_from_slice(&self.center_y.to_le_bytes());
+        buf.extend_from_slice(&self.zoom.to_le_bytes());
+        buf.extend_from_slice(&self.max_iter.to_le_bytes());
+        URL_SAFE_NO_PAD.encode(buf)
+    }
+
+    /// Decode a code produced by [`encode`](Self::encode).  Surrounding
+    /// whitespace is ignored so pasted text works as-is.
+    pub fn decode(code: &str) -> Result<Self, ViewCodeError> {
+        let buf = URL_SAFE_NO_PAD
+            .decode(code.trim())
+            .map_err(|_| ViewCodeError::InvalidBase64)?;
+        if buf.len() != PAYLOAD_LEN {
+            return Err(ViewCodeError::WrongLength(buf.len()));
+        }
+        if buf[0] != VIEW_CODE_VERSION {
+            return Err(ViewCodeError::UnsupportedVersion(buf[0]));
+        }
+        let generator = generator_from_id(buf[1]).ok_or(ViewCodeError::UnknownGenerator(buf[1]))?;
+        let word = |i: usize| [buf[i], buf[i + 1], buf[i + 2], buf[i + 3]];
+        Ok(Self {
+            generator,
+            center_x: f32::from_le_bytes(word(2)),
+            center_y: f32::from_le_bytes(word(6)),
+            zoom: f32::from_le_bytes(word(10)),
+            max_iter: u32::from_le_bytes(word(14)),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ViewState {
+        ViewState {
+            generator: GeneratorKind::BurningShip,
+            center_x: -1.7443,
+            center_y: -0.0173,
+            zoom: 512.0,
+            max_iter: 350,
+        }
+    }
+
+    #[test]
+    fn round_trip_is_exact() {
+        let v = sample();
+        assert_eq!(ViewState::decode(&v.encode()).unwrap(), v);
+    }
+
+    #[test]
+    fn code_is_short_and_url_safe() {
+        let code = sample().encode();
+        assert_eq!(code.len(), 24);
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn decode_trims_whitespace() {
+        let code = format!("  {}\n", sample().encode());
+        assert_eq!(ViewState::decode(&code).unwrap(), sample());
+    }
+
+    #[test]
+    fn decode_rejects_garbage() {
+        assert_eq!(
+            ViewState::decode("not base64!"),
+            Err(ViewCodeError::InvalidBase64)
+        );
+        assert_eq!(
+            ViewState::decode("AAAA"),
+            Err(ViewCodeError::WrongLength(3))
+        );
+    }
+
+    #[test]
+    fn decode_rejects_unknown_generator() {
+        let mut buf = URL_SAFE_NO_PAD.decode(sample().encode()).unwrap();
+        buf[1] = 99;
+        let code = URL_SAFE_NO_PAD.encode(buf);
+        assert_eq!(
+            ViewState::decode(&code),
+            Err(ViewCodeError::UnknownGenerator(99))
+        );
+    }
+
+    #[test]
+    fn every_generator_round_trips_and_has_a_preset() {
+        for g in [
+            GeneratorKind::Mandelbrot,
+            GeneratorKind::Julia,
+            GeneratorKind::BurningShip,
+            GeneratorKind::NoiseField,
+            GeneratorKind::Video,
+            GeneratorKind::Image,
+            GeneratorKind::TestPattern,
+            GeneratorKind::InverseJulia,
+            GeneratorKind::Multibrot,
+            GeneratorKind::Celtic,
+            GeneratorKind::Buffalo,
+            GeneratorKind::Ifs,
+            GeneratorKind::Flame,
+            GeneratorKind::Kleinian,
+            GeneratorKind::Hybrid(HybridSchedule::DEFAULT),
+            GeneratorKind::CustomFormula,
+            GeneratorKind::SimplexField,
+            GeneratorKind::WorleyField,
+            GeneratorKind::ReactionDiffusion,
+            GeneratorKind::DomainWarp,
+            GeneratorKind::Plasma,
+        ] {
+            let v = ViewState {
+                generator: g,
+                ..sample()
+            };
+            assert_eq!(ViewState::decode(&v.encode()).unwrap().generator, g);
+            assert_eq!(v.preset().build().generator.kind(), g);
+        }
+    }
+
+    #[test]
+    fn hybrid_decodes_with_the_default_schedule() {
+        let v = ViewState {
+            generator: GeneratorKind::Hybrid(HybridSchedule::parse("MBB").unwrap()),
+            ..sample()
+        };
+        let decoded = ViewState::decode(&v.encode()).unwrap();
+        assert_eq!(
+            decoded.generator,
+            GeneratorKind::Hybrid(HybridSchedule::DEFAULT)
+        );
+        assert_eq!(v.preset(), decoded.preset());
+    }
+
+    #[test]
+    fn apply_sets_view_fields() {
+        let mut p = Params::default();
+        sample().apply(&mut p);
+        assert_eq!(p.zoom, 512.0);
+        assert_eq!(p.max_iter, 350);
+        assert_eq!(p.center_x, -1.7443);
+    }
+}
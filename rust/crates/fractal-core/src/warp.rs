@@ -0,0 +1,245 @@
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// Output correction — mesh warp and edge blend for projection mapping
+// ---------------------------------------------------------------------------
+//
+// Coordinates here are normalised to the output frame with the origin at the
+// top-left: (0, 0) is the top-left corner, (1, 1) the bottom-right.
+
+/// Largest warp grid side; keeps the GPU lookup texture tiny.
+pub const MAX_WARP_SIDE: u32 = 64;
+
+/// A `cols`×`rows` mesh over the output.  Each node holds the position in
+/// the rendered frame shown at that node; between nodes it is bilinearly
+/// interpolated.  The identity grid places every node at its own position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WarpGrid {
+    pub cols: u32,
+    pub rows: u32,
+    /// Row-major from the top-left node.
+    pub points: Vec<[f32; 2]>,
+}
+
+impl WarpGrid {
+    pub fn identity(cols: u32, rows: u32) -> Self {
+        let (cols, rows) = (cols.max(2), rows.max(2));
+        let points = (0..rows)
+            .flat_map(|r| {
+                (0..cols).map(move |c| [c as f32 / (cols - 1) as f32, r as f32 / (rows - 1) as f32])
+            })
+            .collect();
+        Self { cols, rows, points }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let sides = 2..=MAX_WARP_SIDE;
+        if !sides.contains(&self.cols) || !sides.contains(&self.rows) {
+            return Err(format!(
+                "warp grid is {}×{}; each side must be 2–{MAX_WARP_SIDE}",
+                self.cols, self.rows
+            ));
+        }
+        if self.points.len() != (self.cols * self.rows) as usize {
+            return Err(format!(
+                "warp grid {}×{} needs {} points, got {}",
+                self.cols,
+                self.rows,
+                self.cols * self.rows,
+                self.points.len()
+            ));
+        }
+        if self.points.iter().flatten().any(|v| !v.is_finite()) {
+            return Err("warp grid has a non-finite point".into());
+        }
+        Ok(())
+    }
+
+    /// Frame position shown at output position `s`.  Mirrors `warp` in the
+    /// output shader.
+    pub fn sample(&self, s: [f32; 2]) -> [f32; 2] {
+        let cell = |v: f32, n: u32| {
+            let g = v.clamp(0.0, 1.0) * (n - 1) as f32;
+            let i = (g.floor() as u32).min(n - 2);
+            (i, g - i as f32)
+        };
+        let ((cx, fx), (cy, fy)) = (cell(s[0], self.cols), cell(s[1], self.rows));
+        let at = |c: u32, r: u32| self.points[(r * self.cols + c) as usize];
+        let lerp =
+            |a: [f32; 2], b: [f32; 2], t: f32| [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t];
+        let top = lerp(at(cx, cy), at(cx + 1, cy), fx);
+        let bottom = lerp(at(cx, cy + 1), at(cx + 1, cy + 1), fx);
+        lerp(top, bottom, fy)
+    }
+}
+
+/// Brightness ramps at the output edges, where neighbouring projectors
+/// overlap.  Each width is a fraction of the output (0 = no ramp); across it
+/// the light rises from 0 to full as `t^gamma`, so two matching ramps in the
+/// overlap add up to one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EdgeBlend {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+    /// Shape of the ramps; tune until the overlap matches its surroundings.
+    pub gamma: f32,
+}
+
+impl Default for EdgeBlend {
+    fn default() -> Self {
+        Self {
+            left: 0.0,
+            right: 0.0,
+            top: 0.0,
+            bottom: 0.0,
+            gamma: 1.0,
+        }
+    }
+}
+
+impl EdgeBlend {
+    pub fn is_off(&self) -> bool {
+        [self.left, self.right, self.top, self.bottom]
+            .iter()
+            .all(|w| *w <= 0.0)
+    }
+
+    /// Brightness factor at output position `s`.  Mirrors `edge_blend` in
+    /// the output shader.
+    pub fn factor(&self, s: [f32; 2]) -> f32 {
+        let ramp = |distance: f32, width: f32| {
+            if width > 0.0 {
+                (distance / width).clamp(0.0, 1.0).powf(self.gamma)
+            } else {
+                1.0
+            }
+        };
+        ramp(s[0], self.left)
+            * ramp(1.0 - s[0], self.right)
+            * ramp(s[1], self.top)
+            * ramp(1.0 - s[1], self.bottom)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let widths = [self.left, self.right, self.top, self.bottom];
+        if widths.iter().any(|w| !(0.0..=0.5).contains(w)) {
+            return Err("edge blend widths must be 0–0.5".into());
+        }
+        if !(self.gamma.is_finite() && self.gamma > 0.0) {
+            return Err("edge blend gamma must be positive".into());
+        }
+        Ok(())
+    }
+}
+
+/// Warp and blend applied as the final frame is drawn to the window, e.g.
+/// `{"warp": {"cols": 2, "rows": 2, "points": [[0,0],[1,0],[0,1],[1,1]]},
+/// "blend": {"right": 0.15}}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputCorrection {
+    /// `None` shows the frame unwarped.
+    pub warp: Option<WarpGrid>,
+    pub blend: EdgeBlend,
+}
+
+impl OutputCorrection {
+    pub fn from_json(src: &str) -> io::Result<Self> {
+        let correction: Self =
+            serde_json::from_str(src).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        correction
+            .validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(correction)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.warp
+            .as_ref()
+            .map_or(Ok(()), WarpGrid::validate)
+            .and_then(|()| self.blend.validate())
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Frame position shown at output position `s` and its brightness.
+    pub fn apply(&self, s: [f32; 2]) -> ([f32; 2], f32) {
+        let source = self.warp.as_ref().map_or(s, |w| w.sample(s));
+        (source, self.blend.factor(s))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: [f32; 2], b: [f32; 2]) -> bool {
+        (a[0] - b[0]).abs() < 1e-5 && (a[1] - b[1]).abs() < 1e-5
+    }
+
+    #[test]
+    fn identity_grid_maps_every_point_to_itself() {
+        let grid = WarpGrid::identity(4, 3);
+        assert_eq!(grid.points.len(), 12);
+        for s in [[0.0, 0.0], [0.3, 0.7], [1.0, 1.0], [0.5, 0.25]] {
+            assert!(close(grid.sample(s), s), "{s:?}");
+        }
+    }
+
+    #[test]
+    fn moved_corner_pulls_its_cell() {
+        let mut grid = WarpGrid::identity(2, 2);
+        grid.points[3] = [0.8, 0.9]; // bottom-right node
+        assert!(close(grid.sample([1.0, 1.0]), [0.8, 0.9]));
+        assert!(close(grid.sample([0.0, 0.0]), [0.0, 0.0]));
+        // Halfway along the bottom edge: midway to the moved node.
+        assert!(close(grid.sample([0.5, 1.0]), [0.4, 0.95]));
+    }
+
+    #[test]
+    fn overlapping_blends_add_up_to_one() {
+        let left_projector = EdgeBlend {
+            right: 0.2,
+            ..Default::default()
+        };
+        let right_projector = EdgeBlend {
+            left: 0.2,
+            ..Default::default()
+        };
+        // The last fifth of the left output shows the same content as the
+        // first fifth of the right one.
+        for t in [0.0, 0.25, 0.5, 0.9] {
+            let sum = left_projector.factor([0.8 + 0.2 * t, 0.5])
+                + right_projector.factor([0.2 * t, 0.5]);
+            assert!((sum - 1.0).abs() < 1e-5, "{t}: {sum}");
+        }
+        assert_eq!(left_projector.factor([0.5, 0.5]), 1.0);
+        assert!(EdgeBlend::default().is_off());
+    }
+
+    #[test]
+    fn json_fills_defaults_and_validates() {
+        let c = OutputCorrection::from_json(r#"{"blend": {"right": 0.15}}"#).unwrap();
+        assert_eq!(c.warp, None);
+        assert_eq!(c.blend.right, 0.15);
+        assert_eq!(c.blend.gamma, 1.0);
+        assert_eq!(c.apply([0.5, 0.5]), ([0.5, 0.5], 1.0));
+
+        let short = r#"{"warp": {"cols": 2, "rows": 2, "points": [[0,0],[1,0]]}}"#;
+        assert!(OutputCorrection::from_json(short).is_err());
+        assert!(OutputCorrection::from_json(r#"{"blend": {"left": 0.8}}"#).is_err());
+        assert!(OutputCorrection::from_json(r#"{"blend": {"gamma": 0}}"#).is_err());
+    }
+}
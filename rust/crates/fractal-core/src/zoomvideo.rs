@@ -0,0 +1,435 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::tiling::MAX_CANVAS_SIDE;
+
+// ---------------------------------------------------------------------------
+// Zoom video — a long zoom assembled from a few deep keyframes
+// ---------------------------------------------------------------------------
+//
+// Rendering every frame of a deep zoom at full iteration depth is slow.
+// Instead, keyframes are rendered at twice the frame size, each zoomed in
+// twice as far as the one before, and every video frame is reprojected from
+// the two keyframes around its zoom: the outer one magnified, with the
+// inner one — the centre in twice the detail — fading in over it.
+
+/// Each keyframe zooms in this much further than the one before.
+pub const KEYFRAME_RATIO: f32 = 2.0;
+
+/// Keyframes are rendered this many times the frame size, so magnifying
+/// one by up to [`KEYFRAME_RATIO`] never shows its pixels larger than the
+/// frame's.
+pub const KEYFRAME_SCALE: u32 = 2;
+
+/// Largest frame, 4K UHD: its keyframes' 16-bit readback just fits
+/// wgpu's default 256 MiB buffer limit.
+pub const MAX_FRAME_PIXELS: u32 = 3840 * 2160;
+
+/// Width over which an inner keyframe's edge fades into the outer one, in
+/// keyframe pixels.
+const FEATHER: f32 = 16.0;
+
+fn default_fps() -> u32 {
+    30
+}
+
+/// What to render, e.g. a one-minute 1080p dive to a zoom of a million:
+/// `{"output": "dive.mp4", "size": [1920, 1080], "seconds": 60, "zoom": 1e6}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZoomVideoSpec {
+    /// Video file ffmpeg writes; its extension picks the container.
+    pub output: PathBuf,
+    /// Frame width and height; both even, as most encoders need.
+    pub size: [u32; 2],
+    #[serde(default = "default_fps")]
+    pub fps: u32,
+    pub seconds: f32,
+    /// Zoom of the last frame.  The first shows the patch's own view, so a
+    /// zoom below it gives a zoom-out.
+    pub zoom: f32,
+}
+
+impl ZoomVideoSpec {
+    pub fn validate(&self) -> Result<(), String> {
+        let [w, h] = self.size;
+        let max = MAX_CANVAS_SIDE / KEYFRAME_SCALE;
+        if !(2..=max).contains(&w) || !(2..=max).contains(&h) || w % 2 != 0 || h % 2 != 0 {
+            return Err(format!(
+                "frame size is {w}×{h}; each side must be even and 2–{max}"
+            ));
+        }
+        if w * h > MAX_FRAME_PIXELS {
+            return Err(format!("frame size {w}×{h} is larger than 4K UHD"));
+        }
+        if !(1..=240).contains(&self.fps) {
+            return Err(format!("fps is {}; it must be 1–240", self.fps));
+        }
+        if !(self.seconds > 0.0 && self.seconds <= 3600.0) {
+            return Err(format!(
+                "length is {} s; it must be above 0 and at most an hour",
+                self.seconds
+            ));
+        }
+        if !(self.zoom.is_finite() && self.zoom > 0.0) {
+            return Err(format!("zoom {} must be a positive number", self.zoom));
+        }
+        Ok(())
+    }
+
+    pub fn from_json(src: &str) -> io::Result<Self> {
+        let spec: Self =
+            serde_json::from_str(src).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        spec.validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(spec)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        ((self.seconds * self.fps as f32).round() as u32).max(1)
+    }
+
+    /// Keyframe size: the frame size times [`KEYFRAME_SCALE`].
+    pub fn keyframe_size(&self) -> [u32; 2] {
+        self.size.map(|v| v * KEYFRAME_SCALE)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ZoomPath — zoom per frame and the keyframes behind it
+// ---------------------------------------------------------------------------
+
+/// A zoom from `start` to `end` over `frames` frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoomPath {
+    pub start: f32,
+    pub end: f32,
+    pub frames: u32,
+}
+
+impl ZoomPath {
+    /// Zoom of the shallowest keyframe.
+    fn base(&self) -> f32 {
+        self.start.min(self.end)
+    }
+
+    /// Zoom of frame `i`: exponential, so the dive looks equally fast
+    /// throughout.
+    pub fn frame_zoom(&self, i: u32) -> f32 {
+        let t = if self.frames > 1 {
+            i as f32 / (self.frames - 1) as f32
+        } else {
+            1.0
+        };
+        self.start * (self.end / self.start).powf(t)
+    }
+
+    /// How many keyframes the path needs, including the inner one of the
+    /// deepest frame.
+    pub fn keyframe_count(&self) -> usize {
+        let depth = (self.start.max(self.end) / self.base()).log2();
+        depth.floor() as usize + 2
+    }
+
+    /// Zoom of keyframe `k`.
+    pub fn keyframe_zoom(&self, k: usize) -> f32 {
+        self.base() * KEYFRAME_RATIO.powi(k as i32)
+    }
+
+    /// The outer keyframe showing a frame at `zoom`, and how far the frame
+    /// magnifies it (1 to [`KEYFRAME_RATIO`]).
+    pub fn keyframe_for(&self, zoom: f32) -> (usize, f32) {
+        let k = (zoom / self.base()).log2().floor().max(0.0) as usize;
+        let k = k.min(self.keyframe_count() - 2);
+        (k, (zoom / self.keyframe_zoom(k)).max(1.0))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reprojection
+// ---------------------------------------------------------------------------
+
+/// A rendered keyframe: tightly packed RGBA8.
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe<'a> {
+    pub rgba: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Keyframe<'_> {
+    /// Bilinear sample at pixel position (`x`, `y`), edges clamped.
+    fn sample(&self, x: f32, y: f32) -> [f32; 4] {
+        let max_x = (self.width - 1) as f32;
+        let max_y = (self.height - 1) as f32;
+        let (x, y) = (x.clamp(0.0, max_x), y.clamp(0.0, max_y));
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as u32, y0 as u32);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let texel = |x: u32, y: u32| {
+            let i = ((y * self.width + x) * 4) as usize;
+            let p = &self.rgba[i..i + 4];
+            [p[0] as f32, p[1] as f32, p[2] as f32, p[3] as f32]
+        };
+        let (a, b, c, d) = (texel(x0, y0), texel(x1, y0), texel(x0, y1), texel(x1, y1));
+        std::array::from_fn(|i| {
+            let top = a[i] + (b[i] - a[i]) * fx;
+            let bottom = c[i] + (d[i] - c[i]) * fx;
+            top + (bottom - top) * fy
+        })
+    }
+
+    /// Distance from (`x`, `y`) to the nearest edge, negative outside.
+    fn edge_distance(&self, x: f32, y: f32) -> f32 {
+        let max_x = (self.width - 1) as f32;
+        let max_y = (self.height - 1) as f32;
+        x.min(max_x - x).min(y).min(max_y - y)
+    }
+}
+
+/// A `width`×`height` RGBA8 frame magnifying `outer` by `scale` about its
+/// centre (both views share a centre).  `inner`, the next keyframe, covers
+/// the middle of `outer` in twice the detail; it fades in as `scale` goes
+/// from 1 to [`KEYFRAME_RATIO`], so handing over to the next pair of
+/// keyframes doesn't pop.
+pub fn compose_frame(
+    outer: &Keyframe,
+    inner: Option<&Keyframe>,
+    scale: f32,
+    width: u32,
+    height: u32,
+) -> Vec<u8> {
+    // Output pixel → keyframe pixel: both views span the same plane height
+    // per frame height, `scale` apart in zoom.
+    let to_outer = outer.height as f32 / height as f32 / scale;
+    let fade = ((scale - 1.0) / (KEYFRAME_RATIO - 1.0)).clamp(0.0, 1.0);
+    let (cx, cy) = (width as f32 * 0.5, height as f32 * 0.5);
+
+    let mut out = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+            let mut colour = outer.sample(
+                outer.width as f32 * 0.5 + dx * to_outer,
+                outer.height as f32 * 0.5 + dy * to_outer,
+            );
+            if let Some(inner) = inner.filter(|_| fade > 0.0) {
+                let to_inner = inner.height as f32 / height as f32 / scale * KEYFRAME_RATIO;
+                let ix = inner.width as f32 * 0.5 + dx * to_inner;
+                let iy = inner.height as f32 * 0.5 + dy * to_inner;
+                let w = fade * (inner.edge_distance(ix, iy) / FEATHER).clamp(0.0, 1.0);
+                if w > 0.0 {
+                    let detail = inner.sample(ix, iy);
+                    for (c, d) in colour.iter_mut().zip(detail) {
+                        *c += (d - *c) * w;
+                    }
+                }
+            }
+            out.extend(colour.map(|c| c.round().clamp(0.0, 255.0) as u8));
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // --- ZoomVideoSpec ---
+
+    #[test]
+    fn spec_parses_with_default_fps() {
+        let spec = ZoomVideoSpec::from_json(
+            r#"{"output": "dive.mp4", "size": [1920, 1080], "seconds": 60, "zoom": 1e6}"#,
+        )
+        .unwrap();
+        assert_eq!(spec.fps, 30);
+        assert_eq!(spec.frame_count(), 1800);
+        assert_eq!(spec.keyframe_size(), [3840, 2160]);
+    }
+
+    #[test]
+    fn spec_rejects_odd_sizes_and_bad_numbers() {
+        let base = ZoomVideoSpec {
+            output: "a.mp4".into(),
+            size: [640, 360],
+            fps: 30,
+            seconds: 10.0,
+            zoom: 100.0,
+        };
+        assert!(base.validate().is_ok());
+        for bad in [
+            ZoomVideoSpec {
+                size: [641, 360],
+                ..base.clone()
+            },
+            ZoomVideoSpec {
+                size: [8000, 360],
+                ..base.clone()
+            },
+            ZoomVideoSpec {
+                size: [4096, 2304],
+                ..base.clone()
+            },
+            ZoomVideoSpec {
+                fps: 0,
+                ..base.clone()
+            },
+            ZoomVideoSpec {
+                seconds: 0.0,
+                ..base.clone()
+            },
+            ZoomVideoSpec {
+                zoom: f32::NAN,
+                ..base.clone()
+            },
+        ] {
+            assert!(bad.validate().is_err(), "{bad:?}");
+        }
+    }
+
+    // --- ZoomPath ---
+
+    #[test]
+    fn path_is_exponential_between_its_ends() {
+        let path = ZoomPath {
+            start: 1.0,
+            end: 1024.0,
+            frames: 11,
+        };
+        assert_eq!(path.frame_zoom(0), 1.0);
+        assert!((path.frame_zoom(10) - 1024.0).abs() < 1e-2);
+        assert!((path.frame_zoom(5) - 32.0).abs() < 1e-3);
+        assert_eq!(path.keyframe_count(), 12);
+    }
+
+    #[test]
+    fn keyframe_for_picks_the_outer_keyframe_and_magnification() {
+        let path = ZoomPath {
+            start: 2.0,
+            end: 64.0,
+            frames: 100,
+        };
+        assert_eq!(path.keyframe_for(2.0), (0, 1.0));
+        let (k, s) = path.keyframe_for(12.0);
+        assert_eq!(k, 2);
+        assert!((s - 1.5).abs() < 1e-5);
+        // The deepest frame still has an inner keyframe.
+        let (k, _) = path.keyframe_for(64.0);
+        assert!(k + 1 < path.keyframe_count());
+    }
+
+    #[test]
+    fn zoom_outs_share_the_keyframes_of_the_zoom_in() {
+        let dive = ZoomPath {
+            start: 1.0,
+            end: 50.0,
+            frames: 30,
+        };
+        let rise = ZoomPath {
+            start: 50.0,
+            end: 1.0,
+            frames: 30,
+        };
+        assert_eq!(dive.keyframe_count(), rise.keyframe_count());
+        assert_eq!(dive.keyframe_zoom(3), rise.keyframe_zoom(3));
+        assert_eq!(rise.frame_zoom(0), 50.0);
+    }
+
+    proptest! {
+        #[test]
+        fn frame_zoom_moves_steadily_from_start_to_end(
+            start in 0.5f32..1e6,
+            end in 0.5f32..1e6,
+            frames in 2u32..2000,
+        ) {
+            let path = ZoomPath { start, end, frames };
+            let zooms: Vec<f32> = (0..frames).map(|i| path.frame_zoom(i)).collect();
+            for pair in zooms.windows(2) {
+                let step = (pair[1] - pair[0]) * (end - start).signum();
+                prop_assert!(step >= 0.0, "{} then {}", pair[0], pair[1]);
+            }
+            prop_assert!((zooms[0] - start).abs() <= start * 1e-5);
+            let last = zooms[frames as usize - 1];
+            prop_assert!((last - end).abs() <= end * 1e-4, "{last} vs {end}");
+        }
+
+        #[test]
+        fn every_frame_has_an_outer_and_inner_keyframe(
+            start in 0.5f32..1e6,
+            end in 0.5f32..1e6,
+            t in 0.0f32..=1.0,
+        ) {
+            let path = ZoomPath { start, end, frames: 2 };
+            let zoom = start.min(end) * (start.max(end) / start.min(end)).powf(t);
+            let (k, scale) = path.keyframe_for(zoom);
+            prop_assert!(k + 1 < path.keyframe_count());
+            prop_assert!(
+                (1.0..=KEYFRAME_RATIO * (1.0 + 1e-5)).contains(&scale),
+                "zoom {zoom} magnifies keyframe {k} by {scale}"
+            );
+        }
+    }
+
+    // --- compose_frame ---
+
+    /// 4×4 keyframe split into a left half of `a` and a right half of `b`.
+    fn halves(a: u8, b: u8) -> Vec<u8> {
+        (0..16)
+            .flat_map(|i| {
+                let v = if i % 4 < 2 { a } else { b };
+                [v, v, v, 255]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unmagnified_frame_downsamples_the_outer_keyframe() {
+        let rgba = halves(0, 200);
+        let outer = Keyframe {
+            rgba: &rgba,
+            width: 4,
+            height: 4,
+        };
+        let frame = compose_frame(&outer, None, 1.0, 2, 2);
+        assert_eq!(frame.len(), 2 * 2 * 4);
+        // Left column samples the dark half, right column the bright one.
+        assert_eq!(frame[0], 0);
+        assert_eq!(frame[4], 200);
+        assert_eq!(frame[3], 255);
+    }
+
+    #[test]
+    fn inner_keyframe_fades_in_with_magnification() {
+        let dark = vec![0u8; 64];
+        let light: Vec<u8> = vec![255; 64];
+        let outer = Keyframe {
+            rgba: &dark,
+            width: 4,
+            height: 4,
+        };
+        let inner = Keyframe {
+            rgba: &light,
+            width: 4,
+            height: 4,
+        };
+        // At the start of the pair the inner keyframe is invisible…
+        let start = compose_frame(&outer, Some(&inner), 1.0, 2, 2);
+        assert!(start.iter().all(|&v| v == 0));
+        // …and it only ever adds, never darkens.
+        let later = compose_frame(&outer, Some(&inner), 1.9, 64, 64);
+        let centre = ((32 * 64 + 32) * 4) as usize;
+        assert!(later[centre] > 0);
+    }
+}
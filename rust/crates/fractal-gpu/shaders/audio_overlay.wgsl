@@ -0,0 +1,99 @@
+struct Uniforms {
+    resolution : vec2<f32>,
+    center     : vec2<f32>,
+    zoom       : f32,
+    time       : f32,
+    max_iter   : u32,
+    _pad       : u32,
+}
+// Effect: draw the live audio spectrum (bars) or waveform (a line) in a box
+// over the image, coloured from one of the color_map palettes.
+struct OverlayParams {
+    mode     : u32,  // 0 = spectrum, 1 = waveform
+    scheme   : u32,  // palette, numbered as in color_map.wgsl
+    position : u32,  // pack2x16unorm: top-left corner, fraction of output
+    size     : u32,  // pack2x16unorm: width / height, fraction of output
+}
+// 64 samples packed four to a vec4 (uniform arrays need 16-byte stride).
+struct Samples {
+    v : array<vec4<f32>, 16>,
+}
+
+@group(0) @binding(0) var<uniform>  u      : Uniforms;
+@group(0) @binding(1) var<uniform>  op     : OverlayParams;
+@group(0) @binding(2) var           input  : texture_2d<f32>;
+@group(0) @binding(3) var           output : texture_storage_2d<rgba16float, write>;
+@group(0) @binding(4) var<uniform>  s      : Samples;
+
+const TAU: f32 = 6.28318530718;
+const SAMPLES: u32 = 64u;
+const OPACITY: f32 = 0.85;
+
+fn classic(t: f32) -> vec3<f32> {
+    return 0.5 + 0.5 * vec3(cos(TAU * (t + 0.0)),
+                             cos(TAU * (t + 0.33)),
+                             cos(TAU * (t + 0.67)));
+}
+fn fire(t: f32) -> vec3<f32> {
+    return vec3(t, t * t, t * t * t);
+}
+fn ocean(t: f32) -> vec3<f32> {
+    return vec3(0.0, t * 0.5, t);
+}
+fn psychedelic(t: f32) -> vec3<f32> {
+    return 0.5 + 0.5 * vec3(sin(t * 30.0), sin(t * 19.0 + 1.0), sin(t * 13.0 + 2.0));
+}
+
+fn palette(t: f32) -> vec3<f32> {
+    switch op.scheme {
+        case 1u:  { return fire(t); }
+        case 2u:  { return ocean(t); }
+        case 3u:  { return psychedelic(t); }
+        default:  { return classic(t); }
+    }
+}
+
+fn sample_at(i: u32) -> f32 {
+    let j = min(i, SAMPLES - 1u);
+    return s.v[j / 4u][j % 4u];
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let coord = vec2<i32>(gid.xy);
+    if f32(gid.x) >= u.resolution.x || f32(gid.y) >= u.resolution.y { return; }
+    let px = textureLoad(input, coord, 0);
+
+    let origin = unpack2x16unorm(op.position) * u.resolution;
+    let extent = max(unpack2x16unorm(op.size) * u.resolution, vec2(1.0));
+    let local  = (vec2<f32>(gid.xy) + 0.5 - origin) / extent;
+    if any(local < vec2(0.0)) || any(local >= vec2(1.0)) {
+        textureStore(output, coord, px);
+        return;
+    }
+
+    var hit = false;
+    var t = 0.0;
+    if op.mode == 0u {
+        // Bars rise from the bottom; one pixel-ish gap between neighbours.
+        let x   = local.x * f32(SAMPLES);
+        let bar = clamp(sample_at(u32(x)), 0.0, 1.0);
+        hit = fract(x) < 0.8 && 1.0 - local.y <= bar;
+        t = bar;
+    } else {
+        // Line through the samples, linearly interpolated, ~1.5 px thick.
+        let x  = local.x * f32(SAMPLES - 1u);
+        let i  = u32(x);
+        let v  = clamp(mix(sample_at(i), sample_at(i + 1u), fract(x)), -1.0, 1.0);
+        let y  = 0.5 - 0.5 * v;
+        hit = abs(local.y - y) * extent.y < 1.5;
+        t = abs(v);
+    }
+
+    if hit {
+        let rgb = mix(px.rgb, palette(0.25 + 0.75 * t), OPACITY);
+        textureStore(output, coord, vec4<f32>(rgb, px.a));
+    } else {
+        textureStore(output, coord, px);
+    }
+}
@@ -5,8 +5,6 @@ struct Uniforms {
     time       : f32,
     max_iter   : u32,
     _pad       : u32,
-    julia_c    : vec2<f32>,
-    _pad2      : vec2<f32>,
 }
 struct BCParams {
     brightness : f32,
@@ -1,4 +1,4 @@
-// Burning Ship fractal — compute shader
+// Burning Ship fractal, with its Celtic and Buffalo variants — compute shader
 //
 // Iteration: z = (|Re(z)| + i|Im(z)|)² + c
 // Expanding: x_new = x² - y² + cx        (x² = |x|², so no abs needed)
@@ -6,6 +6,10 @@
 //
 // This matches the Clojure BurningShipGenerator exactly.
 // Default view center: (-0.5, -0.5) — the ship appears in the lower half.
+//
+// The variants fold other parts of z² before adding c, selected by `fold`:
+//   Celtic:  z = |Re(z²)| + i·Im(z²) + c
+//   Buffalo: z = |Re(z²)| + i·|Im(z²)| + c
 
 struct Uniforms {
     resolution: vec2<f32>,
@@ -13,40 +17,118 @@ struct Uniforms {
     zoom:       f32,
     time:       f32,
     max_iter:   u32,
-    pad0:       u32,
-    julia_c:    vec2<f32>,
-    pad1:       vec2<f32>,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+    smoothing:  u32, // 1 = log-log smoothed count, 0 = plain count
+    samples:    u32, // jittered samples per pixel: 1, 2, 4 or 8
+    origin:     vec2<u32>, // pixel the dispatch starts at
 }
 
 @group(0) @binding(0) var<uniform> u: Uniforms;
 @group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
 
-@compute @workgroup_size(8, 8)
-fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let px = vec2<f32>(f32(gid.x), f32(gid.y));
-    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+// Orbit trap for the orbit-trap coloring; mirrors `TrapUniforms` in
+// context.rs.
+struct TrapParams {
+    point:  vec2<f32>,
+    dir:    vec2<f32>, // unit direction of a line trap
+    radius: f32,       // of a circle trap
+    shape:  u32,       // 0 = point, 1 = line, 2 = circle
+    pad:    vec2<f32>,
+}
 
-    let uv = (px - u.resolution * 0.5) / (u.zoom * u.resolution.y * 0.5);
+// Coloring mode and variant; mirrors `ShipUniforms` in context.rs.
+struct ShipParams {
+    coloring:    u32, // 0 = escape time, 1 = Pickover stalks, 2 = orbit trap
+    stalk_width: f32,
+    fold:        u32, // 0 = Burning Ship, 1 = Celtic, 2 = Buffalo
+    pad:         f32,
+    trap:        TrapParams,
+}
+@group(0) @binding(2) var<uniform> cp: ShipParams;
+
+// Brent periodicity check, as in mandelbrot.wgsl: an orbit that returns to
+// its saved point is cycling, so the pixel is interior and stops early.
+const PERIOD_START:   u32 = 8u;
+const PERIOD_EPSILON: f32 = 1e-12; // squared distance counted as a return
+
+// Pickover stalks: brighten wherever the orbit (past its starting point)
+// grazed a coordinate axis.  `trap` is its closest approach.
+fn stalks(t: f32, trap: f32) -> f32 {
+    if cp.coloring != 1u || cp.stalk_width <= 0.0 { return t; }
+    return max(t, 1.0 - clamp(trap / cp.stalk_width, 0.0, 1.0));
+}
+
+// Orbit trap: the orbit's closest approach to a point, a line through
+// `point` along `dir`, or a circle of `radius` around `point`.  Written to
+// the green channel, capped at TRAP_FAR so it fits a half float.
+const TRAP_FAR: f32 = 1e4;
+fn trap_distance(z: vec2<f32>) -> f32 {
+    let d = z - cp.trap.point;
+    switch cp.trap.shape {
+        case 1u:  { return abs(d.x * cp.trap.dir.y - d.y * cp.trap.dir.x); }
+        case 2u:  { return abs(length(d) - cp.trap.radius); }
+        default:  { return length(d); }
+    }
+}
+
+// One sample at `px`, in pixels.
+fn shade(px: vec2<f32>) -> vec4<f32> {
+
+    let uv = plane_uv(px) / u.zoom;
     let c  = u.center + uv;
 
     var z = vec2<f32>(0.0, 0.0);
     var i = 0u;
+    var trap = 1e10;
+    var orbit = TRAP_FAR;
+    var saved  = z;
+    var period = 0u;
+    var window = PERIOD_START;
     while i < u.max_iter {
         if dot(z, z) > 4.0 { break; }
-        // Take abs of both components before squaring — the "burning ship" transform
-        z = vec2<f32>(
-            z.x * z.x - z.y * z.y + c.x,
-            2.0 * abs(z.x) * abs(z.y) + c.y,
-        );
+        if i > 0u {
+            trap = min(trap, min(abs(z.x), abs(z.y)));
+            if cp.coloring == 2u { orbit = min(orbit, trap_distance(z)); }
+        }
+        // Burning Ship takes abs of both components before squaring, which
+        // only flips the sign of Im(z²); Celtic and Buffalo fold Re(z²).
+        var re = z.x * z.x - z.y * z.y;
+        var im = 2.0 * z.x * z.y;
+        if cp.fold != 1u { im = abs(im); }
+        if cp.fold != 0u { re = abs(re); }
+        z = vec2<f32>(re + c.x, im + c.y);
         i++;
+        let d = z - saved;
+        if dot(d, d) < PERIOD_EPSILON { i = u.max_iter; break; }
+        period++;
+        if period == window { period = 0u; window *= 2u; saved = z; }
     }
 
     var t = 0.0;
     if i < u.max_iter {
         let log_zn = log2(max(dot(z, z), 1e-10)) * 0.5;
-        let nu     = log2(max(log_zn, 1e-10));
+        let nu     = select(1.0, log2(max(log_zn, 1e-10)), u.smoothing == 1u);
         t = clamp((f32(i) + 1.0 - nu) / f32(u.max_iter), 0.0, 1.0);
     }
 
-    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(t, 0.0, 0.0, 1.0));
+    t = stalks(t, trap);
+
+    let g = select(0.0, orbit, cp.coloring == 2u);
+    return vec4<f32>(t, g, 0.0, 1.0);
+}
+
+// Average `u.samples` jittered samples; counts other than 2, 4 and 8 take one.
+// The grid starts at `u.origin`, so a pan can recompute only the strips it
+// exposed.
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let p  = gid.xy + u.origin;
+    let px = vec2<f32>(f32(p.x), f32(p.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+    let n = select(1u, u.samples, u.samples == 2u || u.samples == 4u || u.samples == 8u);
+    var sum = vec4<f32>(0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += shade(px + sample_offset(k, n));
+    }
+    textureStore(output, vec2<i32>(p), sum / f32(n));
 }
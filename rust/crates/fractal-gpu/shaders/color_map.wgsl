@@ -1,6 +1,13 @@
 // Effect: map raw escape-time value (r channel) → RGB colour.
 // Scheme is encoded in the uniforms as an integer:
 //   0 = Classic, 1 = Fire, 2 = Ocean, 3 = Psychedelic
+// With `orbit_trap` set, the palette instead follows the orbit's closest
+// approach to its trap (g channel): bright where the orbit grazed it.  With
+// `edge_glow` set it follows the generator's distance estimate (a channel):
+// bright on the set's boundary, inside and out.  A bundle's gradient
+// replaces the palette by rewriting the two GRADIENT lines below (see
+// `EffectPass::gradient_source`); with fewer than two stops the scheme
+// applies.
 
 struct Uniforms {
     resolution : vec2<f32>,
@@ -9,14 +16,12 @@ struct Uniforms {
     time       : f32,
     max_iter   : u32,
     _pad       : u32,
-    julia_c    : vec2<f32>,
-    _pad2      : vec2<f32>,
 }
 struct EffectParams {
-    scheme : u32,
-    _pad0  : u32,
-    _pad1  : u32,
-    _pad2  : u32,
+    scheme     : u32,
+    orbit_trap : u32,
+    edge_glow  : u32,
+    _pad2      : u32,
 }
 
 @group(0) @binding(0) var<uniform>  u      : Uniforms;
@@ -39,13 +44,35 @@ fn psychedelic(t: f32) -> vec3<f32> {
     return 0.5 + 0.5 * vec3(sin(t * 30.0), sin(t * 19.0 + 1.0), sin(t * 13.0 + 2.0));
 }
 
+// Linear RGB stops spread evenly over [0, 1].
+const GRADIENT_LEN: u32 = 0u;
+const GRADIENT = array<vec3<f32>, 1>(vec3(0.0));
+
+fn gradient(t: f32) -> vec3<f32> {
+    var stops = GRADIENT;
+    let last = max(GRADIENT_LEN, 2u) - 1u;
+    let x = clamp(t, 0.0, 1.0) * f32(last);
+    let i = min(u32(x), last - 1u);
+    return mix(stops[i], stops[i + 1u], x - f32(i));
+}
+
 const TAU: f32 = 6.28318530718;
+// How quickly the trap colour fades with distance from the trap.
+const TRAP_FALLOFF: f32 = 6.0;
+// Pixels the edge glow takes to fade to a third.
+const EDGE_WIDTH: f32 = 1.5;
 
 @compute @workgroup_size(8, 8)
 fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     let coord = vec2<i32>(gid.xy);
     let px    = textureLoad(input, coord, 0);
-    let t     = px.r; // normalised escape value in [0, 1]
+    var t     = px.r; // normalised escape value in [0, 1]
+    if ep.orbit_trap != 0u { t = exp(-TRAP_FALLOFF * px.g); }
+    if ep.edge_glow != 0u {
+        // Undo the generator's d / (1 + d) squash back to pixels.
+        let a = min(abs(px.a), 0.9999);
+        t = exp(-a / (1.0 - a) / EDGE_WIDTH);
+    }
 
     var rgb: vec3<f32>;
     switch ep.scheme {
@@ -54,6 +81,7 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
         case 3u:  { rgb = psychedelic(t); }
         default:  { rgb = classic(t); }
     }
+    if GRADIENT_LEN >= 2u { rgb = gradient(t); }
 
     textureStore(output, coord, vec4<f32>(rgb, 1.0));
 }
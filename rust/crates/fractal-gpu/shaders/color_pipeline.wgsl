@@ -0,0 +1,94 @@
+// Fused colour pipeline — compute shader
+//
+// The uber-shader for runs of pointwise effects (hue shift,
+// brightness/contrast, posterize, invert): each pixel is loaded once, put
+// through the run's packed ops in order and stored once, instead of a
+// dispatch and a full-frame round trip per effect.  Each op does exactly
+// what its effect's own shader does, clamping included, so a fused run
+// matches the unfused one.
+
+struct Uniforms {
+    resolution : vec2<f32>,
+    center     : vec2<f32>,
+    zoom       : f32,
+    time       : f32,
+    max_iter   : u32,
+    _pad       : u32,
+}
+
+// Op codes; mirror `ColorOp::new` in effect_pipeline.rs.
+const OP_HUE_SHIFT:           u32 = 0u;
+const OP_BRIGHTNESS_CONTRAST: u32 = 1u;
+const OP_POSTERIZE:           u32 = 2u;
+const OP_INVERT:              u32 = 3u;
+
+// One effect: its op code and up to two parameters, in the order the
+// effect's own params struct has them.  Mirrors `ColorOp`.
+struct ColorOp {
+    code : u32,
+    a    : f32,
+    b    : f32,
+    _pad : f32,
+}
+
+// Mirrors `ColorPipelineUniforms` in effect_pipeline.rs; 16 =
+// `Patch::MAX_EFFECTS`.
+struct ColorPipeline {
+    count : u32,
+    _pad0 : u32,
+    _pad1 : u32,
+    _pad2 : u32,
+    ops   : array<ColorOp, 16>,
+}
+
+@group(0) @binding(0) var<uniform>  u      : Uniforms;
+@group(0) @binding(1) var<uniform>  cp     : ColorPipeline;
+@group(0) @binding(2) var           input  : texture_2d<f32>;
+@group(0) @binding(3) var           output : texture_storage_2d<rgba16float, write>;
+
+// As hue_shift.wgsl.
+fn hue_rotate(rgb: vec3<f32>, angle: f32) -> vec3<f32> {
+    let c = cos(angle);
+    let s = sin(angle);
+    let w = vec3(0.299, 0.587, 0.114);
+    let lum = dot(rgb, w);
+    return vec3(
+        lum + (rgb.r - lum) * c + (0.701 * rgb.r - 0.587 * rgb.g - 0.114 * rgb.b) * s,
+        lum + (rgb.g - lum) * c + (-0.299 * rgb.r + 0.413 * rgb.g - 0.114 * rgb.b) * s,
+        lum + (rgb.b - lum) * c + (-0.299 * rgb.r - 0.587 * rgb.g + 0.886 * rgb.b) * s,
+    );
+}
+
+fn apply(op: ColorOp, rgb: vec3<f32>) -> vec3<f32> {
+    switch op.code {
+        case OP_HUE_SHIFT: {
+            return clamp(hue_rotate(rgb, op.a), vec3(0.0), vec3(1.0));
+        }
+        case OP_BRIGHTNESS_CONTRAST: {
+            return clamp((rgb + op.a) * op.b, vec3(0.0), vec3(1.0));
+        }
+        case OP_POSTERIZE: {
+            let steps = max(op.a, 2.0) - 1.0;
+            return round(clamp(rgb, vec3(0.0), vec3(1.0)) * steps) / steps;
+        }
+        case OP_INVERT: {
+            let c = clamp(rgb, vec3(0.0), vec3(1.0));
+            return mix(c, 1.0 - c, op.a);
+        }
+        default: {
+            return rgb;
+        }
+    }
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let coord = vec2<i32>(gid.xy);
+    if f32(gid.x) >= u.resolution.x || f32(gid.y) >= u.resolution.y { return; }
+    let px  = textureLoad(input, coord, 0);
+    var rgb = px.rgb;
+    for (var i = 0u; i < min(cp.count, 16u); i++) {
+        rgb = apply(cp.ops[i], rgb);
+    }
+    textureStore(output, coord, vec4<f32>(rgb, px.a));
+}
@@ -0,0 +1,21 @@
+struct MixParams {
+    amount : f32,  // 0 = deck A only, 1 = deck B only
+    _pad0  : f32,
+    _pad1  : f32,
+    _pad2  : f32,
+}
+
+@group(0) @binding(0) var<uniform>  mp     : MixParams;
+@group(0) @binding(1) var           deck_a : texture_2d<f32>;
+@group(0) @binding(2) var           deck_b : texture_2d<f32>;
+@group(0) @binding(3) var           output : texture_storage_2d<rgba16float, write>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let size = textureDimensions(output);
+    if gid.x >= size.x || gid.y >= size.y { return; }
+    let coord = vec2<i32>(gid.xy);
+    let a = textureLoad(deck_a, coord, 0);
+    let b = textureLoad(deck_b, coord, 0);
+    textureStore(output, coord, mix(a, b, mp.amount));
+}
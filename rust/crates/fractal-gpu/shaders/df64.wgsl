@@ -0,0 +1,81 @@
+// Double-float (df64) arithmetic — a WGSL library, not a shader
+//
+// A df64 is an unevaluated sum hi + lo of two f32s, |lo| ≤ ½ ulp(hi), good
+// for about 48 bits of mantissa: enough for zooms to around 1e12 on GPUs
+// without SHADER_F64.  The *_df64.wgsl kernels are compiled with this file
+// prepended (see the `*_DF64_SOURCE` constants in generator_pipeline.rs).
+//
+// The error-free transforms below (Knuth's two-sum, Dekker's split and
+// product) rely on f32 rounding exactly as written; a compiler that
+// reassociates or fuses them would quietly turn df64 back into f32.
+//
+// Mirrored in Rust by the `df64` helpers in generator_pipeline.rs tests.
+
+// a + b = s + e exactly.
+fn two_sum(a: f32, b: f32) -> vec2<f32> {
+    let s = a + b;
+    let v = s - a;
+    let e = (a - (s - v)) + (b - v);
+    return vec2<f32>(s, e);
+}
+
+// As two_sum, for |a| ≥ |b|.
+fn quick_two_sum(a: f32, b: f32) -> vec2<f32> {
+    let s = a + b;
+    return vec2<f32>(s, b - (s - a));
+}
+
+// a = hi + lo with each half 12 bits wide, so their products are exact.
+fn df_split(a: f32) -> vec2<f32> {
+    let t  = 4097.0 * a; // 2^12 + 1
+    let hi = t - (t - a);
+    return vec2<f32>(hi, a - hi);
+}
+
+// a · b = p + e exactly.
+fn two_prod(a: f32, b: f32) -> vec2<f32> {
+    let p  = a * b;
+    let sa = df_split(a);
+    let sb = df_split(b);
+    let e  = ((sa.x * sb.x - p) + sa.x * sb.y + sa.y * sb.x) + sa.y * sb.y;
+    return vec2<f32>(p, e);
+}
+
+fn df_add(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    var s = two_sum(a.x, b.x);
+    let t = two_sum(a.y, b.y);
+    s.y += t.x;
+    s = quick_two_sum(s.x, s.y);
+    s.y += t.y;
+    return quick_two_sum(s.x, s.y);
+}
+
+fn df_sub(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return df_add(a, -b);
+}
+
+fn df_mul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    var p = two_prod(a.x, b.x);
+    p.y += a.x * b.y + a.y * b.x;
+    return quick_two_sum(p.x, p.y);
+}
+
+// Exact: scaling by 2 only moves the exponent.
+fn df_twice(a: vec2<f32>) -> vec2<f32> {
+    return a * 2.0;
+}
+
+fn df_abs(a: vec2<f32>) -> vec2<f32> {
+    return select(a, -a, a.x < 0.0 || (a.x == 0.0 && a.y < 0.0));
+}
+
+// A point of the plane: re and im each a df64, (re.hi, re.lo, im.hi, im.lo).
+fn dc_add(a: vec4<f32>, b: vec4<f32>) -> vec4<f32> {
+    return vec4<f32>(df_add(a.xy, b.xy), df_add(a.zw, b.zw));
+}
+
+// The plane point a pixel maps to: the df64 centre plus its f32 offset,
+// which only needs f32's relative precision.
+fn dc_offset(center: vec4<f32>, uv: vec2<f32>) -> vec4<f32> {
+    return dc_add(center, vec4<f32>(uv.x, 0.0, uv.y, 0.0));
+}
@@ -0,0 +1,187 @@
+// Domain warp — compute shader
+//
+// The noise field's FBM, sampled not at the pixel's point p but at p pushed
+// around by the FBM itself (after Inigo Quilez's "warping"):
+//
+//   w₀ = 0
+//   wₖ = (fbm(p + s·R·wₖ₋₁ + aₖ), fbm(p + s·R·wₖ₋₁ + bₖ))   k = 1 … layers
+//   n  = fbm(p + s·R·w_layers)
+//
+// where s is `strength`, R turns by `rotation` and aₖ, bₖ are fixed
+// offsets that decorrelate the two components.  Each layer folds the one
+// before, so two or three give the flowing, marbled currents the flat noise
+// field lacks.  Scale, octaves, lacunarity, gain, seed and the z slice work
+// as in noise_field.wgsl.
+//
+// Output: warped noise value in the red channel [0, 1].
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// DomainWarp-only parameters; mirrors `WarpUniforms` in context.rs.
+struct WarpParams {
+    scale:      f32,
+    octaves:    u32,
+    lacunarity: f32,
+    gain:       f32,
+    seed:       f32,
+    z:          f32,
+    strength:   f32, // displacement per layer, in noise-lattice units
+    rotation:   f32, // radians each displacement is turned by
+    layers:     u32, // 2 or 3 (DomainWarpGen::MAX_LAYERS)
+    pad0:       f32,
+    pad1:       f32,
+    pad2:       f32,
+}
+@group(0) @binding(2) var<uniform> wp: WarpParams;
+
+// ---------------------------------------------------------------------------
+// 3-D simplex noise (Ashima Arts / Stefan Gustavson, MIT licence)
+// ---------------------------------------------------------------------------
+
+fn mod289_3(x: vec3<f32>) -> vec3<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn mod289_4(x: vec4<f32>) -> vec4<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn permute4(x: vec4<f32>) -> vec4<f32> {
+    return mod289_4(((x * 34.0) + 1.0) * x);
+}
+
+fn taylor_inv_sqrt4(r: vec4<f32>) -> vec4<f32> {
+    return 1.79284291400159 - 0.85373472095314 * r;
+}
+
+// Returns a value in roughly [-1, 1].
+fn snoise3(v: vec3<f32>) -> f32 {
+    let C = vec2<f32>(1.0 / 6.0, 1.0 / 3.0);
+    let D = vec4<f32>(0.0, 0.5, 1.0, 2.0);
+
+    // First corner
+    var i  = floor(v + dot(v, C.yyy));
+    let x0 = v - i + dot(i, C.xxx);
+
+    // Other corners
+    let g  = step(x0.yzx, x0.xyz);
+    let l  = 1.0 - g;
+    let i1 = min(g.xyz, l.zxy);
+    let i2 = max(g.xyz, l.zxy);
+    let x1 = x0 - i1 + C.xxx;
+    let x2 = x0 - i2 + C.yyy;
+    let x3 = x0 - D.yyy;
+
+    // Permutations
+    i = mod289_3(i);
+    let p = permute4(permute4(permute4(
+              i.z + vec4<f32>(0.0, i1.z, i2.z, 1.0))
+            + i.y + vec4<f32>(0.0, i1.y, i2.y, 1.0))
+            + i.x + vec4<f32>(0.0, i1.x, i2.x, 1.0));
+
+    // Gradients: 7×7 points over a square, mapped onto an octahedron
+    let ns = (1.0 / 7.0) * D.wyz - D.xzx;
+    let j  = p - 49.0 * floor(p * ns.z * ns.z);
+    let x_ = floor(j * ns.z);
+    let y_ = floor(j - 7.0 * x_);
+    let x  = x_ * ns.x + ns.yyyy;
+    let y  = y_ * ns.x + ns.yyyy;
+    let h  = 1.0 - abs(x) - abs(y);
+    let b0 = vec4<f32>(x.xy, y.xy);
+    let b1 = vec4<f32>(x.zw, y.zw);
+    let s0 = floor(b0) * 2.0 + 1.0;
+    let s1 = floor(b1) * 2.0 + 1.0;
+    let sh = -step(h, vec4<f32>(0.0));
+    let a0 = b0.xzyw + s0.xzyw * sh.xxyy;
+    let a1 = b1.xzyw + s1.xzyw * sh.zzww;
+
+    // Normalise gradients
+    let n  = taylor_inv_sqrt4(vec4<f32>(
+        dot(a0.xy, a0.xy) + h.x * h.x,
+        dot(a0.zw, a0.zw) + h.y * h.y,
+        dot(a1.xy, a1.xy) + h.z * h.z,
+        dot(a1.zw, a1.zw) + h.w * h.w,
+    ));
+    let p0 = vec3<f32>(a0.xy, h.x) * n.x;
+    let p1 = vec3<f32>(a0.zw, h.y) * n.y;
+    let p2 = vec3<f32>(a1.xy, h.z) * n.z;
+    let p3 = vec3<f32>(a1.zw, h.w) * n.w;
+
+    // Mix final noise value
+    var m = max(0.6 - vec4<f32>(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3)), vec4<f32>(0.0));
+    m = m * m;
+    return 42.0 * dot(m * m, vec4<f32>(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
+}
+
+// FBM as in the noise field, but signed — roughly [-1, 1] — so the
+// displacements it feeds scatter in every direction.  Capped at 8 octaves
+// (NoiseFieldGen::MAX_OCTAVES).
+fn fbm(p: vec3<f32>) -> f32 {
+    var value     = 0.0;
+    var norm      = 0.0;
+    var amplitude = 0.5;
+    var frequency = 1.0;
+    for (var oct = 0u; oct < min(wp.octaves, 8u); oct++) {
+        value     += amplitude * snoise3(p * frequency);
+        norm      += amplitude;
+        frequency *= wp.lacunarity;
+        amplitude *= wp.gain;
+    }
+    return value / max(norm, 1e-6);
+}
+
+// The FBM on the noise slice at `z`, at a 2-D point.
+fn fbm2(p: vec2<f32>, z: f32) -> f32 {
+    return fbm(vec3<f32>(p, z));
+}
+
+// ---------------------------------------------------------------------------
+// Shader entry point
+// ---------------------------------------------------------------------------
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+
+    let p = u.center + plane_uv(px) / u.zoom;
+
+    // Same lattice offset and z rate as the noise field, so a seed or an
+    // audio-driven `noise_z` behaves alike in both.
+    let seed_offset = vec2<f32>(wp.seed * 17.13, wp.seed * 31.71);
+    let q = p * wp.scale + seed_offset;
+    let z = wp.z * 0.1;
+
+    // Offsets aₖ (xy) and bₖ (zw) per layer; arbitrary, just far apart.
+    var offsets = array<vec4<f32>, 3>(
+        vec4<f32>(0.0, 0.0, 5.2, 1.3),
+        vec4<f32>(1.7, 9.2, 8.3, 2.8),
+        vec4<f32>(4.1, 6.7, 3.9, 7.4),
+    );
+    let c = cos(wp.rotation);
+    let s = sin(wp.rotation);
+    let turn = mat2x2<f32>(c, s, -s, c) * wp.strength;
+
+    var w = vec2<f32>(0.0);
+    for (var layer = 0u; layer < clamp(wp.layers, 2u, 3u); layer++) {
+        let o  = offsets[layer];
+        let pw = q + turn * w;
+        w = vec2<f32>(fbm2(pw + o.xy, z), fbm2(pw + o.zw, z));
+    }
+    let n = fbm2(q + turn * w, z);
+
+    // The signed FBM rarely strays past ±0.7; stretch that over [0, 1].
+    let v = clamp(0.5 + 0.7 * n, 0.0, 1.0);
+
+    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(v, 0.0, 0.0, 1.0));
+}
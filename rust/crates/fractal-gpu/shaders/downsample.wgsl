@@ -0,0 +1,19 @@
+// Half-resolution downsample — 2×2 box filter into a half-size texture.
+//
+// Each output pixel samples the shared corner of its four source texels,
+// where the linear sampler averages them in one fetch.  Feeds the effects
+// `EffectPass` runs at half resolution; upsample.wgsl brings them back.
+
+@group(0) @binding(0) var input  : texture_2d<f32>;
+@group(0) @binding(1) var output : texture_storage_2d<rgba16float, write>;
+@group(0) @binding(2) var samp   : sampler;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let size = textureDimensions(output);
+    if gid.x >= size.x || gid.y >= size.y { return; }
+
+    let src_size = vec2<f32>(textureDimensions(input));
+    let uv = (vec2<f32>(gid.xy) * 2.0 + 1.0) / src_size;
+    textureStore(output, vec2<i32>(gid.xy), textureSampleLevel(input, samp, uv, 0.0));
+}
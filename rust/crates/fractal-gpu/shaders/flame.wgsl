@@ -0,0 +1,186 @@
+// Fractal flame — compute shader, two entry points
+//
+// `scatter` plays the chaos game like ifs.wgsl, but every affine map is
+// followed by a weighted blend of flam3's non-linear variations, and each
+// walker carries a colour coordinate that moves halfway towards the picked
+// transform's colour at every step.  Each point it lands on adds one hit
+// and its colour to the pixel's pair of slots in the density buffer
+// (binding 5): hits at 2 * index, summed colour (in 1/255ths) at 2 * index + 1.
+//
+// `main` then runs per pixel: it takes the pixel's hits and colour
+// (clearing them for the next frame), and tone-maps the log density with
+// gamma and vibrancy as flam3 does.  Unlike the other point clouds it
+// writes full colour, from a hue wheel turned by `flame_hue`.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32, // steps per walker
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+const TRANSFORMS: u32 = 4u;
+
+// Mirrors `FlameUniforms` in context.rs.
+struct FlameParams {
+    linear: array<vec4<f32>, 4>, // a, b, c, d
+    offset: array<vec4<f32>, 4>, // e, f, pick threshold, colour
+    blend:  array<vec4<f32>, 4>, // linear, sinusoidal, spherical, swirl
+    blend2: array<vec4<f32>, 4>, // horseshoe, polar, unused, unused
+    tone:   vec4<f32>,           // exposure, gamma, vibrancy, hue
+}
+@group(0) @binding(2) var<uniform> fp: FlameParams;
+
+// Hits and summed colour per pixel, interleaved, row-major.
+@group(0) @binding(5) var<storage, read_write> density: array<atomic<u32>>;
+
+const WARMUP:    u32 = 12u;
+const MAX_STEPS: u32 = 1024u;
+// A walker further out than this has escaped a non-contracting map.
+const ESCAPE:    f32 = 1e6;
+const PI:        f32 = 3.1415927;
+
+fn pcg(v: u32) -> u32 {
+    let s = v * 747796405u + 2891336453u;
+    let w = ((s >> ((s >> 28u) + 4u)) ^ s) * 277803737u;
+    return (w >> 22u) ^ w;
+}
+
+fn unit(v: u32) -> f32 {
+    return f32(v) / 4294967295.0;
+}
+
+// Plane position → pixel; the inverse of `plane_uv` in view.wgsl.  Negative
+// when the point falls outside the dome's view (or sits on the exp map's
+// centre, which no row reaches).
+fn plane_px(p: vec2<f32>) -> vec2<f32> {
+    let o   = (p - u.center) * u.zoom;
+    let mid = u.resolution * 0.5;
+    if u.projection == 2u {
+        let len = length(o);
+        if len < 1e-12 { return vec2<f32>(-1.0); }
+        let k     = 6.2831855 / u.resolution.x;
+        let angle = atan2(o.y, o.x);
+        let turn  = select(angle, angle + 6.2831855, angle < 0.0);
+        return vec2<f32>(turn, log(2.0 / len)) / k;
+    }
+    if u.projection == 0u { return mid + o * (u.resolution.y * 0.5); }
+    let len = length(o);
+    if len < 1e-6 { return mid; }
+    let theta = atan(len * 1.5707964);
+    if theta > 1.4835299 { return vec2<f32>(-1.0); }
+    let r = theta / 1.5707964;
+    return mid + o * (r * min(u.resolution.x, u.resolution.y) * 0.5 / len);
+}
+
+// The first transform whose threshold lies above `r`; the host sets the
+// last weighted transform's threshold to 1, so one always does.
+fn pick(r: f32) -> u32 {
+    for (var i = 0u; i < TRANSFORMS - 1u; i++) {
+        if r < fp.offset[i].z { return i; }
+    }
+    return TRANSFORMS - 1u;
+}
+
+fn random_point(state: u32) -> vec2<f32> {
+    return vec2<f32>(unit(state), unit(pcg(state))) * 2.0 - 1.0;
+}
+
+// Transform `t`: its affine map, then its blend of variations.  Mirrors
+// `FlameTransform::apply`.
+fn transform(t: u32, z: vec2<f32>) -> vec2<f32> {
+    let l = fp.linear[t];
+    let p = vec2<f32>(l.x * z.x + l.y * z.y, l.z * z.x + l.w * z.y) + fp.offset[t].xy;
+    let w = fp.blend[t];
+    let w2 = fp.blend2[t];
+    let r2 = dot(p, p);
+    let r = sqrt(r2);
+
+    var out = w.x * p;
+    out += w.y * sin(p);
+    out += w.z * p / max(r2, 1e-10);
+    let s = sin(r2);
+    let c = cos(r2);
+    out += w.w * vec2<f32>(p.x * s - p.y * c, p.x * c + p.y * s);
+    out += w2.x * vec2<f32>((p.x - p.y) * (p.x + p.y), 2.0 * p.x * p.y) / max(r, 1e-10);
+    out += w2.y * vec2<f32>(atan2(p.x, p.y) / PI, r - 1.0);
+    return out;
+}
+
+@compute @workgroup_size(64)
+fn scatter(@builtin(global_invocation_id) gid: vec3<u32>) {
+    var state = pcg(gid.x ^ pcg(bitcast<u32>(u.time)));
+    var z = random_point(state);
+    var colour = unit(pcg(state ^ 0x9e3779b9u));
+    var settled = 0u;
+
+    let width = u32(u.resolution.x);
+    let steps = min(u.max_iter, MAX_STEPS) + WARMUP;
+    for (var i = 0u; i < steps; i++) {
+        state = pcg(state);
+        let t = pick(unit(state));
+        z = transform(t, z);
+        colour = (colour + fp.offset[t].w) * 0.5;
+
+        // NaN fails every comparison, so test for "not inside" explicitly.
+        if !(abs(z.x) < ESCAPE && abs(z.y) < ESCAPE) {
+            state = pcg(state);
+            z = random_point(state);
+            settled = 0u;
+            continue;
+        }
+        settled++;
+        if settled <= WARMUP { continue; }
+
+        let px = plane_px(z);
+        if px.x < 0.0 || px.y < 0.0 || px.x >= u.resolution.x || px.y >= u.resolution.y {
+            continue;
+        }
+        let index = (u32(px.y) * width + u32(px.x)) * 2u;
+        if index + 1u < arrayLength(&density) {
+            atomicAdd(&density[index], 1u);
+            atomicAdd(&density[index + 1u], u32(clamp(colour, 0.0, 1.0) * 255.0 + 0.5));
+        }
+    }
+}
+
+// Colour coordinate → RGB on a cosine hue wheel.
+fn palette(t: f32) -> vec3<f32> {
+    return 0.5 + 0.5 * cos(6.2831855 * (t + vec3<f32>(0.0, 0.3333333, 0.6666667)));
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+
+    let index = (gid.y * u32(u.resolution.x) + gid.x) * 2u;
+    var hits = 0u;
+    var colour_sum = 0u;
+    if index + 1u < arrayLength(&density) {
+        hits = atomicExchange(&density[index], 0u);
+        colour_sum = atomicExchange(&density[index + 1u], 0u);
+    }
+    if hits == 0u {
+        textureStore(output, vec2<i32>(gid.xy), vec4<f32>(0.0, 0.0, 0.0, 1.0));
+        return;
+    }
+
+    let exposure = max(fp.tone.x, 1.0);
+    let inv_gamma = 1.0 / fp.tone.y;
+    let vibrancy = fp.tone.z;
+    let colour = palette(f32(colour_sum) / (255.0 * f32(hits)) + fp.tone.w);
+
+    // Log density, then flam3's blend of gamma applied to alpha alone
+    // (vibrant) and to each channel (washed out).
+    let alpha = clamp(log2(1.0 + f32(hits)) / log2(1.0 + exposure), 0.0, 1.0);
+    let rgb = vibrancy * pow(alpha, inv_gamma) * colour
+            + (1.0 - vibrancy) * pow(alpha * colour, vec3<f32>(inv_gamma));
+
+    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(rgb, 1.0));
+}
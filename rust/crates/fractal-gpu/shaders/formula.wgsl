@@ -0,0 +1,183 @@
+// Custom formula — compute shader template
+//
+// For each pixel, iterate z = formula(z, c) with a user-typed step compiled
+// from fractal-core's `Formula::to_wgsl`.  `formula_source` in
+// generator_pipeline.rs replaces everything after the `// @formula` marker
+// at the bottom with the generated function, which calls the `c_*` helpers
+// below and reads `a` and `b` from binding 2.  As written the template
+// holds the default, z² + c.
+//
+// The orbit starts at the pixel.  In Mandelbrot form c is the pixel too;
+// in Julia form c is the constant in binding 2.  A typed formula can be
+// anything, so the escape test also catches inf and NaN, and the count is
+// smoothed as if the step were quadratic.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+    smoothing:  u32, // 1 = log-log smoothed count, 0 = plain count
+    samples:    u32, // jittered samples per pixel: 1, 2, 4 or 8
+    origin:     vec2<u32>, // pixel the dispatch starts at
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// Julia constant, the formula's a and b, and the form; mirrors
+// `FormulaUniforms` in context.rs.
+struct FormulaParams {
+    c:     vec2<f32>,
+    a:     f32,
+    b:     f32,
+    julia: u32, // 1 = Julia form
+    pad0:  f32,
+    pad1:  vec2<f32>,
+}
+@group(0) @binding(2) var<uniform> fp: FormulaParams;
+
+// --- complex helpers the generated formula calls ---------------------------
+// Mirrored in Rust by `eval` in fractal-core's formula.rs tests.
+
+fn c_mul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+fn c_div(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return c_mul(a, vec2<f32>(b.x, -b.y)) / dot(b, b);
+}
+
+// z^n by repeated squaring.
+fn c_powi(z: vec2<f32>, n: u32) -> vec2<f32> {
+    var r = vec2<f32>(1.0, 0.0);
+    var base = z;
+    var k = n;
+    while k > 0u {
+        if (k & 1u) == 1u { r = c_mul(r, base); }
+        base = c_mul(base, base);
+        k = k >> 1u;
+    }
+    return r;
+}
+
+fn c_exp(z: vec2<f32>) -> vec2<f32> {
+    return exp(z.x) * vec2<f32>(cos(z.y), sin(z.y));
+}
+
+// Principal branch, cut along the negative real axis.
+fn c_log(z: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(0.5 * log(dot(z, z)), atan2(z.y, z.x));
+}
+
+// z^w = exp(w·log z); 0^w = 0.
+fn c_pow(z: vec2<f32>, w: vec2<f32>) -> vec2<f32> {
+    if dot(z, z) == 0.0 { return vec2<f32>(0.0, 0.0); }
+    return c_exp(c_mul(w, c_log(z)));
+}
+
+fn c_sqrt(z: vec2<f32>) -> vec2<f32> {
+    let theta = atan2(z.y, z.x) * 0.5;
+    return sqrt(length(z)) * vec2<f32>(cos(theta), sin(theta));
+}
+
+fn c_sin(z: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(sin(z.x) * cosh(z.y), cos(z.x) * sinh(z.y));
+}
+
+fn c_cos(z: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(cos(z.x) * cosh(z.y), -sin(z.x) * sinh(z.y));
+}
+
+fn c_tan(z: vec2<f32>) -> vec2<f32> {
+    return c_div(c_sin(z), c_cos(z));
+}
+
+fn c_sinh(z: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(sinh(z.x) * cos(z.y), cosh(z.x) * sin(z.y));
+}
+
+fn c_cosh(z: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(cosh(z.x) * cos(z.y), sinh(z.x) * sin(z.y));
+}
+
+fn c_tanh(z: vec2<f32>) -> vec2<f32> {
+    return c_div(c_sinh(z), c_cosh(z));
+}
+
+// Both components folded positive: the Burning Ship's fold.
+fn c_abs(z: vec2<f32>) -> vec2<f32> {
+    return abs(z);
+}
+
+fn c_conj(z: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(z.x, -z.y);
+}
+
+fn c_re(z: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(z.x, 0.0);
+}
+
+fn c_im(z: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(z.y, 0.0);
+}
+
+// Squared radius past which an orbit counts as escaped; wider than the
+// Mandelbrot's 4 so slow-growing formulas still smooth cleanly.
+const BAILOUT: f32 = 256.0;
+
+// One sample at `px`, in pixels.
+fn shade(px: vec2<f32>) -> vec4<f32> {
+
+    let p = u.center + plane_uv(px) / u.zoom;
+    let c = select(p, fp.c, fp.julia == 1u);
+
+    var z = p;
+    var i = 0u;
+    while i < u.max_iter {
+        // Negated so inf and NaN count as escaped.
+        if !(dot(z, z) <= BAILOUT) { break; }
+        z = formula(z, c);
+        i++;
+    }
+
+    // Interior points → 0.  Escaped points → smooth normalised count; an
+    // orbit that blew up to inf or NaN gets the plain count.
+    var t = 0.0;
+    if i < u.max_iter {
+        let r2 = dot(z, z);
+        var nu = 0.0;
+        if u.smoothing == 0u {
+            nu = 1.0;
+        } else if r2 < 1e30 {
+            // log2 |z| / log2 √BAILOUT, which a quadratic step keeps in [1, 2).
+            nu = clamp(log2(log2(r2) * 0.125), 0.0, 1.0);
+        }
+        t = clamp((f32(i) + 1.0 - nu) / f32(u.max_iter), 0.0, 1.0);
+    }
+
+    return vec4<f32>(t, 0.0, 0.0, 1.0);
+}
+
+// Average `u.samples` jittered samples; counts other than 2, 4 and 8 take one.
+// The grid starts at `u.origin`, so a pan can recompute only the strips it
+// exposed.
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let p  = gid.xy + u.origin;
+    let px = vec2<f32>(f32(p.x), f32(p.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+    let n = select(1u, u.samples, u.samples == 2u || u.samples == 4u || u.samples == 8u);
+    var sum = vec4<f32>(0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += shade(px + sample_offset(k, n));
+    }
+    textureStore(output, vec2<i32>(p), sum / f32(n));
+}
+
+// @formula
+fn formula(z: vec2<f32>, c: vec2<f32>) -> vec2<f32> {
+    return (c_powi(z, 2u) + c);
+}
@@ -0,0 +1,137 @@
+// Hybrid fractal — f64 compute shader, built only on devices with
+// SHADER_F64 and run past the zoom where f32 pixels merge, as
+// burning_ship_f64.wgsl is.
+//
+// The orbit of c starts at 0 and steps through a repeating schedule of two
+// formulas, chosen per iteration by a bit of `ship_steps`:
+//   M: z = z² + c                          (Mandelbrot)
+//   B: z = (|Re(z)| + i|Im(z)|)² + c       (Burning Ship)
+// "MMBB" is period 4 with bits 2 and 3 set.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+    smoothing:  u32, // 1 = log-log smoothed count, 0 = plain count
+    samples:    u32, // jittered samples per pixel: 1, 2, 4 or 8
+    pad1:       vec2<u32>,
+    center_df:  vec4<f32>, // unread here; see the df64 kernels
+    center64:   vec2<f64>,
+    zoom64:     f64,
+    pad64:      f64,
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// Orbit trap for the orbit-trap coloring; mirrors `TrapUniforms` in
+// context.rs.
+struct TrapParams {
+    point:  vec2<f32>,
+    dir:    vec2<f32>, // unit direction of a line trap
+    radius: f32,       // of a circle trap
+    shape:  u32,       // 0 = point, 1 = line, 2 = circle
+    pad:    vec2<f32>,
+}
+
+// Coloring mode and schedule; mirrors `HybridUniforms` in context.rs.
+struct HybridParams {
+    coloring:    u32, // 0 = escape time, 1 = Pickover stalks, 2 = orbit trap
+    stalk_width: f32,
+    ship_steps:  u32, // bit i set: step i of the period is a Burning Ship step
+    period:      u32,
+    trap:        TrapParams,
+}
+@group(0) @binding(2) var<uniform> hp: HybridParams;
+
+// Brent periodicity check, as in mandelbrot.wgsl, but the saved point only
+// counts as a return at the same place in the schedule — the same z a
+// different step later follows a different formula.
+const PERIOD_START:   u32 = 8u;
+const PERIOD_EPSILON: f64 = 1e-28lf; // squared distance counted as a return
+
+// Pickover stalks: brighten wherever the orbit (past its starting point)
+// grazed a coordinate axis.  `trap` is its closest approach.
+fn stalks(t: f32, trap: f32) -> f32 {
+    if hp.coloring != 1u || hp.stalk_width <= 0.0 { return t; }
+    return max(t, 1.0 - clamp(trap / hp.stalk_width, 0.0, 1.0));
+}
+
+// Orbit trap: the orbit's closest approach to a point, a line through
+// `point` along `dir`, or a circle of `radius` around `point`.  Written to
+// the green channel, capped at TRAP_FAR so it fits a half float.
+const TRAP_FAR: f32 = 1e4;
+fn trap_distance(z: vec2<f32>) -> f32 {
+    let d = z - hp.trap.point;
+    switch hp.trap.shape {
+        case 1u:  { return abs(d.x * hp.trap.dir.y - d.y * hp.trap.dir.x); }
+        case 2u:  { return abs(length(d) - hp.trap.radius); }
+        default:  { return length(d); }
+    }
+}
+
+// One sample at `px`, in pixels.
+fn shade(px: vec2<f32>) -> vec4<f32> {
+
+    let uv = vec2<f64>(plane_uv(px)) / u.zoom64;
+    let c  = u.center64 + uv;
+    let period = max(hp.period, 1u);
+
+    var z = vec2<f64>(0.0lf, 0.0lf);
+    var i = 0u;
+    var step = 0u; // i mod period
+    var trap = 1e10;
+    var orbit = TRAP_FAR;
+    var saved      = z;
+    var saved_step = 0u;
+    var since  = 0u;
+    var window = PERIOD_START;
+    while i < u.max_iter {
+        if dot(z, z) > 4.0lf { break; }
+        if i > 0u {
+            let zf = vec2<f32>(z);
+            trap = min(trap, min(abs(zf.x), abs(zf.y)));
+            if hp.coloring == 2u { orbit = min(orbit, trap_distance(zf)); }
+        }
+        // Burning Ship takes abs of both components before squaring, which
+        // only flips the sign of Im(z²).
+        var im = 2.0lf * z.x * z.y;
+        if (hp.ship_steps & (1u << step)) != 0u { im = abs(im); }
+        z = vec2<f64>(z.x * z.x - z.y * z.y + c.x, im + c.y);
+        i++;
+        step++;
+        if step == period { step = 0u; }
+        let d = z - saved;
+        if step == saved_step && dot(d, d) < PERIOD_EPSILON { i = u.max_iter; break; }
+        since++;
+        if since == window { since = 0u; window *= 2u; saved = z; saved_step = step; }
+    }
+
+    var t = 0.0;
+    if i < u.max_iter {
+        let log_zn = log2(max(f32(dot(z, z)), 1e-10)) * 0.5;
+        let nu     = select(1.0, log2(max(log_zn, 1e-10)), u.smoothing == 1u);
+        t = clamp((f32(i) + 1.0 - nu) / f32(u.max_iter), 0.0, 1.0);
+    }
+
+    t = stalks(t, trap);
+
+    let g = select(0.0, orbit, hp.coloring == 2u);
+    return vec4<f32>(t, g, 0.0, 1.0);
+}
+
+// Average `u.samples` jittered samples; counts other than 2, 4 and 8 take one.
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+    let n = select(1u, u.samples, u.samples == 2u || u.samples == 4u || u.samples == 8u);
+    var sum = vec4<f32>(0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += shade(px + sample_offset(k, n));
+    }
+    textureStore(output, vec2<i32>(gid.xy), sum / f32(n));
+}
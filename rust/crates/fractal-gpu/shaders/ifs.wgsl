@@ -0,0 +1,139 @@
+// Iterated function system — compute shader, two entry points
+//
+// `scatter` plays the chaos game: each invocation is one random walker that
+// applies an affine map, picked by weight, at every step.  After a few
+// warm-up steps every walker sits on the attractor, and each point it lands
+// on adds one hit to its pixel in the density buffer (binding 5).
+//
+// `main` then runs per pixel, exactly as in inverse_julia.wgsl: it takes the
+// pixel's hits (clearing them for the next frame) and writes log-scaled
+// brightness to the r channel for the colour map.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32, // steps per walker
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+const MAPS: u32 = 4u;
+
+// Mirrors `IfsUniforms` in context.rs.
+struct IfsParams {
+    linear:   array<vec4<f32>, 4>, // a, b, c, d
+    offset:   array<vec4<f32>, 4>, // e, f, pick threshold, unused
+    exposure: f32,                 // hits that make a pixel full bright
+    pad0:     f32,
+    pad1:     f32,
+    pad2:     f32,
+}
+@group(0) @binding(2) var<uniform> ip: IfsParams;
+
+// Hit count per pixel, row-major.
+@group(0) @binding(5) var<storage, read_write> density: array<atomic<u32>>;
+
+const WARMUP:    u32 = 12u;
+const MAX_STEPS: u32 = 1024u;
+// A walker further out than this has escaped a non-contracting map.
+const ESCAPE:    f32 = 1e6;
+
+fn pcg(v: u32) -> u32 {
+    let s = v * 747796405u + 2891336453u;
+    let w = ((s >> ((s >> 28u) + 4u)) ^ s) * 277803737u;
+    return (w >> 22u) ^ w;
+}
+
+fn unit(v: u32) -> f32 {
+    return f32(v) / 4294967295.0;
+}
+
+// Plane position → pixel; the inverse of `plane_uv` in view.wgsl.  Negative
+// when the point falls outside the dome's view (or sits on the exp map's
+// centre, which no row reaches).
+fn plane_px(p: vec2<f32>) -> vec2<f32> {
+    let o   = (p - u.center) * u.zoom;
+    let mid = u.resolution * 0.5;
+    if u.projection == 2u {
+        let len = length(o);
+        if len < 1e-12 { return vec2<f32>(-1.0); }
+        let k     = 6.2831855 / u.resolution.x;
+        let angle = atan2(o.y, o.x);
+        let turn  = select(angle, angle + 6.2831855, angle < 0.0);
+        return vec2<f32>(turn, log(2.0 / len)) / k;
+    }
+    if u.projection == 0u { return mid + o * (u.resolution.y * 0.5); }
+    let len = length(o);
+    if len < 1e-6 { return mid; }
+    let theta = atan(len * 1.5707964);
+    if theta > 1.4835299 { return vec2<f32>(-1.0); }
+    let r = theta / 1.5707964;
+    return mid + o * (r * min(u.resolution.x, u.resolution.y) * 0.5 / len);
+}
+
+// The first map whose threshold lies above `r`; the host sets the last
+// weighted map's threshold to 1, so one always does.
+fn pick(r: f32) -> u32 {
+    for (var i = 0u; i < MAPS - 1u; i++) {
+        if r < ip.offset[i].z { return i; }
+    }
+    return MAPS - 1u;
+}
+
+fn random_point(state: u32) -> vec2<f32> {
+    return vec2<f32>(unit(state), unit(pcg(state))) * 2.0 - 1.0;
+}
+
+@compute @workgroup_size(64)
+fn scatter(@builtin(global_invocation_id) gid: vec3<u32>) {
+    var state = pcg(gid.x ^ pcg(bitcast<u32>(u.time)));
+    var z = random_point(state);
+    var settled = 0u;
+
+    let width = u32(u.resolution.x);
+    let steps = min(u.max_iter, MAX_STEPS) + WARMUP;
+    for (var i = 0u; i < steps; i++) {
+        state = pcg(state);
+        let m = pick(unit(state));
+        let l = ip.linear[m];
+        z = vec2<f32>(l.x * z.x + l.y * z.y, l.z * z.x + l.w * z.y) + ip.offset[m].xy;
+
+        // NaN fails every comparison, so test for "not inside" explicitly.
+        if !(abs(z.x) < ESCAPE && abs(z.y) < ESCAPE) {
+            state = pcg(state);
+            z = random_point(state);
+            settled = 0u;
+            continue;
+        }
+        settled++;
+        if settled <= WARMUP { continue; }
+
+        let px = plane_px(z);
+        if px.x < 0.0 || px.y < 0.0 || px.x >= u.resolution.x || px.y >= u.resolution.y {
+            continue;
+        }
+        let index = u32(px.y) * width + u32(px.x);
+        if index < arrayLength(&density) {
+            atomicAdd(&density[index], 1u);
+        }
+    }
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+
+    let index = gid.y * u32(u.resolution.x) + gid.x;
+    var hits = 0u;
+    if index < arrayLength(&density) {
+        hits = atomicExchange(&density[index], 0u);
+    }
+    let t = clamp(log2(1.0 + f32(hits)) / log2(1.0 + max(ip.exposure, 1.0)), 0.0, 1.0);
+
+    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(t, 0.0, 0.0, 1.0));
+}
@@ -2,9 +2,11 @@
 //
 // Identical iteration to Mandelbrot except:
 //   z starts at the pixel's complex position (center + uv)
-//   c is fixed, taken from uniforms.julia_c
+//   c is fixed, taken from the Julia extension block (binding 2)
+//   the distance estimate differentiates by z rather than c, and has no
+//   interior half: points inside the set write -1, "deep inside"
 //
-// For the PsychedelicJulia preset: julia_c = (-0.7, 0.27015)
+// For the PsychedelicJulia preset: c = (-0.7, 0.27015)
 
 struct Uniforms {
     resolution: vec2<f32>,
@@ -12,37 +14,149 @@ struct Uniforms {
     zoom:       f32,
     time:       f32,
     max_iter:   u32,
-    pad0:       u32,
-    julia_c:    vec2<f32>,
-    pad1:       vec2<f32>,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+    smoothing:  u32, // 1 = log-log smoothed count, 0 = plain count
+    samples:    u32, // jittered samples per pixel: 1, 2, 4 or 8
+    origin:     vec2<u32>, // pixel the dispatch starts at
 }
 
 @group(0) @binding(0) var<uniform> u: Uniforms;
 @group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
 
-@compute @workgroup_size(8, 8)
-fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let px = vec2<f32>(f32(gid.x), f32(gid.y));
-    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+// Orbit trap for the orbit-trap coloring; mirrors `TrapUniforms` in
+// context.rs.
+struct TrapParams {
+    point:  vec2<f32>,
+    dir:    vec2<f32>, // unit direction of a line trap
+    radius: f32,       // of a circle trap
+    shape:  u32,       // 0 = point, 1 = line, 2 = circle
+    pad:    vec2<f32>,
+}
+
+// Julia-only parameters plus the coloring mode; mirrors `JuliaUniforms`
+// in context.rs.
+struct JuliaParams {
+    c:           vec2<f32>,
+    coloring:    u32, // 0 = escape time, 1 = Pickover stalks, 2 = orbit trap, 3 = edge glow
+    stalk_width: f32,
+    distance:    u32, // 1 = write the distance estimate to alpha
+    pad0:        f32,
+    pad1:        vec2<f32>,
+    trap:        TrapParams,
+}
+@group(0) @binding(2) var<uniform> jp: JuliaParams;
+
+// Brent periodicity check, as in mandelbrot.wgsl: an orbit that returns to
+// its saved point is cycling, so the pixel is interior and stops early.
+const PERIOD_START:   u32 = 8u;
+const PERIOD_EPSILON: f32 = 1e-12; // squared distance counted as a return
+
+// Pickover stalks: brighten wherever the orbit (past its starting point)
+// grazed a coordinate axis.  `trap` is its closest approach.
+fn stalks(t: f32, trap: f32) -> f32 {
+    if jp.coloring != 1u || jp.stalk_width <= 0.0 { return t; }
+    return max(t, 1.0 - clamp(trap / jp.stalk_width, 0.0, 1.0));
+}
+
+// Orbit trap: the orbit's closest approach to a point, a line through
+// `point` along `dir`, or a circle of `radius` around `point`.  Written to
+// the green channel, capped at TRAP_FAR so it fits a half float.
+const TRAP_FAR: f32 = 1e4;
+fn trap_distance(z: vec2<f32>) -> f32 {
+    let d = z - jp.trap.point;
+    switch jp.trap.shape {
+        case 1u:  { return abs(d.x * jp.trap.dir.y - d.y * jp.trap.dir.x); }
+        case 2u:  { return abs(length(d) - jp.trap.radius); }
+        default:  { return length(d); }
+    }
+}
+
+fn c_mul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+// Distance estimate → alpha, as in mandelbrot.wgsl.
+fn distance_alpha(d: f32) -> f32 {
+    let px = min(d * u.zoom * u.resolution.y * 0.5, 1e6);
+    return px / (1.0 + abs(px));
+}
+
+// Exterior estimate 0.5·|z|·ln|z| / |dz/dz₀|, after a few more steps so the
+// orbit is far enough out for it to hold.
+const DE_RADIUS2: f32 = 1e8;
+fn exterior_distance(z_esc: vec2<f32>, dz_esc: vec2<f32>, c: vec2<f32>) -> f32 {
+    var z  = z_esc;
+    var dz = dz_esc;
+    for (var k = 0u; k < 8u && dot(z, z) < DE_RADIUS2; k++) {
+        dz = 2.0 * c_mul(z, dz);
+        z  = c_mul(z, z) + c;
+    }
+    let r = length(z);
+    let d = 0.5 * r * log(r) / length(dz);
+    return select(0.0, d, d >= 0.0);
+}
+
+// One sample at `px`, in pixels.
+fn shade(px: vec2<f32>) -> vec4<f32> {
 
     // z starts at the pixel's position in the complex plane
-    let uv = (px - u.resolution * 0.5) / (u.zoom * u.resolution.y * 0.5);
+    let uv = plane_uv(px) / u.zoom;
     var z  = u.center + uv;
-    let c  = u.julia_c;
+    let c  = jp.c;
+    let de = jp.distance == 1u;
+    var dz = vec2<f32>(1.0, 0.0);
 
     var i = 0u;
+    var trap = 1e10;
+    var orbit = TRAP_FAR;
+    var saved  = z;
+    var period = 0u;
+    var window = PERIOD_START;
     while i < u.max_iter {
         if dot(z, z) > 4.0 { break; }
+        if i > 0u {
+            trap = min(trap, min(abs(z.x), abs(z.y)));
+            if jp.coloring == 2u { orbit = min(orbit, trap_distance(z)); }
+        }
+        if de { dz = 2.0 * c_mul(z, dz); }
         z = vec2<f32>(z.x * z.x - z.y * z.y + c.x, 2.0 * z.x * z.y + c.y);
         i++;
+        let d = z - saved;
+        if dot(d, d) < PERIOD_EPSILON { i = u.max_iter; break; }
+        period++;
+        if period == window { period = 0u; window *= 2u; saved = z; }
     }
 
     var t = 0.0;
     if i < u.max_iter {
         let log_zn = log2(max(dot(z, z), 1e-10)) * 0.5;
-        let nu     = log2(max(log_zn, 1e-10));
+        let nu     = select(1.0, log2(max(log_zn, 1e-10)), u.smoothing == 1u);
         t = clamp((f32(i) + 1.0 - nu) / f32(u.max_iter), 0.0, 1.0);
     }
 
-    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(t, 0.0, 0.0, 1.0));
+    t = stalks(t, trap);
+
+    var a = 1.0;
+    if de {
+        a = select(-1.0, distance_alpha(exterior_distance(z, dz, c)), i < u.max_iter);
+    }
+
+    let g = select(0.0, orbit, jp.coloring == 2u);
+    return vec4<f32>(t, g, 0.0, a);
+}
+
+// Average `u.samples` jittered samples; counts other than 2, 4 and 8 take one.
+// The grid starts at `u.origin`, so a pan can recompute only the strips it
+// exposed.
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let p  = gid.xy + u.origin;
+    let px = vec2<f32>(f32(p.x), f32(p.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+    let n = select(1u, u.samples, u.samples == 2u || u.samples == 4u || u.samples == 8u);
+    var sum = vec4<f32>(0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += shade(px + sample_offset(k, n));
+    }
+    textureStore(output, vec2<i32>(p), sum / f32(n));
 }
@@ -0,0 +1,136 @@
+// Julia set — df64 compute shader
+//
+// df64 variant for zooms past f32 precision on GPUs without SHADER_F64:
+// the orbit is iterated in double-float pairs from df64.wgsl, prepended at
+// build time, and colored from the hi halves as the f32 shader does.
+//
+// Identical iteration to Mandelbrot except:
+//   z starts at the pixel's complex position (center + uv)
+//   c is fixed, taken from the Julia extension block (binding 2)
+//
+// For the PsychedelicJulia preset: c = (-0.7, 0.27015)
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+    smoothing:  u32, // 1 = log-log smoothed count, 0 = plain count
+    samples:    u32, // jittered samples per pixel: 1, 2, 4 or 8
+    pad1:       vec2<u32>,
+    center_df:  vec4<f32>, // the centre as df64: (re.hi, re.lo, im.hi, im.lo)
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// Orbit trap for the orbit-trap coloring; mirrors `TrapUniforms` in
+// context.rs.
+struct TrapParams {
+    point:  vec2<f32>,
+    dir:    vec2<f32>, // unit direction of a line trap
+    radius: f32,       // of a circle trap
+    shape:  u32,       // 0 = point, 1 = line, 2 = circle
+    pad:    vec2<f32>,
+}
+
+// Julia-only parameters plus the coloring mode; mirrors `JuliaUniforms`
+// in context.rs.
+struct JuliaParams {
+    c:           vec2<f32>,
+    coloring:    u32, // 0 = escape time, 1 = Pickover stalks, 2 = orbit trap
+    stalk_width: f32,
+    distance:    u32, // unread here; see julia.wgsl
+    pad0:        f32,
+    pad1:        vec2<f32>,
+    trap:        TrapParams,
+}
+@group(0) @binding(2) var<uniform> jp: JuliaParams;
+
+// Brent periodicity check, as in mandelbrot.wgsl: an orbit that returns to
+// its saved point is cycling, so the pixel is interior and stops early.
+const PERIOD_START:   u32 = 8u;
+const PERIOD_EPSILON: f32 = 1e-26; // squared distance counted as a return, at df64 precision
+
+// Pickover stalks: brighten wherever the orbit (past its starting point)
+// grazed a coordinate axis.  `trap` is its closest approach.
+fn stalks(t: f32, trap: f32) -> f32 {
+    if jp.coloring != 1u || jp.stalk_width <= 0.0 { return t; }
+    return max(t, 1.0 - clamp(trap / jp.stalk_width, 0.0, 1.0));
+}
+
+// Orbit trap: the orbit's closest approach to a point, a line through
+// `point` along `dir`, or a circle of `radius` around `point`.  Written to
+// the green channel, capped at TRAP_FAR so it fits a half float.
+const TRAP_FAR: f32 = 1e4;
+fn trap_distance(z: vec2<f32>) -> f32 {
+    let d = z - jp.trap.point;
+    switch jp.trap.shape {
+        case 1u:  { return abs(d.x * jp.trap.dir.y - d.y * jp.trap.dir.x); }
+        case 2u:  { return abs(length(d) - jp.trap.radius); }
+        default:  { return length(d); }
+    }
+}
+
+// One sample at `px`, in pixels.
+fn shade(px: vec2<f32>) -> vec4<f32> {
+
+    // z starts at the pixel's position in the complex plane
+    let z0 = dc_offset(u.center_df, plane_uv(px) / u.zoom);
+    let c  = vec4<f32>(jp.c.x, 0.0, jp.c.y, 0.0);
+
+    var zx = z0.xy;
+    var zy = z0.zw;
+    var i = 0u;
+    var trap = 1e10;
+    var orbit = TRAP_FAR;
+    var saved  = z0;
+    var period = 0u;
+    var window = PERIOD_START;
+    while i < u.max_iter {
+        let xx = df_mul(zx, zx);
+        let yy = df_mul(zy, zy);
+        if xx.x + yy.x > 4.0 { break; }
+        if i > 0u {
+            let z = vec2<f32>(zx.x, zy.x);
+            trap = min(trap, min(abs(z.x), abs(z.y)));
+            if jp.coloring == 2u { orbit = min(orbit, trap_distance(z)); }
+        }
+        let xy = df_mul(zx, zy);
+        zx = df_add(df_sub(xx, yy), c.xy);
+        zy = df_add(df_twice(xy), c.zw);
+        i++;
+        let d = vec2<f32>(df_sub(zx, saved.xy).x, df_sub(zy, saved.zw).x);
+        if dot(d, d) < PERIOD_EPSILON { i = u.max_iter; break; }
+        period++;
+        if period == window { period = 0u; window *= 2u; saved = vec4<f32>(zx, zy); }
+    }
+
+    var t = 0.0;
+    if i < u.max_iter {
+        let z      = vec2<f32>(zx.x, zy.x);
+        let log_zn = log2(max(dot(z, z), 1e-10)) * 0.5;
+        let nu     = select(1.0, log2(max(log_zn, 1e-10)), u.smoothing == 1u);
+        t = clamp((f32(i) + 1.0 - nu) / f32(u.max_iter), 0.0, 1.0);
+    }
+
+    t = stalks(t, trap);
+
+    let g = select(0.0, orbit, jp.coloring == 2u);
+    return vec4<f32>(t, g, 0.0, 1.0);
+}
+
+// Average `u.samples` jittered samples; counts other than 2, 4 and 8 take one.
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+    let n = select(1u, u.samples, u.samples == 2u || u.samples == 4u || u.samples == 8u);
+    var sum = vec4<f32>(0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += shade(px + sample_offset(k, n));
+    }
+    textureStore(output, vec2<i32>(gid.xy), sum / f32(n));
+}
@@ -0,0 +1,108 @@
+// Kleinian limit set — compute shader
+//
+// Maskit's slice of Kleinian groups, after Jos Leys and knighty.  The group
+// is generated by a translation by 2 along the (sheared) real axis and the
+// Möbius map `a` whose trace is `t` = kp.trace.  Each pixel's point is
+// folded into the fundamental strip 0 ≤ y ≤ t.x, moved to the lower half
+// of it past the separation line, and pushed through `a`, until it:
+//
+//   leaves the strip      → it lies outside the limit set; brightness grows
+//                           with the steps it took to get out
+//   falls into a 2-cycle  → it sits inside one of the tangent discs; black
+//   runs out of steps     → it lies on (or very near) the limit set; full
+//                           brightness
+//
+// Brightness goes to the r channel for the colour map, like the escape-time
+// generators.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+    smoothing:  u32, // unread here
+    samples:    u32, // jittered samples per pixel: 1, 2, 4 or 8
+    origin:     vec2<u32>, // pixel the dispatch starts at
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// Mirrors `KleinianUniforms` in context.rs.
+struct KleinianParams {
+    trace: vec2<f32>, // re = strip height, im = shear
+    pad:   vec2<f32>,
+}
+@group(0) @binding(2) var<uniform> kp: KleinianParams;
+
+// Squared distance between consecutive-but-one points counted as a 2-cycle.
+const CYCLE_EPSILON: f32 = 1e-6;
+
+// `x` wrapped into [s, s + period).
+fn wrap(x: f32, period: f32, s: f32) -> f32 {
+    let y = x - s;
+    return y - period * floor(y / period) + s;
+}
+
+// The generator `a` as Jos Leys writes it: invert in the unit circle,
+// reflect, and shift by the trace t = (re, im) = (a, b).
+fn trans_a(z: vec2<f32>, a: f32, b: f32) -> vec2<f32> {
+    let w = -z / max(dot(z, z), 1e-20);
+    return vec2<f32>(-b - w.x, a + w.y);
+}
+
+// One sample at `px`, in pixels.
+fn shade(px: vec2<f32>) -> vec4<f32> {
+
+    var z = u.center + plane_uv(px) / u.zoom;
+    let a = kp.trace.x;
+    let b = kp.trace.y;
+    let f = select(-1.0, 1.0, b >= 0.0);
+    let shear = f * b / a;
+
+    var lz  = z + vec2<f32>(1.0, 0.0);
+    var llz = z - vec2<f32>(1.0, 0.0);
+    var t = 1.0;
+    for (var i = 0u; i < u.max_iter; i++) {
+        // Translate into the fundamental strip along the sheared axis.
+        z.x = wrap(z.x + shear * z.y, 2.0, -1.0) - shear * z.y;
+
+        // Above the separation line, turn half way round (−b/2, a/2).
+        let dx = z.x + b * 0.5;
+        let line = a * 0.5 + f * (2.0 * a - 1.95) / 4.0 * sign(dx)
+            * (1.0 - exp(-(7.2 - (1.95 - a) * 15.0) * abs(dx)));
+        if z.y >= line { z = vec2<f32>(-b, a) - z; }
+
+        z = trans_a(z, a, b);
+
+        let d = z - llz;
+        if dot(d, d) < CYCLE_EPSILON { t = 0.0; break; }
+        // NaN fails every comparison, so test for "not inside" explicitly.
+        if !(z.y >= 0.0 && z.y <= a) {
+            t = f32(i + 1u) / f32(u.max_iter);
+            break;
+        }
+        llz = lz;
+        lz = z;
+    }
+
+    return vec4<f32>(t, 0.0, 0.0, 1.0);
+}
+
+// Average `u.samples` jittered samples; counts other than 2, 4 and 8 take one.
+// The grid starts at `u.origin`, so a pan can recompute only the strips it
+// exposed.
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let p  = gid.xy + u.origin;
+    let px = vec2<f32>(f32(p.x), f32(p.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+    let n = select(1u, u.samples, u.samples == 2u || u.samples == 4u || u.samples == 8u);
+    var sum = vec4<f32>(0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += shade(px + sample_offset(k, n));
+    }
+    textureStore(output, vec2<i32>(p), sum / f32(n));
+}
@@ -3,7 +3,12 @@
 // For each pixel, iterate z = z² + c where c is the point on the complex plane
 // and z starts at 0.  Store a smooth normalised iteration count in the red
 // channel of the output texture.  The colour_map effect shader converts this
-// [0, 1] value to an RGB colour.
+// [0, 1] value to an RGB colour.  With Pickover-stalk coloring (binding 2)
+// the orbit's closest approach to the axes brightens it further; with
+// orbit-trap coloring its closest approach to the trap goes in the green
+// channel, which the colour map then paints instead.  With the distance
+// estimate on, the alpha channel holds the distance to the set's boundary
+// (see `distance_alpha`), which edge-glow coloring paints.
 
 struct Uniforms {
     resolution: vec2<f32>,
@@ -11,29 +16,147 @@ struct Uniforms {
     zoom:       f32,
     time:       f32,
     max_iter:   u32,
-    pad0:       u32,
-    julia_c:    vec2<f32>,
-    pad1:       vec2<f32>,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+    smoothing:  u32, // 1 = log-log smoothed count, 0 = plain count
+    samples:    u32, // jittered samples per pixel: 1, 2, 4 or 8
+    origin:     vec2<u32>, // pixel the dispatch starts at
 }
 
 @group(0) @binding(0) var<uniform> u: Uniforms;
 @group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
 
-@compute @workgroup_size(8, 8)
-fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let px = vec2<f32>(f32(gid.x), f32(gid.y));
-    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+// Orbit trap for the orbit-trap coloring; mirrors `TrapUniforms` in
+// context.rs.
+struct TrapParams {
+    point:  vec2<f32>,
+    dir:    vec2<f32>, // unit direction of a line trap
+    radius: f32,       // of a circle trap
+    shape:  u32,       // 0 = point, 1 = line, 2 = circle
+    pad:    vec2<f32>,
+}
+
+// Coloring mode; mirrors `ColoringUniforms` in context.rs.
+struct ColoringParams {
+    coloring:    u32, // 0 = escape time, 1 = Pickover stalks, 2 = orbit trap, 3 = edge glow
+    stalk_width: f32,
+    distance:    u32, // 1 = write the distance estimate to alpha
+    pad:         f32,
+    trap:        TrapParams,
+}
+@group(0) @binding(2) var<uniform> cp: ColoringParams;
+
+// Brent periodicity check: the orbit is compared against a saved point that
+// is replaced after 8, 16, 32, … steps.  Landing back on it means the orbit
+// has fallen into a cycle and will never escape, so interior points stop
+// early instead of running all `max_iter` steps.
+const PERIOD_START:   u32 = 8u;
+const PERIOD_EPSILON: f32 = 1e-12; // squared distance counted as a return
+
+// Pickover stalks: brighten wherever the orbit (past its starting point)
+// grazed a coordinate axis.  `trap` is its closest approach.
+fn stalks(t: f32, trap: f32) -> f32 {
+    if cp.coloring != 1u || cp.stalk_width <= 0.0 { return t; }
+    return max(t, 1.0 - clamp(trap / cp.stalk_width, 0.0, 1.0));
+}
+
+// Orbit trap: the orbit's closest approach to a point, a line through
+// `point` along `dir`, or a circle of `radius` around `point`.  Written to
+// the green channel, capped at TRAP_FAR so it fits a half float.
+const TRAP_FAR: f32 = 1e4;
+fn trap_distance(z: vec2<f32>) -> f32 {
+    let d = z - cp.trap.point;
+    switch cp.trap.shape {
+        case 1u:  { return abs(d.x * cp.trap.dir.y - d.y * cp.trap.dir.x); }
+        case 2u:  { return abs(length(d) - cp.trap.radius); }
+        default:  { return length(d); }
+    }
+}
+
+fn c_mul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+fn c_div(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return c_mul(a, vec2<f32>(b.x, -b.y)) / dot(b, b);
+}
+
+// Distance estimate → alpha: plane units scaled to pixels (at the flat
+// projection's scale), then squashed to d / (1 + d) so any distance fits a
+// half float, 0 on the boundary.  Outside is positive, inside negative;
+// with the estimate off alpha stays 1, "far outside".
+fn distance_alpha(d: f32) -> f32 {
+    let px = min(d * u.zoom * u.resolution.y * 0.5, 1e6);
+    return px / (1.0 + abs(px));
+}
+
+// Exterior estimate 0.5·|z|·ln|z| / |dz/dc|, after a few more steps so the
+// orbit is far enough out for it to hold.  An overflowed derivative means
+// the pixel is on the boundary.
+const DE_RADIUS2: f32 = 1e8;
+fn exterior_distance(z_esc: vec2<f32>, dz_esc: vec2<f32>, c: vec2<f32>) -> f32 {
+    var z  = z_esc;
+    var dz = dz_esc;
+    for (var k = 0u; k < 8u && dot(z, z) < DE_RADIUS2; k++) {
+        dz = 2.0 * c_mul(z, dz) + vec2<f32>(1.0, 0.0);
+        z  = c_mul(z, z) + c;
+    }
+    let r = length(z);
+    let d = 0.5 * r * log(r) / length(dz);
+    return select(0.0, d, d >= 0.0);
+}
+
+// Interior estimate for an orbit that fell into a cycle of length `p`
+// through `z0`: (1 − |A|²) / |D + C·B / (1 − A)|, with A = ∂z/∂z,
+// B = ∂²z/∂z², C = ∂z/∂c and D = ∂²z/∂z∂c taken once round the cycle.
+fn interior_distance(z0: vec2<f32>, p: u32, c: vec2<f32>) -> f32 {
+    let one = vec2<f32>(1.0, 0.0);
+    var z  = z0;
+    var a  = one;
+    var b  = vec2<f32>(0.0, 0.0);
+    var dc = vec2<f32>(0.0, 0.0);
+    var d  = vec2<f32>(0.0, 0.0);
+    for (var k = 0u; k < p; k++) {
+        d  = 2.0 * (c_mul(a, dc) + c_mul(z, d));
+        b  = 2.0 * (c_mul(a, a) + c_mul(z, b));
+        dc = 2.0 * c_mul(z, dc) + one;
+        a  = 2.0 * c_mul(z, a);
+        z  = c_mul(z, z) + c;
+    }
+    let denom = length(d + c_div(c_mul(dc, b), one - a));
+    let e = max(1.0 - dot(a, a), 0.0) / denom;
+    return select(0.0, e, e >= 0.0);
+}
+
+// One sample at `px`, in pixels.
+fn shade(px: vec2<f32>) -> vec4<f32> {
 
     // Map pixel → complex plane
-    let uv = (px - u.resolution * 0.5) / (u.zoom * u.resolution.y * 0.5);
+    let uv = plane_uv(px) / u.zoom;
     let c  = u.center + uv;
 
+    let de = cp.distance == 1u;
     var z  = vec2<f32>(0.0, 0.0);
+    var dz = vec2<f32>(0.0, 0.0);
+    var cycle = 0u; // length of the cycle the orbit fell into
     var i  = 0u;
+    var trap = 1e10;
+    var orbit = TRAP_FAR;
+    var saved  = z;
+    var period = 0u;
+    var window = PERIOD_START;
     while i < u.max_iter {
         if dot(z, z) > 4.0 { break; }
+        if i > 0u {
+            trap = min(trap, min(abs(z.x), abs(z.y)));
+            if cp.coloring == 2u { orbit = min(orbit, trap_distance(z)); }
+        }
+        if de { dz = 2.0 * c_mul(z, dz) + vec2<f32>(1.0, 0.0); }
         z = vec2<f32>(z.x * z.x - z.y * z.y + c.x, 2.0 * z.x * z.y + c.y);
         i++;
+        let d = z - saved;
+        if dot(d, d) < PERIOD_EPSILON { i = u.max_iter; cycle = period + 1u; break; }
+        period++;
+        if period == window { period = 0u; window *= 2u; saved = z; }
     }
 
     // Interior points → 0.  Escaped points → smooth normalised count.
@@ -41,9 +164,40 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     if i < u.max_iter {
         // log-log escape smoothing (Inigo Quilez)
         let log_zn = log2(max(dot(z, z), 1e-10)) * 0.5;
-        let nu     = log2(max(log_zn, 1e-10));
+        let nu     = select(1.0, log2(max(log_zn, 1e-10)), u.smoothing == 1u);
         t = clamp((f32(i) + 1.0 - nu) / f32(u.max_iter), 0.0, 1.0);
     }
 
-    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(t, 0.0, 0.0, 1.0));
+    t = stalks(t, trap);
+
+    // No cycle found by `max_iter` counts as deep inside.
+    var a = 1.0;
+    if de {
+        if i < u.max_iter {
+            a = distance_alpha(exterior_distance(z, dz, c));
+        } else if cycle > 0u {
+            a = -distance_alpha(interior_distance(z, cycle, c));
+        } else {
+            a = -1.0;
+        }
+    }
+
+    let g = select(0.0, orbit, cp.coloring == 2u);
+    return vec4<f32>(t, g, 0.0, a);
+}
+
+// Average `u.samples` jittered samples; counts other than 2, 4 and 8 take one.
+// The grid starts at `u.origin`, so a pan can recompute only the strips it
+// exposed.
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let p  = gid.xy + u.origin;
+    let px = vec2<f32>(f32(p.x), f32(p.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+    let n = select(1u, u.samples, u.samples == 2u || u.samples == 4u || u.samples == 8u);
+    var sum = vec4<f32>(0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += shade(px + sample_offset(k, n));
+    }
+    textureStore(output, vec2<i32>(p), sum / f32(n));
 }
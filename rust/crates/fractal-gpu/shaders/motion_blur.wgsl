@@ -1,6 +1,7 @@
-// Motion blur — blends the current frame with a history accumulation buffer.
-// The accumulation texture is wired up in Phase 6; for now this is a
-// pass-through that compiles and dispatches correctly.
+// Motion blur — directional streak along `direction * length` pixels.
+// The history accumulation buffer (trail opacity) is wired up in Phase 6;
+// until then `opacity` is carried but unused and a zero `length` is a
+// pass-through.
 
 struct Uniforms {
     resolution : vec2<f32>,
@@ -9,26 +10,36 @@ struct Uniforms {
     time       : f32,
     max_iter   : u32,
     _pad       : u32,
-    julia_c    : vec2<f32>,
-    _pad2      : vec2<f32>,
 }
 struct MotionBlurParams {
     opacity : f32,
-    _pad0   : f32,
-    _pad1   : f32,
-    _pad2   : f32,
+    dir_x   : f32,  // scalars, not vec2: keeps the 16-byte packed layout
+    dir_y   : f32,
+    length  : f32,
 }
 
 @group(0) @binding(0) var<uniform>  u      : Uniforms;
 @group(0) @binding(1) var<uniform>  mp     : MotionBlurParams;
 @group(0) @binding(2) var           input  : texture_2d<f32>;
 @group(0) @binding(3) var           output : texture_storage_2d<rgba16float, write>;
+@group(0) @binding(4) var           samp   : sampler;
+
+const TAPS: u32 = 9u;
 
 @compute @workgroup_size(8, 8)
 fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let px = vec2<i32>(i32(gid.x), i32(gid.y));
-    if f32(gid.x) >= u.resolution.x || f32(gid.y) >= u.resolution.y { return; }
-    // Pass-through — accumulation buffer blending wired in Phase 6.
-    let color = textureLoad(input, px, 0);
-    textureStore(output, px, color);
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+
+    // Centre the streak on the pixel: taps span [-length/2, +length/2].
+    let step = vec2<f32>(mp.dir_x, mp.dir_y) * (mp.length / f32(TAPS - 1u));
+    let base = px + 0.5 - step * f32(TAPS - 1u) * 0.5;
+
+    var colour = vec4<f32>(0.0);
+    for (var t = 0u; t < TAPS; t++) {
+        let src_uv = (base + step * f32(t)) / u.resolution;
+        colour    += textureSampleLevel(input, samp, src_uv, 0.0);
+    }
+
+    textureStore(output, vec2<i32>(gid.xy), colour / f32(TAPS));
 }
@@ -0,0 +1,136 @@
+// Multibrot set — compute shader
+//
+// For each pixel, iterate z = z^d + c where c is the point on the complex
+// plane, z starts at 0 and d is a real exponent (binding 2) that an LFO can
+// sweep.  z^d is taken in polar form, so fractional powers work (with a
+// branch cut along the negative real axis).  d = 2 is the Mandelbrot set.
+// Stores a smooth normalised iteration count in the red channel, like
+// mandelbrot.wgsl, Pickover stalks included.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+    smoothing:  u32, // 1 = log-log smoothed count, 0 = plain count
+    samples:    u32, // jittered samples per pixel: 1, 2, 4 or 8
+    origin:     vec2<u32>, // pixel the dispatch starts at
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// Orbit trap for the orbit-trap coloring; mirrors `TrapUniforms` in
+// context.rs.
+struct TrapParams {
+    point:  vec2<f32>,
+    dir:    vec2<f32>, // unit direction of a line trap
+    radius: f32,       // of a circle trap
+    shape:  u32,       // 0 = point, 1 = line, 2 = circle
+    pad:    vec2<f32>,
+}
+
+// Exponent and coloring mode; mirrors `MultibrotUniforms` in context.rs.
+struct MultibrotParams {
+    power:       f32, // clamped to [1, 16] on the CPU
+    coloring:    u32, // 0 = escape time, 1 = Pickover stalks, 2 = orbit trap
+    stalk_width: f32,
+    pad:         f32,
+    trap:        TrapParams,
+}
+@group(0) @binding(2) var<uniform> mp: MultibrotParams;
+
+// Brent periodicity check, as in mandelbrot.wgsl.
+const PERIOD_START:   u32 = 8u;
+const PERIOD_EPSILON: f32 = 1e-12; // squared distance counted as a return
+
+// Pickover stalks: brighten wherever the orbit (past its starting point)
+// grazed a coordinate axis.  `trap` is its closest approach.
+fn stalks(t: f32, trap: f32) -> f32 {
+    if mp.coloring != 1u || mp.stalk_width <= 0.0 { return t; }
+    return max(t, 1.0 - clamp(trap / mp.stalk_width, 0.0, 1.0));
+}
+
+// Orbit trap: the orbit's closest approach to a point, a line through
+// `point` along `dir`, or a circle of `radius` around `point`.  Written to
+// the green channel, capped at TRAP_FAR so it fits a half float.
+const TRAP_FAR: f32 = 1e4;
+fn trap_distance(z: vec2<f32>) -> f32 {
+    let d = z - mp.trap.point;
+    switch mp.trap.shape {
+        case 1u:  { return abs(d.x * mp.trap.dir.y - d.y * mp.trap.dir.x); }
+        case 2u:  { return abs(length(d) - mp.trap.radius); }
+        default:  { return length(d); }
+    }
+}
+
+// z^d in polar form; 0^d = 0 (atan2 is undefined at the origin).
+fn cpow(z: vec2<f32>, d: f32) -> vec2<f32> {
+    let r2 = dot(z, z);
+    if r2 == 0.0 { return vec2<f32>(0.0, 0.0); }
+    let r     = pow(r2, 0.5 * d);
+    let theta = atan2(z.y, z.x) * d;
+    return r * vec2<f32>(cos(theta), sin(theta));
+}
+
+// One sample at `px`, in pixels.
+fn shade(px: vec2<f32>) -> vec4<f32> {
+
+    // Map pixel → complex plane
+    let uv = plane_uv(px) / u.zoom;
+    let c  = u.center + uv;
+    let d  = mp.power;
+
+    var z  = vec2<f32>(0.0, 0.0);
+    var i  = 0u;
+    var trap = 1e10;
+    var orbit = TRAP_FAR;
+    var saved  = z;
+    var period = 0u;
+    var window = PERIOD_START;
+    while i < u.max_iter {
+        if dot(z, z) > 4.0 { break; }
+        if i > 0u {
+            trap = min(trap, min(abs(z.x), abs(z.y)));
+            if mp.coloring == 2u { orbit = min(orbit, trap_distance(z)); }
+        }
+        z = cpow(z, d) + c;
+        i++;
+        let dz = z - saved;
+        if dot(dz, dz) < PERIOD_EPSILON { i = u.max_iter; break; }
+        period++;
+        if period == window { period = 0u; window *= 2u; saved = z; }
+    }
+
+    // Interior points → 0.  Escaped points → smooth normalised count; the
+    // log-log smoothing divides by log d, since |z| grows as |z|^d.
+    var t = 0.0;
+    if i < u.max_iter {
+        let log_zn = log2(max(dot(z, z), 1e-10)) * 0.5;
+        let nu     = select(1.0, log2(max(log_zn, 1e-10)) / log2(max(d, 1.0001)), u.smoothing == 1u);
+        t = clamp((f32(i) + 1.0 - nu) / f32(u.max_iter), 0.0, 1.0);
+    }
+
+    t = stalks(t, trap);
+
+    let g = select(0.0, orbit, mp.coloring == 2u);
+    return vec4<f32>(t, g, 0.0, 1.0);
+}
+
+// Average `u.samples` jittered samples; counts other than 2, 4 and 8 take one.
+// The grid starts at `u.origin`, so a pan can recompute only the strips it
+// exposed.
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let p  = gid.xy + u.origin;
+    let px = vec2<f32>(f32(p.x), f32(p.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+    let n = select(1u, u.samples, u.samples == 2u || u.samples == 4u || u.samples == 8u);
+    var sum = vec4<f32>(0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += shade(px + sample_offset(k, n));
+    }
+    textureStore(output, vec2<i32>(p), sum / f32(n));
+}
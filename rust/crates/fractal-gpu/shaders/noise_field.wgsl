@@ -1,8 +1,11 @@
 // Noise field — compute shader
 //
 // Approximates the Clojure NoiseGenerator which uses Quil/Processing Perlin
-// noise at scale 0.01 animated with `time`.  Here we implement 4-octave FBM
-// (fractional Brownian motion) with smooth value noise entirely in WGSL.
+// noise at scale 0.01 animated with `time`.  Here we implement FBM (fractional
+// Brownian motion) over 3-D simplex noise entirely in WGSL.  The pixel picks
+// the x/y position and `noise_z` picks the slice through the volume, so the
+// field evolves in place rather than scrolling.  Scale, octaves, lacunarity,
+// gain and seed are read from the noise extension block (binding 2).
 //
 // Output: normalised noise value in the red channel [0, 1].
 
@@ -12,49 +15,118 @@ struct Uniforms {
     zoom:       f32,
     time:       f32,
     max_iter:   u32,
-    pad0:       u32,
-    julia_c:    vec2<f32>,
-    pad1:       vec2<f32>,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
 }
 
 @group(0) @binding(0) var<uniform> u: Uniforms;
 @group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
 
+// NoiseField-only parameters; mirrors `NoiseUniforms` in context.rs.
+struct NoiseParams {
+    scale:      f32,
+    octaves:    u32,
+    lacunarity: f32,
+    gain:       f32,
+    seed:       f32,
+    z:          f32,
+    pad:        vec2<f32>,
+}
+@group(0) @binding(2) var<uniform> np: NoiseParams;
+
 // ---------------------------------------------------------------------------
-// Value noise helpers
+// 3-D simplex noise (Ashima Arts / Stefan Gustavson, MIT licence)
 // ---------------------------------------------------------------------------
 
-// Hash a 2-D grid point to a pseudo-random scalar in [0, 1].
-fn hash2(p: vec2<f32>) -> f32 {
-    var q = fract(p * vec2<f32>(0.1031, 0.1030));
-    q += dot(q, q.yx + 33.33);
-    return fract((q.x + q.y) * q.x);
+fn mod289_3(x: vec3<f32>) -> vec3<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn mod289_4(x: vec4<f32>) -> vec4<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn permute4(x: vec4<f32>) -> vec4<f32> {
+    return mod289_4(((x * 34.0) + 1.0) * x);
+}
+
+fn taylor_inv_sqrt4(r: vec4<f32>) -> vec4<f32> {
+    return 1.79284291400159 - 0.85373472095314 * r;
 }
 
-// Smooth (C² continuous) value noise.
-fn vnoise(p: vec2<f32>) -> f32 {
-    let i = floor(p);
-    let f = fract(p);
-    // Quintic smoothstep for better continuity
-    let u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
-    return mix(
-        mix(hash2(i + vec2<f32>(0.0, 0.0)), hash2(i + vec2<f32>(1.0, 0.0)), u.x),
-        mix(hash2(i + vec2<f32>(0.0, 1.0)), hash2(i + vec2<f32>(1.0, 1.0)), u.x),
-        u.y,
-    );
+// Returns a value in roughly [-1, 1].
+fn snoise3(v: vec3<f32>) -> f32 {
+    let C = vec2<f32>(1.0 / 6.0, 1.0 / 3.0);
+    let D = vec4<f32>(0.0, 0.5, 1.0, 2.0);
+
+    // First corner
+    var i  = floor(v + dot(v, C.yyy));
+    let x0 = v - i + dot(i, C.xxx);
+
+    // Other corners
+    let g  = step(x0.yzx, x0.xyz);
+    let l  = 1.0 - g;
+    let i1 = min(g.xyz, l.zxy);
+    let i2 = max(g.xyz, l.zxy);
+    let x1 = x0 - i1 + C.xxx;
+    let x2 = x0 - i2 + C.yyy;
+    let x3 = x0 - D.yyy;
+
+    // Permutations
+    i = mod289_3(i);
+    let p = permute4(permute4(permute4(
+              i.z + vec4<f32>(0.0, i1.z, i2.z, 1.0))
+            + i.y + vec4<f32>(0.0, i1.y, i2.y, 1.0))
+            + i.x + vec4<f32>(0.0, i1.x, i2.x, 1.0));
+
+    // Gradients: 7×7 points over a square, mapped onto an octahedron
+    let ns = (1.0 / 7.0) * D.wyz - D.xzx;
+    let j  = p - 49.0 * floor(p * ns.z * ns.z);
+    let x_ = floor(j * ns.z);
+    let y_ = floor(j - 7.0 * x_);
+    let x  = x_ * ns.x + ns.yyyy;
+    let y  = y_ * ns.x + ns.yyyy;
+    let h  = 1.0 - abs(x) - abs(y);
+    let b0 = vec4<f32>(x.xy, y.xy);
+    let b1 = vec4<f32>(x.zw, y.zw);
+    let s0 = floor(b0) * 2.0 + 1.0;
+    let s1 = floor(b1) * 2.0 + 1.0;
+    let sh = -step(h, vec4<f32>(0.0));
+    let a0 = b0.xzyw + s0.xzyw * sh.xxyy;
+    let a1 = b1.xzyw + s1.xzyw * sh.zzww;
+
+    // Normalise gradients
+    let n  = taylor_inv_sqrt4(vec4<f32>(
+        dot(a0.xy, a0.xy) + h.x * h.x,
+        dot(a0.zw, a0.zw) + h.y * h.y,
+        dot(a1.xy, a1.xy) + h.z * h.z,
+        dot(a1.zw, a1.zw) + h.w * h.w,
+    ));
+    let p0 = vec3<f32>(a0.xy, h.x) * n.x;
+    let p1 = vec3<f32>(a0.zw, h.y) * n.y;
+    let p2 = vec3<f32>(a1.xy, h.z) * n.z;
+    let p3 = vec3<f32>(a1.zw, h.w) * n.w;
+
+    // Mix final noise value
+    var m = max(0.6 - vec4<f32>(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3)), vec4<f32>(0.0));
+    m = m * m;
+    return 42.0 * dot(m * m, vec4<f32>(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
 }
 
-// 4-octave FBM — matches Clojure NoiseGenerator's `octaves` parameter.
-fn fbm(p: vec2<f32>) -> f32 {
+// FBM — octave count, lacunarity and gain come from the uniforms so they can
+// be modulated.  The loop is capped at 8 octaves (NoiseFieldGen::MAX_OCTAVES).
+// Returns a value in [0, 1].
+fn fbm(p: vec3<f32>) -> f32 {
     var value     = 0.0;
+    var norm      = 0.0;
     var amplitude = 0.5;
     var frequency = 1.0;
-    for (var oct = 0; oct < 4; oct++) {
-        value     += amplitude * vnoise(p * frequency);
-        frequency *= 2.0;
-        amplitude *= 0.5;
+    for (var oct = 0u; oct < min(np.octaves, 8u); oct++) {
+        value     += amplitude * snoise3(p * frequency);
+        norm      += amplitude;
+        frequency *= np.lacunarity;
+        amplitude *= np.gain;
     }
-    return value;
+    return 0.5 + 0.5 * value / max(norm, 1e-6);
 }
 
 // ---------------------------------------------------------------------------
@@ -67,17 +139,19 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
     if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
 
     // Map pixel → complex plane (same as other generators)
-    let uv = (px - u.resolution * 0.5) / (u.zoom * u.resolution.y * 0.5);
+    let uv = plane_uv(px) / u.zoom;
     let p  = u.center + uv;
 
-    // Scale to match Clojure's 0.01 pixel-scale at default zoom.
+    // Default scale (3.0) matches Clojure's 0.01 pixel-scale at default zoom.
     // At zoom=1, uv spans ~[-1.33, 1.33] x [-1, 1]; * 3 gives noise coords
     // comparable to 0.01 * 800px = 8 units.
-    let noise_scale = 3.0;
-    // Animate with time in two directions (mimics 3-D Perlin's time axis)
-    let animated = p * noise_scale + vec2<f32>(u.time * 0.10, u.time * 0.07);
+    // The seed shifts the lattice so different seeds give unrelated fields.
+    let seed_offset = vec2<f32>(np.seed * 17.13, np.seed * 31.71);
+    // `noise_z` is in seconds-like units (it defaults to `time`); 0.1 keeps
+    // the default evolution rate close to the old 2-D scroll speed.
+    let q = vec3<f32>(p * np.scale + seed_offset, np.z * 0.1);
 
-    let n = fbm(animated);
+    let n = fbm(q);
 
     textureStore(output, vec2<i32>(gid.xy), vec4<f32>(n, 0.0, 0.0, 1.0));
 }
@@ -0,0 +1,34 @@
+struct OverlayParams {
+    position : vec2<f32>, // overlay centre, 0–1 across the frame
+    scale    : f32,       // overlay height as a fraction of the frame's
+    opacity  : f32,
+}
+
+@group(0) @binding(0) var<uniform>  op             : OverlayParams;
+@group(0) @binding(1) var           base           : texture_2d<f32>;
+@group(0) @binding(2) var           image          : texture_2d<f32>;
+@group(0) @binding(3) var           image_sampler  : sampler;
+@group(0) @binding(4) var           output         : texture_storage_2d<rgba16float, write>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let size = textureDimensions(output);
+    if gid.x >= size.x || gid.y >= size.y { return; }
+    let coord = vec2<i32>(gid.xy);
+    let under = textureLoad(base, coord, 0);
+
+    // Overlay rectangle in pixels, keeping the image's aspect ratio.
+    let frame  = vec2<f32>(size);
+    let dims   = vec2<f32>(textureDimensions(image));
+    let height = op.scale * frame.y;
+    let extent = vec2<f32>(height * dims.x / max(dims.y, 1.0), height);
+    let st     = (vec2<f32>(gid.xy) + 0.5 - op.position * frame) / max(extent, vec2<f32>(1e-3)) + 0.5;
+
+    if any(st < vec2<f32>(0.0)) || any(st > vec2<f32>(1.0)) {
+        textureStore(output, coord, under);
+        return;
+    }
+    let over = textureSampleLevel(image, image_sampler, st, 0.0);
+    let a = over.a * op.opacity;
+    textureStore(output, coord, vec4<f32>(mix(under.rgb, over.rgb, a), under.a));
+}
@@ -0,0 +1,152 @@
+// Perturbation deep zoom — compute shader for Mandelbrot and Julia
+//
+// Past f32 precision the plane can't be sampled directly, so each pixel
+// iterates only its offset δ from a reference orbit Z_n the CPU computed in
+// high precision (binding 6):
+//
+//     δ_{n+1} = 2·Z_n·δ_n + δ_n² + δc
+//
+// with δc the pixel's offset from the reference for the Mandelbrot set; for
+// a Julia the offset goes into δ_0 instead, and δc is how far c has moved
+// since the reference was computed, so an animated c needn't recompute it
+// every frame.  When Z_n + δ_n
+// falls closer to 0 than δ_n, or the reference runs out, the pixel rebases:
+// it carries on from the reference's start with δ = Z_n + δ_n − Z_0, which
+// keeps δ small and avoids the classic glitches.  Output matches
+// mandelbrot.wgsl / julia.wgsl, without their periodicity check.
+//
+// The first `skip` steps are replaced by the cubic series the CPU fitted to
+// them (`ReferenceOrbit::series_skip`): δ_skip = A·d + B·d² + C·d³ for the
+// pixel's offset d from the reference, evaluated in d / r with the terms
+// pre-scaled by r, the frame's largest offset, so none overflows f32.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+    smoothing:  u32, // 1 = log-log smoothed count, 0 = plain count
+    samples:    u32, // jittered samples per pixel: 1, 2, 4 or 8
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// Orbit trap for the orbit-trap coloring; mirrors `TrapUniforms` in
+// context.rs.
+struct TrapParams {
+    point:  vec2<f32>,
+    dir:    vec2<f32>, // unit direction of a line trap
+    radius: f32,       // of a circle trap
+    shape:  u32,       // 0 = point, 1 = line, 2 = circle
+    pad:    vec2<f32>,
+}
+
+// Mirrors `PerturbationUniforms` in context.rs.
+struct PerturbParams {
+    offset:      vec2<f32>, // view centre − reference start
+    orbit_len:   u32,
+    julia:       u32,       // 0 = Mandelbrot, 1 = Julia
+    coloring:    u32,       // 0 = escape time, 1 = Pickover stalks, 2 = orbit trap
+    stalk_width: f32,
+    c_offset:    vec2<f32>, // Julia c − the reference's c
+    trap:        TrapParams,
+    series_a:    vec2<f32>, // A·r
+    series_b:    vec2<f32>, // B·r²
+    series_c:    vec2<f32>, // C·r³
+    skip:        u32,       // steps the series replaces; 0 for none
+    series_scale: f32,      // 1 / r
+}
+@group(0) @binding(2) var<uniform> pp: PerturbParams;
+
+// Z_0 … Z_{orbit_len − 1} of the reference, rounded to f32.
+@group(0) @binding(6) var<storage, read> orbit: array<vec2<f32>>;
+
+// Pickover stalks: brighten wherever the orbit (past its starting point)
+// grazed a coordinate axis.  `trap` is its closest approach.
+fn stalks(t: f32, trap: f32) -> f32 {
+    if pp.coloring != 1u || pp.stalk_width <= 0.0 { return t; }
+    return max(t, 1.0 - clamp(trap / pp.stalk_width, 0.0, 1.0));
+}
+
+// Orbit trap: the orbit's closest approach to a point, a line through
+// `point` along `dir`, or a circle of `radius` around `point`.  Written to
+// the green channel, capped at TRAP_FAR so it fits a half float.
+const TRAP_FAR: f32 = 1e4;
+fn trap_distance(z: vec2<f32>) -> f32 {
+    let d = z - pp.trap.point;
+    switch pp.trap.shape {
+        case 1u:  { return abs(d.x * pp.trap.dir.y - d.y * pp.trap.dir.x); }
+        case 2u:  { return abs(length(d) - pp.trap.radius); }
+        default:  { return length(d); }
+    }
+}
+
+fn cmul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+// One sample at `px`, in pixels.
+fn shade(px: vec2<f32>) -> vec4<f32> {
+    if pp.orbit_len == 0u { return vec4<f32>(0.0, 0.0, 0.0, 1.0); }
+
+    // Offset of the pixel from the reference's start.
+    let d0 = pp.offset + plane_uv(px) / u.zoom;
+    let julia = pp.julia == 1u;
+    let dc = select(d0, pp.c_offset, julia);
+    var dz = select(vec2<f32>(0.0, 0.0), d0, julia);
+
+    var n = 0u;
+    var i = 0u;
+    if pp.skip > 0u {
+        let t = d0 * pp.series_scale;
+        dz = cmul(t, pp.series_a + cmul(t, pp.series_b + cmul(t, pp.series_c)));
+        n = pp.skip;
+        i = pp.skip;
+    }
+    var z = orbit[n] + dz;
+    var trap = 1e10;
+    var trapped = TRAP_FAR;
+    while i < u.max_iter {
+        if dot(z, z) > 4.0 { break; }
+        if i > 0u {
+            trap = min(trap, min(abs(z.x), abs(z.y)));
+            if pp.coloring == 2u { trapped = min(trapped, trap_distance(z)); }
+        }
+        dz = 2.0 * cmul(orbit[n], dz) + cmul(dz, dz) + dc;
+        n++;
+        i++;
+        z = orbit[n] + dz;
+        if n + 1u >= pp.orbit_len || dot(z, z) < dot(dz, dz) {
+            dz = z - orbit[0];
+            n = 0u;
+        }
+    }
+
+    var t = 0.0;
+    if i < u.max_iter {
+        let log_zn = log2(max(dot(z, z), 1e-10)) * 0.5;
+        let nu     = select(1.0, log2(max(log_zn, 1e-10)), u.smoothing == 1u);
+        t = clamp((f32(i) + 1.0 - nu) / f32(u.max_iter), 0.0, 1.0);
+    }
+
+    t = stalks(t, trap);
+
+    let g = select(0.0, trapped, pp.coloring == 2u);
+    return vec4<f32>(t, g, 0.0, 1.0);
+}
+
+// Average `u.samples` jittered samples; counts other than 2, 4 and 8 take one.
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+    let n = select(1u, u.samples, u.samples == 2u || u.samples == 4u || u.samples == 8u);
+    var sum = vec4<f32>(0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += shade(px + sample_offset(k, n));
+    }
+    textureStore(output, vec2<i32>(gid.xy), sum / f32(n));
+}
@@ -0,0 +1,54 @@
+// Plasma — compute shader
+//
+// The demoscene classic: sine waves summed over the plane,
+//
+//   v = Σᵢ sin(fᵢ · dᵢ(p) + φᵢ) / waves       i < waves
+//
+// where wave 0 runs along x, wave 1 along y, wave 2 along the diagonal and
+// wave 3 outwards from the origin (dᵢ is the distance along each), fᵢ is
+// its frequency and φᵢ its phase.  The phases are `plasma{i}_speed` × time,
+// wrapped on the CPU, so the interference drifts on its own.  Cheap enough
+// to sit behind anything.
+//
+// Output: the sum mapped to [0, 1] in the red channel.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// Plasma-only parameters; mirrors `PlasmaUniforms` in context.rs.
+struct PlasmaParams {
+    waves:     u32,       // 1 … 4 (PlasmaGen::MAX_WAVES)
+    pad0:      u32,
+    pad1:      u32,
+    pad2:      u32,
+    frequency: vec4<f32>, // radians per plane unit, one per wave
+    phase:     vec4<f32>, // radians in [0, 2π), one per wave
+}
+@group(0) @binding(2) var<uniform> pp: PlasmaParams;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+
+    let p = u.center + plane_uv(px) / u.zoom;
+
+    // Distance along each wave: x, y, the diagonal, the radius.
+    let d = vec4<f32>(p.x, p.y, (p.x + p.y) * 0.70710677, length(p));
+    let waves = clamp(pp.waves, 1u, 4u);
+    var sum = 0.0;
+    for (var i = 0u; i < waves; i++) {
+        sum += sin(pp.frequency[i] * d[i] + pp.phase[i]);
+    }
+    let n = 0.5 + 0.5 * sum / f32(waves);
+    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(n, 0.0, 0.0, 1.0));
+}
@@ -0,0 +1,29 @@
+struct Uniforms {
+    resolution : vec2<f32>,
+    center     : vec2<f32>,
+    zoom       : f32,
+    time       : f32,
+    max_iter   : u32,
+    _pad       : u32,
+}
+struct PosterizeParams {
+    levels : f32,  // steps per channel, at least 2
+    _pad0  : f32,
+    _pad1  : f32,
+    _pad2  : f32,
+}
+
+@group(0) @binding(0) var<uniform>  u      : Uniforms;
+@group(0) @binding(1) var<uniform>  pp     : PosterizeParams;
+@group(0) @binding(2) var           input  : texture_2d<f32>;
+@group(0) @binding(3) var           output : texture_storage_2d<rgba16float, write>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let coord = vec2<i32>(gid.xy);
+    if f32(gid.x) >= u.resolution.x || f32(gid.y) >= u.resolution.y { return; }
+    let px    = textureLoad(input, coord, 0);
+    let steps = max(pp.levels, 2.0) - 1.0;
+    let rgb   = round(clamp(px.rgb, vec3(0.0), vec3(1.0)) * steps) / steps;
+    textureStore(output, coord, vec4<f32>(rgb, px.a));
+}
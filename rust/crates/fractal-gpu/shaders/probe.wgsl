@@ -0,0 +1,72 @@
+// Coordinate probes — compute shader
+//
+// Runs the escape-time iteration at up to 16 points on the complex plane
+// (the cursor, markers, …) rather than at pixels, so the app can read
+// iteration data back without copying a whole frame.  The loops mirror
+// mandelbrot.wgsl, julia.wgsl and burning_ship.wgsl (with its Celtic and
+// Buffalo folds), periodicity check included, so a probe agrees with the
+// pixel it lands on.
+
+// Mirrors `ProbeUniforms` in probe.rs.
+struct ProbeParams {
+    kind:     u32, // 0 = Mandelbrot, 1 = Julia, 2 = Burning Ship, 3 = Celtic, 4 = Buffalo
+    count:    u32, // probes in use
+    max_iter: u32,
+    pad:      u32,
+    c:        vec2<f32>, // Julia constant
+    pad2:     vec2<f32>,
+    points:   array<vec4<f32>, 16>, // xy = plane position
+}
+@group(0) @binding(0) var<uniform> p: ProbeParams;
+
+// Mirrors `RawProbe` in probe.rs.
+struct ProbeResult {
+    iterations: u32,
+    escaped:    u32,
+    smoothed:   f32, // log-log smoothed iteration count
+    trap:       f32, // closest approach to an axis, as for Pickover stalks
+}
+@group(0) @binding(1) var<storage, read_write> results: array<ProbeResult, 16>;
+
+const PERIOD_START:   u32 = 8u;
+const PERIOD_EPSILON: f32 = 1e-12;
+
+@compute @workgroup_size(16)
+fn main(@builtin(local_invocation_index) index: u32) {
+    if index >= p.count { return; }
+    let point = p.points[index].xy;
+    let julia = p.kind == 1u;
+
+    var z = select(vec2<f32>(0.0, 0.0), point, julia);
+    let c = select(point, p.c, julia);
+    var i = 0u;
+    var trap = 1e10;
+    var saved  = z;
+    var period = 0u;
+    var window = PERIOD_START;
+    while i < p.max_iter {
+        if dot(z, z) > 4.0 { break; }
+        if i > 0u { trap = min(trap, min(abs(z.x), abs(z.y))); }
+        var x = z.x * z.x - z.y * z.y;
+        var y = 2.0 * z.x * z.y;
+        if p.kind == 2u || p.kind == 4u { y = abs(y); }
+        if p.kind >= 3u { x = abs(x); }
+        z = vec2<f32>(x + c.x, y + c.y);
+        i++;
+        let d = z - saved;
+        if dot(d, d) < PERIOD_EPSILON { i = p.max_iter; break; }
+        period++;
+        if period == window { period = 0u; window *= 2u; saved = z; }
+    }
+
+    var result: ProbeResult;
+    result.iterations = i;
+    result.escaped = select(0u, 1u, i < p.max_iter);
+    result.smoothed = f32(i);
+    if i < p.max_iter {
+        let log_zn = log2(max(dot(z, z), 1e-10)) * 0.5;
+        result.smoothed = max(f32(i) + 1.0 - log2(max(log_zn, 1e-10)), 0.0);
+    }
+    result.trap = trap;
+    results[index] = result;
+}
@@ -0,0 +1,107 @@
+// Reaction–diffusion (Gray–Scott) — compute shader
+//
+// Two chemicals on a grid of output pixels: U is fed in at `feed`, V eats
+// it (U + 2V → 3V) and is removed at `feed + kill`, and both diffuse, U
+// twice as fast as V.  The state lives in a texture pair the generator
+// pass ping-pongs across frames (bindings 7 and 8), so the pattern grows
+// over time instead of being recomputed from scratch.  Three entry points:
+//
+//   seed     — U = 1 everywhere with a scatter of V spots; runs when the
+//              simulation starts or is reset.
+//   simulate — one explicit Euler step, wrapping at the edges.  The pass
+//              runs it `steps` times a frame.
+//   main     — writes V to the output's red channel for the colour map.
+//
+// The grid is the output's pixels, so center / zoom leave it alone.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// ReactionDiffusion-only parameters; mirrors `ReactionUniforms` in context.rs.
+struct ReactionParams {
+    feed:  f32,
+    kill:  f32,
+    steps: u32,
+    seed:  f32,
+}
+@group(0) @binding(2) var<uniform> rp: ReactionParams;
+
+// (U, V) after the previous step, and where this step writes.
+@group(0) @binding(7) var state_in:  texture_2d<f32>;
+@group(0) @binding(8) var state_out: texture_storage_2d<rg32float, write>;
+
+const DIFFUSE_U: f32 = 1.0;
+const DIFFUSE_V: f32 = 0.5;
+
+// Edge length in pixels of the cells the seed scatters spots over.
+const SEED_CELL: f32 = 24.0;
+
+fn in_bounds(gid: vec3<u32>) -> bool {
+    return f32(gid.x) < u.resolution.x && f32(gid.y) < u.resolution.y;
+}
+
+// State at `p`, wrapped so the grid is a torus.
+fn load(p: vec2<i32>) -> vec2<f32> {
+    let size = vec2<i32>(textureDimensions(state_in));
+    return textureLoad(state_in, (p + size) % size, 0).xy;
+}
+
+// Pseudo-random value in [0, 1) for a cell and the seed.
+fn hash(cell: vec2<f32>) -> f32 {
+    return fract(sin(dot(cell + rp.seed * 7.31, vec2<f32>(127.1, 311.7))) * 43758.547);
+}
+
+@compute @workgroup_size(8, 8)
+fn seed(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if !in_bounds(gid) { return; }
+    let px   = vec2<f32>(gid.xy);
+    let cell = floor(px / SEED_CELL);
+    // About one cell in six gets a spot, placed anywhere inside it.
+    var v = 0.0;
+    if hash(cell) < 0.16 {
+        let centre = (cell + 0.25 + 0.5 * vec2<f32>(hash(cell + 0.5), hash(cell + 1.5))) * SEED_CELL;
+        if distance(px, centre) < SEED_CELL * 0.2 {
+            v = 0.25;
+        }
+    }
+    textureStore(state_out, vec2<i32>(gid.xy), vec4<f32>(1.0 - 2.0 * v, v, 0.0, 0.0));
+}
+
+@compute @workgroup_size(8, 8)
+fn simulate(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if !in_bounds(gid) { return; }
+    let p = vec2<i32>(gid.xy);
+
+    // 3×3 Laplacian: 0.2 to the edge neighbours, 0.05 to the corners.
+    let c = load(p);
+    let edges = load(p + vec2<i32>(1, 0)) + load(p - vec2<i32>(1, 0))
+              + load(p + vec2<i32>(0, 1)) + load(p - vec2<i32>(0, 1));
+    let corners = load(p + vec2<i32>(1, 1)) + load(p - vec2<i32>(1, 1))
+                + load(p + vec2<i32>(1, -1)) + load(p - vec2<i32>(1, -1));
+    let lap = 0.2 * edges + 0.05 * corners - c;
+
+    let uv2 = c.x * c.y * c.y;
+    let du  = DIFFUSE_U * lap.x - uv2 + rp.feed * (1.0 - c.x);
+    let dv  = DIFFUSE_V * lap.y + uv2 - (rp.feed + rp.kill) * c.y;
+    let next = clamp(c + vec2<f32>(du, dv), vec2<f32>(0.0), vec2<f32>(1.0));
+
+    textureStore(state_out, p, vec4<f32>(next, 0.0, 0.0));
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if !in_bounds(gid) { return; }
+    // V rarely passes ~0.4; stretch it over the colour map's range.
+    let v = load(vec2<i32>(gid.xy)).y;
+    let n = smoothstep(0.0, 0.4, v);
+    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(n, 0.0, 0.0, 1.0));
+}
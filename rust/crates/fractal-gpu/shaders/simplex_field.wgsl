@@ -0,0 +1,158 @@
+// Simplex field — compute shader
+//
+// The noise field's FBM over 3-D simplex noise, with each octave folded at
+// zero before it is summed.  Plain turbulence (|n|) gives billowing smoke;
+// ridged noise ((1 - |n|)^2) turns the zero crossings into sharp creases —
+// marble veins, lightning, mountain ridges.  `ridge` blends between the
+// two.  Scale, octaves, lacunarity, gain, seed and the z slice work as in
+// noise_field.wgsl.
+//
+// Output: folded noise value in the red channel [0, 1].
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// SimplexField-only parameters; mirrors `SimplexUniforms` in context.rs.
+struct SimplexParams {
+    scale:      f32,
+    octaves:    u32,
+    lacunarity: f32,
+    gain:       f32,
+    seed:       f32,
+    z:          f32,
+    ridge:      f32, // 0 = turbulence |n|, 1 = ridges (1 - |n|)^2
+    pad:        f32,
+}
+@group(0) @binding(2) var<uniform> np: SimplexParams;
+
+// ---------------------------------------------------------------------------
+// 3-D simplex noise (Ashima Arts / Stefan Gustavson, MIT licence)
+// ---------------------------------------------------------------------------
+
+fn mod289_3(x: vec3<f32>) -> vec3<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn mod289_4(x: vec4<f32>) -> vec4<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn permute4(x: vec4<f32>) -> vec4<f32> {
+    return mod289_4(((x * 34.0) + 1.0) * x);
+}
+
+fn taylor_inv_sqrt4(r: vec4<f32>) -> vec4<f32> {
+    return 1.79284291400159 - 0.85373472095314 * r;
+}
+
+// Returns a value in roughly [-1, 1].
+fn snoise3(v: vec3<f32>) -> f32 {
+    let C = vec2<f32>(1.0 / 6.0, 1.0 / 3.0);
+    let D = vec4<f32>(0.0, 0.5, 1.0, 2.0);
+
+    // First corner
+    var i  = floor(v + dot(v, C.yyy));
+    let x0 = v - i + dot(i, C.xxx);
+
+    // Other corners
+    let g  = step(x0.yzx, x0.xyz);
+    let l  = 1.0 - g;
+    let i1 = min(g.xyz, l.zxy);
+    let i2 = max(g.xyz, l.zxy);
+    let x1 = x0 - i1 + C.xxx;
+    let x2 = x0 - i2 + C.yyy;
+    let x3 = x0 - D.yyy;
+
+    // Permutations
+    i = mod289_3(i);
+    let p = permute4(permute4(permute4(
+              i.z + vec4<f32>(0.0, i1.z, i2.z, 1.0))
+            + i.y + vec4<f32>(0.0, i1.y, i2.y, 1.0))
+            + i.x + vec4<f32>(0.0, i1.x, i2.x, 1.0));
+
+    // Gradients: 7×7 points over a square, mapped onto an octahedron
+    let ns = (1.0 / 7.0) * D.wyz - D.xzx;
+    let j  = p - 49.0 * floor(p * ns.z * ns.z);
+    let x_ = floor(j * ns.z);
+    let y_ = floor(j - 7.0 * x_);
+    let x  = x_ * ns.x + ns.yyyy;
+    let y  = y_ * ns.x + ns.yyyy;
+    let h  = 1.0 - abs(x) - abs(y);
+    let b0 = vec4<f32>(x.xy, y.xy);
+    let b1 = vec4<f32>(x.zw, y.zw);
+    let s0 = floor(b0) * 2.0 + 1.0;
+    let s1 = floor(b1) * 2.0 + 1.0;
+    let sh = -step(h, vec4<f32>(0.0));
+    let a0 = b0.xzyw + s0.xzyw * sh.xxyy;
+    let a1 = b1.xzyw + s1.xzyw * sh.zzww;
+
+    // Normalise gradients
+    let n  = taylor_inv_sqrt4(vec4<f32>(
+        dot(a0.xy, a0.xy) + h.x * h.x,
+        dot(a0.zw, a0.zw) + h.y * h.y,
+        dot(a1.xy, a1.xy) + h.z * h.z,
+        dot(a1.zw, a1.zw) + h.w * h.w,
+    ));
+    let p0 = vec3<f32>(a0.xy, h.x) * n.x;
+    let p1 = vec3<f32>(a0.zw, h.y) * n.y;
+    let p2 = vec3<f32>(a1.xy, h.z) * n.z;
+    let p3 = vec3<f32>(a1.zw, h.w) * n.w;
+
+    // Mix final noise value
+    var m = max(0.6 - vec4<f32>(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3)), vec4<f32>(0.0));
+    m = m * m;
+    return 42.0 * dot(m * m, vec4<f32>(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
+}
+
+// Folded FBM — each octave's |n| or (1 - |n|)^2, weighted as in the noise
+// field; capped at 8 octaves (NoiseFieldGen::MAX_OCTAVES).  Ridged octaves
+// are also weighted by the octave before, so ridges sharpen where they
+// cross.  Returns a value in [0, 1].
+fn folded_fbm(p: vec3<f32>) -> f32 {
+    var value     = 0.0;
+    var norm      = 0.0;
+    var amplitude = 0.5;
+    var frequency = 1.0;
+    var prev      = 1.0;
+    for (var oct = 0u; oct < min(np.octaves, 8u); oct++) {
+        let n      = abs(snoise3(p * frequency));
+        let ridged = (1.0 - n) * (1.0 - n);
+        let octave = mix(n, ridged * prev, np.ridge);
+        prev       = clamp(ridged * 2.0, 0.0, 1.0);
+        value     += amplitude * octave;
+        norm      += amplitude;
+        frequency *= np.lacunarity;
+        amplitude *= np.gain;
+    }
+    return clamp(value / max(norm, 1e-6), 0.0, 1.0);
+}
+
+// ---------------------------------------------------------------------------
+// Shader entry point
+// ---------------------------------------------------------------------------
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+
+    let p = u.center + plane_uv(px) / u.zoom;
+
+    // Same lattice offset and z rate as the noise field, so a seed or an
+    // audio-driven `noise_z` behaves alike in both.
+    let seed_offset = vec2<f32>(np.seed * 17.13, np.seed * 31.71);
+    let q = vec3<f32>(p * np.scale + seed_offset, np.z * 0.1);
+
+    let n = folded_fbm(q);
+
+    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(n, 0.0, 0.0, 1.0));
+}
@@ -0,0 +1,106 @@
+// Test pattern — compute shader
+//
+// A static alignment chart for projectors and LED walls, drawn in output
+// pixels so center / zoom leave it alone: a grid centred on the frame, a
+// one-pixel border, colour bars and a grey step wedge, 4:3 / 16:9 aspect
+// markers with the inscribed circle, action- and title-safe boxes, and
+// convergence crosses at the centre and near each corner.  Colours are
+// written as linear RGB; the bar and wedge levels are display (sRGB) values.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+fn to_linear(c: vec3<f32>) -> vec3<f32> {
+    return select(
+        pow((c + 0.055) / 1.055, vec3<f32>(2.4)),
+        c / 12.92,
+        c <= vec3<f32>(0.04045),
+    );
+}
+
+// Within `width` pixels inside the edge of the centred box of half-size `half`.
+fn box_outline(d: vec2<f32>, half: vec2<f32>, width: f32) -> bool {
+    let e = abs(d) - half;
+    return max(e.x, e.y) <= 0.0 && max(e.x, e.y) > -width;
+}
+
+// Cross of arm length `arm` plus a ring of radius 0.6 * `arm` around `c`.
+fn convergence(px: vec2<f32>, c: vec2<f32>, arm: f32, width: f32) -> bool {
+    let d = abs(px - c);
+    let cross = (d.x < width && d.y < arm) || (d.y < width && d.x < arm);
+    let ring = abs(length(px - c) - arm * 0.6) < width;
+    return cross || ring;
+}
+
+const BARS = array<vec3<f32>, 8>(
+    vec3<f32>(0.75, 0.75, 0.75),
+    vec3<f32>(0.75, 0.75, 0.0),
+    vec3<f32>(0.0, 0.75, 0.75),
+    vec3<f32>(0.0, 0.75, 0.0),
+    vec3<f32>(0.75, 0.0, 0.75),
+    vec3<f32>(0.75, 0.0, 0.0),
+    vec3<f32>(0.0, 0.0, 0.75),
+    vec3<f32>(0.0, 0.0, 0.0),
+);
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y)) + 0.5;
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+
+    let res  = u.resolution;
+    let mid  = res * 0.5;
+    let d    = px - mid;
+    let unit = res.y / 10.0;                  // grid pitch: ten cells down the frame
+    let line = max(1.0, floor(res.y / 540.0)); // line width, thicker on big outputs
+
+    var rgb = vec3<f32>(0.08);
+
+    // Grid, centred so a line crosses the middle of the frame.
+    let g = abs(d / unit - round(d / unit)) * unit;
+    if min(g.x, g.y) < line * 0.5 { rgb = vec3<f32>(0.35); }
+
+    // Colour bars across the middle fifth, step wedge just below.
+    let v = d.y / res.y;
+    if v > -0.1 && v < 0.1 {
+        var bars = BARS; // a const array can't take a runtime index
+        let i = min(u32(px.x / res.x * 8.0), 7u);
+        rgb = to_linear(bars[i]);
+    } else if v >= 0.1 && v < 0.2 {
+        let level = floor(px.x / res.x * 11.0) / 10.0;
+        rgb = to_linear(vec3<f32>(min(level, 1.0)));
+    }
+
+    // Aspect markers: 4:3 and 16:9 frame edges and the inscribed circle.
+    let ax = abs(d.x);
+    if abs(ax - res.y * 2.0 / 3.0) < line || abs(ax - res.y * 8.0 / 9.0) < line {
+        rgb = vec3<f32>(0.9, 0.6, 0.0);
+    }
+    if abs(length(d) - res.y * 0.5) < line { rgb = vec3<f32>(0.0, 0.8, 1.0); }
+
+    // Safe areas (93 % action, 90 % title) and the edge of the frame.
+    if box_outline(d, res * 0.465, line) { rgb = vec3<f32>(0.0, 1.0, 0.0); }
+    if box_outline(d, res * 0.45, line) { rgb = vec3<f32>(1.0, 1.0, 0.0); }
+    if box_outline(d, mid, line) { rgb = vec3<f32>(1.0); }
+
+    // Convergence crosses at the centre and one grid cell in from each corner.
+    let arm = unit * 0.4;
+    if convergence(px, mid, arm, line)
+        || convergence(px, vec2<f32>(unit, unit), arm, line)
+        || convergence(px, vec2<f32>(res.x - unit, unit), arm, line)
+        || convergence(px, vec2<f32>(unit, res.y - unit), arm, line)
+        || convergence(px, res - unit, arm, line) {
+        rgb = vec3<f32>(1.0);
+    }
+
+    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(rgb, 1.0));
+}
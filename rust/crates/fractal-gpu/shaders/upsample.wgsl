@@ -0,0 +1,32 @@
+// Half-resolution upsample — composites a half-size effect back at full size.
+//
+// A plain bilinear upsample of the effect's output would soften the whole
+// frame.  Instead the full-resolution detail the downsample threw away
+// (`full - up(half_in)`) is added back, faded out wherever the effect
+// changed the picture: untouched regions stay sharp, smeared ones take the
+// effect's (softer) result.
+
+@group(0) @binding(0) var full     : texture_2d<f32>;  // effect input, full size
+@group(0) @binding(1) var half_in  : texture_2d<f32>;  // downsampled input
+@group(0) @binding(2) var half_out : texture_2d<f32>;  // effect output, half size
+@group(0) @binding(3) var output   : texture_storage_2d<rgba16float, write>;
+@group(0) @binding(4) var samp     : sampler;
+
+// Change in the effect's output (max channel) that drops all detail.
+const DETAIL_FADE: f32 = 0.1;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let size = textureDimensions(output);
+    if gid.x >= size.x || gid.y >= size.y { return; }
+
+    let uv     = (vec2<f32>(gid.xy) + 0.5) / vec2<f32>(size);
+    let base   = textureLoad(full, vec2<i32>(gid.xy), 0);
+    let before = textureSampleLevel(half_in, samp, uv, 0.0);
+    let after  = textureSampleLevel(half_out, samp, uv, 0.0);
+
+    let change = abs(after - before);
+    let keep   = 1.0 - smoothstep(0.0, DETAIL_FADE, max(max(change.r, change.g), change.b));
+    let detail = (base - before) * keep;
+    textureStore(output, vec2<i32>(gid.xy), max(after + detail, vec4<f32>(0.0)));
+}
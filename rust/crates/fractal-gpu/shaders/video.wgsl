@@ -0,0 +1,38 @@
+// Video frame — compute shader
+//
+// Samples the latest frame the app streamed into the source texture.  The
+// frame spans y ∈ [-1, 1] of the plane around the origin at its own aspect
+// ratio, so center / zoom pan and zoom it like any generator; outside the
+// frame is black.  Colour goes straight into RGB for the effect chain.
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+@group(0) @binding(3) var source: texture_2d<f32>;
+@group(0) @binding(4) var source_sampler: sampler;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+
+    let p      = u.center + plane_uv(px) / u.zoom;
+    let size   = vec2<f32>(textureDimensions(source));
+    let aspect = size.x / max(size.y, 1.0);
+    let st     = vec2<f32>(p.x / aspect, p.y) * 0.5 + 0.5;
+
+    var rgb = vec3<f32>(0.0);
+    if all(st >= vec2<f32>(0.0)) && all(st <= vec2<f32>(1.0)) {
+        rgb = textureSampleLevel(source, source_sampler, st, 0.0).rgb;
+    }
+
+    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(rgb, 1.0));
+}
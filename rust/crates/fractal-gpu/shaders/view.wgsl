@@ -0,0 +1,39 @@
+// View helpers — a WGSL library, not a shader
+//
+// Prepended at build time to every generator kernel that maps pixels onto
+// the complex plane (see `with_view!` in generator_pipeline.rs).  Both read
+// the kernel's `u: Uniforms`, which must have `resolution` and `projection`.
+
+// Pixel → offset from the view centre at zoom 1.  Flat: one window height
+// spans 2 units.  Dome: 180° equidistant fisheye in the inscribed circle,
+// looking up at the plane, capped at 85° short of the horizon.  Exp map:
+// log-polar — x sweeps one full turn, y steps inwards from radius 2 in
+// log radius at the same rate, so each row down is a small zoom-in.
+// Mirrors `Projection::plane_offset` in fractal-core.
+fn plane_uv(px: vec2<f32>) -> vec2<f32> {
+    if u.projection == 2u {
+        let k = 6.2831855 / u.resolution.x;
+        return vec2<f32>(cos(px.x * k), sin(px.x * k)) * (2.0 * exp(-px.y * k));
+    }
+    let d = px - u.resolution * 0.5;
+    if u.projection == 0u { return d / (u.resolution.y * 0.5); }
+    let len = length(d);
+    if len < 1e-6 { return vec2<f32>(0.0, 0.0); }
+    let r     = len / (min(u.resolution.x, u.resolution.y) * 0.5);
+    let theta = min(min(r, 1.0) * 1.5707964, 1.4835299);
+    return d * (tan(theta) / 1.5707964 / len);
+}
+
+// Sub-pixel offset of sample `k` of `n`, in pixels: the 2×, 4× and 8× MSAA
+// patterns, which jitter every sample into its own row and column of the
+// pixel so edges at any angle are resolved.  One sample sits on the pixel.
+fn sample_offset(k: u32, n: u32) -> vec2<f32> {
+    if n < 2u { return vec2<f32>(0.0, 0.0); }
+    var pattern = array<vec2<f32>, 14>(
+        vec2<f32>( 4.0,  4.0), vec2<f32>(-4.0, -4.0),
+        vec2<f32>(-2.0, -6.0), vec2<f32>( 6.0, -2.0), vec2<f32>(-6.0,  2.0), vec2<f32>( 2.0,  6.0),
+        vec2<f32>( 1.0, -3.0), vec2<f32>(-1.0,  3.0), vec2<f32>( 5.0,  1.0), vec2<f32>(-3.0, -5.0),
+        vec2<f32>(-5.0,  5.0), vec2<f32>(-7.0, -1.0), vec2<f32>( 3.0,  7.0), vec2<f32>( 7.0, -7.0),
+    );
+    return pattern[n - 2u + k] / 16.0;
+}
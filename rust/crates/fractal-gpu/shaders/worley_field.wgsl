@@ -0,0 +1,101 @@
+// Worley field — compute shader
+//
+// Cellular noise after Steven Worley: a jittered lattice of feature points
+// fills 3-D space, one per unit cell, and each pixel measures its distance
+// to the nearest (F1) and second nearest (F2) of them.  The pixel picks the
+// x/y position and `z` the slice, so cells drift, swell and split as the
+// slice moves.  F1 paints round cells bright at their rims; F2 - F1 is zero
+// on the borders between cells, giving a crackle / stained-glass look.
+// `edge` blends between the two.
+//
+// Output: cell value in the red channel [0, 1].
+
+struct Uniforms {
+    resolution: vec2<f32>,
+    center:     vec2<f32>,
+    zoom:       f32,
+    time:       f32,
+    max_iter:   u32,
+    projection: u32, // 0 = flat, 1 = dome, 2 = exp map
+}
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;
+
+// WorleyField-only parameters; mirrors `WorleyUniforms` in context.rs.
+struct WorleyParams {
+    scale:  f32,
+    seed:   f32,
+    z:      f32,
+    jitter: f32, // 0 = feature points on a grid, 1 = anywhere in their cell
+    edge:   f32, // 0 = F1 (round cells), 1 = F2 - F1 (cell borders)
+    pad0:   f32,
+    pad1:   vec2<f32>,
+}
+@group(0) @binding(2) var<uniform> wp: WorleyParams;
+
+// ---------------------------------------------------------------------------
+// Cellular noise
+// ---------------------------------------------------------------------------
+
+// Integer hash (PCG-style) of a cell, three pseudo-random values in [0, 1).
+fn hash3(cell: vec3<i32>) -> vec3<f32> {
+    var v = bitcast<vec3<u32>>(cell) * vec3<u32>(1664525u) + vec3<u32>(1013904223u);
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    v ^= v >> vec3<u32>(16u);
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    return vec3<f32>(v & vec3<u32>(0xffffffu)) / 16777216.0;
+}
+
+// Distances to the nearest and second nearest feature points.  With the
+// jitter capped at 1 every point stays inside its cell, so the 3×3×3
+// neighbourhood always holds both.
+fn worley(p: vec3<f32>) -> vec2<f32> {
+    let base = vec3<i32>(floor(p));
+    let f    = fract(p);
+    var f1 = 8.0;
+    var f2 = 8.0;
+    for (var z = -1; z <= 1; z++) {
+        for (var y = -1; y <= 1; y++) {
+            for (var x = -1; x <= 1; x++) {
+                let offset  = vec3<i32>(x, y, z);
+                let feature = 0.5 + (hash3(base + offset) - 0.5) * wp.jitter;
+                let d = length(vec3<f32>(offset) + feature - f);
+                if d < f1 {
+                    f2 = f1;
+                    f1 = d;
+                } else if d < f2 {
+                    f2 = d;
+                }
+            }
+        }
+    }
+    return vec2<f32>(f1, f2);
+}
+
+// ---------------------------------------------------------------------------
+// Shader entry point
+// ---------------------------------------------------------------------------
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let px = vec2<f32>(f32(gid.x), f32(gid.y));
+    if px.x >= u.resolution.x || px.y >= u.resolution.y { return; }
+
+    let p = u.center + plane_uv(px) / u.zoom;
+
+    // Same seed offset and z rate as the noise field.
+    let seed_offset = vec2<f32>(wp.seed * 17.13, wp.seed * 31.71);
+    let q = vec3<f32>(p * wp.scale + seed_offset, wp.z * 0.1);
+
+    let d = worley(q);
+    // F1 rarely passes ~0.9 and F2 - F1 ~0.6 in unit cells; scale both into
+    // [0, 1] so the colour map sees its full range.
+    let n = mix(d.x / 0.9, 1.0 - (d.y - d.x) / 0.6, wp.edge);
+
+    textureStore(output, vec2<i32>(gid.xy), vec4<f32>(clamp(n, 0.0, 1.0), 0.0, 0.0, 1.0));
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use wgpu::{Device, Texture, TextureView};
+
+/// Frames an auxiliary texture may go unrequested before it is dropped —
+/// long enough to survive an effect being toggled off and on, short enough
+/// that a removed effect's history doesn't pin GPU memory.
+pub const IDLE_FRAMES: u64 = 120;
+
+/// Shape of an auxiliary texture relative to the frame it serves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuxDesc {
+    /// Divisor of the frame size: 1 = full resolution, 2 = half, …
+    pub scale: u32,
+    /// Mip levels, clamped to what the size allows (bloom chains).
+    pub mip_levels: u32,
+}
+
+impl AuxDesc {
+    /// One full-resolution level: previous frame, accumulation, TAA history.
+    pub const FULL: AuxDesc = AuxDesc {
+        scale: 1,
+        mip_levels: 1,
+    };
+
+    /// Texture size for a `width`×`height` frame, never zero.
+    pub fn size(&self, width: u32, height: u32) -> (u32, u32) {
+        let scale = self.scale.max(1);
+        ((width / scale).max(1), (height / scale).max(1))
+    }
+
+    /// Mip levels for a `width`×`height` frame: the requested count, down
+    /// to the last level that is still at least 1×1.
+    pub fn mips(&self, width: u32, height: u32) -> u32 {
+        let (w, h) = self.size(width, height);
+        let full_chain = 32 - w.max(h).leading_zeros();
+        self.mip_levels.clamp(1, full_chain)
+    }
+}
+
+/// One registered texture.  `view` covers every mip level for sampling;
+/// `mip_views[i]` is level `i` alone, for storage writes.
+pub struct AuxTexture {
+    pub texture: Texture,
+    pub view: TextureView,
+    pub mip_views: Vec<TextureView>,
+    pub width: u32,
+    pub height: u32,
+    desc: AuxDesc,
+    /// Frame it was (re)created on; its contents are undefined until the
+    /// first writer fills them.
+    created: u64,
+    last_used: u64,
+}
+
+// ---------------------------------------------------------------------------
+// AuxTextures — named textures that outlive a frame
+// ---------------------------------------------------------------------------
+
+/// Named `rgba16float` textures kept across frames for effects that need
+/// more than the ping-pong pair: the previous frame, accumulation buffers,
+/// bloom mips.  A texture is made on first request and remade whenever the
+/// frame size or its [`AuxDesc`] changes; [`end_frame`](Self::end_frame)
+/// drops the ones nobody asked for in [`IDLE_FRAMES`] frames.
+#[derive(Default)]
+pub struct AuxTextures {
+    textures: HashMap<&'static str, AuxTexture>,
+    frame: u64,
+}
+
+impl AuxTextures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The texture registered as `name`, shaped by `desc` for a
+    /// `width`×`height` frame, creating or resizing it as needed.  Check
+    /// [`is_fresh`](Self::is_fresh) before reading history out of it.
+    pub fn acquire(
+        &mut self,
+        device: &Device,
+        name: &'static str,
+        width: u32,
+        height: u32,
+        desc: AuxDesc,
+    ) -> &AuxTexture {
+        let (w, h) = desc.size(width, height);
+        let stale = self
+            .textures
+            .get(name)
+            .is_none_or(|t| (t.width, t.height, t.desc) != (w, h, desc));
+        if stale {
+            let texture = aux_texture(device, name, w, h, desc.mips(width, height));
+            self.textures
+                .insert(name, texture_entry(texture, desc, self.frame));
+        }
+        let entry = self.textures.get_mut(name).expect("inserted above");
+        entry.last_used = self.frame;
+        entry
+    }
+
+    /// The texture registered as `name`, if it is alive.
+    pub fn get(&self, name: &str) -> Option<&AuxTexture> {
+        self.textures.get(name)
+    }
+
+    /// `name` was (re)created this frame, so it holds no history yet.
+    pub fn is_fresh(&self, name: &str) -> bool {
+        self.textures
+            .get(name)
+            .is_some_and(|t| t.created == self.frame)
+    }
+
+    /// Drop `name` now rather than after it idles out.
+    pub fn release(&mut self, name: &str) {
+        self.textures.remove(name);
+    }
+
+    /// Registered textures.
+    pub fn len(&self) -> usize {
+        self.textures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.textures.is_empty()
+    }
+
+    /// Close the frame: drop textures idle for [`IDLE_FRAMES`].
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+        let frame = self.frame;
+        self.textures
+            .retain(|_, t| frame - t.last_used <= IDLE_FRAMES);
+    }
+}
+
+fn texture_entry(texture: Texture, desc: AuxDesc, frame: u64) -> AuxTexture {
+    let view = texture.create_view(&Default::default());
+    let mip_views = (0..texture.mip_level_count())
+        .map(|level| {
+            texture.create_view(&wgpu::TextureViewDescriptor {
+                base_mip_level: level,
+                mip_level_count: Some(1),
+                ..Default::default()
+            })
+        })
+        .collect();
+    AuxTexture {
+        width: texture.width(),
+        height: texture.height(),
+        texture,
+        view,
+        mip_views,
+        desc,
+        created: frame,
+        last_used: frame,
+    }
+}
+
+/// Storage-writable, sampleable and copyable, so a pass can write it, a
+/// later one sample it, and the previous frame can be copied straight in.
+fn aux_texture(device: &Device, label: &str, width: u32, height: u32, mips: u32) -> Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: mips,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba16Float,
+        usage: wgpu::TextureUsages::STORAGE_BINDING
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_divides_the_frame_and_never_reaches_zero() {
+        assert_eq!(AuxDesc::FULL.size(800, 600), (800, 600));
+        let half = AuxDesc {
+            scale: 2,
+            mip_levels: 1,
+        };
+        assert_eq!(half.size(801, 600), (400, 300));
+        assert_eq!(half.size(1, 1), (1, 1));
+        let zero = AuxDesc {
+            scale: 0,
+            mip_levels: 1,
+        };
+        assert_eq!(zero.size(64, 32), (64, 32));
+    }
+
+    #[test]
+    fn mips_stop_at_one_pixel() {
+        let bloom = AuxDesc {
+            scale: 2,
+            mip_levels: 6,
+        };
+        assert_eq!(bloom.mips(1920, 1080), 6);
+        // 8×8 after halving: 8, 4, 2, 1.
+        assert_eq!(bloom.mips(16, 16), 4);
+        let none = AuxDesc {
+            scale: 1,
+            mip_levels: 0,
+        };
+        assert_eq!(none.mips(64, 64), 1);
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn textures_resize_and_idle_out() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut aux = AuxTextures::new();
+            let t = aux.acquire(&ctx.device, "previous", 64, 32, AuxDesc::FULL);
+            assert_eq!((t.width, t.height, t.mip_views.len()), (64, 32, 1));
+            assert!(aux.is_fresh("previous"));
+
+            aux.end_frame();
+            aux.acquire(&ctx.device, "previous", 64, 32, AuxDesc::FULL);
+            assert!(!aux.is_fresh("previous"));
+            let t = aux.acquire(&ctx.device, "previous", 128, 32, AuxDesc::FULL);
+            assert_eq!(t.width, 128);
+            assert!(aux.is_fresh("previous"));
+
+            for _ in 0..IDLE_FRAMES {
+                aux.end_frame();
+            }
+            assert_eq!(aux.len(), 1);
+            aux.end_frame();
+            assert!(aux.is_empty());
+        });
+    }
+}
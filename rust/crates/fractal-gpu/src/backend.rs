@@ -0,0 +1,430 @@
+use fractal_core::{
+    perturbation::ReferenceOrbit, projection::Projection, EffectKind, GeneratorKind,
+};
+use wgpu::{CommandEncoder, Device, Queue, TextureView};
+
+use crate::{
+    context::{GeneratorUniforms, Uniforms},
+    effect_pipeline::{EffectPass, PingPong},
+    generator_pipeline::GeneratorPass,
+    graph::{FrameGraph, Slot},
+    mix_pipeline::MixPass,
+    overlay_pipeline::{OverlayParams, OverlayPass},
+    preview::PreviewCapture,
+    renderer::{OutputPass, Viewport},
+};
+
+// ---------------------------------------------------------------------------
+// FrameBackend — the GPU work of one frame
+// ---------------------------------------------------------------------------
+
+/// One of the two patches a frame can crossfade between.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Deck {
+    #[default]
+    A,
+    B,
+}
+
+/// The GPU operations the app issues each frame.  The app state machine
+/// drives this trait, so it can be unit-tested against `MockBackend`
+/// without an adapter.
+pub trait FrameBackend {
+    /// Render the generator into the frame's source texture.  `extension`
+    /// carries the parameters only `kind` reads.
+    fn dispatch_generator(
+        &mut self,
+        kind: GeneratorKind,
+        uniforms: &Uniforms,
+        extension: &GeneratorUniforms,
+    );
+
+    /// Make `orbit` the reference the next perturbation dispatch reads; a
+    /// no-op when it is already uploaded.
+    fn upload_orbit(&mut self, orbit: &ReferenceOrbit);
+
+    /// Run `effects` in order over the generator output.
+    fn dispatch_chain(&mut self, effects: &[EffectKind], uniforms: &Uniforms);
+
+    /// Aim the following generator and chain dispatches at `deck`'s
+    /// textures.  A frame starts on `Deck::A`.
+    fn select_deck(&mut self, deck: Deck);
+
+    /// Blend deck B's final texture over deck A's by `amount` (0 = all A);
+    /// `present` then draws the blend.
+    fn crossfade(&mut self, amount: f32);
+
+    /// Composite the text / logo overlay over the shown texture; `present`
+    /// then draws the result.
+    fn overlay(&mut self, params: &OverlayParams);
+
+    /// Draw the final texture to the output target.
+    fn present(&mut self, projection: Projection);
+}
+
+// ---------------------------------------------------------------------------
+// DeckPasses — the size-dependent resources of deck B
+// ---------------------------------------------------------------------------
+
+/// A second generator output and ping-pong pair, so deck B renders its own
+/// patch next to deck A's, plus the pass that blends the two.  The effect
+/// pass is shared.
+pub struct DeckPasses {
+    pub gen_pass: GeneratorPass,
+    pub pp: PingPong,
+    pub mix: MixPass,
+}
+
+impl DeckPasses {
+    pub fn new(device: &Device, width: u32, height: u32) -> Self {
+        Self {
+            gen_pass: GeneratorPass::new(device, width, height),
+            pp: PingPong::new(device, width, height),
+            mix: MixPass::new(device, width, height),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// WgpuFrame — records one frame into a wgpu command encoder
+// ---------------------------------------------------------------------------
+
+/// Borrows the app's passes for one frame and records into `encoder`; the
+/// caller submits.
+pub struct WgpuFrame<'a> {
+    pub device: &'a Device,
+    pub queue: &'a Queue,
+    pub encoder: &'a mut CommandEncoder,
+    pub gen_pass: &'a GeneratorPass,
+    pub effect_pass: &'a mut EffectPass,
+    pub pp: &'a mut PingPong,
+    pub output: &'a OutputPass,
+    /// Where `present` draws, usually the swapchain texture.
+    pub target: &'a TextureView,
+    /// Region of `target` the frame fills; `None` = all of it.
+    pub viewport: Option<Viewport>,
+    /// Deck B's passes; without them deck B dispatches are dropped.
+    pub deck_b: Option<&'a mut DeckPasses>,
+    /// The overlay pass; without it overlay calls are dropped.
+    pub overlay: Option<&'a mut OverlayPass>,
+    /// Deck the next dispatches go to.
+    deck: Deck,
+    /// The passes recorded so far and the textures they wrote.
+    graph: FrameGraph,
+}
+
+impl<'a> WgpuFrame<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &'a Device,
+        queue: &'a Queue,
+        encoder: &'a mut CommandEncoder,
+        gen_pass: &'a GeneratorPass,
+        effect_pass: &'a mut EffectPass,
+        pp: &'a mut PingPong,
+        output: &'a OutputPass,
+        target: &'a TextureView,
+    ) -> Self {
+        Self {
+            device,
+            queue,
+            encoder,
+            gen_pass,
+            effect_pass,
+            pp,
+            output,
+            target,
+            viewport: None,
+            deck_b: None,
+            overlay: None,
+            deck: Deck::A,
+            graph: FrameGraph::new(),
+        }
+    }
+
+    /// Present into `viewport` of the target, leaving black bars around it.
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    /// Give deck B somewhere to render, for frames that crossfade.
+    pub fn with_deck_b(mut self, passes: &'a mut DeckPasses) -> Self {
+        self.deck_b = Some(passes);
+        self
+    }
+
+    /// Give the text / logo overlay somewhere to composite.
+    pub fn with_overlay(mut self, pass: &'a mut OverlayPass) -> Self {
+        self.overlay = Some(pass);
+        self
+    }
+
+    /// Record a downscaled copy of the final texture for `preview`.
+    pub fn capture(&mut self, preview: &mut PreviewCapture, projection: Projection) {
+        self.with_final_texture(|device, queue, encoder, source| {
+            preview.record(device, queue, encoder, source, projection);
+        });
+    }
+
+    /// Draw the final texture to another `target` through `output`, e.g. a
+    /// tiled output window with its own crop and correction.
+    pub fn present_to(
+        &mut self,
+        output: &OutputPass,
+        target: &TextureView,
+        projection: Projection,
+    ) {
+        self.with_final_texture(|device, queue, encoder, source| {
+            output.draw(device, queue, encoder, source, target, projection);
+        });
+    }
+
+    /// The passes recorded so far, in order.
+    pub fn graph(&self) -> &FrameGraph {
+        &self.graph
+    }
+
+    /// Run `record` with the texture the frame shows once everything is
+    /// dispatched.
+    fn with_final_texture(
+        &mut self,
+        record: impl FnOnce(&Device, &Queue, &mut CommandEncoder, &TextureView),
+    ) {
+        let textures = Textures {
+            gen_pass: self.gen_pass,
+            pp: self.pp,
+            deck_b: self.deck_b.as_deref(),
+            overlay: self.overlay.as_deref(),
+        };
+        let source = textures.shown(&self.graph, self.deck);
+        record(self.device, self.queue, self.encoder, source);
+    }
+}
+
+/// Resolves graph slots to the views of the passes that own them.
+struct Textures<'v> {
+    gen_pass: &'v GeneratorPass,
+    pp: &'v PingPong,
+    deck_b: Option<&'v DeckPasses>,
+    overlay: Option<&'v OverlayPass>,
+}
+
+impl<'v> Textures<'v> {
+    /// The view behind `slot`; `None` when its pass has no resources this
+    /// frame.
+    fn view(&self, slot: Slot) -> Option<&'v TextureView> {
+        Some(match slot {
+            Slot::Generated(Deck::A) => &self.gen_pass.output_view,
+            Slot::Chained(Deck::A) => self.pp.read_view(),
+            Slot::Generated(Deck::B) => &self.deck_b?.gen_pass.output_view,
+            Slot::Chained(Deck::B) => self.deck_b?.pp.read_view(),
+            Slot::Mixed => &self.deck_b?.mix.output_view,
+            Slot::Overlaid => &self.overlay?.output_view,
+        })
+    }
+
+    /// The texture shown with `deck` selected, falling back to deck A's
+    /// output when `deck` has nowhere to render.
+    fn shown(&self, graph: &FrameGraph, deck: Deck) -> &'v TextureView {
+        self.view(graph.shown(deck))
+            .or_else(|| self.view(graph.deck_output(Deck::A)))
+            .unwrap_or(&self.gen_pass.output_view)
+    }
+}
+
+impl FrameBackend for WgpuFrame<'_> {
+    fn dispatch_generator(
+        &mut self,
+        kind: GeneratorKind,
+        uniforms: &Uniforms,
+        extension: &GeneratorUniforms,
+    ) {
+        let gen_pass = match (self.deck, &self.deck_b) {
+            (Deck::A, _) => self.gen_pass,
+            (Deck::B, Some(b)) => &b.gen_pass,
+            (Deck::B, None) => return,
+        };
+        gen_pass.dispatch(
+            self.device,
+            self.encoder,
+            self.queue,
+            kind,
+            uniforms,
+            extension,
+        );
+        self.graph.add("generator", &[], Slot::Generated(self.deck));
+    }
+
+    fn upload_orbit(&mut self, orbit: &ReferenceOrbit) {
+        match (self.deck, &self.deck_b) {
+            (Deck::A, _) => self.gen_pass.upload_orbit(self.queue, orbit),
+            (Deck::B, Some(b)) => b.gen_pass.upload_orbit(self.queue, orbit),
+            (Deck::B, None) => {}
+        }
+    }
+
+    fn dispatch_chain(&mut self, effects: &[EffectKind], uniforms: &Uniforms) {
+        let [width, height] = uniforms.resolution.map(|v| v as u32);
+        let (gen_pass, pp) = match (self.deck, &mut self.deck_b) {
+            (Deck::A, _) => (self.gen_pass, &mut *self.pp),
+            (Deck::B, Some(b)) => (&b.gen_pass, &mut b.pp),
+            (Deck::B, None) => return,
+        };
+        self.effect_pass.dispatch_chain(
+            self.device,
+            self.encoder,
+            self.queue,
+            effects,
+            uniforms,
+            &gen_pass.output_view,
+            pp,
+            width,
+            height,
+        );
+        if !effects.is_empty() {
+            let deck = self.deck;
+            self.graph
+                .add("effects", &[Slot::Generated(deck)], Slot::Chained(deck));
+        }
+    }
+
+    fn select_deck(&mut self, deck: Deck) {
+        self.deck = deck;
+    }
+
+    fn crossfade(&mut self, amount: f32) {
+        let Some(b) = &self.deck_b else {
+            return;
+        };
+        let inputs = [
+            self.graph.deck_output(Deck::A),
+            self.graph.deck_output(Deck::B),
+        ];
+        let textures = Textures {
+            gen_pass: self.gen_pass,
+            pp: self.pp,
+            deck_b: Some(b),
+            overlay: None,
+        };
+        let (Some(deck_a), Some(deck_b)) = (textures.view(inputs[0]), textures.view(inputs[1]))
+        else {
+            return;
+        };
+        b.mix.dispatch(
+            self.device,
+            self.encoder,
+            self.queue,
+            deck_a,
+            deck_b,
+            amount,
+        );
+        self.graph.add("crossfade", &inputs, Slot::Mixed);
+    }
+
+    fn overlay(&mut self, params: &OverlayParams) {
+        let Some(overlay) = &self.overlay else {
+            return;
+        };
+        let input = self.graph.shown(self.deck);
+        let textures = Textures {
+            gen_pass: self.gen_pass,
+            pp: self.pp,
+            deck_b: self.deck_b.as_deref(),
+            overlay: None,
+        };
+        let base = textures.shown(&self.graph, self.deck);
+        overlay.dispatch(self.device, self.encoder, self.queue, base, params);
+        self.graph.add("overlay", &[input], Slot::Overlaid);
+    }
+
+    fn present(&mut self, projection: Projection) {
+        let (output, target, viewport) = (self.output, self.target, self.viewport);
+        self.with_final_texture(|device, queue, encoder, source| {
+            output.draw_in(device, queue, encoder, source, target, viewport, projection);
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MockBackend — records calls instead of touching a GPU
+// ---------------------------------------------------------------------------
+
+/// One call made through a `FrameBackend`.
+// Recorded by value so tests can compare extension blocks; the mock never
+// holds more than a few frames of calls.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum GpuCall {
+    Generator {
+        kind: GeneratorKind,
+        uniforms: Uniforms,
+        extension: GeneratorUniforms,
+    },
+    /// A reference orbit upload, by generation.
+    Orbit(u64),
+    Chain(Vec<EffectKind>),
+    SelectDeck(Deck),
+    Crossfade(f32),
+    Overlay(OverlayParams),
+    Present(Projection),
+}
+
+/// A `FrameBackend` for tests: keeps every call in order.
+#[derive(Debug, Default)]
+pub struct MockBackend {
+    pub calls: Vec<GpuCall>,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uniforms of the most recent generator dispatch.
+    pub fn last_uniforms(&self) -> Option<&Uniforms> {
+        self.calls.iter().rev().find_map(|c| match c {
+            GpuCall::Generator { uniforms, .. } => Some(uniforms),
+            _ => None,
+        })
+    }
+}
+
+impl FrameBackend for MockBackend {
+    fn dispatch_generator(
+        &mut self,
+        kind: GeneratorKind,
+        uniforms: &Uniforms,
+        extension: &GeneratorUniforms,
+    ) {
+        self.calls.push(GpuCall::Generator {
+            kind,
+            uniforms: *uniforms,
+            extension: *extension,
+        });
+    }
+
+    fn upload_orbit(&mut self, orbit: &ReferenceOrbit) {
+        self.calls.push(GpuCall::Orbit(orbit.generation));
+    }
+
+    fn dispatch_chain(&mut self, effects: &[EffectKind], _uniforms: &Uniforms) {
+        self.calls.push(GpuCall::Chain(effects.to_vec()));
+    }
+
+    fn select_deck(&mut self, deck: Deck) {
+        self.calls.push(GpuCall::SelectDeck(deck));
+    }
+
+    fn crossfade(&mut self, amount: f32) {
+        self.calls.push(GpuCall::Crossfade(amount));
+    }
+
+    fn overlay(&mut self, params: &OverlayParams) {
+        self.calls.push(GpuCall::Overlay(*params));
+    }
+
+    fn present(&mut self, projection: Projection) {
+        self.calls.push(GpuCall::Present(projection));
+    }
+}
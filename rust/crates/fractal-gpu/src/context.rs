@@ -1,4 +1,23 @@
-use wgpu::{Device, Instance, Queue};
+use fractal_core::{
+    coloring::{Coloring, Trap},
+    flame::FlameGen,
+    patch::Patch,
+    perturbation::{DeepZoom, Family, ReferenceOrbit, SeriesSkip},
+    projection::Projection,
+    CustomFormulaGen, DomainWarpGen, GeneratorKind, IfsGen, KleinianGen, MultibrotGen,
+    NoiseFieldGen, Params, PlasmaGen, ReactionDiffusionGen, SimplexFieldGen, WorleyFieldGen,
+};
+use wgpu::{Adapter, Device, Features, Instance, Queue};
+
+/// Features used when the adapter has them: `SHADER_F64` builds the f64
+/// generator kernels (see [`GeneratorPass::precise_for`](crate::generator_pipeline::GeneratorPass::precise_for)).
+pub const OPTIONAL_FEATURES: Features = Features::SHADER_F64;
+
+/// The [`OPTIONAL_FEATURES`] `adapter` supports, to request at device
+/// creation.
+pub fn optional_features(adapter: &Adapter) -> Features {
+    adapter.features() & OPTIONAL_FEATURES
+}
 
 pub struct GpuContext {
     pub instance: Instance,
@@ -25,7 +44,7 @@ impl GpuContext {
             .request_device(
                 &wgpu::DeviceDescriptor {
                     label: Some("fractal-gpu device"),
-                    required_features: wgpu::Features::empty(),
+                    required_features: optional_features(&adapter),
                     required_limits: wgpu::Limits::default(),
                     memory_hints: Default::default(),
                 },
@@ -42,9 +61,16 @@ impl GpuContext {
     }
 }
 
-/// All per-frame data uploaded to the GPU as a single uniform buffer.
-/// Must match the `Uniforms` struct in every WGSL shader.
+/// Per-frame data shared by every pass (binding 0 of the generator and of
+/// each effect).  Must match the `Uniforms` struct in every WGSL shader.
+/// Generator-specific values live in [`GeneratorUniforms`] instead, so new
+/// generator parameters don't grow the block every effect reads.
 /// `repr(C)` + `bytemuck` ensures safe casting to `&[u8]`.
+///
+/// The wide view at the end is declared only by the kernels that go past
+/// f32 precision — the df64 centre by the df64 and f64 ones, the f64 view
+/// by the f64 ones alone.  The other escape-time shaders read the first 48
+/// bytes, through `origin`, and the rest just the first 32.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Uniforms {
@@ -53,8 +79,1214 @@ pub struct Uniforms {
     pub zoom: f32,
     pub time: f32,
     pub max_iter: u32,
-    pub _pad: u32, // keep 16-byte alignment
-    // Julia-set specific (unused for other generators — zero them out)
-    pub julia_c: [f32; 2],
-    pub _pad2: [f32; 2],
+    /// `Projection::gpu_index` — 0 flat, 1 dome, 2 exp map.
+    pub projection: u32,
+    /// 1 to store the log-log smoothed escape count, 0 for the plain
+    /// integer count, which bands.
+    pub smoothing: u32,
+    /// Jittered samples each escape-time invocation averages per pixel:
+    /// 1, or one of [`SUPERSAMPLE_COUNTS`]; any other count takes one.
+    pub samples: u32,
+    /// Pixel the escape-time dispatch starts at, so a pan can recompute
+    /// just the strips it exposed (see [`crate::pan`]); 0 for a full frame.
+    pub origin: [u32; 2],
+    /// `center` as df64 hi/lo pairs, `[re.hi, re.lo, im.hi, im.lo]`, for
+    /// GPUs without f64.
+    pub center_df: [f32; 4],
+    /// `center` at full f64 precision — the deep centre while zoomed deep.
+    pub center64: [f64; 2],
+    pub zoom64: f64,
+    pub _pad64: f64,
+}
+
+impl Uniforms {
+    /// Build the per-frame uniforms for a `width`×`height` target from the
+    /// patch's current `Params`.
+    pub fn from_params(params: &Params, width: u32, height: u32) -> Self {
+        Self {
+            resolution: [width as f32, height as f32],
+            center: [params.center_x, params.center_y],
+            zoom: params.zoom,
+            time: params.time,
+            max_iter: params.max_iter,
+            projection: Projection::Flat.gpu_index(),
+            smoothing: 1,
+            samples: 1,
+            origin: [0; 2],
+            center_df: [params.center_x, 0.0, params.center_y, 0.0],
+            center64: [params.center_x as f64, params.center_y as f64],
+            zoom64: params.zoom as f64,
+            _pad64: 0.0,
+        }
+    }
+
+    /// Same uniforms with the wide centres taken from `deep`, if zoomed
+    /// deep, rather than rounded through the f32 params.
+    pub fn with_deep(mut self, deep: Option<&DeepZoom>) -> Self {
+        if let Some(deep) = deep {
+            let [x, y] = deep.center.to_f64();
+            let ([x_hi, x_lo], [y_hi, y_lo]) = (df64(x), df64(y));
+            self.center_df = [x_hi, x_lo, y_hi, y_lo];
+            self.center64 = [x, y];
+        }
+        self
+    }
+
+    /// Same uniforms rendered through `projection`.
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection.gpu_index();
+        self
+    }
+
+    /// Same uniforms with escape-time smoothing on or off.
+    pub fn with_smoothing(mut self, smoothing: bool) -> Self {
+        self.smoothing = smoothing as u32;
+        self
+    }
+
+    /// Same uniforms averaging `samples` jittered samples per pixel in the
+    /// escape-time generators.
+    pub fn with_samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+        self
+    }
+}
+
+/// Sample counts the escape-time generators supersample at, beyond one.
+/// Each uses the MSAA pattern of that size, every sample in its own row
+/// and column of the pixel.
+pub const SUPERSAMPLE_COUNTS: [u32; 3] = [2, 4, 8];
+
+/// `x` as a df64 pair: the nearest f32 and the f32 nearest the remainder.
+fn df64(x: f64) -> [f32; 2] {
+    let hi = x as f32;
+    [hi, (x - hi as f64) as f32]
+}
+
+/// The orbit trap of [`Coloring::OrbitTrap`] (see [`Trap`]), the tail of
+/// every escape-time extension block.  Must match `TrapParams` in their
+/// shaders.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TrapUniforms {
+    pub point: [f32; 2],
+    /// Unit direction of a line trap.
+    pub dir: [f32; 2],
+    pub radius: f32,
+    /// `TrapShape::gpu_index`.
+    pub shape: u32,
+    pub _pad: [f32; 2],
+}
+
+impl TrapUniforms {
+    pub fn from_params(params: &Params) -> Self {
+        let trap = Trap::from_params(params);
+        Self {
+            point: trap.point,
+            dir: [trap.angle.cos(), trap.angle.sin()],
+            radius: trap.radius,
+            shape: trap.shape.gpu_index(),
+            _pad: [0.0; 2],
+        }
+    }
+}
+
+/// Mandelbrot extension block: the [`Coloring`] mode, stalk thickness,
+/// distance-estimate switch and orbit trap.  Must match `ColoringParams` in
+/// `mandelbrot.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ColoringUniforms {
+    /// `Coloring::gpu_index`.
+    pub coloring: u32,
+    pub stalk_width: f32,
+    /// 1 to write the distance estimate to alpha
+    /// (`Coloring::distance_estimate`).
+    pub distance: u32,
+    pub _pad: f32,
+    pub trap: TrapUniforms,
+}
+
+impl ColoringUniforms {
+    pub fn from_params(params: &Params) -> Self {
+        Self {
+            coloring: Coloring::from_params(params).gpu_index(),
+            stalk_width: Coloring::stalk_width(params),
+            distance: Coloring::distance_estimate(params) as u32,
+            _pad: 0.0,
+            trap: TrapUniforms::from_params(params),
+        }
+    }
+}
+
+/// Burning Ship / Celtic / Buffalo extension block: the same coloring
+/// fields as [`ColoringUniforms`] plus which parts of z² the shared shader
+/// folds.  Must match `ShipParams` in `burning_ship.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ShipUniforms {
+    pub coloring: u32,
+    pub stalk_width: f32,
+    /// [`ShipUniforms::fold`] of the generator kind.
+    pub fold: u32,
+    pub _pad: f32,
+    pub trap: TrapUniforms,
+}
+
+impl ShipUniforms {
+    /// The shader's `fold` for `kind`: 0 Burning Ship, 1 Celtic, 2 Buffalo.
+    pub fn fold(kind: GeneratorKind) -> u32 {
+        match kind {
+            GeneratorKind::Celtic => 1,
+            GeneratorKind::Buffalo => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// Julia extension block: `c` plus the same coloring fields as
+/// [`ColoringUniforms`].  Must match `JuliaParams` in `julia.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct JuliaUniforms {
+    pub c: [f32; 2],
+    pub coloring: u32,
+    pub stalk_width: f32,
+    pub distance: u32,
+    pub _pad: [f32; 3],
+    pub trap: TrapUniforms,
+}
+
+/// Hybrid extension block: the same coloring fields as
+/// [`ColoringUniforms`] plus the
+/// [`HybridSchedule`](fractal_core::HybridSchedule) bitmask.  Must match
+/// `HybridParams` in `hybrid.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct HybridUniforms {
+    pub coloring: u32,
+    pub stalk_width: f32,
+    pub ship_steps: u32,
+    pub period: u32,
+    pub trap: TrapUniforms,
+}
+
+/// Perturbation extension block for a deep Mandelbrot or Julia view (see
+/// [`DeepZoom`]): where the view sits relative to the reference orbit, the
+/// series skip, plus the same coloring fields as [`ColoringUniforms`].
+/// Must match `PerturbParams` in `perturbation.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PerturbationUniforms {
+    pub offset: [f32; 2],
+    /// Points of the reference orbit in the orbit buffer.
+    pub orbit_len: u32,
+    /// 0 = Mandelbrot, 1 = Julia.
+    pub julia: u32,
+    pub coloring: u32,
+    pub stalk_width: f32,
+    /// A Julia's `c` relative to the orbit's (see [`DeepZoom::c_offset`]).
+    pub c_offset: [f32; 2],
+    pub trap: TrapUniforms,
+    /// `[A_n·r, B_n·r², C_n·r³]` of the series skip, for a pixel's offset
+    /// from the reference over `r`, the frame's largest: the scaled terms
+    /// stay within f32 range at any depth.
+    pub series: [[f32; 2]; 3],
+    /// Iterations every pixel jumps by the series (see
+    /// [`ReferenceOrbit::series_skip`]); 0 to iterate from the start.
+    pub skip: u32,
+    /// 1 / r.
+    pub series_scale: f32,
+}
+
+impl PerturbationUniforms {
+    /// The block for `deep`, perturbed from its reference `orbit`, for a
+    /// frame rendered with `uniforms`.
+    pub fn new(
+        deep: &DeepZoom,
+        orbit: &ReferenceOrbit,
+        params: &Params,
+        uniforms: &Uniforms,
+    ) -> Self {
+        let coloring = ColoringUniforms::from_params(params);
+        let [ox, oy] = deep.offset();
+        let projection = Projection::ALL
+            .into_iter()
+            .find(|p| p.gpu_index() == uniforms.projection)
+            .unwrap_or_default();
+        let [w, h] = uniforms.resolution;
+        let radius = (ox as f64).hypot(oy as f64)
+            + projection.max_plane_offset((w, h)) as f64 / uniforms.zoom as f64;
+        // Stalks and orbit traps read every step, and the series leaves out
+        // a moving Julia c.
+        let skip = (coloring.coloring == 0 && deep.c_offset() == [0.0; 2] && radius > 0.0)
+            .then(|| orbit.series_skip(radius, uniforms.max_iter))
+            .flatten();
+        let (series, skip, series_scale) = match skip {
+            Some(SeriesSkip { n, coefficients }) => {
+                let mut r = radius;
+                let series = coefficients.map(|[x, y]| {
+                    let term = [(x * r) as f32, (y * r) as f32];
+                    r *= radius;
+                    term
+                });
+                (series, n, (1.0 / radius) as f32)
+            }
+            None => ([[0.0; 2]; 3], 0, 0.0),
+        };
+        Self {
+            offset: deep.offset(),
+            orbit_len: orbit.points.len().min(ReferenceOrbit::MAX_POINTS) as u32,
+            julia: matches!(orbit.family, Family::Julia(_)) as u32,
+            coloring: coloring.coloring,
+            stalk_width: coloring.stalk_width,
+            c_offset: deep.c_offset(),
+            trap: coloring.trap,
+            series,
+            skip,
+            series_scale,
+        }
+    }
+}
+
+/// Multibrot extension block: the exponent plus the same coloring fields as
+/// [`ColoringUniforms`].  Must match `MultibrotParams` in `multibrot.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct MultibrotUniforms {
+    pub power: f32,
+    pub coloring: u32,
+    pub stalk_width: f32,
+    pub _pad: f32,
+    pub trap: TrapUniforms,
+}
+
+/// Kleinian extension block: the trace of generator `a` (see
+/// [`KleinianGen::trace`]).  Must match `KleinianParams` in `kleinian.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct KleinianUniforms {
+    pub trace: [f32; 2],
+    pub _pad: [f32; 2],
+}
+
+/// CustomFormula extension block (see [`CustomFormulaGen`] for the Params
+/// keys).  Must match `FormulaParams` in `formula.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FormulaUniforms {
+    /// The Julia constant; unused unless `julia` is set.
+    pub c: [f32; 2],
+    pub a: f32,
+    pub b: f32,
+    /// 1 to start the orbit at the pixel with a fixed `c`, 0 to start it at
+    /// `c` = the pixel.
+    pub julia: u32,
+    pub _pad: [f32; 3],
+}
+
+/// InverseJulia extension block.  Must match `InverseJuliaParams` in
+/// `inverse_julia.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct InverseJuliaUniforms {
+    pub c: [f32; 2],
+    pub exposure: f32,
+    pub _pad: f32,
+}
+
+/// IFS extension block: every map slot of [`IfsGen::maps`] with the
+/// cumulative pick thresholds the walkers compare a random number against.
+/// Must match `IfsParams` in `ifs.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct IfsUniforms {
+    /// Per map: a, b, c, d.
+    pub linear: [[f32; 4]; IfsGen::MAX_MAPS],
+    /// Per map: e, f, pick threshold, unused.
+    pub offset: [[f32; 4]; IfsGen::MAX_MAPS],
+    pub exposure: f32,
+    pub _pad: [f32; 3],
+}
+
+impl IfsUniforms {
+    pub fn from_params(params: &Params) -> Self {
+        let maps = IfsGen::maps(params);
+        let thresholds = pick_thresholds(maps.map(|m| m.weight));
+        let mut linear = [[0.0; 4]; IfsGen::MAX_MAPS];
+        let mut offset = [[0.0; 4]; IfsGen::MAX_MAPS];
+        for (i, m) in maps.iter().enumerate() {
+            linear[i] = [m.a, m.b, m.c, m.d];
+            offset[i] = [m.e, m.f, thresholds[i], 0.0];
+        }
+        Self {
+            linear,
+            offset,
+            exposure: params.get("ifs_exposure"),
+            _pad: [0.0; 3],
+        }
+    }
+}
+
+/// Cumulative pick thresholds for maps weighted `weights`.  Map i is picked
+/// when r ∈ [0, 1) falls below its threshold and not below any earlier one.
+/// The last weighted map's threshold is exactly 1 so rounding never hands
+/// the pick to an unweighted map; with no weight at all, map 0 is always
+/// picked.
+fn pick_thresholds<const N: usize>(weights: [f32; N]) -> [f32; N] {
+    let total: f32 = weights.iter().sum();
+    let last = weights.iter().rposition(|&w| w > 0.0).unwrap_or(0);
+    let mut sum = 0.0;
+    std::array::from_fn(|i| {
+        sum += weights[i];
+        if i >= last {
+            1.0
+        } else {
+            sum / total
+        }
+    })
+}
+
+/// Flame extension block: every transform of [`FlameGen::transforms`] with
+/// its pick threshold, colour and variation weights, then the tone-mapping
+/// settings.  Must match `FlameParams` in `flame.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FlameUniforms {
+    /// Per transform: a, b, c, d.
+    pub linear: [[f32; 4]; FlameGen::MAX_TRANSFORMS],
+    /// Per transform: e, f, pick threshold, colour.
+    pub offset: [[f32; 4]; FlameGen::MAX_TRANSFORMS],
+    /// Per transform: linear, sinusoidal, spherical and swirl weights.
+    pub blend: [[f32; 4]; FlameGen::MAX_TRANSFORMS],
+    /// Per transform: horseshoe and polar weights, unused, unused.
+    pub blend2: [[f32; 4]; FlameGen::MAX_TRANSFORMS],
+    /// Exposure, gamma, vibrancy, hue.
+    pub tone: [f32; 4],
+}
+
+impl FlameUniforms {
+    pub fn from_params(params: &Params) -> Self {
+        let transforms = FlameGen::transforms(params);
+        let thresholds = pick_thresholds(transforms.map(|t| t.map.weight));
+        let mut u: Self = bytemuck::Zeroable::zeroed();
+        for (i, t) in transforms.iter().enumerate() {
+            let (m, v) = (t.map, t.variations);
+            u.linear[i] = [m.a, m.b, m.c, m.d];
+            u.offset[i] = [m.e, m.f, thresholds[i], t.color];
+            u.blend[i] = [v[0], v[1], v[2], v[3]];
+            u.blend2[i] = [v[4], v[5], 0.0, 0.0];
+        }
+        u.tone = [
+            params.get("flame_exposure"),
+            FlameGen::gamma(params),
+            FlameGen::vibrancy(params),
+            params.get("flame_hue"),
+        ];
+        u
+    }
+}
+
+/// NoiseField extension block (see `NoiseFieldGen` for the Params keys).
+/// Must match `NoiseParams` in `noise_field.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct NoiseUniforms {
+    pub scale: f32,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub seed: f32,
+    pub z: f32,
+    pub _pad: [f32; 2],
+}
+
+/// SimplexField extension block: the noise field's FBM plus the ridge blend
+/// (see `SimplexFieldGen`).  Must match `SimplexParams` in
+/// `simplex_field.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SimplexUniforms {
+    pub scale: f32,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub seed: f32,
+    pub z: f32,
+    pub ridge: f32,
+    pub _pad: f32,
+}
+
+/// WorleyField extension block (see `WorleyFieldGen` for the Params keys).
+/// Must match `WorleyParams` in `worley_field.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct WorleyUniforms {
+    pub scale: f32,
+    pub seed: f32,
+    pub z: f32,
+    pub jitter: f32,
+    pub edge: f32,
+    pub _pad: [f32; 3],
+}
+
+/// ReactionDiffusion extension block (see `ReactionDiffusionGen` for the
+/// Params keys).  Must match `ReactionParams` in `reaction_diffusion.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ReactionUniforms {
+    pub feed: f32,
+    pub kill: f32,
+    /// Simulation steps the generator pass runs this frame.
+    pub steps: u32,
+    pub seed: f32,
+}
+
+/// DomainWarp extension block: the noise field's FBM plus the warp (see
+/// `DomainWarpGen`).  Must match `WarpParams` in `domain_warp.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct WarpUniforms {
+    pub scale: f32,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+    pub seed: f32,
+    pub z: f32,
+    pub strength: f32,
+    /// Radians each layer's displacement is turned by.
+    pub rotation: f32,
+    pub layers: u32,
+    pub _pad: [f32; 3],
+}
+
+/// Plasma extension block: the wave count and each wave's frequency and
+/// phase (see `PlasmaGen`).  Must match `PlasmaParams` in `plasma.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PlasmaUniforms {
+    pub waves: u32,
+    pub _pad: [u32; 3],
+    pub frequency: [f32; 4],
+    /// Radians, already wrapped to \[0, 2π).
+    pub phase: [f32; 4],
+}
+
+/// Generator-specific uniforms, bound at binding 2 of the generator pass
+/// next to the shared [`Uniforms`].  Generators without extra parameters
+/// leave the binding unused.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GeneratorUniforms {
+    None,
+    Coloring(ColoringUniforms),
+    Ship(ShipUniforms),
+    Julia(JuliaUniforms),
+    Multibrot(MultibrotUniforms),
+    Hybrid(HybridUniforms),
+    /// A deep Mandelbrot or Julia; the generator pass swaps in the
+    /// perturbation pipeline.
+    Perturbation(PerturbationUniforms),
+    Kleinian(KleinianUniforms),
+    Formula(FormulaUniforms),
+    InverseJulia(InverseJuliaUniforms),
+    Ifs(IfsUniforms),
+    Flame(FlameUniforms),
+    Noise(NoiseUniforms),
+    Simplex(SimplexUniforms),
+    Worley(WorleyUniforms),
+    Reaction(ReactionUniforms),
+    Warp(WarpUniforms),
+    Plasma(PlasmaUniforms),
+}
+
+impl GeneratorUniforms {
+    /// Byte size of the largest block; the extension buffer is this big.
+    pub const MAX_SIZE: usize = std::mem::size_of::<FlameUniforms>();
+
+    /// The extension block `patch` renders with through `uniforms`: the
+    /// perturbation block while it is zoomed deep with a reference orbit
+    /// (see [`Patch::deep`]), otherwise its generator's block.
+    pub fn for_patch(patch: &Patch, uniforms: &Uniforms) -> Self {
+        if let Some(deep) = &patch.deep {
+            if let Some(orbit) = &deep.orbit {
+                return Self::Perturbation(PerturbationUniforms::new(
+                    deep,
+                    orbit,
+                    &patch.params,
+                    uniforms,
+                ));
+            }
+        }
+        Self::from_params(patch.generator.kind(), &patch.params)
+    }
+
+    /// The extension block `kind` reads, filled from `params`.
+    pub fn from_params(kind: GeneratorKind, params: &Params) -> Self {
+        match kind {
+            GeneratorKind::Mandelbrot => Self::Coloring(ColoringUniforms::from_params(params)),
+            GeneratorKind::BurningShip | GeneratorKind::Celtic | GeneratorKind::Buffalo => {
+                let coloring = ColoringUniforms::from_params(params);
+                Self::Ship(ShipUniforms {
+                    coloring: coloring.coloring,
+                    stalk_width: coloring.stalk_width,
+                    fold: ShipUniforms::fold(kind),
+                    _pad: 0.0,
+                    trap: coloring.trap,
+                })
+            }
+            GeneratorKind::Video | GeneratorKind::Image | GeneratorKind::TestPattern => Self::None,
+            GeneratorKind::Julia => {
+                let coloring = ColoringUniforms::from_params(params);
+                Self::Julia(JuliaUniforms {
+                    c: [params.get("julia_cx"), params.get("julia_cy")],
+                    coloring: coloring.coloring,
+                    stalk_width: coloring.stalk_width,
+                    distance: coloring.distance,
+                    _pad: [0.0; 3],
+                    trap: coloring.trap,
+                })
+            }
+            GeneratorKind::Multibrot => {
+                let coloring = ColoringUniforms::from_params(params);
+                Self::Multibrot(MultibrotUniforms {
+                    power: MultibrotGen::power(params),
+                    coloring: coloring.coloring,
+                    stalk_width: coloring.stalk_width,
+                    _pad: 0.0,
+                    trap: coloring.trap,
+                })
+            }
+            GeneratorKind::Hybrid(schedule) => {
+                let coloring = ColoringUniforms::from_params(params);
+                Self::Hybrid(HybridUniforms {
+                    coloring: coloring.coloring,
+                    stalk_width: coloring.stalk_width,
+                    ship_steps: schedule.ship_steps(),
+                    period: schedule.period(),
+                    trap: coloring.trap,
+                })
+            }
+            GeneratorKind::Kleinian => Self::Kleinian(KleinianUniforms {
+                trace: KleinianGen::trace(params),
+                _pad: [0.0; 2],
+            }),
+            GeneratorKind::CustomFormula => Self::Formula(FormulaUniforms {
+                c: [params.get("julia_cx"), params.get("julia_cy")],
+                a: params.get("formula_a"),
+                b: params.get("formula_b"),
+                julia: CustomFormulaGen::julia(params) as u32,
+                _pad: [0.0; 3],
+            }),
+            GeneratorKind::InverseJulia => Self::InverseJulia(InverseJuliaUniforms {
+                c: [params.get("julia_cx"), params.get("julia_cy")],
+                exposure: params.get("inverse_julia_exposure"),
+                _pad: 0.0,
+            }),
+            GeneratorKind::Ifs => Self::Ifs(IfsUniforms::from_params(params)),
+            GeneratorKind::Flame => Self::Flame(FlameUniforms::from_params(params)),
+            GeneratorKind::NoiseField => Self::Noise(NoiseUniforms {
+                scale: params.get("noise_scale"),
+                octaves: NoiseFieldGen::octaves(params),
+                lacunarity: params.get("noise_lacunarity"),
+                gain: params.get("noise_gain"),
+                seed: params.get("noise_seed"),
+                z: NoiseFieldGen::z_slice(params),
+                _pad: [0.0; 2],
+            }),
+            GeneratorKind::SimplexField => Self::Simplex(SimplexUniforms {
+                scale: params.get("noise_scale"),
+                octaves: NoiseFieldGen::octaves(params),
+                lacunarity: params.get("noise_lacunarity"),
+                gain: params.get("noise_gain"),
+                seed: params.get("noise_seed"),
+                z: NoiseFieldGen::z_slice(params),
+                ridge: SimplexFieldGen::ridge(params),
+                _pad: 0.0,
+            }),
+            GeneratorKind::WorleyField => Self::Worley(WorleyUniforms {
+                scale: params.get("noise_scale"),
+                seed: params.get("noise_seed"),
+                z: NoiseFieldGen::z_slice(params),
+                jitter: WorleyFieldGen::jitter(params),
+                edge: WorleyFieldGen::edge(params),
+                _pad: [0.0; 3],
+            }),
+            GeneratorKind::ReactionDiffusion => Self::Reaction(ReactionUniforms {
+                feed: ReactionDiffusionGen::feed(params),
+                kill: ReactionDiffusionGen::kill(params),
+                steps: ReactionDiffusionGen::steps(params),
+                seed: params.get("reaction_seed"),
+            }),
+            GeneratorKind::DomainWarp => Self::Warp(WarpUniforms {
+                scale: params.get("noise_scale"),
+                octaves: NoiseFieldGen::octaves(params),
+                lacunarity: params.get("noise_lacunarity"),
+                gain: params.get("noise_gain"),
+                seed: params.get("noise_seed"),
+                z: NoiseFieldGen::z_slice(params),
+                strength: DomainWarpGen::strength(params),
+                rotation: DomainWarpGen::rotation(params),
+                layers: DomainWarpGen::layers(params),
+                _pad: [0.0; 3],
+            }),
+            GeneratorKind::Plasma => Self::Plasma(PlasmaUniforms {
+                waves: PlasmaGen::waves(params),
+                _pad: [0; 3],
+                frequency: PlasmaGen::frequencies(params),
+                phase: PlasmaGen::phases(params),
+            }),
+        }
+    }
+
+    /// Bytes to upload; empty for `None`.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::None => &[],
+            Self::Coloring(u) => bytemuck::bytes_of(u),
+            Self::Ship(u) => bytemuck::bytes_of(u),
+            Self::Julia(u) => bytemuck::bytes_of(u),
+            Self::Multibrot(u) => bytemuck::bytes_of(u),
+            Self::Hybrid(u) => bytemuck::bytes_of(u),
+            Self::Perturbation(u) => bytemuck::bytes_of(u),
+            Self::Kleinian(u) => bytemuck::bytes_of(u),
+            Self::Formula(u) => bytemuck::bytes_of(u),
+            Self::InverseJulia(u) => bytemuck::bytes_of(u),
+            Self::Ifs(u) => bytemuck::bytes_of(u),
+            Self::Flame(u) => bytemuck::bytes_of(u),
+            Self::Noise(u) => bytemuck::bytes_of(u),
+            Self::Simplex(u) => bytemuck::bytes_of(u),
+            Self::Worley(u) => bytemuck::bytes_of(u),
+            Self::Reaction(u) => bytemuck::bytes_of(u),
+            Self::Warp(u) => bytemuck::bytes_of(u),
+            Self::Plasma(u) => bytemuck::bytes_of(u),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fractal_core::{
+        coloring::TrapShape,
+        flame::{FlameTransform, Variation},
+        IfsMap,
+    };
+
+    fn noise(p: &Params) -> NoiseUniforms {
+        match GeneratorUniforms::from_params(GeneratorKind::NoiseField, p) {
+            GeneratorUniforms::Noise(n) => n,
+            other => panic!("expected noise uniforms, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn from_params_copies_view() {
+        let p = Params {
+            center_x: 0.25,
+            center_y: -0.5,
+            zoom: 4.0,
+            max_iter: 250,
+            ..Default::default()
+        };
+        let u = Uniforms::from_params(&p, 800, 600);
+        assert_eq!(u.resolution, [800.0, 600.0]);
+        assert_eq!(u.center, [0.25, -0.5]);
+        assert_eq!(u.zoom, 4.0);
+        assert_eq!(u.max_iter, 250);
+    }
+
+    #[test]
+    fn projection_defaults_to_flat() {
+        let u = Uniforms::from_params(&Params::default(), 64, 64);
+        assert_eq!(u.projection, Projection::Flat.gpu_index());
+        let u = u.with_projection(Projection::Dome);
+        assert_eq!(u.projection, Projection::Dome.gpu_index());
+    }
+
+    #[test]
+    fn smoothing_defaults_on() {
+        assert_eq!(std::mem::offset_of!(Uniforms, smoothing), 32);
+        let u = Uniforms::from_params(&Params::default(), 64, 64);
+        assert_eq!(u.smoothing, 1);
+        assert_eq!(u.with_smoothing(false).smoothing, 0);
+        assert_eq!(u.with_smoothing(false).with_smoothing(true).smoothing, 1);
+    }
+
+    #[test]
+    fn samples_default_to_one_after_smoothing() {
+        assert_eq!(std::mem::offset_of!(Uniforms, samples), 36);
+        let u = Uniforms::from_params(&Params::default(), 64, 64);
+        assert_eq!(u.samples, 1);
+        assert_eq!(u.with_samples(8).samples, 8);
+        assert_eq!(std::mem::offset_of!(Uniforms, origin), 40);
+        assert_eq!(u.origin, [0, 0]);
+    }
+
+    #[test]
+    fn wide_view_follows_the_f32_block_and_the_deep_centre() {
+        // Where `center64` sits in the f64 kernels' `Uniforms`.
+        assert_eq!(std::mem::offset_of!(Uniforms, center_df), 48);
+        assert_eq!(std::mem::offset_of!(Uniforms, center64), 64);
+        let mut patch = Patch::new(Box::new(fractal_core::BurningShipGen), Params::default());
+        patch.params.zoom = 1e9;
+        patch.tick(0.0);
+        patch.pan(1e-9, 0.0);
+        let u = Uniforms::from_params(&patch.params, 64, 64);
+        assert_eq!(u.center64[0], u.center[0] as f64, "rounded through f32");
+        let u = u.with_deep(patch.deep.as_ref());
+        assert!((u.center64[0] - (u.center[0] as f64 + 1e-9)).abs() < 1e-15);
+        let [hi, lo, ..] = u.center_df;
+        assert_eq!(hi, u.center[0]);
+        assert!((hi as f64 + lo as f64 - u.center64[0]).abs() < 1e-15);
+        assert_eq!(u.zoom64, 1e9);
+        assert!(matches!(
+            GeneratorUniforms::for_patch(&patch, &u),
+            GeneratorUniforms::Ship(_)
+        ));
+    }
+
+    #[test]
+    fn extension_blocks_are_16_byte_multiples() {
+        assert_eq!(std::mem::size_of::<TrapUniforms>(), 32);
+        assert_eq!(std::mem::size_of::<ColoringUniforms>(), 48);
+        assert_eq!(std::mem::size_of::<ShipUniforms>(), 48);
+        assert_eq!(std::mem::size_of::<JuliaUniforms>(), 64);
+        assert_eq!(std::mem::size_of::<MultibrotUniforms>(), 48);
+        assert_eq!(std::mem::size_of::<InverseJuliaUniforms>(), 16);
+        assert_eq!(std::mem::size_of::<KleinianUniforms>(), 16);
+        assert_eq!(std::mem::size_of::<FormulaUniforms>(), 32);
+        assert_eq!(std::mem::size_of::<HybridUniforms>(), 48);
+        assert_eq!(std::mem::size_of::<PerturbationUniforms>(), 96);
+        assert_eq!(std::mem::size_of::<NoiseUniforms>(), 32);
+        assert_eq!(std::mem::size_of::<WarpUniforms>(), 48);
+        assert_eq!(std::mem::size_of::<PlasmaUniforms>(), 48);
+        assert_eq!(std::mem::size_of::<IfsUniforms>(), 144);
+        assert_eq!(std::mem::size_of::<FlameUniforms>(), 272);
+        assert_eq!(GeneratorUniforms::MAX_SIZE, 272);
+    }
+
+    #[test]
+    fn ifs_thresholds_split_by_weight() {
+        let mut p = Params::default();
+        IfsGen::set_maps(&mut p, &IfsGen::BARNSLEY_FERN);
+        p.set("ifs_exposure", 10.0);
+        let GeneratorUniforms::Ifs(u) = GeneratorUniforms::from_params(GeneratorKind::Ifs, &p)
+        else {
+            panic!("expected ifs uniforms");
+        };
+        let thresholds: Vec<f32> = u.offset.iter().map(|o| o[2]).collect();
+        assert!((thresholds[0] - 0.01).abs() < 1e-6);
+        assert!((thresholds[1] - 0.86).abs() < 1e-6);
+        assert!((thresholds[2] - 0.93).abs() < 1e-6);
+        assert_eq!(thresholds[3], 1.0);
+        assert_eq!(u.linear[1], [0.85, -0.04, 0.04, 0.85]);
+        assert_eq!(u.exposure, 10.0);
+    }
+
+    #[test]
+    fn ifs_unweighted_slots_are_never_picked() {
+        let mut p = Params::default();
+        IfsGen::set_maps(&mut p, &IfsGen::SIERPINSKI);
+        let u = IfsUniforms::from_params(&p);
+        assert_eq!(u.offset[2][2], 1.0);
+        assert_eq!(u.offset[3][2], 1.0);
+        // Nothing weighted: map 0 takes every pick.
+        IfsGen::set_maps(&mut p, &[]);
+        let u = IfsUniforms::from_params(&p);
+        assert!(u.offset.iter().all(|o| o[2] == 1.0));
+    }
+
+    #[test]
+    fn flame_block_carries_transforms_and_tone() {
+        let mut p = Params::default();
+        FlameGen::set_defaults(&mut p);
+        FlameGen::set_transforms(
+            &mut p,
+            &[
+                FlameTransform::new(IfsMap::toward(0.0, 0.0, 0.5), 0.0, Variation::Swirl),
+                FlameTransform::new(IfsMap::toward(1.0, 0.0, 0.5), 0.5, Variation::Polar)
+                    .with(Variation::Sinusoidal, 0.25),
+            ],
+        );
+        p.set("flame_gamma", 3.0);
+        let GeneratorUniforms::Flame(u) = GeneratorUniforms::from_params(GeneratorKind::Flame, &p)
+        else {
+            panic!("expected flame uniforms");
+        };
+        assert_eq!(u.offset[0], [0.0, 0.0, 0.5, 0.0]);
+        assert_eq!(u.offset[1], [0.5, 0.0, 1.0, 0.5]);
+        assert_eq!(u.offset[2][2], 1.0);
+        assert_eq!(u.blend[0], [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(u.blend[1], [0.0, 0.25, 0.0, 0.0]);
+        assert_eq!(u.blend2[1], [0.0, 1.0, 0.0, 0.0]);
+        assert_eq!(
+            u.tone,
+            [
+                FlameGen::DEFAULT_EXPOSURE,
+                3.0,
+                FlameGen::DEFAULT_VIBRANCY,
+                0.0
+            ]
+        );
+        assert_eq!(GeneratorUniforms::Flame(u).as_bytes().len(), 272);
+    }
+
+    #[test]
+    fn hybrid_extension_carries_the_schedule() {
+        let mut p = Params::default();
+        Coloring::PickoverStalks.set(&mut p);
+        let schedule = fractal_core::HybridSchedule::parse("MBB").unwrap();
+        let ext = GeneratorUniforms::from_params(GeneratorKind::Hybrid(schedule), &p);
+        let GeneratorUniforms::Hybrid(u) = ext else {
+            panic!("expected a hybrid block, got {ext:?}");
+        };
+        assert_eq!((u.ship_steps, u.period), (0b110, 3));
+        assert_eq!(u.coloring, Coloring::PickoverStalks.gpu_index());
+    }
+
+    #[test]
+    fn kleinian_extension_carries_the_clamped_trace() {
+        let mut p = Params::default();
+        p.set("kleinian_trace_re", 1.9);
+        p.set("kleinian_trace_im", 3.0);
+        let ext = GeneratorUniforms::from_params(GeneratorKind::Kleinian, &p);
+        assert_eq!(
+            ext,
+            GeneratorUniforms::Kleinian(KleinianUniforms {
+                trace: [1.9, KleinianGen::MAX_TRACE[1]],
+                _pad: [0.0; 2],
+            })
+        );
+        assert_eq!(ext.as_bytes().len(), 16);
+    }
+
+    #[test]
+    fn formula_extension_carries_a_b_and_the_julia_switch() {
+        let mut p = Params::default();
+        p.set("formula_a", 0.5);
+        p.set("formula_b", -2.0);
+        p.set("julia_cx", 0.3);
+        let ext = GeneratorUniforms::from_params(GeneratorKind::CustomFormula, &p);
+        let GeneratorUniforms::Formula(u) = ext else {
+            panic!("expected formula uniforms, got {ext:?}");
+        };
+        assert_eq!((u.c, u.a, u.b, u.julia), ([0.3, 0.0], 0.5, -2.0, 0));
+        p.set("formula_julia", 1.0);
+        let GeneratorUniforms::Formula(u) =
+            GeneratorUniforms::from_params(GeneratorKind::CustomFormula, &p)
+        else {
+            unreachable!()
+        };
+        assert_eq!(u.julia, 1);
+    }
+
+    #[test]
+    fn julia_extension_carries_c() {
+        let mut p = Params::default();
+        p.set("julia_cx", -0.7);
+        p.set("julia_cy", 0.27);
+        let ext = GeneratorUniforms::from_params(GeneratorKind::Julia, &p);
+        assert_eq!(
+            ext,
+            GeneratorUniforms::Julia(JuliaUniforms {
+                c: [-0.7, 0.27],
+                coloring: 0,
+                stalk_width: Coloring::DEFAULT_STALK_WIDTH,
+                distance: 0,
+                _pad: [0.0; 3],
+                trap: TrapUniforms::from_params(&p),
+            })
+        );
+        assert_eq!(ext.as_bytes().len(), 64);
+    }
+
+    #[test]
+    fn deep_patch_renders_with_the_perturbation_block() {
+        let mut patch = Patch::new(Box::new(fractal_core::JuliaGen), Params::default());
+        patch.params.set("julia_cx", -0.7);
+        patch.tick(0.0);
+        let view = |patch: &Patch| Uniforms::from_params(&patch.params, 64, 64);
+        assert!(matches!(
+            GeneratorUniforms::for_patch(&patch, &view(&patch)),
+            GeneratorUniforms::Julia(_)
+        ));
+        patch.params.zoom = 1e8;
+        patch.tick(0.0);
+        patch.pan(1e-9, 0.0);
+        let GeneratorUniforms::Perturbation(u) =
+            GeneratorUniforms::for_patch(&patch, &view(&patch))
+        else {
+            panic!("expected the perturbation block");
+        };
+        assert_eq!(u.julia, 1);
+        let orbit = patch.deep.as_ref().unwrap().orbit.as_ref().unwrap();
+        assert_eq!(u.orbit_len as usize, orbit.points.len());
+        assert!((u.offset[0] - 1e-9).abs() < 1e-15, "{:?}", u.offset);
+        assert_eq!(GeneratorUniforms::Perturbation(u).as_bytes().len(), 96);
+
+        // The series skip, its terms scaled by the frame's radius.
+        assert!(u.skip > 0);
+        let radius = 1.0 / u.series_scale as f64;
+        assert!(radius > 2f64.sqrt() / 1e8 && radius < 2.0 / 1e8, "{radius}");
+        let a = orbit.series[u.skip as usize][0];
+        assert!((u.series[0][0] as f64 - a[0] * radius).abs() <= (a[0] * radius).abs() * 1e-6);
+        Coloring::PickoverStalks.set(&mut patch.params);
+        let GeneratorUniforms::Perturbation(u) =
+            GeneratorUniforms::for_patch(&patch, &view(&patch))
+        else {
+            panic!("expected the perturbation block");
+        };
+        assert_eq!(u.skip, 0, "stalks read every step");
+    }
+
+    #[test]
+    fn escape_time_generators_carry_coloring() {
+        let mut p = Params::default();
+        Coloring::PickoverStalks.set(&mut p);
+        p.set("stalk_width", 0.05);
+        let ext = GeneratorUniforms::from_params(GeneratorKind::Mandelbrot, &p);
+        assert_eq!(
+            ext,
+            GeneratorUniforms::Coloring(ColoringUniforms {
+                coloring: 1,
+                stalk_width: 0.05,
+                distance: 0,
+                _pad: 0.0,
+                trap: TrapUniforms::from_params(&p),
+            })
+        );
+        assert_eq!(ext.as_bytes().len(), 48);
+        let GeneratorUniforms::Ship(ship) =
+            GeneratorUniforms::from_params(GeneratorKind::BurningShip, &p)
+        else {
+            panic!("expected ship uniforms");
+        };
+        assert_eq!((ship.coloring, ship.stalk_width), (1, 0.05));
+        let GeneratorUniforms::Julia(julia) =
+            GeneratorUniforms::from_params(GeneratorKind::Julia, &p)
+        else {
+            panic!("expected julia uniforms");
+        };
+        assert_eq!((julia.coloring, julia.stalk_width), (1, 0.05));
+    }
+
+    #[test]
+    fn edge_glow_switches_on_the_distance_estimate() {
+        let mut p = Params::default();
+        Coloring::EdgeGlow.set(&mut p);
+        let u = ColoringUniforms::from_params(&p);
+        assert_eq!((u.coloring, u.distance), (3, 1));
+        let GeneratorUniforms::Julia(julia) =
+            GeneratorUniforms::from_params(GeneratorKind::Julia, &p)
+        else {
+            panic!("expected julia uniforms");
+        };
+        assert_eq!(julia.distance, 1);
+        Coloring::EscapeTime.set(&mut p);
+        assert_eq!(ColoringUniforms::from_params(&p).distance, 0);
+        p.set("distance_estimate", 1.0);
+        assert_eq!(ColoringUniforms::from_params(&p).distance, 1);
+    }
+
+    #[test]
+    fn orbit_trap_rides_in_every_escape_time_block() {
+        let mut p = Params::default();
+        Coloring::OrbitTrap.set(&mut p);
+        Trap {
+            shape: TrapShape::Line,
+            point: [0.5, -0.25],
+            angle: std::f32::consts::FRAC_PI_2,
+            radius: 1.0,
+        }
+        .set(&mut p);
+        let trap = TrapUniforms::from_params(&p);
+        assert_eq!(trap.point, [0.5, -0.25]);
+        assert!(trap.dir[0].abs() < 1e-6 && (trap.dir[1] - 1.0).abs() < 1e-6);
+        assert_eq!((trap.shape, trap.radius), (1, 1.0));
+        for kind in [
+            GeneratorKind::Mandelbrot,
+            GeneratorKind::Julia,
+            GeneratorKind::BurningShip,
+            GeneratorKind::Multibrot,
+            GeneratorKind::Hybrid(Default::default()),
+        ] {
+            let ext = GeneratorUniforms::from_params(kind, &p);
+            // The trap is the last 32 bytes of each block.
+            let bytes = ext.as_bytes();
+            assert_eq!(
+                &bytes[bytes.len() - 32..],
+                bytemuck::bytes_of(&trap),
+                "{kind:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn ship_family_shares_one_block_with_its_fold() {
+        let p = Params::default();
+        for (kind, fold) in [
+            (GeneratorKind::BurningShip, 0),
+            (GeneratorKind::Celtic, 1),
+            (GeneratorKind::Buffalo, 2),
+        ] {
+            let ext = GeneratorUniforms::from_params(kind, &p);
+            assert_eq!(
+                ext,
+                GeneratorUniforms::Ship(ShipUniforms {
+                    coloring: 0,
+                    stalk_width: Coloring::DEFAULT_STALK_WIDTH,
+                    fold,
+                    _pad: 0.0,
+                    trap: TrapUniforms::from_params(&p),
+                })
+            );
+            assert_eq!(ext.as_bytes().len(), 48);
+        }
+    }
+
+    #[test]
+    fn multibrot_extension_carries_power_and_coloring() {
+        let mut p = Params::default();
+        p.set("multibrot_power", 5.5);
+        Coloring::PickoverStalks.set(&mut p);
+        let ext = GeneratorUniforms::from_params(GeneratorKind::Multibrot, &p);
+        assert_eq!(
+            ext,
+            GeneratorUniforms::Multibrot(MultibrotUniforms {
+                power: 5.5,
+                coloring: 1,
+                stalk_width: Coloring::DEFAULT_STALK_WIDTH,
+                _pad: 0.0,
+                trap: TrapUniforms::from_params(&p),
+            })
+        );
+        assert_eq!(ext.as_bytes().len(), 48);
+    }
+
+    #[test]
+    fn sourced_generators_have_no_extension() {
+        for kind in [GeneratorKind::Video, GeneratorKind::Image] {
+            let ext = GeneratorUniforms::from_params(kind, &Params::default());
+            assert_eq!(ext, GeneratorUniforms::None);
+            assert!(ext.as_bytes().is_empty());
+        }
+    }
+
+    #[test]
+    fn noise_extension_copies_noise_fields() {
+        let mut p = Params::default();
+        NoiseFieldGen::set_defaults(&mut p);
+        p.set("noise_seed", 7.0);
+        let n = noise(&p);
+        assert_eq!(n.scale, NoiseFieldGen::DEFAULT_SCALE);
+        assert_eq!(n.octaves, NoiseFieldGen::DEFAULT_OCTAVES);
+        assert_eq!(n.lacunarity, NoiseFieldGen::DEFAULT_LACUNARITY);
+        assert_eq!(n.gain, NoiseFieldGen::DEFAULT_GAIN);
+        assert_eq!(n.seed, 7.0);
+    }
+
+    #[test]
+    fn noise_z_defaults_to_time() {
+        let mut p = Params {
+            time: 3.5,
+            ..Default::default()
+        };
+        assert_eq!(noise(&p).z, 3.5);
+        p.set("noise_z", -1.25);
+        assert_eq!(noise(&p).z, -1.25);
+    }
+
+    #[test]
+    fn simplex_and_worley_blocks_share_the_noise_keys() {
+        let mut p = Params {
+            time: 2.0,
+            ..Default::default()
+        };
+        SimplexFieldGen::set_defaults(&mut p);
+        p.set("worley_edge", 0.25);
+        let GeneratorUniforms::Simplex(s) =
+            GeneratorUniforms::from_params(GeneratorKind::SimplexField, &p)
+        else {
+            panic!("expected the simplex block");
+        };
+        assert_eq!(s.scale, NoiseFieldGen::DEFAULT_SCALE);
+        assert_eq!(s.octaves, NoiseFieldGen::DEFAULT_OCTAVES);
+        assert_eq!(s.ridge, SimplexFieldGen::DEFAULT_RIDGE);
+        assert_eq!(s.z, 2.0);
+        let ext = GeneratorUniforms::from_params(GeneratorKind::WorleyField, &p);
+        let GeneratorUniforms::Worley(w) = ext else {
+            panic!("expected the worley block, got {ext:?}");
+        };
+        assert_eq!(w.scale, NoiseFieldGen::DEFAULT_SCALE);
+        assert_eq!(w.edge, 0.25);
+        assert_eq!(w.z, 2.0);
+        assert_eq!(ext.as_bytes().len(), 32);
+    }
+
+    #[test]
+    fn reaction_block_clamps_its_rates_and_steps() {
+        let mut p = Params::default();
+        ReactionDiffusionGen::set_defaults(&mut p);
+        p.set("reaction_kill", 2.0);
+        p.set("reaction_steps", 500.0);
+        let ext = GeneratorUniforms::from_params(GeneratorKind::ReactionDiffusion, &p);
+        assert_eq!(
+            ext,
+            GeneratorUniforms::Reaction(ReactionUniforms {
+                feed: ReactionDiffusionGen::DEFAULT_FEED,
+                kill: ReactionDiffusionGen::MAX_RATE,
+                steps: ReactionDiffusionGen::MAX_STEPS,
+                seed: 0.0,
+            })
+        );
+        assert_eq!(ext.as_bytes().len(), 16);
+    }
+
+    #[test]
+    fn warp_block_carries_the_noise_keys_and_the_warp() {
+        let mut p = Params {
+            time: 3.0,
+            ..Default::default()
+        };
+        DomainWarpGen::set_defaults(&mut p);
+        p.set("warp_strength", 99.0);
+        p.set("warp_rotation", 0.5);
+        p.set("warp_layers", 3.0);
+        let ext = GeneratorUniforms::from_params(GeneratorKind::DomainWarp, &p);
+        let GeneratorUniforms::Warp(w) = ext else {
+            panic!("expected the warp block, got {ext:?}");
+        };
+        assert_eq!(w.scale, NoiseFieldGen::DEFAULT_SCALE);
+        assert_eq!(w.octaves, NoiseFieldGen::DEFAULT_OCTAVES);
+        assert_eq!(w.z, 3.0);
+        assert_eq!(w.strength, DomainWarpGen::MAX_STRENGTH);
+        assert_eq!(w.rotation, 0.5);
+        assert_eq!(w.layers, 3);
+        assert_eq!(ext.as_bytes().len(), 48);
+    }
+
+    #[test]
+    fn plasma_block_carries_each_waves_frequency_and_phase() {
+        let mut p = Params {
+            time: 2.0,
+            ..Default::default()
+        };
+        PlasmaGen::set_defaults(&mut p);
+        p.set("plasma_waves", 3.0);
+        p.set("plasma1_freq", 99.0);
+        let ext = GeneratorUniforms::from_params(GeneratorKind::Plasma, &p);
+        let GeneratorUniforms::Plasma(w) = ext else {
+            panic!("expected the plasma block, got {ext:?}");
+        };
+        assert_eq!(w.waves, 3);
+        assert_eq!(w.frequency[0], PlasmaGen::DEFAULT_FREQUENCIES[0]);
+        assert_eq!(w.frequency[1], PlasmaGen::MAX_FREQUENCY);
+        assert_eq!(w.phase, PlasmaGen::phases(&p));
+        assert!((w.phase[0] - 2.0).abs() < 1e-6);
+        assert_eq!(ext.as_bytes().len(), 48);
+    }
 }
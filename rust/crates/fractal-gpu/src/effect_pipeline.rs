@@ -1,7 +1,14 @@
-use fractal_core::{ColorScheme, EffectKind};
-use wgpu::{BindGroupLayout, Buffer, ComputePipeline, Device, Queue, Sampler};
+use std::ops::Range;
 
-use crate::context::Uniforms;
+use fractal_core::{patch::Patch, ColorScheme, EffectKind, OverlayMode, OVERLAY_SAMPLES};
+use wgpu::{BindGroupLayout, ComputePipeline, Device, PipelineLayout, Queue, Sampler};
+
+use crate::{
+    aux_textures::{AuxDesc, AuxTextures},
+    context::Uniforms,
+    shader::{self, ShaderError},
+    stats,
+};
 
 /// Shared per-effect params buffer size.
 /// 16 bytes fits every effect's parameter struct.
@@ -31,7 +38,9 @@ impl PingPong {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba16Float,
-            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+            usage: wgpu::TextureUsages::STORAGE_BINDING
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
             view_formats: &[],
         };
         let tex_a = device.create_texture(&wgpu::TextureDescriptor {
@@ -60,6 +69,14 @@ impl PingPong {
             &self.view_a
         }
     }
+    /// Texture behind [`read_view`](Self::read_view), for copy-out.
+    pub fn read_texture(&self) -> &wgpu::Texture {
+        if self.current {
+            &self.tex_b
+        } else {
+            &self.tex_a
+        }
+    }
     pub fn write_view(&self) -> &wgpu::TextureView {
         if self.current {
             &self.view_a
@@ -72,35 +89,220 @@ impl PingPong {
     }
 }
 
+/// Effects that sample their input through the linear sampler (UV warps)
+/// and therefore use the layout with binding 4.
+fn uses_sampler(kind: &EffectKind) -> bool {
+    matches!(
+        kind,
+        EffectKind::Ripple { .. } | EffectKind::Echo { .. } | EffectKind::MotionBlur { .. }
+    )
+}
+
+/// Effects that also read a block of audio samples at binding 4.
+fn uses_samples(kind: &EffectKind) -> bool {
+    matches!(kind, EffectKind::AudioOverlay { .. })
+}
+
+/// Effects worth running at half resolution when [`EffectPass::half_res`]
+/// is on: the multi-tap blurs, whose cost grows with every pixel they touch.
+pub fn runs_at_half_res(kind: &EffectKind) -> bool {
+    matches!(
+        kind,
+        EffectKind::Echo { .. } | EffectKind::MotionBlur { .. }
+    )
+}
+
+/// `kind` with its pixel distances halved, so it looks the same on a
+/// half-size texture.
+pub(crate) fn half_res_kind(kind: &EffectKind) -> EffectKind {
+    let mut kind = kind.clone();
+    match &mut kind {
+        EffectKind::Echo { offset, .. } => *offset *= 0.5,
+        EffectKind::MotionBlur { length, .. } => *length *= 0.5,
+        _ => {}
+    }
+    kind
+}
+
+/// Chains of more passes than this fuse each run of
+/// [pointwise](EffectKind::is_pointwise) effects into one colour-pipeline
+/// pass.  Shorter chains keep a pass per effect, so every effect runs its
+/// own (live-editable) shader.
+pub const FUSE_AFTER: usize = 3;
+
+/// One compute pass of a planned chain, as indices into the effect list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Step {
+    /// An effect through its own shader.
+    Single(usize),
+    /// Two or more consecutive pointwise effects through color_pipeline.wgsl.
+    Fused(Range<usize>),
+}
+
+/// The passes [`EffectPass::dispatch_chain`] records for `effects`: one per
+/// effect, except that past [`FUSE_AFTER`] effects each run of two or more
+/// pointwise ones shares a pass.  Effects past [`Patch::MAX_EFFECTS`] are
+/// dropped.
+pub(crate) fn plan_chain(effects: &[EffectKind]) -> Vec<Step> {
+    let len = effects.len().min(Patch::MAX_EFFECTS);
+    let fuse = len > FUSE_AFTER;
+    let mut steps = Vec::new();
+    let mut i = 0;
+    while i < len {
+        let run = if fuse {
+            effects[i..len]
+                .iter()
+                .take_while(|kind| kind.is_pointwise())
+                .count()
+        } else {
+            0
+        };
+        if run >= 2 {
+            steps.push(Step::Fused(i..i + run));
+            i += run;
+        } else {
+            steps.push(Step::Single(i));
+            i += 1;
+        }
+    }
+    steps
+}
+
+/// One op of the fused pass: its code and the leading params its effect's
+/// own shader reads.  Must match `ColorOp` in color_pipeline.wgsl.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct ColorOp {
+    pub code: u32,
+    pub a: f32,
+    pub b: f32,
+    pub _pad: f32,
+}
+
+impl ColorOp {
+    /// `kind` as an op; `None` unless it is
+    /// [pointwise](EffectKind::is_pointwise).
+    fn new(kind: &EffectKind) -> Option<Self> {
+        let (code, a, b) = match *kind {
+            EffectKind::HueShift { amount } => (0, amount, 0.0),
+            EffectKind::BrightnessContrast {
+                brightness,
+                contrast,
+            } => (1, brightness, contrast),
+            EffectKind::Posterize { levels } => (2, levels, 0.0),
+            EffectKind::Invert { amount } => (3, amount, 0.0),
+            _ => return None,
+        };
+        Some(Self {
+            code,
+            a,
+            b,
+            _pad: 0.0,
+        })
+    }
+}
+
+/// Parameters of one fused colour-pipeline pass, packed: the run's ops in
+/// order, as many as a chain can hold, so a kind may appear any number of
+/// times.  Must match `ColorPipeline` in color_pipeline.wgsl.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct ColorPipelineUniforms {
+    /// Ops in use.
+    pub count: u32,
+    pub _pad: [u32; 3],
+    pub ops: [ColorOp; Patch::MAX_EFFECTS],
+}
+
+impl ColorPipelineUniforms {
+    /// The packed block for a run planned by [`plan_chain`].  Anything
+    /// that isn't pointwise is skipped, as are ops past
+    /// [`Patch::MAX_EFFECTS`].
+    pub fn pack(kinds: &[EffectKind]) -> Self {
+        let mut block = Self::default();
+        for (slot, op) in block
+            .ops
+            .iter_mut()
+            .zip(kinds.iter().filter_map(ColorOp::new))
+        {
+            *slot = op;
+            block.count += 1;
+        }
+        block
+    }
+}
+
+/// Aux texture names for the half-resolution round trip.
+const HALF_IN: &str = "half_res_in";
+const HALF_OUT: &str = "half_res_out";
+
+/// Shape of the half-resolution textures.
+const HALF: AuxDesc = AuxDesc {
+    scale: 2,
+    mip_levels: 1,
+};
+
 // ---------------------------------------------------------------------------
 // EffectPass
 // ---------------------------------------------------------------------------
 
 /// Owns all effect compute pipelines and the GPU resources shared across
-/// every effect dispatch: a uniform buffer, two bind group layouts (with /
-/// without a sampler), and a linear sampler.
+/// every effect dispatch: the bind group layouts, a linear sampler and the
+/// auxiliary textures effects keep between frames.  Buffers are made per
+/// dispatch, so one pass serves both decks' chains.
 pub struct EffectPass {
     pub color_map: ComputePipeline,
     pub ripple: ComputePipeline,
     pub echo: ComputePipeline,
     pub hue_shift: ComputePipeline,
     pub brightness_contrast: ComputePipeline,
+    pub posterize: ComputePipeline,
+    pub invert: ComputePipeline,
     pub motion_blur: ComputePipeline,
-
-    /// BGL for effects that sample via UV warp (ripple, echo):
+    pub audio_overlay: ComputePipeline,
+    /// Runs of pointwise effects in one pass: color_pipeline.wgsl.
+    pub color_pipeline: ComputePipeline,
+    /// Half-resolution round trip: downsample.wgsl and upsample.wgsl.
+    pub downsample: ComputePipeline,
+    pub upsample: ComputePipeline,
+
+    /// BGL for effects that sample via UV warp (ripple, echo, motion_blur):
     ///   binding 0: Uniforms · binding 1: params · binding 2: input ·
     ///   binding 3: output · binding 4: sampler
     bgl_sampler: BindGroupLayout,
     /// BGL for effects that use textureLoad (color_map, hue_shift,
-    /// brightness_contrast, motion_blur):
+    /// brightness_contrast, posterize, invert):
     ///   binding 0: Uniforms · binding 1: params · binding 2: input ·
     ///   binding 3: output
     bgl: BindGroupLayout,
+    /// BGL for effects that draw audio data (audio_overlay):
+    ///   binding 0: Uniforms · binding 1: params · binding 2: input ·
+    ///   binding 3: output · binding 4: samples
+    bgl_samples: BindGroupLayout,
+    pl: PipelineLayout,
+    pl_sampler: PipelineLayout,
+    pl_samples: PipelineLayout,
+    /// BGL for the fused colour pipeline: as `bgl`, but binding 1 is the
+    /// packed [`ColorPipelineUniforms`].
+    bgl_fused: BindGroupLayout,
+    /// BGL for the downsample: binding 0: input · binding 1: half-size
+    /// output · binding 2: sampler
+    bgl_down: BindGroupLayout,
+    /// BGL for the upsample composite: binding 0: full-size input ·
+    /// binding 1: downsampled input · binding 2: half-size effect output ·
+    /// binding 3: output · binding 4: sampler
+    bgl_up: BindGroupLayout,
 
-    /// Shared uniform buffer — same Uniforms data is valid for all effects in a
-    /// frame so a single buffer (written once per chain) is sufficient.
-    uniform_buf: Buffer,
     sampler: Sampler,
+
+    /// Quality trade: run the [`runs_at_half_res`] effects on a half-size
+    /// copy and upsample the result.  Slightly softer, a quarter of the
+    /// fill cost.
+    pub half_res: bool,
+
+    /// Named textures that outlive a frame (previous frame, accumulation,
+    /// bloom mips).  The app closes each frame with `aux.end_frame()`.
+    pub aux: AuxTextures,
 }
 
 impl EffectPass {
@@ -108,28 +310,31 @@ impl EffectPass {
         // --- bind group layouts -----------------------------------------------
         let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("effect_bgl"),
-            entries: &[
-                uniform_entry(0),
-                uniform_entry(1),
-                texture_entry(2),
-                storage_tex_entry(3),
-            ],
+            entries: &EFFECT_ENTRIES,
         });
 
         let bgl_sampler = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("effect_bgl_sampler"),
-            entries: &[
-                uniform_entry(0),
-                uniform_entry(1),
-                texture_entry(2),
-                storage_tex_entry(3),
-                wgpu::BindGroupLayoutEntry {
-                    binding: 4,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
-                },
-            ],
+            entries: &SAMPLER_ENTRIES,
+        });
+
+        let bgl_samples = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("effect_bgl_samples"),
+            entries: &SAMPLES_ENTRIES,
+        });
+
+        let bgl_fused = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("effect_bgl_fused"),
+            entries: &FUSED_ENTRIES,
+        });
+
+        let bgl_down = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("effect_bgl_down"),
+            entries: &DOWN_ENTRIES,
+        });
+        let bgl_up = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("effect_bgl_up"),
+            entries: &UP_ENTRIES,
         });
 
         let pl = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -142,14 +347,28 @@ impl EffectPass {
             bind_group_layouts: &[&bgl_sampler],
             push_constant_ranges: &[],
         });
-
-        // --- shared buffers + sampler -----------------------------------------
-        let uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("effect_uniforms"),
-            size: std::mem::size_of::<Uniforms>() as u64,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
+        let pl_samples = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("effect_pl_samples"),
+            bind_group_layouts: &[&bgl_samples],
+            push_constant_ranges: &[],
+        });
+        let pl_fused = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("effect_pl_fused"),
+            bind_group_layouts: &[&bgl_fused],
+            push_constant_ranges: &[],
+        });
+        let pl_down = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("effect_pl_down"),
+            bind_group_layouts: &[&bgl_down],
+            push_constant_ranges: &[],
         });
+        let pl_up = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("effect_pl_up"),
+            bind_group_layouts: &[&bgl_up],
+            push_constant_ranges: &[],
+        });
+
+        // --- shared sampler ---------------------------------------------------
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("effect_sampler"),
             address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -189,22 +408,49 @@ impl EffectPass {
                 include_str!("../shaders/brightness_contrast.wgsl"),
                 &pl,
             ),
+            posterize: make("posterize", include_str!("../shaders/posterize.wgsl"), &pl),
+            invert: make("invert", include_str!("../shaders/invert.wgsl"), &pl),
             motion_blur: make(
                 "motion_blur",
                 include_str!("../shaders/motion_blur.wgsl"),
-                &pl,
+                &pl_sampler,
+            ),
+            audio_overlay: make(
+                "audio_overlay",
+                include_str!("../shaders/audio_overlay.wgsl"),
+                &pl_samples,
             ),
+            color_pipeline: make(
+                "color_pipeline",
+                include_str!("../shaders/color_pipeline.wgsl"),
+                &pl_fused,
+            ),
+            downsample: make(
+                "downsample",
+                include_str!("../shaders/downsample.wgsl"),
+                &pl_down,
+            ),
+            upsample: make("upsample", include_str!("../shaders/upsample.wgsl"), &pl_up),
             bgl,
             bgl_sampler,
-            uniform_buf,
+            bgl_samples,
+            pl,
+            pl_sampler,
+            pl_samples,
+            bgl_fused,
+            bgl_down,
+            bgl_up,
             sampler,
+            half_res: false,
+            aux: AuxTextures::new(),
         }
     }
 
     /// Record one compute pass with explicit read/write texture views.
     ///
-    /// A fresh per-call params buffer is created so that multiple effects can
-    /// be recorded into a single `CommandEncoder` without the `write_buffer`
+    /// Fresh per-call uniform and params buffers are created so that multiple
+    /// effects — and two decks' chains with different uniforms — can be
+    /// recorded into a single `CommandEncoder` without the `write_buffer`
     /// calls aliasing each other.
     #[allow(clippy::too_many_arguments)]
     fn dispatch_raw(
@@ -219,80 +465,113 @@ impl EffectPass {
         width: u32,
         height: u32,
     ) {
-        // Per-call params buffer: avoids write_buffer aliasing when chaining.
-        let params_buf = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("effect_params"),
-            size: PARAMS_SIZE,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-        queue.write_buffer(&self.uniform_buf, 0, bytemuck::bytes_of(uniforms));
-        queue.write_buffer(&params_buf, 0, &effect_params_bytes(kind));
+        // Per-call buffers: avoid write_buffer aliasing when chaining.
+        let uniform_buf = uniform_buffer(
+            device,
+            queue,
+            "effect_uniforms",
+            bytemuck::bytes_of(uniforms),
+        );
+        let params_buf = uniform_buffer(device, queue, "effect_params", &effect_params_bytes(kind));
 
-        let uses_sampler = matches!(kind, EffectKind::Ripple { .. } | EffectKind::Echo { .. });
+        // Per-call samples buffer, for the same reason as the params buffer.
+        let samples_buf = uses_samples(kind).then(|| {
+            uniform_buffer(
+                device,
+                queue,
+                "effect_samples",
+                &overlay_samples_bytes(kind),
+            )
+        });
 
-        let bind_group = if uses_sampler {
-            device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("effect_bg"),
-                layout: &self.bgl_sampler,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: self.uniform_buf.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: params_buf.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 2,
-                        resource: wgpu::BindingResource::TextureView(read_view),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 3,
-                        resource: wgpu::BindingResource::TextureView(write_view),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 4,
-                        resource: wgpu::BindingResource::Sampler(&self.sampler),
-                    },
-                ],
-            })
+        let mut entries = vec![
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buf.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: params_buf.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(read_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::TextureView(write_view),
+            },
+        ];
+        let layout = if let Some(buf) = &samples_buf {
+            entries.push(wgpu::BindGroupEntry {
+                binding: 4,
+                resource: buf.as_entire_binding(),
+            });
+            &self.bgl_samples
+        } else if uses_sampler(kind) {
+            entries.push(wgpu::BindGroupEntry {
+                binding: 4,
+                resource: wgpu::BindingResource::Sampler(&self.sampler),
+            });
+            &self.bgl_sampler
         } else {
-            device.create_bind_group(&wgpu::BindGroupDescriptor {
+            &self.bgl
+        };
+        let bind_group = stats::create_bind_group(
+            device,
+            &wgpu::BindGroupDescriptor {
                 label: Some("effect_bg"),
-                layout: &self.bgl,
+                layout,
+                entries: &entries,
+            },
+        );
+
+        self.record(encoder, self.pipeline_for(kind), &bind_group, width, height);
+    }
+
+    /// Record one colour-pipeline pass applying every effect in `kinds`, all
+    /// [pointwise](EffectKind::is_pointwise), in order.
+    #[allow(clippy::too_many_arguments)]
+    fn dispatch_fused(
+        &self,
+        device: &Device,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &Queue,
+        kinds: &[EffectKind],
+        uniforms: &Uniforms,
+        read_view: &wgpu::TextureView,
+        write_view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+    ) {
+        let uniform_buf = uniform_buffer(
+            device,
+            queue,
+            "effect_uniforms",
+            bytemuck::bytes_of(uniforms),
+        );
+        let block = ColorPipelineUniforms::pack(kinds);
+        let block_buf = uniform_buffer(device, queue, "color_pipeline", bytemuck::bytes_of(&block));
+        let bind_group = stats::create_bind_group(
+            device,
+            &wgpu::BindGroupDescriptor {
+                label: Some("color_pipeline_bg"),
+                layout: &self.bgl_fused,
                 entries: &[
                     wgpu::BindGroupEntry {
                         binding: 0,
-                        resource: self.uniform_buf.as_entire_binding(),
+                        resource: uniform_buf.as_entire_binding(),
                     },
                     wgpu::BindGroupEntry {
                         binding: 1,
-                        resource: params_buf.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 2,
-                        resource: wgpu::BindingResource::TextureView(read_view),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 3,
-                        resource: wgpu::BindingResource::TextureView(write_view),
+                        resource: block_buf.as_entire_binding(),
                     },
+                    texture_binding(2, read_view),
+                    texture_binding(3, write_view),
                 ],
-            })
-        };
-
-        {
-            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("effect_pass"),
-                timestamp_writes: None,
-            });
-            pass.set_pipeline(self.pipeline_for(kind));
-            pass.set_bind_group(0, &bind_group, &[]);
-            let wg = 8u32;
-            pass.dispatch_workgroups(width.div_ceil(wg), height.div_ceil(wg), 1);
-        }
+            },
+        );
+        self.record(encoder, &self.color_pipeline, &bind_group, width, height);
     }
 
     /// Upload uniforms + per-effect params, record one compute pass into
@@ -331,10 +610,16 @@ impl EffectPass {
     ///
     /// After this call the final composited image lives in `pp.read_view()`.
     /// If `effects` is empty this is a no-op; the caller should present
-    /// `gen_view` directly to the renderer.
+    /// `gen_view` directly to the renderer.  With [`half_res`](Self::half_res)
+    /// on, the [`runs_at_half_res`] effects go through
+    /// [`dispatch_half_res`](Self::dispatch_half_res) instead.
+    ///
+    /// Long chains fuse runs of pointwise effects into one pass, which swaps
+    /// once for the whole run, and stop at [`Patch::MAX_EFFECTS`]; see
+    /// [`plan_chain`].
     #[allow(clippy::too_many_arguments)]
     pub fn dispatch_chain(
-        &self,
+        &mut self,
         device: &Device,
         encoder: &mut wgpu::CommandEncoder,
         queue: &Queue,
@@ -345,10 +630,35 @@ impl EffectPass {
         width: u32,
         height: u32,
     ) {
-        for (i, kind) in effects.iter().enumerate() {
-            // Seed the first effect from the generator output; subsequent
-            // effects read from whatever the previous effect wrote.
-            let read_view: &wgpu::TextureView = if i == 0 { gen_view } else { pp.read_view() };
+        for (n, step) in plan_chain(effects).into_iter().enumerate() {
+            // Seed the first pass from the generator output; subsequent
+            // passes read from whatever the previous one wrote.
+            let read_view: &wgpu::TextureView = if n == 0 { gen_view } else { pp.read_view() };
+            let kind = match step {
+                Step::Single(i) => &effects[i],
+                Step::Fused(run) => {
+                    self.dispatch_fused(
+                        device,
+                        encoder,
+                        queue,
+                        &effects[run],
+                        uniforms,
+                        read_view,
+                        pp.write_view(),
+                        width,
+                        height,
+                    );
+                    pp.swap();
+                    continue;
+                }
+            };
+            if self.half_res && runs_at_half_res(kind) {
+                self.dispatch_half_res(
+                    device, encoder, queue, kind, uniforms, read_view, pp, width, height,
+                );
+                pp.swap();
+                continue;
+            }
             self.dispatch_raw(
                 device,
                 encoder,
@@ -364,6 +674,180 @@ impl EffectPass {
         }
     }
 
+    /// Run `kind` on a half-size copy of `read_view` and composite the
+    /// result into `pp.write_view()`: downsample, effect, upsample.
+    #[allow(clippy::too_many_arguments)]
+    fn dispatch_half_res(
+        &mut self,
+        device: &Device,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &Queue,
+        kind: &EffectKind,
+        uniforms: &Uniforms,
+        read_view: &wgpu::TextureView,
+        pp: &PingPong,
+        width: u32,
+        height: u32,
+    ) {
+        let (half_w, half_h) = HALF.size(width, height);
+        self.aux.acquire(device, HALF_IN, width, height, HALF);
+        self.aux.acquire(device, HALF_OUT, width, height, HALF);
+        let (Some(half_in), Some(half_out)) = (self.aux.get(HALF_IN), self.aux.get(HALF_OUT))
+        else {
+            return;
+        };
+
+        let down = stats::create_bind_group(
+            device,
+            &wgpu::BindGroupDescriptor {
+                label: Some("downsample_bg"),
+                layout: &self.bgl_down,
+                entries: &[
+                    texture_binding(0, read_view),
+                    texture_binding(1, &half_in.view),
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            },
+        );
+        self.record(encoder, &self.downsample, &down, half_w, half_h);
+
+        let half_uniforms = Uniforms {
+            resolution: [half_w as f32, half_h as f32],
+            ..*uniforms
+        };
+        self.dispatch_raw(
+            device,
+            encoder,
+            queue,
+            &half_res_kind(kind),
+            &half_uniforms,
+            &half_in.view,
+            &half_out.view,
+            half_w,
+            half_h,
+        );
+
+        let up = stats::create_bind_group(
+            device,
+            &wgpu::BindGroupDescriptor {
+                label: Some("upsample_bg"),
+                layout: &self.bgl_up,
+                entries: &[
+                    texture_binding(0, read_view),
+                    texture_binding(1, &half_in.view),
+                    texture_binding(2, &half_out.view),
+                    texture_binding(3, pp.write_view()),
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            },
+        );
+        self.record(encoder, &self.upsample, &up, width, height);
+    }
+
+    /// One `width`×`height` compute pass of `pipeline` over `bind_group`.
+    fn record(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &ComputePipeline,
+        bind_group: &wgpu::BindGroup,
+        width: u32,
+        height: u32,
+    ) {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("effect_pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        let wg = 8u32;
+        stats::dispatch_workgroups(&mut pass, width.div_ceil(wg), height.div_ceil(wg), 1);
+    }
+
+    /// Built-in WGSL source for an effect.
+    pub fn default_source(kind: &EffectKind) -> &'static str {
+        match kind {
+            EffectKind::ColorMap { .. } => include_str!("../shaders/color_map.wgsl"),
+            EffectKind::Ripple { .. } => include_str!("../shaders/ripple.wgsl"),
+            EffectKind::Echo { .. } => include_str!("../shaders/echo.wgsl"),
+            EffectKind::HueShift { .. } => include_str!("../shaders/hue_shift.wgsl"),
+            EffectKind::BrightnessContrast { .. } => {
+                include_str!("../shaders/brightness_contrast.wgsl")
+            }
+            EffectKind::Posterize { .. } => include_str!("../shaders/posterize.wgsl"),
+            EffectKind::Invert { .. } => include_str!("../shaders/invert.wgsl"),
+            EffectKind::MotionBlur { .. } => include_str!("../shaders/motion_blur.wgsl"),
+            EffectKind::AudioOverlay { .. } => include_str!("../shaders/audio_overlay.wgsl"),
+        }
+    }
+
+    /// The colour map's source with its palette replaced by `stops`, linear
+    /// RGB spread evenly from escape value 0 to 1.  Compile it with
+    /// [`set_source`](Self::set_source) like any edited colour map.
+    pub fn gradient_source(stops: &[[f32; 3]]) -> String {
+        let src = include_str!("../shaders/color_map.wgsl");
+        let vec = |[r, g, b]: [f32; 3]| format!("vec3({r:?}, {g:?}, {b:?})");
+        let stops_wgsl: Vec<String> = stops.iter().copied().map(vec).collect();
+        src.replacen(
+            "const GRADIENT_LEN: u32 = 0u;",
+            &format!("const GRADIENT_LEN: u32 = {}u;", stops.len()),
+            1,
+        )
+        .replacen(
+            "const GRADIENT = array<vec3<f32>, 1>(vec3(0.0));",
+            &format!(
+                "const GRADIENT = array<vec3<f32>, {}>({});",
+                stops.len().max(1),
+                if stops.is_empty() {
+                    "vec3(0.0)".to_string()
+                } else {
+                    stops_wgsl.join(", ")
+                }
+            ),
+            1,
+        )
+    }
+
+    /// Recompile the pipeline for `kind` from `src`, using the bind group
+    /// layout that effect already expects.  On error the previous pipeline
+    /// stays in place.
+    pub fn set_source(
+        &mut self,
+        device: &Device,
+        kind: &EffectKind,
+        src: &str,
+    ) -> Result<(), ShaderError> {
+        let layout = if uses_samples(kind) {
+            &self.pl_samples
+        } else if uses_sampler(kind) {
+            &self.pl_sampler
+        } else {
+            &self.pl
+        };
+        let pipeline = shader::compile_compute(device, "effect_live", src, layout)?;
+        *self.pipeline_for_mut(kind) = pipeline;
+        Ok(())
+    }
+
+    fn pipeline_for_mut(&mut self, kind: &EffectKind) -> &mut ComputePipeline {
+        match kind {
+            EffectKind::ColorMap { .. } => &mut self.color_map,
+            EffectKind::Ripple { .. } => &mut self.ripple,
+            EffectKind::Echo { .. } => &mut self.echo,
+            EffectKind::HueShift { .. } => &mut self.hue_shift,
+            EffectKind::BrightnessContrast { .. } => &mut self.brightness_contrast,
+            EffectKind::Posterize { .. } => &mut self.posterize,
+            EffectKind::Invert { .. } => &mut self.invert,
+            EffectKind::MotionBlur { .. } => &mut self.motion_blur,
+            EffectKind::AudioOverlay { .. } => &mut self.audio_overlay,
+        }
+    }
+
     fn pipeline_for(&self, kind: &EffectKind) -> &ComputePipeline {
         match kind {
             EffectKind::ColorMap { .. } => &self.color_map,
@@ -371,7 +855,10 @@ impl EffectPass {
             EffectKind::Echo { .. } => &self.echo,
             EffectKind::HueShift { .. } => &self.hue_shift,
             EffectKind::BrightnessContrast { .. } => &self.brightness_contrast,
+            EffectKind::Posterize { .. } => &self.posterize,
+            EffectKind::Invert { .. } => &self.invert,
             EffectKind::MotionBlur { .. } => &self.motion_blur,
+            EffectKind::AudioOverlay { .. } => &self.audio_overlay,
         }
     }
 }
@@ -380,17 +867,17 @@ impl EffectPass {
 // Serialise EffectKind → 16-byte params buffer (matches each WGSL params struct)
 // ---------------------------------------------------------------------------
 
-pub(crate) fn effect_params_bytes(kind: &EffectKind) -> [u8; 16] {
-    let mut buf = [0u8; 16];
+pub(crate) fn effect_params_bytes(kind: &EffectKind) -> [u8; PARAMS_SIZE as usize] {
+    let mut buf = [0u8; PARAMS_SIZE as usize];
     match kind {
-        EffectKind::ColorMap { scheme } => {
-            let v: u32 = match scheme {
-                ColorScheme::Classic => 0,
-                ColorScheme::Fire => 1,
-                ColorScheme::Ocean => 2,
-                ColorScheme::Psychedelic => 3,
-            };
-            buf[..4].copy_from_slice(&v.to_ne_bytes());
+        EffectKind::ColorMap {
+            scheme,
+            orbit_trap,
+            edge_glow,
+        } => {
+            buf[..4].copy_from_slice(&scheme_index(*scheme).to_ne_bytes());
+            buf[4..8].copy_from_slice(&u32::from(*orbit_trap).to_ne_bytes());
+            buf[8..12].copy_from_slice(&u32::from(*edge_glow).to_ne_bytes());
         }
         EffectKind::Ripple {
             frequency,
@@ -420,18 +907,116 @@ pub(crate) fn effect_params_bytes(kind: &EffectKind) -> [u8; 16] {
             buf[0..4].copy_from_slice(&brightness.to_ne_bytes());
             buf[4..8].copy_from_slice(&contrast.to_ne_bytes());
         }
-        EffectKind::MotionBlur { opacity } => {
+        EffectKind::Posterize { levels } => {
+            buf[0..4].copy_from_slice(&levels.to_ne_bytes());
+        }
+        EffectKind::Invert { amount } => {
+            buf[0..4].copy_from_slice(&amount.to_ne_bytes());
+        }
+        EffectKind::MotionBlur {
+            opacity,
+            direction,
+            length,
+        } => {
             buf[0..4].copy_from_slice(&opacity.to_ne_bytes());
+            buf[4..8].copy_from_slice(&direction[0].to_ne_bytes());
+            buf[8..12].copy_from_slice(&direction[1].to_ne_bytes());
+            buf[12..16].copy_from_slice(&length.to_ne_bytes());
+        }
+        EffectKind::AudioOverlay {
+            mode,
+            position,
+            size,
+            scheme,
+            ..
+        } => {
+            let mode: u32 = match mode {
+                OverlayMode::Spectrum => 0,
+                OverlayMode::Waveform => 1,
+            };
+            buf[0..4].copy_from_slice(&mode.to_ne_bytes());
+            buf[4..8].copy_from_slice(&scheme_index(*scheme).to_ne_bytes());
+            buf[8..12].copy_from_slice(&pack_unorm2x16(*position).to_ne_bytes());
+            buf[12..16].copy_from_slice(&pack_unorm2x16(*size).to_ne_bytes());
         }
     }
     buf
 }
 
+/// Palette number as switched on in color_map.wgsl and audio_overlay.wgsl.
+fn scheme_index(scheme: ColorScheme) -> u32 {
+    match scheme {
+        ColorScheme::Classic => 0,
+        ColorScheme::Fire => 1,
+        ColorScheme::Ocean => 2,
+        ColorScheme::Psychedelic => 3,
+    }
+}
+
+/// WGSL `pack2x16unorm`: component 0 in the low 16 bits.
+fn pack_unorm2x16(v: [f32; 2]) -> u32 {
+    let q = |x: f32| (x.clamp(0.0, 1.0) * 65535.0).round() as u32;
+    q(v[0]) | (q(v[1]) << 16)
+}
+
+/// The overlay's samples; zeros for any other effect.
+pub(crate) fn overlay_samples_bytes(kind: &EffectKind) -> Vec<u8> {
+    let values = match kind {
+        EffectKind::AudioOverlay { samples, .. } => samples,
+        _ => &[0.0; OVERLAY_SAMPLES],
+    };
+    bytemuck::cast_slice(values).to_vec()
+}
+
 // ---------------------------------------------------------------------------
-// BGL entry helpers
+// Bind group layouts
 // ---------------------------------------------------------------------------
 
-fn uniform_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+/// Every effect: shared uniforms, effect params, input, output.
+const EFFECT_ENTRIES: [wgpu::BindGroupLayoutEntry; 4] = [
+    uniform_entry(0),
+    uniform_entry(1),
+    texture_entry(2),
+    storage_tex_entry(3),
+];
+
+/// [`uses_sampler`] effects: plus the linear sampler.
+const SAMPLER_ENTRIES: [wgpu::BindGroupLayoutEntry; 5] = [
+    uniform_entry(0),
+    uniform_entry(1),
+    texture_entry(2),
+    storage_tex_entry(3),
+    sampler_entry(4),
+];
+
+/// [`uses_samples`] effects: plus the audio samples.
+const SAMPLES_ENTRIES: [wgpu::BindGroupLayoutEntry; 5] = [
+    uniform_entry(0),
+    uniform_entry(1),
+    texture_entry(2),
+    storage_tex_entry(3),
+    uniform_entry(4),
+];
+
+/// The fused colour pipeline: as [`EFFECT_ENTRIES`], with the packed
+/// [`ColorPipelineUniforms`] at binding 1.
+const FUSED_ENTRIES: [wgpu::BindGroupLayoutEntry; 4] = EFFECT_ENTRIES;
+
+/// Downsample: full-size input, half-size output, sampler.
+const DOWN_ENTRIES: [wgpu::BindGroupLayoutEntry; 3] =
+    [texture_entry(0), storage_tex_entry(1), sampler_entry(2)];
+
+/// Upsample: full-size input, the half-size input and output, full-size
+/// output, sampler.
+const UP_ENTRIES: [wgpu::BindGroupLayoutEntry; 5] = [
+    texture_entry(0),
+    texture_entry(1),
+    texture_entry(2),
+    storage_tex_entry(3),
+    sampler_entry(4),
+];
+
+const fn uniform_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
     wgpu::BindGroupLayoutEntry {
         binding,
         visibility: wgpu::ShaderStages::COMPUTE,
@@ -444,7 +1029,7 @@ fn uniform_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
     }
 }
 
-fn texture_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+const fn texture_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
     wgpu::BindGroupLayoutEntry {
         binding,
         visibility: wgpu::ShaderStages::COMPUTE,
@@ -457,7 +1042,38 @@ fn texture_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
     }
 }
 
-fn storage_tex_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+const fn sampler_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    }
+}
+
+/// A uniform buffer holding `contents`, counted in the frame stats.
+fn uniform_buffer(device: &Device, queue: &Queue, label: &str, contents: &[u8]) -> wgpu::Buffer {
+    let buf = stats::create_buffer(
+        device,
+        &wgpu::BufferDescriptor {
+            label: Some(label),
+            size: contents.len() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+    stats::write_buffer(queue, &buf, 0, contents);
+    buf
+}
+
+fn texture_binding(binding: u32, view: &wgpu::TextureView) -> wgpu::BindGroupEntry<'_> {
+    wgpu::BindGroupEntry {
+        binding,
+        resource: wgpu::BindingResource::TextureView(view),
+    }
+}
+
+const fn storage_tex_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
     wgpu::BindGroupLayoutEntry {
         binding,
         visibility: wgpu::ShaderStages::COMPUTE,
@@ -477,7 +1093,11 @@ fn storage_tex_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use fractal_core::{ColorScheme, EffectKind};
+    use crate::shader::{binding_mismatches, uniform_size};
+    use fractal_core::{ColorScheme, EffectKind, OverlayMode, OVERLAY_SAMPLES};
+
+    /// Size of the samples uniform: `OVERLAY_SAMPLES` f32s.
+    const SAMPLES_SIZE: u64 = (OVERLAY_SAMPLES * 4) as u64;
 
     // --- WGSL validation (CPU-only, no GPU required) -------------------------
 
@@ -498,6 +1118,16 @@ mod tests {
         validate_wgsl("color_map", include_str!("../shaders/color_map.wgsl"));
     }
 
+    #[test]
+    fn gradient_source_is_valid_and_carries_its_stops() {
+        let src =
+            EffectPass::gradient_source(&[[0.0, 0.0, 0.0], [1.0, 0.5, 0.25], [0.0, 0.0, 1.0]]);
+        validate_wgsl("color_map_gradient", &src);
+        assert!(src.contains("const GRADIENT_LEN: u32 = 3u;"));
+        assert!(src.contains("vec3(1.0, 0.5, 0.25)"));
+        validate_wgsl("color_map_no_stops", &EffectPass::gradient_source(&[]));
+    }
+
     #[test]
     fn ripple_wgsl_is_valid() {
         validate_wgsl("ripple", include_str!("../shaders/ripple.wgsl"));
@@ -521,11 +1151,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn posterize_wgsl_is_valid() {
+        validate_wgsl("posterize", include_str!("../shaders/posterize.wgsl"));
+    }
+
+    #[test]
+    fn invert_wgsl_is_valid() {
+        validate_wgsl("invert", include_str!("../shaders/invert.wgsl"));
+    }
+
+    #[test]
+    fn color_pipeline_wgsl_is_valid() {
+        validate_wgsl(
+            "color_pipeline",
+            include_str!("../shaders/color_pipeline.wgsl"),
+        );
+    }
+
     #[test]
     fn motion_blur_wgsl_is_valid() {
         validate_wgsl("motion_blur", include_str!("../shaders/motion_blur.wgsl"));
     }
 
+    #[test]
+    fn downsample_wgsl_is_valid() {
+        validate_wgsl("downsample", include_str!("../shaders/downsample.wgsl"));
+    }
+
+    #[test]
+    fn upsample_wgsl_is_valid() {
+        validate_wgsl("upsample", include_str!("../shaders/upsample.wgsl"));
+    }
+
+    #[test]
+    fn audio_overlay_wgsl_is_valid() {
+        validate_wgsl(
+            "audio_overlay",
+            include_str!("../shaders/audio_overlay.wgsl"),
+        );
+    }
+
     // --- effect_params_bytes --------------------------------------------------
 
     fn f32_at(buf: &[u8; 16], offset: usize) -> f32 {
@@ -539,6 +1205,8 @@ mod tests {
     fn params_bytes_color_map_classic() {
         let buf = effect_params_bytes(&EffectKind::ColorMap {
             scheme: ColorScheme::Classic,
+            orbit_trap: false,
+            edge_glow: false,
         });
         assert_eq!(u32_at(&buf, 0), 0);
     }
@@ -547,6 +1215,8 @@ mod tests {
     fn params_bytes_color_map_fire() {
         let buf = effect_params_bytes(&EffectKind::ColorMap {
             scheme: ColorScheme::Fire,
+            orbit_trap: false,
+            edge_glow: false,
         });
         assert_eq!(u32_at(&buf, 0), 1);
     }
@@ -555,6 +1225,8 @@ mod tests {
     fn params_bytes_color_map_ocean() {
         let buf = effect_params_bytes(&EffectKind::ColorMap {
             scheme: ColorScheme::Ocean,
+            orbit_trap: false,
+            edge_glow: false,
         });
         assert_eq!(u32_at(&buf, 0), 2);
     }
@@ -563,10 +1235,35 @@ mod tests {
     fn params_bytes_color_map_psychedelic() {
         let buf = effect_params_bytes(&EffectKind::ColorMap {
             scheme: ColorScheme::Psychedelic,
+            orbit_trap: false,
+            edge_glow: false,
         });
         assert_eq!(u32_at(&buf, 0), 3);
     }
 
+    #[test]
+    fn params_bytes_color_map_orbit_trap() {
+        let buf = effect_params_bytes(&EffectKind::ColorMap {
+            scheme: ColorScheme::Ocean,
+            orbit_trap: true,
+            edge_glow: false,
+        });
+        assert_eq!((u32_at(&buf, 0), u32_at(&buf, 4)), (2, 1));
+    }
+
+    #[test]
+    fn params_bytes_color_map_edge_glow() {
+        let buf = effect_params_bytes(&EffectKind::ColorMap {
+            scheme: ColorScheme::Fire,
+            orbit_trap: false,
+            edge_glow: true,
+        });
+        assert_eq!(
+            (u32_at(&buf, 0), u32_at(&buf, 4), u32_at(&buf, 8)),
+            (1, 0, 1)
+        );
+    }
+
     #[test]
     fn params_bytes_ripple() {
         let buf = effect_params_bytes(&EffectKind::Ripple {
@@ -610,18 +1307,73 @@ mod tests {
         assert_eq!(&buf[8..16], &[0u8; 8]);
     }
 
+    #[test]
+    fn params_bytes_posterize() {
+        let buf = effect_params_bytes(&EffectKind::Posterize { levels: 6.0 });
+        assert_eq!(f32_at(&buf, 0), 6.0);
+        assert_eq!(&buf[4..16], &[0u8; 12]);
+    }
+
+    #[test]
+    fn params_bytes_invert() {
+        let buf = effect_params_bytes(&EffectKind::Invert { amount: 0.75 });
+        assert_eq!(f32_at(&buf, 0), 0.75);
+        assert_eq!(&buf[4..16], &[0u8; 12]);
+    }
+
     #[test]
     fn params_bytes_motion_blur() {
-        let buf = effect_params_bytes(&EffectKind::MotionBlur { opacity: 0.85 });
+        let buf = effect_params_bytes(&EffectKind::MotionBlur {
+            opacity: 0.85,
+            direction: [0.6, -0.8],
+            length: 24.0,
+        });
         assert!((f32_at(&buf, 0) - 0.85).abs() < 1e-6);
-        assert_eq!(&buf[4..16], &[0u8; 12]);
+        assert!((f32_at(&buf, 4) - 0.6).abs() < 1e-6);
+        assert!((f32_at(&buf, 8) - (-0.8)).abs() < 1e-6);
+        assert!((f32_at(&buf, 12) - 24.0).abs() < 1e-6);
+    }
+
+    fn overlay(samples: [f32; OVERLAY_SAMPLES]) -> EffectKind {
+        EffectKind::AudioOverlay {
+            mode: OverlayMode::Waveform,
+            position: [0.0, 0.75],
+            size: [1.0, 0.25],
+            scheme: ColorScheme::Ocean,
+            samples,
+        }
     }
 
     #[test]
-    fn params_bytes_always_16_bytes() {
-        let kinds = [
+    fn params_bytes_audio_overlay() {
+        let buf = effect_params_bytes(&overlay([0.0; OVERLAY_SAMPLES]));
+        assert_eq!(u32_at(&buf, 0), 1);
+        assert_eq!(u32_at(&buf, 4), 2);
+        // pack2x16unorm: x in the low half, y in the high half.
+        assert_eq!(u32_at(&buf, 8), 49151 << 16);
+        assert_eq!(u32_at(&buf, 12), (16384 << 16) | 65535);
+    }
+
+    #[test]
+    fn overlay_samples_fill_the_fixed_size_buffer() {
+        let mut samples = [0.0; OVERLAY_SAMPLES];
+        samples[..3].fill(0.5);
+        let bytes = overlay_samples_bytes(&overlay(samples));
+        assert_eq!(bytes.len() as u64, SAMPLES_SIZE);
+        let values: &[f32] = bytemuck::cast_slice(&bytes);
+        assert_eq!(&values[..4], &[0.5, 0.5, 0.5, 0.0]);
+
+        let bytes = overlay_samples_bytes(&EffectKind::Invert { amount: 1.0 });
+        assert_eq!(bytes, [0; SAMPLES_SIZE as usize]);
+    }
+
+    /// One of each effect.
+    fn every_kind() -> [EffectKind; 9] {
+        [
             EffectKind::ColorMap {
                 scheme: ColorScheme::Classic,
+                orbit_trap: false,
+                edge_glow: false,
             },
             EffectKind::Ripple {
                 frequency: 1.0,
@@ -638,20 +1390,224 @@ mod tests {
                 brightness: 0.0,
                 contrast: 1.0,
             },
-            EffectKind::MotionBlur { opacity: 1.0 },
-        ];
-        for kind in &kinds {
+            EffectKind::Posterize { levels: 4.0 },
+            EffectKind::Invert { amount: 1.0 },
+            EffectKind::MotionBlur {
+                opacity: 1.0,
+                direction: [1.0, 0.0],
+                length: 0.0,
+            },
+            overlay([0.0; OVERLAY_SAMPLES]),
+        ]
+    }
+
+    #[test]
+    fn params_bytes_always_16_bytes() {
+        for kind in &every_kind() {
             assert_eq!(effect_params_bytes(kind).len(), 16);
         }
     }
 
+    // --- Binding contract -------------------------------------------------------
+
+    #[test]
+    fn effect_shaders_match_the_layout_they_are_bound_with() {
+        for kind in &every_kind() {
+            let entries: &[wgpu::BindGroupLayoutEntry] = if uses_samples(kind) {
+                &SAMPLES_ENTRIES
+            } else if uses_sampler(kind) {
+                &SAMPLER_ENTRIES
+            } else {
+                &EFFECT_ENTRIES
+            };
+            let src = EffectPass::default_source(kind);
+            let problems = binding_mismatches(src, entries);
+            assert!(problems.is_empty(), "{kind:?}:\n{}", problems.join("\n"));
+            assert_eq!(uniform_size(src, 1), Some(16), "{kind:?} params block");
+            if uses_samples(kind) {
+                assert_eq!(uniform_size(src, 4), Some(SAMPLES_SIZE as u32));
+            }
+        }
+    }
+
+    #[test]
+    fn color_pipeline_matches_its_layout_and_packed_block() {
+        let src = include_str!("../shaders/color_pipeline.wgsl");
+        let problems = binding_mismatches(src, &FUSED_ENTRIES);
+        assert!(problems.is_empty(), "{}", problems.join("\n"));
+        let size = std::mem::size_of::<ColorPipelineUniforms>();
+        assert_eq!(size, 16 + 16 * Patch::MAX_EFFECTS);
+        assert_eq!(uniform_size(src, 1), Some(size as u32));
+    }
+
+    #[test]
+    fn half_res_shaders_match_their_layouts() {
+        for (label, src, entries) in [
+            (
+                "downsample",
+                include_str!("../shaders/downsample.wgsl"),
+                &DOWN_ENTRIES[..],
+            ),
+            (
+                "upsample",
+                include_str!("../shaders/upsample.wgsl"),
+                &UP_ENTRIES[..],
+            ),
+        ] {
+            let problems = binding_mismatches(src, entries);
+            assert!(problems.is_empty(), "{label}:\n{}", problems.join("\n"));
+        }
+    }
+
+    // --- Chain planning -----------------------------------------------------------
+
+    fn hue() -> EffectKind {
+        EffectKind::HueShift { amount: 0.5 }
+    }
+
+    fn ripple() -> EffectKind {
+        EffectKind::Ripple {
+            frequency: 1.0,
+            amplitude: 1.0,
+            speed: 1.0,
+        }
+    }
+
+    #[test]
+    fn short_chains_keep_a_pass_per_effect() {
+        let effects = vec![hue(), hue(), hue()];
+        assert_eq!(
+            plan_chain(&effects),
+            vec![Step::Single(0), Step::Single(1), Step::Single(2)]
+        );
+        assert!(plan_chain(&[]).is_empty());
+    }
+
+    fn posterize() -> EffectKind {
+        EffectKind::Posterize { levels: 4.0 }
+    }
+
+    fn invert() -> EffectKind {
+        EffectKind::Invert { amount: 1.0 }
+    }
+
+    #[test]
+    fn long_chains_fuse_each_pointwise_run() {
+        let effects = vec![
+            every_kind()[0].clone(),
+            hue(),
+            EffectKind::Posterize { levels: 4.0 },
+            ripple(),
+            EffectKind::Invert { amount: 1.0 },
+            ripple(),
+            hue(),
+            hue(),
+        ];
+        assert_eq!(
+            plan_chain(&effects),
+            vec![
+                Step::Single(0),
+                Step::Fused(1..3),
+                Step::Single(3),
+                Step::Single(4),
+                Step::Single(5),
+                Step::Fused(6..8),
+            ]
+        );
+    }
+
+    #[test]
+    fn plans_stop_at_the_maximum_chain_length() {
+        let effects = vec![ripple(); Patch::MAX_EFFECTS + 4];
+        let steps = plan_chain(&effects);
+        assert_eq!(steps.len(), Patch::MAX_EFFECTS);
+        assert_eq!(steps.last(), Some(&Step::Single(Patch::MAX_EFFECTS - 1)));
+        let effects = vec![hue(); Patch::MAX_EFFECTS + 4];
+        assert_eq!(
+            plan_chain(&effects),
+            vec![Step::Fused(0..Patch::MAX_EFFECTS)]
+        );
+    }
+
+    #[test]
+    fn packing_keeps_each_ops_own_params_in_order() {
+        let block = ColorPipelineUniforms::pack(&[
+            invert(),
+            EffectKind::BrightnessContrast {
+                brightness: 0.2,
+                contrast: 1.5,
+            },
+            ripple(),
+            posterize(),
+            EffectKind::Invert { amount: 0.75 },
+        ]);
+        assert_eq!(block.count, 4, "the ripple is not an op");
+        let ops: Vec<_> = block.ops[..4]
+            .iter()
+            .map(|op| (op.code, op.a, op.b))
+            .collect();
+        assert_eq!(
+            ops,
+            vec![(3, 1.0, 0.0), (1, 0.2, 1.5), (2, 4.0, 0.0), (3, 0.75, 0.0)]
+        );
+        assert!(block.ops[4..].iter().all(|op| *op == ColorOp::default()));
+        let full = ColorPipelineUniforms::pack(&vec![hue(); Patch::MAX_EFFECTS + 4]);
+        assert_eq!(full.count as usize, Patch::MAX_EFFECTS);
+    }
+
+    // --- Half resolution --------------------------------------------------------
+
+    #[test]
+    fn only_the_blurs_run_at_half_res() {
+        assert!(runs_at_half_res(&EffectKind::MotionBlur {
+            opacity: 1.0,
+            direction: [1.0, 0.0],
+            length: 8.0,
+        }));
+        assert!(runs_at_half_res(&EffectKind::Echo {
+            layers: 4,
+            offset: 2.0,
+            decay: 0.5,
+        }));
+        assert!(!runs_at_half_res(&EffectKind::HueShift { amount: 0.5 }));
+    }
+
+    #[test]
+    fn half_res_kind_halves_pixel_distances() {
+        let blur = EffectKind::MotionBlur {
+            opacity: 1.0,
+            direction: [1.0, 0.0],
+            length: 8.0,
+        };
+        assert!(matches!(
+            half_res_kind(&blur),
+            EffectKind::MotionBlur { length, .. } if length == 4.0
+        ));
+        let echo = EffectKind::Echo {
+            layers: 4,
+            offset: 3.0,
+            decay: 0.5,
+        };
+        assert!(matches!(
+            half_res_kind(&echo),
+            EffectKind::Echo { layers: 4, offset, .. } if offset == 1.5
+        ));
+        let hue = EffectKind::HueShift { amount: 0.5 };
+        assert!(matches!(
+            half_res_kind(&hue),
+            EffectKind::HueShift { amount } if amount == 0.5
+        ));
+    }
+
     // --- Uniforms layout ------------------------------------------------------
 
     #[test]
-    fn uniforms_size_is_48_bytes() {
-        // Uniforms must be 48 bytes to satisfy wgpu's min uniform buffer alignment
-        // and match the WGSL struct: 2+2+1+1+1+1 f32/u32 + 2+2 padding f32 = 12 × 4
-        assert_eq!(std::mem::size_of::<crate::context::Uniforms>(), 48);
+    fn uniforms_size_is_96_bytes() {
+        // Uniforms must be a multiple of 16 bytes and match the WGSL struct:
+        // 2+2 resolution/center + 1+1+1+1 zoom/time/max_iter/projection = 8 × 4,
+        // smoothing, samples and their padding (4 × 4), then the wide view only the deep
+        // kernels declare: the df64 centre (4 × 4) and the f64 view (2+1+1 × 8).
+        assert_eq!(std::mem::size_of::<crate::context::Uniforms>(), 96);
     }
 
     // --- dispatch_chain CPU-side logic ----------------------------------------
@@ -712,21 +1668,16 @@ mod tests {
     fn dispatch_chain_swaps_once_per_effect() {
         pollster::block_on(async {
             let ctx = crate::context::GpuContext::new_headless().await;
-            let pass = EffectPass::new(&ctx.device);
+            let mut pass = EffectPass::new(&ctx.device);
             let mut pp = PingPong::new(&ctx.device, 64, 64);
             // Use the generator output texture as the seed view.
             let gen_pass = crate::generator_pipeline::GeneratorPass::new(&ctx.device, 64, 64);
 
-            let uniforms = crate::context::Uniforms {
-                resolution: [64.0, 64.0],
-                center: [0.0, 0.0],
-                zoom: 1.0,
-                time: 0.0,
+            let params = fractal_core::Params {
                 max_iter: 16,
-                _pad: 0,
-                julia_c: [0.0, 0.0],
-                _pad2: [0.0, 0.0],
+                ..Default::default()
             };
+            let uniforms = crate::context::Uniforms::from_params(&params, 64, 64);
 
             let effects = vec![
                 EffectKind::HueShift { amount: 0.5 },
@@ -734,6 +1685,7 @@ mod tests {
                     brightness: 0.1,
                     contrast: 1.2,
                 },
+                overlay([0.5; OVERLAY_SAMPLES]),
             ];
 
             let mut encoder = ctx
@@ -743,7 +1695,7 @@ mod tests {
                 });
 
             // pp.current starts as false (A=read, B=write).
-            // After 2 effects: 2 swaps → current = false again.
+            // After 3 effects: 3 swaps → current = true.
             pass.dispatch_chain(
                 &ctx.device,
                 &mut encoder,
@@ -756,10 +1708,88 @@ mod tests {
                 64,
             );
 
-            // 2 effects → 2 swaps → current toggles back to false
-            assert!(!pp.current, "even number of effects leaves current=false");
+            // 3 effects → 3 swaps → current ends up true
+            assert!(pp.current, "odd number of effects leaves current=true");
 
             ctx.queue.submit(std::iter::once(encoder.finish()));
         });
     }
+
+    /// A fused run takes one ping-pong step for all its effects.
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn fused_runs_swap_once() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut pass = EffectPass::new(&ctx.device);
+            let mut pp = PingPong::new(&ctx.device, 64, 64);
+            let gen_pass = crate::generator_pipeline::GeneratorPass::new(&ctx.device, 64, 64);
+            let uniforms = crate::context::Uniforms::from_params(&Default::default(), 64, 64);
+            // ColorMap, then one fused pass for the other four.
+            let effects = vec![
+                every_kind()[0].clone(),
+                hue(),
+                EffectKind::Posterize { levels: 4.0 },
+                EffectKind::BrightnessContrast {
+                    brightness: 0.1,
+                    contrast: 1.2,
+                },
+                EffectKind::Invert { amount: 1.0 },
+            ];
+
+            let mut encoder = ctx.device.create_command_encoder(&Default::default());
+            pass.dispatch_chain(
+                &ctx.device,
+                &mut encoder,
+                &ctx.queue,
+                &effects,
+                &uniforms,
+                &gen_pass.output_view,
+                &mut pp,
+                64,
+                64,
+            );
+            ctx.queue.submit(std::iter::once(encoder.finish()));
+
+            assert!(!pp.current, "two passes leave current=false");
+        });
+    }
+
+    /// A half-resolution blur still takes one ping-pong step, through two
+    /// half-size aux textures.
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn half_res_chain_round_trips_through_aux_textures() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut pass = EffectPass::new(&ctx.device);
+            pass.half_res = true;
+            let mut pp = PingPong::new(&ctx.device, 64, 48);
+            let gen_pass = crate::generator_pipeline::GeneratorPass::new(&ctx.device, 64, 48);
+            let uniforms = crate::context::Uniforms::from_params(&Default::default(), 64, 48);
+            let effects = vec![EffectKind::MotionBlur {
+                opacity: 1.0,
+                direction: [1.0, 0.0],
+                length: 8.0,
+            }];
+
+            let mut encoder = ctx.device.create_command_encoder(&Default::default());
+            pass.dispatch_chain(
+                &ctx.device,
+                &mut encoder,
+                &ctx.queue,
+                &effects,
+                &uniforms,
+                &gen_pass.output_view,
+                &mut pp,
+                64,
+                48,
+            );
+            ctx.queue.submit(std::iter::once(encoder.finish()));
+
+            assert!(pp.current);
+            let half = pass.aux.get(HALF_OUT).expect("half-size output");
+            assert_eq!((half.width, half.height), (32, 24));
+        });
+    }
 }
@@ -0,0 +1,243 @@
+use fractal_core::{patch::Patch, projection::Projection};
+use wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
+
+use crate::{
+    backend::{FrameBackend, WgpuFrame},
+    context::{GeneratorUniforms, Uniforms},
+    effect_pipeline::{EffectPass, PingPong},
+    generator_pipeline::GeneratorPass,
+    renderer::OutputPass,
+};
+
+// ---------------------------------------------------------------------------
+// FractalRenderer — the fractal pipeline as a texture source for other apps
+// ---------------------------------------------------------------------------
+//
+// Everything the windowed app does for one deck, minus the window: the
+// caller owns the device, ticks its `Patch` and hands over any texture view
+// of the format the renderer was built for.  A game can render a patch into
+// a material texture each frame; an engine can sample it like any other
+// render target.
+
+/// Renders patches into caller-owned textures.  Holds the generator, effect
+/// chain and output passes at a fixed internal resolution; the output pass
+/// scales to whatever target it is given.
+pub struct FractalRenderer {
+    gen_pass: GeneratorPass,
+    effect_pass: EffectPass,
+    pp: PingPong,
+    output: OutputPass,
+    width: u32,
+    height: u32,
+    projection: Projection,
+}
+
+impl FractalRenderer {
+    /// A renderer computing `width`×`height` frames and drawing into views
+    /// of `target_format`.
+    pub fn new(device: &Device, width: u32, height: u32, target_format: TextureFormat) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        Self {
+            gen_pass: GeneratorPass::new(device, width, height),
+            effect_pass: EffectPass::new(device),
+            pp: PingPong::new(device, width, height),
+            output: OutputPass::new(device, target_format),
+            width,
+            height,
+            projection: Projection::Flat,
+        }
+    }
+
+    /// Mask the output to `projection`, e.g. a dome's circle.
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Internal resolution in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Change the internal resolution; a no-op when it is unchanged.
+    pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
+        let (width, height) = (width.max(1), height.max(1));
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+        self.gen_pass = GeneratorPass::new(device, width, height);
+        self.pp = PingPong::new(device, width, height);
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Render `patch` as it stands into `target` and submit.  Tick the patch
+    /// beforehand to animate it and to keep deep zooms' reference orbits
+    /// current.
+    pub fn render(&mut self, device: &Device, queue: &Queue, patch: &Patch, target: &TextureView) {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("fractal-renderer-encoder"),
+        });
+        self.encode(device, queue, &mut encoder, patch, target);
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Like [`render`](Self::render), but record into the caller's
+    /// `encoder`, for callers that batch their own submissions.
+    pub fn encode(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        patch: &Patch,
+        target: &TextureView,
+    ) {
+        if let Some(formula) = patch.generator.formula() {
+            if let Err(e) = self.gen_pass.set_formula(device, formula) {
+                log::warn!("formula {:?} failed to compile: {e}", formula.source());
+            }
+        }
+        let uniforms = patch_uniforms(patch, self.width, self.height, self.projection);
+        let mut frame = WgpuFrame::new(
+            device,
+            queue,
+            encoder,
+            &self.gen_pass,
+            &mut self.effect_pass,
+            &mut self.pp,
+            &self.output,
+            target,
+        );
+        encode_patch(&mut frame, patch, &uniforms, self.projection);
+    }
+}
+
+/// The shared uniforms for rendering `patch` at `width`×`height`.
+fn patch_uniforms(patch: &Patch, width: u32, height: u32, projection: Projection) -> Uniforms {
+    Uniforms::from_params(&patch.params, width, height)
+        .with_projection(projection)
+        .with_deep(patch.deep.as_ref())
+}
+
+/// Issue `patch`'s generator and effect chain through `gpu`, then present
+/// through `projection`.
+fn encode_patch(
+    gpu: &mut impl FrameBackend,
+    patch: &Patch,
+    uniforms: &Uniforms,
+    projection: Projection,
+) {
+    if let Some(orbit) = patch.deep.as_ref().and_then(|deep| deep.orbit.as_ref()) {
+        gpu.upload_orbit(orbit);
+    }
+    let extension = GeneratorUniforms::for_patch(patch, uniforms);
+    gpu.dispatch_generator(patch.generator.kind(), uniforms, &extension);
+    let effects: Vec<_> = patch
+        .effects
+        .iter()
+        .map(|e| e.kind(&patch.params))
+        .collect();
+    gpu.dispatch_chain(&effects, uniforms);
+    gpu.present(projection);
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{GpuCall, MockBackend};
+    use fractal_core::perturbation::DeepZoom;
+    use fractal_core::presets::Preset;
+    use fractal_core::GeneratorKind;
+
+    fn frame(patch: &Patch) -> MockBackend {
+        let mut gpu = MockBackend::new();
+        let uniforms = patch_uniforms(patch, 64, 48, Projection::Flat);
+        encode_patch(&mut gpu, patch, &uniforms, Projection::Flat);
+        gpu
+    }
+
+    // --- encode_patch ---
+
+    #[test]
+    fn renders_generator_then_chain_then_presents() {
+        let patch = Preset::ALL[0].build();
+        let gpu = frame(&patch);
+        assert_eq!(gpu.calls.len(), 3, "{:?}", gpu.calls);
+        let GpuCall::Generator { kind, uniforms, .. } = &gpu.calls[0] else {
+            panic!("expected the generator dispatch, got {:?}", gpu.calls[0]);
+        };
+        assert_eq!(*kind, patch.generator.kind());
+        assert_eq!(uniforms.resolution, [64.0, 48.0]);
+        let GpuCall::Chain(effects) = &gpu.calls[1] else {
+            panic!("expected the effect chain, got {:?}", gpu.calls[1]);
+        };
+        assert_eq!(effects.len(), patch.effects.len());
+        assert!(matches!(gpu.calls[2], GpuCall::Present(_)));
+    }
+
+    #[test]
+    fn deep_patches_upload_their_orbit_first() {
+        let mut patch = Patch::new(
+            Box::new(fractal_core::MandelbrotGen),
+            fractal_core::Params {
+                zoom: DeepZoom::THRESHOLD * 1e6,
+                ..Default::default()
+            },
+        );
+        patch.tick(0.016);
+        let generation = patch
+            .deep
+            .as_ref()
+            .unwrap()
+            .orbit
+            .as_ref()
+            .unwrap()
+            .generation;
+        let gpu = frame(&patch);
+        assert!(matches!(gpu.calls[0], GpuCall::Orbit(g) if g == generation));
+        assert!(matches!(
+            gpu.calls[1],
+            GpuCall::Generator {
+                kind: GeneratorKind::Mandelbrot,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn renders_into_a_caller_texture() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let format = TextureFormat::Rgba8UnormSrgb;
+            let target = ctx.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("embed_target"),
+                size: wgpu::Extent3d {
+                    width: 32,
+                    height: 32,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            let view = target.create_view(&Default::default());
+            let mut renderer = FractalRenderer::new(&ctx.device, 32, 32, format);
+            for preset in Preset::ALL {
+                let mut patch = preset.build();
+                patch.tick(0.016);
+                renderer.render(&ctx.device, &ctx.queue, &patch, &view);
+            }
+            renderer.resize(&ctx.device, 16, 8);
+            assert_eq!(renderer.size(), (16, 8));
+            ctx.device.poll(wgpu::Maintain::Wait);
+        });
+    }
+}
@@ -1,19 +1,118 @@
-use fractal_core::GeneratorKind;
-use wgpu::{BindGroupLayout, Buffer, ComputePipeline, Device, Queue, Texture, TextureView};
+use std::cell::{Cell, RefCell};
 
-use crate::context::Uniforms;
+use fractal_core::{
+    formula::Formula,
+    perturbation::{DeepZoom, ReferenceOrbit},
+    GeneratorKind,
+};
+use wgpu::{
+    BindGroup, BindGroupLayout, Buffer, ComputePipeline, Device, Features, PipelineLayout, Queue,
+    Sampler, Texture, TextureView,
+};
+
+use crate::{
+    context::{GeneratorUniforms, Uniforms},
+    pan::{self, LastRender, PanPlan, Strip},
+    shader::{self, ShaderError},
+    stats,
+};
+
+/// The shader files concatenated after view.wgsl (`plane_uv`,
+/// `sample_offset`), for the kernels that map pixels onto the plane.
+macro_rules! with_view {
+    ($($file:literal),+) => {
+        concat!(include_str!("../shaders/view.wgsl"), $(include_str!($file)),+)
+    };
+}
 
 /// Holds one compute pipeline per generator variant plus the GPU resources
-/// shared across all of them: a uniform buffer, a bind group layout, and the
-/// output texture that every pipeline writes into.
+/// shared across all of them: the shared and extension uniform buffers, a
+/// bind group layout, the source texture external frames are streamed into,
+/// and the output texture that every pipeline writes into.
 pub struct GeneratorPass {
     pub mandelbrot: ComputePipeline,
     pub julia: ComputePipeline,
     pub burning_ship: ComputePipeline,
+    /// Celtic and Buffalo run burning_ship.wgsl too, told apart by the
+    /// `fold` in their extension block.
+    pub celtic: ComputePipeline,
+    pub buffalo: ComputePipeline,
+    pub multibrot: ComputePipeline,
+    /// One pipeline for every schedule; the schedule rides in the
+    /// extension block.
+    pub hybrid: ComputePipeline,
+    pub kleinian: ComputePipeline,
+    /// The custom formula template with the patch's formula spliced in;
+    /// rebuilt by [`Self::set_formula`].
+    pub formula: ComputePipeline,
     pub noise_field: ComputePipeline,
+    pub simplex_field: ComputePipeline,
+    pub worley_field: ComputePipeline,
+    pub domain_warp: ComputePipeline,
+    pub plasma: ComputePipeline,
+    /// Colours the reaction–diffusion state; its `seed` and `simulate` entry
+    /// points run first as `reaction_seed` and `reaction_step`.
+    pub reaction: ComputePipeline,
+    pub reaction_seed: ComputePipeline,
+    pub reaction_step: ComputePipeline,
+    pub video: ComputePipeline,
+    pub image: ComputePipeline,
+    pub test_pattern: ComputePipeline,
+    /// Per-pixel resolve of the inverse-iteration Julia; its `scatter`
+    /// entry point runs first as `inverse_julia_scatter`.
+    pub inverse_julia: ComputePipeline,
+    pub inverse_julia_scatter: ComputePipeline,
+    /// Per-pixel resolve of the iterated function system; its walkers run
+    /// first as `ifs_scatter`, like the inverse-iteration Julia's.
+    pub ifs: ComputePipeline,
+    pub ifs_scatter: ComputePipeline,
+    /// Tone-mapping resolve of the fractal flame; its walkers run first as
+    /// `flame_scatter`.
+    pub flame: ComputePipeline,
+    pub flame_scatter: ComputePipeline,
+    /// Deep Mandelbrot and Julia views, perturbed from the reference orbit
+    /// in `orbit_buf`; chosen by the extension block rather than the kind.
+    pub perturbation: ComputePipeline,
+    /// f64 kernels of the ship family and the hybrid, built only when the
+    /// device has `SHADER_F64` (see [`Self::precise_for`]).
+    pub burning_ship_f64: Option<ComputePipeline>,
+    pub hybrid_f64: Option<ComputePipeline>,
+    /// df64 kernels, for deep views on any device.
+    pub mandelbrot_df64: ComputePipeline,
+    pub julia_df64: ComputePipeline,
+    pub burning_ship_df64: ComputePipeline,
 
     bind_group_layout: BindGroupLayout,
+    pipeline_layout: PipelineLayout,
     uniform_buf: Buffer,
+    /// Generator-specific block (`GeneratorUniforms`), binding 2.
+    extension_buf: Buffer,
+    /// Latest external frame or still image (sRGB), binding 3, sampled through binding 4.
+    source_tex: Texture,
+    source_view: TextureView,
+    source_sampler: Sampler,
+    /// Per-pixel hit counts of the point-cloud generators, binding 5.  The
+    /// flame interleaves a colour sum with each count, so it holds two
+    /// `u32`s per pixel.
+    density_buf: Buffer,
+    /// Reference orbit of a deep view, binding 6.
+    orbit_buf: Buffer,
+    /// `generation` of the orbit last uploaded to `orbit_buf`.
+    orbit_generation: Cell<Option<u64>>,
+    /// Chemical state of the reaction–diffusion, bindings 7 and 8.
+    state: StatePingPong,
+    /// Source last handed to `set_formula`, compiled or not, so a formula
+    /// is compiled once rather than every frame.
+    formula_source: String,
+    /// What the output holds, so a pan can shift it and render only the
+    /// strips it uncovered (see [`crate::pan`]).
+    last_render: RefCell<Option<LastRender>>,
+    /// The shifted output passes through here, as a texture can't be
+    /// copied onto itself.
+    scratch_tex: Texture,
+    /// Uniforms of each strip a pan uncovered, bound at 0 in place of
+    /// `uniform_buf` since every strip needs its own `origin`.
+    strip_bufs: [Buffer; 2],
 
     /// rgba16float texture written by the active generator each frame.
     pub output_tex: Texture,
@@ -22,35 +121,112 @@ pub struct GeneratorPass {
     pub height: u32,
 }
 
+/// The bind group layout every generator pipeline shares, by binding:
+///
+/// - 0: Uniforms uniform buffer
+/// - 1: rgba16float storage texture (write-only)
+/// - 2: generator-specific uniform buffer (unused by some)
+/// - 3: external source frame (read by video and image)
+/// - 4: linear sampler for the source frame
+/// - 5: per-pixel hit counts (read by inverse_julia, ifs and flame)
+/// - 6: reference orbit (read by perturbation)
+/// - 7: simulation state, previous step (read by reaction)
+/// - 8: simulation state, next step (written by reaction)
+const LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 9] = [
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 1,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::StorageTexture {
+            access: wgpu::StorageTextureAccess::WriteOnly,
+            format: wgpu::TextureFormat::Rgba16Float,
+            view_dimension: wgpu::TextureViewDimension::D2,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 2,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 3,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 4,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 5,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 6,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 7,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    wgpu::BindGroupLayoutEntry {
+        binding: 8,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::StorageTexture {
+            access: wgpu::StorageTextureAccess::WriteOnly,
+            format: STATE_FORMAT,
+            view_dimension: wgpu::TextureViewDimension::D2,
+        },
+        count: None,
+    },
+];
+
 impl GeneratorPass {
     pub fn new(device: &Device, width: u32, height: u32) -> Self {
         // --- bind group layout -------------------------------------------------
-        // binding 0 : Uniforms uniform buffer
-        // binding 1 : rgba16float storage texture (write-only)
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("gen_bgl"),
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::StorageTexture {
-                        access: wgpu::StorageTextureAccess::WriteOnly,
-                        format: wgpu::TextureFormat::Rgba16Float,
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                    },
-                    count: None,
-                },
-            ],
+            entries: &LAYOUT_ENTRIES,
         });
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -67,6 +243,13 @@ impl GeneratorPass {
             mapped_at_creation: false,
         });
 
+        let extension_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gen_extension_uniforms"),
+            size: GeneratorUniforms::MAX_SIZE as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         // --- output texture ----------------------------------------------------
         let output_tex = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("gen_output"),
@@ -79,13 +262,66 @@ impl GeneratorPass {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba16Float,
-            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+            usage: wgpu::TextureUsages::STORAGE_BINDING
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
         let output_view = output_tex.create_view(&Default::default());
 
+        // --- pan scratch and strip uniforms ------------------------------------
+        let scratch_tex = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("gen_pan_scratch"),
+            size: output_tex.size(),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let strip_buf = || {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("gen_strip_uniforms"),
+                size: std::mem::size_of::<Uniforms>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        };
+        let strip_bufs = [strip_buf(), strip_buf()];
+
+        // --- source frame (1×1 black until the app streams one in) -------------
+        let (source_tex, source_view) = source_texture(device, "gen_source", 1, 1);
+        let source_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("gen_source_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        // --- hit counts (two u32s per pixel, capped at the binding limit) ------
+        let density_size = (u64::from(width) * u64::from(height) * 8).clamp(
+            4,
+            u64::from(device.limits().max_storage_buffer_binding_size),
+        );
+        let density_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gen_density"),
+            size: density_size,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        // --- reference orbit (one vec2<f32> per point) -----------------------
+        let orbit_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gen_orbit"),
+            size: (ReferenceOrbit::MAX_POINTS * 8) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         // --- pipelines --------------------------------------------------------
-        let make = |label: &str, src: &str| {
+        let make_entry = |label: &str, src: &str, entry: &str| {
             let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
                 label: Some(label),
                 source: wgpu::ShaderSource::Wgsl(src.into()),
@@ -94,19 +330,97 @@ impl GeneratorPass {
                 label: Some(label),
                 layout: Some(&pipeline_layout),
                 module: &module,
-                entry_point: "main",
+                entry_point: entry,
                 compilation_options: Default::default(),
                 cache: None,
             })
         };
+        let make = |label: &str, src: &str| make_entry(label, src, "main");
+        let inverse_julia_src = Self::default_source(GeneratorKind::InverseJulia);
+        let ifs_src = Self::default_source(GeneratorKind::Ifs);
+        let flame_src = Self::default_source(GeneratorKind::Flame);
+        let reaction_src = Self::default_source(GeneratorKind::ReactionDiffusion);
+        let f64 = device.features().contains(Features::SHADER_F64);
 
         Self {
-            mandelbrot: make("mandelbrot", include_str!("../shaders/mandelbrot.wgsl")),
-            julia: make("julia", include_str!("../shaders/julia.wgsl")),
-            burning_ship: make("burning_ship", include_str!("../shaders/burning_ship.wgsl")),
-            noise_field: make("noise_field", include_str!("../shaders/noise_field.wgsl")),
+            mandelbrot: make(
+                "mandelbrot",
+                Self::default_source(GeneratorKind::Mandelbrot),
+            ),
+            julia: make("julia", Self::default_source(GeneratorKind::Julia)),
+            burning_ship: make(
+                "burning_ship",
+                Self::default_source(GeneratorKind::BurningShip),
+            ),
+            celtic: make("celtic", Self::default_source(GeneratorKind::Celtic)),
+            buffalo: make("buffalo", Self::default_source(GeneratorKind::Buffalo)),
+            multibrot: make("multibrot", Self::default_source(GeneratorKind::Multibrot)),
+            hybrid: make(
+                "hybrid",
+                Self::default_source(GeneratorKind::Hybrid(Default::default())),
+            ),
+            kleinian: make("kleinian", Self::default_source(GeneratorKind::Kleinian)),
+            formula: make(
+                "formula",
+                Self::default_source(GeneratorKind::CustomFormula),
+            ),
+            noise_field: make(
+                "noise_field",
+                Self::default_source(GeneratorKind::NoiseField),
+            ),
+            simplex_field: make(
+                "simplex_field",
+                Self::default_source(GeneratorKind::SimplexField),
+            ),
+            worley_field: make(
+                "worley_field",
+                Self::default_source(GeneratorKind::WorleyField),
+            ),
+            domain_warp: make(
+                "domain_warp",
+                Self::default_source(GeneratorKind::DomainWarp),
+            ),
+            plasma: make("plasma", Self::default_source(GeneratorKind::Plasma)),
+            reaction: make("reaction", reaction_src),
+            reaction_seed: make_entry("reaction_seed", reaction_src, "seed"),
+            reaction_step: make_entry("reaction_step", reaction_src, "simulate"),
+            video: make("video", Self::default_source(GeneratorKind::Video)),
+            image: make("image", Self::default_source(GeneratorKind::Image)),
+            test_pattern: make(
+                "test_pattern",
+                Self::default_source(GeneratorKind::TestPattern),
+            ),
+            inverse_julia: make("inverse_julia", inverse_julia_src),
+            inverse_julia_scatter: make_entry(
+                "inverse_julia_scatter",
+                inverse_julia_src,
+                "scatter",
+            ),
+            ifs: make("ifs", ifs_src),
+            ifs_scatter: make_entry("ifs_scatter", ifs_src, "scatter"),
+            flame: make("flame", flame_src),
+            flame_scatter: make_entry("flame_scatter", flame_src, "scatter"),
+            perturbation: make("perturbation", PERTURBATION_SOURCE),
+            burning_ship_f64: f64.then(|| make("burning_ship_f64", BURNING_SHIP_F64_SOURCE)),
+            hybrid_f64: f64.then(|| make("hybrid_f64", HYBRID_F64_SOURCE)),
+            mandelbrot_df64: make("mandelbrot_df64", MANDELBROT_DF64_SOURCE),
+            julia_df64: make("julia_df64", JULIA_DF64_SOURCE),
+            burning_ship_df64: make("burning_ship_df64", BURNING_SHIP_DF64_SOURCE),
             bind_group_layout,
+            pipeline_layout,
             uniform_buf,
+            extension_buf,
+            source_tex,
+            source_view,
+            source_sampler,
+            density_buf,
+            orbit_buf,
+            orbit_generation: Cell::new(None),
+            state: StatePingPong::new(device, width, height),
+            formula_source: Self::default_source(GeneratorKind::CustomFormula).to_string(),
+            last_render: RefCell::new(None),
+            scratch_tex,
+            strip_bufs,
             output_tex,
             output_view,
             width,
@@ -114,8 +428,11 @@ impl GeneratorPass {
         }
     }
 
-    /// Upload uniforms and record the generator compute pass into `encoder`.
-    /// The result lands in `self.output_tex`, ready for the effect chain.
+    /// Upload the shared and `kind`-specific uniforms and record the
+    /// generator compute pass into `encoder`.  The result lands in
+    /// `self.output_tex`, ready for the effect chain.  A frame that only
+    /// pans the last one shifts it instead and renders just the uncovered
+    /// strips; one that changes nothing records no work at all.
     pub fn dispatch(
         &self,
         device: &Device,
@@ -123,33 +440,412 @@ impl GeneratorPass {
         queue: &Queue,
         kind: GeneratorKind,
         uniforms: &Uniforms,
+        extension: &GeneratorUniforms,
     ) {
-        queue.write_buffer(&self.uniform_buf, 0, bytemuck::bytes_of(uniforms));
-
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("gen_bg"),
-            layout: &self.bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: self.uniform_buf.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::TextureView(&self.output_view),
-                },
-            ],
-        });
+        let bytes = extension.as_bytes();
+        let plan = if uniforms.resolution == [self.width as f32, self.height as f32] {
+            pan::plan(self.last_render.borrow().as_ref(), kind, uniforms, bytes)
+        } else {
+            PanPlan::Full
+        };
+        if let PanPlan::Reuse {
+            shift,
+            strips,
+            uniforms: snapped,
+            offset,
+        } = plan
+        {
+            self.pan(device, encoder, queue, kind, shift, &strips, &snapped);
+            if let Some(last) = self.last_render.borrow_mut().as_mut() {
+                last.uniforms = snapped;
+                last.requested = uniforms.center;
+                last.offset = offset;
+            }
+            return;
+        }
+        *self.last_render.borrow_mut() =
+            pan::reusable(kind).then(|| LastRender::full(kind, uniforms, bytes));
 
+        stats::write_buffer(queue, &self.uniform_buf, 0, bytemuck::bytes_of(uniforms));
+        let pipeline = match extension {
+            GeneratorUniforms::Perturbation(_) => &self.perturbation,
+            _ => self
+                .precise_for(kind, uniforms)
+                .unwrap_or_else(|| self.pipeline_for(kind)),
+        };
+        if !bytes.is_empty() {
+            stats::write_buffer(queue, &self.extension_buf, 0, bytes);
+        }
+
+        let wg = 8u32;
+        let groups = (self.width.div_ceil(wg), self.height.div_ceil(wg));
         let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
             label: Some("gen_pass"),
             timestamp_writes: None,
         });
-        pass.set_pipeline(self.pipeline_for(kind));
-        pass.set_bind_group(0, &bind_group, &[]);
+        match extension {
+            GeneratorUniforms::Reaction(r) => {
+                // Each dispatch is its own usage scope, so every step reads
+                // the texture the one before it wrote.
+                if !self.state.seeded.replace(true) {
+                    self.advance_state(device, &mut pass, &self.reaction_seed, groups);
+                }
+                for _ in 0..r.steps {
+                    self.advance_state(device, &mut pass, &self.reaction_step, groups);
+                }
+            }
+            // Leaving the simulation reseeds it on the way back.
+            _ => self.state.seeded.set(false),
+        }
+        let front = self.state.front.get();
+        pass.set_bind_group(0, &self.bind_group(device, &self.uniform_buf, front), &[]);
+        if let Some(scatter) = self.scatter_for(kind) {
+            // Walkers scatter hits first; each dispatch is its own usage
+            // scope, so the resolve below sees every hit.
+            pass.set_pipeline(scatter);
+            stats::dispatch_workgroups(&mut pass, WALKER_GROUPS, 1, 1);
+        }
+        pass.set_pipeline(pipeline);
+        stats::dispatch_workgroups(&mut pass, groups.0, groups.1, 1);
+    }
 
+    /// Shift the output so pixel `p` takes what was at `p + shift`, by way
+    /// of the scratch texture, then render the uncovered `strips` of `kind`
+    /// with `uniforms`.
+    #[allow(clippy::too_many_arguments)]
+    fn pan(
+        &self,
+        device: &Device,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &Queue,
+        kind: GeneratorKind,
+        shift: [i32; 2],
+        strips: &[Strip],
+        uniforms: &Uniforms,
+    ) {
+        if shift != [0, 0] {
+            let [dx, dy] = shift;
+            let copy = |texture, x: i32, y: i32| wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: x.max(0) as u32,
+                    y: y.max(0) as u32,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            };
+            let size = wgpu::Extent3d {
+                width: self.width - dx.unsigned_abs(),
+                height: self.height - dy.unsigned_abs(),
+                depth_or_array_layers: 1,
+            };
+            encoder.copy_texture_to_texture(
+                copy(&self.output_tex, dx, dy),
+                copy(&self.scratch_tex, -dx, -dy),
+                size,
+            );
+            encoder.copy_texture_to_texture(
+                copy(&self.scratch_tex, -dx, -dy),
+                copy(&self.output_tex, -dx, -dy),
+                size,
+            );
+        }
+        if strips.is_empty() {
+            return;
+        }
         let wg = 8u32;
-        pass.dispatch_workgroups(self.width.div_ceil(wg), self.height.div_ceil(wg), 1);
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("gen_pan_pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(self.pipeline_for(kind));
+        let front = self.state.front.get();
+        for (strip, buf) in strips.iter().zip(&self.strip_bufs) {
+            let strip_uniforms = Uniforms {
+                origin: [strip.x, strip.y],
+                ..*uniforms
+            };
+            stats::write_buffer(queue, buf, 0, bytemuck::bytes_of(&strip_uniforms));
+            pass.set_bind_group(0, &self.bind_group(device, buf, front), &[]);
+            stats::dispatch_workgroups(
+                &mut pass,
+                strip.width.div_ceil(wg),
+                strip.height.div_ceil(wg),
+                1,
+            );
+        }
+    }
+
+    /// Run one simulation `pipeline` over the state: read the front
+    /// texture, write the back one, then swap them.
+    fn advance_state(
+        &self,
+        device: &Device,
+        pass: &mut wgpu::ComputePass,
+        pipeline: &ComputePipeline,
+        groups: (u32, u32),
+    ) {
+        let front = self.state.front.get();
+        pass.set_bind_group(0, &self.bind_group(device, &self.uniform_buf, front), &[]);
+        pass.set_pipeline(pipeline);
+        stats::dispatch_workgroups(pass, groups.0, groups.1, 1);
+        self.state.front.set(1 - front);
+    }
+
+    /// Start the reaction–diffusion over from its seed pattern on the next
+    /// dispatch.
+    pub fn reset_state(&self) {
+        self.state.seeded.set(false);
+    }
+
+    /// The bind group with `uniforms` at binding 0, state texture `read` at
+    /// binding 7 and the other at binding 8.
+    fn bind_group(&self, device: &Device, uniforms: &Buffer, read: usize) -> BindGroup {
+        stats::create_bind_group(
+            device,
+            &wgpu::BindGroupDescriptor {
+                label: Some("gen_bg"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: uniforms.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&self.output_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: self.extension_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: wgpu::BindingResource::TextureView(&self.source_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: wgpu::BindingResource::Sampler(&self.source_sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 5,
+                        resource: self.density_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 6,
+                        resource: self.orbit_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 7,
+                        resource: wgpu::BindingResource::TextureView(&self.state.views[read]),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 8,
+                        resource: wgpu::BindingResource::TextureView(&self.state.views[1 - read]),
+                    },
+                ],
+            },
+        )
+    }
+
+    /// The wide kernel to run `kind` with instead of its f32 pipeline past
+    /// [`DeepZoom::THRESHOLD`], where f32 pixels merge: f64 on a device that
+    /// built one, df64 otherwise.  A deep Mandelbrot or Julia in the app
+    /// renders by perturbation instead; their df64 kernels serve the views
+    /// built from params alone, such as thumbnails and previews.  The
+    /// kernels aren't live-editable, so an edited source shows only below
+    /// the threshold.
+    pub fn precise_for(
+        &self,
+        kind: GeneratorKind,
+        uniforms: &Uniforms,
+    ) -> Option<&ComputePipeline> {
+        if uniforms.zoom < DeepZoom::THRESHOLD {
+            return None;
+        }
+        match kind {
+            GeneratorKind::Mandelbrot => Some(&self.mandelbrot_df64),
+            GeneratorKind::Julia => Some(&self.julia_df64),
+            GeneratorKind::BurningShip | GeneratorKind::Celtic | GeneratorKind::Buffalo => Some(
+                self.burning_ship_f64
+                    .as_ref()
+                    .unwrap_or(&self.burning_ship_df64),
+            ),
+            GeneratorKind::Hybrid(_) => self.hybrid_f64.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Copy `orbit` into the orbit buffer the perturbation pipeline reads,
+    /// unless it is already there.
+    pub fn upload_orbit(&self, queue: &Queue, orbit: &ReferenceOrbit) {
+        if self.orbit_generation.get() == Some(orbit.generation) {
+            return;
+        }
+        let len = orbit.points.len().min(ReferenceOrbit::MAX_POINTS);
+        stats::write_buffer(
+            queue,
+            &self.orbit_buf,
+            0,
+            bytemuck::cast_slice(&orbit.points[..len]),
+        );
+        self.orbit_generation.set(Some(orbit.generation));
+    }
+
+    /// Upload a tightly packed sRGB RGBA8 `width`×`height` frame into the
+    /// source texture the video and image generators sample, resizing it as
+    /// needed.  A frame past the device's texture size limit is dropped
+    /// (callers shrink it first); creating the texture would panic.
+    pub fn set_frame(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) {
+        let max = device.limits().max_texture_dimension_2d;
+        if width > max || height > max {
+            log::warn!("dropping {width}×{height} source frame: past the {max} texture limit");
+            return;
+        }
+        let size = self.source_tex.size();
+        if (size.width, size.height) != (width, height) {
+            (self.source_tex, self.source_view) =
+                source_texture(device, "gen_source", width, height);
+        }
+        queue.write_texture(
+            self.source_tex.as_image_copy(),
+            rgba,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: Some(height),
+            },
+            self.source_tex.size(),
+        );
+    }
+
+    /// Built-in WGSL source for a generator.
+    pub fn default_source(kind: GeneratorKind) -> &'static str {
+        match kind {
+            GeneratorKind::Mandelbrot => with_view!("../shaders/mandelbrot.wgsl"),
+            GeneratorKind::Julia => with_view!("../shaders/julia.wgsl"),
+            GeneratorKind::BurningShip | GeneratorKind::Celtic | GeneratorKind::Buffalo => {
+                with_view!("../shaders/burning_ship.wgsl")
+            }
+            GeneratorKind::Multibrot => with_view!("../shaders/multibrot.wgsl"),
+            GeneratorKind::Hybrid(_) => with_view!("../shaders/hybrid.wgsl"),
+            GeneratorKind::Kleinian => with_view!("../shaders/kleinian.wgsl"),
+            GeneratorKind::CustomFormula => FORMULA_TEMPLATE,
+            GeneratorKind::NoiseField => with_view!("../shaders/noise_field.wgsl"),
+            GeneratorKind::SimplexField => with_view!("../shaders/simplex_field.wgsl"),
+            GeneratorKind::WorleyField => with_view!("../shaders/worley_field.wgsl"),
+            GeneratorKind::ReactionDiffusion => include_str!("../shaders/reaction_diffusion.wgsl"),
+            GeneratorKind::DomainWarp => with_view!("../shaders/domain_warp.wgsl"),
+            GeneratorKind::Plasma => with_view!("../shaders/plasma.wgsl"),
+            GeneratorKind::Video => with_view!("../shaders/video.wgsl"),
+            GeneratorKind::Image => with_view!("../shaders/image.wgsl"),
+            GeneratorKind::TestPattern => include_str!("../shaders/test_pattern.wgsl"),
+            GeneratorKind::InverseJulia => include_str!("../shaders/inverse_julia.wgsl"),
+            GeneratorKind::Ifs => include_str!("../shaders/ifs.wgsl"),
+            GeneratorKind::Flame => include_str!("../shaders/flame.wgsl"),
+        }
+    }
+
+    /// Recompile the pipeline for `kind` from `src` (for the point-cloud
+    /// generators, both entry points; for the reaction–diffusion, all
+    /// three).  On error the previous pipelines stay
+    /// in place.
+    pub fn set_source(
+        &mut self,
+        device: &Device,
+        kind: GeneratorKind,
+        src: &str,
+    ) -> Result<(), ShaderError> {
+        let pipeline = shader::compile_compute(device, "gen_live", src, &self.pipeline_layout)?;
+        if self.scatter_for(kind).is_some() {
+            let scatter = shader::compile_compute_entry(
+                device,
+                "gen_live_scatter",
+                src,
+                &self.pipeline_layout,
+                "scatter",
+            )?;
+            match kind {
+                GeneratorKind::Ifs => self.ifs_scatter = scatter,
+                GeneratorKind::Flame => self.flame_scatter = scatter,
+                _ => self.inverse_julia_scatter = scatter,
+            }
+        }
+        if kind == GeneratorKind::ReactionDiffusion {
+            let entry = |label, entry| {
+                shader::compile_compute_entry(device, label, src, &self.pipeline_layout, entry)
+            };
+            let seed = entry("gen_live_seed", "seed")?;
+            let step = entry("gen_live_step", "simulate")?;
+            self.reaction_seed = seed;
+            self.reaction_step = step;
+        }
+        *self.pipeline_for_mut(kind) = pipeline;
+        *self.last_render.get_mut() = None;
+        Ok(())
+    }
+
+    /// Compile the custom formula pipeline for `formula`, unless it was the
+    /// last one asked for.  On error the previous pipeline stays in place
+    /// and the same formula isn't retried until another has been set.
+    pub fn set_formula(&mut self, device: &Device, formula: &Formula) -> Result<(), ShaderError> {
+        let src = formula_source(formula);
+        if src == self.formula_source {
+            return Ok(());
+        }
+        self.formula_source = src;
+        *self.last_render.get_mut() = None;
+        self.formula = shader::compile_compute(
+            device,
+            "gen_formula",
+            &self.formula_source,
+            &self.pipeline_layout,
+        )?;
+        Ok(())
+    }
+
+    /// The walker pass the point-cloud generators run before `main`.
+    fn scatter_for(&self, kind: GeneratorKind) -> Option<&ComputePipeline> {
+        match kind {
+            GeneratorKind::InverseJulia => Some(&self.inverse_julia_scatter),
+            GeneratorKind::Ifs => Some(&self.ifs_scatter),
+            GeneratorKind::Flame => Some(&self.flame_scatter),
+            _ => None,
+        }
+    }
+
+    fn pipeline_for_mut(&mut self, kind: GeneratorKind) -> &mut ComputePipeline {
+        match kind {
+            GeneratorKind::Mandelbrot => &mut self.mandelbrot,
+            GeneratorKind::Julia => &mut self.julia,
+            GeneratorKind::BurningShip => &mut self.burning_ship,
+            GeneratorKind::Celtic => &mut self.celtic,
+            GeneratorKind::Buffalo => &mut self.buffalo,
+            GeneratorKind::Multibrot => &mut self.multibrot,
+            GeneratorKind::Hybrid(_) => &mut self.hybrid,
+            GeneratorKind::Kleinian => &mut self.kleinian,
+            GeneratorKind::CustomFormula => &mut self.formula,
+            GeneratorKind::NoiseField => &mut self.noise_field,
+            GeneratorKind::SimplexField => &mut self.simplex_field,
+            GeneratorKind::WorleyField => &mut self.worley_field,
+            GeneratorKind::ReactionDiffusion => &mut self.reaction,
+            GeneratorKind::DomainWarp => &mut self.domain_warp,
+            GeneratorKind::Plasma => &mut self.plasma,
+            GeneratorKind::Video => &mut self.video,
+            GeneratorKind::Image => &mut self.image,
+            GeneratorKind::TestPattern => &mut self.test_pattern,
+            GeneratorKind::InverseJulia => &mut self.inverse_julia,
+            GeneratorKind::Ifs => &mut self.ifs,
+            GeneratorKind::Flame => &mut self.flame,
+        }
     }
 
     fn pipeline_for(&self, kind: GeneratorKind) -> &ComputePipeline {
@@ -157,17 +853,149 @@ impl GeneratorPass {
             GeneratorKind::Mandelbrot => &self.mandelbrot,
             GeneratorKind::Julia => &self.julia,
             GeneratorKind::BurningShip => &self.burning_ship,
+            GeneratorKind::Celtic => &self.celtic,
+            GeneratorKind::Buffalo => &self.buffalo,
+            GeneratorKind::Multibrot => &self.multibrot,
+            GeneratorKind::Hybrid(_) => &self.hybrid,
+            GeneratorKind::Kleinian => &self.kleinian,
+            GeneratorKind::CustomFormula => &self.formula,
             GeneratorKind::NoiseField => &self.noise_field,
+            GeneratorKind::SimplexField => &self.simplex_field,
+            GeneratorKind::WorleyField => &self.worley_field,
+            GeneratorKind::ReactionDiffusion => &self.reaction,
+            GeneratorKind::DomainWarp => &self.domain_warp,
+            GeneratorKind::Plasma => &self.plasma,
+            GeneratorKind::Video => &self.video,
+            GeneratorKind::Image => &self.image,
+            GeneratorKind::TestPattern => &self.test_pattern,
+            GeneratorKind::InverseJulia => &self.inverse_julia,
+            GeneratorKind::Ifs => &self.ifs,
+            GeneratorKind::Flame => &self.flame,
         }
     }
 }
 
+/// The custom formula shader, holding the default formula after its
+/// `// @formula` marker.
+pub const FORMULA_TEMPLATE: &str = with_view!("../shaders/formula.wgsl");
+
+/// Marker in [`FORMULA_TEMPLATE`] after which the formula function goes.
+const FORMULA_MARKER: &str = "// @formula\n";
+
+/// [`FORMULA_TEMPLATE`] with its formula function replaced by `formula`'s.
+pub fn formula_source(formula: &Formula) -> String {
+    let end = FORMULA_TEMPLATE
+        .find(FORMULA_MARKER)
+        .expect("formula.wgsl has its marker")
+        + FORMULA_MARKER.len();
+    format!("{}{}", &FORMULA_TEMPLATE[..end], formula.to_wgsl())
+}
+
+/// The perturbation shader for deep Mandelbrot and Julia views.
+pub const PERTURBATION_SOURCE: &str = with_view!("../shaders/perturbation.wgsl");
+
+/// Double-precision Burning Ship (and Celtic and Buffalo), for devices with
+/// `SHADER_F64`.
+pub const BURNING_SHIP_F64_SOURCE: &str = with_view!("../shaders/burning_ship_f64.wgsl");
+
+/// Double-precision hybrid, for devices with `SHADER_F64`.
+pub const HYBRID_F64_SOURCE: &str = with_view!("../shaders/hybrid_f64.wgsl");
+
+/// The df64 kernels, each with the double-float library prepended.
+pub const MANDELBROT_DF64_SOURCE: &str =
+    with_view!("../shaders/df64.wgsl", "../shaders/mandelbrot_df64.wgsl");
+pub const JULIA_DF64_SOURCE: &str =
+    with_view!("../shaders/df64.wgsl", "../shaders/julia_df64.wgsl");
+pub const BURNING_SHIP_DF64_SOURCE: &str =
+    with_view!("../shaders/df64.wgsl", "../shaders/burning_ship_df64.wgsl");
+
+/// Workgroups of 64 walkers the point-cloud generators scatter per frame.
+const WALKER_GROUPS: u32 = 1024;
+
+/// Texel format of the simulation state: two f32 concentrations per pixel,
+/// since Gray–Scott drifts at f16 precision.
+const STATE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rg32Float;
+
+/// The generator-side ping-pong pair a stateful generator steps through,
+/// kept across frames.  Each step reads `views[front]` and writes the
+/// other, then swaps.
+struct StatePingPong {
+    _textures: [Texture; 2],
+    views: [TextureView; 2],
+    /// The texture holding the latest state.
+    front: Cell<usize>,
+    /// Whether the state holds a simulation; cleared to reseed it.
+    seeded: Cell<bool>,
+}
+
+impl StatePingPong {
+    fn new(device: &Device, width: u32, height: u32) -> Self {
+        let make = || {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("gen_state"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: STATE_FORMAT,
+                usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            })
+        };
+        let textures = [make(), make()];
+        let views = [
+            textures[0].create_view(&Default::default()),
+            textures[1].create_view(&Default::default()),
+        ];
+        Self {
+            _textures: textures,
+            views,
+            front: Cell::new(0),
+            seeded: Cell::new(false),
+        }
+    }
+}
+
+/// Sampled sRGB texture for images uploaded from the CPU.
+pub(crate) fn source_texture(
+    device: &Device,
+    label: &str,
+    width: u32,
+    height: u32,
+) -> (Texture, TextureView) {
+    let tex = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    let view = tex.create_view(&Default::default());
+    (tex, view)
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
 
 #[cfg(test)]
 mod tests {
+    use super::GeneratorPass;
+    use crate::context::GeneratorUniforms;
+    use crate::shader::{binding_mismatches, uniform_size};
+    use fractal_core::{GeneratorKind, HybridSchedule, Params};
+
     // --- WGSL validation (CPU-only, no GPU required) -------------------------
 
     /// Parse and type-check a WGSL shader using naga, the same validator that
@@ -187,22 +1015,318 @@ mod tests {
 
     #[test]
     fn mandelbrot_wgsl_is_valid() {
-        validate_wgsl("mandelbrot", include_str!("../shaders/mandelbrot.wgsl"));
+        validate_wgsl(
+            "mandelbrot",
+            GeneratorPass::default_source(GeneratorKind::Mandelbrot),
+        );
     }
 
     #[test]
     fn julia_wgsl_is_valid() {
-        validate_wgsl("julia", include_str!("../shaders/julia.wgsl"));
+        validate_wgsl("julia", GeneratorPass::default_source(GeneratorKind::Julia));
     }
 
     #[test]
     fn burning_ship_wgsl_is_valid() {
-        validate_wgsl("burning_ship", include_str!("../shaders/burning_ship.wgsl"));
+        validate_wgsl(
+            "burning_ship",
+            GeneratorPass::default_source(GeneratorKind::BurningShip),
+        );
+    }
+
+    #[test]
+    fn multibrot_wgsl_is_valid() {
+        validate_wgsl(
+            "multibrot",
+            GeneratorPass::default_source(GeneratorKind::Multibrot),
+        );
+    }
+
+    #[test]
+    fn hybrid_wgsl_is_valid() {
+        validate_wgsl(
+            "hybrid",
+            GeneratorPass::default_source(GeneratorKind::Hybrid(Default::default())),
+        );
+    }
+
+    #[test]
+    fn kleinian_wgsl_is_valid() {
+        validate_wgsl(
+            "kleinian",
+            GeneratorPass::default_source(GeneratorKind::Kleinian),
+        );
+    }
+
+    #[test]
+    fn formula_wgsl_is_valid() {
+        validate_wgsl("formula", super::FORMULA_TEMPLATE);
+    }
+
+    #[test]
+    fn template_holds_the_default_formula() {
+        assert_eq!(
+            super::formula_source(&super::Formula::default()),
+            super::FORMULA_TEMPLATE
+        );
+    }
+
+    #[test]
+    fn every_formula_construct_compiles_to_valid_wgsl() {
+        for src in [
+            "z*z*z + c*sin(z)",
+            "abs(z)^2 + c",
+            "z^a + c*b",
+            "z^-1.5 + c / (1 + t)",
+            "exp(z) - cos(z)*tan(z) + sinh(z)/cosh(z) + tanh(z)",
+            "log(z) + sqrt(z) + conj(z) + re(z)*im(z)*i + pi*e",
+            "-z^8 + c",
+        ] {
+            let formula = super::Formula::parse(src).unwrap();
+            validate_wgsl(src, &super::formula_source(&formula));
+        }
     }
 
     #[test]
     fn noise_field_wgsl_is_valid() {
-        validate_wgsl("noise_field", include_str!("../shaders/noise_field.wgsl"));
+        validate_wgsl(
+            "noise_field",
+            GeneratorPass::default_source(GeneratorKind::NoiseField),
+        );
+    }
+
+    #[test]
+    fn simplex_field_wgsl_is_valid() {
+        validate_wgsl(
+            "simplex_field",
+            GeneratorPass::default_source(GeneratorKind::SimplexField),
+        );
+    }
+
+    #[test]
+    fn reaction_diffusion_wgsl_is_valid() {
+        validate_wgsl(
+            "reaction_diffusion",
+            include_str!("../shaders/reaction_diffusion.wgsl"),
+        );
+    }
+
+    #[test]
+    fn worley_field_wgsl_is_valid() {
+        validate_wgsl(
+            "worley_field",
+            GeneratorPass::default_source(GeneratorKind::WorleyField),
+        );
+    }
+
+    #[test]
+    fn domain_warp_wgsl_is_valid() {
+        validate_wgsl(
+            "domain_warp",
+            GeneratorPass::default_source(GeneratorKind::DomainWarp),
+        );
+    }
+
+    #[test]
+    fn plasma_wgsl_is_valid() {
+        validate_wgsl(
+            "plasma",
+            GeneratorPass::default_source(GeneratorKind::Plasma),
+        );
+    }
+
+    #[test]
+    fn video_wgsl_is_valid() {
+        validate_wgsl("video", GeneratorPass::default_source(GeneratorKind::Video));
+    }
+
+    #[test]
+    fn image_wgsl_is_valid() {
+        validate_wgsl("image", GeneratorPass::default_source(GeneratorKind::Image));
+    }
+
+    #[test]
+    fn test_pattern_wgsl_is_valid() {
+        validate_wgsl("test_pattern", include_str!("../shaders/test_pattern.wgsl"));
+    }
+
+    #[test]
+    fn inverse_julia_wgsl_is_valid() {
+        validate_wgsl(
+            "inverse_julia",
+            include_str!("../shaders/inverse_julia.wgsl"),
+        );
+    }
+
+    #[test]
+    fn ifs_wgsl_is_valid() {
+        validate_wgsl("ifs", include_str!("../shaders/ifs.wgsl"));
+    }
+
+    #[test]
+    fn flame_wgsl_is_valid() {
+        validate_wgsl("flame", include_str!("../shaders/flame.wgsl"));
+    }
+
+    #[test]
+    fn perturbation_wgsl_is_valid() {
+        validate_wgsl("perturbation", super::PERTURBATION_SOURCE);
+    }
+
+    #[test]
+    fn burning_ship_f64_wgsl_is_valid() {
+        validate_wgsl("burning_ship_f64", super::BURNING_SHIP_F64_SOURCE);
+    }
+
+    #[test]
+    fn hybrid_f64_wgsl_is_valid() {
+        validate_wgsl("hybrid_f64", super::HYBRID_F64_SOURCE);
+    }
+
+    #[test]
+    fn df64_kernels_are_valid() {
+        validate_wgsl("mandelbrot_df64", super::MANDELBROT_DF64_SOURCE);
+        validate_wgsl("julia_df64", super::JULIA_DF64_SOURCE);
+        validate_wgsl("burning_ship_df64", super::BURNING_SHIP_DF64_SOURCE);
+    }
+
+    // --- Binding contract -----------------------------------------------------
+
+    /// Every generator, with the Hybrid at its default schedule.
+    const KINDS: [GeneratorKind; 21] = [
+        GeneratorKind::Mandelbrot,
+        GeneratorKind::Julia,
+        GeneratorKind::BurningShip,
+        GeneratorKind::NoiseField,
+        GeneratorKind::SimplexField,
+        GeneratorKind::WorleyField,
+        GeneratorKind::ReactionDiffusion,
+        GeneratorKind::DomainWarp,
+        GeneratorKind::Plasma,
+        GeneratorKind::Video,
+        GeneratorKind::Image,
+        GeneratorKind::TestPattern,
+        GeneratorKind::InverseJulia,
+        GeneratorKind::Multibrot,
+        GeneratorKind::Celtic,
+        GeneratorKind::Buffalo,
+        GeneratorKind::Ifs,
+        GeneratorKind::Flame,
+        GeneratorKind::Kleinian,
+        GeneratorKind::Hybrid(HybridSchedule::DEFAULT),
+        GeneratorKind::CustomFormula,
+    ];
+
+    #[test]
+    fn generator_shaders_match_the_shared_layout() {
+        let kernels = [
+            ("perturbation", super::PERTURBATION_SOURCE),
+            ("burning_ship_f64", super::BURNING_SHIP_F64_SOURCE),
+            ("hybrid_f64", super::HYBRID_F64_SOURCE),
+            ("mandelbrot_df64", super::MANDELBROT_DF64_SOURCE),
+            ("julia_df64", super::JULIA_DF64_SOURCE),
+            ("burning_ship_df64", super::BURNING_SHIP_DF64_SOURCE),
+        ];
+        let sources = KINDS
+            .iter()
+            .map(|kind| (format!("{kind:?}"), GeneratorPass::default_source(*kind)))
+            .chain(kernels.map(|(label, src)| (label.to_string(), src)));
+        for (label, src) in sources {
+            let problems = binding_mismatches(src, &super::LAYOUT_ENTRIES);
+            assert!(problems.is_empty(), "{label}:\n{}", problems.join("\n"));
+        }
+    }
+
+    /// Byte offset of `member` in the shared `Uniforms` (binding 0) of
+    /// `src`, if it declares one.
+    fn uniforms_offset(src: &str, member: &str) -> Option<u32> {
+        member_offset(src, 0, member)
+    }
+
+    /// Byte offset of `member` in the struct bound at `binding` in `src`.
+    fn member_offset(src: &str, binding: u32, member: &str) -> Option<u32> {
+        let module = naga::front::wgsl::parse_str(src).ok()?;
+        let (_, var) = module
+            .global_variables
+            .iter()
+            .find(|(_, var)| var.binding.as_ref().is_some_and(|rb| rb.binding == binding))?;
+        let naga::TypeInner::Struct { members, .. } = &module.types[var.ty].inner else {
+            return None;
+        };
+        members
+            .iter()
+            .find(|m| m.name.as_deref() == Some(member))
+            .map(|m| m.offset)
+    }
+
+    #[test]
+    fn perturbation_block_matches_its_shader() {
+        use crate::context::PerturbationUniforms as P;
+        let src = super::PERTURBATION_SOURCE;
+        for (member, offset) in [
+            ("c_offset", std::mem::offset_of!(P, c_offset)),
+            ("trap", std::mem::offset_of!(P, trap)),
+            ("series_a", std::mem::offset_of!(P, series)),
+            ("skip", std::mem::offset_of!(P, skip)),
+            ("series_scale", std::mem::offset_of!(P, series_scale)),
+        ] {
+            assert_eq!(
+                member_offset(src, 2, member),
+                Some(offset as u32),
+                "{member}"
+            );
+        }
+    }
+
+    #[test]
+    fn escape_time_kernels_read_the_sample_count() {
+        let samples = std::mem::offset_of!(crate::context::Uniforms, samples) as u32;
+        let kernels = [
+            ("perturbation", super::PERTURBATION_SOURCE),
+            ("burning_ship_f64", super::BURNING_SHIP_F64_SOURCE),
+            ("hybrid_f64", super::HYBRID_F64_SOURCE),
+            ("mandelbrot_df64", super::MANDELBROT_DF64_SOURCE),
+            ("julia_df64", super::JULIA_DF64_SOURCE),
+            ("burning_ship_df64", super::BURNING_SHIP_DF64_SOURCE),
+        ];
+        for (label, src) in kernels {
+            assert_eq!(uniforms_offset(src, "samples"), Some(samples), "{label}");
+        }
+        for kind in KINDS {
+            let src = GeneratorPass::default_source(kind);
+            let expected = crate::pan::reusable(kind).then_some(samples);
+            assert_eq!(uniforms_offset(src, "samples"), expected, "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn kernels_a_pan_reuses_start_at_the_dispatch_origin() {
+        let origin = std::mem::offset_of!(crate::context::Uniforms, origin) as u32;
+        for kind in KINDS {
+            let src = GeneratorPass::default_source(kind);
+            let expected = crate::pan::reusable(kind).then_some(origin);
+            assert_eq!(uniforms_offset(src, "origin"), expected, "{kind:?}");
+            if expected.is_some() {
+                assert!(src.contains("gid.xy + u.origin"), "{kind:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn extension_blocks_match_their_shader_structs() {
+        for kind in KINDS {
+            let block = GeneratorUniforms::from_params(kind, &Params::default());
+            let src = GeneratorPass::default_source(kind);
+            let declared = uniform_size(src, 2);
+            match block {
+                GeneratorUniforms::None => assert_eq!(declared, None, "{kind:?}"),
+                _ => assert_eq!(
+                    declared,
+                    Some(block.as_bytes().len() as u32),
+                    "{kind:?} uploads {block:?}"
+                ),
+            }
+        }
     }
 
     // --- Coordinate mapping (Rust mirror of the WGSL UV formula) -------------
@@ -253,11 +1377,88 @@ mod tests {
         );
     }
 
+    // --- Periodicity check (mirrors shaders' Brent check) ---------------------
+
+    const PERIOD_START: u32 = 8;
+    const PERIOD_EPSILON: f32 = 1e-12;
+
+    /// Compares each new orbit point against one saved point, re-saved after
+    /// 8, 16, 32, … steps, so any cycle is caught once the window outgrows it.
+    struct Periodicity {
+        saved: (f32, f32),
+        steps: u32,
+        window: u32,
+    }
+
+    impl Periodicity {
+        fn new(x: f32, y: f32) -> Self {
+            Self {
+                saved: (x, y),
+                steps: 0,
+                window: PERIOD_START,
+            }
+        }
+
+        fn returned(&mut self, x: f32, y: f32) -> bool {
+            let (dx, dy) = (x - self.saved.0, y - self.saved.1);
+            if dx * dx + dy * dy < PERIOD_EPSILON {
+                return true;
+            }
+            self.steps += 1;
+            if self.steps == self.window {
+                self.steps = 0;
+                self.window *= 2;
+                self.saved = (x, y);
+            }
+            false
+        }
+    }
+
+    /// Steps until the Mandelbrot orbit of `c` is caught cycling, if ever.
+    fn mandelbrot_period_steps(cx: f32, cy: f32, max_iter: u32) -> Option<u32> {
+        let (mut x, mut y) = (0.0f32, 0.0f32);
+        let mut period = Periodicity::new(x, y);
+        for i in 1..=max_iter {
+            if x * x + y * y > 4.0 {
+                return None;
+            }
+            let xn = x * x - y * y + cx;
+            y = 2.0 * x * y + cy;
+            x = xn;
+            if period.returned(x, y) {
+                return Some(i);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn periodicity_catches_attracting_cycles_early() {
+        // Main cardioid (fixed point), period-2 bulb and period-3 bulb.
+        for (cx, cy) in [(-0.2, 0.1), (-1.0, 0.05), (-0.12, 0.75)] {
+            let steps = mandelbrot_period_steps(cx, cy, 10_000);
+            assert!(
+                steps.is_some_and(|n| n < 1_000),
+                "c=({cx},{cy}) should be caught early; got {steps:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn periodicity_never_flags_escaping_points() {
+        // Just outside the cusp: escapes only after a long, slow orbit.
+        for (cx, cy) in [(0.26, 0.0), (0.5, 0.5), (-0.75, 0.05)] {
+            assert_eq!(mandelbrot_period_steps(cx, cy, 10_000), None);
+            assert!(mandelbrot_iter(cx, cy, 10_000).0 < 10_000);
+        }
+    }
+
     // --- Mandelbrot iteration (mirrors shader loop) --------------------------
 
     fn mandelbrot_iter(cx: f32, cy: f32, max_iter: u32) -> (u32, f32, f32) {
         let (mut x, mut y) = (0.0f32, 0.0f32);
         let mut i = 0u32;
+        let mut period = Periodicity::new(x, y);
         while i < max_iter {
             if x * x + y * y > 4.0 {
                 break;
@@ -266,6 +1467,9 @@ mod tests {
             y = 2.0 * x * y + cy;
             x = xn;
             i += 1;
+            if period.returned(x, y) {
+                return (max_iter, x, y);
+            }
         }
         (i, x, y)
     }
@@ -296,6 +1500,7 @@ mod tests {
     fn julia_iter(zx: f32, zy: f32, cx: f32, cy: f32, max_iter: u32) -> (u32, f32, f32) {
         let (mut x, mut y) = (zx, zy);
         let mut i = 0u32;
+        let mut period = Periodicity::new(x, y);
         while i < max_iter {
             if x * x + y * y > 4.0 {
                 break;
@@ -304,6 +1509,9 @@ mod tests {
             y = 2.0 * x * y + cy;
             x = xn;
             i += 1;
+            if period.returned(x, y) {
+                return (max_iter, x, y);
+            }
         }
         (i, x, y)
     }
@@ -330,6 +1538,7 @@ mod tests {
     fn burning_ship_iter(cx: f32, cy: f32, max_iter: u32) -> (u32, f32, f32) {
         let (mut x, mut y) = (0.0f32, 0.0f32);
         let mut i = 0u32;
+        let mut period = Periodicity::new(x, y);
         while i < max_iter {
             if x * x + y * y > 4.0 {
                 break;
@@ -338,6 +1547,9 @@ mod tests {
             y = 2.0 * x.abs() * y.abs() + cy;
             x = xn;
             i += 1;
+            if period.returned(x, y) {
+                return (max_iter, x, y);
+            }
         }
         (i, x, y)
     }
@@ -366,6 +1578,415 @@ mod tests {
         );
     }
 
+    // --- Ship family (mirrors the shader's `fold` switch) ---------------------
+
+    fn ship_iter(cx: f32, cy: f32, fold: u32, max_iter: u32) -> u32 {
+        let (mut x, mut y) = (0.0f32, 0.0f32);
+        let mut i = 0u32;
+        let mut period = Periodicity::new(x, y);
+        while i < max_iter {
+            if x * x + y * y > 4.0 {
+                break;
+            }
+            let mut re = x * x - y * y;
+            let mut im = 2.0 * x * y;
+            if fold != 1 {
+                im = im.abs();
+            }
+            if fold != 0 {
+                re = re.abs();
+            }
+            (x, y) = (re + cx, im + cy);
+            i += 1;
+            if period.returned(x, y) {
+                return max_iter;
+            }
+        }
+        i
+    }
+
+    /// burning_ship_f64.wgsl's loop: the same folds in f64, from a centre
+    /// and pixel offset added in f64.  No periodicity check — it only stops
+    /// interior points early.
+    fn ship_iter_f64(center: [f64; 2], uv: [f32; 2], zoom: f64, fold: u32, max_iter: u32) -> u32 {
+        let (cx, cy) = (
+            center[0] + uv[0] as f64 / zoom,
+            center[1] + uv[1] as f64 / zoom,
+        );
+        let (mut x, mut y) = (0.0f64, 0.0f64);
+        let mut i = 0u32;
+        while i < max_iter && x * x + y * y <= 4.0 {
+            let mut re = x * x - y * y;
+            let mut im = 2.0 * x * y;
+            if fold != 1 {
+                im = im.abs();
+            }
+            if fold != 0 {
+                re = re.abs();
+            }
+            (x, y) = (re + cx, im + cy);
+            i += 1;
+        }
+        i
+    }
+
+    #[test]
+    fn ship_f64_resolves_pixels_f32_cannot() {
+        let (center, zoom) = ([-1.7548776662466927, -0.0220], 1e8);
+        // A row of pixels, as `plane_uv(px) / zoom` places them.
+        let uv = |k: i32| [k as f32 / 512.0, 0.0];
+        let f32_points: std::collections::BTreeSet<u32> = (-256..256)
+            .map(|k| (center[0] as f32 + uv(k)[0] / zoom as f32).to_bits())
+            .collect();
+        assert!(f32_points.len() <= 3, "f32 merges the row: {f32_points:?}");
+        let counts: std::collections::BTreeSet<u32> = (-256..256)
+            .map(|k| ship_iter_f64(center, uv(k), zoom, 0, 2000))
+            .collect();
+        assert!(counts.len() > 8, "{counts:?}");
+    }
+
+    #[test]
+    fn fold_zero_is_the_burning_ship() {
+        for (cx, cy) in [(-1.76, -0.02), (-0.5, -0.5), (0.3, 0.5), (3.0, 3.0)] {
+            let (bi, _, _) = burning_ship_iter(cx, cy, 200);
+            assert_eq!(ship_iter(cx, cy, 0, 200), bi, "c=({cx},{cy})");
+        }
+    }
+
+    #[test]
+    fn celtic_and_buffalo_match_mandelbrot_on_the_real_axis() {
+        // Real z keeps Re(z²) = x² ≥ 0 and Im(z²) = 0, so folding is a no-op.
+        for cx in [-1.9, -1.3, -0.75, 0.1, 0.26, 0.5] {
+            let (mi, _, _) = mandelbrot_iter(cx, 0.0, 200);
+            assert_eq!(ship_iter(cx, 0.0, 1, 200), mi, "celtic c={cx}");
+            assert_eq!(ship_iter(cx, 0.0, 2, 200), mi, "buffalo c={cx}");
+        }
+    }
+
+    #[test]
+    fn each_fold_is_a_different_fractal() {
+        // c = (-1.0, 0.25) is inside the Mandelbrot set; every fold escapes
+        // it after a different number of steps.
+        let (mi, _, _) = mandelbrot_iter(-1.0, 0.25, 200);
+        let counts = [
+            mi,
+            ship_iter(-1.0, 0.25, 0, 200),
+            ship_iter(-1.0, 0.25, 1, 200),
+            ship_iter(-1.0, 0.25, 2, 200),
+        ];
+        for (a, x) in counts.iter().enumerate() {
+            for y in &counts[a + 1..] {
+                assert_ne!(x, y, "{counts:?}");
+            }
+        }
+    }
+
+    // --- Multibrot iteration (mirrors shader loop, polar z^d) ----------------
+
+    fn multibrot_iter(cx: f32, cy: f32, power: f32, max_iter: u32) -> u32 {
+        let (mut x, mut y) = (0.0f32, 0.0f32);
+        let mut i = 0u32;
+        let mut period = Periodicity::new(x, y);
+        while i < max_iter {
+            let r2 = x * x + y * y;
+            if r2 > 4.0 {
+                break;
+            }
+            let (mut zx, mut zy) = (0.0, 0.0);
+            if r2 > 0.0 {
+                let r = r2.powf(0.5 * power);
+                let theta = y.atan2(x) * power;
+                (zx, zy) = (r * theta.cos(), r * theta.sin());
+            }
+            x = zx + cx;
+            y = zy + cy;
+            i += 1;
+            if period.returned(x, y) {
+                return max_iter;
+            }
+        }
+        i
+    }
+
+    #[test]
+    fn multibrot_power_two_matches_mandelbrot() {
+        for (cx, cy) in [(-0.2, 0.1), (0.5, 0.5), (-1.76, -0.02), (0.3, -0.6)] {
+            let (mi, _, _) = mandelbrot_iter(cx, cy, 200);
+            let di = multibrot_iter(cx, cy, 2.0, 200);
+            assert!(mi.abs_diff(di) <= 1, "c=({cx},{cy}): {mi} vs {di}");
+        }
+    }
+
+    #[test]
+    fn multibrot_higher_power_changes_the_set() {
+        // c = -1.1 is in the Mandelbrot set's period-2 bulb but escapes the
+        // cubic Multibrot, whose real-axis extent is only about ±0.38.
+        assert_eq!(multibrot_iter(-1.1, 0.0, 2.0, 200), 200);
+        assert!(multibrot_iter(-1.1, 0.0, 3.0, 200) < 200);
+        assert_eq!(multibrot_iter(0.0, 0.0, 5.0, 200), 200);
+    }
+
+    // --- df64 (Rust mirror of df64.wgsl and mandelbrot_df64.wgsl) ------------
+
+    type Df = [f32; 2];
+
+    fn two_sum(a: f32, b: f32) -> Df {
+        let s = a + b;
+        let v = s - a;
+        [s, (a - (s - v)) + (b - v)]
+    }
+
+    fn quick_two_sum(a: f32, b: f32) -> Df {
+        let s = a + b;
+        [s, b - (s - a)]
+    }
+
+    fn df_split(a: f32) -> Df {
+        let t = 4097.0 * a;
+        let hi = t - (t - a);
+        [hi, a - hi]
+    }
+
+    fn two_prod(a: f32, b: f32) -> Df {
+        let p = a * b;
+        let (sa, sb) = (df_split(a), df_split(b));
+        [
+            p,
+            ((sa[0] * sb[0] - p) + sa[0] * sb[1] + sa[1] * sb[0]) + sa[1] * sb[1],
+        ]
+    }
+
+    fn df_add(a: Df, b: Df) -> Df {
+        let mut s = two_sum(a[0], b[0]);
+        let t = two_sum(a[1], b[1]);
+        s[1] += t[0];
+        s = quick_two_sum(s[0], s[1]);
+        s[1] += t[1];
+        quick_two_sum(s[0], s[1])
+    }
+
+    fn df_mul(a: Df, b: Df) -> Df {
+        let mut p = two_prod(a[0], b[0]);
+        p[1] += a[0] * b[1] + a[1] * b[0];
+        quick_two_sum(p[0], p[1])
+    }
+
+    fn df(x: f64) -> Df {
+        let hi = x as f32;
+        [hi, (x - hi as f64) as f32]
+    }
+
+    fn wide(a: Df) -> f64 {
+        a[0] as f64 + a[1] as f64
+    }
+
+    /// mandelbrot_df64.wgsl's loop, without the periodicity check.
+    fn mandelbrot_df64_iter(c: [Df; 2], max_iter: u32) -> u32 {
+        let (mut x, mut y) = ([0.0f32; 2], [0.0f32; 2]);
+        let mut i = 0;
+        while i < max_iter {
+            let (xx, yy) = (df_mul(x, x), df_mul(y, y));
+            if xx[0] + yy[0] > 4.0 {
+                break;
+            }
+            let xy = df_mul(x, y);
+            x = df_add(df_add(xx, [-yy[0], -yy[1]]), c[0]);
+            y = df_add([xy[0] * 2.0, xy[1] * 2.0], c[1]);
+            i += 1;
+        }
+        i
+    }
+
+    #[test]
+    fn df64_arithmetic_carries_about_48_bits() {
+        let values = [
+            1.0 / 3.0,
+            -0.743643887037158,
+            1.2345678901234e-3,
+            2.0f64.sqrt(),
+        ];
+        for a in values {
+            for b in values {
+                let sum = wide(df_add(df(a), df(b)));
+                let product = wide(df_mul(df(a), df(b)));
+                assert!(
+                    (sum - (a + b)).abs() <= 1e-14 * (a.abs() + b.abs()),
+                    "{a} + {b}"
+                );
+                assert!(
+                    (product - a * b).abs() <= 1e-13 * (a * b).abs(),
+                    "{a} * {b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn df64_mandelbrot_resolves_pixels_f32_cannot() {
+        let (center, zoom) = ([-0.743643887037158f64, 0.131825904205312f64], 1e9);
+        let row = (-256..256).map(|k| k as f64 / 512.0 / zoom);
+        let f32_points: std::collections::BTreeSet<u32> = row
+            .clone()
+            .map(|dx| (center[0] as f32 + dx as f32).to_bits())
+            .collect();
+        assert!(f32_points.len() <= 3, "f32 merges the row: {f32_points:?}");
+        let mut agree = 0;
+        let mut counts = std::collections::BTreeSet::new();
+        for dx in row {
+            let c = [df_add(df(center[0]), [dx as f32, 0.0]), df(center[1])];
+            let n = mandelbrot_df64_iter(c, 3000);
+            counts.insert(n);
+            let (mut x, mut y, mut i) = (0.0f64, 0.0f64, 0);
+            while i < 3000 && x * x + y * y <= 4.0 {
+                (x, y) = (x * x - y * y + center[0] + dx, 2.0 * x * y + center[1]);
+                i += 1;
+            }
+            agree += (i == n) as u32;
+        }
+        assert!(counts.len() > 8, "{counts:?}");
+        assert!(agree >= 400, "only {agree} of 512 pixels match f64");
+    }
+
+    // --- Perturbation (Rust mirror of perturbation.wgsl) ----------------------
+
+    fn cmul(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+        [a[0] * b[0] - a[1] * b[1], a[0] * b[1] + a[1] * b[0]]
+    }
+
+    fn perturbed_iter(orbit: &[[f32; 2]], d0: [f32; 2], julia: bool, max_iter: u32) -> u32 {
+        let (dc, dz) = if julia {
+            ([0.0; 2], d0)
+        } else {
+            (d0, [0.0; 2])
+        };
+        perturbed_iter_from(orbit, dc, 0, dz, max_iter)
+    }
+
+    /// The shader's loop from step `start`, where the pixel's offset is
+    /// `dz`.
+    fn perturbed_iter_from(
+        orbit: &[[f32; 2]],
+        dc: [f32; 2],
+        start: usize,
+        mut dz: [f32; 2],
+        max_iter: u32,
+    ) -> u32 {
+        let (mut n, mut i) = (start, start as u32);
+        let mut z = [orbit[n][0] + dz[0], orbit[n][1] + dz[1]];
+        let norm = |v: [f32; 2]| v[0] * v[0] + v[1] * v[1];
+        while i < max_iter {
+            if norm(z) > 4.0 {
+                break;
+            }
+            let [a, b] = [cmul(orbit[n], dz), cmul(dz, dz)];
+            dz = [2.0 * a[0] + b[0] + dc[0], 2.0 * a[1] + b[1] + dc[1]];
+            n += 1;
+            i += 1;
+            z = [orbit[n][0] + dz[0], orbit[n][1] + dz[1]];
+            if n + 1 >= orbit.len() || norm(z) < norm(dz) {
+                dz = [z[0] - orbit[0][0], z[1] - orbit[0][1]];
+                n = 0;
+            }
+        }
+        i
+    }
+
+    fn direct_iter(z0: [f64; 2], c: [f64; 2], max_iter: u32) -> u32 {
+        let [mut x, mut y] = z0;
+        for i in 0..max_iter {
+            if x * x + y * y > 4.0 {
+                return i;
+            }
+            (x, y) = (x * x - y * y + c[0], 2.0 * x * y + c[1]);
+        }
+        max_iter
+    }
+
+    #[test]
+    fn perturbation_matches_direct_iteration() {
+        use fractal_core::perturbation::{DeepPoint, Family, ReferenceOrbit};
+        let (rx, ry) = (-0.743_643_887, 0.131_825_904);
+        let reference = DeepPoint::from_f64(rx, ry);
+        let mandel = ReferenceOrbit::compute(reference, Family::Mandelbrot, 500);
+        let julia = ReferenceOrbit::compute(reference, Family::Julia([-0.8, 0.156]), 500);
+        for d in [
+            [1e-3, 0.0],
+            [0.0, -2e-3],
+            [3e-3, 1e-3],
+            [-0.05, 0.02],
+            [1e-7, 1e-7],
+        ] {
+            let p = [rx + d[0] as f64, ry + d[1] as f64];
+            let want = direct_iter([0.0; 2], p, 500);
+            let got = perturbed_iter(&mandel.points, d, false, 500);
+            assert!(got.abs_diff(want) <= 1, "mandelbrot {d:?}: {got} vs {want}");
+            let want = direct_iter(p, [-0.8, 0.156], 500);
+            let got = perturbed_iter(&julia.points, d, true, 500);
+            assert!(got.abs_diff(want) <= 1, "julia {d:?}: {got} vs {want}");
+        }
+    }
+
+    #[test]
+    fn perturbation_resolves_pixels_f32_cannot() {
+        use fractal_core::perturbation::{DeepPoint, Family, ReferenceOrbit};
+        let reference = DeepPoint::parse(
+            "-0.743643887037158704752191506114774",
+            "0.131825904205311970493132056385139",
+        )
+        .unwrap();
+        let orbit = ReferenceOrbit::compute(reference, Family::Mandelbrot, 5000);
+        // A row of pixels 1e-12 apart: one f32 point, many iteration counts.
+        let counts: std::collections::BTreeSet<u32> = (0..64)
+            .map(|k| perturbed_iter(&orbit.points, [k as f32 * 1e-12, 0.0], false, 5000))
+            .collect();
+        assert!(counts.len() > 1, "{counts:?}");
+    }
+
+    #[test]
+    fn series_skip_counts_as_well_as_iterating_from_the_start() {
+        use fractal_core::perturbation::{DeepPoint, Family, ReferenceOrbit};
+        let reference = DeepPoint::parse(
+            "-0.743643887037158704752191506114774",
+            "0.131825904205311970493132056385139",
+        )
+        .unwrap();
+        let orbit = ReferenceOrbit::compute(reference, Family::Mandelbrot, 5000);
+        let radius = 64e-12;
+        let skip = orbit.series_skip(radius, 5000).expect("a skip this deep");
+        assert!(skip.n > 100, "skips only {}", skip.n);
+        // Scaled as `PerturbationUniforms` does, evaluated as the shader does.
+        let mut r = radius;
+        let [a, b, c] = skip.coefficients.map(|[x, y]| {
+            let term = [(x * r) as f32, (y * r) as f32];
+            r *= radius;
+            term
+        });
+        let add = |p: [f32; 2], q: [f32; 2]| [p[0] + q[0], p[1] + q[1]];
+        // f32 perturbation is itself off for a few chaotic pixels, so both
+        // are held to the count of each pixel's own high-precision orbit.
+        let (mut series_right, mut full_right) = (0, 0);
+        for k in 0..64 {
+            let d = [k as f32 * 1e-12, -(k as f32) * 0.5e-12];
+            let t = [d[0] / radius as f32, d[1] / radius as f32];
+            let dz = cmul(t, add(a, cmul(t, add(b, cmul(t, c)))));
+            let got = perturbed_iter_from(&orbit.points, d, skip.n as usize, dz, 5000);
+            let full = perturbed_iter(&orbit.points, d, false, 5000);
+            let mut px = reference;
+            px.shift(d[0] as f64, d[1] as f64);
+            let truth = ReferenceOrbit::compute(px, Family::Mandelbrot, 5000)
+                .points
+                .len() as u32
+                - 1;
+            series_right += (got.abs_diff(truth) <= 1) as u32;
+            full_right += (full.abs_diff(truth) <= 1) as u32;
+        }
+        assert!(
+            series_right >= full_right,
+            "{series_right} < {full_right} of 64"
+        );
+        assert!(series_right >= 56, "{series_right} of 64");
+    }
+
     // --- GPU smoke test (requires adapter, skipped in CI) --------------------
 
     /// Verify GeneratorPass::new compiles all four shaders on the actual device.
@@ -378,4 +1999,47 @@ mod tests {
             let _pass = super::GeneratorPass::new(&ctx.device, 64, 64);
         });
     }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn set_frame_resizes_the_source() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut pass = super::GeneratorPass::new(&ctx.device, 64, 64);
+            pass.set_frame(&ctx.device, &ctx.queue, 4, 2, &[255; 4 * 4 * 2]);
+            assert_eq!(pass.source_tex.size().width, 4);
+            assert_eq!(pass.source_tex.size().height, 2);
+        });
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn set_source_swaps_pipeline_and_keeps_old_on_error() {
+        use fractal_core::GeneratorKind;
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut pass = super::GeneratorPass::new(&ctx.device, 64, 64);
+            let julia = super::GeneratorPass::default_source(GeneratorKind::Julia);
+            assert!(pass
+                .set_source(&ctx.device, GeneratorKind::Mandelbrot, julia)
+                .is_ok());
+            let err = pass
+                .set_source(&ctx.device, GeneratorKind::Mandelbrot, "fn main( {")
+                .unwrap_err();
+            assert_eq!(err.line, Some(1));
+        });
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn set_formula_compiles_once_per_formula() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut pass = super::GeneratorPass::new(&ctx.device, 64, 64);
+            let formula = super::Formula::parse("z*z*z + c*sin(z)").unwrap();
+            pass.set_formula(&ctx.device, &formula).unwrap();
+            assert_eq!(pass.formula_source, super::formula_source(&formula));
+            pass.set_formula(&ctx.device, &formula).unwrap();
+        });
+    }
 }
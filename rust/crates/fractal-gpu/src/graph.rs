@@ -0,0 +1,131 @@
+use crate::backend::Deck;
+
+// ---------------------------------------------------------------------------
+// FrameGraph — which pass wrote which texture this frame
+// ---------------------------------------------------------------------------
+
+/// A texture a frame's passes read or write.  The backend resolves each to
+/// a concrete view; the graph only tracks which ones hold this frame's data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    /// A deck's generator output.
+    Generated(Deck),
+    /// The last effect's output in a deck's ping-pong pair.
+    Chained(Deck),
+    /// Deck A and deck B blended by the crossfade pass.
+    Mixed,
+    /// The text / logo overlay composited over the shown texture.
+    Overlaid,
+}
+
+/// One recorded pass: what it read and what it wrote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassNode {
+    pub name: &'static str,
+    pub inputs: Vec<Slot>,
+    pub output: Slot,
+}
+
+/// The passes of one frame in recording order.  Each pass declares its
+/// output and takes its inputs from the graph, so the texture the next pass
+/// reads — and the one `present` draws — follows from what actually ran
+/// instead of flags threaded through the frame.
+#[derive(Debug, Default, Clone)]
+pub struct FrameGraph {
+    passes: Vec<PassNode>,
+}
+
+impl FrameGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `name` read `inputs` and wrote `output`.
+    pub fn add(&mut self, name: &'static str, inputs: &[Slot], output: Slot) {
+        self.passes.push(PassNode {
+            name,
+            inputs: inputs.to_vec(),
+            output,
+        });
+    }
+
+    /// Every pass recorded so far, in order.
+    pub fn passes(&self) -> &[PassNode] {
+        &self.passes
+    }
+
+    /// The newest texture written for `deck` alone: its chain output, or
+    /// its generator output when no chain ran after it.
+    pub fn deck_output(&self, deck: Deck) -> Slot {
+        self.last_output(|slot| slot == Slot::Generated(deck) || slot == Slot::Chained(deck))
+            .unwrap_or(Slot::Generated(deck))
+    }
+
+    /// The texture the frame shows with `deck` selected: the overlay or
+    /// crossfade output when either ran, otherwise `deck`'s own output.
+    pub fn shown(&self, deck: Deck) -> Slot {
+        self.last_output(|slot| matches!(slot, Slot::Mixed | Slot::Overlaid))
+            .unwrap_or_else(|| self.deck_output(deck))
+    }
+
+    fn last_output(&self, wanted: impl Fn(Slot) -> bool) -> Option<Slot> {
+        self.passes
+            .iter()
+            .rev()
+            .map(|p| p.output)
+            .find(|&s| wanted(s))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_empty_frame_shows_deck_a_generator() {
+        let graph = FrameGraph::new();
+        assert_eq!(graph.shown(Deck::A), Slot::Generated(Deck::A));
+        assert!(graph.passes().is_empty());
+    }
+
+    #[test]
+    fn chain_output_replaces_generator_output() {
+        let mut graph = FrameGraph::new();
+        graph.add("generator", &[], Slot::Generated(Deck::A));
+        assert_eq!(graph.deck_output(Deck::A), Slot::Generated(Deck::A));
+        graph.add("chain", &[Slot::Generated(Deck::A)], Slot::Chained(Deck::A));
+        assert_eq!(graph.deck_output(Deck::A), Slot::Chained(Deck::A));
+        assert_eq!(graph.shown(Deck::A), Slot::Chained(Deck::A));
+    }
+
+    #[test]
+    fn decks_are_tracked_separately() {
+        let mut graph = FrameGraph::new();
+        graph.add("generator", &[], Slot::Generated(Deck::A));
+        graph.add("chain", &[Slot::Generated(Deck::A)], Slot::Chained(Deck::A));
+        graph.add("generator", &[], Slot::Generated(Deck::B));
+        assert_eq!(graph.deck_output(Deck::A), Slot::Chained(Deck::A));
+        assert_eq!(graph.deck_output(Deck::B), Slot::Generated(Deck::B));
+        assert_eq!(graph.shown(Deck::B), Slot::Generated(Deck::B));
+    }
+
+    #[test]
+    fn crossfade_then_overlay_is_shown_whichever_deck_is_selected() {
+        let mut graph = FrameGraph::new();
+        graph.add("generator", &[], Slot::Generated(Deck::A));
+        graph.add("generator", &[], Slot::Generated(Deck::B));
+        graph.add(
+            "crossfade",
+            &[Slot::Generated(Deck::A), Slot::Generated(Deck::B)],
+            Slot::Mixed,
+        );
+        assert_eq!(graph.shown(Deck::A), Slot::Mixed);
+        graph.add("overlay", &[Slot::Mixed], Slot::Overlaid);
+        assert_eq!(graph.shown(Deck::B), Slot::Overlaid);
+        assert_eq!(graph.passes()[3].inputs, vec![Slot::Mixed]);
+    }
+}
@@ -1,4 +1,16 @@
+pub mod aux_textures;
+pub mod backend;
 pub mod context;
 pub mod effect_pipeline;
+pub mod embed;
 pub mod generator_pipeline;
+pub mod graph;
+pub mod mix_pipeline;
+pub mod overlay_pipeline;
+pub mod pan;
+pub mod preview;
+pub mod probe;
 pub mod renderer;
+pub mod shader;
+pub mod stats;
+pub mod thumbnail;
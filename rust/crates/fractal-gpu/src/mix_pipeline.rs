@@ -0,0 +1,221 @@
+use wgpu::{BindGroupLayout, Buffer, ComputePipeline, Device, Queue, Texture, TextureView};
+
+use crate::stats;
+
+/// Params of the crossfade pass (binding 0).  Must match `MixParams` in
+/// `crossfade.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct MixParams {
+    /// 0 = deck A only, 1 = deck B only.
+    pub amount: f32,
+    pub _pad: [f32; 3],
+}
+
+impl MixParams {
+    pub fn new(amount: f32) -> Self {
+        Self {
+            amount: amount.clamp(0.0, 1.0),
+            _pad: [0.0; 3],
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MixPass — blends two decks' final textures into one
+// ---------------------------------------------------------------------------
+
+/// Crossfades deck A's and deck B's final textures into its own
+/// `rgba16float` output, which the output pass then presents.
+pub struct MixPass {
+    pipeline: ComputePipeline,
+    bgl: BindGroupLayout,
+    params_buf: Buffer,
+
+    pub output_tex: Texture,
+    pub output_view: TextureView,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MixPass {
+    pub fn new(device: &Device, width: u32, height: u32) -> Self {
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("mix_bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(1),
+                texture_entry(2),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: wgpu::TextureFormat::Rgba16Float,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("mix_pl"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("crossfade"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/crossfade.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("crossfade"),
+            layout: Some(&layout),
+            module: &module,
+            entry_point: "main",
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let params_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("mix_params"),
+            size: std::mem::size_of::<MixParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let output_tex = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("mix_output"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::STORAGE_BINDING
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let output_view = output_tex.create_view(&Default::default());
+
+        Self {
+            pipeline,
+            bgl,
+            params_buf,
+            output_tex,
+            output_view,
+            width,
+            height,
+        }
+    }
+
+    /// Record the blend of `deck_a` and `deck_b` by `amount` into
+    /// `self.output_tex`.
+    pub fn dispatch(
+        &self,
+        device: &Device,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &Queue,
+        deck_a: &TextureView,
+        deck_b: &TextureView,
+        amount: f32,
+    ) {
+        stats::write_buffer(
+            queue,
+            &self.params_buf,
+            0,
+            bytemuck::bytes_of(&MixParams::new(amount)),
+        );
+        let bind_group = stats::create_bind_group(
+            device,
+            &wgpu::BindGroupDescriptor {
+                label: Some("mix_bg"),
+                layout: &self.bgl,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: self.params_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(deck_a),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::TextureView(deck_b),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: wgpu::BindingResource::TextureView(&self.output_view),
+                    },
+                ],
+            },
+        );
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("mix_pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        let wg = 8u32;
+        stats::dispatch_workgroups(
+            &mut pass,
+            self.width.div_ceil(wg),
+            self.height.div_ceil(wg),
+            1,
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crossfade_wgsl_is_valid() {
+        crate::shader::validate_wgsl(include_str!("../shaders/crossfade.wgsl"))
+            .expect("crossfade.wgsl");
+    }
+
+    #[test]
+    fn mix_params_are_16_bytes_and_clamped() {
+        assert_eq!(std::mem::size_of::<MixParams>(), 16);
+        assert_eq!(MixParams::new(1.5).amount, 1.0);
+        assert_eq!(MixParams::new(-0.5).amount, 0.0);
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn mix_pass_new_does_not_panic() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let _pass = MixPass::new(&ctx.device, 64, 64);
+        });
+    }
+}
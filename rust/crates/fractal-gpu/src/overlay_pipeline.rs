@@ -0,0 +1,302 @@
+use wgpu::{
+    BindGroupLayout, Buffer, ComputePipeline, Device, Queue, Sampler, Texture, TextureView,
+};
+
+use crate::{generator_pipeline::source_texture, stats};
+
+/// Params of the overlay pass (binding 0).  Must match `OverlayParams` in
+/// `overlay.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct OverlayParams {
+    /// Centre of the overlay, 0–1 across and down the frame.
+    pub position: [f32; 2],
+    /// Overlay height as a fraction of the frame height.
+    pub scale: f32,
+    pub opacity: f32,
+}
+
+impl OverlayParams {
+    pub fn new(position: [f32; 2], scale: f32, opacity: f32) -> Self {
+        Self {
+            position,
+            scale: scale.max(0.0),
+            opacity: opacity.clamp(0.0, 1.0),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OverlayPass — composites a text or logo image over the final texture
+// ---------------------------------------------------------------------------
+
+/// Draws an uploaded RGBA image (rendered text or a logo) over a frame's
+/// final texture into its own `rgba16float` output, which the output pass
+/// then presents.
+pub struct OverlayPass {
+    pipeline: ComputePipeline,
+    bgl: BindGroupLayout,
+    params_buf: Buffer,
+    /// The overlay image (sRGB, straight alpha), binding 2.
+    image_tex: Texture,
+    image_view: TextureView,
+    sampler: Sampler,
+
+    pub output_tex: Texture,
+    pub output_view: TextureView,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl OverlayPass {
+    pub fn new(device: &Device, width: u32, height: u32) -> Self {
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("overlay_bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: wgpu::TextureFormat::Rgba16Float,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("overlay_pl"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("overlay"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/overlay.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("overlay"),
+            layout: Some(&layout),
+            module: &module,
+            entry_point: "main",
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let params_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("overlay_params"),
+            size: std::mem::size_of::<OverlayParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        // 1×1 transparent until the app uploads an image.
+        let (image_tex, image_view) = source_texture(device, "overlay_image", 1, 1);
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("overlay_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let (output_tex, output_view) = output_texture(device, width, height);
+
+        Self {
+            pipeline,
+            bgl,
+            params_buf,
+            image_tex,
+            image_view,
+            sampler,
+            output_tex,
+            output_view,
+            width,
+            height,
+        }
+    }
+
+    /// Reallocate the output for a new frame size, keeping the image.
+    pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
+        (self.output_tex, self.output_view) = output_texture(device, width, height);
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Upload a tightly packed sRGB RGBA8 `width`×`height` image with
+    /// straight alpha, resizing the texture as needed.  An image past the
+    /// device's texture size limit is dropped; callers shrink it first.
+    pub fn set_image(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) {
+        let max = device.limits().max_texture_dimension_2d;
+        if width > max || height > max {
+            log::warn!("dropping {width}×{height} overlay image: past the {max} texture limit");
+            return;
+        }
+        let size = self.image_tex.size();
+        if (size.width, size.height) != (width, height) {
+            (self.image_tex, self.image_view) =
+                source_texture(device, "overlay_image", width, height);
+        }
+        queue.write_texture(
+            self.image_tex.as_image_copy(),
+            rgba,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: Some(height),
+            },
+            self.image_tex.size(),
+        );
+    }
+
+    /// Record the overlay composited over `base` into `self.output_tex`.
+    pub fn dispatch(
+        &self,
+        device: &Device,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &Queue,
+        base: &TextureView,
+        params: &OverlayParams,
+    ) {
+        stats::write_buffer(queue, &self.params_buf, 0, bytemuck::bytes_of(params));
+        let bind_group = stats::create_bind_group(
+            device,
+            &wgpu::BindGroupDescriptor {
+                label: Some("overlay_bg"),
+                layout: &self.bgl,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: self.params_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(base),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::TextureView(&self.image_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: wgpu::BindingResource::TextureView(&self.output_view),
+                    },
+                ],
+            },
+        );
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("overlay_pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        let wg = 8u32;
+        stats::dispatch_workgroups(
+            &mut pass,
+            self.width.div_ceil(wg),
+            self.height.div_ceil(wg),
+            1,
+        );
+    }
+}
+
+fn output_texture(device: &Device, width: u32, height: u32) -> (Texture, TextureView) {
+    let tex = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("overlay_output"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba16Float,
+        usage: wgpu::TextureUsages::STORAGE_BINDING
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = tex.create_view(&Default::default());
+    (tex, view)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_wgsl_is_valid() {
+        crate::shader::validate_wgsl(include_str!("../shaders/overlay.wgsl"))
+            .expect("overlay.wgsl");
+    }
+
+    #[test]
+    fn overlay_params_are_16_bytes_and_clamped() {
+        assert_eq!(std::mem::size_of::<OverlayParams>(), 16);
+        let p = OverlayParams::new([0.5, 0.9], -1.0, 1.5);
+        assert_eq!((p.scale, p.opacity), (0.0, 1.0));
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn overlay_pass_keeps_its_image_across_resize() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut pass = OverlayPass::new(&ctx.device, 64, 64);
+            pass.set_image(&ctx.device, &ctx.queue, 4, 2, &[255; 4 * 4 * 2]);
+            pass.resize(&ctx.device, 32, 16);
+            assert_eq!(pass.output_tex.size().width, 32);
+            assert_eq!(pass.image_tex.size().width, 4);
+        });
+    }
+}
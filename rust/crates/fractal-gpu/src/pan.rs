@@ -0,0 +1,435 @@
+use fractal_core::{perturbation::DeepZoom, projection::Projection, GeneratorKind};
+
+use crate::context::Uniforms;
+
+// ---------------------------------------------------------------------------
+// Pan reuse — recompute only the pixels a pan uncovered
+// ---------------------------------------------------------------------------
+//
+// A slow pan over a high-iteration view recomputes a whole frame of which
+// nearly every pixel was on screen the frame before.  When the next frame
+// differs from the last only in its centre, the generator pass shifts its
+// last output by the pan and dispatches just the strips along the edges it
+// uncovered.  The centre is snapped to the pixel lattice of the last full
+// render so shifted pixels land exactly on pixels; once the view comes to
+// rest off that lattice, one full frame puts it on the exact centre.
+
+/// Most of the frame a pan may uncover before a full render is cheaper
+/// than the strips plus the copy.
+const MAX_EXPOSED: f64 = 0.5;
+
+/// How far, in pixels, the f32 centre may round from its lattice point
+/// before the strips would visibly misalign with the shifted pixels.
+const SNAP_TOLERANCE: f64 = 0.125;
+
+/// A rectangle of output pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strip {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// What the generator pass last wrote to its output.
+#[derive(Debug, Clone)]
+pub struct LastRender {
+    pub kind: GeneratorKind,
+    /// The uniforms it rendered with, centre snapped.
+    pub uniforms: Uniforms,
+    /// The centre asked for, before snapping.
+    pub requested: [f32; 2],
+    pub extension: Vec<u8>,
+    /// Centre of the last full render, which the lattice is anchored at.
+    pub anchor: [f64; 2],
+    /// Whole pixels the output has been shifted since that render.
+    pub offset: [i64; 2],
+}
+
+impl LastRender {
+    /// The record of a full render of `kind` with `uniforms`.
+    pub fn full(kind: GeneratorKind, uniforms: &Uniforms, extension: &[u8]) -> Self {
+        Self {
+            kind,
+            uniforms: *uniforms,
+            requested: uniforms.center,
+            extension: extension.to_vec(),
+            anchor: uniforms.center.map(f64::from),
+            offset: [0, 0],
+        }
+    }
+}
+
+/// How to produce the next frame.
+#[derive(Debug, Clone)]
+pub enum PanPlan {
+    /// Render every pixel.
+    Full,
+    /// Shift the last output so pixel `p` takes what was at `p + shift`,
+    /// then render `strips` with `uniforms`, whose centre is snapped.  No
+    /// shift and no strips leaves the output as it is.
+    Reuse {
+        shift: [i32; 2],
+        strips: Vec<Strip>,
+        uniforms: Uniforms,
+        /// `LastRender::offset` after this frame.
+        offset: [i64; 2],
+    },
+}
+
+/// Whether `kind` shades each pixel from its own coordinate alone, without
+/// reading the time, so a pixel of the last frame is still right after a
+/// pan.  The deep kernels and perturbation aren't among them; see
+/// [`plan`].
+pub fn reusable(kind: GeneratorKind) -> bool {
+    matches!(
+        kind,
+        GeneratorKind::Mandelbrot
+            | GeneratorKind::Julia
+            | GeneratorKind::BurningShip
+            | GeneratorKind::Celtic
+            | GeneratorKind::Buffalo
+            | GeneratorKind::Multibrot
+            | GeneratorKind::Hybrid(_)
+            | GeneratorKind::Kleinian
+            | GeneratorKind::CustomFormula
+    )
+}
+
+/// Plan the next frame of `kind` after `last`.  Reuse needs a
+/// [`reusable`] kind on its f32 kernel (below [`DeepZoom::THRESHOLD`]),
+/// the flat projection, and everything but the centre and time unchanged.
+pub fn plan(
+    last: Option<&LastRender>,
+    kind: GeneratorKind,
+    uniforms: &Uniforms,
+    extension: &[u8],
+) -> PanPlan {
+    let Some(last) = last else {
+        return PanPlan::Full;
+    };
+    let prev = &last.uniforms;
+    let same_view = last.kind == kind
+        && last.extension == extension
+        && prev.resolution == uniforms.resolution
+        && prev.zoom == uniforms.zoom
+        && prev.max_iter == uniforms.max_iter
+        && prev.projection == uniforms.projection
+        && prev.smoothing == uniforms.smoothing
+        && prev.samples == uniforms.samples;
+    if !same_view
+        || !reusable(kind)
+        || uniforms.projection != Projection::Flat.gpu_index()
+        || uniforms.zoom >= DeepZoom::THRESHOLD
+    {
+        return PanPlan::Full;
+    }
+
+    // Plane units per pixel of the flat projection: a window height spans
+    // 2 / zoom.
+    let pitch = 2.0 / (f64::from(uniforms.resolution[1]) * f64::from(uniforms.zoom));
+    if !pitch.is_finite() || pitch <= 0.0 {
+        return PanPlan::Full;
+    }
+    let mut offset = [0i64; 2];
+    let mut center = [0f32; 2];
+    for axis in 0..2 {
+        let px = (f64::from(uniforms.center[axis]) - last.anchor[axis]) / pitch;
+        if !px.is_finite() || px.abs() > f64::from(u32::MAX) {
+            return PanPlan::Full;
+        }
+        offset[axis] = px.round() as i64;
+        let exact = last.anchor[axis] + offset[axis] as f64 * pitch;
+        center[axis] = exact as f32;
+        if (f64::from(center[axis]) - exact).abs() > SNAP_TOLERANCE * pitch {
+            return PanPlan::Full;
+        }
+    }
+    // At rest off the lattice: land on the exact centre.
+    if uniforms.center == last.requested && center != uniforms.center {
+        return PanPlan::Full;
+    }
+
+    let shift = [offset[0] - last.offset[0], offset[1] - last.offset[1]];
+    let [width, height] = uniforms.resolution.map(|r| r as u32);
+    let Some(strips) = exposed(width, height, shift) else {
+        return PanPlan::Full;
+    };
+    let mut snapped = *uniforms;
+    snapped.center = center;
+    snapped.center_df = [center[0], 0.0, center[1], 0.0];
+    snapped.center64 = center.map(f64::from);
+    PanPlan::Reuse {
+        shift: shift.map(|s| s as i32),
+        strips,
+        uniforms: snapped,
+        offset,
+    }
+}
+
+/// The strips of a `width`×`height` frame shifted by `shift` that no
+/// pixel of the last frame covers: a full-height one on the side `x`
+/// moved towards and a row strip beside it on the `y` side.  `None` when
+/// they would be over [`MAX_EXPOSED`] of the frame.
+pub fn exposed(width: u32, height: u32, shift: [i64; 2]) -> Option<Vec<Strip>> {
+    let (w, h) = (i64::from(width), i64::from(height));
+    let (dx, dy) = (shift[0].abs(), shift[1].abs());
+    if dx >= w || dy >= h {
+        return None;
+    }
+    let area = dx * h + dy * (w - dx);
+    if area as f64 > MAX_EXPOSED * (w * h) as f64 {
+        return None;
+    }
+    // Pixel `p` takes `p + shift`, so a positive shift uncovers the far
+    // edge and a negative one the near edge.
+    let edge = |s: i64, len: i64| if s > 0 { len - s } else { 0 };
+    let mut strips = Vec::new();
+    if dx > 0 {
+        strips.push(Strip {
+            x: edge(shift[0], w) as u32,
+            y: 0,
+            width: dx as u32,
+            height,
+        });
+    }
+    if dy > 0 {
+        strips.push(Strip {
+            x: if shift[0] < 0 { dx as u32 } else { 0 },
+            y: edge(shift[1], h) as u32,
+            width: (w - dx) as u32,
+            height: dy as u32,
+        });
+    }
+    Some(strips)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fractal_core::Params;
+
+    fn view(center: [f32; 2]) -> Uniforms {
+        let params = Params {
+            center_x: center[0],
+            center_y: center[1],
+            zoom: 100.0,
+            max_iter: 2000,
+            ..Params::default()
+        };
+        Uniforms::from_params(&params, 200, 100)
+    }
+
+    /// Plane units per pixel of [`view`].
+    const PITCH: f32 = 2.0 / (100.0 * 100.0);
+
+    fn reuse(plan: PanPlan) -> ([i32; 2], Vec<Strip>, Uniforms) {
+        match plan {
+            PanPlan::Reuse {
+                shift,
+                strips,
+                uniforms,
+                ..
+            } => (shift, strips, uniforms),
+            PanPlan::Full => panic!("expected reuse"),
+        }
+    }
+
+    // --- Exposed strips ---
+
+    #[test]
+    fn a_pan_right_and_down_uncovers_the_far_edges() {
+        let strips = exposed(200, 100, [3, 2]).unwrap();
+        assert_eq!(
+            strips,
+            vec![
+                Strip {
+                    x: 197,
+                    y: 0,
+                    width: 3,
+                    height: 100
+                },
+                Strip {
+                    x: 0,
+                    y: 98,
+                    width: 197,
+                    height: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn a_pan_left_and_up_uncovers_the_near_edges() {
+        let strips = exposed(200, 100, [-3, -2]).unwrap();
+        assert_eq!(
+            strips,
+            vec![
+                Strip {
+                    x: 0,
+                    y: 0,
+                    width: 3,
+                    height: 100
+                },
+                Strip {
+                    x: 3,
+                    y: 0,
+                    width: 197,
+                    height: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn strips_cover_each_uncovered_pixel_once() {
+        for shift in [[5, -7], [-1, 0], [0, 4], [0, 0], [-10, 6]] {
+            let strips = exposed(40, 30, shift).unwrap();
+            let mut hits = vec![0u8; 40 * 30];
+            for s in &strips {
+                for y in s.y..s.y + s.height {
+                    for x in s.x..s.x + s.width {
+                        hits[(y * 40 + x) as usize] += 1;
+                    }
+                }
+            }
+            for (i, &n) in hits.iter().enumerate() {
+                let (x, y) = ((i % 40) as i64 + shift[0], (i / 40) as i64 + shift[1]);
+                let covered = (0..40).contains(&x) && (0..30).contains(&y);
+                assert_eq!(n, u8::from(!covered), "pixel {i} for shift {shift:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn a_long_jump_renders_in_full() {
+        assert!(exposed(200, 100, [120, 0]).is_none());
+        assert!(exposed(200, 100, [0, -100]).is_none());
+        assert!(exposed(200, 100, [50, 20]).is_some());
+    }
+
+    // --- Planning ---
+
+    #[test]
+    fn nothing_to_reuse_before_the_first_render() {
+        let u = view([0.0, 0.0]);
+        assert!(matches!(
+            plan(None, GeneratorKind::Mandelbrot, &u, &[]),
+            PanPlan::Full
+        ));
+    }
+
+    #[test]
+    fn an_unchanged_view_is_left_as_it_is() {
+        let u = view([-0.5, 0.0]);
+        let last = LastRender::full(GeneratorKind::Mandelbrot, &u, &[1, 2]);
+        let mut next = u;
+        next.time += 1.0;
+        let (shift, strips, _) =
+            reuse(plan(Some(&last), GeneratorKind::Mandelbrot, &next, &[1, 2]));
+        assert_eq!(shift, [0, 0]);
+        assert!(strips.is_empty());
+    }
+
+    #[test]
+    fn a_pan_snaps_to_whole_pixels_of_the_last_render() {
+        let u = view([-0.5, 0.0]);
+        let last = LastRender::full(GeneratorKind::Julia, &u, &[]);
+        let next = view([-0.5 + 3.4 * PITCH, -1.6 * PITCH]);
+        let (shift, strips, snapped) = reuse(plan(Some(&last), GeneratorKind::Julia, &next, &[]));
+        assert_eq!(shift, [3, -2]);
+        assert_eq!(strips.len(), 2);
+        assert!((snapped.center[0] - (-0.5 + 3.0 * PITCH)).abs() < PITCH * 0.01);
+        assert!((snapped.center[1] + 2.0 * PITCH).abs() < PITCH * 0.01);
+        assert_eq!(snapped.center64[0], f64::from(snapped.center[0]));
+    }
+
+    #[test]
+    fn shifts_add_up_from_the_anchor() {
+        let u = view([0.0, 0.0]);
+        let mut last = LastRender::full(GeneratorKind::Mandelbrot, &u, &[]);
+        let PanPlan::Reuse {
+            offset, uniforms, ..
+        } = plan(
+            Some(&last),
+            GeneratorKind::Mandelbrot,
+            &view([2.0 * PITCH, 0.0]),
+            &[],
+        )
+        else {
+            panic!("expected reuse");
+        };
+        last.uniforms = uniforms;
+        last.requested = uniforms.center;
+        last.offset = offset;
+        let next = view([5.0 * PITCH, 0.0]);
+        let (shift, ..) = reuse(plan(Some(&last), GeneratorKind::Mandelbrot, &next, &[]));
+        assert_eq!(shift, [3, 0]);
+    }
+
+    #[test]
+    fn coming_to_rest_off_the_lattice_renders_in_full() {
+        let u = view([0.0, 0.0]);
+        let mut last = LastRender::full(GeneratorKind::Mandelbrot, &u, &[]);
+        let next = view([2.3 * PITCH, 0.0]);
+        let PanPlan::Reuse {
+            uniforms, offset, ..
+        } = plan(Some(&last), GeneratorKind::Mandelbrot, &next, &[])
+        else {
+            panic!("expected reuse");
+        };
+        last.uniforms = uniforms;
+        last.requested = next.center;
+        last.offset = offset;
+        assert!(matches!(
+            plan(Some(&last), GeneratorKind::Mandelbrot, &next, &[]),
+            PanPlan::Full
+        ));
+    }
+
+    #[test]
+    fn any_other_change_renders_in_full() {
+        let u = view([0.0, 0.0]);
+        let last = LastRender::full(GeneratorKind::Mandelbrot, &u, &[0]);
+        let full = |kind, next: Uniforms, ext: &[u8]| {
+            matches!(plan(Some(&last), kind, &next, ext), PanPlan::Full)
+        };
+        assert!(full(GeneratorKind::Julia, u, &[0]));
+        assert!(full(GeneratorKind::Mandelbrot, u, &[1]));
+        assert!(full(GeneratorKind::Mandelbrot, u.with_samples(4), &[0]));
+        assert!(full(
+            GeneratorKind::Mandelbrot,
+            u.with_smoothing(false),
+            &[0]
+        ));
+        assert!(full(
+            GeneratorKind::Mandelbrot,
+            u.with_projection(Projection::Dome),
+            &[0]
+        ));
+        let mut zoomed = u;
+        zoomed.zoom *= 1.01;
+        assert!(full(GeneratorKind::Mandelbrot, zoomed, &[0]));
+    }
+
+    #[test]
+    fn time_driven_and_deep_kernels_always_render_in_full() {
+        let u = view([0.0, 0.0]);
+        let last = LastRender::full(GeneratorKind::Plasma, &u, &[]);
+        assert!(matches!(
+            plan(Some(&last), GeneratorKind::Plasma, &u, &[]),
+            PanPlan::Full
+        ));
+        let mut deep = u;
+        deep.zoom = DeepZoom::THRESHOLD;
+        let last = LastRender::full(GeneratorKind::Mandelbrot, &deep, &[]);
+        assert!(matches!(
+            plan(Some(&last), GeneratorKind::Mandelbrot, &deep, &[]),
+            PanPlan::Full
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+use std::sync::{Arc, OnceLock};
+
+use fractal_core::projection::Projection;
+use wgpu::{Buffer, CommandEncoder, Device, Queue, TextureView};
+
+use crate::renderer::OutputPass;
+
+/// Format of the downscaled preview; matches the sRGB swapchain so the
+/// readback bytes are what the window shows.
+const PREVIEW_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Bytes per `rgba8` texel.
+const TEXEL_BYTES: u32 = 4;
+
+/// Fit `width × height` into `max_width` columns, keeping the aspect ratio.
+/// Never upscales; both sides are at least one pixel.
+pub fn preview_size(width: u32, height: u32, max_width: u32) -> (u32, u32) {
+    let width = width.max(1);
+    let height = height.max(1);
+    if width <= max_width {
+        return (width, height);
+    }
+    let scaled = (height as u64 * max_width as u64 + width as u64 / 2) / width as u64;
+    (max_width.max(1), (scaled as u32).max(1))
+}
+
+/// Where a capture is in its copy → map → read cycle.  Shared with the
+/// probe pass, which reads back the same way.
+pub(crate) enum Readback {
+    Idle,
+    /// The copy was recorded; the buffer is mapped once it's submitted.
+    Copied,
+    /// `map_async` in flight; holds `Ok(())` / `Err(())` when it completes.
+    Mapping(Arc<OnceLock<Result<(), ()>>>),
+}
+
+impl Readback {
+    /// Advance the readback of `buffer` without blocking, mapping it once
+    /// the copy is submitted.  `Some(Ok(()))` when it is mapped and ready to
+    /// read (the caller unmaps it); `what` names it in failure logs.
+    pub(crate) fn poll(
+        &mut self,
+        device: &Device,
+        buffer: &Buffer,
+        what: &'static str,
+    ) -> Option<Result<(), ()>> {
+        if let Readback::Copied = self {
+            let done = Arc::new(OnceLock::new());
+            let signal = Arc::clone(&done);
+            buffer.slice(..).map_async(wgpu::MapMode::Read, move |r| {
+                let _ = signal.set(r.map_err(|e| log::warn!("{what} readback failed: {e}")));
+            });
+            *self = Readback::Mapping(done);
+        }
+        let Readback::Mapping(done) = self else {
+            return None;
+        };
+        device.poll(wgpu::Maintain::Poll);
+        let result = *done.get()?;
+        *self = Readback::Idle;
+        Some(result)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PreviewCapture — downscaled copy of the output with non-blocking readback
+// ---------------------------------------------------------------------------
+
+/// Draws the final texture into a small sRGB target (through the same
+/// fullscreen pass as the window, so the dome mask is included) and reads
+/// it back without stalling the frame loop.
+pub struct PreviewCapture {
+    output: OutputPass,
+    texture: wgpu::Texture,
+    view: TextureView,
+    readback: Buffer,
+    width: u32,
+    height: u32,
+    state: Readback,
+}
+
+impl PreviewCapture {
+    pub fn new(device: &Device, width: u32, height: u32) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("preview"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: PREVIEW_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("preview_readback"),
+            size: padded_bytes_per_row(width) as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        Self {
+            output: OutputPass::new(device, PREVIEW_FORMAT),
+            texture,
+            view,
+            readback,
+            width,
+            height,
+            state: Readback::Idle,
+        }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// A capture is recorded or being read back; `record` would be ignored.
+    pub fn is_busy(&self) -> bool {
+        !matches!(self.state, Readback::Idle)
+    }
+
+    /// Record the downscale and copy into `encoder`.  Call `poll` after the
+    /// encoder is submitted.  Ignored while a previous capture is pending.
+    pub fn record(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        source: &TextureView,
+        projection: Projection,
+    ) {
+        if self.is_busy() {
+            return;
+        }
+        self.output
+            .draw(device, queue, encoder, source, &self.view, projection);
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &self.readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row(self.width)),
+                    rows_per_image: Some(self.height),
+                },
+            },
+            wgpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.state = Readback::Copied;
+    }
+
+    /// Advance the readback without blocking.  Returns the finished capture
+    /// as `width * height * 4` bytes of tightly packed sRGB RGBA.
+    pub fn poll(&mut self, device: &Device) -> Option<Vec<u8>> {
+        self.state.poll(device, &self.readback, "preview")?.ok()?;
+        let pixels = {
+            let data = self.readback.slice(..).get_mapped_range();
+            unpad_rows(&data, self.width, self.height)
+        };
+        self.readback.unmap();
+        Some(pixels)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Row helpers (pure — unit tested below)
+// ---------------------------------------------------------------------------
+
+/// Row pitch for a `width`-texel `rgba8` copy, rounded up to wgpu's
+/// 256-byte `COPY_BYTES_PER_ROW_ALIGNMENT`.
+fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded = width * TEXEL_BYTES;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    unpadded.div_ceil(align) * align
+}
+
+/// Strip the row padding from a mapped readback.
+fn unpad_rows(data: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let row = (width * TEXEL_BYTES) as usize;
+    let padded = padded_bytes_per_row(width) as usize;
+    (0..height as usize)
+        .flat_map(|y| &data[y * padded..][..row])
+        .copied()
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_keeps_aspect() {
+        assert_eq!(preview_size(1920, 1080, 480), (480, 270));
+        assert_eq!(preview_size(1080, 1920, 480), (480, 853));
+        assert_eq!(preview_size(4000, 1, 480), (480, 1));
+    }
+
+    #[test]
+    fn preview_never_upscales() {
+        assert_eq!(preview_size(320, 200, 480), (320, 200));
+        assert_eq!(preview_size(0, 0, 480), (1, 1));
+    }
+
+    #[test]
+    fn rows_are_unpadded() {
+        let mut data = vec![0u8; 512];
+        data[..4].copy_from_slice(&[1, 2, 3, 4]);
+        data[256..260].copy_from_slice(&[5, 6, 7, 8]);
+        assert_eq!(unpad_rows(&data, 1, 2), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn captures_generator_output() {
+        use crate::{
+            context::{GeneratorUniforms, Uniforms},
+            generator_pipeline::GeneratorPass,
+        };
+
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let (w, h) = (64, 48);
+            let gen = GeneratorPass::new(&ctx.device, w, h);
+            let mut capture = PreviewCapture::new(&ctx.device, 32, 24);
+            let patch = fractal_core::presets::Preset::ClassicMandelbrot.build();
+            let uniforms = Uniforms::from_params(&patch.params, w, h);
+            let extension = GeneratorUniforms::from_params(patch.generator.kind(), &patch.params);
+
+            let mut encoder = ctx.device.create_command_encoder(&Default::default());
+            gen.dispatch(
+                &ctx.device,
+                &mut encoder,
+                &ctx.queue,
+                patch.generator.kind(),
+                &uniforms,
+                &extension,
+            );
+            capture.record(
+                &ctx.device,
+                &ctx.queue,
+                &mut encoder,
+                &gen.output_view,
+                Projection::Flat,
+            );
+            assert!(capture.is_busy());
+            ctx.queue.submit(std::iter::once(encoder.finish()));
+
+            let px = loop {
+                if let Some(px) = capture.poll(&ctx.device) {
+                    break px;
+                }
+                ctx.device.poll(wgpu::Maintain::Wait);
+            };
+            assert!(!capture.is_busy());
+            assert_eq!(px.len(), 32 * 24 * 4);
+            let first = &px[..4];
+            assert!(px.chunks_exact(4).any(|p| p != first));
+        });
+    }
+}
@@ -0,0 +1,336 @@
+use fractal_core::GeneratorKind;
+use wgpu::{BindGroup, Buffer, CommandEncoder, ComputePipeline, Device, Queue};
+
+use crate::{
+    context::{GeneratorUniforms, Uniforms},
+    preview::Readback,
+    stats,
+};
+
+/// Points one probe dispatch evaluates.  Must match the array sizes in
+/// `probe.wgsl`.
+pub const MAX_PROBES: usize = 16;
+
+/// Params of the probe pass (binding 0).  Must match `ProbeParams` in
+/// `probe.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ProbeUniforms {
+    kind: u32,
+    count: u32,
+    max_iter: u32,
+    _pad: u32,
+    c: [f32; 2],
+    _pad2: [f32; 2],
+    /// xy = plane position; zw unused (uniform arrays stride 16 bytes).
+    points: [[f32; 4]; MAX_PROBES],
+}
+
+/// One probe as the shader writes it.  Must match `ProbeResult` in
+/// `probe.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct RawProbe {
+    iterations: u32,
+    escaped: u32,
+    smoothed: f32,
+    trap: f32,
+}
+
+/// Iteration data at one probed point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProbeResult {
+    /// Iterations run; `max_iter` for interior points, including orbits the
+    /// periodicity check caught cycling.
+    pub iterations: u32,
+    pub escaped: bool,
+    /// Log-log smoothed iteration count; equals `iterations` inside.
+    pub smoothed: f32,
+    /// Closest approach of the orbit to a coordinate axis.
+    pub trap: f32,
+}
+
+impl From<RawProbe> for ProbeResult {
+    fn from(raw: RawProbe) -> Self {
+        Self {
+            iterations: raw.iterations,
+            escaped: raw.escaped != 0,
+            smoothed: raw.smoothed,
+            trap: raw.trap,
+        }
+    }
+}
+
+/// The probe shader's `kind` for generators it can evaluate; `None` for the
+/// rest, which have no orbit to probe.
+pub fn probe_kind(kind: GeneratorKind) -> Option<u32> {
+    match kind {
+        GeneratorKind::Mandelbrot => Some(0),
+        GeneratorKind::Julia => Some(1),
+        GeneratorKind::BurningShip => Some(2),
+        GeneratorKind::Celtic => Some(3),
+        GeneratorKind::Buffalo => Some(4),
+        _ => None,
+    }
+}
+
+const RESULTS_SIZE: u64 = (std::mem::size_of::<RawProbe>() * MAX_PROBES) as u64;
+
+// ---------------------------------------------------------------------------
+// ProbePass — escape-time data at a few plane points, read back async
+// ---------------------------------------------------------------------------
+
+/// Evaluates the current generator's iteration at a handful of points on
+/// the complex plane and reads the results back without stalling the frame
+/// loop, like [`PreviewCapture`](crate::preview::PreviewCapture) does for
+/// pixels.
+pub struct ProbePass {
+    pipeline: ComputePipeline,
+    bind_group: BindGroup,
+    uniform_buf: Buffer,
+    results_buf: Buffer,
+    readback: Buffer,
+    /// Probes in the capture being read back.
+    count: usize,
+    state: Readback,
+}
+
+impl ProbePass {
+    pub fn new(device: &Device) -> Self {
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("probe_bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("probe_pl"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("probe"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/probe.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("probe"),
+            layout: Some(&layout),
+            module: &module,
+            entry_point: "main",
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("probe_params"),
+            size: std::mem::size_of::<ProbeUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let results_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("probe_results"),
+            size: RESULTS_SIZE,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("probe_readback"),
+            size: RESULTS_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("probe_bg"),
+            layout: &bgl,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: results_buf.as_entire_binding(),
+                },
+            ],
+        });
+
+        Self {
+            pipeline,
+            bind_group,
+            uniform_buf,
+            results_buf,
+            readback,
+            count: 0,
+            state: Readback::Idle,
+        }
+    }
+
+    /// Probes are recorded or being read back; `record` would be ignored.
+    pub fn is_busy(&self) -> bool {
+        !matches!(self.state, Readback::Idle)
+    }
+
+    /// Record the iteration of `kind` at `points` (plane positions; only the
+    /// first [`MAX_PROBES`] are used) with the frame's `uniforms` and
+    /// `extension`, and the copy for readback.  Call `poll` after the
+    /// encoder is submitted.  Returns `false`, recording nothing, while a
+    /// previous probe is pending or for generators [`probe_kind`] rejects.
+    pub fn record(
+        &mut self,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        kind: GeneratorKind,
+        uniforms: &Uniforms,
+        extension: &GeneratorUniforms,
+        points: &[[f32; 2]],
+    ) -> bool {
+        let Some(probe) = probe_kind(kind) else {
+            return false;
+        };
+        if self.is_busy() || points.is_empty() {
+            return false;
+        }
+        let count = points.len().min(MAX_PROBES);
+        let mut params = ProbeUniforms {
+            kind: probe,
+            count: count as u32,
+            max_iter: uniforms.max_iter,
+            _pad: 0,
+            c: match extension {
+                GeneratorUniforms::Julia(julia) => julia.c,
+                _ => [0.0; 2],
+            },
+            _pad2: [0.0; 2],
+            points: [[0.0; 4]; MAX_PROBES],
+        };
+        for (slot, p) in params.points.iter_mut().zip(points) {
+            *slot = [p[0], p[1], 0.0, 0.0];
+        }
+        stats::write_buffer(queue, &self.uniform_buf, 0, bytemuck::bytes_of(&params));
+
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("probe_pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            stats::dispatch_workgroups(&mut pass, 1, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&self.results_buf, 0, &self.readback, 0, RESULTS_SIZE);
+        self.count = count;
+        self.state = Readback::Copied;
+        true
+    }
+
+    /// Advance the readback without blocking.  Returns one result per
+    /// recorded point, in order, once the copy has landed.
+    pub fn poll(&mut self, device: &Device) -> Option<Vec<ProbeResult>> {
+        self.state.poll(device, &self.readback, "probe")?.ok()?;
+        let results = {
+            let data = self.readback.slice(..).get_mapped_range();
+            bytemuck::cast_slice::<u8, RawProbe>(&data)[..self.count]
+                .iter()
+                .map(|&raw| raw.into())
+                .collect()
+        };
+        self.readback.unmap();
+        Some(results)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_uniforms_match_the_shader_layout() {
+        // 4 u32 + 2 vec2 + 16 vec4 = 16 + 16 + 256 bytes
+        assert_eq!(std::mem::size_of::<ProbeUniforms>(), 288);
+        assert_eq!(std::mem::size_of::<RawProbe>(), 16);
+    }
+
+    #[test]
+    fn only_escape_time_generators_are_probed() {
+        let probed: Vec<_> = [
+            GeneratorKind::Mandelbrot,
+            GeneratorKind::Julia,
+            GeneratorKind::BurningShip,
+            GeneratorKind::Celtic,
+            GeneratorKind::Buffalo,
+        ]
+        .into_iter()
+        .filter_map(probe_kind)
+        .collect();
+        assert_eq!(probed, vec![0, 1, 2, 3, 4]);
+        assert_eq!(probe_kind(GeneratorKind::NoiseField), None);
+        assert_eq!(probe_kind(GeneratorKind::InverseJulia), None);
+    }
+
+    #[test]
+    fn probe_wgsl_is_valid() {
+        assert_eq!(
+            crate::shader::validate_wgsl(include_str!("../shaders/probe.wgsl")),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn probes_tell_interior_from_exterior() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut probes = ProbePass::new(&ctx.device);
+            let patch = fractal_core::presets::Preset::ClassicMandelbrot.build();
+            let uniforms = Uniforms::from_params(&patch.params, 64, 64);
+            let extension = GeneratorUniforms::from_params(patch.generator.kind(), &patch.params);
+
+            let mut encoder = ctx.device.create_command_encoder(&Default::default());
+            assert!(probes.record(
+                &ctx.queue,
+                &mut encoder,
+                GeneratorKind::Mandelbrot,
+                &uniforms,
+                &extension,
+                &[[-0.2, 0.1], [2.1, 0.0]],
+            ));
+            assert!(probes.is_busy());
+            ctx.queue.submit(std::iter::once(encoder.finish()));
+
+            let results = loop {
+                if let Some(r) = probes.poll(&ctx.device) {
+                    break r;
+                }
+                ctx.device.poll(wgpu::Maintain::Wait);
+            };
+            assert_eq!(results.len(), 2);
+            assert!(!results[0].escaped);
+            assert_eq!(results[0].iterations, uniforms.max_iter);
+            assert!(results[1].escaped);
+            assert_eq!(results[1].iterations, 1);
+        });
+    }
+}
@@ -1,9 +1,70 @@
+use fractal_core::projection::Projection;
+use fractal_core::warp::{OutputCorrection, WarpGrid};
+use wgpu::util::DeviceExt;
+
+use crate::stats;
+
+/// Uniforms of the full-screen pass (binding 2).  Must match `Output` in
+/// `FULLSCREEN_WGSL`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct OutputUniforms {
+    /// `Projection::gpu_index` — the dome mode masks outside its circle.
+    pub projection: u32,
+    pub _pad: [u32; 3],
+}
+
+impl OutputUniforms {
+    pub fn new(projection: Projection) -> Self {
+        Self {
+            projection: projection.gpu_index(),
+            _pad: [0; 3],
+        }
+    }
+}
+
+/// Warp and edge blend of the full-screen pass (binding 3).  Must match
+/// `Correction` in `FULLSCREEN_WGSL`; the warp grid's points are bound
+/// separately as a storage buffer (binding 4).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CorrectionUniforms {
+    /// Region of the source texture shown: x, y, width, height as
+    /// fractions, top-left origin.  [`FULL_CROP`] shows all of it.
+    pub crop: [f32; 4],
+    /// Edge-blend widths: left, right, top, bottom.
+    pub blend: [f32; 4],
+    pub gamma: f32,
+    pub cols: u32,
+    pub rows: u32,
+    pub _pad: u32,
+}
+
+/// The whole source texture.
+pub const FULL_CROP: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+impl CorrectionUniforms {
+    pub fn new(correction: &OutputCorrection, grid: &WarpGrid) -> Self {
+        let b = correction.blend;
+        Self {
+            crop: FULL_CROP,
+            blend: [b.left, b.right, b.top, b.bottom],
+            gamma: b.gamma,
+            cols: grid.cols,
+            rows: grid.rows,
+            _pad: 0,
+        }
+    }
+}
+
 /// Full-screen quad renderer — samples the final effect texture and
 /// presents it to the wgpu Surface.
 ///
 /// The vertex shader generates a clip-space quad from vertex indices
-/// (no vertex buffer needed). The fragment shader simply samples the
-/// texture produced by the effect chain.
+/// (no vertex buffer needed). The fragment shader looks each output pixel up
+/// through the warp grid, samples the texture produced by the effect chain
+/// there (within the crop, for a tiled output) and dims it by the edge blend; in dome projection everything
+/// outside the inscribed circle is black.
 pub const FULLSCREEN_WGSL: &str = r#"
 struct VertexOut {
     @builtin(position) pos: vec4<f32>,
@@ -27,8 +88,502 @@ fn vs_main(@builtin(vertex_index) vi: u32) -> VertexOut {
 @group(0) @binding(0) var t_result:  texture_2d<f32>;
 @group(0) @binding(1) var s_result:  sampler;
 
+struct Output {
+    projection: u32,
+    pad0:       u32,
+    pad1:       u32,
+    pad2:       u32,
+};
+@group(0) @binding(2) var<uniform> out_u: Output;
+
+struct Correction {
+    crop:  vec4<f32>, // x, y, width, height
+    blend: vec4<f32>, // left, right, top, bottom
+    gamma: f32,
+    cols:  u32,
+    rows:  u32,
+    pad0:  u32,
+};
+@group(0) @binding(3) var<uniform> corr: Correction;
+@group(0) @binding(4) var<storage, read> grid: array<vec2<f32>>;
+
+// Positions below are top-left origin, 0–1 across the output.
+
+fn node(c: u32, r: u32) -> vec2<f32> {
+    return grid[r * corr.cols + c];
+}
+
+// Frame position shown at output position `s`: bilinear between grid nodes.
+fn warp(s: vec2<f32>) -> vec2<f32> {
+    let n = vec2<f32>(f32(corr.cols - 1u), f32(corr.rows - 1u));
+    let g = clamp(s, vec2<f32>(0.0), vec2<f32>(1.0)) * n;
+    let i = min(floor(g), n - 1.0);
+    let f = g - i;
+    let c = u32(i.x);
+    let r = u32(i.y);
+    let top    = mix(node(c, r), node(c + 1u, r), f.x);
+    let bottom = mix(node(c, r + 1u), node(c + 1u, r + 1u), f.x);
+    return mix(top, bottom, f.y);
+}
+
+fn ramp(distance: f32, width: f32) -> f32 {
+    if width <= 0.0 { return 1.0; }
+    return pow(clamp(distance / width, 0.0, 1.0), corr.gamma);
+}
+
+fn edge_blend(s: vec2<f32>) -> f32 {
+    return ramp(s.x, corr.blend.x) * ramp(1.0 - s.x, corr.blend.y)
+         * ramp(s.y, corr.blend.z) * ramp(1.0 - s.y, corr.blend.w);
+}
+
 @fragment
 fn fs_main(in: VertexOut) -> @location(0) vec4<f32> {
-    return textureSample(t_result, s_result, in.uv);
+    let s  = vec2<f32>(in.uv.x, 1.0 - in.uv.y);
+    let q  = warp(s);
+    let c  = corr.crop.xy + q * corr.crop.zw;
+    let uv = vec2<f32>(c.x, 1.0 - c.y);
+    let colour = textureSample(t_result, s_result, uv);
+    if any(q < vec2<f32>(0.0)) || any(q > vec2<f32>(1.0)) {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+    if out_u.projection == 1u {
+        let res = vec2<f32>(textureDimensions(t_result));
+        let d   = (uv - 0.5) * res;
+        if length(d) > min(res.x, res.y) * 0.5 {
+            return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+        }
+    }
+    return vec4<f32>(colour.rgb * edge_blend(s), colour.a);
 }
 "#;
+
+// ---------------------------------------------------------------------------
+// Viewport — the part of a render target the fractal occupies
+// ---------------------------------------------------------------------------
+
+/// A rectangle of the output target in physical pixels.  With a fixed frame
+/// aspect the fractal fills only this region and the rest stays black
+/// (letterbox or pillarbox bars).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Viewport {
+    /// The whole `width`×`height` target.
+    pub fn full(width: u32, height: u32) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }
+    }
+
+    /// The largest centred region of a `width`×`height` target with the
+    /// given `aspect` (width / height); `None` fills the target.
+    pub fn fit(width: u32, height: u32, aspect: Option<f32>) -> Self {
+        let Some(aspect) = aspect.filter(|a| a.is_finite() && *a > 0.0) else {
+            return Self::full(width, height);
+        };
+        let (fw, fh) = if width as f32 > height as f32 * aspect {
+            ((height as f32 * aspect).round() as u32, height)
+        } else {
+            (width, (width as f32 / aspect).round() as u32)
+        };
+        let (fw, fh) = (fw.clamp(1, width.max(1)), fh.clamp(1, height.max(1)));
+        Self {
+            x: (width.saturating_sub(fw)) / 2,
+            y: (height.saturating_sub(fh)) / 2,
+            width: fw,
+            height: fh,
+        }
+    }
+
+    /// `pos` (target pixels) relative to the region's top-left corner.
+    pub fn local(&self, pos: (f32, f32)) -> (f32, f32) {
+        (pos.0 - self.x as f32, pos.1 - self.y as f32)
+    }
+
+    /// Whether `pos` (target pixels) falls inside the region.
+    pub fn contains(&self, pos: (f32, f32)) -> bool {
+        let (x, y) = self.local(pos);
+        (0.0..self.width as f32).contains(&x) && (0.0..self.height as f32).contains(&y)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OutputPass — draws a finished texture to a render target
+// ---------------------------------------------------------------------------
+
+/// The fullscreen-quad pipeline plus its sampler and uniform buffers.  Used
+/// for the window, each tile window and the downscaled preview capture; only
+/// the windows' passes are given an output correction or crop.
+pub struct OutputPass {
+    bgl: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    pipeline: wgpu::RenderPipeline,
+    uniforms: wgpu::Buffer,
+    correction: CorrectionUniforms,
+    correction_buf: wgpu::Buffer,
+    grid: wgpu::Buffer,
+}
+
+impl OutputPass {
+    /// Build the pass for targets of `target_format` (resolution-agnostic).
+    pub fn new(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> Self {
+        let (bgl, sampler, pipeline) = build_output_pipeline(device, target_format);
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("render_uniforms"),
+            size: std::mem::size_of::<OutputUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let grid = WarpGrid::identity(2, 2);
+        let correction = CorrectionUniforms::new(&OutputCorrection::default(), &grid);
+        let correction_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("render_correction"),
+            contents: bytemuck::bytes_of(&correction),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        Self {
+            bgl,
+            sampler,
+            pipeline,
+            uniforms,
+            correction,
+            correction_buf,
+            grid: grid_buffer(device, &grid),
+        }
+    }
+
+    /// Warp and edge-blend everything this pass draws from now on.
+    pub fn set_correction(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        correction: &OutputCorrection,
+    ) {
+        let grid = correction
+            .warp
+            .clone()
+            .unwrap_or_else(|| WarpGrid::identity(2, 2));
+        self.grid = grid_buffer(device, &grid);
+        self.correction = CorrectionUniforms {
+            crop: self.correction.crop,
+            ..CorrectionUniforms::new(correction, &grid)
+        };
+        self.write_correction(queue);
+    }
+
+    /// Show only `crop` of the source (see [`CorrectionUniforms::crop`]),
+    /// stretched over the target.
+    pub fn set_crop(&mut self, queue: &wgpu::Queue, crop: [f32; 4]) {
+        self.correction.crop = crop;
+        self.write_correction(queue);
+    }
+
+    fn write_correction(&self, queue: &wgpu::Queue) {
+        stats::write_buffer(
+            queue,
+            &self.correction_buf,
+            0,
+            bytemuck::bytes_of(&self.correction),
+        );
+    }
+
+    /// Clear `target` to black and draw `source` over it through
+    /// `projection`'s mask.
+    pub fn draw(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+        projection: Projection,
+    ) {
+        self.draw_in(device, queue, encoder, source, target, None, projection);
+    }
+
+    /// Like [`draw`](Self::draw), but `source` fills only `viewport` of the
+    /// target (`None` = all of it); the bars around it stay black.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_in(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+        viewport: Option<Viewport>,
+        projection: Projection,
+    ) {
+        let bind_group = stats::create_bind_group(
+            device,
+            &wgpu::BindGroupDescriptor {
+                label: Some("render_bg"),
+                layout: &self.bgl,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(source),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: self.uniforms.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: self.correction_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: self.grid.as_entire_binding(),
+                    },
+                ],
+            },
+        );
+        stats::write_buffer(
+            queue,
+            &self.uniforms,
+            0,
+            bytemuck::bytes_of(&OutputUniforms::new(projection)),
+        );
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("fullscreen-pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        if let Some(v) = viewport {
+            rpass.set_viewport(
+                v.x as f32,
+                v.y as f32,
+                v.width as f32,
+                v.height as f32,
+                0.0,
+                1.0,
+            );
+        }
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &bind_group, &[]);
+        rpass.draw(0..6, 0..1);
+    }
+}
+
+/// The warp grid's points as a storage buffer for binding 4.
+fn grid_buffer(device: &wgpu::Device, grid: &WarpGrid) -> wgpu::Buffer {
+    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("render_warp_grid"),
+        contents: bytemuck::cast_slice(&grid.points),
+        usage: wgpu::BufferUsages::STORAGE,
+    })
+}
+
+/// Build the fullscreen-quad pipeline drawing `FULLSCREEN_WGSL` into
+/// `target_format`.  Returns the bind group layout (texture, sampler,
+/// `OutputUniforms`, `CorrectionUniforms`, warp grid), the sampler and the
+/// pipeline.
+fn build_output_pipeline(
+    device: &wgpu::Device,
+    target_format: wgpu::TextureFormat,
+) -> (wgpu::BindGroupLayout, wgpu::Sampler, wgpu::RenderPipeline) {
+    let render_bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("render_bgl"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 4,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    });
+
+    let render_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("render_sampler"),
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        ..Default::default()
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("render_pl"),
+        bind_group_layouts: &[&render_bgl],
+        push_constant_ranges: &[],
+    });
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("fullscreen"),
+        source: wgpu::ShaderSource::Wgsl(FULLSCREEN_WGSL.into()),
+    });
+
+    let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("render_pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: target_format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    });
+
+    (render_bgl, render_sampler, render_pipeline)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fullscreen_wgsl_is_valid() {
+        crate::shader::validate_wgsl(FULLSCREEN_WGSL).expect("fullscreen.wgsl");
+    }
+
+    #[test]
+    fn output_uniforms_are_16_bytes() {
+        assert_eq!(std::mem::size_of::<OutputUniforms>(), 16);
+        assert_eq!(OutputUniforms::new(Projection::Dome).projection, 1);
+    }
+
+    #[test]
+    fn correction_uniforms_carry_blend_and_grid_size() {
+        assert_eq!(std::mem::size_of::<CorrectionUniforms>(), 48);
+        let correction =
+            OutputCorrection::from_json(r#"{"blend": {"left": 0.1, "gamma": 2.2}}"#).unwrap();
+        let u = CorrectionUniforms::new(&correction, &WarpGrid::identity(5, 3));
+        assert_eq!(u.blend, [0.1, 0.0, 0.0, 0.0]);
+        assert_eq!((u.gamma, u.cols, u.rows), (2.2, 5, 3));
+        assert_eq!(u.crop, FULL_CROP);
+    }
+
+    // --- Viewport ---
+
+    #[test]
+    fn viewport_without_aspect_fills_target() {
+        assert_eq!(Viewport::fit(1280, 720, None), Viewport::full(1280, 720));
+        assert_eq!(
+            Viewport::fit(1280, 720, Some(0.0)),
+            Viewport::full(1280, 720)
+        );
+    }
+
+    #[test]
+    fn viewport_pillarboxes_wide_targets() {
+        let v = Viewport::fit(1920, 1080, Some(1.0));
+        assert_eq!(
+            v,
+            Viewport {
+                x: 420,
+                y: 0,
+                width: 1080,
+                height: 1080
+            }
+        );
+    }
+
+    #[test]
+    fn viewport_letterboxes_tall_targets() {
+        let v = Viewport::fit(1080, 1080, Some(16.0 / 9.0));
+        assert_eq!(
+            v,
+            Viewport {
+                x: 0,
+                y: 236,
+                width: 1080,
+                height: 608
+            }
+        );
+    }
+
+    #[test]
+    fn viewport_maps_target_positions() {
+        let v = Viewport::fit(1920, 1080, Some(1.0));
+        assert_eq!(v.local((420.0, 10.0)), (0.0, 10.0));
+        assert!(v.contains((960.0, 540.0)));
+        assert!(!v.contains((100.0, 540.0)));
+        assert!(!v.contains((1500.0, 540.0)));
+    }
+}
@@ -0,0 +1,435 @@
+use wgpu::{ComputePipeline, Device, PipelineLayout};
+
+// ---------------------------------------------------------------------------
+// ShaderError — a WGSL compile failure, with a source location when known
+// ---------------------------------------------------------------------------
+
+/// Why user-supplied WGSL could not be turned into a pipeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderError {
+    /// Human-readable diagnostic (multi-line, with a source excerpt when the
+    /// error came from naga).
+    pub message: String,
+    /// 1-based line of the offending span, if known.
+    pub line: Option<u32>,
+    /// 1-based column of the offending span, if known.
+    pub column: Option<u32>,
+}
+
+impl ShaderError {
+    fn without_location(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            line: None,
+            column: None,
+        }
+    }
+}
+
+impl std::fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(l), Some(c)) => write!(f, "{l}:{c}: {}", self.message),
+            _ => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+// ---------------------------------------------------------------------------
+// Validation and live compilation
+// ---------------------------------------------------------------------------
+
+/// Parse and validate `src` with naga (the validator wgpu uses internally),
+/// so errors can be reported without touching the device.
+pub fn validate_wgsl(src: &str) -> Result<(), ShaderError> {
+    let module = naga::front::wgsl::parse_str(src).map_err(|e| {
+        let loc = e.location(src);
+        ShaderError {
+            message: e.emit_to_string(src),
+            line: loc.map(|l| l.line_number),
+            column: loc.map(|l| l.line_position),
+        }
+    })?;
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .map_err(|e| {
+        let loc = e.location(src);
+        ShaderError {
+            message: e.emit_to_string(src),
+            line: loc.map(|l| l.line_number),
+            column: loc.map(|l| l.line_position),
+        }
+    })?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Binding contract — a shader's resources against a bind group layout
+// ---------------------------------------------------------------------------
+
+/// How the resources `src` declares disagree with the bind group layout
+/// `entries` its pipeline is built with, one line per binding; empty when
+/// they agree.  Checks what wgpu checks when the pipeline is created —
+/// binding indices, buffer kinds, texture sample types, dimensions, storage
+/// formats and access — without a device.  Layout entries the shader
+/// doesn't declare are fine: a pass shares one layout across shaders that
+/// each use part of it.
+pub fn binding_mismatches(src: &str, entries: &[wgpu::BindGroupLayoutEntry]) -> Vec<String> {
+    let module = match naga::front::wgsl::parse_str(src) {
+        Ok(module) => module,
+        Err(e) => return vec![format!("WGSL parse failed: {e}")],
+    };
+    let mut problems = Vec::new();
+    for (_, var) in module.global_variables.iter() {
+        let Some(rb) = &var.binding else { continue };
+        let name = var.name.as_deref().unwrap_or("_");
+        let at = format!("@group({}) @binding({}) {name}", rb.group, rb.binding);
+        if rb.group != 0 {
+            problems.push(format!("{at}: only group 0 is bound"));
+            continue;
+        }
+        let Some(entry) = entries.iter().find(|e| e.binding == rb.binding) else {
+            problems.push(format!("{at}: the layout has no such binding"));
+            continue;
+        };
+        let inner = &module.types[var.ty].inner;
+        if let Err(why) = binding_agrees(var.space, inner, &entry.ty) {
+            problems.push(format!("{at}: {why}"));
+        }
+    }
+    problems
+}
+
+/// Whether a global in `space` of type `inner` can bind to a layout entry
+/// of type `ty`.
+fn binding_agrees(
+    space: naga::AddressSpace,
+    inner: &naga::TypeInner,
+    ty: &wgpu::BindingType,
+) -> Result<(), String> {
+    use naga::{AddressSpace, ImageClass, ScalarKind, StorageAccess, TypeInner};
+    use wgpu::{
+        BindingType, BufferBindingType, SamplerBindingType, StorageTextureAccess, TextureSampleType,
+    };
+
+    let agrees = match (space, inner, ty) {
+        (
+            AddressSpace::Uniform,
+            _,
+            BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                ..
+            },
+        ) => true,
+        (
+            AddressSpace::Storage { access },
+            _,
+            BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only },
+                ..
+            },
+        ) => {
+            if *read_only && access.contains(StorageAccess::STORE) {
+                return Err("the shader writes a buffer the layout makes read-only".into());
+            }
+            true
+        }
+        (AddressSpace::Handle, TypeInner::Sampler { comparison }, BindingType::Sampler(kind)) => {
+            *comparison == (*kind == SamplerBindingType::Comparison)
+        }
+        (
+            AddressSpace::Handle,
+            TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            },
+            _,
+        ) => {
+            let dimension = view_dimension(*dim, *arrayed);
+            match (class, ty) {
+                (
+                    ImageClass::Sampled { kind, multi },
+                    BindingType::Texture {
+                        sample_type,
+                        view_dimension,
+                        multisampled,
+                    },
+                ) => {
+                    matches!(
+                        (kind, sample_type),
+                        (ScalarKind::Float, TextureSampleType::Float { .. })
+                            | (ScalarKind::Sint, TextureSampleType::Sint)
+                            | (ScalarKind::Uint, TextureSampleType::Uint)
+                    ) && multi == multisampled
+                        && dimension == *view_dimension
+                }
+                (
+                    ImageClass::Depth { multi },
+                    BindingType::Texture {
+                        sample_type: TextureSampleType::Depth,
+                        view_dimension,
+                        multisampled,
+                    },
+                ) => multi == multisampled && dimension == *view_dimension,
+                (
+                    ImageClass::Storage { format, access },
+                    BindingType::StorageTexture {
+                        access: layout_access,
+                        format: layout_format,
+                        view_dimension,
+                    },
+                ) => {
+                    let layout_access = match layout_access {
+                        StorageTextureAccess::WriteOnly => StorageAccess::STORE,
+                        StorageTextureAccess::ReadOnly => StorageAccess::LOAD,
+                        StorageTextureAccess::ReadWrite => {
+                            StorageAccess::LOAD | StorageAccess::STORE
+                        }
+                    };
+                    *access == layout_access
+                        && storage_format(*layout_format) == Some(*format)
+                        && dimension == *view_dimension
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+    if agrees {
+        Ok(())
+    } else {
+        Err(format!(
+            "declared as {inner:?} in {space:?}, layout has {ty:?}"
+        ))
+    }
+}
+
+/// Byte size of the uniform block `src` declares at `@group(0)
+/// @binding(binding)`, for checking it against the Rust struct uploaded
+/// there.
+#[cfg(test)]
+pub(crate) fn uniform_size(src: &str, binding: u32) -> Option<u32> {
+    let module = naga::front::wgsl::parse_str(src).ok()?;
+    let (_, var) = module.global_variables.iter().find(|(_, var)| {
+        var.space == naga::AddressSpace::Uniform
+            && var
+                .binding
+                .as_ref()
+                .is_some_and(|rb| rb.group == 0 && rb.binding == binding)
+    })?;
+    Some(module.types[var.ty].inner.size(module.to_ctx()))
+}
+
+/// The view dimension a WGSL texture of `dim` binds to.
+fn view_dimension(dim: naga::ImageDimension, arrayed: bool) -> wgpu::TextureViewDimension {
+    use naga::ImageDimension;
+    use wgpu::TextureViewDimension;
+    match (dim, arrayed) {
+        (ImageDimension::D1, _) => TextureViewDimension::D1,
+        (ImageDimension::D2, false) => TextureViewDimension::D2,
+        (ImageDimension::D2, true) => TextureViewDimension::D2Array,
+        (ImageDimension::D3, _) => TextureViewDimension::D3,
+        (ImageDimension::Cube, false) => TextureViewDimension::Cube,
+        (ImageDimension::Cube, true) => TextureViewDimension::CubeArray,
+    }
+}
+
+/// The WGSL storage format of `format`, for the formats the passes bind as
+/// storage textures.
+fn storage_format(format: wgpu::TextureFormat) -> Option<naga::StorageFormat> {
+    use naga::StorageFormat;
+    use wgpu::TextureFormat;
+    Some(match format {
+        TextureFormat::Rgba16Float => StorageFormat::Rgba16Float,
+        TextureFormat::Rgba32Float => StorageFormat::Rgba32Float,
+        TextureFormat::Rg32Float => StorageFormat::Rg32Float,
+        TextureFormat::R32Float => StorageFormat::R32Float,
+        TextureFormat::R32Uint => StorageFormat::R32Uint,
+        TextureFormat::Rgba8Unorm => StorageFormat::Rgba8Unorm,
+        _ => return None,
+    })
+}
+
+/// Compile a compute pipeline (entry point `main`) from user-supplied WGSL.
+///
+/// Source is validated with naga first; anything naga accepts but wgpu
+/// rejects (e.g. bindings that don't match `layout`) is caught with an
+/// error scope instead of hitting wgpu's panicking default handler.
+pub fn compile_compute(
+    device: &Device,
+    label: &str,
+    src: &str,
+    layout: &PipelineLayout,
+) -> Result<ComputePipeline, ShaderError> {
+    compile_compute_entry(device, label, src, layout, "main")
+}
+
+/// Like [`compile_compute`], for the entry point `entry`.
+pub fn compile_compute_entry(
+    device: &Device,
+    label: &str,
+    src: &str,
+    layout: &PipelineLayout,
+    entry: &str,
+) -> Result<ComputePipeline, ShaderError> {
+    validate_wgsl(src)?;
+
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        source: wgpu::ShaderSource::Wgsl(src.into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some(label),
+        layout: Some(layout),
+        module: &module,
+        entry_point: entry,
+        compilation_options: Default::default(),
+        cache: None,
+    });
+    match pollster::block_on(device.pop_error_scope()) {
+        None => Ok(pipeline),
+        Some(e) => Err(ShaderError::without_location(e.to_string())),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_shader_passes() {
+        assert_eq!(
+            validate_wgsl(crate::generator_pipeline::GeneratorPass::default_source(
+                fractal_core::GeneratorKind::Mandelbrot
+            )),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn parse_error_has_location() {
+        let err = validate_wgsl("fn main() {\n    let x = ;\n}").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert!(err.column.is_some());
+        assert!(!err.message.is_empty());
+    }
+
+    #[test]
+    fn type_error_is_reported() {
+        let err = validate_wgsl("fn f() -> f32 {\n    return 1u;\n}").unwrap_err();
+        assert!(!err.message.is_empty());
+        assert!(err.to_string().contains(&err.message));
+    }
+
+    // --- binding_mismatches ---
+
+    fn entry(binding: u32, ty: wgpu::BindingType) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty,
+            count: None,
+        }
+    }
+
+    fn storage_texture(format: wgpu::TextureFormat) -> wgpu::BindingType {
+        wgpu::BindingType::StorageTexture {
+            access: wgpu::StorageTextureAccess::WriteOnly,
+            format,
+            view_dimension: wgpu::TextureViewDimension::D2,
+        }
+    }
+
+    const OUTPUT: &str =
+        "@group(0) @binding(1) var output: texture_storage_2d<rgba16float, write>;\n\
+                          @compute @workgroup_size(1) fn main() {}";
+
+    #[test]
+    fn matching_layout_has_no_mismatches() {
+        let entries = [
+            entry(
+                0,
+                wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            ),
+            entry(1, storage_texture(wgpu::TextureFormat::Rgba16Float)),
+        ];
+        assert!(binding_mismatches(OUTPUT, &entries).is_empty());
+    }
+
+    #[test]
+    fn drifted_bindings_are_reported() {
+        let wrong_format = [entry(1, storage_texture(wgpu::TextureFormat::Rgba32Float))];
+        let problems = binding_mismatches(OUTPUT, &wrong_format);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("@group(0) @binding(1) output"));
+
+        let moved = [entry(2, storage_texture(wgpu::TextureFormat::Rgba16Float))];
+        assert!(binding_mismatches(OUTPUT, &moved)[0].contains("no such binding"));
+
+        let sampler = [entry(
+            1,
+            wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        )];
+        assert_eq!(binding_mismatches(OUTPUT, &sampler).len(), 1);
+    }
+
+    #[test]
+    fn writing_a_read_only_buffer_is_reported() {
+        let src = "@group(0) @binding(0) var<storage, read_write> buf: array<f32>;\n\
+                   @compute @workgroup_size(1) fn main() { buf[0] = 1.0; }";
+        let buffer = |read_only| {
+            [entry(
+                0,
+                wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+            )]
+        };
+        assert!(binding_mismatches(src, &buffer(false)).is_empty());
+        assert!(binding_mismatches(src, &buffer(true))[0].contains("read-only"));
+    }
+
+    #[test]
+    fn uniform_size_reads_the_declared_struct() {
+        let src = "struct P { a: f32, b: vec3<f32> }\n\
+                   @group(0) @binding(2) var<uniform> p: P;\n\
+                   @compute @workgroup_size(1) fn main() { _ = p.a; }";
+        assert_eq!(uniform_size(src, 2), Some(32));
+        assert_eq!(uniform_size(src, 0), None);
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn compile_catches_layout_mismatch() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let layout = ctx
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: None,
+                    bind_group_layouts: &[],
+                    push_constant_ranges: &[],
+                });
+            // Valid WGSL, but binds a resource the empty layout doesn't have.
+            let src = "@group(0) @binding(0) var<storage, read_write> buf: array<f32>;\n\
+                       @compute @workgroup_size(1) fn main() { buf[0] = 1.0; }";
+            assert!(compile_compute(&ctx.device, "bad", src, &layout).is_err());
+
+            let ok = "@compute @workgroup_size(1) fn main() {}";
+            assert!(compile_compute(&ctx.device, "ok", ok, &layout).is_ok());
+        });
+    }
+}
@@ -0,0 +1,132 @@
+use std::cell::Cell;
+
+use wgpu::{BindGroup, BindGroupDescriptor, Buffer, BufferDescriptor, ComputePass, Device, Queue};
+
+// ---------------------------------------------------------------------------
+// GpuStats — per-frame counts of instrumented wgpu calls
+// ---------------------------------------------------------------------------
+//
+// Passes call the wrappers below instead of `Device` / `Queue` directly so the
+// HUD can show how much GPU work a patch generates each frame.  Counters are
+// thread-local: rendering happens on one thread, and parallel tests don't see
+// each other's counts.
+
+/// Counts of instrumented GPU calls since the last [`take_frame`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GpuStats {
+    pub dispatches: u32,
+    pub bind_groups: u32,
+    pub buffers_created: u32,
+    pub buffer_writes: u32,
+    pub bytes_written: u64,
+}
+
+thread_local! {
+    static STATS: Cell<GpuStats> = Cell::new(GpuStats::default());
+}
+
+fn bump(f: impl FnOnce(&mut GpuStats)) {
+    STATS.with(|s| {
+        let mut stats = s.get();
+        f(&mut stats);
+        s.set(stats);
+    });
+}
+
+/// Return the counts accumulated since the previous call and reset them.
+/// Call once per frame.
+pub fn take_frame() -> GpuStats {
+    STATS.with(|s| s.take())
+}
+
+pub fn create_bind_group(device: &Device, desc: &BindGroupDescriptor) -> BindGroup {
+    bump(|s| s.bind_groups += 1);
+    device.create_bind_group(desc)
+}
+
+pub fn create_buffer(device: &Device, desc: &BufferDescriptor) -> Buffer {
+    bump(|s| s.buffers_created += 1);
+    device.create_buffer(desc)
+}
+
+pub fn write_buffer(queue: &Queue, buffer: &Buffer, offset: u64, data: &[u8]) {
+    bump(|s| {
+        s.buffer_writes += 1;
+        s.bytes_written += data.len() as u64;
+    });
+    queue.write_buffer(buffer, offset, data);
+}
+
+pub fn dispatch_workgroups(pass: &mut ComputePass, x: u32, y: u32, z: u32) {
+    bump(|s| s.dispatches += 1);
+    pass.dispatch_workgroups(x, y, z);
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_frame_resets_counters() {
+        take_frame();
+        bump(|s| s.dispatches += 3);
+        assert_eq!(take_frame().dispatches, 3);
+        assert_eq!(take_frame(), GpuStats::default());
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn generator_and_chain_are_counted() {
+        use fractal_core::EffectKind;
+
+        use crate::context::{GeneratorUniforms, GpuContext, Uniforms};
+        use crate::effect_pipeline::{EffectPass, PingPong};
+        use crate::generator_pipeline::GeneratorPass;
+
+        pollster::block_on(async {
+            let ctx = GpuContext::new_headless().await;
+            let gen_pass = GeneratorPass::new(&ctx.device, 64, 64);
+            let mut effect_pass = EffectPass::new(&ctx.device);
+            let mut pp = PingPong::new(&ctx.device, 64, 64);
+            let uniforms = Uniforms::from_params(&Default::default(), 64, 64);
+            let effects = [
+                EffectKind::HueShift { amount: 0.5 },
+                EffectKind::HueShift { amount: 0.2 },
+            ];
+
+            take_frame();
+            let mut encoder = ctx.device.create_command_encoder(&Default::default());
+            gen_pass.dispatch(
+                &ctx.device,
+                &mut encoder,
+                &ctx.queue,
+                fractal_core::GeneratorKind::Mandelbrot,
+                &uniforms,
+                &GeneratorUniforms::None,
+            );
+            effect_pass.dispatch_chain(
+                &ctx.device,
+                &mut encoder,
+                &ctx.queue,
+                &effects,
+                &uniforms,
+                &gen_pass.output_view,
+                &mut pp,
+                64,
+                64,
+            );
+            ctx.queue.submit(std::iter::once(encoder.finish()));
+
+            let stats = take_frame();
+            assert_eq!(stats.dispatches, 3);
+            assert_eq!(stats.bind_groups, 3);
+            assert_eq!(stats.buffers_created, 2);
+            // 1 generator uniform write + 2 × (uniforms + params)
+            assert_eq!(stats.buffer_writes, 5);
+        });
+    }
+}
@@ -0,0 +1,359 @@
+use fractal_core::{animation::frame_times, patch::Patch};
+use wgpu::{Buffer, Device, Queue};
+
+use crate::{
+    context::{GeneratorUniforms, Uniforms},
+    effect_pipeline::{EffectPass, PingPong},
+    generator_pipeline::GeneratorPass,
+};
+
+/// Edge length of a preset thumbnail in pixels.
+pub const THUMBNAIL_SIZE: u32 = 128;
+
+/// Patch time (seconds) thumbnails are captured at, so modulated presets
+/// look the same on every run.
+pub const THUMBNAIL_TIME: f32 = 2.0;
+
+/// Bytes per `rgba16float` texel.
+const TEXEL_BYTES: u32 = 8;
+
+// ---------------------------------------------------------------------------
+// ThumbnailRenderer — offscreen generator + effect chain with CPU readback
+// ---------------------------------------------------------------------------
+
+/// Renders patches into an offscreen target and reads the result back as
+/// 8-bit sRGB RGBA, ready to be written out as PNG.  Square and small for
+/// preset thumbnails; zoom videos use a frame-shaped one for keyframes.
+pub struct ThumbnailRenderer {
+    gen_pass: GeneratorPass,
+    effect_pass: EffectPass,
+    pp: PingPong,
+    readback: Buffer,
+    width: u32,
+    height: u32,
+}
+
+impl ThumbnailRenderer {
+    pub fn new(device: &Device, size: u32) -> Self {
+        Self::with_size(device, size, size)
+    }
+
+    /// A `width`×`height` renderer.
+    pub fn with_size(device: &Device, width: u32, height: u32) -> Self {
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("thumbnail_readback"),
+            size: padded_bytes_per_row(width) as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        Self {
+            gen_pass: GeneratorPass::new(device, width, height),
+            effect_pass: EffectPass::new(device),
+            pp: PingPong::new(device, width, height),
+            readback,
+            width,
+            height,
+        }
+    }
+
+    /// Edge length of a square renderer (the width otherwise).
+    pub fn size(&self) -> u32 {
+        self.width
+    }
+
+    /// Advance `patch` to `time`, render one frame and return
+    /// `width * height * 4` bytes of tightly packed sRGB RGBA.  Blocks until
+    /// the GPU has finished.
+    pub fn render(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        patch: &mut Patch,
+        time: f32,
+    ) -> Vec<u8> {
+        patch.tick(time - patch.params.time);
+        let uniforms = Uniforms::from_params(&patch.params, self.width, self.height);
+        self.capture(device, queue, patch, &uniforms)
+    }
+
+    /// Render `frames` frames of `patch` on a fixed `fps` clock starting at
+    /// `start` seconds, for parameter sweeps and LFO plots that must come
+    /// out the same on every run.  The patch is left at the last frame's
+    /// time.
+    ///
+    /// # Panics
+    ///
+    /// If `fps` is not a positive number.
+    pub fn render_sequence(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        patch: &mut Patch,
+        start: f32,
+        frames: u32,
+        fps: f32,
+    ) -> FrameStack {
+        let mut rgba = Vec::with_capacity(frames as usize * self.frame_bytes());
+        for time in frame_times(start, frames, fps) {
+            rgba.extend(self.render(device, queue, patch, time));
+        }
+        FrameStack {
+            frames,
+            width: self.width,
+            height: self.height,
+            rgba,
+        }
+    }
+
+    fn frame_bytes(&self) -> usize {
+        (self.width * self.height * 4) as usize
+    }
+
+    /// Render `patch` as it stands, without ticking it, through `uniforms`
+    /// (which must be sized for this renderer).  Same output as
+    /// [`render`](Self::render).
+    pub fn capture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        patch: &Patch,
+        uniforms: &Uniforms,
+    ) -> Vec<u8> {
+        let (width, height) = (self.width, self.height);
+        if let Some(formula) = patch.generator.formula() {
+            if let Err(e) = self.gen_pass.set_formula(device, formula) {
+                log::warn!("formula {:?} failed to compile: {e}", formula.source());
+            }
+        }
+        let params = &patch.params;
+        let extension = GeneratorUniforms::from_params(patch.generator.kind(), params);
+        let effect_kinds: Vec<_> = patch.effects.iter().map(|e| e.kind(params)).collect();
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("thumbnail-encoder"),
+        });
+        self.gen_pass.dispatch(
+            device,
+            &mut encoder,
+            queue,
+            patch.generator.kind(),
+            uniforms,
+            &extension,
+        );
+        self.effect_pass.dispatch_chain(
+            device,
+            &mut encoder,
+            queue,
+            &effect_kinds,
+            uniforms,
+            &self.gen_pass.output_view,
+            &mut self.pp,
+            width,
+            height,
+        );
+
+        let source = if effect_kinds.is_empty() {
+            &self.gen_pass.output_tex
+        } else {
+            self.pp.read_texture()
+        };
+        let padded_row = padded_bytes_per_row(width);
+        encoder.copy_texture_to_buffer(
+            source.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &self.readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = self.readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |r| {
+            r.expect("failed to map thumbnail readback buffer")
+        });
+        device.poll(wgpu::Maintain::Wait);
+
+        let pixels = {
+            let data = slice.get_mapped_range();
+            rgba16f_rows_to_srgb8(&data, width, height, padded_row)
+        };
+        self.readback.unmap();
+        pixels
+    }
+}
+
+/// Frames from [`ThumbnailRenderer::render_sequence`], stacked into one
+/// C-order `frames × height × width × 4` sRGB RGBA array.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameStack {
+    pub frames: u32,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl FrameStack {
+    /// Array shape, outermost first.
+    pub fn shape(&self) -> [usize; 4] {
+        [self.frames, self.height, self.width, 4].map(|n| n as usize)
+    }
+
+    /// Frame `i`, as from [`ThumbnailRenderer::render`].
+    pub fn frame(&self, i: u32) -> &[u8] {
+        let len = (self.width * self.height * 4) as usize;
+        &self.rgba[i as usize * len..][..len]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Pixel conversion helpers (pure — unit tested below)
+// ---------------------------------------------------------------------------
+
+/// Row pitch for a `width`-texel `rgba16float` copy, rounded up to wgpu's
+/// 256-byte `COPY_BYTES_PER_ROW_ALIGNMENT`.
+fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded = width * TEXEL_BYTES;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    unpadded.div_ceil(align) * align
+}
+
+/// Decode an IEEE 754 half-precision float.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((bits >> 10) & 0x1f) as i32;
+    let frac = (bits & 0x3ff) as f32;
+    match exp {
+        0 => sign * frac * 2f32.powi(-24),
+        0x1f if frac == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + frac / 1024.0) * 2f32.powi(exp - 15),
+    }
+}
+
+/// Linear [0, 1] → 8-bit sRGB, matching what the sRGB swapchain does on
+/// present.  Out-of-range and NaN values are clamped.
+fn linear_to_srgb8(v: f32) -> u8 {
+    let v = if v.is_nan() { 0.0 } else { v.clamp(0.0, 1.0) };
+    let s = if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0).round() as u8
+}
+
+/// Convert padded `rgba16float` rows to tightly packed sRGB RGBA8.  Alpha
+/// is stored linearly.
+fn rgba16f_rows_to_srgb8(data: &[u8], width: u32, height: u32, padded_row: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        let row = &data[(y * padded_row) as usize..][..(width * TEXEL_BYTES) as usize];
+        for texel in row.chunks_exact(TEXEL_BYTES as usize) {
+            let ch = |i: usize| f16_to_f32(u16::from_le_bytes([texel[2 * i], texel[2 * i + 1]]));
+            out.push(linear_to_srgb8(ch(0)));
+            out.push(linear_to_srgb8(ch(1)));
+            out.push(linear_to_srgb8(ch(2)));
+            out.push((ch(3).clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padded_row_is_aligned() {
+        assert_eq!(padded_bytes_per_row(128), 1024);
+        assert_eq!(padded_bytes_per_row(1), 256);
+        assert_eq!(padded_bytes_per_row(33), 512);
+    }
+
+    #[test]
+    fn f16_decodes_common_values() {
+        assert_eq!(f16_to_f32(0x0000), 0.0);
+        assert_eq!(f16_to_f32(0x3c00), 1.0);
+        assert_eq!(f16_to_f32(0x3800), 0.5);
+        assert_eq!(f16_to_f32(0xc000), -2.0);
+        assert_eq!(f16_to_f32(0x7c00), f32::INFINITY);
+        assert!(f16_to_f32(0x7e00).is_nan());
+        // Smallest subnormal
+        assert!((f16_to_f32(0x0001) - 5.960_464_5e-8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn srgb_encoding_endpoints_and_midpoint() {
+        assert_eq!(linear_to_srgb8(0.0), 0);
+        assert_eq!(linear_to_srgb8(1.0), 255);
+        assert_eq!(linear_to_srgb8(0.5), 188);
+        assert_eq!(linear_to_srgb8(-1.0), 0);
+        assert_eq!(linear_to_srgb8(7.0), 255);
+        assert_eq!(linear_to_srgb8(f32::NAN), 0);
+    }
+
+    #[test]
+    fn rows_are_unpadded() {
+        // 1×2 image, each row padded to 256 bytes; texel = (1, 0, 0.5, 1).
+        let texel: [u16; 4] = [0x3c00, 0x0000, 0x3800, 0x3c00];
+        let mut data = vec![0u8; 512];
+        for row in 0..2 {
+            for (i, c) in texel.iter().enumerate() {
+                data[row * 256 + 2 * i..][..2].copy_from_slice(&c.to_le_bytes());
+            }
+        }
+        let px = rgba16f_rows_to_srgb8(&data, 1, 2, 256);
+        assert_eq!(px, vec![255, 0, 188, 255, 255, 0, 188, 255]);
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn renders_every_preset() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut renderer = ThumbnailRenderer::new(&ctx.device, 32);
+            for preset in fractal_core::presets::Preset::ALL {
+                let mut patch = preset.build();
+                let px = renderer.render(&ctx.device, &ctx.queue, &mut patch, THUMBNAIL_TIME);
+                assert_eq!(px.len(), 32 * 32 * 4, "{preset:?}");
+                let first = &px[..4];
+                assert!(
+                    px.chunks_exact(4).any(|p| p != first),
+                    "{preset:?} rendered a flat image"
+                );
+                assert!((patch.params.time - THUMBNAIL_TIME).abs() < 1e-6);
+            }
+        });
+    }
+
+    #[test]
+    #[ignore = "requires GPU adapter"]
+    fn sequences_stack_frames_on_a_fixed_clock() {
+        pollster::block_on(async {
+            let ctx = crate::context::GpuContext::new_headless().await;
+            let mut renderer = ThumbnailRenderer::with_size(&ctx.device, 16, 8);
+            let mut patch = fractal_core::presets::Preset::ALL[0].build();
+            let stack = renderer.render_sequence(&ctx.device, &ctx.queue, &mut patch, 1.0, 3, 4.0);
+            assert_eq!(stack.shape(), [3, 8, 16, 4]);
+            assert_eq!(stack.rgba.len(), 3 * 8 * 16 * 4);
+            assert!((patch.params.time - 1.5).abs() < 1e-6);
+
+            let mut again = fractal_core::presets::Preset::ALL[0].build();
+            let last = renderer.render(&ctx.device, &ctx.queue, &mut again, 1.5);
+            assert_eq!(stack.frame(2), last.as_slice());
+        });
+    }
+}